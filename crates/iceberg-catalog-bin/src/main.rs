@@ -1,21 +1,39 @@
 use anyhow::Error;
 use clap::{Parser, Subcommand};
+use iceberg_catalog::service::api_usage::ApiUsageRecorder;
+use iceberg_catalog::service::commit_hook::CommitHooks;
 use iceberg_catalog::service::contract_verification::ContractVerifiers;
+use iceberg_catalog::service::credential_rotation::CredentialRotationJob;
 use iceberg_catalog::service::event_publisher::{
     CloudEventBackend, CloudEventsPublisher, CloudEventsPublisherBackgroundTask, Message,
-    NatsBackend,
+    NatsBackend, WarehouseFilteredBackend,
 };
+use iceberg_catalog::service::hmac_auth::create_service_key;
+use iceberg_catalog::service::maintenance::MaintenanceMode;
+use iceberg_catalog::service::resolution_cache::ResolutionCache;
+use iceberg_catalog::service::runtime_config::{LogLevelSetter, RuntimeConfigHandle};
+use iceberg_catalog::service::secrets::SecretStore;
+use iceberg_catalog::service::snapshot_expiration::SnapshotExpirationJob;
+use iceberg_catalog::service::storage_usage::StorageUsageJob;
+use iceberg_catalog::service::storage_validation::StorageValidationJob;
+use iceberg_catalog::service::stores::Stores;
+use iceberg_catalog::service::table_cache::TableCache;
+use iceberg_catalog::service::table_gc::TableGarbageCollector;
 use iceberg_catalog::service::token_verification::Verifier;
+use iceberg_catalog::service::wasm_policy::WasmPolicyHook;
+use iceberg_catalog::service::webhook::WebhookBackend;
+use iceberg_catalog::service::{config::ConfigProvider, Catalog};
 use iceberg_catalog::{
     api::router::{new_full_router, serve as service_serve},
     implementations::{
-        postgres::{Catalog, CatalogState, SecretsState, SecretsStore},
-        AllowAllAuthState, AllowAllAuthZHandler,
+        mysql, postgres, AllowAllAuthState, AllowAllAuthZHandler, OpaAuthState, OpaAuthZHandler,
+        RbacAuthState, RbacAuthZHandler,
     },
-    CONFIG,
+    CatalogBackend, CONFIG,
 };
 use reqwest::Url;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing_subscriber::filter::LevelFilter;
 use tracing_subscriber::EnvFilter;
 
@@ -28,39 +46,81 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Migrate the database
+    /// Migrate the database of the `catalog_backend`
     Migrate {},
     /// Run the server - The database must be migrated before running the server
     Serve {},
+    /// Create a service key and print its id and secret.
+    /// Use it to create the first key if there is no identity provider.
+    CreateServiceKey {
+        /// Principal the service is authenticated as
+        #[arg(long)]
+        principal: String,
+    },
     /// Check the health of the server
     Healthcheck {},
     /// Print the version of the server
     Version {},
 }
 
-async fn serve(bind_addr: std::net::SocketAddr) -> Result<(), anyhow::Error> {
-    let read_pool = iceberg_catalog::implementations::postgres::get_reader_pool().await?;
-    let write_pool = iceberg_catalog::implementations::postgres::get_writer_pool().await?;
-
-    let catalog_state = CatalogState {
-        read_pool: read_pool.clone(),
-        write_pool: write_pool.clone(),
-    };
-    let secrets_state = SecretsState {
-        read_pool,
-        write_pool,
-    };
+async fn serve<C: Catalog + ConfigProvider<C>, S: SecretStore>(
+    catalog_state: C::State,
+    secrets_state: S::State,
+    stores: Stores,
+    bind_addr: std::net::SocketAddr,
+    log_level: LogLevelSetter,
+) -> Result<(), anyhow::Error> {
+    let runtime_config = RuntimeConfigHandle::new(
+        MaintenanceMode::new(
+            CONFIG.maintenance_mode,
+            CONFIG.maintenance_retry_after_seconds,
+        ),
+        Some(log_level),
+    );
+    runtime_config.init_log_level()?;
+    if CONFIG.metrics_port > 0 {
+        metrics_exporter_prometheus::PrometheusBuilder::new()
+            .with_http_listener(std::net::SocketAddr::from((
+                [0, 0, 0, 0],
+                CONFIG.metrics_port,
+            )))
+            .install()?;
+    }
+    let reload_handle = tokio::task::spawn(runtime_config.clone().reload_on_sighup());
 
     let mut cloud_event_sinks = vec![];
 
+    let table_cache = TableCache::new(
+        CONFIG.table_cache_capacity,
+        Duration::from_secs(CONFIG.resolution_cache_ttl_seconds),
+    );
+    cloud_event_sinks
+        .push(Arc::new(table_cache.invalidator()) as Arc<dyn CloudEventBackend + Sync + Send>);
+
+    // Sinks outside of the catalog only receive the events warehouses publish
+    let event_filter_cache =
+        ResolutionCache::new(Duration::from_secs(CONFIG.resolution_cache_ttl_seconds));
+    let filtered = |sink: Arc<dyn CloudEventBackend + Sync + Send>| {
+        Arc::new(WarehouseFilteredBackend::<C>::new(
+            sink,
+            event_filter_cache.clone(),
+            catalog_state.clone(),
+        )) as Arc<dyn CloudEventBackend + Sync + Send>
+    };
+
     if let Some(nat_addr) = &CONFIG.nats_address {
         let nats_publisher = build_nats_client(nat_addr).await?;
-        cloud_event_sinks
-            .push(Arc::new(nats_publisher) as Arc<dyn CloudEventBackend + Sync + Send>);
+        cloud_event_sinks.push(filtered(Arc::new(nats_publisher)));
     } else {
         tracing::info!("Running without publisher.");
     };
 
+    if let Some(webhook_publisher) =
+        WebhookBackend::from_config(stores.webhook_dead_letters.clone())?
+    {
+        cloud_event_sinks.push(filtered(Arc::new(webhook_publisher)));
+    }
+
     // TODO: what about this magic number
     let (tx, rx) = tokio::sync::mpsc::channel(1000);
 
@@ -68,25 +128,79 @@ async fn serve(bind_addr: std::net::SocketAddr) -> Result<(), anyhow::Error> {
         source: rx,
         sinks: cloud_event_sinks,
     };
-    let listener = tokio::net::TcpListener::bind(bind_addr).await?;
-    let router = new_full_router::<
-        Catalog,
-        Catalog,
-        AllowAllAuthZHandler,
-        AllowAllAuthZHandler,
-        SecretsStore,
-    >(
-        AllowAllAuthState,
-        catalog_state,
-        secrets_state,
+    let table_gc = TableGarbageCollector::<C, S>::new(
+        catalog_state.clone(),
+        secrets_state.clone(),
+        CloudEventsPublisher::new(tx.clone()),
+        Duration::from_secs(CONFIG.dropped_table_retention_seconds),
+        CONFIG.dropped_table_purge_storage,
+    );
+    let snapshot_expiration = SnapshotExpirationJob::<C, S>::new(
+        catalog_state.clone(),
+        secrets_state.clone(),
         CloudEventsPublisher::new(tx.clone()),
-        ContractVerifiers::new(vec![]),
-        if let Some(uri) = CONFIG.openid_provider_uri.clone() {
-            Some(Verifier::new(uri).await?)
-        } else {
-            None
-        },
     );
+    let storage_validation =
+        StorageValidationJob::<C, S>::new(catalog_state.clone(), secrets_state.clone());
+    let credential_rotation = CredentialRotationJob::<C, S>::new(
+        catalog_state.clone(),
+        secrets_state.clone(),
+        Duration::from_secs(CONFIG.credential_rotation_grace_period_seconds),
+    );
+    let api_usage = ApiUsageRecorder::new();
+    let commit_hooks = match WasmPolicyHook::from_config()? {
+        Some(hook) => CommitHooks::new(vec![Arc::new(hook)]),
+        None => CommitHooks::default(),
+    };
+    let listener = tokio::net::TcpListener::bind(bind_addr).await?;
+    let token_verifier = if let Some(uri) = CONFIG.openid_provider_uri.clone() {
+        Some(Verifier::new(uri).await?)
+    } else {
+        None
+    };
+    let router = if let Some(opa_state) = OpaAuthState::from_config()? {
+        new_full_router::<C, C, OpaAuthZHandler, OpaAuthZHandler, S>(
+            opa_state,
+            catalog_state.clone(),
+            secrets_state,
+            stores.clone(),
+            CloudEventsPublisher::new(tx.clone()),
+            ContractVerifiers::new(vec![]),
+            commit_hooks,
+            table_cache,
+            api_usage.clone(),
+            token_verifier,
+            runtime_config,
+        )
+    } else if CONFIG.rbac_enabled {
+        new_full_router::<C, C, RbacAuthZHandler<C>, RbacAuthZHandler<C>, S>(
+            RbacAuthState::from_config(catalog_state.clone(), stores.roles.clone()),
+            catalog_state.clone(),
+            secrets_state,
+            stores.clone(),
+            CloudEventsPublisher::new(tx.clone()),
+            ContractVerifiers::new(vec![]),
+            commit_hooks,
+            table_cache,
+            api_usage.clone(),
+            token_verifier,
+            runtime_config,
+        )
+    } else {
+        new_full_router::<C, C, AllowAllAuthZHandler, AllowAllAuthZHandler, S>(
+            AllowAllAuthState,
+            catalog_state.clone(),
+            secrets_state,
+            stores.clone(),
+            CloudEventsPublisher::new(tx.clone()),
+            ContractVerifiers::new(vec![]),
+            commit_hooks,
+            table_cache,
+            api_usage.clone(),
+            token_verifier,
+            runtime_config,
+        )
+    };
 
     let publisher_handle = tokio::task::spawn(async move {
         match x.publish().await {
@@ -95,8 +209,61 @@ async fn serve(bind_addr: std::net::SocketAddr) -> Result<(), anyhow::Error> {
         };
     });
 
+    let table_gc_handle = tokio::task::spawn(table_gc.run(Duration::from_secs(
+        CONFIG.dropped_table_gc_interval_seconds,
+    )));
+
+    let api_usage_handle = tokio::task::spawn(api_usage.clone().run(
+        stores.api_usage.clone(),
+        Duration::from_secs(CONFIG.api_usage_flush_interval_seconds),
+    ));
+
+    let snapshot_expiration_handle = (CONFIG.snapshot_expiration_interval_seconds > 0).then(|| {
+        tokio::task::spawn(snapshot_expiration.run(Duration::from_secs(
+            CONFIG.snapshot_expiration_interval_seconds,
+        )))
+    });
+
+    let storage_usage_handle = (CONFIG.storage_usage_interval_seconds > 0).then(|| {
+        tokio::task::spawn(
+            StorageUsageJob::new(stores.storage_usage.clone())
+                .run(Duration::from_secs(CONFIG.storage_usage_interval_seconds)),
+        )
+    });
+
+    let storage_validation_handle = (CONFIG.storage_validation_interval_seconds > 0).then(|| {
+        tokio::task::spawn(storage_validation.run(Duration::from_secs(
+            CONFIG.storage_validation_interval_seconds,
+        )))
+    });
+
+    let credential_rotation_handle = (CONFIG.credential_rotation_interval_seconds > 0).then(|| {
+        tokio::task::spawn(credential_rotation.run(Duration::from_secs(
+            CONFIG.credential_rotation_interval_seconds,
+        )))
+    });
+
     service_serve(listener, router).await?;
 
+    reload_handle.abort();
+    table_gc_handle.abort();
+    api_usage_handle.abort();
+    if let Some(handle) = storage_usage_handle {
+        handle.abort();
+    }
+    if let Some(handle) = snapshot_expiration_handle {
+        handle.abort();
+    }
+    if let Some(handle) = storage_validation_handle {
+        handle.abort();
+    }
+    if let Some(handle) = credential_rotation_handle {
+        handle.abort();
+    }
+    if let Err(e) = api_usage.flush(stores.api_usage.as_ref()).await {
+        tracing::warn!("Failed to write API usage statistics: {:?}", e.error);
+    }
+
     tracing::debug!("Sending shutdown signal to event publisher.");
     tx.send(Message::Shutdown).await?;
     publisher_handle.await?;
@@ -104,11 +271,18 @@ async fn serve(bind_addr: std::net::SocketAddr) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
+fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    // Before the runtime starts threads, as the environment is modified
+    iceberg_catalog::service::http_client::export_proxy_env();
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(run(cli))
+}
 
-    tracing_subscriber::fmt()
+async fn run(cli: Cli) -> anyhow::Result<()> {
+    let subscriber = tracing_subscriber::fmt()
         .json()
         .flatten_event(true)
         .with_current_span(true)
@@ -119,28 +293,100 @@ async fn main() -> anyhow::Result<()> {
                 .with_default_directive(LevelFilter::INFO.into())
                 .from_env_lossy(),
         )
-        .init();
+        .with_filter_reloading();
+    let filter_handle = subscriber.reload_handle();
+    subscriber.init();
+    let log_level: LogLevelSetter = Arc::new(move |level: &str| {
+        let filter = EnvFilter::try_new(level).map_err(|e| e.to_string())?;
+        filter_handle.reload(filter).map_err(|e| e.to_string())
+    });
 
     match cli.command {
         Some(Commands::Migrate {}) => {
             print_info();
             println!("Migrating database...");
-            let write_pool = iceberg_catalog::implementations::postgres::get_writer_pool().await?;
-
             // This embeds database migrations in the application binary so we can ensure the database
             // is migrated correctly on startup
-            iceberg_catalog::implementations::postgres::migrate(&write_pool).await?;
+            match CONFIG.catalog_backend {
+                CatalogBackend::Postgres => {
+                    postgres::migrate(&postgres::get_writer_pool().await?).await?;
+                }
+                CatalogBackend::Mysql => mysql::migrate(&mysql::get_writer_pool().await?).await?,
+            }
             println!("Database migration complete.");
         }
         Some(Commands::Serve {}) => {
             print_info();
             println!("Starting server on 0.0.0.0:8080...");
             let bind_addr = std::net::SocketAddr::from(([0, 0, 0, 0], 8080));
-            serve(bind_addr).await?;
+            match CONFIG.catalog_backend {
+                CatalogBackend::Postgres => {
+                    let read_pool = postgres::get_reader_pool().await?;
+                    let write_pool = postgres::get_writer_pool().await?;
+                    let secrets_state = postgres::SecretsState {
+                        read_pool: read_pool.clone(),
+                        write_pool: write_pool.clone(),
+                    };
+                    let mut catalog_state = postgres::CatalogState::new(read_pool, write_pool);
+                    if CONFIG.pg_schema_per_project {
+                        catalog_state = catalog_state.with_schema_per_project();
+                    }
+                    serve::<postgres::Catalog, postgres::SecretsStore>(
+                        catalog_state.clone(),
+                        secrets_state,
+                        Stores::new(catalog_state),
+                        bind_addr,
+                        log_level,
+                    )
+                    .await?;
+                }
+                CatalogBackend::Mysql => {
+                    let read_pool = mysql::get_reader_pool().await?;
+                    let write_pool = mysql::get_writer_pool().await?;
+                    let secrets_state = mysql::SecretsState {
+                        read_pool: read_pool.clone(),
+                        write_pool: write_pool.clone(),
+                    };
+                    let catalog_state = mysql::CatalogState {
+                        read_pool,
+                        write_pool,
+                    };
+                    serve::<mysql::Catalog, mysql::SecretsStore>(
+                        catalog_state.clone(),
+                        secrets_state,
+                        Stores::new(catalog_state),
+                        bind_addr,
+                        log_level,
+                    )
+                    .await?;
+                }
+            }
+        }
+        Some(Commands::CreateServiceKey { principal }) => {
+            let created = match CONFIG.catalog_backend {
+                CatalogBackend::Postgres => {
+                    let secrets_state = postgres::SecretsState {
+                        read_pool: postgres::get_reader_pool().await?,
+                        write_pool: postgres::get_writer_pool().await?,
+                    };
+                    create_service_key::<postgres::SecretsStore>(principal, secrets_state).await
+                }
+                CatalogBackend::Mysql => {
+                    let secrets_state = mysql::SecretsState {
+                        read_pool: mysql::get_reader_pool().await?,
+                        write_pool: mysql::get_writer_pool().await?,
+                    };
+                    create_service_key::<mysql::SecretsStore>(principal, secrets_state).await
+                }
+            };
+            let (key_id, key) = created.map_err(|e| anyhow::anyhow!(e.error.message))?;
+            println!("Key id: {key_id}");
+            println!("Secret: {}", key.hmac_secret);
         }
         Some(Commands::Healthcheck {}) => {
             println!("Checking health...");
-            let client = reqwest::Client::new();
+            // The health endpoint is local and must not be requested via a proxy
+            let client = reqwest::Client::builder().no_proxy().build()?;
             let response = client.get("http://localhost:8080/health").send().await?;
             let status = response.status();
             // Fail with an error if the server is not healthy
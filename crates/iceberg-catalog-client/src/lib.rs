@@ -0,0 +1,656 @@
+#![warn(
+    missing_debug_implementations,
+    rust_2018_idioms,
+    unreachable_pub,
+    clippy::pedantic
+)]
+#![forbid(unsafe_code)]
+
+//! Typed async client for the management API of the iceberg-catalog.
+//!
+//! ```no_run
+//! # async fn example() -> iceberg_catalog_client::Result<()> {
+//! use iceberg_catalog_client::ManagementClient;
+//!
+//! let client = ManagementClient::new("http://localhost:8080".parse().unwrap())?
+//!     .with_token("my-token");
+//! let projects = client.list_projects().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub use iceberg_catalog::api::management::v1::{
+    maintenance::{MaintenanceModeResponse, SetMaintenanceModeRequest},
+    role::{
+        GrantRoleRequest, GrantRoleResponse, ListRoleAssignmentsResponse, RevokeRoleRequest,
+        RoleAssignmentObject, RoleAssignmentResponse,
+    },
+    service_key::{CreateServiceKeyRequest, CreateServiceKeyResponse},
+    share::{CreateShareRequest, CreateShareResponse, ListSharesResponse, ShareObject},
+    table::{
+        BulkDropTableResult, BulkDropTablesRequest, BulkDropTablesResponse, BulkLoadTablesRequest,
+        BulkLoadTablesResponse, BulkLoadedTable, BulkTableIdent, ListStagedTablesResponse,
+    },
+    token::{RevokeTokenRequest, RevokeTokenTarget},
+    warehouse::{
+        CreateWarehouseRequest, CreateWarehouseResponse, GetWarehouseResponse,
+        ListClientUsageResponse, ListProjectsResponse, ListWarehousesResponse, ProjectResponse,
+        RenameProjectRequest, RenameWarehouseRequest, SetProjectMetadataRequest,
+        SetWarehouseReadOnlyRequest, StorageCredential, StorageProfile,
+        UpdateWarehouseCredentialRequest, UpdateWarehouseStorageRequest,
+    },
+};
+pub use iceberg_catalog::service::Role;
+pub use iceberg_catalog::{ProjectIdent, SecretIdent, WarehouseIdent};
+pub use iceberg_ext::catalog::rest::ErrorModel;
+
+use iceberg_ext::catalog::rest::IcebergErrorResponse;
+use reqwest::{Method, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use url::Url;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The base URL can not be extended with the API path.
+    InvalidBaseUrl(Url),
+    /// The request could not be sent or the response could not be read.
+    Request(reqwest::Error),
+    /// The catalog answered with a non-success status code.
+    Api(ErrorModel),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidBaseUrl(url) => write!(f, "Invalid base URL: {url}"),
+            Error::Request(e) => write!(f, "Request failed: {e}"),
+            Error::Api(e) => write!(
+                f,
+                "Catalog returned {} ({}): {}",
+                e.code, e.r#type, e.message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Request(e) => Some(e),
+            Error::InvalidBaseUrl(_) | Error::Api(_) => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(value: reqwest::Error) -> Self {
+        Error::Request(value)
+    }
+}
+
+/// Client for the management API (`/management/v1`) of a catalog.
+#[derive(Debug, Clone)]
+pub struct ManagementClient {
+    http: reqwest::Client,
+    base_url: Url,
+    token: Option<String>,
+}
+
+impl ManagementClient {
+    /// Create a new client for the catalog served at `base_url`,
+    /// e.g. `http://localhost:8080`.
+    ///
+    /// # Errors
+    /// Fails if `base_url` can not be a base, e.g. a `data:` URL.
+    pub fn new(base_url: Url) -> Result<Self> {
+        if base_url.cannot_be_a_base() {
+            return Err(Error::InvalidBaseUrl(base_url));
+        }
+
+        Ok(Self {
+            http: reqwest::Client::new(),
+            base_url,
+            token: None,
+        })
+    }
+
+    /// Send the given token as bearer token with every request.
+    #[must_use]
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Use a pre-configured HTTP client, e.g. with custom timeouts or TLS settings.
+    #[must_use]
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    // ------------------- Projects -------------------
+
+    /// List all projects the user has access to.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn list_projects(&self) -> Result<ListProjectsResponse> {
+        send_json(self.request(Method::GET, &["project"])).await
+    }
+
+    /// Get a project by its ID or name.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error,
+    /// i.e. `404` if no project has this name.
+    pub async fn get_project(&self, project: &str) -> Result<ProjectResponse> {
+        send_json(self.request(Method::GET, &["project", project])).await
+    }
+
+    /// Rename a project, given by its ID or name.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error,
+    /// i.e. `409` if another project has the new name.
+    pub async fn rename_project(
+        &self,
+        project: &str,
+        request: &RenameProjectRequest,
+    ) -> Result<()> {
+        send(
+            self.request(Method::POST, &["project", project, "rename"])
+                .json(request),
+        )
+        .await
+    }
+
+    /// Replace the metadata of a project, given by its ID or name.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn set_project_metadata(
+        &self,
+        project: &str,
+        request: &SetProjectMetadataRequest,
+    ) -> Result<()> {
+        send(
+            self.request(Method::POST, &["project", project, "metadata"])
+                .json(request),
+        )
+        .await
+    }
+
+    /// Delete a project, given by its ID or name, including its roles.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error,
+    /// i.e. `409` if the project still has warehouses.
+    pub async fn delete_project(&self, project: &str) -> Result<()> {
+        send(self.request(Method::DELETE, &["project", project])).await
+    }
+
+    // ------------------- Warehouses -------------------
+
+    /// Create a new warehouse.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn create_warehouse(
+        &self,
+        request: &CreateWarehouseRequest,
+    ) -> Result<CreateWarehouseResponse> {
+        send_json(self.request(Method::POST, &["warehouse"]).json(request)).await
+    }
+
+    /// List all active warehouses of a project.
+    /// If `project_id` is not set, the project is determined by the catalog.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn list_warehouses(
+        &self,
+        project_id: Option<&ProjectIdent>,
+    ) -> Result<ListWarehousesResponse> {
+        let mut request = self.request(Method::GET, &["warehouse"]);
+        if let Some(project_id) = project_id {
+            request = request.query(&[("project-id", project_id.to_string())]);
+        }
+        send_json(request).await
+    }
+
+    /// Get a warehouse by ID.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn get_warehouse(
+        &self,
+        warehouse_id: &WarehouseIdent,
+    ) -> Result<GetWarehouseResponse> {
+        let warehouse_id = warehouse_id.to_string();
+        send_json(self.request(Method::GET, &["warehouse", &warehouse_id])).await
+    }
+
+    /// Get a warehouse by its name within a project, including deactivated warehouses.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error,
+    /// i.e. `404` if no such warehouse exists.
+    pub async fn get_warehouse_by_name(
+        &self,
+        project_id: &ProjectIdent,
+        name: &str,
+    ) -> Result<GetWarehouseResponse> {
+        let project_id = project_id.to_string();
+        send_json(
+            self.request(Method::GET, &["project", &project_id, "warehouse"])
+                .query(&[("name", name)]),
+        )
+        .await
+    }
+
+    /// Delete a warehouse by ID.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn delete_warehouse(&self, warehouse_id: &WarehouseIdent) -> Result<()> {
+        let warehouse_id = warehouse_id.to_string();
+        send(self.request(Method::DELETE, &["warehouse", &warehouse_id])).await
+    }
+
+    /// Rename a warehouse.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn rename_warehouse(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        request: &RenameWarehouseRequest,
+    ) -> Result<()> {
+        self.warehouse_post(warehouse_id, "rename", request).await
+    }
+
+    /// Deactivate a warehouse.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn deactivate_warehouse(&self, warehouse_id: &WarehouseIdent) -> Result<()> {
+        let warehouse_id = warehouse_id.to_string();
+        send(self.request(Method::POST, &["warehouse", &warehouse_id, "deactivate"])).await
+    }
+
+    /// Activate a warehouse.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn activate_warehouse(&self, warehouse_id: &WarehouseIdent) -> Result<()> {
+        let warehouse_id = warehouse_id.to_string();
+        send(self.request(Method::POST, &["warehouse", &warehouse_id, "activate"])).await
+    }
+
+    /// Mark a warehouse as read-only or writable again.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn set_warehouse_read_only(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        request: &SetWarehouseReadOnlyRequest,
+    ) -> Result<()> {
+        self.warehouse_post(warehouse_id, "read-only", request)
+            .await
+    }
+
+    // ------------------- Storage & Secrets -------------------
+
+    /// Update the storage profile of a warehouse, optionally replacing its credential.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn update_storage_profile(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        request: &UpdateWarehouseStorageRequest,
+    ) -> Result<()> {
+        self.warehouse_post(warehouse_id, "storage", request).await
+    }
+
+    /// Replace the storage credential of a warehouse.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn update_storage_credential(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        request: &UpdateWarehouseCredentialRequest,
+    ) -> Result<()> {
+        self.warehouse_post(warehouse_id, "storage-credential", request)
+            .await
+    }
+
+    /// Create a service key. The secret of the key is only returned once.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn create_service_key(
+        &self,
+        request: &CreateServiceKeyRequest,
+    ) -> Result<CreateServiceKeyResponse> {
+        send_json(self.request(Method::POST, &["service-key"]).json(request)).await
+    }
+
+    /// Delete a service key.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn delete_service_key(&self, key_id: &SecretIdent) -> Result<()> {
+        let key_id = key_id.to_string();
+        send(self.request(Method::DELETE, &["service-key", &key_id])).await
+    }
+
+    /// Revoke one or more tokens.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn revoke_token(&self, request: &RevokeTokenRequest) -> Result<()> {
+        send(
+            self.request(Method::POST, &["token", "revoke"])
+                .json(request),
+        )
+        .await
+    }
+
+    // ------------------- Stats -------------------
+
+    /// List which clients accessed a warehouse and how often.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn list_client_usage(
+        &self,
+        warehouse_id: &WarehouseIdent,
+    ) -> Result<ListClientUsageResponse> {
+        let warehouse_id = warehouse_id.to_string();
+        send_json(self.request(Method::GET, &["warehouse", &warehouse_id, "client-usage"])).await
+    }
+
+    /// Load the metadata of up to 100 tables with a single request.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn bulk_load_tables(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        request: &BulkLoadTablesRequest,
+    ) -> Result<BulkLoadTablesResponse> {
+        let warehouse_id = warehouse_id.to_string();
+        send_json(
+            self.request(
+                Method::POST,
+                &["warehouse", &warehouse_id, "table", "bulk-load"],
+            )
+            .json(request),
+        )
+        .await
+    }
+
+    /// Drop up to 100 tables in a single transaction.
+    /// The response reports for each table whether it was dropped.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn bulk_drop_tables(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        request: &BulkDropTablesRequest,
+    ) -> Result<BulkDropTablesResponse> {
+        let warehouse_id = warehouse_id.to_string();
+        send_json(
+            self.request(
+                Method::POST,
+                &["warehouse", &warehouse_id, "table", "bulk-drop"],
+            )
+            .json(request),
+        )
+        .await
+    }
+
+    /// List tables of a warehouse that were staged but never committed.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn list_staged_tables(
+        &self,
+        warehouse_id: &WarehouseIdent,
+    ) -> Result<ListStagedTablesResponse> {
+        let warehouse_id = warehouse_id.to_string();
+        send_json(self.request(Method::GET, &["warehouse", &warehouse_id, "staged-table"])).await
+    }
+
+    // ------------------- Shares -------------------
+
+    /// Share a namespace or table of a warehouse with another project.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn create_share(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        request: &CreateShareRequest,
+    ) -> Result<CreateShareResponse> {
+        let warehouse_id = warehouse_id.to_string();
+        send_json(
+            self.request(Method::POST, &["warehouse", &warehouse_id, "share"])
+                .json(request),
+        )
+        .await
+    }
+
+    /// List all shares of a warehouse.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn list_shares(&self, warehouse_id: &WarehouseIdent) -> Result<ListSharesResponse> {
+        let warehouse_id = warehouse_id.to_string();
+        send_json(self.request(Method::GET, &["warehouse", &warehouse_id, "share"])).await
+    }
+
+    /// Delete a share.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn delete_share(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        share_id: uuid::Uuid,
+    ) -> Result<()> {
+        let warehouse_id = warehouse_id.to_string();
+        let share_id = share_id.to_string();
+        send(self.request(
+            Method::DELETE,
+            &["warehouse", &warehouse_id, "share", &share_id],
+        ))
+        .await
+    }
+
+    // ------------------- Roles -------------------
+
+    /// Grant a role on a project, warehouse, namespace or table to a principal.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn grant_role(&self, request: &GrantRoleRequest) -> Result<GrantRoleResponse> {
+        send_json(
+            self.request(Method::POST, &["role-assignment"])
+                .json(request),
+        )
+        .await
+    }
+
+    /// Revoke the role of a principal on an object.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error,
+    /// i.e. `404` if the principal has no role on the object.
+    pub async fn revoke_role(&self, request: &RevokeRoleRequest) -> Result<()> {
+        send(
+            self.request(Method::POST, &["role-assignment", "revoke"])
+                .json(request),
+        )
+        .await
+    }
+
+    /// List the role assignments of a project, given by its ID or name,
+    /// optionally only those of `principal`.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn list_role_assignments(
+        &self,
+        project: &str,
+        principal: Option<&str>,
+    ) -> Result<ListRoleAssignmentsResponse> {
+        let mut request = self.request(Method::GET, &["project", project, "role-assignment"]);
+        if let Some(principal) = principal {
+            request = request.query(&[("principal", principal)]);
+        }
+        send_json(request).await
+    }
+
+    // ------------------- Maintenance -------------------
+
+    /// Get the maintenance mode of the instance that serves the request.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn get_maintenance_mode(&self) -> Result<MaintenanceModeResponse> {
+        send_json(self.request(Method::GET, &["maintenance"])).await
+    }
+
+    /// Enable or disable maintenance mode on the instance that serves the request.
+    ///
+    /// # Errors
+    /// Fails if the request fails or the catalog returns an error.
+    pub async fn set_maintenance_mode(
+        &self,
+        request: &SetMaintenanceModeRequest,
+    ) -> Result<MaintenanceModeResponse> {
+        send_json(self.request(Method::POST, &["maintenance"]).json(request)).await
+    }
+
+    // ------------------- Helpers -------------------
+
+    async fn warehouse_post<T: Serialize + ?Sized>(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        action: &str,
+        body: &T,
+    ) -> Result<()> {
+        let warehouse_id = warehouse_id.to_string();
+        send(
+            self.request(Method::POST, &["warehouse", &warehouse_id, action])
+                .json(body),
+        )
+        .await
+    }
+
+    fn url(&self, segments: &[&str]) -> Url {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            // Checked in `new`
+            .expect("Base URL can be a base")
+            .pop_if_empty()
+            .extend(["management", "v1"])
+            .extend(segments);
+        url
+    }
+
+    fn request(&self, method: Method, segments: &[&str]) -> RequestBuilder {
+        let request = self.http.request(method, self.url(segments));
+        if let Some(token) = &self.token {
+            request.bearer_auth(token)
+        } else {
+            request
+        }
+    }
+}
+
+async fn send(request: RequestBuilder) -> Result<()> {
+    error_for_status(request.send().await?).await.map(|_| ())
+}
+
+async fn send_json<T: DeserializeOwned>(request: RequestBuilder) -> Result<T> {
+    Ok(error_for_status(request.send().await?)
+        .await?
+        .json::<T>()
+        .await?)
+}
+
+async fn error_for_status(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().await?;
+    Err(Error::Api(parse_error(status, &body)))
+}
+
+fn parse_error(status: http::StatusCode, body: &str) -> ErrorModel {
+    serde_json::from_str::<IcebergErrorResponse>(body).map_or_else(
+        |_| {
+            ErrorModel::builder()
+                .code(status.into())
+                .message(if body.is_empty() {
+                    status.to_string()
+                } else {
+                    body.to_string()
+                })
+                .r#type("UnexpectedResponse")
+                .build()
+        },
+        |response| response.error,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_url() {
+        let warehouse_id = "d6c4bfa4-1a1b-4b4c-8a55-76d8b3b1a4b2";
+        for base in [
+            "http://localhost:8080",
+            "http://localhost:8080/",
+            "http://localhost:8080/catalog-root",
+            "http://localhost:8080/catalog-root/",
+        ] {
+            let client = ManagementClient::new(base.parse().unwrap()).unwrap();
+            let url = client.url(&["warehouse", warehouse_id, "rename"]);
+            assert_eq!(
+                url.as_str(),
+                format!(
+                    "{}/management/v1/warehouse/{warehouse_id}/rename",
+                    base.trim_end_matches('/')
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_error(
+            http::StatusCode::NOT_FOUND,
+            r#"{"error": {"message": "Warehouse not found", "type": "WarehouseNotFound", "code": 404}}"#,
+        );
+        assert_eq!(error.r#type, "WarehouseNotFound");
+        assert_eq!(error.code, 404);
+
+        let error = parse_error(http::StatusCode::BAD_GATEWAY, "");
+        assert_eq!(error.r#type, "UnexpectedResponse");
+        assert_eq!(error.code, 502);
+    }
+}
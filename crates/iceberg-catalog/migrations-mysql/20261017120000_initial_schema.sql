@@ -0,0 +1,184 @@
+-- Schema of the MySQL catalog, equivalent to the Postgres schema in `migrations`.
+--
+-- Ids are UUIDs stored as binary(16). Timestamps are UTC, the connection time zone is set to UTC.
+-- Names that are unique case-insensitively use the accent-sensitive, case-insensitive collation
+-- `utf8mb4_0900_as_ci`, like the case-insensitive collation of the Postgres schema.
+--
+-- Locations are compared byte-wise with `utf8mb4_bin`.
+--
+-- Namespace names are stored as a JSON array. As JSON can't be indexed, `namespace_key` holds the
+-- elements joined by the unit separator (0x1F). It is unique per warehouse and used for lookups.
+create table project (
+    project_id binary(16) primary key,
+    project_name varchar(255) not null collate utf8mb4_0900_as_ci,
+    metadata json not null default (json_object()),
+    created_at datetime(6) not null default current_timestamp(6),
+    updated_at datetime(6) null on update current_timestamp(6),
+    constraint unique_project_name unique (project_name)
+);
+
+create table warehouse (
+    warehouse_id binary(16) primary key,
+    project_id binary(16) not null,
+    warehouse_name varchar(255) not null collate utf8mb4_0900_as_ci,
+    storage_profile json not null,
+    storage_secret_id binary(16),
+    previous_storage_secret_id binary(16),
+    storage_secret_rotated_at datetime(6),
+    `status` enum('active', 'inactive') not null,
+    created_by text,
+    `owner` text,
+    allowlist json,
+    read_only boolean not null default false,
+    pyiceberg_compat boolean not null default false,
+    engine_table_config json not null default (json_object()),
+    signer_uri text,
+    retention_policy json,
+    unauthorized_response enum('forbidden', 'not-found'),
+    access_delegation enum('remote-signing', 'vended-credentials', 'disabled'),
+    allow_external_locations boolean not null default false,
+    object_storage_layout boolean not null default false,
+    storage_validated_at datetime(6),
+    storage_validation_error text,
+    created_at datetime(6) not null default current_timestamp(6),
+    updated_at datetime(6) null on update current_timestamp(6),
+    constraint unique_warehouse_name_in_project unique (project_id, warehouse_name)
+);
+
+create table namespace (
+    namespace_id binary(16) primary key,
+    warehouse_id binary(16) not null,
+    namespace_name json not null,
+    namespace_key varchar(700) not null collate utf8mb4_0900_as_ci,
+    namespace_properties json not null,
+    retention_policy json,
+    created_by text,
+    `owner` text,
+    created_at datetime(6) not null default current_timestamp(6),
+    updated_at datetime(6) null on update current_timestamp(6),
+    constraint unique_namespace_per_warehouse unique (warehouse_id, namespace_key),
+    constraint namespace_warehouse_id_fkey foreign key (warehouse_id) references warehouse (warehouse_id)
+);
+
+create table `table` (
+    table_id binary(16) primary key,
+    namespace_id binary(16) not null,
+    table_name varchar(255) not null collate utf8mb4_0900_as_ci,
+    metadata json not null,
+    -- NULL if the table is staged
+    metadata_location text collate utf8mb4_bin,
+    metadata_version bigint not null default 0,
+    table_location text not null collate utf8mb4_bin,
+    `policy` json,
+    created_by text,
+    `owner` text,
+    created_at datetime(6) not null default current_timestamp(6),
+    updated_at datetime(6) null on update current_timestamp(6),
+    constraint unique_table_name_per_namespace unique (namespace_id, table_name),
+    constraint table_namespace_id_fkey foreign key (namespace_id) references namespace (namespace_id)
+);
+
+-- Locations can be longer than an index key, they are unique by their hash instead.
+create table table_metadata_log (
+    table_id binary(16) not null,
+    metadata_location text not null collate utf8mb4_bin,
+    metadata_location_hash binary(32) as (unhex(sha2(metadata_location, 256))) stored not null,
+    created_at datetime(6) not null default current_timestamp(6),
+    primary key (table_id, metadata_location_hash),
+    constraint table_metadata_log_table_id_fkey foreign key (table_id) references `table` (table_id) on delete cascade
+);
+
+create table table_commit (
+    table_id binary(16) not null,
+    metadata_location text not null collate utf8mb4_bin,
+    metadata_location_hash binary(32) as (unhex(sha2(metadata_location, 256))) stored not null,
+    committed_at datetime(6) not null,
+    committed_by text,
+    snapshot_id bigint,
+    operation text,
+    primary key (table_id, metadata_location_hash),
+    index table_commit_committed_at_idx (table_id, committed_at),
+    constraint table_commit_table_id_fkey foreign key (table_id) references `table` (table_id) on delete cascade
+);
+
+create table table_lineage_event (
+    table_id binary(16) not null,
+    snapshot_id bigint not null,
+    event json not null,
+    primary key (table_id, snapshot_id),
+    constraint table_lineage_event_table_id_fkey foreign key (table_id) references `table` (table_id) on delete cascade
+);
+
+create table dropped_table (
+    table_id binary(16) primary key,
+    warehouse_id binary(16) not null,
+    namespace_name json not null,
+    table_name varchar(255) not null,
+    metadata json,
+    metadata_locations json not null,
+    table_location text not null collate utf8mb4_bin,
+    dropped_at datetime(6) not null default current_timestamp(6),
+    reclaimed_at datetime(6),
+    storage_purged boolean,
+    index dropped_table_dropped_at_idx (reclaimed_at, dropped_at),
+    constraint dropped_table_warehouse_id_fkey foreign key (warehouse_id) references warehouse (warehouse_id) on delete cascade
+);
+
+-- Either namespace_id or table_id is set. Not enforced by a check constraint,
+-- as MySQL doesn't allow them on columns of cascading foreign keys.
+create table `share` (
+    share_id binary(16) primary key,
+    warehouse_id binary(16) not null,
+    namespace_id binary(16),
+    table_id binary(16),
+    consumer_project_id binary(16) not null,
+    mount_name varchar(255) not null collate utf8mb4_0900_as_ci,
+    created_by text,
+    created_at datetime(6) not null default current_timestamp(6),
+    constraint unique_mount_name_per_consumer unique (consumer_project_id, mount_name),
+    constraint share_warehouse_id_fkey foreign key (warehouse_id) references warehouse (warehouse_id) on delete cascade,
+    constraint share_namespace_id_fkey foreign key (namespace_id) references namespace (namespace_id) on delete cascade,
+    constraint share_table_id_fkey foreign key (table_id) references `table` (table_id) on delete cascade
+);
+
+create table client_usage (
+    warehouse_id binary(16) not null,
+    engine varchar(255) not null,
+    engine_version varchar(255) not null default '',
+    session_count bigint not null default 0,
+    first_seen datetime(6) not null default current_timestamp(6),
+    last_seen datetime(6) not null default current_timestamp(6),
+    primary key (warehouse_id, engine, engine_version),
+    constraint client_usage_warehouse_id_fkey foreign key (warehouse_id) references warehouse (warehouse_id) on delete cascade
+);
+
+create table api_usage (
+    warehouse_id binary(16) not null,
+    window_start datetime(6) not null,
+    request_count bigint not null default 0,
+    commit_count bigint not null default 0,
+    primary key (warehouse_id, window_start),
+    constraint api_usage_warehouse_id_fkey foreign key (warehouse_id) references warehouse (warehouse_id) on delete cascade
+);
+
+create table storage_usage (
+    namespace_id binary(16) primary key,
+    warehouse_id binary(16) not null,
+    table_count bigint not null,
+    total_files_size_bytes bigint not null,
+    tables_without_size bigint not null,
+    computed_at datetime(6) not null,
+    constraint storage_usage_namespace_id_fkey foreign key (namespace_id) references namespace (namespace_id) on delete cascade,
+    constraint storage_usage_warehouse_id_fkey foreign key (warehouse_id) references warehouse (warehouse_id) on delete cascade
+);
+
+create table token_revocation (
+    token_revocation_id bigint auto_increment primary key,
+    token_id varchar(255),
+    subject varchar(255),
+    revoked_at datetime(6) not null,
+    expires_at datetime(6),
+    reason text,
+    index token_revocation_expires_at_idx (expires_at),
+    constraint token_revocation_target check ((token_id is null) <> (subject is null))
+);
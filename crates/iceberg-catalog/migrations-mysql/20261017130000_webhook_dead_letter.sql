@@ -0,0 +1,12 @@
+-- Webhook deliveries that failed permanently, kept for inspection and manual redelivery.
+create table webhook_dead_letter (
+    webhook_dead_letter_id bigint auto_increment primary key,
+    event_id varchar(255) not null,
+    url text not null,
+    -- The event as it was posted, in the structured CloudEvents JSON format.
+    event json not null,
+    attempts int not null,
+    error text not null,
+    failed_at datetime(6) not null default current_timestamp(6),
+    index webhook_dead_letter_failed_at_idx (failed_at)
+);
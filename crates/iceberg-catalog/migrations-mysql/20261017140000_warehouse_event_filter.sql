@@ -0,0 +1,2 @@
+-- Events of the warehouse that are published, an empty object publishes all events.
+alter table warehouse add column event_filter json not null default (json_object());
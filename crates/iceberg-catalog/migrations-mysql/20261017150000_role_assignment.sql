@@ -0,0 +1,20 @@
+-- Roles granted to principals by the built-in role based access control.
+-- `object_id` is the id of the object the role is granted on, the most specific of the ids.
+create table role_assignment (
+    assignment_id binary(16) primary key,
+    principal varchar(255) not null,
+    `role` enum('reader', 'writer', 'admin') not null,
+    project_id binary(16) not null,
+    warehouse_id binary(16),
+    namespace_id binary(16),
+    table_id binary(16),
+    object_id binary(16) not null,
+    created_by text,
+    created_at datetime(6) not null default current_timestamp(6),
+    constraint unique_role_per_principal_and_object unique (principal, object_id),
+    index role_assignment_project_id_idx (project_id, principal),
+    constraint role_assignment_project_id_fkey foreign key (project_id) references project (project_id) on delete cascade,
+    constraint role_assignment_warehouse_id_fkey foreign key (warehouse_id) references warehouse (warehouse_id) on delete cascade,
+    constraint role_assignment_namespace_id_fkey foreign key (namespace_id) references namespace (namespace_id) on delete cascade,
+    constraint role_assignment_table_id_fkey foreign key (table_id) references `table` (table_id) on delete cascade
+);
@@ -0,0 +1,8 @@
+-- Secrets of the MySQL catalog, encrypted by the catalog with `mysql_encryption_key`.
+-- `secret` holds the nonce, the authentication tag and the ciphertext.
+create table secret (
+    secret_id binary(16) primary key,
+    secret blob not null,
+    created_at datetime(6) not null default current_timestamp(6),
+    updated_at datetime(6) null on update current_timestamp(6)
+);
@@ -0,0 +1,18 @@
+-- Principal (typically the `sub` claim of the token) that created the object
+-- and the principal currently owning it. Both are null if the object was created
+-- without authentication.
+alter table "warehouse"
+    add column created_by text,
+    add column "owner" text;
+
+alter table "namespace"
+    add column created_by text,
+    add column "owner" text;
+
+alter table "table"
+    add column created_by text,
+    add column "owner" text;
+
+create index "warehouse_owner_idx" on "warehouse" ("owner");
+create index "namespace_owner_idx" on "namespace" ("owner");
+create index "table_owner_idx" on "table" ("owner");
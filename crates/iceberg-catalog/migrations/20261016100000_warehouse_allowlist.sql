@@ -0,0 +1,3 @@
+-- Principals and groups that may access the warehouse at all.
+-- Null means that access is not restricted beyond the AuthZHandler.
+alter table "warehouse" add column allowlist jsonb;
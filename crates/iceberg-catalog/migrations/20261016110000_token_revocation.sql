@@ -0,0 +1,13 @@
+-- Revoked tokens. A row either revokes a single token by its `jti` claim
+-- or all tokens of a subject that were issued before `revoked_at`.
+create table "token_revocation" (
+    token_revocation_id uuid primary key default uuid_generate_v1mc(),
+    token_id text,
+    subject text,
+    revoked_at timestamptz not null default now(),
+    -- Rows can be ignored after this point in time, typically the `exp` of the revoked token.
+    expires_at timestamptz,
+    reason text,
+    CONSTRAINT token_revocation_single_target CHECK ((token_id is null) <> (subject is null))
+);
+create index "token_revocation_expires_at_idx" on "token_revocation" (expires_at);
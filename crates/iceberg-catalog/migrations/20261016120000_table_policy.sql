@@ -0,0 +1,3 @@
+-- Governance policy attached to a table (classification, column sensitivity, retention class).
+-- Null if no policy is attached.
+alter table "table" add column "policy" jsonb;
@@ -0,0 +1,16 @@
+-- Read-only shares of namespaces or tables with other projects.
+-- Shares are mounted in all warehouses of the consumer project under `mount_name`.
+create table "share" (
+    share_id uuid primary key default uuid_generate_v1mc(),
+    -- Warehouse that owns the shared namespace or table.
+    warehouse_id uuid not null REFERENCES "warehouse"(warehouse_id) ON DELETE CASCADE,
+    namespace_id uuid REFERENCES "namespace"(namespace_id) ON DELETE CASCADE,
+    table_id uuid REFERENCES "table"(table_id) ON DELETE CASCADE,
+    consumer_project_id uuid not null,
+    mount_name text collate "case_insensitive" not null,
+    created_by text,
+    created_at timestamptz not null default now(),
+    CONSTRAINT "share_namespace_xor_table" CHECK ((namespace_id is null) <> (table_id is null)),
+    CONSTRAINT "unique_mount_name_per_project" UNIQUE (consumer_project_id, mount_name)
+);
+CREATE INDEX "share_warehouse_id_idx" ON "share" (warehouse_id);
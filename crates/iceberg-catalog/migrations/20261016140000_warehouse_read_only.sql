@@ -0,0 +1,3 @@
+-- Read-only warehouses serve all read operations but reject
+-- creates, commits, drops and renames.
+alter table warehouse add column read_only boolean not null default false;
@@ -0,0 +1,29 @@
+-- Every metadata file a table has pointed to, recorded whenever
+-- the metadata location of a table changes.
+create table "table_metadata_log" (
+    table_id uuid not null REFERENCES "table"(table_id) ON DELETE CASCADE,
+    metadata_location text not null,
+    created_at timestamptz not null default now(),
+    PRIMARY KEY (table_id, metadata_location)
+);
+
+create or replace function log_table_metadata_location() returns trigger as $$ begin
+    if NEW.metadata_location is not null
+        and (TG_OP = 'INSERT' or NEW.metadata_location is distinct from OLD.metadata_location) then
+        insert into table_metadata_log (table_id, metadata_location)
+        values (NEW.table_id, NEW.metadata_location)
+        on conflict do nothing;
+    end if;
+    return NEW;
+end;
+$$ language plpgsql;
+
+create trigger log_table_metadata_location
+    after insert or update of metadata_location on "table"
+    for each row execute function log_table_metadata_location();
+
+-- Tables created before the log existed only know their current metadata file.
+insert into table_metadata_log (table_id, metadata_location, created_at)
+select table_id, metadata_location, coalesce(updated_at, created_at)
+from "table"
+where metadata_location is not null;
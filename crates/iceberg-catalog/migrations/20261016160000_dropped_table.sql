@@ -0,0 +1,17 @@
+-- Dropped tables are kept here until they are reclaimed by the
+-- garbage collector after the configured retention period.
+create table "dropped_table" (
+    table_id uuid primary key,
+    warehouse_id uuid not null REFERENCES "warehouse"(warehouse_id) ON DELETE CASCADE,
+    namespace_name text[] not null,
+    table_name text not null,
+    -- Cleared once the table is reclaimed.
+    "metadata" jsonb,
+    -- All metadata files the table pointed to while it existed.
+    metadata_locations text[] not null,
+    table_location text not null,
+    dropped_at timestamptz not null default now(),
+    reclaimed_at timestamptz,
+    storage_purged boolean not null default false
+);
+create index "dropped_table_dropped_at_idx" on "dropped_table" (dropped_at) where reclaimed_at is null;
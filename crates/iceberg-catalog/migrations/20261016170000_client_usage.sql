@@ -0,0 +1,11 @@
+-- Catalog sessions per warehouse and client engine version.
+-- Clients without a known version are stored with an empty `engine_version`.
+create table "client_usage" (
+    warehouse_id uuid not null REFERENCES "warehouse"(warehouse_id) ON DELETE CASCADE,
+    engine text not null,
+    engine_version text not null default '',
+    session_count bigint not null default 0,
+    first_seen timestamptz not null default now(),
+    last_seen timestamptz not null default now(),
+    PRIMARY KEY (warehouse_id, engine, engine_version)
+);
@@ -0,0 +1,3 @@
+-- Warehouses in pyiceberg compatibility mode tolerate identifier and
+-- signer deviations of older pyiceberg clients.
+alter table warehouse add column pyiceberg_compat boolean not null default false;
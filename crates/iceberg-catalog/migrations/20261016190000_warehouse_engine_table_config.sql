@@ -0,0 +1,2 @@
+-- Extra table config returned to specific client engines, keyed by engine name.
+alter table warehouse add column engine_table_config jsonb not null default '{}';
@@ -0,0 +1,3 @@
+-- Address of the S3 signer returned to clients, if it differs
+-- from the one derived from the base URI of the catalog.
+alter table warehouse add column signer_uri text;
@@ -0,0 +1,4 @@
+-- Allows existence checks of non-staged tables to be answered by an index-only scan.
+-- Staged tables are covered by the index of "unique_table_name_per_namespace".
+create index "table_namespace_id_table_name_not_staged_idx" on "table" (namespace_id, table_name)
+    where metadata_location is not null;
@@ -0,0 +1,8 @@
+-- Requests and successful commits per warehouse and hour.
+create table "api_usage" (
+    warehouse_id uuid not null REFERENCES "warehouse"(warehouse_id) ON DELETE CASCADE,
+    window_start timestamptz not null,
+    request_count bigint not null default 0,
+    commit_count bigint not null default 0,
+    PRIMARY KEY (warehouse_id, window_start)
+);
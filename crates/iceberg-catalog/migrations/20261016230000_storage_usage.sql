@@ -0,0 +1,13 @@
+-- Estimated storage usage per namespace, refreshed periodically by the storage usage job.
+-- Sizes are taken from the `total-files-size` summary of each table's current snapshot.
+create table "storage_usage" (
+    namespace_id uuid primary key REFERENCES "namespace"(namespace_id) ON DELETE CASCADE,
+    warehouse_id uuid not null REFERENCES "warehouse"(warehouse_id) ON DELETE CASCADE,
+    table_count bigint not null,
+    total_files_size_bytes bigint not null,
+    -- Tables whose current snapshot has no `total-files-size` summary.
+    tables_without_size bigint not null,
+    computed_at timestamptz not null default now()
+);
+
+create index "storage_usage_warehouse_id_idx" on "storage_usage" (warehouse_id);
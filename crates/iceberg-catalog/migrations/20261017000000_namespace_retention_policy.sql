@@ -0,0 +1,3 @@
+-- Snapshot retention applied to the tables of a namespace and its child namespaces.
+-- Null if no policy is set.
+alter table "namespace" add column "retention_policy" jsonb;
@@ -0,0 +1,3 @@
+-- Default snapshot retention of the tables in a warehouse.
+-- Namespace policies take precedence. Null if no policy is set.
+alter table "warehouse" add column "retention_policy" jsonb;
@@ -0,0 +1,3 @@
+-- Incremented on every change of the metadata of a table.
+-- Commits only update a table if its version is still the one they read.
+alter table "table" add column "metadata_version" bigint not null default 0;
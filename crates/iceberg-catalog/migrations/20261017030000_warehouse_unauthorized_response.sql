@@ -0,0 +1,5 @@
+-- Response to requests for objects the principal is not allowed to access.
+-- null falls back to the server default.
+create type unauthorized_response as enum ('forbidden', 'not-found');
+
+alter table warehouse add column unauthorized_response unauthorized_response;
@@ -0,0 +1,4 @@
+-- Result of the last periodic check of the storage profile and credential.
+-- null if the warehouse was not checked yet.
+alter table warehouse add column storage_validated_at timestamptz;
+alter table warehouse add column storage_validation_error text;
@@ -0,0 +1,13 @@
+-- Commits to tables through the catalog, including the principal that committed.
+-- Rows are written in the same transaction as the new metadata location.
+create table "table_commit" (
+    table_id uuid not null REFERENCES "table"(table_id) ON DELETE CASCADE,
+    metadata_location text not null,
+    committed_at timestamptz not null,
+    committed_by text,
+    snapshot_id bigint,
+    operation text,
+    PRIMARY KEY (table_id, metadata_location)
+);
+
+create index "table_commit_table_id_committed_at_idx" on "table_commit" (table_id, committed_at desc);
@@ -0,0 +1,8 @@
+-- Lineage events of snapshots, only written if `store_lineage_events` is enabled.
+create table "table_lineage_event" (
+    table_id uuid not null REFERENCES "table"(table_id) ON DELETE CASCADE,
+    snapshot_id bigint not null,
+    event jsonb not null,
+    created_at timestamptz not null default now(),
+    PRIMARY KEY (table_id, snapshot_id)
+);
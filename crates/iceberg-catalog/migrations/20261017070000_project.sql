@@ -0,0 +1,15 @@
+-- Human readable names and metadata of projects. Projects are still created implicitly
+-- with their first warehouse, named after their id until they are renamed.
+create table "project" (
+    project_id uuid primary key,
+    project_name text collate "case_insensitive" not null,
+    metadata jsonb not null default '{}'::jsonb,
+    created_at timestamptz not null default now(),
+    updated_at timestamptz,
+    -- Also serves lookups of projects by name.
+    CONSTRAINT unique_project_name UNIQUE (project_name)
+);
+SELECT trigger_updated_at('"project"');
+
+insert into "project" (project_id, project_name)
+select distinct project_id, project_id::text from warehouse;
@@ -0,0 +1,5 @@
+-- Access delegation used if a client does not request a mode via
+-- the X-Iceberg-Access-Delegation header. null keeps the storage default.
+create type access_delegation as enum ('remote-signing', 'vended-credentials', 'disabled');
+
+alter table warehouse add column access_delegation access_delegation;
@@ -0,0 +1,3 @@
+-- Whether tables may be located outside of the storage profile of the warehouse,
+-- e.g. via the `set-location` update or the `write.data.path` property.
+alter table warehouse add column allow_external_locations boolean not null default false;
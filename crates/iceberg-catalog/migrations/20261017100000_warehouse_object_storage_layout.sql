@@ -0,0 +1,4 @@
+-- Whether new tables are located below a hash prefix to spread them across the
+-- key space of the bucket. Tables and namespaces may override it with the
+-- `write.object-storage.enabled` property.
+alter table warehouse add column object_storage_layout boolean not null default false;
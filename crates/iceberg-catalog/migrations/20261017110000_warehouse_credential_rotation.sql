@@ -0,0 +1,5 @@
+-- The storage secret replaced by the last rotation of the credential of a warehouse.
+-- It is kept for a grace period and then deleted by the credential rotation job.
+alter table warehouse
+    add column previous_storage_secret_id uuid,
+    add column storage_secret_rotated_at timestamptz;
@@ -0,0 +1,12 @@
+-- Webhook deliveries that failed permanently, kept for inspection and manual redelivery.
+create table "webhook_dead_letter" (
+    webhook_dead_letter_id uuid primary key default uuid_generate_v1mc(),
+    event_id text not null,
+    url text not null,
+    -- The event as it was posted, in the structured CloudEvents JSON format.
+    event jsonb not null,
+    attempts integer not null,
+    error text not null,
+    failed_at timestamptz not null default now()
+);
+create index "webhook_dead_letter_failed_at_idx" on "webhook_dead_letter" (failed_at);
@@ -0,0 +1,20 @@
+-- Roles granted to principals by the built-in role based access control.
+create type rbac_role as enum ('reader', 'writer', 'admin');
+
+create table "role_assignment" (
+    assignment_id uuid primary key default uuid_generate_v1mc(),
+    principal text not null,
+    "role" rbac_role not null,
+    -- Project of the object, set for all assignments.
+    project_id uuid not null REFERENCES "project"(project_id) ON DELETE CASCADE,
+    -- Set for roles on warehouses, namespaces and tables.
+    warehouse_id uuid REFERENCES "warehouse"(warehouse_id) ON DELETE CASCADE,
+    namespace_id uuid REFERENCES "namespace"(namespace_id) ON DELETE CASCADE,
+    table_id uuid REFERENCES "table"(table_id) ON DELETE CASCADE,
+    -- Id of the object the role is granted on, the most specific of the ids above.
+    object_id uuid not null,
+    created_by text,
+    created_at timestamptz not null default now(),
+    CONSTRAINT "unique_role_per_principal_and_object" UNIQUE (principal, object_id)
+);
+CREATE INDEX "role_assignment_project_id_idx" ON "role_assignment" (project_id, principal);
@@ -0,0 +1,6 @@
+-- Project of each warehouse, only maintained with `pg_schema_per_project`. The table of
+-- the shared schema routes requests for a warehouse to the schema of its project.
+create table "project_warehouse" (
+    warehouse_id uuid primary key,
+    project_id uuid not null REFERENCES "project"(project_id) ON DELETE CASCADE
+);
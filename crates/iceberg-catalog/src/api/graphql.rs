@@ -0,0 +1,470 @@
+//! Read-only GraphQL API over the catalog model.
+//!
+//! Catalog browsers need projects, warehouses, namespaces and tables including
+//! their statistics, which takes dozens of round trips with the REST API.
+//! All resolvers call the corresponding REST services, so that the same
+//! authorization checks apply. Objects the caller may not see fail the
+//! respective field, not the whole query.
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Enum, ErrorExtensions as _, Object, Schema,
+    SimpleObject,
+};
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use axum::extract::State as AxumState;
+use axum::routing::post;
+use axum::{Extension, Router};
+use iceberg::spec::{FormatVersion, TableMetadata};
+
+use crate::api::iceberg::types::{PageToken, Prefix};
+use crate::api::iceberg::v1::namespace::Service as _;
+use crate::api::iceberg::v1::tables::Service as _;
+use crate::api::iceberg::v1::{ListNamespacesQuery, NamespaceParameters, PaginationQuery};
+use crate::api::management::v1::table::{
+    BulkLoadTablesRequest, BulkLoadedTable, Service as _, MAX_BULK_TABLES,
+};
+use crate::api::management::v1::warehouse::{
+    GetWarehouseResponse, ListWarehousesRequest, Service as _,
+};
+use crate::api::management::v1::ApiServer;
+use crate::api::{ApiContext, IcebergErrorResponse};
+use crate::catalog::CatalogServer;
+use crate::request_metadata::RequestMetadata;
+use crate::service::auth::AuthZHandler;
+use crate::service::{Catalog, NamespaceIdent, SecretStore, State, TableIdent, WarehouseStatus};
+use crate::WarehouseIdent;
+
+/// Maximum nesting of a query. Namespaces can be nested arbitrarily deep,
+/// each level costs one request per namespace.
+const MAX_QUERY_DEPTH: usize = 16;
+
+type CatalogSchema<C, A, S> = Schema<QueryRoot<C, A, S>, EmptyMutation, EmptySubscription>;
+type GraphQLResult<T> = async_graphql::Result<T>;
+
+/// Router serving the GraphQL API at `/graphql`.
+#[must_use]
+pub fn new_router<C: Catalog, A: AuthZHandler, S: SecretStore>(
+) -> Router<ApiContext<State<A, C, S>>> {
+    let schema = Schema::build(
+        QueryRoot::<C, A, S>(PhantomData),
+        EmptyMutation,
+        EmptySubscription,
+    )
+    .limit_depth(MAX_QUERY_DEPTH)
+    .finish();
+
+    Router::new()
+        .route("/graphql", post(graphql_handler::<C, A, S>))
+        .layer(Extension(schema))
+}
+
+async fn graphql_handler<C: Catalog, A: AuthZHandler, S: SecretStore>(
+    AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+    Extension(metadata): Extension<RequestMetadata>,
+    Extension(schema): Extension<CatalogSchema<C, A, S>>,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    schema
+        .execute(request.into_inner().data(api_context).data(metadata))
+        .await
+        .into()
+}
+
+fn request_context<C: Catalog, A: AuthZHandler, S: SecretStore>(
+    ctx: &Context<'_>,
+) -> GraphQLResult<(ApiContext<State<A, C, S>>, RequestMetadata)> {
+    Ok((
+        ctx.data::<ApiContext<State<A, C, S>>>()?.clone(),
+        ctx.data::<RequestMetadata>()?.clone(),
+    ))
+}
+
+fn graphql_error(error: IcebergErrorResponse) -> async_graphql::Error {
+    let error = error.error;
+    async_graphql::Error::new(error.message).extend_with(|_, extensions| {
+        extensions.set("code", error.code);
+        extensions.set("type", error.r#type);
+    })
+}
+
+fn properties(properties: &HashMap<String, String>) -> Vec<Property> {
+    let mut properties = properties
+        .iter()
+        .map(|(key, value)| Property {
+            key: key.clone(),
+            value: value.clone(),
+        })
+        .collect::<Vec<_>>();
+    properties.sort_by(|a, b| a.key.cmp(&b.key));
+    properties
+}
+
+struct QueryRoot<C: Catalog, A: AuthZHandler, S: SecretStore>(PhantomData<fn() -> (C, A, S)>);
+
+#[Object(name = "Query")]
+impl<C: Catalog, A: AuthZHandler, S: SecretStore> QueryRoot<C, A, S> {
+    /// Projects the caller has access to.
+    async fn projects(&self, ctx: &Context<'_>) -> GraphQLResult<Vec<Project<C, A, S>>> {
+        let (api_context, metadata) = request_context::<C, A, S>(ctx)?;
+        let projects = ApiServer::<C, A, S>::list_projects(api_context, metadata)
+            .await
+            .map_err(graphql_error)?;
+        Ok(projects
+            .projects
+            .into_iter()
+            .map(|project| Project {
+                id: project.project_id,
+                name: project.project_name,
+                phantom: PhantomData,
+            })
+            .collect())
+    }
+
+    /// A single warehouse.
+    async fn warehouse(
+        &self,
+        ctx: &Context<'_>,
+        id: uuid::Uuid,
+    ) -> GraphQLResult<Warehouse<C, A, S>> {
+        let (api_context, metadata) = request_context::<C, A, S>(ctx)?;
+        let warehouse = ApiServer::<C, A, S>::get_warehouse(id.into(), api_context, metadata)
+            .await
+            .map_err(graphql_error)?;
+        Ok(Warehouse::new(warehouse))
+    }
+}
+
+struct Project<C: Catalog, A: AuthZHandler, S: SecretStore> {
+    id: uuid::Uuid,
+    name: String,
+    phantom: PhantomData<fn() -> (C, A, S)>,
+}
+
+#[Object(name = "Project")]
+impl<C: Catalog, A: AuthZHandler, S: SecretStore> Project<C, A, S> {
+    async fn id(&self) -> uuid::Uuid {
+        self.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Warehouses of the project. Inactive warehouses are only included if requested.
+    async fn warehouses(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = false)] include_inactive: bool,
+    ) -> GraphQLResult<Vec<Warehouse<C, A, S>>> {
+        let (api_context, metadata) = request_context::<C, A, S>(ctx)?;
+        let request = ListWarehousesRequest {
+            warehouse_status: include_inactive
+                .then(|| vec![WarehouseStatus::Active, WarehouseStatus::Inactive]),
+            project_id: Some(self.id.to_string()),
+        };
+        let warehouses = ApiServer::<C, A, S>::list_warehouses(request, api_context, metadata)
+            .await
+            .map_err(graphql_error)?;
+        Ok(warehouses
+            .warehouses
+            .into_iter()
+            .map(Warehouse::new)
+            .collect())
+    }
+}
+
+/// Status of a warehouse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+#[graphql(name = "WarehouseStatus")]
+enum WarehouseStatusObject {
+    Active,
+    Inactive,
+}
+
+impl From<WarehouseStatus> for WarehouseStatusObject {
+    fn from(status: WarehouseStatus) -> Self {
+        match status {
+            WarehouseStatus::Active => Self::Active,
+            WarehouseStatus::Inactive => Self::Inactive,
+        }
+    }
+}
+
+struct Warehouse<C: Catalog, A: AuthZHandler, S: SecretStore> {
+    warehouse: GetWarehouseResponse,
+    phantom: PhantomData<fn() -> (C, A, S)>,
+}
+
+impl<C: Catalog, A: AuthZHandler, S: SecretStore> Warehouse<C, A, S> {
+    fn new(warehouse: GetWarehouseResponse) -> Self {
+        Self {
+            warehouse,
+            phantom: PhantomData,
+        }
+    }
+
+    fn warehouse_id(&self) -> WarehouseIdent {
+        self.warehouse.id.into()
+    }
+}
+
+#[Object(name = "Warehouse")]
+impl<C: Catalog, A: AuthZHandler, S: SecretStore> Warehouse<C, A, S> {
+    async fn id(&self) -> uuid::Uuid {
+        self.warehouse.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.warehouse.name
+    }
+
+    async fn project_id(&self) -> uuid::Uuid {
+        self.warehouse.project_id
+    }
+
+    async fn status(&self) -> WarehouseStatusObject {
+        self.warehouse.status.into()
+    }
+
+    async fn read_only(&self) -> bool {
+        self.warehouse.read_only
+    }
+
+    /// Top-level namespaces of the warehouse.
+    async fn namespaces(&self, ctx: &Context<'_>) -> GraphQLResult<Vec<Namespace<C, A, S>>> {
+        list_namespaces(ctx, self.warehouse_id(), None).await
+    }
+}
+
+async fn list_namespaces<C: Catalog, A: AuthZHandler, S: SecretStore>(
+    ctx: &Context<'_>,
+    warehouse_id: WarehouseIdent,
+    parent: Option<NamespaceIdent>,
+) -> GraphQLResult<Vec<Namespace<C, A, S>>> {
+    let (api_context, metadata) = request_context::<C, A, S>(ctx)?;
+    let query = ListNamespacesQuery {
+        page_token: PageToken::NotSpecified,
+        page_size: None,
+        parent,
+    };
+    let namespaces = CatalogServer::<C, A, S>::list_namespaces(
+        Some(Prefix::from(warehouse_id.to_string())),
+        query,
+        api_context,
+        metadata,
+    )
+    .await
+    .map_err(graphql_error)?;
+    Ok(namespaces
+        .namespaces
+        .into_iter()
+        .map(|namespace| Namespace {
+            warehouse_id: warehouse_id.clone(),
+            namespace,
+            phantom: PhantomData,
+        })
+        .collect())
+}
+
+struct Namespace<C: Catalog, A: AuthZHandler, S: SecretStore> {
+    warehouse_id: WarehouseIdent,
+    namespace: NamespaceIdent,
+    phantom: PhantomData<fn() -> (C, A, S)>,
+}
+
+impl<C: Catalog, A: AuthZHandler, S: SecretStore> Namespace<C, A, S> {
+    fn parameters(&self) -> NamespaceParameters {
+        NamespaceParameters {
+            prefix: Some(Prefix::from(self.warehouse_id.to_string())),
+            namespace: self.namespace.clone(),
+        }
+    }
+}
+
+#[Object(name = "Namespace")]
+impl<C: Catalog, A: AuthZHandler, S: SecretStore> Namespace<C, A, S> {
+    /// Parts of the namespace, starting at the top level.
+    async fn name(&self) -> Vec<String> {
+        self.namespace.clone().inner()
+    }
+
+    async fn properties(&self, ctx: &Context<'_>) -> GraphQLResult<Vec<Property>> {
+        let (api_context, metadata) = request_context::<C, A, S>(ctx)?;
+        let namespace = CatalogServer::<C, A, S>::load_namespace_metadata(
+            self.parameters(),
+            api_context,
+            metadata,
+        )
+        .await
+        .map_err(graphql_error)?;
+        Ok(namespace
+            .properties
+            .as_ref()
+            .map(properties)
+            .unwrap_or_default())
+    }
+
+    /// Direct children of the namespace.
+    async fn namespaces(&self, ctx: &Context<'_>) -> GraphQLResult<Vec<Namespace<C, A, S>>> {
+        list_namespaces(ctx, self.warehouse_id.clone(), Some(self.namespace.clone())).await
+    }
+
+    /// Tables in the namespace. Metadata of all tables is loaded in batches.
+    async fn tables(&self, ctx: &Context<'_>) -> GraphQLResult<Vec<Table>> {
+        let (api_context, metadata) = request_context::<C, A, S>(ctx)?;
+        let identifiers = CatalogServer::<C, A, S>::list_tables(
+            self.parameters(),
+            PaginationQuery {
+                page_token: PageToken::NotSpecified,
+                page_size: None,
+            },
+            api_context.clone(),
+            metadata.clone(),
+        )
+        .await
+        .map_err(graphql_error)?
+        .identifiers;
+
+        let mut loaded = HashMap::with_capacity(identifiers.len());
+        for chunk in identifiers.chunks(MAX_BULK_TABLES) {
+            let request = BulkLoadTablesRequest {
+                tables: chunk.iter().cloned().map(Into::into).collect(),
+            };
+            let response = ApiServer::<C, A, S>::bulk_load_tables(
+                self.warehouse_id.clone(),
+                request,
+                api_context.clone(),
+                metadata.clone(),
+            )
+            .await
+            .map_err(graphql_error)?;
+            loaded.extend(
+                response
+                    .tables
+                    .into_iter()
+                    .map(|table| (table.name.clone(), table)),
+            );
+        }
+
+        Ok(identifiers
+            .into_iter()
+            .map(|ident| Table {
+                loaded: loaded.remove(&ident.name),
+                ident,
+            })
+            .collect())
+    }
+}
+
+/// A table. Tables mounted from a share are listed without metadata.
+struct Table {
+    ident: TableIdent,
+    loaded: Option<BulkLoadedTable>,
+}
+
+#[Object]
+impl Table {
+    async fn namespace(&self) -> Vec<String> {
+        self.ident.namespace.clone().inner()
+    }
+
+    async fn name(&self) -> &str {
+        &self.ident.name
+    }
+
+    async fn id(&self) -> Option<uuid::Uuid> {
+        self.loaded.as_ref().map(|table| table.table_id)
+    }
+
+    async fn metadata_location(&self) -> Option<&str> {
+        self.loaded
+            .as_ref()
+            .map(|table| table.metadata_location.as_str())
+    }
+
+    async fn properties(&self) -> Vec<Property> {
+        self.loaded
+            .as_ref()
+            .map(|table| properties(table.metadata.properties()))
+            .unwrap_or_default()
+    }
+
+    async fn stats(&self) -> Option<TableStats> {
+        self.loaded
+            .as_ref()
+            .map(|table| TableStats::from(&table.metadata))
+    }
+}
+
+/// Statistics of a table, taken from its metadata and the summary of the current snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, SimpleObject)]
+struct TableStats {
+    format_version: i32,
+    snapshot_count: u64,
+    current_snapshot_id: Option<i64>,
+    last_updated_ms: i64,
+    total_records: Option<i64>,
+    total_data_files: Option<i64>,
+    total_file_size_bytes: Option<i64>,
+}
+
+impl From<&TableMetadata> for TableStats {
+    fn from(metadata: &TableMetadata) -> Self {
+        let summary = metadata
+            .current_snapshot()
+            .map(|snapshot| &snapshot.summary().other);
+        let summary_value = |key: &str| {
+            summary
+                .and_then(|summary| summary.get(key))
+                .and_then(|value| value.parse().ok())
+        };
+
+        Self {
+            format_version: match metadata.format_version() {
+                FormatVersion::V1 => 1,
+                FormatVersion::V2 => 2,
+            },
+            snapshot_count: metadata.snapshots().count() as u64,
+            current_snapshot_id: metadata.current_snapshot().map(|s| s.snapshot_id()),
+            last_updated_ms: metadata.last_updated_ms(),
+            total_records: summary_value("total-records"),
+            total_data_files: summary_value("total-data-files"),
+            total_file_size_bytes: summary_value("total-files-size"),
+        }
+    }
+}
+
+/// A namespace or table property.
+#[derive(Debug, Clone, PartialEq, Eq, SimpleObject)]
+struct Property {
+    key: String,
+    value: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_properties_are_sorted() {
+        let properties = properties(&HashMap::from([
+            ("owner".to_string(), "alice".to_string()),
+            ("location".to_string(), "s3://bucket".to_string()),
+        ]));
+        assert_eq!(
+            properties,
+            vec![
+                Property {
+                    key: "location".to_string(),
+                    value: "s3://bucket".to_string(),
+                },
+                Property {
+                    key: "owner".to_string(),
+                    value: "alice".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -22,11 +22,11 @@ pub mod v1 {
     pub use crate::api::{
         ApiContext, CatalogConfig, CommitTableRequest, CommitTableResponse,
         CommitTransactionRequest, CommitViewRequest, CreateNamespaceRequest,
-        CreateNamespaceResponse, CreateTableRequest, CreateViewRequest, ErrorModel,
+        CreateNamespaceResponse, CreateTableRequest, CreateViewRequest, ErrorModel, ErrorType,
         GetNamespaceResponse, IcebergErrorResponse, ListNamespacesResponse, ListTablesResponse,
-        LoadTableResult, LoadViewResult, OAuthTokenRequest, OAuthTokenResponse,
-        RegisterTableRequest, RenameTableRequest, Result, UpdateNamespacePropertiesRequest,
-        UpdateNamespacePropertiesResponse,
+        ListViewsResponse, LoadCredentialsResponse, LoadTableResult, LoadViewResult,
+        OAuthTokenRequest, OAuthTokenResponse, RegisterTableRequest, RenameTableRequest, Result,
+        TableCredential, UpdateNamespacePropertiesRequest, UpdateNamespacePropertiesResponse,
     };
     pub use crate::request_metadata::RequestMetadata;
 
@@ -56,4 +56,9 @@ pub mod v1 {
     pub fn new_v1_config_router<C: config::Service<S>, S: ThreadSafe>() -> Router<ApiContext<S>> {
         config::router::<C, S>()
     }
+
+    /// Router of the OAuth token endpoint, which is served without authentication.
+    pub fn new_v1_oauth_router<T: oauth::Service<S>, S: ThreadSafe>() -> Router<ApiContext<S>> {
+        oauth::router::<T, S>()
+    }
 }
@@ -19,6 +19,12 @@ impl Prefix {
     }
 }
 
+impl From<String> for Prefix {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 #[non_exhaustive]
 pub enum PageToken {
@@ -1,16 +1,22 @@
-use crate::api::{ApiContext, Result};
+use crate::api::{ApiContext, IcebergErrorResponse, Result};
 use crate::request_metadata::RequestMetadata;
 use async_trait::async_trait;
+use axum::extract::rejection::FormRejection;
 use axum::extract::State;
+use axum::response::{IntoResponse, Response};
 use axum::routing::post;
-use axum::{Extension, Form, Router};
-use iceberg_ext::catalog::rest::{OAuthTokenRequest, OAuthTokenResponse};
+use axum::{Extension, Form, Json, Router};
+use http::StatusCode;
+use iceberg_ext::catalog::rest::{
+    OAuthError, OAuthErrorType, OAuthTokenRequest, OAuthTokenResponse,
+};
 
 #[async_trait]
 pub trait Service<S: crate::api::ThreadSafe>
 where
     Self: Send + Sync + 'static,
 {
+    /// Errors with an [`OAuthErrorType`] as type are returned as [`OAuthError`].
     async fn get_token(
         state: ApiContext<S>,
         request_metadata: RequestMetadata,
@@ -26,9 +32,84 @@ pub fn router<I: Service<S>, S: crate::api::ThreadSafe>() -> Router<ApiContext<S
             |State(api_context): State<ApiContext<S>>,
              Extension(metadata): Extension<RequestMetadata>,
              // application/x-www-form-urlencoded
-             Form(request): Form<OAuthTokenRequest>| {
-                I::get_token(api_context, metadata, request)
+             request: std::result::Result<Form<OAuthTokenRequest>, FormRejection>| async move {
+                let request = match request {
+                    Ok(Form(request)) => request,
+                    Err(rejection) => {
+                        return oauth_error_response(
+                            StatusCode::BAD_REQUEST,
+                            OAuthErrorType::InvalidRequest,
+                            rejection.body_text(),
+                        )
+                    }
+                };
+                match I::get_token(api_context, metadata, request).await {
+                    Ok(response) => response.into_response(),
+                    Err(error) => into_oauth_response(error),
+                }
             },
         ),
     )
 }
+
+/// Token endpoints respond with an [`OAuthError`] as required by RFC 6749.
+/// Other errors, such as internal errors, keep the Iceberg error format.
+fn into_oauth_response(error: IcebergErrorResponse) -> Response {
+    let Ok(error_type) = serde_json::from_value::<OAuthErrorType>(serde_json::Value::String(
+        error.error.r#type.clone(),
+    )) else {
+        return error.into_response();
+    };
+    oauth_error_response(
+        StatusCode::from_u16(error.error.code).unwrap_or(StatusCode::BAD_REQUEST),
+        error_type,
+        error.error.message,
+    )
+}
+
+fn oauth_error_response(
+    status: StatusCode,
+    error: OAuthErrorType,
+    error_description: String,
+) -> Response {
+    (
+        status,
+        Json(OAuthError {
+            error,
+            error_description: Some(error_description),
+            error_uri: None,
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_into_oauth_response() {
+        let error: IcebergErrorResponse = crate::api::ErrorModel::builder()
+            .code(StatusCode::UNAUTHORIZED.into())
+            .message("Invalid client credentials.")
+            .r#type("invalid_client")
+            .build()
+            .into();
+        let response = into_oauth_response(error);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: OAuthError = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.error, OAuthErrorType::InvalidClient);
+
+        let error: IcebergErrorResponse = crate::api::ErrorModel::builder()
+            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+            .message("Failed to create catalog token.")
+            .r#type("CatalogTokenCreationFailed")
+            .build()
+            .into();
+        let response = into_oauth_response(error);
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -2,8 +2,8 @@ use crate::api::iceberg::types::Prefix;
 use crate::api::iceberg::v1::namespace::{NamespaceIdentUrl, NamespaceParameters, PaginationQuery};
 use crate::api::{
     ApiContext, CommitTableRequest, CommitTableResponse, CommitTransactionRequest,
-    CreateTableRequest, ListTablesResponse, LoadTableResult, RegisterTableRequest,
-    RenameTableRequest, Result,
+    CreateTableRequest, ListTablesResponse, LoadCredentialsResponse, LoadTableResult,
+    PresignRequest, PresignResponse, RegisterTableRequest, RenameTableRequest, Result,
 };
 use crate::request_metadata::RequestMetadata;
 use axum::extract::{Path, Query, State};
@@ -51,6 +51,22 @@ where
         request_metadata: RequestMetadata,
     ) -> Result<LoadTableResult>;
 
+    /// Load fresh storage credentials of a table, without its metadata
+    async fn load_table_credentials(
+        parameters: TableParameters,
+        data_access: DataAccess,
+        state: ApiContext<S>,
+        request_metadata: RequestMetadata,
+    ) -> Result<LoadCredentialsResponse>;
+
+    /// Pre-signed URLs to read files of a table, such as its metadata and manifests
+    async fn presign_table_files(
+        parameters: TableParameters,
+        request: PresignRequest,
+        state: ApiContext<S>,
+        request_metadata: RequestMetadata,
+    ) -> Result<PresignResponse>;
+
     /// Commit updates to a table
     async fn commit_table(
         parameters: TableParameters,
@@ -238,6 +254,54 @@ pub fn router<I: Service<S>, S: crate::api::ThreadSafe>() -> Router<ApiContext<S
                 },
             ),
         )
+        // /{prefix}/namespaces/{namespace}/tables/{table}/credentials
+        .route(
+            "/:prefix/namespaces/:namespace/tables/:table/credentials",
+            // Load fresh storage credentials of a table
+            get(
+                |Path((prefix, namespace, table)): Path<(Prefix, NamespaceIdentUrl, String)>,
+                 State(api_context): State<ApiContext<S>>,
+                 headers: HeaderMap,
+                 Extension(metadata): Extension<RequestMetadata>| {
+                    I::load_table_credentials(
+                        TableParameters {
+                            prefix: Some(prefix),
+                            table: TableIdent {
+                                namespace: namespace.into(),
+                                name: table,
+                            },
+                        },
+                        parse_data_access(&headers),
+                        api_context,
+                        metadata,
+                    )
+                },
+            ),
+        )
+        // /{prefix}/namespaces/{namespace}/tables/{table}/presign
+        .route(
+            "/:prefix/namespaces/:namespace/tables/:table/presign",
+            // Pre-signed URLs to read files of a table
+            post(
+                |Path((prefix, namespace, table)): Path<(Prefix, NamespaceIdentUrl, String)>,
+                 State(api_context): State<ApiContext<S>>,
+                 Extension(metadata): Extension<RequestMetadata>,
+                 Json(request): Json<PresignRequest>| {
+                    I::presign_table_files(
+                        TableParameters {
+                            prefix: Some(prefix),
+                            table: TableIdent {
+                                namespace: namespace.into(),
+                                name: table,
+                            },
+                        },
+                        request,
+                        api_context,
+                        metadata,
+                    )
+                },
+            ),
+        )
         // /{prefix}/tables/rename
         .route(
             "/:prefix/tables/rename",
@@ -289,10 +353,12 @@ pub struct DataAccess {
 }
 
 fn parse_data_access(headers: &HeaderMap) -> DataAccess {
+    // The header may be repeated or hold a comma separated list of modes.
     let header = headers
         .get_all(DATA_ACCESS_HEADER)
         .iter()
-        .map(|v| v.to_str().unwrap())
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(',').map(str::trim))
         .collect::<Vec<_>>();
     let vended_credentials = header.contains(&"vended-credentials");
     let remote_signing = header.contains(&"remote-signing");
@@ -334,4 +400,16 @@ mod test {
         assert!(data_access.vended_credentials);
         assert!(!data_access.remote_signing);
     }
+
+    #[test]
+    fn test_parse_data_access_list() {
+        let mut headers = http::header::HeaderMap::new();
+        headers.insert(
+            http::header::HeaderName::from_str(super::DATA_ACCESS_HEADER).unwrap(),
+            http::header::HeaderValue::from_static("vended-credentials, remote-signing"),
+        );
+        let data_access = super::parse_data_access(&headers);
+        assert!(data_access.vended_credentials);
+        assert!(data_access.remote_signing);
+    }
 }
@@ -1,7 +1,7 @@
 use crate::api::iceberg::types::Prefix;
 use crate::api::iceberg::v1::namespace::{NamespaceIdentUrl, NamespaceParameters, PaginationQuery};
 use crate::api::{
-    ApiContext, CommitViewRequest, CreateViewRequest, ListTablesResponse, LoadViewResult,
+    ApiContext, CommitViewRequest, CreateViewRequest, ListViewsResponse, LoadViewResult,
     RenameTableRequest, Result,
 };
 use crate::request_metadata::RequestMetadata;
@@ -28,7 +28,7 @@ where
         query: PaginationQuery,
         state: ApiContext<S>,
         request_metadata: RequestMetadata,
-    ) -> Result<ListTablesResponse>;
+    ) -> Result<ListViewsResponse>;
 
     /// Create a view in the given namespace
     async fn create_view(
@@ -1,4 +1,11 @@
 pub mod v1 {
+    pub mod maintenance;
+    pub mod role;
+    pub mod runtime_config;
+    pub mod service_key;
+    pub mod share;
+    pub mod table;
+    pub mod token;
     pub mod warehouse;
     use axum::{Extension, Json, Router};
     use utoipa::OpenApi;
@@ -8,15 +15,50 @@ pub mod v1 {
     use crate::service::auth::AuthZHandler;
     use std::marker::PhantomData;
 
-    use crate::service::{Catalog, SecretStore, State};
+    use crate::service::runtime_config::RuntimeConfig;
+    use crate::service::{Catalog, Role, SecretStore, State};
     use axum::extract::{Path, Query, State as AxumState};
-    use axum::routing::{get, post};
+    use axum::routing::{delete, get, post};
+    use maintenance::{MaintenanceModeResponse, Service as _, SetMaintenanceModeRequest};
+    use role::{
+        GrantRoleRequest, GrantRoleResponse, ListRoleAssignmentsQuery, ListRoleAssignmentsResponse,
+        RevokeRoleRequest, RoleAssignmentObject, RoleAssignmentResponse, Service as _,
+    };
+    use runtime_config::{LogLevelResponse, Service as _, SetLogLevelRequest};
+    use service_key::{CreateServiceKeyRequest, CreateServiceKeyResponse, Service as _};
+    use share::{
+        CreateShareRequest, CreateShareResponse, ListSharesResponse, Service as _, ShareObject,
+        ShareResponse,
+    };
+    use table::{
+        BulkDropTableResult, BulkDropTablesRequest, BulkDropTablesResponse, BulkLoadTablesRequest,
+        BulkLoadTablesResponse, BulkLoadedTable, BulkTableIdent, GetTableMetadataLogResponse,
+        ListStagedTablesResponse, ListTableCommitsQuery, ListTableCommitsResponse,
+        MetadataLogEntryResponse, RollbackTableRequest, RollbackTableResponse, Service as _,
+        StagedTableResponse, TableCommitResponse,
+    };
+    use token::{RevokeTokenRequest, RevokeTokenTarget, Service as _};
     use warehouse::{
-        CreateWarehouseRequest, CreateWarehouseResponse, GetWarehouseResponse,
-        ListProjectsResponse, ListWarehousesRequest, ListWarehousesResponse, ProjectResponse,
-        RenameWarehouseRequest, S3Credential, S3Profile, Service, StorageCredential,
-        StorageProfile, UpdateWarehouseCredentialRequest, UpdateWarehouseStorageRequest,
-        WarehouseStatus,
+        AccessDelegation, ApiUsageResponse, ClientUsageResponse, CreateWarehouseRequest,
+        CreateWarehouseResponse, EngineTableConfig, EventFilter, FileProfile, GcsCredential,
+        GcsProfile, GcsServiceKey, GetProjectUsageQuery, GetProjectUsageResponse,
+        GetWarehouseByNameQuery, GetWarehouseResponse, GetWarehouseStorageUsageResponse,
+        HealthCheckResult, HealthStatus, ListClientUsageResponse, ListProjectsResponse,
+        ListWarehousesRequest, ListWarehousesResponse, NamespaceStorageUsageResponse,
+        OwnershipTransferObject, ProjectResponse, RenameProjectRequest, RenameWarehouseRequest,
+        RotateWarehouseCredentialRequest, RotateWarehouseCredentialResponse, S3Credential,
+        S3Flavor, S3Profile, S3SseAlgorithm, Service, SetEngineTableConfigRequest,
+        SetEventFilterRequest, SetNamespaceRetentionPolicyRequest, SetProjectMetadataRequest,
+        SetTablePolicyRequest, SetWarehouseAccessDelegationRequest,
+        SetWarehouseAllowExternalLocationsRequest, SetWarehouseAllowlistRequest,
+        SetWarehouseObjectStorageLayoutRequest, SetWarehousePyicebergCompatRequest,
+        SetWarehouseReadOnlyRequest, SetWarehouseRetentionPolicyRequest,
+        SetWarehouseSignerUriRequest, SetWarehouseUnauthorizedResponseRequest,
+        SnapshotRetentionPolicy, StorageCredential, StorageOperation, StorageProfile,
+        StorageValidation, TablePolicy, TransferOwnershipRequest, TransferOwnershipResponse,
+        UnauthorizedResponse, UpdateWarehouseCredentialRequest, UpdateWarehouseStorageRequest,
+        UsageWindow, ValidateStorageRequest, ValidateStorageResponse, WarehouseAllowlist,
+        WarehouseHealthResponse, WarehouseStatus,
     };
 
     #[derive(Debug, OpenApi)]
@@ -26,31 +68,164 @@ pub mod v1 {
         ),
         paths(
             activate_warehouse,
+            bulk_drop_tables,
+            bulk_load_tables,
+            create_service_key,
+            create_share,
             create_warehouse,
             deactivate_warehouse,
+            delete_project,
+            delete_service_key,
+            delete_share,
             delete_warehouse,
+            discard_staged_table,
+            get_log_level,
+            get_maintenance_mode,
+            get_project,
+            get_project_usage,
+            get_table_metadata_log,
             get_warehouse,
+            get_warehouse_by_name,
+            get_warehouse_health,
+            get_warehouse_storage_usage,
+            grant_role,
+            list_client_usage,
             list_projects,
+            list_role_assignments,
+            list_shares,
+            list_staged_tables,
+            list_table_commits,
             list_warehouses,
+            reload_config,
+            rename_project,
             rename_warehouse,
+            revoke_role,
+            revoke_token,
+            rollback_table,
+            rotate_warehouse_credential,
+            set_engine_table_config,
+            set_event_filter,
+            set_log_level,
+            set_maintenance_mode,
+            set_namespace_retention_policy,
+            set_project_metadata,
+            set_table_policy,
+            set_warehouse_allowlist,
+            set_warehouse_pyiceberg_compat,
+            set_warehouse_read_only,
+            set_warehouse_retention_policy,
+            set_warehouse_signer_uri,
+            set_warehouse_unauthorized_response,
+            set_warehouse_access_delegation,
+            set_warehouse_allow_external_locations,
+            set_warehouse_object_storage_layout,
+            transfer_ownership,
             update_storage_credential,
-            update_storage_profile
+            update_storage_profile,
+            validate_storage
         ),
         components(schemas(
+            AccessDelegation,
+            ApiUsageResponse,
+            BulkDropTableResult,
+            BulkDropTablesRequest,
+            BulkDropTablesResponse,
+            BulkTableIdent,
+            BulkLoadTablesRequest,
+            BulkLoadTablesResponse,
+            BulkLoadedTable,
+            ClientUsageResponse,
+            CreateServiceKeyRequest,
+            CreateServiceKeyResponse,
+            CreateShareRequest,
+            CreateShareResponse,
             CreateWarehouseRequest,
             CreateWarehouseResponse,
+            EngineTableConfig,
+            EventFilter,
+            FileProfile,
+            GcsCredential,
+            GcsProfile,
+            GcsServiceKey,
+            GetProjectUsageQuery,
+            GetProjectUsageResponse,
+            GetTableMetadataLogResponse,
+            GetWarehouseByNameQuery,
             GetWarehouseResponse,
+            GetWarehouseStorageUsageResponse,
+            GrantRoleRequest,
+            GrantRoleResponse,
+            HealthCheckResult,
+            HealthStatus,
+            ListClientUsageResponse,
             ListProjectsResponse,
+            ListRoleAssignmentsQuery,
+            ListRoleAssignmentsResponse,
+            ListSharesResponse,
+            ListStagedTablesResponse,
+            ListTableCommitsQuery,
+            ListTableCommitsResponse,
             ListWarehousesRequest,
             ListWarehousesResponse,
+            LogLevelResponse,
+            MaintenanceModeResponse,
+            MetadataLogEntryResponse,
+            NamespaceStorageUsageResponse,
+            OwnershipTransferObject,
             ProjectResponse,
+            RenameProjectRequest,
             RenameWarehouseRequest,
+            RevokeRoleRequest,
+            RevokeTokenRequest,
+            RevokeTokenTarget,
+            Role,
+            RoleAssignmentObject,
+            RoleAssignmentResponse,
+            RollbackTableRequest,
+            RollbackTableResponse,
+            RotateWarehouseCredentialRequest,
+            RotateWarehouseCredentialResponse,
+            RuntimeConfig,
             S3Credential,
+            S3Flavor,
             S3Profile,
+            S3SseAlgorithm,
+            SetEngineTableConfigRequest,
+            SetEventFilterRequest,
+            SetLogLevelRequest,
+            SetMaintenanceModeRequest,
+            SetNamespaceRetentionPolicyRequest,
+            SetProjectMetadataRequest,
+            SetTablePolicyRequest,
+            SetWarehouseAllowlistRequest,
+            SetWarehousePyicebergCompatRequest,
+            SetWarehouseReadOnlyRequest,
+            SetWarehouseRetentionPolicyRequest,
+            SetWarehouseSignerUriRequest,
+            SetWarehouseUnauthorizedResponseRequest,
+            SetWarehouseAccessDelegationRequest,
+            SetWarehouseAllowExternalLocationsRequest,
+            SetWarehouseObjectStorageLayoutRequest,
+            ShareObject,
+            ShareResponse,
+            SnapshotRetentionPolicy,
+            StagedTableResponse,
             StorageCredential,
+            StorageOperation,
             StorageProfile,
+            StorageValidation,
+            TableCommitResponse,
+            TablePolicy,
+            TransferOwnershipRequest,
+            TransferOwnershipResponse,
+            UnauthorizedResponse,
             UpdateWarehouseCredentialRequest,
             UpdateWarehouseStorageRequest,
+            UsageWindow,
+            ValidateStorageRequest,
+            ValidateStorageResponse,
+            WarehouseAllowlist,
+            WarehouseHealthResponse,
             WarehouseStatus
 
         ))
@@ -103,6 +278,114 @@ pub mod v1 {
         ApiServer::<C, A, S>::list_projects(api_context, metadata).await
     }
 
+    /// Get a project by ID or name
+    #[utoipa::path(
+        get,
+        tag = "management",
+        path = "management/v1/project/{project_id}",
+        params(("project_id" = String, Path, description = "ID or name of the project")),
+        responses(
+            (status = 200, description = "Project details", body = [ProjectResponse])
+        )
+    )]
+    async fn get_project<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(project): Path<String>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<ProjectResponse> {
+        ApiServer::<C, A, S>::get_project(&project, api_context, metadata).await
+    }
+
+    /// Delete a project
+    ///
+    /// Deletes the name, metadata and roles of the project. All warehouses of the
+    /// project must be deleted before.
+    #[utoipa::path(
+        delete,
+        tag = "management",
+        path = "management/v1/project/{project_id}",
+        params(("project_id" = String, Path, description = "ID or name of the project")),
+        responses(
+            (status = 200, description = "Project deleted successfully")
+        )
+    )]
+    async fn delete_project<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(project): Path<String>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::delete_project(&project, api_context, metadata).await
+    }
+
+    /// Rename a project
+    ///
+    /// Project names are unique and can be used instead of the project ID in paths
+    /// and the `project-id` query parameter. Until renamed, a project is named after its ID.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/project/{project_id}/rename",
+        params(("project_id" = String, Path, description = "ID or name of the project")),
+        request_body = RenameProjectRequest,
+        responses(
+            (status = 200, description = "Project renamed successfully")
+        )
+    )]
+    async fn rename_project<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(project): Path<String>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<RenameProjectRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::rename_project(&project, request, api_context, metadata).await
+    }
+
+    /// Replace the metadata of a project
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/project/{project_id}/metadata",
+        params(("project_id" = String, Path, description = "ID or name of the project")),
+        request_body = SetProjectMetadataRequest,
+        responses(
+            (status = 200, description = "Metadata set successfully")
+        )
+    )]
+    async fn set_project_metadata<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(project): Path<String>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<SetProjectMetadataRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::set_project_metadata(&project, request, api_context, metadata).await
+    }
+
+    /// Get the API usage of a project
+    ///
+    /// Returns the number of requests and successful commits per warehouse and window.
+    /// Requests that do not address a warehouse, such as `GET /config`, are not counted.
+    /// Usage is written periodically, so the most recent requests may be missing.
+    #[utoipa::path(
+        get,
+        tag = "management",
+        path = "management/v1/project/{project_id}/usage",
+        params(
+            ("project_id" = String, Path, description = "ID or name of the project"),
+            GetProjectUsageQuery
+        ),
+        responses(
+            (status = 200, description = "API usage of the project", body = [GetProjectUsageResponse])
+        )
+    )]
+    async fn get_project_usage<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(project): Path<String>,
+        Query(query): Query<GetProjectUsageQuery>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<GetProjectUsageResponse> {
+        ApiServer::<C, A, S>::get_project_usage(&project, query, api_context, metadata).await
+    }
+
     /// List all warehouses in a project
     ///
     /// By default, this endpoint does not return deactivated warehouses.
@@ -141,6 +424,123 @@ pub mod v1 {
         ApiServer::<C, A, S>::get_warehouse(warehouse_id.into(), api_context, metadata).await
     }
 
+    /// Get a warehouse by its name
+    ///
+    /// Warehouse names are unique within a project. Deactivated warehouses are returned as well.
+    #[utoipa::path(
+        get,
+        tag = "management",
+        path = "management/v1/project/{project_id}/warehouse",
+        params(
+            ("project_id" = String, Path, description = "ID or name of the project"),
+            GetWarehouseByNameQuery
+        ),
+        responses(
+            (status = 200, description = "Warehouse details", body = [GetWarehouseResponse])
+        )
+    )]
+    async fn get_warehouse_by_name<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(project): Path<String>,
+        Query(query): Query<GetWarehouseByNameQuery>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<GetWarehouseResponse> {
+        ApiServer::<C, A, S>::get_warehouse_by_name(&project, query, api_context, metadata).await
+    }
+
+    /// List client engines using a warehouse
+    ///
+    /// Returns the number of catalog sessions per engine and version,
+    /// as reported by the `User-Agent` and `X-Client-Version` headers of `GET /config` requests.
+    #[utoipa::path(
+        get,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/client-usage",
+        responses(
+            (status = 200, description = "Client usage of the warehouse", body = [ListClientUsageResponse])
+        )
+    )]
+    async fn list_client_usage<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<ListClientUsageResponse> {
+        ApiServer::<C, A, S>::list_client_usage(warehouse_id.into(), api_context, metadata).await
+    }
+
+    /// Get the storage usage of a warehouse
+    ///
+    /// Returns the estimated size of all tables per namespace, as reported by the
+    /// `total-files-size` summary of the current snapshot of each table.
+    /// Files of expired snapshots and orphaned files are not included.
+    /// Usage is computed periodically, see `computed-at` for the time of the last computation.
+    #[utoipa::path(
+        get,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/storage-usage",
+        responses(
+            (status = 200, description = "Storage usage of the warehouse", body = [GetWarehouseStorageUsageResponse])
+        )
+    )]
+    async fn get_warehouse_storage_usage<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<GetWarehouseStorageUsageResponse> {
+        ApiServer::<C, A, S>::get_warehouse_storage_usage(
+            warehouse_id.into(),
+            api_context,
+            metadata,
+        )
+        .await
+    }
+
+    /// Validate storage without creating a warehouse
+    ///
+    /// Runs the checks of `createWarehouse` with the given storage profile and credential,
+    /// followed by writing, reading and deleting a canary object. Responds with `200`
+    /// also if the storage is invalid, reporting the operation that failed and the error
+    /// of the storage. Requires permission to create warehouses in the project.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/validate-storage",
+        request_body = ValidateStorageRequest,
+        responses(
+            (status = 200, description = "Storage validated", body = [ValidateStorageResponse]),
+        )
+    )]
+    async fn validate_storage<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<ValidateStorageRequest>,
+    ) -> Result<ValidateStorageResponse> {
+        ApiServer::<C, A, S>::validate_storage(request, api_context, metadata).await
+    }
+
+    /// Check the health of a warehouse
+    ///
+    /// Loads the storage credential from the secret store and writes, reads and deletes
+    /// a canary object in the storage. Unlike the periodic storage validation, the checks
+    /// run on every request. Responds with `503` if any check failed. As the check writes
+    /// to the storage, it requires permission to update the storage of the warehouse.
+    #[utoipa::path(
+        get,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/health",
+        responses(
+            (status = 200, description = "All checks succeeded", body = [WarehouseHealthResponse]),
+            (status = 503, description = "At least one check failed", body = [WarehouseHealthResponse])
+        )
+    )]
+    async fn get_warehouse_health<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<WarehouseHealthResponse> {
+        ApiServer::<C, A, S>::get_warehouse_health(warehouse_id.into(), api_context, metadata).await
+    }
+
     /// Delete a warehouse by ID
     #[utoipa::path(
         delete,
@@ -252,45 +652,1077 @@ pub mod v1 {
             .await
     }
 
-    impl<C: Catalog, A: AuthZHandler, S: SecretStore> ApiServer<C, A, S> {
-        pub fn new_v1_router() -> Router<ApiContext<State<A, C, S>>> {
-            Router::new()
-                // Create a new warehouse
-                .route("/warehouse", post(create_warehouse))
-                // List all projects
-                .route("/project", get(list_projects))
-                .route(
-                    "/warehouse",
-                    // List all warehouses within a project
-                    get(list_warehouses),
-                )
-                .route(
-                    "/warehouse/:warehouse_id",
-                    get(get_warehouse).delete(delete_warehouse),
-                )
-                // Rename warehouse
-                .route("/warehouse/:warehouse_id/rename", post(rename_warehouse))
-                // Deactivate warehouse
-                .route(
-                    "/warehouse/:warehouse_id/deactivate",
-                    post(deactivate_warehouse),
-                )
-                .route(
-                    "/warehouse/:warehouse_id/activate",
-                    post(activate_warehouse),
-                )
-                // Update storage profile and credential.
-                // The old credential is not re-used. If credentials are not provided,
-                // we assume that this endpoint does not require a secret.
-                .route(
-                    "/warehouse/:warehouse_id/storage",
-                    post(update_storage_profile),
-                )
-                // Update only the storage credential - keep the storage profile as is
-                .route(
-                    "/warehouse/:warehouse_id/storage-credential",
-                    post(update_storage_credential),
+    /// Rotate the storage credential of a warehouse
+    ///
+    /// The new credential is validated against the storage and replaces the current
+    /// credential atomically. The replaced credential is kept for the grace period
+    /// configured by `credential_rotation_grace_period_seconds`. Afterwards, the new
+    /// credential is validated again and the previous one is deleted if the storage is
+    /// still accessible. Responds with `409` if the credential was changed concurrently.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/rotate-credential",
+        request_body = RotateWarehouseCredentialRequest,
+        responses(
+            (status = 200, description = "Storage credential rotated", body = [RotateWarehouseCredentialResponse]),
+        )
+    )]
+    async fn rotate_warehouse_credential<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<RotateWarehouseCredentialRequest>,
+    ) -> Result<RotateWarehouseCredentialResponse> {
+        ApiServer::<C, A, S>::rotate_credential(warehouse_id.into(), request, api_context, metadata)
+            .await
+    }
+
+    /// Set the allowlist of a warehouse
+    ///
+    /// Only principals or members of groups on the allowlist may access
+    /// the warehouse via the catalog API. Requests are rejected before
+    /// the authorization backend is consulted.
+    /// Omit the allowlist to remove the restriction.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/allowlist",
+        request_body = SetWarehouseAllowlistRequest,
+        responses(
+            (status = 200, description = "Allowlist updated successfully")
+        )
+    )]
+    async fn set_warehouse_allowlist<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<SetWarehouseAllowlistRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::set_allowlist(warehouse_id.into(), request, api_context, metadata)
+            .await
+    }
+
+    /// Switch a warehouse to read-only mode and back
+    ///
+    /// Read-only warehouses serve all read operations, but reject creates,
+    /// commits, drops and renames with 403. Useful during migrations or
+    /// incident freezes.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/read-only",
+        request_body = SetWarehouseReadOnlyRequest,
+        responses(
+            (status = 200, description = "Read-only mode updated successfully")
+        )
+    )]
+    async fn set_warehouse_read_only<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<SetWarehouseReadOnlyRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::set_read_only(warehouse_id.into(), request, api_context, metadata)
+            .await
+    }
+
+    /// Set the signer URI of a warehouse
+    ///
+    /// Overrides the `s3.signer.uri` returned to clients, which is otherwise derived from
+    /// the base URI of the catalog. Useful if the signer is reachable via a separate ingress.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/signer-uri",
+        request_body = SetWarehouseSignerUriRequest,
+        responses(
+            (status = 200, description = "Signer URI updated successfully")
+        )
+    )]
+    async fn set_warehouse_signer_uri<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<SetWarehouseSignerUriRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::set_signer_uri(warehouse_id.into(), request, api_context, metadata)
+            .await
+    }
+
+    /// Set the response of a warehouse to unauthorized access
+    ///
+    /// Decides whether requests for namespaces, tables and views the principal is not
+    /// allowed to access return a `403` or a `404`, which hides whether the object exists.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/unauthorized-response",
+        request_body = SetWarehouseUnauthorizedResponseRequest,
+        responses(
+            (status = 200, description = "Unauthorized response updated successfully")
+        )
+    )]
+    async fn set_warehouse_unauthorized_response<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<SetWarehouseUnauthorizedResponseRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::set_unauthorized_response(
+            warehouse_id.into(),
+            request,
+            api_context,
+            metadata,
+        )
+        .await
+    }
+
+    /// Allow table locations outside of the storage profile of a warehouse
+    ///
+    /// By default, the locations of tables and their `write.data.path` and
+    /// `write.metadata.path` properties must be below the bucket and key prefix of the
+    /// storage profile, so that vended credentials and signatures stay within the storage
+    /// of the warehouse. Warehouses with migrated tables may opt out.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/external-locations",
+        request_body = SetWarehouseAllowExternalLocationsRequest,
+        responses(
+            (status = 200, description = "External locations updated successfully")
+        )
+    )]
+    async fn set_warehouse_allow_external_locations<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<SetWarehouseAllowExternalLocationsRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::set_allow_external_locations(
+            warehouse_id.into(),
+            request,
+            api_context,
+            metadata,
+        )
+        .await
+    }
+
+    /// Locate new tables of a warehouse below a hash prefix
+    ///
+    /// Object stores such as S3 partition a bucket by key prefix. If all tables share
+    /// the prefix of the warehouse, busy tables can exceed the request rate of a
+    /// partition. With the object storage layout, new tables are located below a short
+    /// hash, e.g. `s3://bucket/1a2b3c4d/<namespace-uuid>/<table-uuid>`. Existing tables
+    /// are not moved.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/object-storage-layout",
+        request_body = SetWarehouseObjectStorageLayoutRequest,
+        responses(
+            (status = 200, description = "Object storage layout updated successfully")
+        )
+    )]
+    async fn set_warehouse_object_storage_layout<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<SetWarehouseObjectStorageLayoutRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::set_object_storage_layout(
+            warehouse_id.into(),
+            request,
+            api_context,
+            metadata,
+        )
+        .await
+    }
+
+    /// Set the default access delegation of a warehouse
+    ///
+    /// Decides whether clients that do not send the `X-Iceberg-Access-Delegation` header
+    /// receive remote signing config, vended credentials or neither.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/access-delegation",
+        request_body = SetWarehouseAccessDelegationRequest,
+        responses(
+            (status = 200, description = "Access delegation updated successfully")
+        )
+    )]
+    async fn set_warehouse_access_delegation<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<SetWarehouseAccessDelegationRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::set_access_delegation(
+            warehouse_id.into(),
+            request,
+            api_context,
+            metadata,
+        )
+        .await
+    }
+
+    /// Set engine specific table config of a warehouse
+    ///
+    /// The config of the engine detected from the `User-Agent` and `X-Client-Version`
+    /// headers is merged into the config returned by `loadTable` and `createTable`.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/engine-table-config",
+        request_body = SetEngineTableConfigRequest,
+        responses(
+            (status = 200, description = "Engine table config updated successfully")
+        )
+    )]
+    async fn set_engine_table_config<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<SetEngineTableConfigRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::set_engine_table_config(
+            warehouse_id.into(),
+            request,
+            api_context,
+            metadata,
+        )
+        .await
+    }
+
+    /// Set the events of a warehouse that are published
+    ///
+    /// Disable publishing entirely or for specific event types, e.g. `table.committed`,
+    /// so that high-traffic warehouses don't flood the event sinks.
+    /// Changes take effect on other instances within `RESOLUTION_CACHE_TTL_SECONDS`.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/event-filter",
+        request_body = SetEventFilterRequest,
+        responses(
+            (status = 200, description = "Event filter updated successfully")
+        )
+    )]
+    async fn set_event_filter<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<SetEventFilterRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::set_event_filter(warehouse_id.into(), request, api_context, metadata)
+            .await
+    }
+
+    /// Enable or disable pyiceberg compatibility mode of a warehouse
+    ///
+    /// Tolerates known deviations of older pyiceberg versions: namespaces
+    /// prefixed with the warehouse name, a missing first namespace segment
+    /// if it matches the warehouse name, and the fsspec `FileIO` ignoring `s3.signer.uri`.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/pyiceberg-compat",
+        request_body = SetWarehousePyicebergCompatRequest,
+        responses(
+            (status = 200, description = "Compatibility mode updated successfully")
+        )
+    )]
+    async fn set_warehouse_pyiceberg_compat<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<SetWarehousePyicebergCompatRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::set_pyiceberg_compat(
+            warehouse_id.into(),
+            request,
+            api_context,
+            metadata,
+        )
+        .await
+    }
+
+    /// Attach a governance policy to a table
+    ///
+    /// The policy is not enforced by the catalog. It is returned in the
+    /// `loadTable` config under the `policy.` prefix, so that engines and
+    /// scanners can enforce it.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/table-policy",
+        request_body = SetTablePolicyRequest,
+        responses(
+            (status = 200, description = "Table policy updated successfully")
+        )
+    )]
+    async fn set_table_policy<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<SetTablePolicyRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::set_table_policy(warehouse_id.into(), request, api_context, metadata)
+            .await
+    }
+
+    /// Set the default snapshot retention policy of a warehouse
+    ///
+    /// The policy applies to all tables of the warehouse, unless one of their
+    /// namespaces has a policy. It is applied on every commit and periodically
+    /// by the snapshot expiration job.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/retention-policy",
+        request_body = SetWarehouseRetentionPolicyRequest,
+        responses(
+            (status = 200, description = "Retention policy updated successfully")
+        )
+    )]
+    async fn set_warehouse_retention_policy<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<SetWarehouseRetentionPolicyRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::set_warehouse_retention_policy(
+            warehouse_id.into(),
+            request,
+            api_context,
+            metadata,
+        )
+        .await
+    }
+
+    /// Set the snapshot retention policy of a namespace
+    ///
+    /// The policy applies to all tables of the namespace and its child namespaces,
+    /// unless a child namespace has a policy itself. Tables override individual settings
+    /// with the properties `history.expire.max-snapshot-age-ms`, `history.expire.max-snapshots`,
+    /// `history.expire.min-snapshots-to-keep` and `history.expire.max-ref-age-ms`.
+    /// Expired snapshots are removed on every commit and periodically by the snapshot expiration job.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/namespace-retention-policy",
+        request_body = SetNamespaceRetentionPolicyRequest,
+        responses(
+            (status = 200, description = "Retention policy updated successfully")
+        )
+    )]
+    async fn set_namespace_retention_policy<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<SetNamespaceRetentionPolicyRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::set_namespace_retention_policy(
+            warehouse_id.into(),
+            request,
+            api_context,
+            metadata,
+        )
+        .await
+    }
+
+    /// Transfer ownership of a warehouse, namespace or table
+    ///
+    /// The new owner is stored as-is. Owners are typically the subject
+    /// of the token used to authenticate against the catalog.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/ownership",
+        request_body = TransferOwnershipRequest,
+        responses(
+            (status = 200, description = "Ownership transferred successfully", body = [TransferOwnershipResponse])
+        )
+    )]
+    async fn transfer_ownership<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<TransferOwnershipRequest>,
+    ) -> Result<TransferOwnershipResponse> {
+        ApiServer::<C, A, S>::transfer_ownership(
+            warehouse_id.into(),
+            request,
+            api_context,
+            metadata,
+        )
+        .await
+    }
+
+    /// Share a namespace or table read-only with another project
+    ///
+    /// The shared object is mounted as namespace `mount-name` in all warehouses
+    /// of the consumer project. Tables below the mount point can be listed and
+    /// loaded, data access is restricted to reads. Mounts take precedence over
+    /// namespaces of the same name in the consumer's warehouses.
+    /// Remote signing for shared tables requires signer tokens to be enabled.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/share",
+        request_body = CreateShareRequest,
+        responses(
+            (status = 201, description = "Share created successfully", body = [CreateShareResponse])
+        )
+    )]
+    async fn create_share<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<CreateShareRequest>,
+    ) -> Result<CreateShareResponse> {
+        ApiServer::<C, A, S>::create_share(warehouse_id.into(), request, api_context, metadata)
+            .await
+    }
+
+    /// List all shares of objects in a warehouse
+    #[utoipa::path(
+        get,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/share",
+        responses(
+            (status = 200, description = "List of shares", body = [ListSharesResponse])
+        )
+    )]
+    async fn list_shares<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<ListSharesResponse> {
+        ApiServer::<C, A, S>::list_shares(warehouse_id.into(), api_context, metadata).await
+    }
+
+    /// Delete a share
+    ///
+    /// The consumer project loses access immediately.
+    /// Signer tokens issued before remain valid until they expire.
+    #[utoipa::path(
+        delete,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/share/{share_id}",
+        responses(
+            (status = 200, description = "Share deleted successfully")
+        )
+    )]
+    async fn delete_share<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path((warehouse_id, share_id)): Path<(uuid::Uuid, uuid::Uuid)>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::delete_share(warehouse_id.into(), share_id, api_context, metadata)
+            .await
+    }
+
+    /// Grant a role to a principal
+    ///
+    /// Roles apply to the object and everything within it, e.g. a role on a namespace
+    /// applies to its tables and child namespaces. Granting a role on an object the
+    /// principal already has a role on replaces that role.
+    /// Only enforced if the built-in role based access control is enabled.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/role-assignment",
+        request_body = GrantRoleRequest,
+        responses(
+            (status = 201, description = "Role granted successfully", body = [GrantRoleResponse])
+        )
+    )]
+    async fn grant_role<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<GrantRoleRequest>,
+    ) -> Result<GrantRoleResponse> {
+        ApiServer::<C, A, S>::grant_role(request, api_context, metadata).await
+    }
+
+    /// Revoke the role of a principal on an object
+    ///
+    /// Roles the principal has on containing objects are not affected.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/role-assignment/revoke",
+        request_body = RevokeRoleRequest,
+        responses(
+            (status = 200, description = "Role revoked successfully")
+        )
+    )]
+    async fn revoke_role<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<RevokeRoleRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::revoke_role(request, api_context, metadata).await
+    }
+
+    /// List the role assignments of a project
+    ///
+    /// Includes roles on the project and on all warehouses, namespaces and tables within it.
+    #[utoipa::path(
+        get,
+        tag = "management",
+        path = "management/v1/project/{project_id}/role-assignment",
+        params(
+            ("project_id" = String, Path, description = "ID or name of the project"),
+            ListRoleAssignmentsQuery
+        ),
+        responses(
+            (status = 200, description = "Role assignments of the project", body = [ListRoleAssignmentsResponse])
+        )
+    )]
+    async fn list_role_assignments<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(project): Path<String>,
+        Query(query): Query<ListRoleAssignmentsQuery>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<ListRoleAssignmentsResponse> {
+        ApiServer::<C, A, S>::list_role_assignments(&project, query, api_context, metadata).await
+    }
+
+    /// Get the metadata log of a table
+    ///
+    /// Returns all metadata files the table has pointed to, oldest first.
+    /// Combines the `metadata-log` of the current metadata with the history recorded by the catalog.
+    #[utoipa::path(
+        get,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/table/{table_id}/metadata-log",
+        responses(
+            (status = 200, description = "Metadata log of the table", body = [GetTableMetadataLogResponse])
+        )
+    )]
+    async fn get_table_metadata_log<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path((warehouse_id, table_id)): Path<(uuid::Uuid, uuid::Uuid)>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<GetTableMetadataLogResponse> {
+        ApiServer::<C, A, S>::get_table_metadata_log(
+            warehouse_id.into(),
+            table_id.into(),
+            api_context,
+            metadata,
+        )
+        .await
+    }
+
+    /// List the commits of a table
+    ///
+    /// Returns the commits recorded by the catalog, newest first, including the
+    /// principal that committed and the operation of the new snapshot.
+    /// Use `before` with the `committed-at` of the last commit to fetch older commits.
+    #[utoipa::path(
+        get,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/table/{table_id}/commits",
+        params(ListTableCommitsQuery),
+        responses(
+            (status = 200, description = "Commits of the table", body = [ListTableCommitsResponse])
+        )
+    )]
+    async fn list_table_commits<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path((warehouse_id, table_id)): Path<(uuid::Uuid, uuid::Uuid)>,
+        Query(query): Query<ListTableCommitsQuery>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<ListTableCommitsResponse> {
+        ApiServer::<C, A, S>::list_table_commits(
+            warehouse_id.into(),
+            table_id.into(),
+            query,
+            api_context,
+            metadata,
+        )
+        .await
+    }
+
+    /// Load the metadata of many tables at once
+    ///
+    /// Intended for services that need to warm caches over many tables.
+    /// At most 100 tables can be requested. Tables that do not exist
+    /// or are staged are listed in `not-found`.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/table/bulk-load",
+        request_body = BulkLoadTablesRequest,
+        responses(
+            (status = 200, description = "Metadata of the requested tables", body = [BulkLoadTablesResponse])
+        )
+    )]
+    async fn bulk_load_tables<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<BulkLoadTablesRequest>,
+    ) -> Result<BulkLoadTablesResponse> {
+        ApiServer::<C, A, S>::bulk_load_tables(warehouse_id.into(), request, api_context, metadata)
+            .await
+    }
+
+    /// Drop many tables at once
+    ///
+    /// Drops up to 100 tables in a single transaction. Tables that cannot be dropped
+    /// are skipped and reported with the reason in the response.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/table/bulk-drop",
+        request_body = BulkDropTablesRequest,
+        responses(
+            (status = 200, description = "Result per requested table", body = [BulkDropTablesResponse])
+        )
+    )]
+    async fn bulk_drop_tables<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<BulkDropTablesRequest>,
+    ) -> Result<BulkDropTablesResponse> {
+        ApiServer::<C, A, S>::bulk_drop_tables(warehouse_id.into(), request, api_context, metadata)
+            .await
+    }
+
+    /// List staged tables of a warehouse
+    ///
+    /// Staged tables were created with `stage-create` but never committed.
+    /// They are not visible through the Iceberg REST API, but reserve their name and location.
+    #[utoipa::path(
+        get,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/staged-table",
+        responses(
+            (status = 200, description = "Staged tables of the warehouse", body = [ListStagedTablesResponse])
+        )
+    )]
+    async fn list_staged_tables<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(warehouse_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<ListStagedTablesResponse> {
+        ApiServer::<C, A, S>::list_staged_tables(warehouse_id.into(), api_context, metadata).await
+    }
+
+    /// Discard a staged table
+    ///
+    /// Releases the name and location of the table. Tables that are not staged cannot be discarded.
+    #[utoipa::path(
+        delete,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/staged-table/{table_id}",
+        responses(
+            (status = 200, description = "Staged table discarded successfully")
+        )
+    )]
+    async fn discard_staged_table<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path((warehouse_id, table_id)): Path<(uuid::Uuid, uuid::Uuid)>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::discard_staged_table(
+            warehouse_id.into(),
+            table_id.into(),
+            api_context,
+            metadata,
+        )
+        .await
+    }
+
+    /// Roll back a table to a previous snapshot
+    ///
+    /// Points a branch or tag (default: `main`) to an existing snapshot of the table.
+    /// The rollback is performed as a regular commit and emits a `table.committed` event.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/warehouse/{warehouse_id}/table/{table_id}/rollback",
+        request_body = RollbackTableRequest,
+        responses(
+            (status = 200, description = "Table rolled back successfully", body = [RollbackTableResponse])
+        )
+    )]
+    async fn rollback_table<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path((warehouse_id, table_id)): Path<(uuid::Uuid, uuid::Uuid)>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<RollbackTableRequest>,
+    ) -> Result<RollbackTableResponse> {
+        ApiServer::<C, A, S>::rollback_table(
+            warehouse_id.into(),
+            table_id.into(),
+            request,
+            api_context,
+            metadata,
+        )
+        .await
+    }
+
+    /// Get the maintenance mode of this instance
+    #[utoipa::path(
+        get,
+        tag = "management",
+        path = "management/v1/maintenance",
+        responses(
+            (status = 200, description = "Maintenance mode", body = [MaintenanceModeResponse])
+        )
+    )]
+    async fn get_maintenance_mode<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<MaintenanceModeResponse> {
+        ApiServer::<C, A, S>::get_maintenance_mode(api_context, metadata).await
+    }
+
+    /// Enable or disable maintenance mode
+    ///
+    /// While maintenance mode is enabled, mutating requests are rejected with
+    /// 503 and a `Retry-After` header, while reads continue to be served.
+    /// The mode only applies to the instance that receives this request.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/maintenance",
+        request_body = SetMaintenanceModeRequest,
+        responses(
+            (status = 200, description = "Maintenance mode updated successfully", body = [MaintenanceModeResponse])
+        )
+    )]
+    async fn set_maintenance_mode<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<SetMaintenanceModeRequest>,
+    ) -> Result<MaintenanceModeResponse> {
+        ApiServer::<C, A, S>::set_maintenance_mode(request, api_context, metadata).await
+    }
+
+    /// Reload the runtime-tunable configuration of this instance
+    ///
+    /// Loads the configuration file and environment again and applies the log level,
+    /// maintenance mode and allowed CORS origins if they changed since the last load.
+    /// Other options require a restart. Sending `SIGHUP` to the process has the same effect.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/config/reload",
+        responses(
+            (status = 200, description = "Configuration reloaded successfully", body = [RuntimeConfig])
+        )
+    )]
+    async fn reload_config<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<RuntimeConfig> {
+        ApiServer::<C, A, S>::reload_config(api_context, metadata).await
+    }
+
+    /// Get the log filter of this instance
+    #[utoipa::path(
+        get,
+        tag = "management",
+        path = "management/v1/server/log-level",
+        responses(
+            (status = 200, description = "Log filter", body = [LogLevelResponse])
+        )
+    )]
+    async fn get_log_level<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<LogLevelResponse> {
+        ApiServer::<C, A, S>::get_log_level(api_context, metadata).await
+    }
+
+    /// Change the log level of this instance
+    ///
+    /// Sets a global level and optional levels for specific targets, such as
+    /// `iceberg_catalog::implementations::postgres`. The filter applies until it is
+    /// changed again or a reload changes `ICEBERG_REST__LOG_LEVEL`.
+    /// It only applies to the instance that receives this request.
+    #[utoipa::path(
+        put,
+        tag = "management",
+        path = "management/v1/server/log-level",
+        request_body = SetLogLevelRequest,
+        responses(
+            (status = 200, description = "Log level changed successfully", body = [LogLevelResponse])
+        )
+    )]
+    async fn set_log_level<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<SetLogLevelRequest>,
+    ) -> Result<LogLevelResponse> {
+        ApiServer::<C, A, S>::set_log_level(request, api_context, metadata).await
+    }
+
+    /// Revoke a token or all tokens of a subject
+    ///
+    /// Revoked tokens are rejected by the catalog before their natural expiry.
+    /// Other instances of the catalog pick up the revocation
+    /// within the configured cache ttl.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/token/revoke",
+        request_body = RevokeTokenRequest,
+        responses(
+            (status = 200, description = "Token revoked successfully")
+        )
+    )]
+    async fn revoke_token<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<RevokeTokenRequest>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::revoke_token(request, api_context, metadata).await
+    }
+
+    /// Create a service key
+    ///
+    /// Services sign their requests with the returned secret instead of sending a token.
+    /// The secret is only returned once. Requires `ICEBERG_REST__HMAC_AUTH_ENABLED`.
+    #[utoipa::path(
+        post,
+        tag = "management",
+        path = "management/v1/service-key",
+        request_body = CreateServiceKeyRequest,
+        responses(
+            (status = 201, description = "Service key created successfully", body = [CreateServiceKeyResponse])
+        )
+    )]
+    async fn create_service_key<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+        Json(request): Json<CreateServiceKeyRequest>,
+    ) -> Result<CreateServiceKeyResponse> {
+        ApiServer::<C, A, S>::create_service_key(request, api_context, metadata).await
+    }
+
+    /// Delete a service key
+    ///
+    /// Requests signed with the key are rejected immediately.
+    #[utoipa::path(
+        delete,
+        tag = "management",
+        path = "management/v1/service-key/{key_id}",
+        responses(
+            (status = 200, description = "Service key deleted successfully")
+        )
+    )]
+    async fn delete_service_key<C: Catalog, A: AuthZHandler, S: SecretStore>(
+        Path(key_id): Path<uuid::Uuid>,
+        AxumState(api_context): AxumState<ApiContext<State<A, C, S>>>,
+        Extension(metadata): Extension<RequestMetadata>,
+    ) -> Result<()> {
+        ApiServer::<C, A, S>::delete_service_key(key_id, api_context, metadata).await
+    }
+
+    impl<C: Catalog, A: AuthZHandler, S: SecretStore> ApiServer<C, A, S> {
+        pub fn new_v1_router() -> Router<ApiContext<State<A, C, S>>> {
+            Router::new()
+                // Create a new warehouse
+                .route("/warehouse", post(create_warehouse))
+                // Dry-run of the storage checks of `create_warehouse`
+                .route("/warehouse/validate-storage", post(validate_storage))
+                // List all projects
+                .route("/project", get(list_projects))
+                .route(
+                    "/project/:project_id",
+                    get(get_project).delete(delete_project),
+                )
+                .route("/project/:project_id/rename", post(rename_project))
+                .route("/project/:project_id/metadata", post(set_project_metadata))
+                .route("/project/:project_id/usage", get(get_project_usage))
+                .route(
+                    "/project/:project_id/role-assignment",
+                    get(list_role_assignments),
+                )
+                .route("/project/:project_id/warehouse", get(get_warehouse_by_name))
+                .route(
+                    "/warehouse",
+                    // List all warehouses within a project
+                    get(list_warehouses),
+                )
+                .route(
+                    "/warehouse/:warehouse_id",
+                    get(get_warehouse).delete(delete_warehouse),
+                )
+                // Engines and versions connecting to the warehouse
+                .route(
+                    "/warehouse/:warehouse_id/client-usage",
+                    get(list_client_usage),
+                )
+                // Storage and secret store checks on demand
+                .route("/warehouse/:warehouse_id/health", get(get_warehouse_health))
+                // Estimated size of the tables in the warehouse
+                .route(
+                    "/warehouse/:warehouse_id/storage-usage",
+                    get(get_warehouse_storage_usage),
+                )
+                // Rename warehouse
+                .route("/warehouse/:warehouse_id/rename", post(rename_warehouse))
+                // Deactivate warehouse
+                .route(
+                    "/warehouse/:warehouse_id/deactivate",
+                    post(deactivate_warehouse),
+                )
+                .route(
+                    "/warehouse/:warehouse_id/activate",
+                    post(activate_warehouse),
+                )
+                // Update storage profile and credential.
+                // The old credential is not re-used. If credentials are not provided,
+                // we assume that this endpoint does not require a secret.
+                .route(
+                    "/warehouse/:warehouse_id/storage",
+                    post(update_storage_profile),
+                )
+                // Update only the storage credential - keep the storage profile as is
+                .route(
+                    "/warehouse/:warehouse_id/storage-credential",
+                    post(update_storage_credential),
+                )
+                // Replace the storage credential and keep the previous one for a grace period
+                .route(
+                    "/warehouse/:warehouse_id/rotate-credential",
+                    post(rotate_warehouse_credential),
+                )
+                // Restrict access to the warehouse
+                .route(
+                    "/warehouse/:warehouse_id/allowlist",
+                    post(set_warehouse_allowlist),
+                )
+                // Freeze or unfreeze the warehouse
+                .route(
+                    "/warehouse/:warehouse_id/read-only",
+                    post(set_warehouse_read_only),
+                )
+                // Serve heterogeneous engines
+                .route(
+                    "/warehouse/:warehouse_id/engine-table-config",
+                    post(set_engine_table_config),
+                )
+                .route(
+                    "/warehouse/:warehouse_id/event-filter",
+                    post(set_event_filter),
+                )
+                // Route sign requests via a separate address
+                .route(
+                    "/warehouse/:warehouse_id/signer-uri",
+                    post(set_warehouse_signer_uri),
+                )
+                // Hide objects the principal cannot access
+                .route(
+                    "/warehouse/:warehouse_id/unauthorized-response",
+                    post(set_warehouse_unauthorized_response),
+                )
+                // Delegation for clients that do not request a mode
+                .route(
+                    "/warehouse/:warehouse_id/access-delegation",
+                    post(set_warehouse_access_delegation),
+                )
+                // Opt-out of keeping table locations within the storage profile
+                .route(
+                    "/warehouse/:warehouse_id/external-locations",
+                    post(set_warehouse_allow_external_locations),
+                )
+                // Hash prefix for locations of new tables
+                .route(
+                    "/warehouse/:warehouse_id/object-storage-layout",
+                    post(set_warehouse_object_storage_layout),
+                )
+                // Serve older pyiceberg clients
+                .route(
+                    "/warehouse/:warehouse_id/pyiceberg-compat",
+                    post(set_warehouse_pyiceberg_compat),
+                )
+                // Attach governance policies to tables
+                .route(
+                    "/warehouse/:warehouse_id/table-policy",
+                    post(set_table_policy),
+                )
+                // Snapshot retention of the tables in a warehouse or namespace
+                .route(
+                    "/warehouse/:warehouse_id/retention-policy",
+                    post(set_warehouse_retention_policy),
+                )
+                .route(
+                    "/warehouse/:warehouse_id/namespace-retention-policy",
+                    post(set_namespace_retention_policy),
+                )
+                // Share namespaces or tables with other projects
+                .route(
+                    "/warehouse/:warehouse_id/share",
+                    get(list_shares).post(create_share),
+                )
+                .route(
+                    "/warehouse/:warehouse_id/share/:share_id",
+                    delete(delete_share),
+                )
+                // Transfer ownership of the warehouse or an object within it
+                .route(
+                    "/warehouse/:warehouse_id/ownership",
+                    post(transfer_ownership),
+                )
+                // History of metadata files of a table
+                .route(
+                    "/warehouse/:warehouse_id/table/:table_id/metadata-log",
+                    get(get_table_metadata_log),
+                )
+                // Commits to a table with principal and operation
+                .route(
+                    "/warehouse/:warehouse_id/table/:table_id/commits",
+                    get(list_table_commits),
+                )
+                .route(
+                    "/warehouse/:warehouse_id/table/bulk-load",
+                    post(bulk_load_tables),
+                )
+                .route(
+                    "/warehouse/:warehouse_id/table/bulk-drop",
+                    post(bulk_drop_tables),
+                )
+                // Staged tables are invisible through the Iceberg REST API
+                .route(
+                    "/warehouse/:warehouse_id/staged-table",
+                    get(list_staged_tables),
+                )
+                .route(
+                    "/warehouse/:warehouse_id/staged-table/:table_id",
+                    delete(discard_staged_table),
+                )
+                // Revert a bad write without an engine
+                .route(
+                    "/warehouse/:warehouse_id/table/:table_id/rollback",
+                    post(rollback_table),
+                )
+                // Reject mutating requests during maintenance
+                .route(
+                    "/maintenance",
+                    get(get_maintenance_mode).post(set_maintenance_mode),
                 )
+                // Apply configuration changes without a restart
+                .route("/config/reload", post(reload_config))
+                // Turn on debug logging during incidents
+                .route("/server/log-level", get(get_log_level).put(set_log_level))
+                // Revoke tokens before their natural expiry
+                .route("/token/revoke", post(revoke_token))
+                // Built-in role based access control
+                .route("/role-assignment", post(grant_role))
+                .route("/role-assignment/revoke", post(revoke_role))
+                // Authenticate services without an identity provider
+                .route("/service-key", post(create_service_key))
+                .route("/service-key/:key_id", delete(delete_service_key))
         }
     }
 }
@@ -0,0 +1,76 @@
+use crate::api::management::v1::ApiServer;
+use crate::api::{ApiContext, Result};
+use crate::request_metadata::RequestMetadata;
+use crate::service::maintenance::MaintenanceMode;
+use crate::service::{auth::AuthZHandler, secrets::SecretStore, Catalog, State};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetMaintenanceModeRequest {
+    /// If true, mutating requests are rejected with 503 while reads continue to be served.
+    pub enabled: bool,
+    /// Value of the `Retry-After` header returned for rejected requests.
+    /// If not specified, the current value is kept.
+    pub retry_after_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct MaintenanceModeResponse {
+    /// Whether maintenance mode is enabled on this instance.
+    pub enabled: bool,
+    /// Value of the `Retry-After` header returned for rejected requests.
+    pub retry_after_seconds: u64,
+}
+
+impl From<&MaintenanceMode> for MaintenanceModeResponse {
+    fn from(value: &MaintenanceMode) -> Self {
+        Self {
+            enabled: value.is_enabled(),
+            retry_after_seconds: value.retry_after_seconds(),
+        }
+    }
+}
+
+impl axum::response::IntoResponse for MaintenanceModeResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl<C: Catalog, A: AuthZHandler, S: SecretStore> Service<C, A, S> for ApiServer<C, A, S> {}
+
+#[async_trait::async_trait]
+pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
+    async fn get_maintenance_mode(
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<MaintenanceModeResponse> {
+        // ------------------- AuthZ -------------------
+        A::check_set_maintenance_mode(&request_metadata, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        Ok(MaintenanceModeResponse::from(&context.v1_state.maintenance))
+    }
+
+    async fn set_maintenance_mode(
+        request: SetMaintenanceModeRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<MaintenanceModeResponse> {
+        // ------------------- AuthZ -------------------
+        A::check_set_maintenance_mode(&request_metadata, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let SetMaintenanceModeRequest {
+            enabled,
+            retry_after_seconds,
+        } = request;
+        let maintenance = context.v1_state.maintenance;
+        maintenance.set(enabled, retry_after_seconds);
+        tracing::info!("Maintenance mode enabled: {enabled}");
+
+        Ok(MaintenanceModeResponse::from(&maintenance))
+    }
+}
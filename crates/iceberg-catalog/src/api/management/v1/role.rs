@@ -0,0 +1,281 @@
+use crate::api::management::v1::warehouse::resolve_project;
+use crate::api::management::v1::ApiServer;
+use crate::api::{ApiContext, Result};
+use crate::request_metadata::RequestMetadata;
+use crate::service::{
+    auth::AuthZHandler, secrets::SecretStore, Catalog, NamespaceIdent, Role, RoleAssignment,
+    RoleObject, State, TableIdent,
+};
+use iceberg_ext::catalog::rest::ErrorModel;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum RoleAssignmentObject {
+    /// A project including all its warehouses.
+    Project {
+        #[serde(rename = "project-id")]
+        project_id: uuid::Uuid,
+    },
+    /// A warehouse including all its namespaces and tables.
+    Warehouse {
+        #[serde(rename = "warehouse-id")]
+        warehouse_id: uuid::Uuid,
+    },
+    /// A namespace including all its tables and child namespaces.
+    Namespace {
+        #[serde(rename = "warehouse-id")]
+        warehouse_id: uuid::Uuid,
+        namespace: Vec<String>,
+    },
+    /// A single table.
+    Table {
+        #[serde(rename = "warehouse-id")]
+        warehouse_id: uuid::Uuid,
+        /// Namespace the table is in.
+        namespace: Vec<String>,
+        /// Name of the table.
+        name: String,
+    },
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct GrantRoleRequest {
+    /// Principal the role is granted to, i.e. the `sub` claim of its tokens.
+    pub principal: String,
+    pub role: Role,
+    /// Object the role is granted on.
+    pub object: RoleAssignmentObject,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct GrantRoleResponse {
+    /// ID of the role assignment.
+    pub assignment_id: uuid::Uuid,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct RevokeRoleRequest {
+    /// Principal the role was granted to.
+    pub principal: String,
+    /// Object the role was granted on.
+    pub object: RoleAssignmentObject,
+}
+
+#[derive(Debug, serde::Deserialize, ToSchema, utoipa::IntoParams)]
+#[serde(rename_all = "kebab-case")]
+pub struct ListRoleAssignmentsQuery {
+    /// Only list the roles of this principal.
+    #[serde(default)]
+    pub principal: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct RoleAssignmentResponse {
+    pub assignment_id: uuid::Uuid,
+    pub principal: String,
+    pub role: Role,
+    pub object: RoleAssignmentObject,
+    /// Principal that granted the role.
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ListRoleAssignmentsResponse {
+    /// Roles granted on the project and all objects within it.
+    pub role_assignments: Vec<RoleAssignmentResponse>,
+}
+
+impl TryFrom<RoleAssignmentObject> for RoleObject {
+    type Error = ErrorModel;
+
+    fn try_from(value: RoleAssignmentObject) -> std::result::Result<Self, Self::Error> {
+        let parse_namespace = |namespace: Vec<String>| {
+            NamespaceIdent::from_vec(namespace).map_err(|e| {
+                ErrorModel::builder()
+                    .code(http::StatusCode::BAD_REQUEST.into())
+                    .message("Invalid namespace".to_string())
+                    .r#type("InvalidNamespace".to_string())
+                    .stack(Some(vec![e.to_string()]))
+                    .build()
+            })
+        };
+
+        Ok(match value {
+            RoleAssignmentObject::Project { project_id } => RoleObject::Project(project_id.into()),
+            RoleAssignmentObject::Warehouse { warehouse_id } => {
+                RoleObject::Warehouse(warehouse_id.into())
+            }
+            RoleAssignmentObject::Namespace {
+                warehouse_id,
+                namespace,
+            } => RoleObject::Namespace(warehouse_id.into(), parse_namespace(namespace)?),
+            RoleAssignmentObject::Table {
+                warehouse_id,
+                namespace,
+                name,
+            } => RoleObject::Table(
+                warehouse_id.into(),
+                TableIdent::new(parse_namespace(namespace)?, name),
+            ),
+        })
+    }
+}
+
+impl From<RoleObject> for RoleAssignmentObject {
+    fn from(value: RoleObject) -> Self {
+        match value {
+            RoleObject::Project(project_id) => RoleAssignmentObject::Project {
+                project_id: project_id.into_uuid(),
+            },
+            RoleObject::Warehouse(warehouse_id) => RoleAssignmentObject::Warehouse {
+                warehouse_id: warehouse_id.into_uuid(),
+            },
+            RoleObject::Namespace(warehouse_id, namespace) => RoleAssignmentObject::Namespace {
+                warehouse_id: warehouse_id.into_uuid(),
+                namespace: namespace.as_ref().clone(),
+            },
+            RoleObject::Table(warehouse_id, TableIdent { namespace, name }) => {
+                RoleAssignmentObject::Table {
+                    warehouse_id: warehouse_id.into_uuid(),
+                    namespace: namespace.as_ref().clone(),
+                    name,
+                }
+            }
+        }
+    }
+}
+
+impl From<RoleAssignment> for RoleAssignmentResponse {
+    fn from(value: RoleAssignment) -> Self {
+        Self {
+            assignment_id: value.assignment_id,
+            principal: value.principal,
+            role: value.role,
+            object: value.object.into(),
+            created_by: value.created_by,
+        }
+    }
+}
+
+impl axum::response::IntoResponse for GrantRoleResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        (http::StatusCode::CREATED, axum::Json(self)).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for ListRoleAssignmentsResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl<C: Catalog, A: AuthZHandler, S: SecretStore> Service<C, A, S> for ApiServer<C, A, S> {}
+
+#[async_trait::async_trait]
+pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
+    async fn grant_role(
+        request: GrantRoleRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<GrantRoleResponse> {
+        let GrantRoleRequest {
+            principal,
+            role,
+            object,
+        } = request;
+        let object = RoleObject::try_from(object)?;
+
+        // ------------------- AuthZ -------------------
+        A::check_manage_roles(&request_metadata, &object, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let assignment_id = context
+            .v1_state
+            .stores
+            .roles
+            .grant_role(&object, &principal, role, request_metadata.principal())
+            .await?;
+
+        Ok(GrantRoleResponse { assignment_id })
+    }
+
+    async fn revoke_role(
+        request: RevokeRoleRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        let RevokeRoleRequest { principal, object } = request;
+        let object = RoleObject::try_from(object)?;
+
+        // ------------------- AuthZ -------------------
+        A::check_manage_roles(&request_metadata, &object, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        context
+            .v1_state
+            .stores
+            .roles
+            .revoke_role(&object, &principal)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn list_role_assignments(
+        project: &str,
+        query: ListRoleAssignmentsQuery,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<ListRoleAssignmentsResponse> {
+        let project_id = resolve_project::<C>(project, context.v1_state.catalog.clone()).await?;
+
+        // ------------------- AuthZ -------------------
+        A::check_manage_roles(
+            &request_metadata,
+            &RoleObject::Project(project_id.clone()),
+            context.v1_state.auth,
+        )
+        .await?;
+
+        // ------------------- Business Logic -------------------
+        let role_assignments = context
+            .v1_state
+            .stores
+            .roles
+            .list_role_assignments(Some(&project_id), query.principal.as_deref())
+            .await?;
+
+        Ok(ListRoleAssignmentsResponse {
+            role_assignments: role_assignments.into_iter().map(Into::into).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_de_grant_role_request() {
+        let request = serde_json::json!({
+            "principal": "alice",
+            "role": "writer",
+            "object": {
+                "type": "namespace",
+                "warehouse-id": "00000000-0000-0000-0000-000000000001",
+                "namespace": ["sales", "gold"]
+            }
+        });
+
+        let request: GrantRoleRequest = serde_json::from_value(request).unwrap();
+        assert_eq!(request.role, Role::Writer);
+        let object = RoleObject::try_from(request.object.clone()).unwrap();
+        assert_eq!(RoleAssignmentObject::from(object), request.object);
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::api::management::v1::ApiServer;
+use crate::api::{ApiContext, ErrorModel, Result};
+use crate::request_metadata::RequestMetadata;
+use crate::service::runtime_config::RuntimeConfig;
+use crate::service::{auth::AuthZHandler, secrets::SecretStore, Catalog, State};
+use http::StatusCode;
+use tracing::level_filters::LevelFilter;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetLogLevelRequest {
+    /// Level of all targets without a specific level:
+    /// `off`, `error`, `warn`, `info`, `debug` or `trace`.
+    pub level: String,
+    /// Levels of specific targets, i.e. `{"iceberg_catalog::implementations::postgres": "debug"}`.
+    #[serde(default)]
+    pub targets: BTreeMap<String, String>,
+}
+
+impl SetLogLevelRequest {
+    /// Filter in `RUST_LOG` syntax.
+    fn to_filter(&self) -> Result<String> {
+        let mut directives = vec![parse_level(&self.level)?];
+        for (target, level) in &self.targets {
+            if target.is_empty()
+                || !target
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | ':' | '-'))
+            {
+                return Err(ErrorModel::builder()
+                    .code(StatusCode::BAD_REQUEST.into())
+                    .message(format!("Invalid log target '{target}'."))
+                    .r#type("InvalidLogTarget")
+                    .build()
+                    .into());
+            }
+            directives.push(format!("{target}={}", parse_level(level)?));
+        }
+        Ok(directives.join(","))
+    }
+}
+
+/// Lower case name of the level.
+fn parse_level(level: &str) -> Result<String> {
+    let level_filter = LevelFilter::from_str(level).map_err(|_| {
+        ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message(format!(
+                "Invalid log level '{level}'. Expected one of off, error, warn, info, debug, trace."
+            ))
+            .r#type("InvalidLogLevel")
+            .build()
+    })?;
+    Ok(level_filter.to_string().to_ascii_lowercase())
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct LogLevelResponse {
+    /// Log filter in `RUST_LOG` syntax. Not set if the filter of the
+    /// `RUST_LOG` environment variable applies.
+    pub filter: Option<String>,
+}
+
+impl axum::response::IntoResponse for LogLevelResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl<C: Catalog, A: AuthZHandler, S: SecretStore> Service<C, A, S> for ApiServer<C, A, S> {}
+
+#[async_trait::async_trait]
+pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
+    async fn reload_config(
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<RuntimeConfig> {
+        // ------------------- AuthZ -------------------
+        A::check_reload_config(&request_metadata, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let config = context.v1_state.runtime_config.reload().map_err(|e| {
+            ErrorModel::builder()
+                .code(StatusCode::BAD_REQUEST.into())
+                .message("Configuration is invalid, nothing was applied.")
+                .r#type("InvalidConfiguration")
+                .stack(Some(e.errors))
+                .build()
+        })?;
+        tracing::info!("Reloaded configuration: {config:?}");
+
+        Ok(config)
+    }
+
+    async fn get_log_level(
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<LogLevelResponse> {
+        // ------------------- AuthZ -------------------
+        A::check_set_log_level(&request_metadata, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        Ok(LogLevelResponse {
+            filter: context.v1_state.runtime_config.log_level(),
+        })
+    }
+
+    async fn set_log_level(
+        request: SetLogLevelRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<LogLevelResponse> {
+        // ------------------- VALIDATIONS -------------------
+        let filter = request.to_filter()?;
+
+        // ------------------- AuthZ -------------------
+        A::check_set_log_level(&request_metadata, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let runtime_config = context.v1_state.runtime_config;
+        runtime_config.set_log_level(&filter).map_err(|e| {
+            ErrorModel::builder()
+                .code(StatusCode::NOT_IMPLEMENTED.into())
+                .message("Failed to change the log level.")
+                .r#type("LogLevelNotChanged")
+                .stack(Some(e.errors))
+                .build()
+        })?;
+        tracing::info!("Log level changed to '{filter}'");
+
+        Ok(LogLevelResponse {
+            filter: runtime_config.log_level(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_log_filter() {
+        let request = SetLogLevelRequest {
+            level: "INFO".to_string(),
+            targets: BTreeMap::from([(
+                "iceberg_catalog::implementations::postgres".to_string(),
+                "debug".to_string(),
+            )]),
+        };
+        assert_eq!(
+            request.to_filter().unwrap(),
+            "info,iceberg_catalog::implementations::postgres=debug"
+        );
+
+        let request = SetLogLevelRequest {
+            level: "verbose".to_string(),
+            targets: BTreeMap::new(),
+        };
+        assert!(request.to_filter().is_err());
+
+        let request = SetLogLevelRequest {
+            level: "info".to_string(),
+            targets: BTreeMap::from([("a=trace,b".to_string(), "debug".to_string())]),
+        };
+        assert!(request.to_filter().is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use crate::api::management::v1::ApiServer;
+use crate::api::{ApiContext, ErrorModel, Result};
+use crate::request_metadata::RequestMetadata;
+use crate::service::hmac_auth::{create_service_key, ServiceKey};
+use crate::service::secrets::SecretIdent;
+use crate::service::{auth::AuthZHandler, secrets::SecretStore, Catalog, State};
+use axum::response::IntoResponse;
+use http::StatusCode;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct CreateServiceKeyRequest {
+    /// Principal the service is authenticated as,
+    /// i.e. the owner of objects it creates.
+    pub principal: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct CreateServiceKeyResponse {
+    /// Id of the key, sent as `x-iceberg-key-id` header.
+    pub key_id: uuid::Uuid,
+    /// Secret used to sign requests. It is only returned once.
+    pub secret: String,
+}
+
+impl IntoResponse for CreateServiceKeyResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        (StatusCode::CREATED, axum::Json(self)).into_response()
+    }
+}
+
+impl<C: Catalog, A: AuthZHandler, S: SecretStore> Service<C, A, S> for ApiServer<C, A, S> {}
+
+#[async_trait::async_trait]
+pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
+    async fn create_service_key(
+        request: CreateServiceKeyRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<CreateServiceKeyResponse> {
+        // ------------------- AuthZ -------------------
+        A::check_manage_service_keys(&request_metadata, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let (key_id, key) =
+            create_service_key::<S>(request.principal, context.v1_state.secrets).await?;
+
+        Ok(CreateServiceKeyResponse {
+            key_id: key_id.into_uuid(),
+            secret: key.hmac_secret,
+        })
+    }
+
+    async fn delete_service_key(
+        key_id: uuid::Uuid,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_manage_service_keys(&request_metadata, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        // Make sure that other secrets, such as storage credentials, can't be deleted.
+        let key_id = SecretIdent::from(key_id);
+        S::get_secret_by_id::<ServiceKey>(&key_id, context.v1_state.secrets.clone())
+            .await
+            .map_err(|_| {
+                ErrorModel::builder()
+                    .code(StatusCode::NOT_FOUND.into())
+                    .message("Service key not found".to_string())
+                    .r#type("ServiceKeyNotFound".to_string())
+                    .build()
+            })?;
+        S::delete_secret(&key_id, context.v1_state.secrets).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn test_de_create_service_key_request() {
+        let request = serde_json::json!({
+            "principal": "ingest-service",
+        });
+
+        let request: super::CreateServiceKeyRequest = serde_json::from_value(request).unwrap();
+        assert_eq!(request.principal, "ingest-service");
+    }
+}
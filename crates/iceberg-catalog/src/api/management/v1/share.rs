@@ -0,0 +1,226 @@
+use crate::api::management::v1::ApiServer;
+use crate::api::{ApiContext, Result};
+use crate::request_metadata::RequestMetadata;
+use crate::service::{
+    auth::AuthZHandler, secrets::SecretStore, Catalog, NamespaceIdent, Share, SharedObject, State,
+    TableIdent, Transaction,
+};
+use crate::{ProjectIdent, WarehouseIdent};
+use iceberg_ext::catalog::rest::ErrorModel;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ShareObject {
+    /// A namespace including all its tables and child namespaces.
+    Namespace {
+        /// Namespace to share.
+        namespace: Vec<String>,
+    },
+    /// A single table.
+    Table {
+        /// Namespace the table is in.
+        namespace: Vec<String>,
+        /// Name of the table.
+        name: String,
+    },
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct CreateShareRequest {
+    /// Namespace or table to share.
+    pub object: ShareObject,
+    /// Project that is granted read access.
+    pub consumer_project_id: uuid::Uuid,
+    /// Name of the namespace under which the object is mounted
+    /// in all warehouses of the consumer project.
+    /// Must be unique within the consumer project.
+    pub mount_name: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct CreateShareResponse {
+    /// ID of the created share.
+    pub share_id: uuid::Uuid,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ShareResponse {
+    /// ID of the share.
+    pub share_id: uuid::Uuid,
+    /// Shared namespace or table.
+    pub object: ShareObject,
+    /// Project that is granted read access.
+    pub consumer_project_id: uuid::Uuid,
+    /// Name of the namespace under which the object is mounted.
+    pub mount_name: String,
+    /// Principal that created the share.
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ListSharesResponse {
+    /// Shares of objects in the warehouse.
+    pub shares: Vec<ShareResponse>,
+}
+
+impl TryFrom<ShareObject> for SharedObject {
+    type Error = ErrorModel;
+
+    fn try_from(value: ShareObject) -> std::result::Result<Self, Self::Error> {
+        let parse_namespace = |namespace: Vec<String>| {
+            NamespaceIdent::from_vec(namespace).map_err(|e| {
+                ErrorModel::builder()
+                    .code(http::StatusCode::BAD_REQUEST.into())
+                    .message("Invalid namespace".to_string())
+                    .r#type("InvalidNamespace".to_string())
+                    .stack(Some(vec![e.to_string()]))
+                    .build()
+            })
+        };
+
+        Ok(match value {
+            ShareObject::Namespace { namespace } => {
+                SharedObject::Namespace(parse_namespace(namespace)?)
+            }
+            ShareObject::Table { namespace, name } => {
+                SharedObject::Table(TableIdent::new(parse_namespace(namespace)?, name))
+            }
+        })
+    }
+}
+
+impl From<SharedObject> for ShareObject {
+    fn from(value: SharedObject) -> Self {
+        match value {
+            SharedObject::Namespace(namespace) => ShareObject::Namespace {
+                namespace: namespace.as_ref().clone(),
+            },
+            SharedObject::Table(TableIdent { namespace, name }) => ShareObject::Table {
+                namespace: namespace.as_ref().clone(),
+                name,
+            },
+        }
+    }
+}
+
+impl From<Share> for ShareResponse {
+    fn from(value: Share) -> Self {
+        Self {
+            share_id: value.share_id,
+            object: value.object.into(),
+            consumer_project_id: value.consumer_project_id.into_uuid(),
+            mount_name: value.mount_name,
+            created_by: value.created_by,
+        }
+    }
+}
+
+impl axum::response::IntoResponse for CreateShareResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        (http::StatusCode::CREATED, axum::Json(self)).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for ListSharesResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl<C: Catalog, A: AuthZHandler, S: SecretStore> Service<C, A, S> for ApiServer<C, A, S> {}
+
+#[async_trait::async_trait]
+pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
+    async fn create_share(
+        warehouse_id: WarehouseIdent,
+        request: CreateShareRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<CreateShareResponse> {
+        let CreateShareRequest {
+            object,
+            consumer_project_id,
+            mount_name,
+        } = request;
+        let object = SharedObject::try_from(object)?;
+
+        // ------------------- AuthZ -------------------
+        A::check_manage_shares(&request_metadata, &warehouse_id, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        let share_id = C::create_share(
+            &warehouse_id,
+            &object,
+            &ProjectIdent::from(consumer_project_id),
+            &mount_name,
+            request_metadata.principal(),
+            transaction.transaction(),
+        )
+        .await?;
+        transaction.commit().await?;
+
+        Ok(CreateShareResponse { share_id })
+    }
+
+    async fn list_shares(
+        warehouse_id: WarehouseIdent,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<ListSharesResponse> {
+        // ------------------- AuthZ -------------------
+        A::check_manage_shares(&request_metadata, &warehouse_id, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let shares = C::list_shares(&warehouse_id, context.v1_state.catalog).await?;
+
+        Ok(ListSharesResponse {
+            shares: shares.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    async fn delete_share(
+        warehouse_id: WarehouseIdent,
+        share_id: uuid::Uuid,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_manage_shares(&request_metadata, &warehouse_id, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        C::delete_share(&warehouse_id, &share_id, transaction.transaction()).await?;
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_de_create_share_request() {
+        let request = serde_json::json!({
+            "object": {
+                "type": "table",
+                "namespace": ["sales", "gold"],
+                "name": "orders"
+            },
+            "consumer-project-id": "00000000-0000-0000-0000-000000000001",
+            "mount-name": "sales_orders"
+        });
+
+        let request: CreateShareRequest = serde_json::from_value(request).unwrap();
+        assert_eq!(request.mount_name, "sales_orders");
+        let object = SharedObject::try_from(request.object.clone()).unwrap();
+        assert_eq!(ShareObject::from(object), request.object);
+    }
+}
@@ -0,0 +1,773 @@
+use crate::api::iceberg::types::Prefix;
+use crate::api::iceberg::v1::tables::{Service as _, TableParameters};
+use crate::api::management::v1::ApiServer;
+use crate::api::{ApiContext, CommitTableRequest, Result};
+use crate::catalog::{require_writable_warehouse, resolve_ownership, CatalogServer};
+use crate::request_metadata::RequestMetadata;
+use crate::service::contract_verification::ContractVerificationOutcome;
+use crate::service::event_publisher::{EventMetadata, EventSubject, EventType};
+use crate::service::{
+    auth::AuthZHandler, secrets::SecretStore, Catalog, ErrorModel, ErrorType, LoadedTable,
+    MetadataLogEntry, NamespaceIdent, NamespaceIdentExt as _, OwnedObject, StagedTable, State,
+    TableCommit, TableIdent, TableIdentUuid, Transaction,
+};
+use crate::WarehouseIdent;
+use iceberg::spec::{SnapshotReference, SnapshotRetention, TableMetadata, MAIN_BRANCH};
+use iceberg::{TableRequirement, TableUpdate};
+use std::collections::HashSet;
+use utoipa::ToSchema;
+
+/// Maximum number of tables that can be loaded or dropped with a single bulk request.
+pub const MAX_BULK_TABLES: usize = 100;
+/// Maximum number of commits returned by a single request.
+pub const MAX_TABLE_COMMITS: i64 = 1000;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct MetadataLogEntryResponse {
+    /// Location of the metadata file.
+    pub metadata_location: String,
+    /// Milliseconds since epoch at which the file became the current metadata.
+    pub timestamp_ms: i64,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct GetTableMetadataLogResponse {
+    /// Metadata files of the table, oldest first.
+    /// The last entry is the current metadata file.
+    pub metadata_log: Vec<MetadataLogEntryResponse>,
+}
+
+#[derive(Debug, serde::Deserialize, ToSchema, utoipa::IntoParams)]
+#[serde(rename_all = "kebab-case")]
+pub struct ListTableCommitsQuery {
+    /// Maximum number of commits to return. Defaults to 100, at most 1000.
+    #[serde(default)]
+    pub limit: Option<i64>,
+    /// Only return commits before this time, exclusive.
+    /// Set to the `committed-at` of the last commit to fetch the next page.
+    #[serde(default)]
+    pub before: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct TableCommitResponse {
+    /// Time the catalog committed the change.
+    pub committed_at: chrono::DateTime<chrono::Utc>,
+    /// Principal that committed. Not set for unauthenticated requests.
+    pub committed_by: Option<String>,
+    /// Snapshot that became the current snapshot.
+    /// Not set if the commit did not change the current snapshot, i.e. schema changes.
+    pub snapshot_id: Option<i64>,
+    /// Operation of the snapshot summary, e.g. `append` or `overwrite`.
+    pub operation: Option<String>,
+    /// Location of the metadata file written by the commit.
+    pub metadata_location: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ListTableCommitsResponse {
+    /// Commits recorded by the catalog, newest first.
+    /// Commits made before the catalog recorded commits are not included.
+    pub commits: Vec<TableCommitResponse>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct RollbackTableRequest {
+    /// Snapshot to roll back to. Must be a snapshot of the table.
+    pub snapshot_id: i64,
+    /// Branch or tag to roll back. Defaults to `main`.
+    #[serde(default)]
+    pub ref_name: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct RollbackTableResponse {
+    /// Snapshot the reference pointed to before the rollback.
+    pub previous_snapshot_id: Option<i64>,
+    /// Snapshot the reference points to now.
+    pub snapshot_id: i64,
+    /// Location of the metadata file written by the rollback.
+    pub metadata_location: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct StagedTableResponse {
+    /// ID of the staged table.
+    pub table_id: uuid::Uuid,
+    /// Namespace of the staged table.
+    pub namespace: Vec<String>,
+    /// Name of the staged table.
+    pub name: String,
+    /// Location reserved for the staged table.
+    pub location: String,
+    /// Time the table was staged.
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Principal that staged the table.
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ListStagedTablesResponse {
+    /// Staged tables of the warehouse, oldest first.
+    pub tables: Vec<StagedTableResponse>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct BulkTableIdent {
+    /// Namespace the table is in.
+    pub namespace: Vec<String>,
+    /// Name of the table.
+    pub name: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct BulkLoadTablesRequest {
+    /// Tables to load. At most 100 tables can be loaded at once.
+    pub tables: Vec<BulkTableIdent>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct BulkLoadedTable {
+    /// ID of the table.
+    pub table_id: uuid::Uuid,
+    /// Namespace the table is in.
+    pub namespace: Vec<String>,
+    /// Name of the table.
+    pub name: String,
+    /// Location of the current metadata file.
+    pub metadata_location: String,
+    /// Current metadata of the table.
+    #[schema(value_type = Object)]
+    pub metadata: TableMetadata,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct BulkLoadTablesResponse {
+    /// Tables that were found.
+    pub tables: Vec<BulkLoadedTable>,
+    /// Requested tables that do not exist or are staged.
+    pub not_found: Vec<BulkTableIdent>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct BulkDropTablesRequest {
+    /// Tables to drop. At most 100 tables can be dropped at once.
+    pub tables: Vec<BulkTableIdent>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct BulkDropTableResult {
+    /// Namespace the table is in.
+    pub namespace: Vec<String>,
+    /// Name of the table.
+    pub name: String,
+    /// Whether the table was dropped.
+    pub dropped: bool,
+    /// Type of the error that prevented the drop, e.g. `NoSuchTableError`.
+    pub error_type: Option<String>,
+    /// Message of the error that prevented the drop.
+    pub error_message: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct BulkDropTablesResponse {
+    /// One result per requested table, in request order.
+    pub results: Vec<BulkDropTableResult>,
+}
+
+impl BulkDropTableResult {
+    fn new(table: &TableIdent, result: std::result::Result<(), ErrorModel>) -> Self {
+        let (error_type, error_message) = match result {
+            Ok(()) => (None, None),
+            Err(e) => (Some(e.r#type), Some(e.message)),
+        };
+        Self {
+            namespace: table.namespace.clone().inner(),
+            name: table.name.clone(),
+            dropped: error_type.is_none(),
+            error_type,
+            error_message,
+        }
+    }
+}
+
+impl axum::response::IntoResponse for BulkDropTablesResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+fn parse_bulk_tables(tables: Vec<BulkTableIdent>) -> Result<Vec<TableIdent>> {
+    if tables.len() > MAX_BULK_TABLES {
+        return Err(ErrorModel::builder()
+            .code(http::StatusCode::BAD_REQUEST.into())
+            .message(format!(
+                "At most {MAX_BULK_TABLES} tables can be processed at once."
+            ))
+            .r#type("TooManyTables".to_string())
+            .build()
+            .into());
+    }
+    Ok(tables
+        .into_iter()
+        .map(TableIdent::try_from)
+        .collect::<std::result::Result<Vec<_>, _>>()?)
+}
+
+impl TryFrom<BulkTableIdent> for TableIdent {
+    type Error = ErrorModel;
+
+    fn try_from(value: BulkTableIdent) -> std::result::Result<Self, Self::Error> {
+        let namespace = NamespaceIdent::from_vec(value.namespace).map_err(|e| {
+            ErrorModel::builder()
+                .code(http::StatusCode::BAD_REQUEST.into())
+                .message("Invalid namespace".to_string())
+                .r#type("InvalidNamespace".to_string())
+                .stack(Some(vec![e.to_string()]))
+                .build()
+        })?;
+        Ok(TableIdent::new(namespace, value.name))
+    }
+}
+
+impl From<TableIdent> for BulkTableIdent {
+    fn from(value: TableIdent) -> Self {
+        Self {
+            namespace: value.namespace.inner(),
+            name: value.name,
+        }
+    }
+}
+
+impl From<LoadedTable> for BulkLoadedTable {
+    fn from(value: LoadedTable) -> Self {
+        Self {
+            table_id: value.table_id.into_uuid(),
+            namespace: value.table.namespace.inner(),
+            name: value.table.name,
+            metadata_location: value.metadata_location,
+            metadata: value.table_metadata,
+        }
+    }
+}
+
+impl axum::response::IntoResponse for BulkLoadTablesResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl From<StagedTable> for StagedTableResponse {
+    fn from(value: StagedTable) -> Self {
+        Self {
+            table_id: value.table_id.into_uuid(),
+            namespace: value.table.namespace.as_ref().clone(),
+            name: value.table.name,
+            location: value.table_location,
+            created_at: value.created_at,
+            created_by: value.created_by,
+        }
+    }
+}
+
+impl axum::response::IntoResponse for ListStagedTablesResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl From<MetadataLogEntry> for MetadataLogEntryResponse {
+    fn from(value: MetadataLogEntry) -> Self {
+        Self {
+            metadata_location: value.metadata_location,
+            timestamp_ms: value.timestamp_ms,
+        }
+    }
+}
+
+impl axum::response::IntoResponse for GetTableMetadataLogResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl From<TableCommit> for TableCommitResponse {
+    fn from(value: TableCommit) -> Self {
+        Self {
+            committed_at: value.committed_at,
+            committed_by: value.committed_by,
+            snapshot_id: value.snapshot_id,
+            operation: value.operation,
+            metadata_location: value.metadata_location,
+        }
+    }
+}
+
+impl axum::response::IntoResponse for ListTableCommitsResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for RollbackTableResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl<C: Catalog, A: AuthZHandler, S: SecretStore> Service<C, A, S> for ApiServer<C, A, S> {}
+
+#[async_trait::async_trait]
+pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
+    async fn get_table_metadata_log(
+        warehouse_id: WarehouseIdent,
+        table_id: TableIdentUuid,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<GetTableMetadataLogResponse> {
+        // ------------------- AuthZ -------------------
+        A::check_load_table(
+            &request_metadata,
+            &warehouse_id,
+            None,
+            Some(&table_id),
+            context.v1_state.auth,
+        )
+        .await?;
+
+        // ------------------- Business Logic -------------------
+        let metadata_log =
+            C::get_table_metadata_log(&warehouse_id, &table_id, context.v1_state.catalog).await?;
+
+        Ok(GetTableMetadataLogResponse {
+            metadata_log: metadata_log.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// Commits to a table recorded by the catalog, newest first.
+    async fn list_table_commits(
+        warehouse_id: WarehouseIdent,
+        table_id: TableIdentUuid,
+        query: ListTableCommitsQuery,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<ListTableCommitsResponse> {
+        // ------------------- VALIDATIONS -------------------
+        let limit = query.limit.unwrap_or(100);
+        if !(1..=MAX_TABLE_COMMITS).contains(&limit) {
+            return Err(ErrorModel::builder()
+                .code(http::StatusCode::BAD_REQUEST.into())
+                .message(format!("Limit must be between 1 and {MAX_TABLE_COMMITS}."))
+                .r#type("InvalidLimit".to_string())
+                .build()
+                .into());
+        }
+
+        // ------------------- AuthZ -------------------
+        A::check_load_table(
+            &request_metadata,
+            &warehouse_id,
+            None,
+            Some(&table_id),
+            context.v1_state.auth,
+        )
+        .await?;
+
+        // ------------------- Business Logic -------------------
+        let commits = C::list_table_commits(
+            &warehouse_id,
+            &table_id,
+            query.before,
+            limit,
+            context.v1_state.catalog,
+        )
+        .await?;
+
+        Ok(ListTableCommitsResponse {
+            commits: commits.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// Load the metadata of many tables with a single query.
+    /// Fails if the caller may not load any of the requested tables.
+    async fn bulk_load_tables(
+        warehouse_id: WarehouseIdent,
+        request: BulkLoadTablesRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<BulkLoadTablesResponse> {
+        let tables = parse_bulk_tables(request.tables)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        // ------------------- AuthZ -------------------
+        let include_staged = false;
+        let table_ids = C::table_idents_to_ids(
+            &warehouse_id,
+            tables.iter().collect(),
+            include_staged,
+            context.v1_state.catalog.clone(),
+        )
+        .await?;
+        for (table, table_id) in &table_ids {
+            A::check_load_table(
+                &request_metadata,
+                &warehouse_id,
+                Some(&table.namespace),
+                table_id.as_ref(),
+                context.v1_state.auth.clone(),
+            )
+            .await?;
+        }
+
+        // ------------------- Business Logic -------------------
+        let loaded = C::load_tables(
+            &warehouse_id,
+            tables.iter().collect(),
+            context.v1_state.catalog,
+        )
+        .await?;
+        let found = loaded.iter().map(|t| &t.table).collect::<HashSet<_>>();
+        let not_found = tables
+            .iter()
+            .filter(|t| !found.contains(t))
+            .cloned()
+            .map(Into::into)
+            .collect();
+
+        Ok(BulkLoadTablesResponse {
+            tables: loaded.into_iter().map(Into::into).collect(),
+            not_found,
+        })
+    }
+
+    /// Drop many tables in a single transaction.
+    ///
+    /// Tables that can not be dropped, e.g. because they do not exist or the
+    /// caller is not allowed to drop them, are reported and skipped. All other
+    /// tables are dropped together. Dropped tables are kept until they are
+    /// reclaimed by the garbage collector.
+    async fn bulk_drop_tables(
+        warehouse_id: WarehouseIdent,
+        request: BulkDropTablesRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<BulkDropTablesResponse> {
+        let tables = parse_bulk_tables(request.tables)?;
+
+        // ------------------- AuthZ -------------------
+        let include_staged = true;
+        let table_ids = C::table_idents_to_ids(
+            &warehouse_id,
+            tables.iter().collect(),
+            include_staged,
+            context.v1_state.catalog.clone(),
+        )
+        .await?;
+        let mut checked = Vec::with_capacity(tables.len());
+        for table in tables {
+            let table_id = table_ids.get(&table).cloned().flatten();
+            let ownership = resolve_ownership::<C>(
+                &warehouse_id,
+                OwnedObject::Table(table.clone()),
+                context.v1_state.catalog.clone(),
+            )
+            .await;
+            let check = A::check_drop_table(
+                &request_metadata,
+                &warehouse_id,
+                table_id.as_ref(),
+                ownership.as_ref(),
+                context.v1_state.auth.clone(),
+            )
+            .await
+            .map_err(|e| e.error);
+            checked.push((table, table_id, check));
+        }
+
+        // ------------------- Business Logic -------------------
+        require_writable_warehouse(&warehouse_id, &context.v1_state).await?;
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog.clone()).await?;
+        let mut results = Vec::with_capacity(checked.len());
+        let mut dropped = vec![];
+        for (table, table_id, check) in checked {
+            let result = match (check, table_id) {
+                (Err(e), _) => Err(e),
+                (Ok(()), None) => Err(ErrorModel::builder()
+                    .code(http::StatusCode::NOT_FOUND.into())
+                    .message("Table not found".to_string())
+                    .r#type("NoSuchTableError".to_string())
+                    .error_type(ErrorType::NoSuchTable)
+                    .build()),
+                (Ok(()), Some(table_id)) => {
+                    let verified = context
+                        .v1_state
+                        .contract_verifiers
+                        .check_drop(table_id.clone())
+                        .await
+                        .and_then(ContractVerificationOutcome::into_result);
+                    match verified {
+                        Ok(()) => {
+                            // Tables that vanished concurrently are reported, other errors
+                            // abort the transaction and thus the whole request.
+                            match C::drop_table(&warehouse_id, &table_id, transaction.transaction())
+                                .await
+                            {
+                                Ok(()) => {
+                                    dropped.push((table.clone(), table_id));
+                                    Ok(())
+                                }
+                                Err(e) if e.error.code == http::StatusCode::NOT_FOUND => {
+                                    Err(e.error)
+                                }
+                                Err(e) => return Err(e),
+                            }
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+            };
+            results.push(BulkDropTableResult::new(&table, result));
+        }
+        transaction.commit().await?;
+
+        let num_events = dropped.len();
+        for (sequence_number, (table, table_id)) in dropped.into_iter().enumerate() {
+            let _ = context
+                .v1_state
+                .publisher
+                .publish(
+                    uuid::Uuid::now_v7(),
+                    EventType::TableDropped,
+                    serde_json::Value::Null,
+                    EventMetadata {
+                        warehouse_id: *warehouse_id.as_uuid(),
+                        subject: EventSubject::Table {
+                            table_id: *table_id.as_uuid(),
+                            namespace: table.namespace.encode_in_url(),
+                            name: table.name,
+                        },
+                        prefix: warehouse_id.to_string(),
+                        num_events,
+                        sequence_number,
+                        trace_id: request_metadata.request_id,
+                        tenant_id: request_metadata.tenant_id().map(ToString::to_string),
+                    },
+                )
+                .await;
+        }
+
+        Ok(BulkDropTablesResponse { results })
+    }
+
+    async fn list_staged_tables(
+        warehouse_id: WarehouseIdent,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<ListStagedTablesResponse> {
+        // ------------------- AuthZ -------------------
+        A::check_manage_staged_tables(&request_metadata, &warehouse_id, context.v1_state.auth)
+            .await?;
+
+        // ------------------- Business Logic -------------------
+        let tables = C::list_staged_tables(&warehouse_id, context.v1_state.catalog).await?;
+
+        Ok(ListStagedTablesResponse {
+            tables: tables.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    async fn discard_staged_table(
+        warehouse_id: WarehouseIdent,
+        table_id: TableIdentUuid,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_manage_staged_tables(&request_metadata, &warehouse_id, context.v1_state.auth)
+            .await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        C::discard_staged_table(&warehouse_id, &table_id, transaction.transaction()).await?;
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    /// Point a branch or tag of a table to a previous snapshot.
+    /// The rollback is performed as a regular commit, so that
+    /// contract verification, read-only checks and events apply.
+    async fn rollback_table(
+        warehouse_id: WarehouseIdent,
+        table_id: TableIdentUuid,
+        request: RollbackTableRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<RollbackTableResponse> {
+        let RollbackTableRequest {
+            snapshot_id,
+            ref_name,
+        } = request;
+        let ref_name = ref_name.unwrap_or_else(|| MAIN_BRANCH.to_string());
+
+        // ------------------- AuthZ -------------------
+        let table = C::get_table_metadata_by_id(
+            &warehouse_id,
+            &table_id,
+            false,
+            context.v1_state.catalog.clone(),
+        )
+        .await
+        .map(|metadata| metadata.table);
+        let ownership = match &table {
+            Ok(table) => {
+                resolve_ownership::<C>(
+                    &warehouse_id,
+                    OwnedObject::Table(table.clone()),
+                    context.v1_state.catalog.clone(),
+                )
+                .await
+            }
+            // We can't fail before AuthZ.
+            Err(_) => None,
+        };
+        A::check_commit_table(
+            &request_metadata,
+            &warehouse_id,
+            Some(&table_id),
+            None,
+            ownership.as_ref(),
+            context.v1_state.auth.clone(),
+        )
+        .await?;
+
+        // ------------------- Business Logic -------------------
+        let table = table?;
+        let metadata = C::load_table(&warehouse_id, &table, context.v1_state.catalog.clone())
+            .await?
+            .table_metadata;
+
+        let previous_ref = metadata.refs.get(&ref_name);
+        let previous_snapshot_id = previous_ref.map(|r| r.snapshot_id);
+        let retention = previous_ref.map_or(
+            SnapshotRetention::Branch {
+                min_snapshots_to_keep: None,
+                max_snapshot_age_ms: None,
+                max_ref_age_ms: None,
+            },
+            |r| r.retention.clone(),
+        );
+
+        let commit_request = CommitTableRequest {
+            identifier: Some(table.clone()),
+            requirements: vec![TableRequirement::RefSnapshotIdMatch {
+                r#ref: ref_name.clone(),
+                snapshot_id: previous_snapshot_id,
+            }],
+            updates: vec![TableUpdate::SetSnapshotRef {
+                ref_name: ref_name.clone(),
+                reference: SnapshotReference {
+                    snapshot_id,
+                    retention,
+                },
+            }],
+        };
+        let principal = request_metadata.principal().map(ToString::to_string);
+        let response = CatalogServer::<C, A, S>::commit_table(
+            TableParameters {
+                prefix: Some(Prefix::from(warehouse_id.to_string())),
+                table: table.clone(),
+            },
+            commit_request,
+            context,
+            request_metadata,
+        )
+        .await?;
+
+        tracing::info!(
+            "Rolled back '{ref_name}' of table {table:?} ({table_id}) in warehouse {warehouse_id} from snapshot {previous_snapshot_id:?} to {snapshot_id}. Principal: {principal:?}"
+        );
+
+        Ok(RollbackTableResponse {
+            previous_snapshot_id,
+            snapshot_id,
+            metadata_location: response.metadata_location,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bulk_load_table_ident() {
+        let request: BulkLoadTablesRequest = serde_json::from_value(serde_json::json!({
+            "tables": [{"namespace": ["sales", "eu"], "name": "orders"}]
+        }))
+        .unwrap();
+        let table = TableIdent::try_from(request.tables[0].clone()).unwrap();
+        assert_eq!(
+            table,
+            TableIdent::from_strs(["sales", "eu", "orders"]).unwrap()
+        );
+
+        let invalid = BulkTableIdent {
+            namespace: vec![],
+            name: "orders".to_string(),
+        };
+        assert!(TableIdent::try_from(invalid).is_err());
+    }
+
+    #[test]
+    fn test_bulk_drop_table_result() {
+        let table = TableIdent::from_strs(["sales", "orders"]).unwrap();
+        let result = BulkDropTableResult::new(&table, Ok(()));
+        assert!(result.dropped);
+        assert_eq!(result.error_type, None);
+
+        let result = BulkDropTableResult::new(
+            &table,
+            Err(ErrorModel::builder()
+                .code(http::StatusCode::NOT_FOUND.into())
+                .message("Table not found".to_string())
+                .r#type("NoSuchTableError".to_string())
+                .build()),
+        );
+        assert!(!result.dropped);
+        assert_eq!(result.namespace, vec!["sales".to_string()]);
+        assert_eq!(result.error_type.as_deref(), Some("NoSuchTableError"));
+        assert_eq!(result.error_message.as_deref(), Some("Table not found"));
+    }
+
+    #[test]
+    fn test_de_rollback_table_request() {
+        let request: RollbackTableRequest = serde_json::from_value(
+            serde_json::json!({"snapshot-id": 3_051_729_675_574_597_004_i64}),
+        )
+        .unwrap();
+        assert_eq!(request.snapshot_id, 3_051_729_675_574_597_004);
+        assert_eq!(request.ref_name, None);
+
+        let request: RollbackTableRequest =
+            serde_json::from_value(serde_json::json!({"snapshot-id": 1, "ref-name": "audit"}))
+                .unwrap();
+        assert_eq!(request.ref_name.as_deref(), Some("audit"));
+    }
+}
@@ -0,0 +1,99 @@
+use crate::api::management::v1::ApiServer;
+use crate::api::{ApiContext, Result};
+use crate::request_metadata::RequestMetadata;
+use crate::service::token_revocation::{RevocationTarget, TokenRevocation};
+use crate::service::{auth::AuthZHandler, secrets::SecretStore, Catalog, State};
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum RevokeTokenTarget {
+    /// A single token, identified by its `jti` claim.
+    TokenId {
+        /// Value of the `jti` claim of the token to revoke.
+        #[serde(rename = "token-id")]
+        token_id: String,
+    },
+    /// All tokens of a subject that were issued up to now.
+    /// Tokens issued after the revocation are accepted again.
+    Subject {
+        /// Value of the `sub` claim of the tokens to revoke.
+        subject: String,
+    },
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct RevokeTokenRequest {
+    /// Token(s) to revoke.
+    pub target: RevokeTokenTarget,
+    /// Point in time after which the revocation can be dropped,
+    /// typically the expiry of the revoked token.
+    /// If not specified, the revocation is kept indefinitely.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Free-text reason, stored for auditing.
+    pub reason: Option<String>,
+}
+
+impl From<RevokeTokenTarget> for RevocationTarget {
+    fn from(value: RevokeTokenTarget) -> Self {
+        match value {
+            RevokeTokenTarget::TokenId { token_id } => RevocationTarget::TokenId(token_id),
+            RevokeTokenTarget::Subject { subject } => RevocationTarget::Subject(subject),
+        }
+    }
+}
+
+impl<C: Catalog, A: AuthZHandler, S: SecretStore> Service<C, A, S> for ApiServer<C, A, S> {}
+
+#[async_trait::async_trait]
+pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
+    async fn revoke_token(
+        request: RevokeTokenRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_revoke_token(&request_metadata, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let RevokeTokenRequest {
+            target,
+            expires_at,
+            reason,
+        } = request;
+        let revocation = TokenRevocation {
+            target: target.into(),
+            revoked_at: Utc::now(),
+            expires_at,
+            reason,
+        };
+
+        context.v1_state.revocations.revoke(&revocation).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn test_de_revoke_token_request() {
+        let request = serde_json::json!({
+            "target": {
+                "type": "token-id",
+                "token-id": "1234"
+            },
+            "expires-at": "2024-06-01T12:00:00Z",
+        });
+
+        let request: super::RevokeTokenRequest = serde_json::from_value(request).unwrap();
+        assert!(matches!(
+            request.target,
+            super::RevokeTokenTarget::TokenId { token_id } if token_id == "1234"
+        ));
+        assert!(request.expires_at.is_some());
+        assert!(request.reason.is_none());
+    }
+}
@@ -1,14 +1,30 @@
 use crate::api::management::v1::ApiServer;
 use crate::api::{ApiContext, Result};
 use crate::request_metadata::RequestMetadata;
-pub use crate::service::storage::{S3Credential, S3Profile, StorageCredential, StorageProfile};
-
+use crate::service::event_publisher::{
+    CloudEventsPublisher, EventMetadata, EventSubject, EventType,
+};
+pub use crate::service::storage::{
+    AccessDelegation, FileProfile, GcsCredential, GcsProfile, GcsServiceKey, S3Credential,
+    S3Flavor, S3Profile, S3SseAlgorithm, StorageCredential, StorageProfile,
+};
+
+pub use crate::service::api_usage::UsageWindow;
+use crate::service::Project;
+use crate::service::{
+    api_usage::ApiUsage, auth::AuthZHandler, client_usage::ClientUsage, secrets::SecretStore,
+    storage_usage::NamespaceStorageUsage, Catalog, NamespaceIdent, OwnedObject, State, TableIdent,
+    Transaction,
+};
 #[allow(clippy::module_name_repetitions)]
-pub use crate::service::WarehouseStatus;
-use crate::service::{auth::AuthZHandler, secrets::SecretStore, Catalog, State, Transaction};
-use crate::{ProjectIdent, WarehouseIdent};
+pub use crate::service::{
+    auth::UnauthorizedResponse, storage_validation::StorageValidation, EngineTableConfig,
+    EventFilter, SnapshotRetentionPolicy, TablePolicy, WarehouseAllowlist, WarehouseStatus,
+};
+use crate::{ProjectIdent, WarehouseIdent, CONFIG};
 use iceberg_ext::catalog::rest::ErrorModel;
 use serde::Deserialize;
+use std::collections::HashMap;
 use utoipa::ToSchema;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
@@ -25,6 +41,79 @@ pub struct CreateWarehouseRequest {
     pub storage_credential: Option<StorageCredential>,
 }
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ValidateStorageRequest {
+    /// Project the warehouse would be created in.
+    pub project_id: uuid::Uuid,
+    /// Storage profile to validate.
+    pub storage_profile: StorageProfile,
+    /// Optional storage credential to validate the profile with.
+    pub storage_credential: Option<StorageCredential>,
+}
+
+/// Operation of the storage validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum StorageOperation {
+    /// Checking the profile itself, creating a client or obtaining credentials.
+    Configuration,
+    Write,
+    Read,
+    Delete,
+}
+
+impl StorageOperation {
+    /// Operation an error of [`StorageProfile::validate`] or [`StorageProfile::probe`] occurred in.
+    fn from_error_type(r#type: &str) -> Self {
+        if r#type.ends_with("ReadError") {
+            Self::Read
+        } else if r#type.ends_with("DeleteError") {
+            Self::Delete
+        } else if r#type.contains("TestFile") || r#type.ends_with("WriteError") {
+            Self::Write
+        } else {
+            Self::Configuration
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ValidateStorageResponse {
+    /// Whether a warehouse can be created with the storage profile and credential.
+    pub valid: bool,
+    /// Operation that failed. Not set if the storage is valid.
+    pub failed_operation: Option<StorageOperation>,
+    /// Type of the error, e.g. `S3TestFileWriteError`. Not set if the storage is valid.
+    pub error_type: Option<String>,
+    /// Error reported by the storage. Not set if the storage is valid.
+    pub error: Option<String>,
+    /// Time the validation took.
+    pub duration_ms: u64,
+}
+
+impl ValidateStorageResponse {
+    fn from_result(result: &Result<()>, started: std::time::Instant) -> Self {
+        let error = result.as_ref().err().map(|e| &e.error);
+        Self {
+            valid: result.is_ok(),
+            failed_operation: error.map(|e| StorageOperation::from_error_type(&e.r#type)),
+            error_type: error.map(|e| e.r#type.clone()),
+            // Errors of storage SDKs may echo parts of the request
+            error: error.map(|e| {
+                iceberg_ext::redaction::redact_text(
+                    &std::iter::once(e.message.as_str())
+                        .chain(e.stack.iter().flatten().map(String::as_str))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                )
+            }),
+            duration_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+        }
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
 #[serde(rename_all = "kebab-case")]
 pub struct CreateWarehouseResponse {
@@ -54,10 +143,17 @@ pub struct ListWarehousesRequest {
     /// If not provided, only active warehouses are returned.
     #[serde(default)]
     pub warehouse_status: Option<Vec<WarehouseStatus>>,
-    /// The project ID to list warehouses for.
-    /// Setting a warehouse is required.
+    /// ID or name of the project to list warehouses for.
+    /// Required unless set via the `x-project-id` header.
     #[serde(default)]
-    pub project_id: Option<uuid::Uuid>,
+    pub project_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+#[serde(rename_all = "kebab-case")]
+pub struct GetWarehouseByNameQuery {
+    /// Name of the warehouse, unique within the project.
+    pub name: String,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
@@ -71,6 +167,26 @@ pub struct RenameWarehouseRequest {
 pub struct ProjectResponse {
     /// ID of the project.
     pub project_id: uuid::Uuid,
+    /// Name of the project, unique in the catalog.
+    /// Defaults to the ID of the project.
+    pub project_name: String,
+    /// Free-form metadata of the project.
+    pub metadata: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct RenameProjectRequest {
+    /// New name for the project. Must be unique in the catalog
+    /// and must not be the ID of another project.
+    pub new_name: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetProjectMetadataRequest {
+    /// Metadata of the project. Replaces the existing metadata.
+    pub metadata: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
@@ -93,6 +209,40 @@ pub struct GetWarehouseResponse {
     pub storage_profile: StorageProfile,
     /// Whether the warehouse is active.
     pub status: WarehouseStatus,
+    /// Principal that currently owns the warehouse.
+    pub owner: Option<String>,
+    /// Principal that created the warehouse.
+    pub created_by: Option<String>,
+    /// Principals and groups allowed to access the warehouse.
+    /// If not set, access is only governed by the authorization backend.
+    pub allowlist: Option<WarehouseAllowlist>,
+    /// Whether the warehouse rejects creates, commits, drops and renames.
+    pub read_only: bool,
+    /// Whether deviations of older pyiceberg clients are tolerated.
+    pub pyiceberg_compat: bool,
+    /// Extra table config per client engine.
+    pub engine_table_config: EngineTableConfig,
+    /// Address of the S3 signer returned to clients.
+    /// If not set, it is derived from the base URI of the catalog.
+    pub signer_uri: Option<String>,
+    /// Response to requests for objects the principal is not allowed to access.
+    /// If not set, the server default applies.
+    pub unauthorized_response: Option<UnauthorizedResponse>,
+    /// Access delegation used if a client does not request a mode.
+    /// If not set, the default of the storage profile applies.
+    pub access_delegation: Option<AccessDelegation>,
+    /// Whether tables may be located outside of the storage profile.
+    pub allow_external_locations: bool,
+    /// Whether new tables are located below a hash prefix.
+    pub object_storage_layout: bool,
+    /// Time of the last rotation of the storage credential.
+    /// If not set, the credential was never rotated.
+    pub credential_rotated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Result of the last periodic check of the storage profile and credential.
+    /// If not set, the warehouse was not checked yet.
+    pub storage_validation: Option<StorageValidation>,
+    /// Events of the warehouse that are published.
+    pub event_filter: EventFilter,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
@@ -110,6 +260,423 @@ pub struct UpdateWarehouseCredentialRequest {
     pub new_storage_credential: Option<StorageCredential>,
 }
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct RotateWarehouseCredentialRequest {
+    /// New storage credential of the warehouse. It is validated against the
+    /// storage before it replaces the current credential.
+    pub new_storage_credential: StorageCredential,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct RotateWarehouseCredentialResponse {
+    /// Time of the rotation.
+    pub rotated_at: chrono::DateTime<chrono::Utc>,
+    /// Earliest time at which the previous credential is deleted.
+    /// Not set if the warehouse had no credential before or previous
+    /// credentials are not deleted automatically.
+    pub previous_credential_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetWarehouseAllowlistRequest {
+    /// Principals and groups allowed to access the warehouse.
+    /// If not specified, the existing allowlist is removed and
+    /// access is no longer restricted.
+    pub allowlist: Option<WarehouseAllowlist>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetWarehouseSignerUriRequest {
+    /// Address of the S3 signer returned to clients, e.g. if the signer is
+    /// reachable via a separate ingress. Clients send sign requests to
+    /// `{signer-uri}/v1/aws/s3/sign`, so this should point to
+    /// `{catalog}/v1/{warehouse_id}` as seen by the client.
+    /// If not specified, the override is removed.
+    #[schema(value_type = Option<String>)]
+    pub signer_uri: Option<url::Url>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetWarehouseUnauthorizedResponseRequest {
+    /// Response to requests for namespaces, tables and views the principal is
+    /// not allowed to access. `not-found` hides whether the object exists.
+    /// If not specified, the server default applies.
+    pub unauthorized_response: Option<UnauthorizedResponse>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetWarehouseAccessDelegationRequest {
+    /// Access delegation used if a client does not send the
+    /// `X-Iceberg-Access-Delegation` header. Modes requested by clients and
+    /// the `catalog.access-delegation` property of namespaces and tables take precedence.
+    /// If not specified, the default of the storage profile applies.
+    pub access_delegation: Option<AccessDelegation>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetWarehouseAllowExternalLocationsRequest {
+    /// Allow table locations, `write.data.path` and `write.metadata.path` outside of
+    /// the bucket and key prefix of the storage profile. Credentials and signatures are
+    /// then issued for storage the warehouse does not own, so this should only be
+    /// enabled for warehouses with migrated tables.
+    pub allow_external_locations: bool,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetWarehouseObjectStorageLayoutRequest {
+    /// Locate new tables below a hash prefix, e.g. `s3://bucket/1a2b3c4d/<namespace-uuid>/<table-uuid>`,
+    /// so that requests are spread across the key space of the bucket. Namespaces and
+    /// tables may override it with the `write.object-storage.enabled` property.
+    /// Existing tables keep their location.
+    pub object_storage_layout: bool,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetEngineTableConfigRequest {
+    /// Table config per client engine, i.e. `spark`, `trino` or `pyiceberg`.
+    /// Replaces the existing config. Keys generated by the catalog,
+    /// such as credentials, take precedence.
+    pub engine_table_config: EngineTableConfig,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetEventFilterRequest {
+    /// Events of the warehouse that are published. Replaces the existing filter.
+    pub event_filter: EventFilter,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetWarehouseReadOnlyRequest {
+    /// If true, the warehouse serves all read operations but rejects
+    /// creates, commits, drops and renames.
+    pub read_only: bool,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetWarehousePyicebergCompatRequest {
+    /// If true, namespaces prefixed with the warehouse name or missing it as
+    /// first segment are normalized, and tables are returned with a signer
+    /// `uri` that also works for the fsspec `FileIO` of older pyiceberg versions.
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetTablePolicyRequest {
+    /// Namespace the table is in.
+    pub namespace: Vec<String>,
+    /// Name of the table.
+    pub name: String,
+    /// Policy to attach to the table.
+    /// If not specified, the existing policy is removed.
+    pub policy: Option<TablePolicy>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetWarehouseRetentionPolicyRequest {
+    /// Policy applied to all tables of the warehouse whose namespaces have no policy.
+    /// If not specified, the existing policy is removed.
+    pub policy: Option<SnapshotRetentionPolicy>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetNamespaceRetentionPolicyRequest {
+    pub namespace: Vec<String>,
+    /// Policy applied to all tables of the namespace and its child namespaces.
+    /// If not specified, the existing policy is removed.
+    pub policy: Option<SnapshotRetentionPolicy>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum OwnershipTransferObject {
+    /// The warehouse itself.
+    Warehouse,
+    /// A namespace in the warehouse.
+    Namespace {
+        /// Namespace to transfer.
+        namespace: Vec<String>,
+    },
+    /// A table in the warehouse.
+    Table {
+        /// Namespace the table is in.
+        namespace: Vec<String>,
+        /// Name of the table.
+        name: String,
+    },
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct TransferOwnershipRequest {
+    /// Object to transfer.
+    pub object: OwnershipTransferObject,
+    /// Principal that should own the object after the transfer.
+    pub new_owner: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct TransferOwnershipResponse {
+    /// Owner of the object before the transfer.
+    pub previous_owner: Option<String>,
+    /// Owner of the object after the transfer.
+    pub owner: String,
+    /// Principal that created the object.
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ClientUsageResponse {
+    /// Engine of the client, i.e. `pyiceberg`, `spark` or `trino`.
+    /// Java clients that do not identify their engine are reported as `iceberg-java`.
+    pub engine: String,
+    /// Version of the engine, if known.
+    pub version: Option<String>,
+    /// Number of catalog sessions (`GET /config` requests) started by the client.
+    pub session_count: i64,
+    pub first_seen: chrono::DateTime<chrono::Utc>,
+    pub last_seen: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ListClientUsageResponse {
+    /// Usage of the warehouse per engine and version.
+    pub clients: Vec<ClientUsageResponse>,
+}
+
+/// Longest time range that can be queried at once.
+const MAX_USAGE_RANGE_DAYS: i64 = 93;
+
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+#[serde(rename_all = "kebab-case")]
+pub struct GetProjectUsageQuery {
+    /// Start of the time range, inclusive. Defaults to 24 hours before `to`.
+    #[serde(default)]
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    /// End of the time range, exclusive. Defaults to now.
+    #[serde(default)]
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    /// Granularity of the returned usage. Defaults to `hour`.
+    #[serde(default)]
+    pub window: UsageWindow,
+    /// Only return the usage of this warehouse.
+    #[serde(default)]
+    pub warehouse_id: Option<uuid::Uuid>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct ApiUsageResponse {
+    pub warehouse_id: uuid::Uuid,
+    /// Start of the window in UTC.
+    pub window_start: chrono::DateTime<chrono::Utc>,
+    /// Number of requests addressing the warehouse.
+    pub request_count: i64,
+    /// Number of successful table and transaction commits.
+    pub commit_count: i64,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct GetProjectUsageResponse {
+    pub window: UsageWindow,
+    /// Usage per warehouse and window, oldest first.
+    /// Windows without requests are omitted.
+    pub usage: Vec<ApiUsageResponse>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct NamespaceStorageUsageResponse {
+    pub namespace: Vec<String>,
+    /// Number of tables directly in the namespace.
+    pub table_count: i64,
+    /// Sum of `total-files-size` of the current snapshot of each table.
+    pub total_files_size_bytes: i64,
+    /// Number of tables whose current snapshot does not report `total-files-size`.
+    /// Their size is not included in `total-files-size-bytes`.
+    pub tables_without_size: i64,
+    pub computed_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct GetWarehouseStorageUsageResponse {
+    pub warehouse_id: uuid::Uuid,
+    /// Number of tables in the warehouse.
+    pub table_count: i64,
+    /// Estimated size of all tables in the warehouse.
+    pub total_files_size_bytes: i64,
+    /// Number of tables whose size is unknown.
+    pub tables_without_size: i64,
+    /// Time of the oldest namespace figure.
+    /// Not set if usage has not been computed yet.
+    pub computed_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Usage per namespace, excluding child namespaces.
+    pub namespaces: Vec<NamespaceStorageUsageResponse>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum HealthStatus {
+    Ok,
+    Failed,
+    /// Not checked because a check it depends on failed.
+    Skipped,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct HealthCheckResult {
+    pub status: HealthStatus,
+    /// Time the check took.
+    pub duration_ms: u64,
+    /// Why the check failed. Not set if it succeeded.
+    pub error: Option<String>,
+}
+
+impl HealthCheckResult {
+    fn skipped() -> Self {
+        Self {
+            status: HealthStatus::Skipped,
+            duration_ms: 0,
+            error: None,
+        }
+    }
+
+    fn from_result<T>(result: &Result<T>, started: std::time::Instant) -> Self {
+        Self {
+            status: if result.is_ok() {
+                HealthStatus::Ok
+            } else {
+                HealthStatus::Failed
+            },
+            duration_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+            // Errors of storage SDKs and secret stores may echo parts of the request
+            error: result.as_ref().err().map(|e| {
+                iceberg_ext::redaction::redact_text(&format!(
+                    "{}: {}",
+                    e.error.r#type, e.error.message
+                ))
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct WarehouseHealthResponse {
+    pub warehouse_id: uuid::Uuid,
+    /// Whether all checks succeeded.
+    pub healthy: bool,
+    pub checked_at: chrono::DateTime<chrono::Utc>,
+    /// Loading the storage credential from the secret store.
+    /// Not set if the warehouse has no storage credential.
+    pub storage_credential: Option<HealthCheckResult>,
+    /// Writing, reading and deleting a canary object in the storage.
+    pub storage: HealthCheckResult,
+}
+
+impl From<NamespaceStorageUsage> for NamespaceStorageUsageResponse {
+    fn from(value: NamespaceStorageUsage) -> Self {
+        Self {
+            namespace: value.namespace.inner(),
+            table_count: value.table_count,
+            total_files_size_bytes: value.total_files_size_bytes,
+            tables_without_size: value.tables_without_size,
+            computed_at: value.computed_at,
+        }
+    }
+}
+
+impl From<ApiUsage> for ApiUsageResponse {
+    fn from(value: ApiUsage) -> Self {
+        Self {
+            warehouse_id: value.warehouse_id.into_uuid(),
+            window_start: value.window_start,
+            request_count: value.request_count,
+            commit_count: value.commit_count,
+        }
+    }
+}
+
+impl From<ClientUsage> for ClientUsageResponse {
+    fn from(value: ClientUsage) -> Self {
+        Self {
+            engine: value.engine,
+            version: value.version,
+            session_count: value.session_count,
+            first_seen: value.first_seen,
+            last_seen: value.last_seen,
+        }
+    }
+}
+
+impl TryFrom<OwnershipTransferObject> for OwnedObject {
+    type Error = ErrorModel;
+
+    fn try_from(value: OwnershipTransferObject) -> std::result::Result<Self, Self::Error> {
+        let parse_namespace = |namespace: Vec<String>| {
+            NamespaceIdent::from_vec(namespace).map_err(|e| {
+                ErrorModel::builder()
+                    .code(http::StatusCode::BAD_REQUEST.into())
+                    .message("Invalid namespace".to_string())
+                    .r#type("InvalidNamespace".to_string())
+                    .stack(Some(vec![e.to_string()]))
+                    .build()
+            })
+        };
+
+        Ok(match value {
+            OwnershipTransferObject::Warehouse => OwnedObject::Warehouse,
+            OwnershipTransferObject::Namespace { namespace } => {
+                OwnedObject::Namespace(parse_namespace(namespace)?)
+            }
+            OwnershipTransferObject::Table { namespace, name } => {
+                OwnedObject::Table(TableIdent::new(parse_namespace(namespace)?, name))
+            }
+        })
+    }
+}
+
+async fn emit_warehouse_event(
+    warehouse_id: &WarehouseIdent,
+    typ: EventType,
+    body: serde_json::Value,
+    request_metadata: &RequestMetadata,
+    publisher: CloudEventsPublisher,
+) {
+    let _ = publisher
+        .publish(
+            uuid::Uuid::now_v7(),
+            typ,
+            body,
+            EventMetadata::single(
+                *warehouse_id.as_uuid(),
+                EventSubject::Warehouse,
+                request_metadata,
+            ),
+        )
+        .await;
+}
+
 impl axum::response::IntoResponse for CreateWarehouseResponse {
     fn into_response(self) -> axum::http::Response<axum::body::Body> {
         (http::StatusCode::CREATED, axum::Json(self)).into_response()
@@ -149,22 +716,66 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
             None
         };
 
+        let body = serde_json::json!({
+            "warehouse-name": warehouse_name,
+            "project-id": project_id,
+        });
         let warehouse_id = C::create_warehouse(
             warehouse_name,
             project_id.into(),
             storage_profile,
             secret_id,
+            request_metadata.principal(),
             transaction.transaction(),
         )
         .await?;
 
         transaction.commit().await?;
+        emit_warehouse_event(
+            &warehouse_id,
+            EventType::WarehouseCreated,
+            body,
+            &request_metadata,
+            context.v1_state.publisher,
+        )
+        .await;
 
         Ok(CreateWarehouseResponse {
             warehouse_id: warehouse_id.into_uuid(),
         })
     }
 
+    async fn validate_storage(
+        request: ValidateStorageRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<ValidateStorageResponse> {
+        let ValidateStorageRequest {
+            project_id,
+            mut storage_profile,
+            storage_credential,
+        } = request;
+        let project_ident = ProjectIdent::from(project_id);
+
+        // ------------------- AuthZ -------------------
+        // Validating storage reaches out to arbitrary endpoints, which is
+        // limited to principals that may create warehouses.
+        A::check_create_warehouse(&request_metadata, &project_ident, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        // The checks of `create_warehouse`, followed by the read of the health check
+        let started = std::time::Instant::now();
+        let result = async {
+            storage_profile
+                .validate(storage_credential.as_ref())
+                .await?;
+            storage_profile.probe(storage_credential.as_ref()).await
+        }
+        .await;
+
+        Ok(ValidateStorageResponse::from_result(&result, started))
+    }
+
     async fn list_projects(
         context: ApiContext<State<A, C, S>>,
         request_metadata: RequestMetadata,
@@ -173,82 +784,368 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
         let projects = A::check_list_projects(&request_metadata, context.v1_state.auth).await?;
 
         // ------------------- Business Logic -------------------
-        if let Some(projects) = projects {
-            return Ok(ListProjectsResponse {
-                projects: projects
-                    .into_iter()
-                    .map(|project_id| ProjectResponse {
-                        project_id: project_id.into_uuid(),
-                    })
-                    .collect(),
-            });
-        }
-
-        let projects = C::list_projects(context.v1_state.catalog).await?;
-        Ok(ListProjectsResponse {
-            projects: projects
-                .into_iter()
-                .map(|project_id| ProjectResponse {
-                    project_id: project_id.into_uuid(),
-                })
-                .collect(),
-        })
+        let project_ids = match projects {
+            Some(projects) => projects,
+            None => C::list_projects(context.v1_state.catalog.clone()).await?,
+        };
+        let mut details = C::get_projects(Some(&project_ids), context.v1_state.catalog)
+            .await?
+            .into_iter()
+            .map(|project| (project.project_id.clone(), project))
+            .collect::<HashMap<_, _>>();
+
+        let mut projects = project_ids
+            .into_iter()
+            .map(|project_id| {
+                details
+                    .remove(&project_id)
+                    .unwrap_or_else(|| Project::unnamed(project_id))
+                    .into()
+            })
+            .collect::<Vec<ProjectResponse>>();
+        projects.sort_by(|a, b| a.project_name.cmp(&b.project_name));
+
+        Ok(ListProjectsResponse { projects })
     }
 
-    async fn list_warehouses(
-        request: ListWarehousesRequest,
+    async fn get_project(
+        project: &str,
         context: ApiContext<State<A, C, S>>,
         request_metadata: RequestMetadata,
-    ) -> Result<ListWarehousesResponse> {
+    ) -> Result<ProjectResponse> {
+        let project_id = resolve_project::<C>(project, context.v1_state.catalog.clone()).await?;
+
         // ------------------- AuthZ -------------------
-        let project_id = ProjectIdent::from(
-            request.project_id.ok_or(
-                ErrorModel::builder()
-                    .code(http::StatusCode::BAD_REQUEST.into())
-                    .message("project-id is required".to_string())
-                    .r#type("MissingProjectId".to_string())
-                    .build(),
-            )?,
-        );
-        let warehouses = A::check_list_warehouse_in_project(
-            &request_metadata,
-            &project_id,
-            context.v1_state.auth,
-        )
-        .await?;
+        let projects = A::check_list_projects(&request_metadata, context.v1_state.auth).await?;
+        if projects.is_some_and(|projects| !projects.contains(&project_id)) {
+            return Err(project_not_found(project).into());
+        }
 
         // ------------------- Business Logic -------------------
-        let warehouses = C::list_warehouses(
-            &project_id,
-            request.warehouse_status,
-            warehouses.as_ref(),
+        let project = C::get_projects(
+            Some(&std::iter::once(project_id.clone()).collect()),
             context.v1_state.catalog,
         )
-        .await?;
+        .await?
+        .pop()
+        .unwrap_or_else(|| Project::unnamed(project_id));
 
-        Ok(ListWarehousesResponse {
-            warehouses: warehouses
-                .into_iter()
-                .map(std::convert::Into::into)
-                .collect(),
-        })
+        Ok(project.into())
     }
 
-    async fn get_warehouse(
-        warehouse_id: WarehouseIdent,
+    async fn rename_project(
+        project: &str,
+        request: RenameProjectRequest,
         context: ApiContext<State<A, C, S>>,
         request_metadata: RequestMetadata,
-    ) -> Result<GetWarehouseResponse> {
+    ) -> Result<()> {
+        let project_id = resolve_project::<C>(project, context.v1_state.catalog.clone()).await?;
+
         // ------------------- AuthZ -------------------
-        A::check_get_warehouse(&request_metadata, &warehouse_id, context.v1_state.auth).await?;
+        A::check_update_project(&request_metadata, &project_id, context.v1_state.auth).await?;
 
         // ------------------- Business Logic -------------------
-        let mut transaction = C::Transaction::begin_read(context.v1_state.catalog).await?;
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        C::rename_project(&project_id, &request.new_name, transaction.transaction()).await?;
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn set_project_metadata(
+        project: &str,
+        request: SetProjectMetadataRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        let project_id = resolve_project::<C>(project, context.v1_state.catalog.clone()).await?;
+
+        // ------------------- AuthZ -------------------
+        A::check_update_project(&request_metadata, &project_id, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        C::set_project_metadata(&project_id, &request.metadata, transaction.transaction()).await?;
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete_project(
+        project: &str,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        let project_id = resolve_project::<C>(project, context.v1_state.catalog.clone()).await?;
+
+        // ------------------- AuthZ -------------------
+        A::check_delete_project(&request_metadata, &project_id, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        C::delete_project(&project_id, transaction.transaction()).await?;
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn list_warehouses(
+        request: ListWarehousesRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<ListWarehousesResponse> {
+        // ------------------- AuthZ -------------------
+        let project_id = match request.project_id {
+            Some(project) => {
+                Some(resolve_project::<C>(&project, context.v1_state.catalog.clone()).await?)
+            }
+            None => request_metadata.project_id.clone(),
+        };
+        let project_id = project_id.ok_or(
+            ErrorModel::builder()
+                .code(http::StatusCode::BAD_REQUEST.into())
+                .message(
+                    "project-id is required, either as query parameter or x-project-id header"
+                        .to_string(),
+                )
+                .r#type("MissingProjectId".to_string())
+                .build(),
+        )?;
+        let warehouses = A::check_list_warehouse_in_project(
+            &request_metadata,
+            &project_id,
+            context.v1_state.auth,
+        )
+        .await?;
+
+        // ------------------- Business Logic -------------------
+        let warehouses = C::list_warehouses(
+            &project_id,
+            request.warehouse_status,
+            warehouses.as_ref(),
+            context.v1_state.catalog,
+        )
+        .await?;
+
+        Ok(ListWarehousesResponse {
+            warehouses: warehouses
+                .into_iter()
+                .map(std::convert::Into::into)
+                .collect(),
+        })
+    }
+
+    async fn get_warehouse(
+        warehouse_id: WarehouseIdent,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<GetWarehouseResponse> {
+        // ------------------- AuthZ -------------------
+        A::check_get_warehouse(&request_metadata, &warehouse_id, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_read(context.v1_state.catalog).await?;
         let warehouses = C::get_warehouse(&warehouse_id, transaction.transaction()).await?;
 
         Ok(warehouses.into())
     }
 
+    async fn get_warehouse_by_name(
+        project: &str,
+        query: GetWarehouseByNameQuery,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<GetWarehouseResponse> {
+        let project_id = resolve_project::<C>(project, context.v1_state.catalog.clone()).await?;
+
+        // ------------------- AuthZ -------------------
+        let allowed_warehouses = A::check_list_warehouse_in_project(
+            &request_metadata,
+            &project_id,
+            context.v1_state.auth,
+        )
+        .await?;
+
+        // ------------------- Business Logic -------------------
+        // Warehouses the principal may not see are reported as missing,
+        // so that their names are not disclosed.
+        let warehouse_id =
+            C::get_warehouse_id_by_name(&project_id, &query.name, context.v1_state.catalog.clone())
+                .await?
+                .filter(|warehouse_id| {
+                    allowed_warehouses
+                        .as_ref()
+                        .map_or(true, |allowed| allowed.contains(warehouse_id))
+                })
+                .ok_or_else(|| {
+                    ErrorModel::builder()
+                        .code(http::StatusCode::NOT_FOUND.into())
+                        .message(format!("Warehouse '{}' not found in project", query.name))
+                        .r#type("WarehouseNotFound".to_string())
+                        .build()
+                })?;
+
+        let mut transaction = C::Transaction::begin_read(context.v1_state.catalog).await?;
+        let warehouse = C::get_warehouse(&warehouse_id, transaction.transaction()).await?;
+
+        Ok(warehouse.into())
+    }
+
+    async fn list_client_usage(
+        warehouse_id: WarehouseIdent,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<ListClientUsageResponse> {
+        // ------------------- AuthZ -------------------
+        A::check_get_warehouse(&request_metadata, &warehouse_id, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let clients = context
+            .v1_state
+            .stores
+            .client_usage
+            .list_client_usage(&warehouse_id)
+            .await?;
+
+        Ok(ListClientUsageResponse {
+            clients: clients.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    async fn get_warehouse_storage_usage(
+        warehouse_id: WarehouseIdent,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<GetWarehouseStorageUsageResponse> {
+        // ------------------- AuthZ -------------------
+        A::check_get_warehouse(&request_metadata, &warehouse_id, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let namespaces = context
+            .v1_state
+            .stores
+            .storage_usage
+            .list_storage_usage(&warehouse_id)
+            .await?;
+
+        Ok(GetWarehouseStorageUsageResponse {
+            warehouse_id: warehouse_id.into_uuid(),
+            table_count: namespaces.iter().map(|n| n.table_count).sum(),
+            total_files_size_bytes: namespaces.iter().map(|n| n.total_files_size_bytes).sum(),
+            tables_without_size: namespaces.iter().map(|n| n.tables_without_size).sum(),
+            computed_at: namespaces.iter().map(|n| n.computed_at).min(),
+            namespaces: namespaces.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    async fn get_warehouse_health(
+        warehouse_id: WarehouseIdent,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<WarehouseHealthResponse> {
+        // ------------------- AuthZ -------------------
+        // The check writes to the storage of the warehouse, so readers must not trigger it.
+        A::check_update_storage(&request_metadata, &warehouse_id, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_read(context.v1_state.catalog).await?;
+        let warehouse = C::get_warehouse(&warehouse_id, transaction.transaction()).await?;
+        transaction.commit().await?;
+
+        let checked_at = chrono::Utc::now();
+        let (credential, storage_credential) = if let Some(secret_id) = &warehouse.storage_secret_id
+        {
+            let started = std::time::Instant::now();
+            let secret =
+                S::get_secret_by_id::<StorageCredential>(secret_id, context.v1_state.secrets)
+                    .await
+                    .map(|secret| secret.secret);
+            let check = HealthCheckResult::from_result(&secret, started);
+            (Some(secret), Some(check))
+        } else {
+            (None, None)
+        };
+
+        let storage = match credential {
+            Some(Err(_)) => HealthCheckResult::skipped(),
+            Some(Ok(credential)) => {
+                let started = std::time::Instant::now();
+                let result = warehouse.storage_profile.probe(Some(&credential)).await;
+                HealthCheckResult::from_result(&result, started)
+            }
+            None => {
+                let started = std::time::Instant::now();
+                let result = warehouse.storage_profile.probe(None).await;
+                HealthCheckResult::from_result(&result, started)
+            }
+        };
+
+        Ok(WarehouseHealthResponse {
+            warehouse_id: warehouse_id.into_uuid(),
+            healthy: storage.status == HealthStatus::Ok
+                && storage_credential
+                    .as_ref()
+                    .map_or(true, |check| check.status == HealthStatus::Ok),
+            checked_at,
+            storage_credential,
+            storage,
+        })
+    }
+
+    async fn get_project_usage(
+        project: &str,
+        query: GetProjectUsageQuery,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<GetProjectUsageResponse> {
+        let project_id = resolve_project::<C>(project, context.v1_state.catalog.clone()).await?;
+
+        // ------------------- VALIDATIONS -------------------
+        let to = query.to.unwrap_or_else(chrono::Utc::now);
+        let from = query.from.unwrap_or(to - chrono::Duration::days(1));
+        if from >= to || to - from > chrono::Duration::days(MAX_USAGE_RANGE_DAYS) {
+            return Err(ErrorModel::builder()
+                .code(http::StatusCode::BAD_REQUEST.into())
+                .message(format!(
+                    "`from` must be before `to` and the range must not exceed {MAX_USAGE_RANGE_DAYS} days"
+                ))
+                .r#type("InvalidUsageRange".to_string())
+                .build()
+                .into());
+        }
+
+        // ------------------- AuthZ -------------------
+        let mut warehouses = A::check_list_warehouse_in_project(
+            &request_metadata,
+            &project_id,
+            context.v1_state.auth,
+        )
+        .await?;
+        if let Some(warehouse_id) = query.warehouse_id.map(WarehouseIdent::from) {
+            let allowed = warehouses
+                .as_ref()
+                .map_or(true, |warehouses| warehouses.contains(&warehouse_id));
+            warehouses = Some(if allowed {
+                std::iter::once(warehouse_id).collect()
+            } else {
+                std::collections::HashSet::new()
+            });
+        }
+
+        // ------------------- Business Logic -------------------
+        let usage = context
+            .v1_state
+            .stores
+            .api_usage
+            .list_api_usage(&project_id, warehouses.as_ref(), query.window, from, to)
+            .await?;
+
+        Ok(GetProjectUsageResponse {
+            window: query.window,
+            usage: usage.into_iter().map(Into::into).collect(),
+        })
+    }
+
     async fn delete_warehouse(
         warehouse_id: WarehouseIdent,
         context: ApiContext<State<A, C, S>>,
@@ -263,6 +1160,19 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
         C::delete_warehouse(&warehouse_id, transaction.transaction()).await?;
 
         transaction.commit().await?;
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+        emit_warehouse_event(
+            &warehouse_id,
+            EventType::WarehouseDeleted,
+            serde_json::Value::Null,
+            &request_metadata,
+            context.v1_state.publisher,
+        )
+        .await;
 
         Ok(())
     }
@@ -281,6 +1191,19 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
         C::rename_warehouse(&warehouse_id, &request.new_name, transaction.transaction()).await?;
 
         transaction.commit().await?;
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+        emit_warehouse_event(
+            &warehouse_id,
+            EventType::WarehouseRenamed,
+            serde_json::json!({ "new-name": request.new_name }),
+            &request_metadata,
+            context.v1_state.publisher,
+        )
+        .await;
 
         Ok(())
     }
@@ -305,6 +1228,19 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
         .await?;
 
         transaction.commit().await?;
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+        emit_warehouse_event(
+            &warehouse_id,
+            EventType::WarehouseDeactivated,
+            serde_json::Value::Null,
+            &request_metadata,
+            context.v1_state.publisher,
+        )
+        .await;
 
         Ok(())
     }
@@ -329,6 +1265,19 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
         .await?;
 
         transaction.commit().await?;
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+        emit_warehouse_event(
+            &warehouse_id,
+            EventType::WarehouseActivated,
+            serde_json::Value::Null,
+            &request_metadata,
+            context.v1_state.publisher,
+        )
+        .await;
 
         Ok(())
     }
@@ -365,6 +1314,7 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
             None
         };
 
+        let body = serde_json::json!({ "storage-profile": storage_profile });
         C::update_storage_profile(
             &warehouse_id,
             storage_profile,
@@ -374,6 +1324,19 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
         .await?;
 
         transaction.commit().await?;
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+        emit_warehouse_event(
+            &warehouse_id,
+            EventType::WarehouseStorageUpdated,
+            body,
+            &request_metadata,
+            context.v1_state.publisher,
+        )
+        .await;
 
         // Delete the old secret if it exists - never fail the request if the deletion fails
         if let Some(old_secret_id) = old_secret_id {
@@ -417,6 +1380,7 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
             None
         };
 
+        let body = serde_json::json!({ "storage-profile": storage_profile });
         C::update_storage_profile(
             &warehouse_id,
             storage_profile,
@@ -426,6 +1390,19 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
         .await?;
 
         transaction.commit().await?;
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+        emit_warehouse_event(
+            &warehouse_id,
+            EventType::WarehouseStorageUpdated,
+            body,
+            &request_metadata,
+            context.v1_state.publisher.clone(),
+        )
+        .await;
 
         // Delete the old secret if it exists - never fail the request if the deletion fails
         if let Some(old_secret_id) = old_secret_id {
@@ -439,62 +1416,960 @@ pub trait Service<C: Catalog, A: AuthZHandler, S: SecretStore> {
 
         Ok(())
     }
-}
 
-impl axum::response::IntoResponse for ListProjectsResponse {
-    fn into_response(self) -> axum::http::Response<axum::body::Body> {
-        axum::Json(self).into_response()
-    }
-}
+    async fn rotate_credential(
+        warehouse_id: WarehouseIdent,
+        request: RotateWarehouseCredentialRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<RotateWarehouseCredentialResponse> {
+        // ------------------- AuthZ -------------------
+        A::check_update_storage(&request_metadata, &warehouse_id, context.v1_state.auth).await?;
 
-impl axum::response::IntoResponse for ListWarehousesResponse {
-    fn into_response(self) -> axum::http::Response<axum::body::Body> {
-        axum::Json(self).into_response()
-    }
-}
+        // ------------------- Business Logic -------------------
+        let RotateWarehouseCredentialRequest {
+            new_storage_credential,
+        } = request;
 
-impl axum::response::IntoResponse for GetWarehouseResponse {
-    fn into_response(self) -> axum::http::Response<axum::body::Body> {
-        axum::Json(self).into_response()
-    }
-}
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        let warehouse = C::get_warehouse(&warehouse_id, transaction.transaction()).await?;
+        let mut storage_profile = warehouse.storage_profile;
+        storage_profile
+            .validate(Some(&new_storage_credential))
+            .await?;
 
-impl From<crate::service::GetWarehouseResponse> for GetWarehouseResponse {
-    fn from(warehouse: crate::service::GetWarehouseResponse) -> Self {
-        Self {
-            id: warehouse.id.into_uuid(),
-            name: warehouse.name,
-            project_id: warehouse.project_id.into_uuid(),
-            storage_profile: warehouse.storage_profile,
-            status: warehouse.status,
+        let secret_id =
+            S::create_secret(new_storage_credential, context.v1_state.secrets.clone()).await?;
+        let rotated_at = chrono::Utc::now();
+        let result = match C::rotate_warehouse_storage_secret(
+            &warehouse_id,
+            warehouse.storage_secret_id.as_ref(),
+            &secret_id,
+            rotated_at,
+            transaction.transaction(),
+        )
+        .await
+        {
+            Ok(()) => transaction.commit().await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            // The new secret is not referenced by the warehouse
+            S::delete_secret(&secret_id, context.v1_state.secrets)
+                .await
+                .map_err(|e| {
+                    tracing::warn!("Failed to delete unused secret: {:?}", e.error);
+                })
+                .ok();
+            return Err(e);
+        }
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+
+        // The previous secret of an earlier rotation has been replaced - never fail the
+        // request if the deletion fails
+        if let Some(replaced_secret_id) = warehouse
+            .credential_rotation
+            .and_then(|rotation| rotation.previous_secret_id)
+        {
+            S::delete_secret(&replaced_secret_id, context.v1_state.secrets)
+                .await
+                .map_err(|e| {
+                    tracing::warn!("Failed to delete old secret: {:?}", e.error);
+                })
+                .ok();
         }
+
+        let grace_period = chrono::Duration::from_std(std::time::Duration::from_secs(
+            CONFIG.credential_rotation_grace_period_seconds,
+        ))
+        .ok();
+        Ok(RotateWarehouseCredentialResponse {
+            rotated_at,
+            previous_credential_expires_at: grace_period
+                .filter(|_| {
+                    warehouse.storage_secret_id.is_some()
+                        && CONFIG.credential_rotation_interval_seconds > 0
+                })
+                .and_then(|grace_period| rotated_at.checked_add_signed(grace_period)),
+        })
     }
-}
 
-#[cfg(test)]
-mod test {
-    #[test]
-    fn test_de_create_warehouse_request() {
-        let request = serde_json::json!({
-            "warehouse-name": "test_warehouse",
-            "project-id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
-            "storage-profile": {
-                "type": "s3",
-                "bucket": "test",
-                "region": "dummy",
-                "path-style-access": true,
-                "endpoint": "http://localhost:9000",
-            },
-            "storage-credential": {
-                "type": "s3",
-                "credential-type": "access-key",
-                "aws-access-key-id": "test-access-key-id",
-                "aws-secret-access-key": "test-secret-access-key",
-            },
-        });
+    async fn set_allowlist(
+        warehouse_id: WarehouseIdent,
+        request: SetWarehouseAllowlistRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_set_warehouse_allowlist(&request_metadata, &warehouse_id, context.v1_state.auth)
+            .await?;
 
-        let request: super::CreateWarehouseRequest = serde_json::from_value(request).unwrap();
-        assert_eq!(request.warehouse_name, "test_warehouse");
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+
+        C::set_warehouse_allowlist(&warehouse_id, request.allowlist, transaction.transaction())
+            .await?;
+
+        transaction.commit().await?;
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+
+        Ok(())
+    }
+
+    async fn set_engine_table_config(
+        warehouse_id: WarehouseIdent,
+        request: SetEngineTableConfigRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_set_engine_table_config(&request_metadata, &warehouse_id, context.v1_state.auth)
+            .await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        C::set_engine_table_config(
+            &warehouse_id,
+            request.engine_table_config,
+            transaction.transaction(),
+        )
+        .await?;
+        transaction.commit().await?;
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+
+        Ok(())
+    }
+
+    async fn set_event_filter(
+        warehouse_id: WarehouseIdent,
+        request: SetEventFilterRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_set_event_filter(&request_metadata, &warehouse_id, context.v1_state.auth).await?;
+
+        // ------------------- Business Logic -------------------
+        if let Some(unknown) = request
+            .event_filter
+            .disabled_event_types
+            .iter()
+            .find(|typ| !EventType::ALL.iter().any(|t| t.as_str() == typ.as_str()))
+        {
+            return Err(ErrorModel::builder()
+                .code(http::StatusCode::BAD_REQUEST.into())
+                .message(format!("Unknown event type '{unknown}'"))
+                .r#type("UnknownEventType".to_string())
+                .build()
+                .into());
+        }
+
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        C::set_event_filter(
+            &warehouse_id,
+            request.event_filter,
+            transaction.transaction(),
+        )
+        .await?;
+        transaction.commit().await?;
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+
+        Ok(())
+    }
+
+    async fn set_signer_uri(
+        warehouse_id: WarehouseIdent,
+        request: SetWarehouseSignerUriRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_set_warehouse_signer_uri(&request_metadata, &warehouse_id, context.v1_state.auth)
+            .await?;
+
+        // ------------------- Business Logic -------------------
+        if let Some(signer_uri) = &request.signer_uri {
+            if !matches!(signer_uri.scheme(), "http" | "https") {
+                return Err(ErrorModel::builder()
+                    .code(http::StatusCode::BAD_REQUEST.into())
+                    .message(format!(
+                        "Signer URI must use http or https, got '{}'.",
+                        signer_uri.scheme()
+                    ))
+                    .r#type("InvalidSignerUri".to_string())
+                    .build()
+                    .into());
+            }
+        }
+
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        C::set_warehouse_signer_uri(
+            &warehouse_id,
+            request.signer_uri.as_ref(),
+            transaction.transaction(),
+        )
+        .await?;
+        transaction.commit().await?;
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+
+        Ok(())
+    }
+
+    async fn set_unauthorized_response(
+        warehouse_id: WarehouseIdent,
+        request: SetWarehouseUnauthorizedResponseRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_set_warehouse_unauthorized_response(
+            &request_metadata,
+            &warehouse_id,
+            context.v1_state.auth,
+        )
+        .await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        C::set_warehouse_unauthorized_response(
+            &warehouse_id,
+            request.unauthorized_response,
+            transaction.transaction(),
+        )
+        .await?;
+        transaction.commit().await?;
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+
+        Ok(())
+    }
+
+    async fn set_access_delegation(
+        warehouse_id: WarehouseIdent,
+        request: SetWarehouseAccessDelegationRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_set_warehouse_access_delegation(
+            &request_metadata,
+            &warehouse_id,
+            context.v1_state.auth,
+        )
+        .await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        C::set_warehouse_access_delegation(
+            &warehouse_id,
+            request.access_delegation,
+            transaction.transaction(),
+        )
+        .await?;
+        transaction.commit().await?;
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+
+        Ok(())
+    }
+
+    async fn set_allow_external_locations(
+        warehouse_id: WarehouseIdent,
+        request: SetWarehouseAllowExternalLocationsRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_set_warehouse_allow_external_locations(
+            &request_metadata,
+            &warehouse_id,
+            context.v1_state.auth,
+        )
+        .await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        C::set_warehouse_allow_external_locations(
+            &warehouse_id,
+            request.allow_external_locations,
+            transaction.transaction(),
+        )
+        .await?;
+        transaction.commit().await?;
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+
+        Ok(())
+    }
+
+    async fn set_object_storage_layout(
+        warehouse_id: WarehouseIdent,
+        request: SetWarehouseObjectStorageLayoutRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_set_warehouse_object_storage_layout(
+            &request_metadata,
+            &warehouse_id,
+            context.v1_state.auth,
+        )
+        .await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        C::set_warehouse_object_storage_layout(
+            &warehouse_id,
+            request.object_storage_layout,
+            transaction.transaction(),
+        )
+        .await?;
+        transaction.commit().await?;
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+
+        Ok(())
+    }
+
+    async fn set_read_only(
+        warehouse_id: WarehouseIdent,
+        request: SetWarehouseReadOnlyRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_set_warehouse_read_only(&request_metadata, &warehouse_id, context.v1_state.auth)
+            .await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+
+        C::set_warehouse_read_only(&warehouse_id, request.read_only, transaction.transaction())
+            .await?;
+
+        transaction.commit().await?;
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+
+        Ok(())
+    }
+
+    async fn set_pyiceberg_compat(
+        warehouse_id: WarehouseIdent,
+        request: SetWarehousePyicebergCompatRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- AuthZ -------------------
+        A::check_set_warehouse_pyiceberg_compat(
+            &request_metadata,
+            &warehouse_id,
+            context.v1_state.auth,
+        )
+        .await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        C::set_warehouse_pyiceberg_compat(
+            &warehouse_id,
+            request.enabled,
+            transaction.transaction(),
+        )
+        .await?;
+        transaction.commit().await?;
+        context
+            .v1_state
+            .resolution_cache
+            .invalidate_warehouse(&warehouse_id)
+            .await;
+
+        Ok(())
+    }
+
+    async fn set_table_policy(
+        warehouse_id: WarehouseIdent,
+        request: SetTablePolicyRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        let SetTablePolicyRequest {
+            namespace,
+            name,
+            policy,
+        } = request;
+        let namespace = NamespaceIdent::from_vec(namespace).map_err(|e| {
+            ErrorModel::builder()
+                .code(http::StatusCode::BAD_REQUEST.into())
+                .message("Invalid namespace".to_string())
+                .r#type("InvalidNamespace".to_string())
+                .stack(Some(vec![e.to_string()]))
+                .build()
+        })?;
+        let table = TableIdent::new(namespace, name);
+
+        // ------------------- AuthZ -------------------
+        A::check_set_table_policy(
+            &request_metadata,
+            &warehouse_id,
+            &table,
+            context.v1_state.auth,
+        )
+        .await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+
+        C::set_table_policy(&warehouse_id, &table, policy, transaction.transaction()).await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn set_warehouse_retention_policy(
+        warehouse_id: WarehouseIdent,
+        request: SetWarehouseRetentionPolicyRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- VALIDATIONS -------------------
+        let SetWarehouseRetentionPolicyRequest { policy } = request;
+        policy.as_ref().map(validate_retention_policy).transpose()?;
+
+        // ------------------- AuthZ -------------------
+        A::check_set_warehouse_retention_policy(
+            &request_metadata,
+            &warehouse_id,
+            context.v1_state.auth,
+        )
+        .await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+
+        C::set_warehouse_retention_policy(&warehouse_id, policy, transaction.transaction()).await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn set_namespace_retention_policy(
+        warehouse_id: WarehouseIdent,
+        request: SetNamespaceRetentionPolicyRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<()> {
+        // ------------------- VALIDATIONS -------------------
+        let SetNamespaceRetentionPolicyRequest { namespace, policy } = request;
+        let namespace = NamespaceIdent::from_vec(namespace).map_err(|e| {
+            ErrorModel::builder()
+                .code(http::StatusCode::BAD_REQUEST.into())
+                .message("Invalid namespace".to_string())
+                .r#type("InvalidNamespace".to_string())
+                .stack(Some(vec![e.to_string()]))
+                .build()
+        })?;
+        policy.as_ref().map(validate_retention_policy).transpose()?;
+
+        // ------------------- AuthZ -------------------
+        A::check_set_namespace_retention_policy(
+            &request_metadata,
+            &warehouse_id,
+            &namespace,
+            context.v1_state.auth,
+        )
+        .await?;
+
+        // ------------------- Business Logic -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+
+        C::set_namespace_retention_policy(
+            &warehouse_id,
+            &namespace,
+            policy,
+            transaction.transaction(),
+        )
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn transfer_ownership(
+        warehouse_id: WarehouseIdent,
+        request: TransferOwnershipRequest,
+        context: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<TransferOwnershipResponse> {
+        let TransferOwnershipRequest { object, new_owner } = request;
+        let object = OwnedObject::try_from(object)?;
+
+        // ------------------- AuthZ -------------------
+        let mut transaction = C::Transaction::begin_write(context.v1_state.catalog).await?;
+        let ownership = C::get_ownership(&warehouse_id, &object, transaction.transaction()).await?;
+        A::check_transfer_ownership(
+            &request_metadata,
+            &warehouse_id,
+            &object,
+            &ownership,
+            context.v1_state.auth,
+        )
+        .await?;
+
+        // ------------------- Business Logic -------------------
+        C::set_owner(
+            &warehouse_id,
+            &object,
+            &new_owner,
+            transaction.transaction(),
+        )
+        .await?;
+        transaction.commit().await?;
+
+        Ok(TransferOwnershipResponse {
+            previous_owner: ownership.owner,
+            owner: new_owner,
+            created_by: ownership.created_by,
+        })
+    }
+}
+
+fn validate_retention_policy(policy: &SnapshotRetentionPolicy) -> Result<()> {
+    let positive = |v: Option<i64>| v.map_or(true, |v| v > 0);
+    if policy.is_empty()
+        || !positive(policy.max_snapshot_age_ms)
+        || !positive(policy.max_ref_age_ms)
+        || policy.max_snapshots == Some(0)
+    {
+        return Err(ErrorModel::builder()
+            .code(http::StatusCode::BAD_REQUEST.into())
+            .message(
+                "Retention policy must set a positive `max-snapshot-age-ms`, `max-snapshots` or `max-ref-age-ms`"
+                    .to_string(),
+            )
+            .r#type("InvalidRetentionPolicy".to_string())
+            .build()
+            .into());
+    }
+    Ok(())
+}
+
+/// Resolve a project given by ID or name, as accepted in paths and query parameters.
+/// Values that are UUIDs are taken as IDs without a lookup.
+pub(crate) async fn resolve_project<C: Catalog>(
+    project: &str,
+    catalog_state: C::State,
+) -> Result<ProjectIdent> {
+    if let Ok(project_id) = uuid::Uuid::parse_str(project) {
+        return Ok(project_id.into());
+    }
+    C::get_project_id_by_name(project, catalog_state)
+        .await?
+        .ok_or_else(|| project_not_found(project).into())
+}
+
+fn project_not_found(project: &str) -> ErrorModel {
+    ErrorModel::builder()
+        .code(http::StatusCode::NOT_FOUND.into())
+        .message(format!("Project '{project}' not found"))
+        .r#type("ProjectNotFound".to_string())
+        .build()
+}
+
+impl axum::response::IntoResponse for ListProjectsResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for ProjectResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl From<Project> for ProjectResponse {
+    fn from(project: Project) -> Self {
+        Self {
+            project_id: project.project_id.into_uuid(),
+            project_name: project.name,
+            metadata: project.metadata,
+        }
+    }
+}
+
+impl axum::response::IntoResponse for ListWarehousesResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for TransferOwnershipResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for ListClientUsageResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for GetWarehouseStorageUsageResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for RotateWarehouseCredentialResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        (http::StatusCode::OK, axum::Json(self)).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for ValidateStorageResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        (http::StatusCode::OK, axum::Json(self)).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for WarehouseHealthResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        // Lets pipelines fail pre-flight checks on the status code alone
+        let status = if self.healthy {
+            http::StatusCode::OK
+        } else {
+            http::StatusCode::SERVICE_UNAVAILABLE
+        };
+        (status, axum::Json(self)).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for GetProjectUsageResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl axum::response::IntoResponse for GetWarehouseResponse {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+impl From<crate::service::GetWarehouseResponse> for GetWarehouseResponse {
+    fn from(warehouse: crate::service::GetWarehouseResponse) -> Self {
+        Self {
+            id: warehouse.id.into_uuid(),
+            name: warehouse.name,
+            project_id: warehouse.project_id.into_uuid(),
+            storage_profile: warehouse.storage_profile,
+            status: warehouse.status,
+            owner: warehouse.owner,
+            created_by: warehouse.created_by,
+            allowlist: warehouse.allowlist,
+            read_only: warehouse.read_only,
+            pyiceberg_compat: warehouse.pyiceberg_compat,
+            engine_table_config: warehouse.engine_table_config,
+            signer_uri: warehouse.signer_uri,
+            unauthorized_response: warehouse.unauthorized_response,
+            access_delegation: warehouse.access_delegation,
+            allow_external_locations: warehouse.allow_external_locations,
+            object_storage_layout: warehouse.object_storage_layout,
+            credential_rotated_at: warehouse.credential_rotation.map(|r| r.rotated_at),
+            storage_validation: warehouse.storage_validation,
+            event_filter: warehouse.event_filter,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ApiServer, HealthCheckResult, HealthStatus, RenameWarehouseRequest, Service as _};
+    use crate::api::iceberg::v1::namespace::Service as _;
+    use crate::api::iceberg::v1::{
+        CreateNamespaceRequest, ErrorModel, NamespaceParameters, UpdateNamespacePropertiesRequest,
+    };
+    use crate::catalog::CatalogServer;
+    use crate::implementations::memory::{MemoryCatalog, MemoryCatalogState, MemoryTransaction};
+    use crate::service::storage::{FileProfile, StorageProfile};
+    use crate::service::stores::Stores;
+    use crate::service::{Catalog as _, SecretIdent, Transaction as _};
+    use crate::test_util::{
+        api_context, AllowAllAuthState, AllowAllAuthZHandler, MemorySecretStore,
+        MemorySecretStoreState, RecordingEventBackend, RequestMetadata,
+    };
+    use crate::{ProjectIdent, WarehouseIdent};
+    use axum::response::IntoResponse as _;
+    use iceberg::NamespaceIdent;
+
+    type Server = ApiServer<MemoryCatalog, AllowAllAuthZHandler, MemorySecretStore>;
+    type CatalogApi = CatalogServer<MemoryCatalog, AllowAllAuthZHandler, MemorySecretStore>;
+
+    #[tokio::test]
+    async fn test_lifecycle_events() {
+        let catalog_state = MemoryCatalogState::default();
+        let mut transaction = MemoryTransaction::begin_write(catalog_state.clone())
+            .await
+            .unwrap();
+        let warehouse_id = MemoryCatalog::create_warehouse(
+            "test_warehouse".to_string(),
+            ProjectIdent::from(uuid::Uuid::nil()),
+            StorageProfile::File(FileProfile {
+                path: "/tmp/warehouse".to_string(),
+            }),
+            None,
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let (backend, publisher) = RecordingEventBackend::publisher();
+        let context = api_context::<AllowAllAuthZHandler, MemoryCatalog, MemorySecretStore>(
+            AllowAllAuthState,
+            catalog_state.clone(),
+            MemorySecretStoreState::default(),
+            Stores::new(catalog_state),
+            publisher,
+        );
+        let prefix = Some(warehouse_id.to_string().into());
+        let namespace = NamespaceIdent::new("ns".to_string());
+
+        CatalogApi::create_namespace(
+            prefix.clone(),
+            CreateNamespaceRequest {
+                namespace: namespace.clone(),
+                properties: None,
+            },
+            context.clone(),
+            RequestMetadata::new_random(),
+        )
+        .await
+        .unwrap();
+        CatalogApi::update_namespace_properties(
+            NamespaceParameters {
+                prefix: prefix.clone(),
+                namespace: namespace.clone(),
+            },
+            UpdateNamespacePropertiesRequest {
+                removals: None,
+                updates: Some([("comment".to_string(), "test".to_string())].into()),
+            },
+            context.clone(),
+            RequestMetadata::new_random(),
+        )
+        .await
+        .unwrap();
+        CatalogApi::drop_namespace(
+            NamespaceParameters { prefix, namespace },
+            context.clone(),
+            RequestMetadata::new_random(),
+        )
+        .await
+        .unwrap();
+        Server::rename_warehouse(
+            warehouse_id.clone(),
+            RenameWarehouseRequest {
+                new_name: "renamed".to_string(),
+            },
+            context.clone(),
+            RequestMetadata::new_random(),
+        )
+        .await
+        .unwrap();
+        Server::deactivate_warehouse(warehouse_id.clone(), context, RequestMetadata::new_random())
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+        let events = backend.events();
+        let types = events
+            .iter()
+            .map(cloudevents::AttributesReader::ty)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            [
+                "namespace.created",
+                "namespace.properties-updated",
+                "namespace.dropped",
+                "warehouse.renamed",
+                "warehouse.deactivated",
+            ]
+        );
+        let warehouse = format!("warehouses/{warehouse_id}");
+        assert_eq!(
+            cloudevents::AttributesReader::subject(&events[0]),
+            Some(format!("{warehouse}/namespaces/ns").as_str())
+        );
+        assert_eq!(
+            cloudevents::AttributesReader::subject(&events[4]),
+            Some(warehouse.as_str())
+        );
+    }
+
+    async fn create_file_warehouse(
+        catalog_state: MemoryCatalogState,
+        storage_secret_id: Option<SecretIdent>,
+    ) -> WarehouseIdent {
+        let path = std::env::temp_dir().join(format!("health-{}", uuid::Uuid::now_v7()));
+        let mut transaction = MemoryTransaction::begin_write(catalog_state).await.unwrap();
+        let warehouse_id = MemoryCatalog::create_warehouse(
+            "test_warehouse".to_string(),
+            ProjectIdent::from(uuid::Uuid::nil()),
+            StorageProfile::File(FileProfile {
+                path: path.to_string_lossy().to_string(),
+            }),
+            storage_secret_id,
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+        warehouse_id
+    }
+
+    #[test]
+    fn test_health_check_result_from_result() {
+        let started = std::time::Instant::now();
+        let ok = HealthCheckResult::from_result(&Ok(()), started);
+        assert_eq!(ok.status, HealthStatus::Ok);
+        assert_eq!(ok.error, None);
+
+        let failed = HealthCheckResult::from_result::<()>(
+            &Err(ErrorModel::builder()
+                .code(http::StatusCode::FAILED_DEPENDENCY.into())
+                .message("Storage probe failed".to_string())
+                .r#type("StorageProbeWriteError".to_string())
+                .build()
+                .into()),
+            started,
+        );
+        assert_eq!(failed.status, HealthStatus::Failed);
+        assert_eq!(
+            failed.error.as_deref(),
+            Some("StorageProbeWriteError: Storage probe failed")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_warehouse_health() {
+        let catalog_state = MemoryCatalogState::default();
+        let warehouse_id = create_file_warehouse(catalog_state.clone(), None).await;
+        let context = api_context::<AllowAllAuthZHandler, MemoryCatalog, MemorySecretStore>(
+            AllowAllAuthState,
+            catalog_state.clone(),
+            MemorySecretStoreState::default(),
+            Stores::new(catalog_state),
+            RecordingEventBackend::publisher().1,
+        );
+
+        let health =
+            Server::get_warehouse_health(warehouse_id, context, RequestMetadata::new_random())
+                .await
+                .unwrap();
+        assert!(health.healthy);
+        assert!(health.storage_credential.is_none());
+        assert_eq!(health.storage.status, HealthStatus::Ok);
+        assert_eq!(health.into_response().status(), http::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_warehouse_health_skips_storage_if_secret_fails() {
+        let catalog_state = MemoryCatalogState::default();
+        // The secret does not exist in the secret store.
+        let warehouse_id = create_file_warehouse(
+            catalog_state.clone(),
+            Some(SecretIdent::from(uuid::Uuid::now_v7())),
+        )
+        .await;
+        let context = api_context::<AllowAllAuthZHandler, MemoryCatalog, MemorySecretStore>(
+            AllowAllAuthState,
+            catalog_state.clone(),
+            MemorySecretStoreState::default(),
+            Stores::new(catalog_state),
+            RecordingEventBackend::publisher().1,
+        );
+
+        let health =
+            Server::get_warehouse_health(warehouse_id, context, RequestMetadata::new_random())
+                .await
+                .unwrap();
+        assert!(!health.healthy);
+        assert_eq!(
+            health.storage_credential.as_ref().map(|check| check.status),
+            Some(HealthStatus::Failed)
+        );
+        assert_eq!(health.storage.status, HealthStatus::Skipped);
+        assert_eq!(
+            health.into_response().status(),
+            http::StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[test]
+    fn test_de_create_warehouse_request() {
+        let request = serde_json::json!({
+            "warehouse-name": "test_warehouse",
+            "project-id": "f47ac10b-58cc-4372-a567-0e02b2c3d479",
+            "storage-profile": {
+                "type": "s3",
+                "bucket": "test",
+                "region": "dummy",
+                "path-style-access": true,
+                "endpoint": "http://localhost:9000",
+            },
+            "storage-credential": {
+                "type": "s3",
+                "credential-type": "access-key",
+                "aws-access-key-id": "test-access-key-id",
+                "aws-secret-access-key": "test-secret-access-key",
+            },
+        });
+
+        let request: super::CreateWarehouseRequest = serde_json::from_value(request).unwrap();
+        assert_eq!(request.warehouse_name, "test_warehouse");
         assert_eq!(
             request.project_id,
             uuid::Uuid::parse_str("f47ac10b-58cc-4372-a567-0e02b2c3d479").unwrap()
@@ -504,4 +2379,49 @@ mod test {
         assert_eq!(s3_profile.region, "dummy");
         assert_eq!(s3_profile.path_style_access, Some(true));
     }
+
+    #[test]
+    fn test_storage_operation_from_error_type() {
+        use super::StorageOperation;
+        let cases = [
+            ("S3TestFileWriterError", StorageOperation::Write),
+            ("GcsTestFileCreationError", StorageOperation::Write),
+            ("StorageProbeWriteError", StorageOperation::Write),
+            ("StorageProbeReadError", StorageOperation::Read),
+            ("S3TestFileDeleteError", StorageOperation::Delete),
+            ("StorageProbeDeleteError", StorageOperation::Delete),
+            ("InvalidBucketName", StorageOperation::Configuration),
+            ("StsError", StorageOperation::Configuration),
+        ];
+        for (r#type, operation) in cases {
+            assert_eq!(
+                StorageOperation::from_error_type(r#type),
+                operation,
+                "{type}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_de_transfer_ownership_request() {
+        let request = serde_json::json!({
+            "object": {
+                "type": "table",
+                "namespace": ["ns1", "ns2"],
+                "name": "my_table",
+            },
+            "new-owner": "new-owner",
+        });
+
+        let request: super::TransferOwnershipRequest = serde_json::from_value(request).unwrap();
+        assert_eq!(request.new_owner, "new-owner");
+        let object = crate::service::OwnedObject::try_from(request.object).unwrap();
+        assert_eq!(
+            object,
+            crate::service::OwnedObject::Table(crate::service::TableIdent::new(
+                crate::service::NamespaceIdent::from_strs(vec!["ns1", "ns2"]).unwrap(),
+                "my_table".to_string()
+            ))
+        );
+    }
 }
@@ -1,8 +1,12 @@
 pub mod iceberg;
 pub mod management;
 
+#[cfg(feature = "graphql")]
+pub mod graphql;
 #[cfg(feature = "router")]
 pub mod router;
+#[cfg(feature = "ui")]
+pub mod ui;
 
 pub use iceberg_ext::catalog::rest::*;
 
@@ -2,14 +2,44 @@ use crate::service::event_publisher::CloudEventsPublisher;
 use crate::tracing::{MakeRequestUuid7, RestMakeSpan};
 
 use crate::api::management::v1::ApiServer;
-use crate::api::{iceberg::v1::new_v1_full_router, shutdown_signal, ApiContext};
+use crate::api::{
+    iceberg::v1::{new_v1_full_router, new_v1_oauth_router},
+    shutdown_signal, ApiContext,
+};
+use crate::request_metadata::{RequestMetadataExtractor, RequestMetadataExtractors};
+use crate::service::api_usage::{api_usage_middleware_fn, ApiUsageRecorder};
+use crate::service::catalog_token::catalog_tokens_enabled;
+use crate::service::commit_hook::CommitHooks;
+use crate::service::commit_queue::CommitQueue;
 use crate::service::contract_verification::ContractVerifiers;
+use crate::service::hmac_auth::{hmac_auth_middleware_fn, HmacVerifier};
+use crate::service::maintenance::{maintenance_middleware_fn, MaintenanceMode};
+use crate::service::recent_writes::RecentWrites;
+use crate::service::resolution_cache::ResolutionCache;
+use crate::service::runtime_config::{CorsOrigins, RuntimeConfigHandle};
+use crate::service::security::{security_middleware_fn, SecurityPolicy};
+use crate::service::sigv4_verification::{HttpSessionCredentialsResolver, SigV4Verifier};
+use crate::service::stores::Stores;
+use crate::service::table_cache::TableCache;
+use crate::service::token_revocation::RevocationList;
 use crate::service::token_verification::Verifier;
-use axum::{routing::get, Router};
-use tower::ServiceBuilder;
+use crate::CONFIG;
+use axum::extract::Request;
+use axum::response::IntoResponse;
+use axum::routing::{get, Route};
+use axum::Router;
+use std::convert::Infallible;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use tower::{Layer, Service, ServiceBuilder};
 use tower_http::{
-    catch_panic::CatchPanicLayer, compression::CompressionLayer,
-    sensitive_headers::SetSensitiveHeadersLayer, timeout::TimeoutLayer, trace, trace::TraceLayer,
+    catch_panic::CatchPanicLayer,
+    compression::CompressionLayer,
+    cors::{AllowOrigin, Any, CorsLayer},
+    sensitive_headers::SetSensitiveHeadersLayer,
+    timeout::TimeoutLayer,
+    trace,
+    trace::TraceLayer,
     ServiceBuilderExt,
 };
 use utoipa::OpenApi;
@@ -22,7 +52,7 @@ use crate::service::{
 
 use super::management::v1::ManagementApiDoc;
 
-#[allow(clippy::module_name_repetitions)]
+#[allow(clippy::module_name_repetitions, clippy::too_many_arguments)]
 pub fn new_full_router<
     CP: ConfigProvider<C>,
     C: Catalog,
@@ -33,70 +63,406 @@ pub fn new_full_router<
     auth_state: A::State,
     catalog_state: C::State,
     secrets_state: S::State,
+    stores: Stores,
     publisher: CloudEventsPublisher,
     table_change_checkers: ContractVerifiers,
+    commit_hooks: CommitHooks,
+    table_cache: TableCache,
+    api_usage: ApiUsageRecorder,
     token_verifier: Option<Verifier>,
+    runtime_config: RuntimeConfigHandle,
 ) -> Router {
-    let v1_routes = new_v1_full_router::<
-        crate::catalog::ConfigServer<CP, C, AH, A>,
-        crate::catalog::CatalogServer<C, A, S>,
-        State<A, C, S>,
-    >();
-    let management_routes = Router::new().merge(ApiServer::new_v1_router());
-
-    maybe_add_auth(
-        token_verifier,
-        Router::new()
-            .nest("/catalog/v1", v1_routes)
-            .nest("/management/v1", management_routes),
+    let builder = RouterBuilder::<CP, C, AH, A, S>::new(
+        auth_state,
+        catalog_state,
+        secrets_state,
+        stores,
+        publisher,
     )
-    .route("/health", get(|| async { "OK" }))
-    .merge(utoipa_swagger_ui::SwaggerUi::new("/swagger-ui").url(
-        "/api-docs/management/v1/openapi.json",
-        ManagementApiDoc::openapi(),
-    ))
-    .layer(axum::middleware::from_fn(
-        crate::request_metadata::create_request_metadata_with_trace_id_fn,
-    ))
-    .layer(
-        ServiceBuilder::new()
-            .set_x_request_id(MakeRequestUuid7)
-            .layer(SetSensitiveHeadersLayer::new([
-                axum::http::header::AUTHORIZATION,
-            ]))
-            .layer(CompressionLayer::new())
-            .layer(
-                TraceLayer::new_for_http()
-                    .on_failure(())
-                    .make_span_with(RestMakeSpan::new(tracing::Level::INFO))
-                    .on_response(trace::DefaultOnResponse::new().level(tracing::Level::DEBUG)),
-            )
-            .layer(TimeoutLayer::new(std::time::Duration::from_secs(30)))
-            .layer(CatchPanicLayer::new())
-            .propagate_x_request_id(),
-    )
-    .with_state(ApiContext {
-        v1_state: State {
-            auth: auth_state,
-            catalog: catalog_state,
-            secrets: secrets_state,
+    .with_contract_verifiers(table_change_checkers)
+    .with_commit_hooks(commit_hooks)
+    .with_table_cache(table_cache)
+    .with_api_usage(api_usage)
+    .with_runtime_config(runtime_config);
+    match token_verifier {
+        Some(token_verifier) => builder.with_token_verifier(token_verifier),
+        None => builder,
+    }
+    .build()
+}
+
+type ApiRouter<A, C, S> = Router<ApiContext<State<A, C, S>>>;
+
+/// Builder of the full router, for services that embed the catalog
+/// instead of running the provided binary.
+///
+/// ```ignore
+/// let router = RouterBuilder::<Catalog, Catalog, AllowAllAuthZHandler, AllowAllAuthZHandler, SecretsStore>::new(
+///     AllowAllAuthState,
+///     catalog_state,
+///     secrets_state,
+///     Stores::new(catalog_state.clone()),
+///     publisher,
+/// )
+/// .with_token_verifier(verifier)
+/// .with_layer(axum::middleware::from_fn(audit_middleware_fn))
+/// .with_routes(Router::new().route("/reports/v1/tables", get(list_table_reports)))
+/// .build();
+/// ```
+///
+/// Everything not set explicitly follows [`CONFIG`], like in the provided binary.
+/// Background jobs, such as the table garbage collector, are not part of the router
+/// and have to be spawned by the embedding service.
+#[allow(clippy::module_name_repetitions)]
+pub struct RouterBuilder<CP, C: Catalog, AH, A: AuthZHandler, S: SecretStore> {
+    auth_state: A::State,
+    catalog_state: C::State,
+    secrets_state: S::State,
+    stores: Stores,
+    publisher: CloudEventsPublisher,
+    contract_verifiers: ContractVerifiers,
+    commit_hooks: CommitHooks,
+    table_cache: TableCache,
+    api_usage: ApiUsageRecorder,
+    token_verifier: Option<Verifier>,
+    runtime_config: RuntimeConfigHandle,
+    request_metadata_extractors: RequestMetadataExtractors,
+    routes: ApiRouter<A, C, S>,
+    public_routes: ApiRouter<A, C, S>,
+    layers: Vec<Box<dyn FnOnce(ApiRouter<A, C, S>) -> ApiRouter<A, C, S> + Send>>,
+    phantom: PhantomData<fn() -> (CP, AH)>,
+}
+
+impl<
+        CP: ConfigProvider<C>,
+        C: Catalog,
+        AH: AuthConfigHandler<A>,
+        A: AuthZHandler,
+        S: SecretStore,
+    > RouterBuilder<CP, C, AH, A, S>
+{
+    #[must_use]
+    pub fn new(
+        auth_state: A::State,
+        catalog_state: C::State,
+        secrets_state: S::State,
+        stores: Stores,
+        publisher: CloudEventsPublisher,
+    ) -> Self {
+        Self {
+            auth_state,
+            catalog_state,
+            secrets_state,
+            stores,
+            publisher,
+            contract_verifiers: ContractVerifiers::new(vec![]),
+            commit_hooks: CommitHooks::default(),
+            table_cache: TableCache::new(
+                CONFIG.table_cache_capacity,
+                std::time::Duration::from_secs(CONFIG.resolution_cache_ttl_seconds),
+            ),
+            api_usage: ApiUsageRecorder::new(),
+            token_verifier: None,
+            runtime_config: RuntimeConfigHandle::new(
+                MaintenanceMode::new(
+                    CONFIG.maintenance_mode,
+                    CONFIG.maintenance_retry_after_seconds,
+                ),
+                None,
+            ),
+            request_metadata_extractors: RequestMetadataExtractors::from_config(),
+            routes: Router::new(),
+            public_routes: Router::new(),
+            layers: vec![],
+            phantom: PhantomData,
+        }
+    }
+
+    #[must_use]
+    pub fn with_contract_verifiers(mut self, contract_verifiers: ContractVerifiers) -> Self {
+        self.contract_verifiers = contract_verifiers;
+        self
+    }
+
+    #[must_use]
+    pub fn with_commit_hooks(mut self, commit_hooks: CommitHooks) -> Self {
+        self.commit_hooks = commit_hooks;
+        self
+    }
+
+    #[must_use]
+    pub fn with_table_cache(mut self, table_cache: TableCache) -> Self {
+        self.table_cache = table_cache;
+        self
+    }
+
+    /// Recorder of the API usage. It must be flushed by the embedding service,
+    /// see [`ApiUsageRecorder::run`].
+    #[must_use]
+    pub fn with_api_usage(mut self, api_usage: ApiUsageRecorder) -> Self {
+        self.api_usage = api_usage;
+        self
+    }
+
+    /// Require a valid token for all API routes, including the ones added with
+    /// [`Self::with_routes`]. Without a verifier, requests are not authenticated.
+    #[must_use]
+    pub fn with_token_verifier(mut self, token_verifier: Verifier) -> Self {
+        self.token_verifier = Some(token_verifier);
+        self
+    }
+
+    #[must_use]
+    pub fn with_runtime_config(mut self, runtime_config: RuntimeConfigHandle) -> Self {
+        self.runtime_config = runtime_config;
+        self
+    }
+
+    /// Add `extractor` after the extractors configured with `tenant_header` and
+    /// `region_affinity_header`. Its values are available in the
+    /// [`RequestMetadata`](crate::request_metadata::RequestMetadata) of every request.
+    #[must_use]
+    pub fn with_request_metadata_extractor(
+        mut self,
+        extractor: Arc<dyn RequestMetadataExtractor>,
+    ) -> Self {
+        self.request_metadata_extractors = self.request_metadata_extractors.with(extractor);
+        self
+    }
+
+    /// Mount additional routes next to `/catalog/v1` and `/management/v1`.
+    /// They are authenticated like the catalog and have access to the [`ApiContext`]
+    /// and the [`RequestMetadata`](crate::request_metadata::RequestMetadata) extension.
+    #[must_use]
+    pub fn with_routes(mut self, routes: ApiRouter<A, C, S>) -> Self {
+        self.routes = self.routes.merge(routes);
+        self
+    }
+
+    /// Mount additional routes that are served without authentication, like `/health`.
+    #[must_use]
+    pub fn with_public_routes(mut self, routes: ApiRouter<A, C, S>) -> Self {
+        self.public_routes = self.public_routes.merge(routes);
+        self
+    }
+
+    /// Wrap all API routes in `layer`. Layers run after authentication in the order
+    /// they are added, so they can inspect the principal of the request.
+    #[must_use]
+    pub fn with_layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<Route> + Clone + Send + 'static,
+        L::Service: Service<Request> + Clone + Send + 'static,
+        <L::Service as Service<Request>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Request>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+    {
+        self.layers
+            .push(Box::new(move |router| router.layer(layer)));
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> Router {
+        let Self {
+            auth_state,
+            catalog_state,
+            secrets_state,
+            stores,
             publisher,
-            contract_verifiers: table_change_checkers,
-        },
-    })
+            contract_verifiers,
+            commit_hooks,
+            table_cache,
+            api_usage,
+            token_verifier,
+            runtime_config,
+            request_metadata_extractors,
+            routes,
+            public_routes,
+            layers,
+            phantom: _,
+        } = self;
+
+        let v1_routes = new_v1_full_router::<
+            crate::catalog::ConfigServer<CP, C, AH, A>,
+            crate::catalog::CatalogServer<C, A, S>,
+            State<A, C, S>,
+        >();
+        let management_routes = Router::new().merge(ApiServer::new_v1_router());
+        #[cfg(feature = "graphql")]
+        let management_routes = if CONFIG.graphql_enabled {
+            management_routes.merge(crate::api::graphql::new_router::<C, A, S>())
+        } else {
+            management_routes
+        };
+        let revocations = RevocationList::new(
+            stores.revocations.clone(),
+            std::time::Duration::from_secs(CONFIG.token_revocation_cache_ttl_seconds),
+        );
+        let maintenance = runtime_config.maintenance().clone();
+        let resolution_cache = ResolutionCache::new(std::time::Duration::from_secs(
+            CONFIG.resolution_cache_ttl_seconds,
+        ));
+
+        // Catalog tokens need the token verifier even without an identity provider
+        let token_verifier =
+            token_verifier.or_else(|| catalog_tokens_enabled().then(Verifier::catalog_tokens_only));
+        let token_verifier = token_verifier.map(|verifier| {
+            let verifier = verifier.with_revocations(revocations.clone());
+            let verifier = match CONFIG.openid_audience.clone() {
+                Some(audience) => verifier.with_audience(audience),
+                None => verifier,
+            };
+            let sigv4 = SigV4Verifier::new(CONFIG.sigv4_access_keys.clone());
+            match CONFIG.sigv4_session_resolver_url.clone() {
+                Some(url) => verifier.with_sigv4(
+                    sigv4.with_session_resolver(Arc::new(HttpSessionCredentialsResolver::new(url))),
+                ),
+                None if CONFIG.sigv4_access_keys.is_empty() => verifier,
+                None => verifier.with_sigv4(sigv4),
+            }
+        });
+
+        let hmac_verifier = CONFIG
+            .hmac_auth_enabled
+            .then(|| HmacVerifier::<S>::new(secrets_state.clone(), token_verifier.is_none()));
+
+        let api_routes = layers.into_iter().fold(
+            Router::new()
+                .nest("/catalog/v1", v1_routes)
+                .nest("/management/v1", management_routes)
+                .merge(routes),
+            |router, layer| layer(router),
+        );
+
+        maybe_add_auth(
+            token_verifier.clone(),
+            hmac_verifier,
+            // Runs after authentication, so that unauthenticated requests are rejected first
+            api_routes.layer(axum::middleware::from_fn_with_state(
+                maintenance.clone(),
+                maintenance_middleware_fn,
+            )),
+        )
+        // Outside of authentication, so that rejected requests are counted as well
+        .layer(axum::middleware::from_fn_with_state(
+            api_usage,
+            api_usage_middleware_fn,
+        ))
+        .route("/health", get(|| async { "OK" }))
+        // Clients obtain their token here, so it can't require one
+        .nest(
+            "/catalog/v1",
+            new_v1_oauth_router::<crate::catalog::CatalogServer<C, A, S>, State<A, C, S>>(),
+        )
+        .merge(public_routes)
+        .merge(maybe_ui_router())
+        .merge(utoipa_swagger_ui::SwaggerUi::new("/swagger-ui").url(
+            "/api-docs/management/v1/openapi.json",
+            ManagementApiDoc::openapi(),
+        ))
+        // Answers preflight requests before they reach authentication
+        .layer(cors_layer(runtime_config.cors_origins().clone()))
+        // Inside of the request metadata, so that rejections contain the request id
+        .layer(axum::middleware::from_fn_with_state(
+            SecurityPolicy::from_config(),
+            security_middleware_fn,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            request_metadata_extractors,
+            crate::request_metadata::create_request_metadata_with_trace_id_fn,
+        ))
+        .layer(
+            ServiceBuilder::new()
+                .set_x_request_id(MakeRequestUuid7)
+                .layer(SetSensitiveHeadersLayer::new([
+                    axum::http::header::AUTHORIZATION,
+                    axum::http::header::PROXY_AUTHORIZATION,
+                    axum::http::header::COOKIE,
+                    axum::http::header::SET_COOKIE,
+                    axum::http::HeaderName::from_static("x-amz-security-token"),
+                ]))
+                .layer(CompressionLayer::new())
+                .layer(
+                    TraceLayer::new_for_http()
+                        .on_failure(())
+                        .make_span_with(RestMakeSpan::new(tracing::Level::INFO))
+                        .on_response(trace::DefaultOnResponse::new().level(tracing::Level::DEBUG)),
+                )
+                .layer(TimeoutLayer::new(std::time::Duration::from_secs(30)))
+                .layer(CatchPanicLayer::new())
+                .propagate_x_request_id(),
+        )
+        .with_state(ApiContext {
+            v1_state: State {
+                auth: auth_state,
+                catalog: catalog_state,
+                secrets: secrets_state,
+                stores,
+                publisher,
+                contract_verifiers,
+                commit_hooks,
+                revocations,
+                token_verifier,
+                maintenance,
+                runtime_config,
+                resolution_cache,
+                table_cache,
+                commit_queue: CommitQueue::new(CONFIG.commit_queue_capacity),
+                recent_writes: RecentWrites::new(std::time::Duration::from_secs(
+                    CONFIG.read_after_write_window_seconds,
+                )),
+            },
+        })
+    }
+}
+
+/// Origins are checked per request, so that changes by a reload apply immediately.
+fn cors_layer(origins: CorsOrigins) -> CorsLayer {
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::predicate(move |origin, _| {
+            origins.allows(origin)
+        }))
+        .allow_methods(Any)
+        .allow_headers(Any)
+}
+
+#[cfg(feature = "ui")]
+fn maybe_ui_router<S: Clone + Send + Sync + 'static>() -> Router<S> {
+    if CONFIG.ui_enabled {
+        crate::api::ui::new_router()
+    } else {
+        Router::new()
+    }
+}
+
+#[cfg(not(feature = "ui"))]
+fn maybe_ui_router<S: Clone + Send + Sync + 'static>() -> Router<S> {
+    Router::new()
 }
 
 fn maybe_add_auth<C: Catalog, A: AuthZHandler, S: SecretStore>(
     token_verifier: Option<Verifier>,
+    hmac_verifier: Option<HmacVerifier<S>>,
     router: Router<ApiContext<State<A, C, S>>>,
 ) -> Router<ApiContext<State<A, C, S>>> {
-    if let Some(token_verifier) = token_verifier {
+    let router = if let Some(token_verifier) = token_verifier {
         router.layer(axum::middleware::from_fn_with_state(
             token_verifier,
             crate::service::token_verification::auth_middleware_fn,
         ))
     } else {
         router
+    };
+
+    // Runs before token verification, which skips requests authenticated by a service key
+    if let Some(hmac_verifier) = hmac_verifier {
+        router.layer(axum::middleware::from_fn_with_state(
+            hmac_verifier,
+            hmac_auth_middleware_fn::<S>,
+        ))
+    } else {
+        router
     }
 }
 
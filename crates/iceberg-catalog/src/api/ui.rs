@@ -0,0 +1,30 @@
+//! Embedded admin UI.
+//!
+//! A single static page that browses warehouses, namespaces, tables and staged
+//! tables through the REST and management APIs. The page itself is served
+//! without authentication; the token entered by the user is sent with each
+//! API request, so the usual authorization applies.
+use axum::http::header;
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use axum::Router;
+
+const INDEX_HTML: &str = include_str!("ui/index.html");
+
+/// Router serving the admin UI at `/ui`.
+pub fn new_router<S: Clone + Send + Sync + 'static>() -> Router<S> {
+    Router::new().route("/ui", get(index))
+}
+
+async fn index() -> impl IntoResponse {
+    (
+        [
+            (header::CACHE_CONTROL, "no-cache"),
+            (
+                header::CONTENT_SECURITY_POLICY,
+                "default-src 'self'; script-src 'unsafe-inline'; style-src 'unsafe-inline'",
+            ),
+        ],
+        Html(INDEX_HTML),
+    )
+}
@@ -58,9 +58,15 @@ impl<
             return Err(e);
         }
 
-        let (project_from_arg, warehouse_from_arg) = query
-            .warehouse
-            .map_or((None, None), |arg| parse_warehouse_arg(&arg));
+        let (project_from_arg, warehouse_from_arg) = match query.warehouse {
+            Some(arg) => {
+                resolve_warehouse_arg::<D>(&arg, api_context.v1_state.catalog.clone()).await?
+            }
+            None => (None, None),
+        };
+
+        // Project selected by a principal belonging to multiple projects
+        let project_from_arg = project_from_arg.or_else(|| request_metadata.project_id.clone());
 
         if let Some(project_from_arg) = &project_from_arg {
             // This is a user-provided project-id, so we need to check if the user is allowed to access it
@@ -104,6 +110,9 @@ impl<
             })?
         };
 
+        super::require_warehouse_access(&request_metadata, &warehouse_id, &api_context.v1_state)
+            .await?;
+
         T::check_user_get_config_for_warehouse(
             api_context.v1_state.auth.clone(),
             &warehouse_id,
@@ -111,6 +120,14 @@ impl<
         )
         .await?;
 
+        // Every client session starts with a config request, so this is where usage is counted.
+        let client = request_metadata.client_info();
+        let usage = api_context
+            .v1_state
+            .stores
+            .client_usage
+            .record_client_usage(&warehouse_id, &client);
+
         // Get config from DB and new token from AuthHandler simultaneously
         let config = C::get_config_for_warehouse(&warehouse_id, api_context.v1_state.catalog);
 
@@ -122,7 +139,14 @@ impl<
             &warehouse_id,
         );
 
-        let (config, new_token) = futures::join!(config, new_token);
+        let (config, new_token, usage) = futures::join!(config, new_token, usage);
+        if let Err(e) = usage {
+            // Usage statistics are best-effort and must not break clients.
+            tracing::warn!(
+                "Failed to record usage of client {client:?} for warehouse {warehouse_id}: {:?}",
+                e.error
+            );
+        }
         let new_token = new_token?;
         let mut config = config?;
 
@@ -142,6 +166,29 @@ impl<
     }
 }
 
+/// Like [`parse_warehouse_arg`], but the project may also be given by its name.
+/// As warehouse names may contain `/`, a prefix is only taken as project name
+/// if a project with this name exists.
+async fn resolve_warehouse_arg<D: Catalog>(
+    arg: &str,
+    catalog_state: D::State,
+) -> Result<(Option<ProjectIdent>, Option<String>)> {
+    let (project_id, warehouse_name) = parse_warehouse_arg(arg);
+    if project_id.is_some() {
+        return Ok((project_id, warehouse_name));
+    }
+
+    if let Some((project_name, name)) = arg.split_once('/') {
+        if !name.is_empty() {
+            if let Some(project_id) = D::get_project_id_by_name(project_name, catalog_state).await?
+            {
+                return Ok((Some(project_id), Some(name.to_string())));
+            }
+        }
+    }
+    Ok((project_id, warehouse_name))
+}
+
 fn parse_warehouse_arg(arg: &str) -> (Option<ProjectIdent>, Option<String>) {
     // structure of the argument is <(optional uuid project_id)>/<warehouse_name which might include />
     fn filter_empty_strings(s: String) -> Option<String> {
@@ -1,21 +1,73 @@
 use crate::api::iceberg::v1::{ApiContext, Result, TableParameters};
 use crate::request_metadata::RequestMetadata;
 
-use crate::service::{auth::AuthZHandler, secrets::SecretStore, Catalog, State};
+use crate::service::metrics::{record_metrics_report, MetricsReport};
+use crate::service::{
+    auth::{AccessedObject, AuthZHandler},
+    secrets::SecretStore,
+    Catalog, State,
+};
 
-use super::CatalogServer;
+use super::{normalize_table, require_warehouse_access, require_warehouse_id, CatalogServer};
 
 #[async_trait::async_trait]
 impl<C: Catalog, A: AuthZHandler, S: SecretStore>
     crate::api::iceberg::v1::metrics::Service<State<A, C, S>> for CatalogServer<C, A, S>
 {
     async fn report_metrics(
-        _: TableParameters,
-        _: serde_json::Value,
-        _: ApiContext<State<A, C, S>>,
-        _: RequestMetadata,
+        parameters: TableParameters,
+        request: serde_json::Value,
+        state: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
     ) -> Result<()> {
-        // ToDo: implement
+        // ------------------- VALIDATIONS -------------------
+        let TableParameters { prefix, table } = parameters;
+        let warehouse_id = require_warehouse_id(prefix)?;
+        let table = normalize_table(&warehouse_id, table, &state.v1_state).await?;
+
+        // ------------------- AUTHZ -------------------
+        let unauthorized =
+            require_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state).await?;
+        let table_id = state
+            .v1_state
+            .table_cache
+            .table_id::<C>(&warehouse_id, &table, state.v1_state.catalog.clone())
+            .await
+            // We can't fail before AuthZ.
+            .ok()
+            .flatten();
+
+        A::check_load_table(
+            &request_metadata,
+            &warehouse_id,
+            Some(&table.namespace),
+            table_id.as_ref(),
+            state.v1_state.auth,
+        )
+        .await
+        .map_err(|e| unauthorized.apply(e, AccessedObject::Table))?;
+
+        // ------------------- BUSINESS LOGIC -------------------
+        // Reports are not stored. Reports of unknown tables are dropped,
+        // so that the number of `table` label values is bounded by the number of tables.
+        if table_id.is_none() {
+            return Ok(());
+        }
+        // Engines must not fail because of a report, so unsupported reports are ignored.
+        match serde_json::from_value::<MetricsReport>(request) {
+            Ok(report) => {
+                record_metrics_report::<C>(
+                    &warehouse_id,
+                    &table,
+                    &report,
+                    &state.v1_state.resolution_cache,
+                    state.v1_state.catalog,
+                )
+                .await;
+            }
+            Err(e) => tracing::debug!("Ignoring unsupported metrics report: {e}"),
+        }
+
         Ok(())
     }
 }
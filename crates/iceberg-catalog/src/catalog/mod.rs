@@ -2,6 +2,7 @@ mod config;
 pub(crate) mod io;
 mod metrics;
 pub(crate) mod namespace;
+mod oauth;
 #[cfg(feature = "s3-signer")]
 mod s3_signer;
 mod tables;
@@ -11,9 +12,15 @@ pub use config::Server as ConfigServer;
 pub use namespace::{MAX_NAMESPACE_DEPTH, UNSUPPORTED_NAMESPACE_PROPERTIES};
 
 use crate::api::{iceberg::v1::Prefix, ErrorModel, Result};
+use crate::request_metadata::RequestMetadata;
 use crate::{
-    service::{auth::AuthZHandler, secrets::SecretStore, Catalog},
-    WarehouseIdent,
+    service::{
+        auth::{AccessedObject, AuthZHandler, UnauthorizedResponse},
+        secrets::SecretStore,
+        Catalog, NamespaceIdent, ObjectOwnership, OwnedObject, PyicebergCompat, State, TableIdent,
+        Transaction as _,
+    },
+    WarehouseIdent, CONFIG,
 };
 use std::marker::PhantomData;
 
@@ -25,6 +32,173 @@ pub struct CatalogServer<C: Catalog, A: AuthZHandler, S: SecretStore> {
     secret_store: PhantomData<S>,
 }
 
+/// Reject the request if the warehouse has an allowlist that
+/// neither contains the principal nor any of its groups.
+/// This check runs before the `AuthZHandler` is consulted.
+///
+/// Returns the response of the warehouse to unauthorized access,
+/// which is applied to the errors of subsequent `AuthZHandler` checks.
+async fn require_warehouse_access<A: AuthZHandler, C: Catalog, S: SecretStore>(
+    metadata: &RequestMetadata,
+    warehouse_id: &WarehouseIdent,
+    state: &State<A, C, S>,
+) -> Result<UnauthorizedResponse> {
+    metadata.require_token_warehouse(warehouse_id, false)?;
+    check_warehouse_allowlist(metadata, warehouse_id, state).await
+}
+
+/// Like [`require_warehouse_access`] for operations on a single table, which
+/// also accept tokens downscoped to a table. The caller must check the table
+/// with [`RequestMetadata::require_token_table`].
+async fn require_table_warehouse_access<A: AuthZHandler, C: Catalog, S: SecretStore>(
+    metadata: &RequestMetadata,
+    warehouse_id: &WarehouseIdent,
+    state: &State<A, C, S>,
+) -> Result<UnauthorizedResponse> {
+    metadata.require_token_warehouse(warehouse_id, true)?;
+    check_warehouse_allowlist(metadata, warehouse_id, state).await
+}
+
+async fn check_warehouse_allowlist<A: AuthZHandler, C: Catalog, S: SecretStore>(
+    metadata: &RequestMetadata,
+    warehouse_id: &WarehouseIdent,
+    state: &State<A, C, S>,
+) -> Result<UnauthorizedResponse> {
+    let warehouse = state
+        .resolution_cache
+        .warehouse::<C>(warehouse_id, state.catalog.clone())
+        .await?;
+    let unauthorized_response = warehouse
+        .as_ref()
+        .map_or(CONFIG.unauthorized_response, |w| {
+            w.effective_unauthorized_response()
+        });
+    let Some(allowlist) = warehouse.and_then(|w| w.allowlist.clone()) else {
+        return Ok(unauthorized_response);
+    };
+
+    if allowlist.allows(metadata.principal(), &metadata.groups()) {
+        Ok(unauthorized_response)
+    } else {
+        Err(unauthorized_response.apply(
+            ErrorModel::builder()
+                .code(http::StatusCode::FORBIDDEN.into())
+                .message("Access to this warehouse is not allowed.".to_string())
+                .r#type("WarehouseAccessDenied".to_string())
+                .build()
+                .into(),
+            AccessedObject::Warehouse,
+        ))
+    }
+}
+
+/// Reject mutating requests if the warehouse is in read-only mode.
+pub(crate) async fn require_writable_warehouse<A: AuthZHandler, C: Catalog, S: SecretStore>(
+    warehouse_id: &WarehouseIdent,
+    state: &State<A, C, S>,
+) -> Result<()> {
+    let read_only = state
+        .resolution_cache
+        .warehouse::<C>(warehouse_id, state.catalog.clone())
+        .await?
+        .is_some_and(|w| w.read_only);
+    if read_only {
+        Err(ErrorModel::builder()
+            .code(http::StatusCode::FORBIDDEN.into())
+            .message("Warehouse is in read-only mode.".to_string())
+            .r#type("WarehouseReadOnly".to_string())
+            .build()
+            .into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Owner and creator of `object`, passed to the `AuthZHandler` checks of
+/// operations that drop or alter it. `None` if the object does not exist.
+pub(crate) async fn resolve_ownership<C: Catalog>(
+    warehouse_id: &WarehouseIdent,
+    object: OwnedObject,
+    catalog_state: C::State,
+) -> Option<ObjectOwnership> {
+    // We can't fail before AuthZ.
+    let mut transaction = C::Transaction::begin_read(catalog_state).await.ok()?;
+    C::get_ownership(warehouse_id, &object, transaction.transaction())
+        .await
+        .ok()
+}
+
+/// Pyiceberg compatibility settings of the warehouse, served from the resolution cache.
+async fn get_pyiceberg_compat<A: AuthZHandler, C: Catalog, S: SecretStore>(
+    warehouse_id: &WarehouseIdent,
+    state: &State<A, C, S>,
+) -> Result<Option<PyicebergCompat>> {
+    Ok(state
+        .resolution_cache
+        .warehouse::<C>(warehouse_id, state.catalog.clone())
+        .await?
+        .and_then(|w| w.pyiceberg_compat_mode()))
+}
+
+/// Undo namespace deviations of older pyiceberg clients
+/// if the warehouse is in pyiceberg compatibility mode.
+async fn normalize_namespace<A: AuthZHandler, C: Catalog, S: SecretStore>(
+    warehouse_id: &WarehouseIdent,
+    namespace: NamespaceIdent,
+    state: &State<A, C, S>,
+) -> Result<NamespaceIdent> {
+    let compat = get_pyiceberg_compat(warehouse_id, state).await?;
+    resolve_compat_namespace(compat.as_ref(), warehouse_id, namespace, state).await
+}
+
+async fn normalize_table<A: AuthZHandler, C: Catalog, S: SecretStore>(
+    warehouse_id: &WarehouseIdent,
+    table: TableIdent,
+    state: &State<A, C, S>,
+) -> Result<TableIdent> {
+    let TableIdent { namespace, name } = table;
+    Ok(TableIdent::new(
+        normalize_namespace(warehouse_id, namespace, state).await?,
+        name,
+    ))
+}
+
+/// The warehouse name is stripped if the client prepended it.
+/// If the remaining namespace does not exist but the one with the warehouse
+/// name as first segment does, the client stripped it and it is restored.
+async fn resolve_compat_namespace<A: AuthZHandler, C: Catalog, S: SecretStore>(
+    compat: Option<&PyicebergCompat>,
+    warehouse_id: &WarehouseIdent,
+    namespace: NamespaceIdent,
+    state: &State<A, C, S>,
+) -> Result<NamespaceIdent> {
+    let Some(compat) = compat else {
+        return Ok(namespace);
+    };
+
+    let namespace = compat.strip_warehouse_name(namespace);
+    if state
+        .resolution_cache
+        .namespace_id::<C>(warehouse_id, &namespace, state.catalog.clone())
+        .await?
+        .is_some()
+    {
+        return Ok(namespace);
+    }
+
+    let prepended = compat.prepend_warehouse_name(&namespace);
+    if state
+        .resolution_cache
+        .namespace_id::<C>(warehouse_id, &prepended, state.catalog.clone())
+        .await?
+        .is_some()
+    {
+        Ok(prepended)
+    } else {
+        Ok(namespace)
+    }
+}
+
 fn require_warehouse_id(prefix: Option<Prefix>) -> Result<WarehouseIdent> {
     prefix
         .ok_or(
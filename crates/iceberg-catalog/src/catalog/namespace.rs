@@ -1,16 +1,26 @@
 use crate::api::iceberg::v1::{
-    ApiContext, CreateNamespaceRequest, CreateNamespaceResponse, ErrorModel, GetNamespaceResponse,
-    ListNamespacesQuery, ListNamespacesResponse, NamespaceParameters, Prefix, Result,
-    UpdateNamespacePropertiesRequest, UpdateNamespacePropertiesResponse,
+    ApiContext, CreateNamespaceRequest, CreateNamespaceResponse, ErrorModel, ErrorType,
+    GetNamespaceResponse, ListNamespacesQuery, ListNamespacesResponse, NamespaceParameters, Prefix,
+    Result, UpdateNamespacePropertiesRequest, UpdateNamespacePropertiesResponse,
 };
 use crate::request_metadata::RequestMetadata;
-use crate::CONFIG;
+use crate::service::event_publisher::{
+    CloudEventsPublisher, EventMetadata, EventSubject, EventType,
+};
+use crate::service::storage::AccessDelegation;
+use crate::{WarehouseIdent, CONFIG};
 use http::StatusCode;
 use iceberg::NamespaceIdent;
 
-use super::{require_warehouse_id, CatalogServer};
+use super::tables::maybe_body_to_json;
+use super::{
+    get_pyiceberg_compat, normalize_namespace, require_warehouse_access, require_warehouse_id,
+    require_writable_warehouse, resolve_ownership, CatalogServer,
+};
 use crate::service::{
-    auth::AuthZHandler, secrets::SecretStore, Catalog, NamespaceIdentExt, State, Transaction as _,
+    auth::{AccessedObject, AuthZHandler},
+    secrets::SecretStore,
+    Catalog, NamespaceIdentExt, OwnedObject, State, Transaction as _,
 };
 
 pub const UNSUPPORTED_NAMESPACE_PROPERTIES: &[&str] = &["location"];
@@ -24,12 +34,15 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
 {
     async fn list_namespaces(
         prefix: Option<Prefix>,
-        query: ListNamespacesQuery,
+        mut query: ListNamespacesQuery,
         state: ApiContext<State<A, C, S>>,
         request_metadata: RequestMetadata,
     ) -> Result<ListNamespacesResponse> {
         // ------------------- VALIDATIONS -------------------
         let warehouse_id = require_warehouse_id(prefix)?;
+        if let Some(parent) = query.parent.take() {
+            query.parent = Some(normalize_namespace(&warehouse_id, parent, &state.v1_state).await?);
+        }
         let ListNamespacesQuery {
             page_token: _,
             page_size: _,
@@ -38,13 +51,16 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         parent.as_ref().map(validate_namespace_ident).transpose()?;
 
         // ------------------- AUTHZ -------------------
+        let unauthorized =
+            require_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state).await?;
         A::check_list_namespace(
             &request_metadata,
             &warehouse_id,
             query.parent.as_ref(),
             state.v1_state.auth,
         )
-        .await?;
+        .await
+        .map_err(|e| unauthorized.apply(e, AccessedObject::Namespace))?;
 
         // ------------------- BUSINESS LOGIC -------------------
         C::list_namespaces(&warehouse_id, &query, state.v1_state.catalog).await
@@ -52,12 +68,16 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
 
     async fn create_namespace(
         prefix: Option<Prefix>,
-        request: CreateNamespaceRequest,
+        mut request: CreateNamespaceRequest,
         state: ApiContext<State<A, C, S>>,
         request_metadata: RequestMetadata,
     ) -> Result<CreateNamespaceResponse> {
         // ------------------- VALIDATIONS -------------------
         let warehouse_id = require_warehouse_id(prefix)?;
+        // The namespace does not exist yet, so only a prepended warehouse name can be detected.
+        if let Some(compat) = get_pyiceberg_compat(&warehouse_id, &state.v1_state).await? {
+            request.namespace = compat.strip_warehouse_name(request.namespace);
+        }
         let CreateNamespaceRequest {
             namespace,
             properties,
@@ -67,6 +87,10 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             .as_ref()
             .map(|p| validate_namespace_properties(p.keys()))
             .transpose()?;
+        properties
+            .as_ref()
+            .map(AccessDelegation::validate_properties)
+            .transpose()?;
 
         if CONFIG
             .reserved_namespaces
@@ -81,39 +105,64 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         }
 
         // ------------------- AUTHZ -------------------
+        let unauthorized =
+            require_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state).await?;
         A::check_create_namespace(
             &request_metadata,
             &warehouse_id,
             request.namespace.parent().as_ref(),
-            state.v1_state.auth,
+            state.v1_state.auth.clone(),
         )
-        .await?;
+        .await
+        .map_err(|e| unauthorized.apply(e, AccessedObject::Namespace))?;
 
         // ------------------- BUSINESS LOGIC -------------------
+        require_writable_warehouse(&warehouse_id, &state.v1_state).await?;
         let mut t = C::Transaction::begin_write(state.v1_state.catalog).await?;
-        let r = C::create_namespace(&warehouse_id, request, t.transaction()).await?;
+        let r = C::create_namespace(
+            &warehouse_id,
+            request,
+            request_metadata.principal(),
+            t.transaction(),
+        )
+        .await?;
         t.commit().await?;
+
+        emit_namespace_event(
+            &warehouse_id,
+            &r.namespace,
+            EventType::NamespaceCreated,
+            maybe_body_to_json(&r),
+            &request_metadata,
+            state.v1_state.publisher,
+        )
+        .await;
         Ok(r)
     }
 
     /// Return all stored metadata properties for a given namespace
     async fn load_namespace_metadata(
-        parameters: NamespaceParameters,
+        mut parameters: NamespaceParameters,
         state: ApiContext<State<A, C, S>>,
         request_metadata: RequestMetadata,
     ) -> Result<GetNamespaceResponse> {
         // ------------------- VALIDATIONS -------------------
         let warehouse_id = require_warehouse_id(parameters.prefix)?;
+        parameters.namespace =
+            normalize_namespace(&warehouse_id, parameters.namespace, &state.v1_state).await?;
         validate_namespace_ident(&parameters.namespace)?;
 
         // ------------------- AUTHZ -------------------
+        let unauthorized =
+            require_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state).await?;
         A::check_load_namespace_metadata(
             &request_metadata,
             &warehouse_id,
             &parameters.namespace,
             state.v1_state.auth,
         )
-        .await?;
+        .await
+        .map_err(|e| unauthorized.apply(e, AccessedObject::Namespace))?;
 
         // ------------------- BUSINESS LOGIC -------------------
         let mut t = C::Transaction::begin_write(state.v1_state.catalog).await?;
@@ -127,25 +176,37 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
 
     /// Check if a namespace exists
     async fn namespace_exists(
-        parameters: NamespaceParameters,
+        mut parameters: NamespaceParameters,
         state: ApiContext<State<A, C, S>>,
         request_metadata: RequestMetadata,
     ) -> Result<()> {
         //  ------------------- VALIDATIONS -------------------
         let warehouse_id = require_warehouse_id(parameters.prefix)?;
+        parameters.namespace =
+            normalize_namespace(&warehouse_id, parameters.namespace, &state.v1_state).await?;
         validate_namespace_ident(&parameters.namespace)?;
 
         //  ------------------- AUTHZ -------------------
+        let unauthorized =
+            require_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state).await?;
         A::check_namespace_exists(
             &request_metadata,
             &warehouse_id,
             &parameters.namespace,
             state.v1_state.auth,
         )
-        .await?;
+        .await
+        .map_err(|e| unauthorized.apply(e, AccessedObject::Namespace))?;
 
         //  ------------------- BUSINESS LOGIC -------------------
-        if C::namespace_ident_to_id(&warehouse_id, &parameters.namespace, state.v1_state.catalog)
+        if state
+            .v1_state
+            .resolution_cache
+            .namespace_id::<C>(
+                &warehouse_id,
+                &parameters.namespace,
+                state.v1_state.catalog.clone(),
+            )
             .await?
             .is_some()
         {
@@ -155,6 +216,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
                 .code(StatusCode::NOT_FOUND.into())
                 .message(format!("Namespace {:#?} not found.", parameters.namespace))
                 .r#type("NoSuchNamespaceException".to_string())
+                .error_type(ErrorType::NoSuchNamespace)
                 .build()
                 .into())
         }
@@ -162,12 +224,14 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
 
     /// Drop a namespace from the catalog. Namespace must be empty.
     async fn drop_namespace(
-        parameters: NamespaceParameters,
+        mut parameters: NamespaceParameters,
         state: ApiContext<State<A, C, S>>,
         request_metadata: RequestMetadata,
     ) -> Result<()> {
         //  ------------------- VALIDATIONS -------------------
         let warehouse_id = require_warehouse_id(parameters.prefix)?;
+        parameters.namespace =
+            normalize_namespace(&warehouse_id, parameters.namespace, &state.v1_state).await?;
         validate_namespace_ident(&parameters.namespace)?;
 
         if CONFIG
@@ -183,51 +247,94 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         }
 
         //  ------------------- AUTHZ -------------------
+        let unauthorized =
+            require_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state).await?;
+        let ownership = resolve_ownership::<C>(
+            &warehouse_id,
+            OwnedObject::Namespace(parameters.namespace.clone()),
+            state.v1_state.catalog.clone(),
+        )
+        .await;
         A::check_drop_namespace(
             &request_metadata,
             &warehouse_id,
             &parameters.namespace,
-            state.v1_state.auth,
+            ownership.as_ref(),
+            state.v1_state.auth.clone(),
         )
-        .await?;
+        .await
+        .map_err(|e| unauthorized.apply(e, AccessedObject::Namespace))?;
 
         //  ------------------- BUSINESS LOGIC -------------------
+        require_writable_warehouse(&warehouse_id, &state.v1_state).await?;
         let mut t = C::Transaction::begin_write(state.v1_state.catalog).await?;
         let r = C::drop_namespace(&warehouse_id, &parameters.namespace, t.transaction()).await?;
         t.commit().await?;
+        state
+            .v1_state
+            .resolution_cache
+            .invalidate_namespace(&warehouse_id, &parameters.namespace)
+            .await;
+
+        emit_namespace_event(
+            &warehouse_id,
+            &parameters.namespace,
+            EventType::NamespaceDropped,
+            serde_json::Value::Null,
+            &request_metadata,
+            state.v1_state.publisher,
+        )
+        .await;
         Ok(r)
     }
 
     /// Set or remove properties on a namespace
     async fn update_namespace_properties(
-        parameters: NamespaceParameters,
+        mut parameters: NamespaceParameters,
         request: UpdateNamespacePropertiesRequest,
         state: ApiContext<State<A, C, S>>,
         request_metadata: RequestMetadata,
     ) -> Result<UpdateNamespacePropertiesResponse> {
         //  ------------------- VALIDATIONS -------------------
         let warehouse_id = require_warehouse_id(parameters.prefix)?;
+        parameters.namespace =
+            normalize_namespace(&warehouse_id, parameters.namespace, &state.v1_state).await?;
         validate_namespace_ident(&parameters.namespace)?;
         let UpdateNamespacePropertiesRequest { removals, updates } = &request;
         updates
             .as_ref()
             .map(|p| validate_namespace_properties(p.keys()))
             .transpose()?;
+        updates
+            .as_ref()
+            .map(AccessDelegation::validate_properties)
+            .transpose()?;
         removals
             .as_ref()
             .map(validate_namespace_properties)
             .transpose()?;
 
         //  ------------------- AUTHZ -------------------
+        let unauthorized =
+            require_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state).await?;
+        let ownership = resolve_ownership::<C>(
+            &warehouse_id,
+            OwnedObject::Namespace(parameters.namespace.clone()),
+            state.v1_state.catalog.clone(),
+        )
+        .await;
         A::check_update_namespace_properties(
             &request_metadata,
             &warehouse_id,
             &parameters.namespace,
-            state.v1_state.auth,
+            ownership.as_ref(),
+            state.v1_state.auth.clone(),
         )
-        .await?;
+        .await
+        .map_err(|e| unauthorized.apply(e, AccessedObject::Namespace))?;
 
         //  ------------------- BUSINESS LOGIC -------------------
+        require_writable_warehouse(&warehouse_id, &state.v1_state).await?;
         let mut t = C::Transaction::begin_write(state.v1_state.catalog).await?;
         let r = C::update_namespace_properties(
             &warehouse_id,
@@ -237,10 +344,44 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         )
         .await?;
         t.commit().await?;
+
+        emit_namespace_event(
+            &warehouse_id,
+            &parameters.namespace,
+            EventType::NamespacePropertiesUpdated,
+            maybe_body_to_json(&r),
+            &request_metadata,
+            state.v1_state.publisher,
+        )
+        .await;
         Ok(r)
     }
 }
 
+async fn emit_namespace_event(
+    warehouse_id: &WarehouseIdent,
+    namespace: &NamespaceIdent,
+    typ: EventType,
+    body: serde_json::Value,
+    request_metadata: &RequestMetadata,
+    publisher: CloudEventsPublisher,
+) {
+    let _ = publisher
+        .publish(
+            uuid::Uuid::now_v7(),
+            typ,
+            body,
+            EventMetadata::single(
+                *warehouse_id.as_uuid(),
+                EventSubject::Namespace {
+                    namespace: namespace.encode_in_url(),
+                },
+                request_metadata,
+            ),
+        )
+        .await;
+}
+
 pub(crate) fn uppercase_first_letter(s: &str) -> String {
     let mut c = s.chars();
     match c.next() {
@@ -0,0 +1,287 @@
+use http::StatusCode;
+use iceberg_ext::catalog::rest::{
+    OAuthAccessTokenType, OAuthClientCredentialsRequest, OAuthError, OAuthErrorType,
+    OAuthTokenExchangeRequest, OAuthTokenRequest, OAuthTokenResponse, OAuthTokenType,
+};
+use serde::Deserialize;
+use url::Url;
+
+use super::CatalogServer;
+use crate::api::iceberg::v1::{ApiContext, ErrorModel, IcebergErrorResponse, Result};
+use crate::request_metadata::RequestMetadata;
+use crate::service::catalog_token::{
+    catalog_tokens_enabled, client_credentials_scopes, exchanged_scopes, mint_catalog_token,
+    mint_exchanged_token, TokenScope,
+};
+use crate::service::hmac_auth::ServiceKey;
+use crate::service::http_client::http_client;
+use crate::service::token_verification::Verifier;
+use crate::service::{auth::AuthZHandler, secrets::SecretStore, Catalog, SecretIdent, State};
+use crate::CONFIG;
+
+#[async_trait::async_trait]
+impl<C: Catalog, A: AuthZHandler, S: SecretStore>
+    crate::api::iceberg::v1::oauth::Service<State<A, C, S>> for CatalogServer<C, A, S>
+{
+    async fn get_token(
+        state: ApiContext<State<A, C, S>>,
+        _request_metadata: RequestMetadata,
+        request: OAuthTokenRequest,
+    ) -> Result<OAuthTokenResponse> {
+        match request {
+            OAuthTokenRequest::OAuthClientCredentialsRequest(request) => {
+                client_credentials::<S>(&request, state.v1_state.secrets).await
+            }
+            OAuthTokenRequest::OAuthTokenExchangeRequest(request) => {
+                token_exchange(&request, state.v1_state.token_verifier.as_ref()).await
+            }
+        }
+    }
+}
+
+/// Exchange a token for a catalog token, downscoped to the warehouse and table
+/// of the `warehouse:<id>` and `table:<id>` scopes of the request.
+async fn token_exchange(
+    request: &OAuthTokenExchangeRequest,
+    verifier: Option<&Verifier>,
+) -> Result<OAuthTokenResponse> {
+    let Some(verifier) = verifier.filter(|_| catalog_tokens_enabled()) else {
+        return Err(oauth_error(
+            StatusCode::BAD_REQUEST,
+            OAuthErrorType::UnsupportedGrantType,
+            "Token exchange requires catalog tokens to be enabled.",
+        ));
+    };
+    if !matches!(
+        request.subject_token_type,
+        OAuthTokenType::AccessToken | OAuthTokenType::Jwt
+    ) || request
+        .requested_token_type
+        .is_some_and(|t| t != OAuthTokenType::AccessToken)
+    {
+        return Err(oauth_error(
+            StatusCode::BAD_REQUEST,
+            OAuthErrorType::InvalidRequest,
+            "Only access tokens can be exchanged for access tokens.",
+        ));
+    }
+    if request.actor_token.is_some() {
+        return Err(oauth_error(
+            StatusCode::BAD_REQUEST,
+            OAuthErrorType::InvalidRequest,
+            "Actor tokens are not supported.",
+        ));
+    }
+
+    let subject = verifier.verify(&request.subject_token).await.map_err(|e| {
+        tracing::debug!("Failed to verify subject token: {:?}", e.error);
+        oauth_error(
+            StatusCode::BAD_REQUEST,
+            OAuthErrorType::InvalidGrant,
+            "Invalid subject token.",
+        )
+    })?;
+
+    let invalid_scope = |message: String| {
+        oauth_error(
+            StatusCode::BAD_REQUEST,
+            OAuthErrorType::InvalidScope,
+            &message,
+        )
+    };
+    let requested = request
+        .scope
+        .as_deref()
+        .unwrap_or_default()
+        .split_whitespace();
+    let (token_scope, requested) = TokenScope::from_claims(&subject)
+        .narrow(requested)
+        .map_err(invalid_scope)?;
+    let scopes = exchanged_scopes(&requested, &subject, &CONFIG.openid_scope_permissions)
+        .map_err(invalid_scope)?;
+    let scope = (!scopes.is_empty()).then(|| scopes.join(" "));
+
+    let token = mint_exchanged_token(&subject, scope.as_deref(), &token_scope)?
+        .ok_or_else(invalid_client)?;
+    let granted = scopes
+        .into_iter()
+        .chain(token_scope.scopes())
+        .collect::<Vec<_>>();
+
+    Ok(OAuthTokenResponse {
+        access_token: token.token,
+        token_type: OAuthAccessTokenType::Bearer,
+        expires_in: Some(token.expires_in),
+        issued_token_type: Some(OAuthTokenType::AccessToken),
+        refresh_token: None,
+        scope: (!granted.is_empty()).then(|| granted.join(" ")),
+    })
+}
+
+/// Issue a catalog token if the client is a service key, otherwise forward the
+/// request to the `oauth_token_endpoint` of the identity provider.
+async fn client_credentials<S: SecretStore>(
+    request: &OAuthClientCredentialsRequest,
+    secrets: S::State,
+) -> Result<OAuthTokenResponse> {
+    if let Some(key) = load_service_key::<S>(&request.client_id, secrets).await {
+        if key.hmac_secret.len() != request.client_secret.len()
+            || !openssl::memcmp::eq(key.hmac_secret.as_bytes(), request.client_secret.as_bytes())
+        {
+            return Err(invalid_client());
+        }
+        let requested = request
+            .scope
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect::<Vec<_>>();
+        let scopes = client_credentials_scopes(&requested, &CONFIG.openid_scope_permissions)
+            .map_err(|message| {
+                oauth_error(
+                    StatusCode::BAD_REQUEST,
+                    OAuthErrorType::InvalidScope,
+                    &message,
+                )
+            })?;
+        let scope = (!scopes.is_empty()).then(|| scopes.join(" "));
+        let token = mint_catalog_token(&key.principal, &request.client_id, scope.as_deref())?
+            .ok_or_else(invalid_client)?;
+
+        return Ok(OAuthTokenResponse {
+            access_token: token.token,
+            token_type: OAuthAccessTokenType::Bearer,
+            expires_in: Some(token.expires_in),
+            issued_token_type: Some(OAuthTokenType::AccessToken),
+            refresh_token: None,
+            scope,
+        });
+    }
+
+    // Tokens of the token endpoint can only be verified with an identity provider
+    match &CONFIG.oauth_token_endpoint {
+        Some(endpoint) if CONFIG.openid_provider_uri.is_some() => {
+            forward_client_credentials(endpoint, request).await
+        }
+        _ if catalog_tokens_enabled() => Err(invalid_client()),
+        _ => Err(oauth_error(
+            StatusCode::BAD_REQUEST,
+            OAuthErrorType::UnsupportedGrantType,
+            "Client credentials require catalog tokens or an identity provider.",
+        )),
+    }
+}
+
+/// The service key with id `client_id`, `None` if catalog tokens are disabled
+/// or the client is not a service key.
+async fn load_service_key<S: SecretStore>(
+    client_id: &str,
+    secrets: S::State,
+) -> Option<ServiceKey> {
+    if !catalog_tokens_enabled() {
+        return None;
+    }
+    let key_id = SecretIdent::from(uuid::Uuid::parse_str(client_id).ok()?);
+
+    match S::get_secret_by_id::<ServiceKey>(&key_id, secrets).await {
+        Ok(key) => Some(key.secret),
+        Err(e) => {
+            tracing::debug!("Client {client_id} is not a service key: {:?}", e.error);
+            None
+        }
+    }
+}
+
+/// Successful response of the token endpoint of the identity provider.
+/// Providers differ in the case of `token_type`, so it is not parsed.
+#[derive(Deserialize)]
+struct ProviderTokenResponse {
+    access_token: String,
+    #[serde(default)]
+    expires_in: Option<u64>,
+    #[serde(default)]
+    scope: Option<String>,
+}
+
+async fn forward_client_credentials(
+    endpoint: &Url,
+    request: &OAuthClientCredentialsRequest,
+) -> Result<OAuthTokenResponse> {
+    let mut form = vec![
+        ("grant_type", "client_credentials"),
+        ("client_id", request.client_id.as_str()),
+        ("client_secret", request.client_secret.as_str()),
+    ];
+    if let Some(scope) = &request.scope {
+        form.push(("scope", scope.as_str()));
+    }
+
+    let response = http_client()
+        .post(endpoint.clone())
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| provider_error(&e))?;
+    let status = response.status();
+
+    if status.is_success() {
+        let token = response
+            .json::<ProviderTokenResponse>()
+            .await
+            .map_err(|e| provider_error(&e))?;
+        Ok(OAuthTokenResponse {
+            access_token: token.access_token,
+            token_type: OAuthAccessTokenType::Bearer,
+            expires_in: token.expires_in,
+            issued_token_type: Some(OAuthTokenType::AccessToken),
+            refresh_token: None,
+            scope: token.scope,
+        })
+    } else if status.is_client_error() {
+        // Relay the error of the provider, e.g. `invalid_client` for wrong credentials
+        let error = response.json::<OAuthError>().await.ok();
+        Err(oauth_error(
+            status,
+            error
+                .as_ref()
+                .map_or(OAuthErrorType::InvalidClient, |e| e.error),
+            error
+                .and_then(|e| e.error_description)
+                .as_deref()
+                .unwrap_or("Identity provider rejected the client credentials."),
+        ))
+    } else {
+        Err(provider_error(&format!(
+            "Identity provider responded with status {status}"
+        )))
+    }
+}
+
+/// Error with an [`OAuthErrorType`] as type, which the token endpoint
+/// returns as [`OAuthError`].
+fn oauth_error(status: StatusCode, error: OAuthErrorType, message: &str) -> IcebergErrorResponse {
+    ErrorModel::builder()
+        .code(status.into())
+        .message(message.to_string())
+        .r#type(error.to_string())
+        .build()
+        .into()
+}
+
+fn invalid_client() -> IcebergErrorResponse {
+    oauth_error(
+        StatusCode::UNAUTHORIZED,
+        OAuthErrorType::InvalidClient,
+        "Invalid client credentials.",
+    )
+}
+
+fn provider_error(e: &impl std::fmt::Display) -> IcebergErrorResponse {
+    ErrorModel::builder()
+        .code(StatusCode::BAD_GATEWAY.into())
+        .message("Failed to obtain a token from the identity provider.".to_string())
+        .r#type("IdentityProviderError".to_string())
+        .stack(Some(vec![e.to_string()]))
+        .build()
+        .into()
+}
@@ -4,33 +4,139 @@ use std::time::SystemTime;
 use std::vec;
 
 use crate::api::iceberg::types::Prefix;
+use crate::api::iceberg::v1::DataAccess;
 use crate::api::{ApiContext, Result};
 use crate::api::{ErrorModel, IcebergErrorResponse, S3SignRequest, S3SignResponse};
 use aws_sigv4::http_request::{sign as aws_sign, SignableBody, SignableRequest, SigningSettings};
 use aws_sigv4::sign::v4;
 use aws_sigv4::{self};
+use quick_xml::events::Event;
 
 use super::CatalogServer;
-use crate::catalog::require_warehouse_id;
+use crate::catalog::{require_table_warehouse_access, require_warehouse_id, resolve_ownership};
 use crate::request_metadata::RequestMetadata;
+use crate::service::metrics::{count_warehouse_operation, WarehouseOperation};
 use crate::service::secrets::SecretStore;
-use crate::service::storage::{S3Profile, StorageCredential};
-use crate::service::{auth::AuthZHandler, Catalog, State};
-use crate::service::{GetTableMetadataResponse, TableIdentUuid};
+use crate::service::signer_token::SignerTokenClaims;
+use crate::service::storage::{AccessDelegation, S3Flavor, S3Profile, StorageCredential};
+use crate::service::token_verification::AuthDetails;
+use crate::service::{
+    auth::{AccessedObject, AuthZHandler, UnauthorizedResponse},
+    Catalog, State,
+};
+use crate::service::{GetTableMetadataResponse, ObjectOwnership, OwnedObject, TableIdentUuid};
 use crate::WarehouseIdent;
 
-const READ_METHODS: &[&str] = &["GET", "HEAD"];
-const WRITE_METHODS: &[&str] = &["PUT", "POST", "DELETE"];
 // Keep only the following headers:
-const HEADERS_TO_SIGN: [&str; 6] = [
+const HEADERS_TO_SIGN: [&str; 11] = [
     "amz-sdk-invocation-id",
     "amz-sdk-request",
     "content-length",
+    "content-md5",
     "content-type",
     "expect",
     "host",
+    // Sent by clients configured with `s3.sse.*` properties
+    "x-amz-server-side-encryption",
+    "x-amz-server-side-encryption-aws-kms-key-id",
+    "x-amz-server-side-encryption-bucket-key-enabled",
+    "x-amz-server-side-encryption-context",
 ];
 
+/// Query parameters of `GetObject`, `HeadObject`, `ListParts` and object listings.
+/// Reads with other parameters access subresources such as ACLs, tags or retention
+/// and require write access.
+const READ_QUERY_PARAMETERS: [&str; 21] = [
+    "versionId",
+    "partNumber",
+    "uploadId",
+    "max-parts",
+    "part-number-marker",
+    "response-cache-control",
+    "response-content-disposition",
+    "response-content-encoding",
+    "response-content-language",
+    "response-content-type",
+    "response-expires",
+    "list-type",
+    "prefix",
+    "delimiter",
+    "marker",
+    "continuation-token",
+    "max-keys",
+    "start-after",
+    "encoding-type",
+    "fetch-owner",
+    "x-id",
+];
+
+/// Query parameters of the writes issued by Iceberg clients: multipart uploads
+/// and the operation hint of some SDKs. Writes with other parameters change
+/// subresources such as ACLs, tags or retention and are never signed.
+const WRITE_QUERY_PARAMETERS: [&str; 4] = ["uploads", "uploadId", "partNumber", "x-id"];
+
+/// Kind of S3 request to sign, derived from its method and query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum S3Operation {
+    /// `GetObject`, `HeadObject` and listings.
+    Read,
+    /// `PutObject` and the requests of multipart uploads:
+    /// `CreateMultipartUpload`, `UploadPart` and `CompleteMultipartUpload`.
+    /// Reads of subresources such as ACLs or tags are treated as writes.
+    Write,
+    /// `DeleteObject` and `AbortMultipartUpload`.
+    Delete,
+    /// `DeleteObjects`, a `POST` to the bucket listing the keys to delete in its body.
+    DeleteObjects,
+}
+
+impl S3Operation {
+    /// Requests other than `GET` and `HEAD` are at least a [`S3Operation::Write`].
+    fn from_request(method: &http::Method, uri: &url::Url) -> Result<Self> {
+        let operation = match *method {
+            http::Method::GET | http::Method::HEAD => {
+                let is_read = uri
+                    .query_pairs()
+                    .all(|(key, _)| READ_QUERY_PARAMETERS.contains(&key.as_ref()));
+                return Ok(if is_read { Self::Read } else { Self::Write });
+            }
+            http::Method::POST if uri.query_pairs().any(|(key, _)| key == "delete") => {
+                return Ok(Self::DeleteObjects)
+            }
+            http::Method::PUT | http::Method::POST => Self::Write,
+            http::Method::DELETE => Self::Delete,
+            _ => {
+                return Err(ErrorModel::builder()
+                    .code(http::StatusCode::METHOD_NOT_ALLOWED.into())
+                    .message("Method not allowed".to_string())
+                    .r#type("MethodNotAllowed".to_string())
+                    .build()
+                    .into())
+            }
+        };
+
+        if let Some((key, _)) = uri
+            .query_pairs()
+            .find(|(key, _)| !WRITE_QUERY_PARAMETERS.contains(&key.as_ref()))
+        {
+            return Err(ErrorModel::builder()
+                .code(http::StatusCode::FORBIDDEN.into())
+                .message(format!(
+                    "Signing {method} requests with query parameter `{key}` is not allowed"
+                ))
+                .r#type("UnsupportedS3Operation".to_string())
+                .build()
+                .into());
+        }
+        Ok(operation)
+    }
+
+    /// Whether the operation modifies objects, which requires write access to the table.
+    fn is_write(self) -> bool {
+        !matches!(self, Self::Read)
+    }
+}
+
 #[async_trait::async_trait]
 impl<C: Catalog, A: AuthZHandler, S: SecretStore>
     crate::api::iceberg::v1::s3_signer::Service<State<A, C, S>> for CatalogServer<C, A, S>
@@ -44,6 +150,24 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         request_metadata: RequestMetadata,
     ) -> Result<S3SignResponse> {
         let warehouse_id = require_warehouse_id(prefix.clone())?;
+        // Signer tokens are minted after the allowlist and AuthZ checks of `loadTable`,
+        // so they are validated against the table they were issued for instead.
+        let signer_token = match &request_metadata.auth_details {
+            Some(AuthDetails::SignerToken(claims)) => Some(claims.clone()),
+            _ => None,
+        };
+        // Signer tokens of shared tables are issued for the owning warehouse,
+        // while the prefix is the one of the consumer's warehouse.
+        let warehouse_id = signer_token
+            .as_ref()
+            .map_or(warehouse_id, |claims| claims.warehouse_id.into());
+        // Signer tokens are validated without consulting the `AuthZHandler`.
+        let unauthorized = if signer_token.is_none() {
+            require_table_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state)
+                .await?
+        } else {
+            UnauthorizedResponse::Forbidden
+        };
 
         let S3SignRequest {
             region: request_region,
@@ -54,6 +178,14 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         } = request.clone();
 
         let include_staged = true;
+        let operation = S3Operation::from_request(&request_method, &request_url)?;
+        // A `DeleteObjects` request is sent to the bucket. The keys of the objects
+        // it deletes are validated instead.
+        let delete_keys = if operation == S3Operation::DeleteObjects {
+            Some(delete_objects_keys(request_body.as_deref())?)
+        } else {
+            None
+        };
 
         // Unfortunately there is currently no way to pass information about warehouse_id & table_id
         // to this function from a get_table or create_table process.
@@ -67,6 +199,10 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         } else {
             // Ideally we could get rid of this else clause.
             let location = parse_s3_url_to_location(&request_url)?;
+            let location = match delete_keys.as_ref().and_then(|keys| keys.first()) {
+                Some(key) => format!("{}/{key}", location.trim_end_matches('/')),
+                None => location,
+            };
             let table_metadata = C::get_table_metadata_by_s3_location(
                 &warehouse_id,
                 &location,
@@ -76,8 +212,8 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             .await
             .map_err(|e| {
                 ErrorModel::builder()
-                    .code(http::StatusCode::UNAUTHORIZED.into())
-                    .message("Unauthorized".to_string())
+                    .code(http::StatusCode::FORBIDDEN.into())
+                    .message("Location does not belong to a table of this warehouse".to_string())
                     .r#type("InvalidLocation".to_string())
                     .stack(Some(vec![format!("{e:?}")]))
                     .build()
@@ -91,14 +227,32 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
 
         // First check - fail fast if requested table is not allowed.
         // We also need to check later if the path matches the table location.
-        validate_table_method::<A>(
-            &request_method,
-            &request_metadata,
-            &warehouse_id,
-            &table_id,
-            state.v1_state.auth,
-        )
-        .await?;
+        if let Some(signer_token) = &signer_token {
+            validate_signer_token_operation(operation, signer_token, &warehouse_id, &table_id)?;
+        } else {
+            // Owners may write to their tables, just like they may commit to them.
+            let ownership = if operation.is_write() {
+                resolve_table_ownership::<C>(
+                    &warehouse_id,
+                    &table_id,
+                    table_metadata.as_ref(),
+                    state.v1_state.catalog.clone(),
+                )
+                .await
+            } else {
+                None
+            };
+            validate_table_operation::<A>(
+                operation,
+                &request_metadata,
+                &warehouse_id,
+                &table_id,
+                ownership.as_ref(),
+                state.v1_state.auth,
+            )
+            .await
+            .map_err(|e| unauthorized.apply(e, AccessedObject::Table))?;
+        }
         // TODO: why were headers dropped here?
         // drop(request_metadata);
 
@@ -111,6 +265,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             metadata_location: _,
             storage_secret_ident,
             storage_profile,
+            access_delegation,
         } = if let Some(table_metadata) = table_metadata {
             table_metadata
         } else {
@@ -118,7 +273,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
                 &warehouse_id,
                 &table_id,
                 include_staged,
-                state.v1_state.catalog,
+                state.v1_state.catalog.clone(),
             )
             .await?
         };
@@ -130,11 +285,19 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             e
         };
 
+        validate_access_delegation(access_delegation).map_err(extend_err)?;
+
+        // Remote signing is only offered for S3, other profiles are a client error
         let storage_profile = storage_profile
-            .try_into_s3(http::StatusCode::INTERNAL_SERVER_ERROR.into())
+            .try_into_s3(http::StatusCode::BAD_REQUEST.into())
             .map_err(extend_err)?;
 
-        validate_uri(&request_url, &location, &storage_profile).map_err(extend_err)?;
+        if let Some(keys) = &delete_keys {
+            validate_delete_objects(&request_url, keys, &location, &storage_profile)
+                .map_err(extend_err)?;
+        } else {
+            validate_uri(&request_url, &location, &storage_profile).map_err(extend_err)?;
+        }
         validate_region(&request_region, &storage_profile).map_err(extend_err)?;
 
         // If all is good, we need the storage secret
@@ -159,17 +322,30 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
 
         let credentials: aws_credential_types::Credentials = storage_profile
             .get_aws_sdk_credentials(storage_secret.as_ref())
+            .await
             .map_err(extend_err)?;
 
-        sign(
+        // Equal to the region of the request, except for S3-compatible stores
+        let response = sign(
             credentials,
             request_body,
-            &request_region,
+            &storage_profile.region,
             &request_url,
             &request_method,
             &request_headers,
         )
-        .map_err(extend_err)
+        .map_err(extend_err)?;
+
+        count_warehouse_operation::<C>(
+            &warehouse_id,
+            WarehouseOperation::Sign,
+            1,
+            &state.v1_state.resolution_cache,
+            state.v1_state.catalog,
+        )
+        .await;
+
+        Ok(response)
     }
 }
 
@@ -306,8 +482,30 @@ fn require_table_id(table_id: Option<String>) -> Result<TableIdentUuid> {
         .and_then(|table_id| TableIdentUuid::from_str(&table_id).map_err(Into::into))
 }
 
+/// Remote signing must be permitted by the access delegation mode of the table.
+/// The default mode of the warehouse does not apply, as clients that use the signer
+/// requested remote signing explicitly.
+fn validate_access_delegation(access_delegation: Option<AccessDelegation>) -> Result<()> {
+    let signing = DataAccess {
+        vended_credentials: false,
+        remote_signing: true,
+    };
+    if AccessDelegation::apply(access_delegation, &signing).is_some_and(|a| a.remote_signing) {
+        return Ok(());
+    }
+
+    Err(ErrorModel::builder()
+        .code(http::StatusCode::FORBIDDEN.into())
+        .message("Access delegation of this table does not permit remote signing".to_string())
+        .r#type("RemoteSigningNotPermitted".to_string())
+        .build()
+        .into())
+}
+
+/// S3-compatible stores are signed for the region of the profile, so any
+/// region sent by the client is accepted.
 fn validate_region(region: &str, storage_profile: &S3Profile) -> Result<()> {
-    if region != storage_profile.region {
+    if storage_profile.flavor != S3Flavor::S3Compat && region != storage_profile.region {
         return Err(ErrorModel::builder()
             .code(http::StatusCode::BAD_REQUEST.into())
             .message("Region does not match storage profile".to_string())
@@ -319,81 +517,249 @@ fn validate_region(region: &str, storage_profile: &S3Profile) -> Result<()> {
     Ok(())
 }
 
-async fn validate_table_method<A: AuthZHandler>(
-    method: &http::Method,
+/// Owner and creator of the table. Ownership is stored by name,
+/// so the metadata of the table is loaded if it is not known yet.
+async fn resolve_table_ownership<C: Catalog>(
+    warehouse_id: &WarehouseIdent,
+    table_id: &TableIdentUuid,
+    table_metadata: Option<&GetTableMetadataResponse>,
+    catalog_state: C::State,
+) -> Option<ObjectOwnership> {
+    let table = match table_metadata {
+        Some(table_metadata) => table_metadata.table.clone(),
+        // We can't fail before AuthZ.
+        None => {
+            C::get_table_metadata_by_id(warehouse_id, table_id, true, catalog_state.clone())
+                .await
+                .ok()?
+                .table
+        }
+    };
+    resolve_ownership::<C>(warehouse_id, OwnedObject::Table(table), catalog_state).await
+}
+
+async fn validate_table_operation<A: AuthZHandler>(
+    operation: S3Operation,
     metadata: &RequestMetadata,
     warehouse_id: &WarehouseIdent,
     table_id: &TableIdentUuid,
+    ownership: Option<&ObjectOwnership>,
     auth_state: A::State,
 ) -> Result<()> {
+    metadata.require_token_table(Some(table_id))?;
+
     // First check - fail fast if requested table is not allowed.
     // We also need to check later if the path matches the table location.
-    if WRITE_METHODS.contains(&method.as_str()) {
+    if operation.is_write() {
         // We specify namespace as none for AuthZ check because we don't want to grant access to potentially
         // locations not known to the catalog.
-        A::check_commit_table(metadata, warehouse_id, Some(table_id), None, auth_state).await?;
-    } else if READ_METHODS.contains(&method.as_str()) {
-        A::check_load_table(metadata, warehouse_id, None, Some(table_id), auth_state).await?;
+        A::check_commit_table(
+            metadata,
+            warehouse_id,
+            Some(table_id),
+            None,
+            ownership,
+            auth_state,
+        )
+        .await
     } else {
+        A::check_load_table(metadata, warehouse_id, None, Some(table_id), auth_state).await
+    }
+}
+
+fn validate_signer_token_operation(
+    operation: S3Operation,
+    signer_token: &SignerTokenClaims,
+    warehouse_id: &WarehouseIdent,
+    table_id: &TableIdentUuid,
+) -> Result<()> {
+    signer_token.require_table(warehouse_id, table_id)?;
+
+    if operation.is_write() && !signer_token.access.allows_write() {
         return Err(ErrorModel::builder()
-            .code(http::StatusCode::METHOD_NOT_ALLOWED.into())
-            .message("Method not allowed".to_string())
-            .r#type("MethodNotAllowed".to_string())
+            .code(http::StatusCode::FORBIDDEN.into())
+            .message("Signer token does not grant write access".to_string())
+            .r#type("SignerTokenReadOnly".to_string())
             .build()
             .into());
     }
-
     Ok(())
 }
 
-const AWS_S3_ACCESS_POINTS: &[&str] = &["s3", "s3.dualstack", "s3-fips.dualstack", "s3-fips"];
+/// Keys of the objects deleted by a `DeleteObjects` request.
+///
+/// The body must be a `Delete` document as specified by S3: `Object` elements with
+/// exactly one `Key` and an optional `VersionId`, followed by an optional `Quiet`.
+/// Any other markup, including document type declarations, is rejected, so that the
+/// keys we validate are the keys S3 deletes.
+fn delete_objects_keys(body: Option<&str>) -> Result<Vec<String>> {
+    let invalid = |message: &str| -> IcebergErrorResponse {
+        ErrorModel::builder()
+            .code(http::StatusCode::BAD_REQUEST.into())
+            .message(message.to_string())
+            .r#type("InvalidDeleteObjectsRequest".to_string())
+            .build()
+            .into()
+    };
+    let body = body.ok_or_else(|| invalid("DeleteObjects request requires a body"))?;
 
-#[allow(clippy::too_many_lines)]
-fn validate_uri(
-    // i.e. https://bucket.s3.region.amazonaws.com/key
-    request_uri: &url::Url,
+    let mut reader = quick_xml::Reader::from_str(body);
+    reader.expand_empty_elements(true);
+
+    let mut path: Vec<Vec<u8>> = Vec::new();
+    let mut keys = Vec::new();
+    // Key of the current `Object` and text of the open element, if it carries text.
+    let mut object_key: Option<String> = None;
+    let mut text: Option<String> = None;
+    loop {
+        let event = reader.read_event().map_err(|e| {
+            let mut error = invalid("DeleteObjects request body is not valid XML");
+            error.error.push_to_stack(e.to_string());
+            error
+        })?;
+        match event {
+            Event::Decl(_) => {}
+            // Comments within keys are rejected below, as they would split the text
+            Event::Comment(_) if text.is_none() => {}
+            Event::Start(element) => {
+                let name = element.local_name().as_ref().to_vec();
+                let allowed = match (path.as_slice(), name.as_slice()) {
+                    ([], b"Delete") => true,
+                    ([delete], b"Object" | b"Quiet") => delete == b"Delete",
+                    ([_, object], b"Key" | b"VersionId") => object == b"Object",
+                    _ => false,
+                };
+                if !allowed {
+                    return Err(invalid(&format!(
+                        "Unexpected element '{}' in DeleteObjects request",
+                        String::from_utf8_lossy(&name)
+                    )));
+                }
+                if name == b"Object" {
+                    object_key = None;
+                }
+                if matches!(name.as_slice(), b"Key" | b"VersionId" | b"Quiet") {
+                    text = Some(String::new());
+                }
+                path.push(name);
+            }
+            Event::Text(content) => {
+                let content = content
+                    .unescape()
+                    .map_err(|_| invalid("DeleteObjects request contains an invalid reference"))?;
+                match text.as_mut() {
+                    Some(text) => text.push_str(&content),
+                    None if content.trim().is_empty() => {}
+                    None => return Err(invalid("Unexpected text in DeleteObjects request")),
+                }
+            }
+            Event::CData(content) => {
+                let content = std::str::from_utf8(&content)
+                    .map_err(|_| invalid("DeleteObjects request is not valid UTF-8"))?;
+                text.as_mut()
+                    .ok_or_else(|| invalid("Unexpected CDATA in DeleteObjects request"))?
+                    .push_str(content);
+            }
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                let content = text.take();
+                match name.as_slice() {
+                    b"Key" => {
+                        if object_key.replace(content.unwrap_or_default()).is_some() {
+                            return Err(invalid(
+                                "Object in DeleteObjects request has multiple keys",
+                            ));
+                        }
+                    }
+                    b"Object" => {
+                        let key = object_key
+                            .take()
+                            .filter(|key| !key.is_empty())
+                            .ok_or_else(|| invalid("Object in DeleteObjects request has no key"))?;
+                        keys.push(key);
+                    }
+                    _ => {}
+                }
+            }
+            Event::Eof if path.is_empty() => break,
+            Event::Eof => return Err(invalid("DeleteObjects request body is incomplete")),
+            Event::Comment(_) | Event::Empty(_) | Event::PI(_) | Event::DocType(_) => {
+                return Err(invalid("Unexpected markup in DeleteObjects request"));
+            }
+        }
+    }
+
+    if keys.is_empty() {
+        return Err(invalid("DeleteObjects request does not list any keys"));
+    }
+    Ok(keys)
+}
+
+/// Validate that a `DeleteObjects` request to `bucket_url` only deletes objects below
+/// `table_location`. Keys are compared as they are, as S3 does not normalize them.
+fn validate_delete_objects(
+    // i.e. https://bucket.s3.region.amazonaws.com/?delete
+    bucket_url: &url::Url,
+    keys: &[String],
     // i.e. s3://bucket/key
     table_location: &str,
     storage_profile: &S3Profile,
 ) -> Result<()> {
-    let table_location = url::Url::parse(table_location.trim_end_matches('/')).map_err(|e| {
-        ErrorModel::builder()
-            .code(http::StatusCode::INTERNAL_SERVER_ERROR.into())
-            .message("Failed to parse table location".to_string())
-            .r#type("FailedToParseTableLocation".to_string())
-            .stack(Some(vec![e.to_string()]))
-            .build()
-    })?;
-    let table_bucket = table_location.host_str().ok_or_else(|| {
-        ErrorModel::builder()
-            .code(http::StatusCode::INTERNAL_SERVER_ERROR.into())
-            .message("Table location does not have a bucket".to_string())
-            .r#type("TableLocationNoBucket".to_string())
-            .build()
-    })?;
-    let table_key_virtual_host: Vec<_> = table_location
-        .path_segments()
-        .map(std::iter::Iterator::collect)
-        .ok_or(
+    let (table_bucket, table_key) = table_location
+        .split_once("://")
+        .and_then(|(_, location)| location.split_once('/'))
+        .filter(|(bucket, key)| !bucket.is_empty() && !key.trim_matches('/').is_empty())
+        .ok_or_else(|| {
             ErrorModel::builder()
                 .code(http::StatusCode::INTERNAL_SERVER_ERROR.into())
-                .message("Table location does not have a key".to_string())
+                .message("Table location does not have a bucket and key".to_string())
                 .r#type("TableLocationNoKey".to_string())
-                .build(),
-        )?;
-    let table_key_path_style = vec![table_bucket]
-        .into_iter()
-        .chain(table_key_virtual_host.clone())
-        .collect::<Vec<_>>();
+                .build()
+        })?;
+    let table_prefix = format!("{}/", table_key.trim_end_matches('/'));
 
-    let request_key: Vec<_> = request_uri
-        .path_segments()
-        .map(std::iter::Iterator::collect)
-        .unwrap_or_default();
+    let table_endpoint_candidates = endpoint_candidates(storage_profile)?;
+    let request_path = bucket_url.path().trim_matches('/');
+    let is_virtual_host = table_endpoint_candidates.iter().any(|(scheme, host)| {
+        bucket_url.scheme() == scheme
+            && bucket_url.host_str() == Some(&format!("{table_bucket}.{host}"))
+            && request_path.is_empty()
+    });
+    let is_path_style = table_endpoint_candidates.iter().any(|(scheme, host)| {
+        bucket_url.scheme() == scheme
+            && bucket_url.host_str() == Some(host)
+            && request_path == table_bucket
+    });
+    if !is_virtual_host && !is_path_style {
+        return Err(ErrorModel::builder()
+            .code(http::StatusCode::FORBIDDEN.into())
+            .message("Request URI does not match the bucket of the table".to_string())
+            .r#type("RequestUriMismatch".to_string())
+            .build()
+            .into());
+    }
 
-    // Obtain tuples of (scheme, host) for the endpoint candidates.
-    // We need multiple candidates only for AWS S3, as there are multiple access points such as s3, s3.dualstack, etc.
-    let table_endpoint_candidates = if let Some(endpoint) = &storage_profile.endpoint {
+    if let Some(key) = keys.iter().find(|key| !key.starts_with(&table_prefix)) {
+        return Err(ErrorModel::builder()
+            .code(http::StatusCode::FORBIDDEN.into())
+            .message("Deleted object does not belong to the table".to_string())
+            .r#type("DeleteObjectsKeyMismatch".to_string())
+            .stack(Some(vec![
+                format!("Expected Prefix: {table_prefix}"),
+                format!("Actual Key: {key}"),
+            ]))
+            .build()
+            .into());
+    }
+    Ok(())
+}
+
+const AWS_S3_ACCESS_POINTS: &[&str] = &["s3", "s3.dualstack", "s3-fips.dualstack", "s3-fips"];
+
+/// Tuples of (scheme, host) for the endpoint candidates of `storage_profile`.
+/// We need multiple candidates only for AWS S3, as there are multiple access points such as s3, s3.dualstack, etc.
+fn endpoint_candidates(storage_profile: &S3Profile) -> Result<Vec<(String, String)>> {
+    let candidates = if let Some(endpoint) = &storage_profile.endpoint {
         let endpoint = url::Url::parse(endpoint).map_err(|e| {
             ErrorModel::builder()
                 .code(http::StatusCode::INTERNAL_SERVER_ERROR.into())
@@ -430,6 +796,53 @@ fn validate_uri(
             })
             .collect::<Vec<_>>()
     };
+    Ok(candidates)
+}
+
+#[allow(clippy::too_many_lines)]
+fn validate_uri(
+    // i.e. https://bucket.s3.region.amazonaws.com/key
+    request_uri: &url::Url,
+    // i.e. s3://bucket/key
+    table_location: &str,
+    storage_profile: &S3Profile,
+) -> Result<()> {
+    let table_location = url::Url::parse(table_location.trim_end_matches('/')).map_err(|e| {
+        ErrorModel::builder()
+            .code(http::StatusCode::INTERNAL_SERVER_ERROR.into())
+            .message("Failed to parse table location".to_string())
+            .r#type("FailedToParseTableLocation".to_string())
+            .stack(Some(vec![e.to_string()]))
+            .build()
+    })?;
+    let table_bucket = table_location.host_str().ok_or_else(|| {
+        ErrorModel::builder()
+            .code(http::StatusCode::INTERNAL_SERVER_ERROR.into())
+            .message("Table location does not have a bucket".to_string())
+            .r#type("TableLocationNoBucket".to_string())
+            .build()
+    })?;
+    let table_key_virtual_host: Vec<_> = table_location
+        .path_segments()
+        .map(std::iter::Iterator::collect)
+        .ok_or(
+            ErrorModel::builder()
+                .code(http::StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message("Table location does not have a key".to_string())
+                .r#type("TableLocationNoKey".to_string())
+                .build(),
+        )?;
+    let table_key_path_style = vec![table_bucket]
+        .into_iter()
+        .chain(table_key_virtual_host.clone())
+        .collect::<Vec<_>>();
+
+    let request_key: Vec<_> = request_uri
+        .path_segments()
+        .map(std::iter::Iterator::collect)
+        .unwrap_or_default();
+
+    let table_endpoint_candidates = endpoint_candidates(storage_profile)?;
 
     // There are two ways to access S3 buckets: path style and virtual host style.
     // Virtual Host style: https://<bucket>.s3.<region>.amazonaws.com/<key>
@@ -530,6 +943,17 @@ fn parse_s3_url_to_location(uri: &url::Url) -> Result<String> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::implementations::memory::{MemoryCatalog, MemoryCatalogState};
+    use crate::implementations::{RbacAuthState, RbacAuthZHandler};
+    use crate::service::signer_token::SignerAccess;
+    use crate::service::storage::{FileProfile, StorageProfile};
+    use crate::service::token_verification::{Aud, Claims};
+    use crate::service::{
+        CreateNamespaceRequest, CreateTableRequest, NamespaceIdent, TableIdent, Transaction,
+    };
+    use crate::ProjectIdent;
+    use iceberg::spec::{NestedField, PrimitiveType, Schema};
+    use std::collections::HashSet;
 
     struct TC {
         request_uri: &'static str,
@@ -545,7 +969,12 @@ mod test {
             endpoint: test_case.endpoint.map(std::string::ToString::to_string),
             region: test_case.region.to_string(),
             assume_role_arn: None,
+            external_id: None,
             path_style_access: None,
+            sts_enabled: false,
+            flavor: S3Flavor::Aws,
+            sse_algorithm: None,
+            kms_key_arn: None,
             key_prefix: None,
         }
     }
@@ -744,7 +1173,12 @@ mod test {
             endpoint: None,
             region: "my-region".to_string(),
             assume_role_arn: None,
+            external_id: None,
             path_style_access: None,
+            sts_enabled: false,
+            flavor: S3Flavor::Aws,
+            sse_algorithm: None,
+            kms_key_arn: None,
             key_prefix: None,
         };
 
@@ -753,5 +1187,374 @@ mod test {
 
         let result = validate_region("wrong-region", &storage_profile);
         assert!(result.is_err());
+
+        let storage_profile = S3Profile {
+            region: "auto".to_string(),
+            flavor: S3Flavor::S3Compat,
+            ..storage_profile
+        };
+        let result = validate_region("us-east-1", &storage_profile);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_s3_operation() {
+        let object = url::Url::parse("https://bucket.s3.us-east-1.amazonaws.com/key").unwrap();
+        let upload_part = url::Url::parse(
+            "https://bucket.s3.us-east-1.amazonaws.com/key?partNumber=1&uploadId=abc",
+        )
+        .unwrap();
+        let delete_objects =
+            url::Url::parse("https://bucket.s3.us-east-1.amazonaws.com/?delete").unwrap();
+
+        let operation =
+            |method: http::Method, uri: &url::Url| S3Operation::from_request(&method, uri).ok();
+        assert_eq!(
+            operation(http::Method::GET, &object),
+            Some(S3Operation::Read)
+        );
+        assert_eq!(
+            operation(http::Method::PUT, &upload_part),
+            Some(S3Operation::Write)
+        );
+        assert_eq!(
+            operation(http::Method::DELETE, &upload_part),
+            Some(S3Operation::Delete)
+        );
+        assert_eq!(
+            operation(http::Method::POST, &delete_objects),
+            Some(S3Operation::DeleteObjects)
+        );
+        assert_eq!(operation(http::Method::PATCH, &object), None);
+
+        let list = url::Url::parse(
+            "https://bucket.s3.us-east-1.amazonaws.com/?list-type=2&prefix=a%2F&max-keys=10",
+        )
+        .unwrap();
+        assert_eq!(operation(http::Method::GET, &list), Some(S3Operation::Read));
+        let version = url::Url::parse(
+            "https://bucket.s3.us-east-1.amazonaws.com/key?versionId=1&x-id=GetObject",
+        )
+        .unwrap();
+        assert_eq!(
+            operation(http::Method::HEAD, &version),
+            Some(S3Operation::Read)
+        );
+
+        // Writes to subresources of an object are never signed,
+        // reads of subresources require write access
+        for subresource in ["tagging", "acl", "retention", "legal-hold", "restore"] {
+            let url = url::Url::parse(&format!(
+                "https://bucket.s3.us-east-1.amazonaws.com/key?{subresource}"
+            ))
+            .unwrap();
+            for method in [http::Method::PUT, http::Method::POST, http::Method::DELETE] {
+                assert_eq!(operation(method.clone(), &url), None, "{method} {url}");
+            }
+            for method in [http::Method::GET, http::Method::HEAD] {
+                let op = operation(method.clone(), &url);
+                assert_eq!(op, Some(S3Operation::Write), "{method} {url}");
+                assert!(op.is_some_and(S3Operation::is_write));
+            }
+        }
+        let create_upload = url::Url::parse(
+            "https://bucket.s3.us-east-1.amazonaws.com/key?uploads&x-id=CreateMultipartUpload",
+        )
+        .unwrap();
+        assert_eq!(
+            operation(http::Method::POST, &create_upload),
+            Some(S3Operation::Write)
+        );
+    }
+
+    fn delete_body(objects: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<Delete xmlns="http://s3.amazonaws.com/doc/2006-03-01/">{objects}<Quiet>true</Quiet></Delete>"#
+        )
+    }
+
+    #[test]
+    fn test_delete_objects_keys() {
+        let body = delete_body(
+            r"
+  <Object><Key>prefix/table/data/a.parquet</Key></Object>
+  <Object><Key>prefix/table/data/b&amp;c.parquet</Key><VersionId>1</VersionId></Object>
+",
+        );
+        assert_eq!(
+            delete_objects_keys(Some(&body)).unwrap(),
+            vec![
+                "prefix/table/data/a.parquet",
+                "prefix/table/data/b&c.parquet"
+            ]
+        );
+
+        // Keys are decoded before they are validated
+        let body = delete_body(
+            "<Object><Key >other/x</Key></Object>\
+             <Object><Key><![CDATA[other/<y>]]></Key></Object>\
+             <Object><Key>&#x2e;&#x2e;/other/z</Key></Object>",
+        );
+        assert_eq!(
+            delete_objects_keys(Some(&body)).unwrap(),
+            vec!["other/x", "other/<y>", "../other/z"]
+        );
+
+        assert!(delete_objects_keys(None).is_err());
+        assert!(delete_objects_keys(Some("<Delete></Delete>")).is_err());
+        assert!(delete_objects_keys(Some("<Delete><Object><Key>a</Key></Object>")).is_err());
+        // Unknown elements and text
+        for objects in [
+            "<Object><Key>a</Key><Key>b</Key></Object>",
+            "<Object><Key/></Object>",
+            "<Object><VersionId>1</VersionId></Object>",
+            "<Object><Key>a</Key><ETag>1</ETag></Object>",
+            "<Object><Key><Key>a</Key></Key></Object>",
+            "<Key>a</Key>",
+            "<Object>a<Key>b</Key></Object>",
+            "<Object><Key>&unknown;</Key></Object>",
+            "<Object><Key>prefix/<!-- -->table</Key></Object>",
+        ] {
+            assert!(
+                delete_objects_keys(Some(&delete_body(objects))).is_err(),
+                "{objects}"
+            );
+        }
+        // Document type declarations could define entities
+        let body = r#"<?xml version="1.0"?>
+<!DOCTYPE Delete [<!ENTITY prefix "other">]>
+<Delete><Object><Key>&prefix;/x</Key></Object></Delete>"#;
+        assert!(delete_objects_keys(Some(body)).is_err());
+    }
+
+    #[test]
+    fn test_validate_delete_objects() {
+        let table_location = "s3://bucket/prefix/table";
+        let storage_profile = make_storage_profile(&TC {
+            request_uri: "https://bucket.s3.us-east-1.amazonaws.com/?delete",
+            table_location,
+            region: "us-east-1",
+            endpoint: None,
+            expected_outcome: true,
+        });
+        let virtual_host =
+            url::Url::parse("https://bucket.s3.us-east-1.amazonaws.com/?delete").unwrap();
+        let path_style =
+            url::Url::parse("https://s3.us-east-1.amazonaws.com/bucket?delete").unwrap();
+        let validate = |url: &url::Url, key: &str| {
+            validate_delete_objects(url, &[key.to_string()], table_location, &storage_profile)
+        };
+
+        assert!(validate(&virtual_host, "prefix/table/data/a.parquet").is_ok());
+        assert!(validate(&path_style, "prefix/table/data/a.parquet").is_ok());
+        assert!(validate(&virtual_host, "prefix/table-2/data/a.parquet").is_err());
+        assert!(validate(&virtual_host, "prefix/other/data/a.parquet").is_err());
+        // Keys are not normalized
+        assert!(validate(&virtual_host, "other/../prefix/table/data/a.parquet").is_err());
+        assert!(validate(&virtual_host, "/prefix/table/data/a.parquet").is_err());
+
+        let other_bucket =
+            url::Url::parse("https://other.s3.us-east-1.amazonaws.com/?delete").unwrap();
+        assert!(validate(&other_bucket, "prefix/table/data/a.parquet").is_err());
+        let object_url = url::Url::parse(
+            "https://bucket.s3.us-east-1.amazonaws.com/prefix/table/data/a.parquet?delete",
+        )
+        .unwrap();
+        assert!(validate(&object_url, "prefix/table/data/a.parquet").is_err());
+
+        // Keys of all objects are validated
+        assert!(validate_delete_objects(
+            &virtual_host,
+            &[
+                "prefix/table/data/a.parquet".to_string(),
+                "other/../prefix/table/data/b.parquet".to_string()
+            ],
+            table_location,
+            &storage_profile
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_access_delegation() {
+        assert!(validate_access_delegation(None).is_ok());
+        assert!(validate_access_delegation(Some(AccessDelegation::RemoteSigning)).is_ok());
+
+        let err =
+            validate_access_delegation(Some(AccessDelegation::VendedCredentials)).unwrap_err();
+        assert_eq!(err.error.code, http::StatusCode::FORBIDDEN);
+        assert_eq!(err.error.r#type, "RemoteSigningNotPermitted");
+        assert!(validate_access_delegation(Some(AccessDelegation::Disabled)).is_err());
+    }
+
+    #[test]
+    fn test_validate_signer_token_operation() {
+        let warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        let table_id = TableIdentUuid::from(uuid::Uuid::now_v7());
+        let mut signer_token = SignerTokenClaims {
+            iss: "iceberg-catalog".to_string(),
+            sub: "alice".to_string(),
+            jti: "signer-token".to_string(),
+            parent_jti: None,
+            warehouse_id: warehouse_id.into_uuid(),
+            table_id: table_id.into_uuid(),
+            access: SignerAccess::Read,
+            iat: 0,
+            exp: 0,
+        };
+
+        let validate = |operation: S3Operation, signer_token: &SignerTokenClaims| {
+            validate_signer_token_operation(operation, signer_token, &warehouse_id, &table_id)
+        };
+        assert!(validate(S3Operation::Read, &signer_token).is_ok());
+        assert!(validate(S3Operation::Write, &signer_token).is_err());
+        assert!(validate(S3Operation::Delete, &signer_token).is_err());
+        assert!(validate(S3Operation::DeleteObjects, &signer_token).is_err());
+
+        signer_token.access = SignerAccess::ReadWrite;
+        assert!(validate(S3Operation::Write, &signer_token).is_ok());
+        assert!(validate(S3Operation::Delete, &signer_token).is_ok());
+        assert!(validate(S3Operation::DeleteObjects, &signer_token).is_ok());
+
+        signer_token.table_id = uuid::Uuid::now_v7();
+        assert!(validate(S3Operation::Read, &signer_token).is_err());
+    }
+
+    fn metadata(principal: &str) -> RequestMetadata {
+        RequestMetadata {
+            auth_details: Some(AuthDetails::JWT(Claims {
+                sub: principal.to_string(),
+                iss: "iss".to_string(),
+                aud: Aud::String("aud".to_string()),
+                exp: 0,
+                iat: 0,
+                jti: None,
+                other: serde_json::json!({}),
+            })),
+            ..RequestMetadata::new_random()
+        }
+    }
+
+    /// Warehouse with a table owned by `owner`.
+    async fn create_owned_table(
+        catalog_state: MemoryCatalogState,
+        owner: &str,
+    ) -> (WarehouseIdent, TableIdentUuid) {
+        let mut transaction =
+            <MemoryCatalog as Catalog>::Transaction::begin_write(catalog_state.clone())
+                .await
+                .unwrap();
+        let warehouse_id = MemoryCatalog::create_warehouse(
+            "test_warehouse".to_string(),
+            ProjectIdent::from(uuid::Uuid::nil()),
+            StorageProfile::File(FileProfile {
+                path: "/tmp/warehouse".to_string(),
+            }),
+            None,
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        let namespace = NamespaceIdent::new("sales".to_string());
+        MemoryCatalog::create_namespace(
+            &warehouse_id,
+            CreateNamespaceRequest {
+                namespace: namespace.clone(),
+                properties: None,
+            },
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let namespace_id =
+            MemoryCatalog::namespace_ident_to_id(&warehouse_id, &namespace, catalog_state.clone())
+                .await
+                .unwrap()
+                .unwrap();
+        let table_id = TableIdentUuid::from(uuid::Uuid::now_v7());
+        let mut transaction =
+            <MemoryCatalog as Catalog>::Transaction::begin_write(catalog_state.clone())
+                .await
+                .unwrap();
+        MemoryCatalog::create_table(
+            &namespace_id,
+            &TableIdent::new(namespace, "orders".to_string()),
+            &table_id,
+            CreateTableRequest {
+                name: "orders".to_string(),
+                location: Some("file:///tmp/warehouse/orders".to_string()),
+                schema: Schema::builder()
+                    .with_fields(vec![NestedField::required(
+                        1,
+                        "id",
+                        iceberg::spec::Type::Primitive(PrimitiveType::Int),
+                    )
+                    .into()])
+                    .build()
+                    .unwrap(),
+                partition_spec: None,
+                write_order: None,
+                stage_create: None,
+                properties: None,
+            },
+            None,
+            Some(owner),
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+        (warehouse_id, table_id)
+    }
+
+    #[tokio::test]
+    async fn test_owner_may_sign_writes() {
+        let catalog_state = MemoryCatalogState::default();
+        let (warehouse_id, table_id) = create_owned_table(catalog_state.clone(), "alice").await;
+        let auth_state = RbacAuthState::<MemoryCatalog>::new(
+            catalog_state.clone(),
+            std::sync::Arc::new(catalog_state.clone()),
+            HashSet::new(),
+        );
+
+        let ownership = resolve_table_ownership::<MemoryCatalog>(
+            &warehouse_id,
+            &table_id,
+            None,
+            catalog_state.clone(),
+        )
+        .await;
+        assert_eq!(
+            ownership.as_ref().and_then(|o| o.owner.as_deref()),
+            Some("alice")
+        );
+
+        // Alice has no role on the table, only ownership
+        validate_table_operation::<RbacAuthZHandler<MemoryCatalog>>(
+            S3Operation::Write,
+            &metadata("alice"),
+            &warehouse_id,
+            &table_id,
+            ownership.as_ref(),
+            auth_state.clone(),
+        )
+        .await
+        .unwrap();
+        let err = validate_table_operation::<RbacAuthZHandler<MemoryCatalog>>(
+            S3Operation::Write,
+            &metadata("bob"),
+            &warehouse_id,
+            &table_id,
+            ownership.as_ref(),
+            auth_state,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, http::StatusCode::FORBIDDEN);
     }
 }
@@ -3,28 +3,50 @@ use std::vec;
 
 use crate::api::iceberg::v1::{
     ApiContext, CommitTableRequest, CommitTableResponse, CommitTransactionRequest,
-    CreateTableRequest, DataAccess, ErrorModel, ListTablesResponse, LoadTableResult,
-    NamespaceParameters, PaginationQuery, Prefix, RegisterTableRequest, RenameTableRequest, Result,
-    TableIdent, TableParameters,
+    CreateTableRequest, DataAccess, ErrorModel, ErrorType, ListTablesResponse,
+    LoadCredentialsResponse, LoadTableResult, NamespaceParameters, PaginationQuery, Prefix,
+    PresignRequest, PresignResponse, PresignedUrl, RegisterTableRequest, RenameTableRequest,
+    Result, TableCredential, TableIdent, TableParameters,
 };
 use crate::request_metadata::RequestMetadata;
+use futures::TryFutureExt as _;
 use http::StatusCode;
 use iceberg::{NamespaceIdent, TableUpdate};
 use serde::Serialize;
 use uuid::Uuid;
 
 use super::{
-    io::write_metadata_file, namespace::validate_namespace_ident, require_warehouse_id,
+    get_pyiceberg_compat, io::write_metadata_file, namespace::validate_namespace_ident,
+    normalize_namespace, normalize_table, require_table_warehouse_access, require_warehouse_access,
+    require_warehouse_id, require_writable_warehouse, resolve_compat_namespace, resolve_ownership,
     CatalogServer,
 };
+use crate::service::commit_diff::CommitDiff;
+use crate::service::commit_hook::{CommitContext, CommitHooks};
 use crate::service::contract_verification::{ContractVerification, ContractVerificationOutcome};
-use crate::service::event_publisher::{CloudEventsPublisher, EventMetadata};
-use crate::service::storage::StorageCredential;
+use crate::service::event_publisher::{
+    CloudEventsPublisher, EventMetadata, EventSubject, EventType,
+};
+use crate::service::lineage::{LineageEvent, LineageStore};
+use crate::service::metrics::{count_warehouse_operation, WarehouseOperation};
+use crate::service::page_token::PageQuery;
+use crate::service::signer_token::{
+    mint_signer_token, signer_tokens_enabled, SignerAccess, SIGNER_TOKEN_CONFIG_KEY,
+};
+use crate::service::snapshot_summary;
+use crate::service::storage::{
+    AccessDelegation, StorageCredential, TableLayout, OBJECT_STORAGE_PROPERTY,
+};
+use crate::service::table_cache::CachedTable;
+use crate::service::table_limits::TableLimits;
 use crate::service::{
-    auth::AuthZHandler, secrets::SecretStore, Catalog, CreateTableResponse,
-    LoadTableResponse as CatalogLoadTableResult, State, Transaction,
+    auth::{AccessedObject, AuthZHandler},
+    secrets::SecretStore,
+    Catalog, CreateTableResponse, LoadTableResponse as CatalogLoadTableResult, OwnedObject, State,
+    TableCommit, Transaction,
 };
-use crate::service::{GetWarehouseResponse, TableIdentUuid, WarehouseStatus};
+use crate::service::{GetWarehouseResponse, ResolvedShare, TableIdentUuid, WarehouseStatus};
+use crate::{WarehouseIdent, CONFIG};
 
 #[async_trait::async_trait]
 impl<C: Catalog, A: AuthZHandler, S: SecretStore>
@@ -33,37 +55,84 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
     /// List all table identifiers underneath a given namespace
     async fn list_tables(
         parameters: NamespaceParameters,
-        _query: PaginationQuery,
+        query: PaginationQuery,
         state: ApiContext<State<A, C, S>>,
         request_metadata: RequestMetadata,
     ) -> Result<ListTablesResponse> {
         // ------------------- VALIDATIONS -------------------
         let NamespaceParameters { namespace, prefix } = parameters;
         let warehouse_id = require_warehouse_id(prefix)?;
+        let namespace = normalize_namespace(&warehouse_id, namespace, &state.v1_state).await?;
         validate_namespace_ident(&namespace)?;
 
         // ------------------- AUTHZ -------------------
+        let unauthorized =
+            require_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state).await?;
         A::check_list_tables(
             &request_metadata,
             &warehouse_id,
             &namespace,
             state.v1_state.auth,
         )
-        .await?;
+        .await
+        .map_err(|e| unauthorized.apply(e, AccessedObject::Namespace))?;
 
         // ------------------- BUSINESS LOGIC -------------------
         let include_staged = false;
-        let tables = C::list_tables(
-            &warehouse_id,
-            &namespace,
-            include_staged,
-            state.v1_state.catalog,
-        )
-        .await?;
+        let page = PageQuery::new(
+            &query.page_token,
+            query.page_size,
+            &[
+                "list_tables",
+                &warehouse_id.to_string(),
+                &namespace.clone().inner().join("\u{1f}"),
+            ],
+        )?;
+        // Namespaces below the mount point of a share are listed from the owning warehouse.
+        let share =
+            resolve_share::<C>(&warehouse_id, &namespace, state.v1_state.catalog.clone()).await?;
+        let page = if let Some(share) = share {
+            let Some(source_namespace) = share.source_namespace(&namespace) else {
+                return Ok(ListTablesResponse {
+                    next_page_token: None,
+                    identifiers: vec![],
+                });
+            };
+            let tables = C::list_tables(
+                &share.warehouse_id,
+                &source_namespace,
+                include_staged,
+                &page,
+                state.v1_state.catalog,
+            )
+            .await?;
+            // The next page starts after the last fetched table, even if it isn't shared.
+            let mut page = page.into_page(tables, |(_, table)| table.name.clone())?;
+            page.items.retain(|(_, t)| {
+                share
+                    .source_table(&TableIdent::new(namespace.clone(), t.name.clone()))
+                    .is_some()
+            });
+            page
+        } else {
+            let tables = C::list_tables(
+                &warehouse_id,
+                &namespace,
+                include_staged,
+                &page,
+                state.v1_state.catalog,
+            )
+            .await?;
+            page.into_page(tables, |(_, table)| table.name.clone())?
+        };
 
         Ok(ListTablesResponse {
-            next_page_token: None,
-            identifiers: tables.into_iter().map(|t| t.1).collect(),
+            next_page_token: page.next_page_token,
+            identifiers: page
+                .items
+                .into_iter()
+                .map(|(_, table)| TableIdent::new(namespace.clone(), table.name))
+                .collect(),
         })
     }
 
@@ -79,34 +148,43 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         // ------------------- VALIDATIONS -------------------
         let NamespaceParameters { namespace, prefix } = parameters;
         let warehouse_id = require_warehouse_id(prefix.clone())?;
+        let namespace = normalize_namespace(&warehouse_id, namespace, &state.v1_state).await?;
         let table = TableIdent::new(namespace.clone(), request.name.clone());
         validate_table_or_view_ident(&table)?;
         require_no_location_specified(&request.location)?;
 
         if let Some(properties) = &request.properties {
             validate_table_properties(properties.keys())?;
+            AccessDelegation::validate_properties(properties)?;
         }
 
         // ------------------- AUTHZ -------------------
+        let unauthorized =
+            require_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state).await?;
         A::check_create_table(
             &request_metadata,
             &warehouse_id,
             &namespace,
-            state.v1_state.auth,
+            state.v1_state.auth.clone(),
         )
-        .await?;
+        .await
+        .map_err(|e| unauthorized.apply(e, AccessedObject::Namespace))?;
 
         // ------------------- BUSINESS LOGIC -------------------
-        let namespace_id =
-            C::namespace_ident_to_id(&warehouse_id, &namespace, state.v1_state.catalog.clone())
-                .await?
-                .ok_or(
-                    ErrorModel::builder()
-                        .code(StatusCode::NOT_FOUND.into())
-                        .message("Namespace does not exist".to_string())
-                        .r#type("NamespaceNotFound".to_string())
-                        .build(),
-                )?;
+        require_writable_warehouse(&warehouse_id, &state.v1_state).await?;
+        let namespace_id = state
+            .v1_state
+            .resolution_cache
+            .namespace_id::<C>(&warehouse_id, &namespace, state.v1_state.catalog.clone())
+            .await?
+            .ok_or(
+                ErrorModel::builder()
+                    .code(StatusCode::NOT_FOUND.into())
+                    .message("Namespace does not exist".to_string())
+                    .r#type("NamespaceNotFound".to_string())
+                    .error_type(ErrorType::NoSuchNamespace)
+                    .build(),
+            )?;
 
         let mut transaction = C::Transaction::begin_write(state.v1_state.catalog).await?;
         let GetWarehouseResponse {
@@ -116,14 +194,58 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             storage_profile,
             storage_secret_id,
             status,
+            owner: _,
+            created_by: _,
+            allowlist: _,
+            read_only: _,
+            pyiceberg_compat,
+            engine_table_config,
+            signer_uri,
+            unauthorized_response: _,
+            access_delegation: default_access_delegation,
+            allow_external_locations,
+            object_storage_layout,
+            credential_rotation: _,
+            storage_validation: _,
+            event_filter: _,
         } = C::get_warehouse(&warehouse_id, transaction.transaction()).await?;
         require_active_warehouse(status)?;
+        if !allow_external_locations {
+            if let Some(properties) = &request.properties {
+                location_properties(properties)
+                    .into_iter()
+                    .try_for_each(|location| storage_profile.require_location_within(location))?;
+            }
+        }
+        let namespace_properties =
+            C::get_namespace(&warehouse_id, &namespace, transaction.transaction())
+                .await?
+                .properties;
+        let access_delegation = AccessDelegation::or_default(
+            AccessDelegation::resolve(request.properties.as_ref(), namespace_properties.as_ref()),
+            default_access_delegation,
+            &data_access,
+        );
+
+        let layout = TableLayout::resolve(
+            request.properties.as_ref(),
+            namespace_properties.as_ref(),
+            object_storage_layout,
+        );
 
         let table_id: TableIdentUuid = uuid::Uuid::now_v7().into();
-        let table_location = storage_profile.table_location(&namespace_id, &table_id);
+        let table_location = storage_profile.table_location(&namespace_id, &table_id, layout);
 
         // This is the only place where we change request
         request.location = Some(table_location.clone());
+        if layout == TableLayout::ObjectStorage {
+            // Let clients spread the data files below hash prefixes as well
+            request
+                .properties
+                .get_or_insert_with(HashMap::new)
+                .entry(OBJECT_STORAGE_PROPERTY.to_string())
+                .or_insert_with(|| "true".to_string());
+        }
         let request = request; // Make it non-mutable again for our sanity
 
         // If stage-create is true, we should not create the metadata file
@@ -143,6 +265,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             &table_id,
             request,
             metadata_location.as_ref(),
+            request_metadata.principal(),
             transaction.transaction(),
         )
         .await?;
@@ -159,7 +282,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         };
 
         if let Some(metadata_location) = &metadata_location {
-            let file_io = storage_profile.file_io(storage_secret.as_ref())?;
+            let file_io = storage_profile.file_io(storage_secret.as_ref()).await?;
             write_metadata_file(metadata_location, &table_metadata, &file_io).await?;
         }
 
@@ -169,15 +292,31 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         // ToDo: There is a small inefficiency here: If storage credentials
         // are not required because of i.e. remote-signing and if this
         // is a stage-create, we still fetch the secret.
-        let config = storage_profile
+        let mut config = storage_profile
             .generate_table_config(
                 &warehouse_id,
-                &namespace_id,
-                &table_id,
+                &table_location,
                 &data_access,
+                access_delegation,
                 storage_secret.as_ref(),
             )
             .await?;
+        // The creator needs to write data files to the new table.
+        insert_signer_token(
+            &mut config,
+            &warehouse_id,
+            &table_id,
+            SignerAccess::ReadWrite,
+            &request_metadata,
+        )?;
+        insert_signer_uri(
+            &mut config,
+            &warehouse_id,
+            signer_uri.as_deref(),
+            pyiceberg_compat,
+        );
+        insert_refresh_credentials_endpoint(&mut config, &warehouse_id, &table);
+        engine_table_config.apply(&request_metadata.client_info().engine, &mut config);
         let load_table_result = LoadTableResult {
             metadata_location,
             metadata: table_metadata,
@@ -186,20 +325,24 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
 
         // Metadata file written, now we can commit the transaction
         transaction.commit().await?;
+        state.v1_state.recent_writes.record(&warehouse_id, &table);
 
         emit_change_event(
             EventMetadata {
-                table_id: *table_id.as_uuid(),
                 warehouse_id: *warehouse_id.as_uuid(),
-                name: table.name.clone(),
-                namespace: table.namespace.encode_in_url(),
+                subject: EventSubject::Table {
+                    table_id: *table_id.as_uuid(),
+                    namespace: table.namespace.encode_in_url(),
+                    name: table.name.clone(),
+                },
                 prefix: prefix.map(Prefix::into_string).unwrap_or_default(),
                 num_events: 1,
                 sequence_number: 0,
                 trace_id: request_metadata.request_id,
+                tenant_id: request_metadata.tenant_id().map(ToString::to_string),
             },
             body,
-            "createTable",
+            EventType::TableCreated,
             state.v1_state.publisher.clone(),
         )
         .await;
@@ -234,6 +377,17 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         // ------------------- VALIDATIONS -------------------
         let TableParameters { prefix, table } = parameters;
         let warehouse_id = require_warehouse_id(prefix)?;
+        let pyiceberg_compat = get_pyiceberg_compat(&warehouse_id, &state.v1_state).await?;
+        let table = TableIdent::new(
+            resolve_compat_namespace(
+                pyiceberg_compat.as_ref(),
+                &warehouse_id,
+                table.namespace,
+                &state.v1_state,
+            )
+            .await?,
+            table.name,
+        );
         // ToDo: Remove workaround when hierarchical namespaces are supported.
         // It is important for now to throw a 404 if a table cannot be found,
         // because spark might check if `table`.`branch` exists, which should return 404.
@@ -250,36 +404,99 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         }
 
         // ------------------- AUTHZ -------------------
-        let include_stage = false;
-        let table_id = C::table_ident_to_id(
+        let unauthorized =
+            require_table_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state)
+                .await?;
+        let catalog_state = state.v1_state.recent_writes.catalog_state::<C>(
             &warehouse_id,
             &table,
-            include_stage,
             state.v1_state.catalog.clone(),
-        )
-        .await
-        // We can't fail before AuthZ.
-        .ok()
-        .flatten();
+        );
+        let table_id = state
+            .v1_state
+            .table_cache
+            .table_id::<C>(&warehouse_id, &table, catalog_state.clone())
+            .await
+            // We can't fail before AuthZ.
+            .ok()
+            .flatten();
+        request_metadata.require_token_table(table_id.as_ref())?;
 
         A::check_load_table(
             &request_metadata,
             &warehouse_id,
             Some(&table.namespace),
             table_id.as_ref(),
-            state.v1_state.auth,
+            state.v1_state.auth.clone(),
         )
-        .await?;
+        .await
+        .map_err(|e| unauthorized.apply(e, AccessedObject::Table))?;
 
         // ------------------- BUSINESS LOGIC -------------------
+        // Tables below the mount point of a share are loaded read-only from the owning warehouse.
+        let share = resolve_share::<C>(
+            &warehouse_id,
+            &table.namespace,
+            state.v1_state.catalog.clone(),
+        )
+        .await?;
+        let (source_warehouse_id, source_table) = if let Some(share) = &share {
+            let source_table = share.source_table(&table).ok_or_else(|| {
+                ErrorModel::builder()
+                    .code(StatusCode::NOT_FOUND.into())
+                    .message("Table not found".to_string())
+                    .r#type("NoSuchTableError".to_string())
+                    .error_type(ErrorType::NoSuchTable)
+                    .build()
+            })?;
+            (share.warehouse_id.clone(), source_table)
+        } else {
+            (warehouse_id.clone(), table.clone())
+        };
+
+        // Signer and engine specific config of the warehouse the client
+        // is connected to, also for shares.
+        let warehouse = state
+            .v1_state
+            .resolution_cache
+            .warehouse::<C>(&warehouse_id, state.v1_state.catalog.clone())
+            .await?;
+        let signer_uri = warehouse.as_ref().and_then(|w| w.signer_uri.clone());
+        let default_access_delegation = warehouse.as_ref().and_then(|w| w.access_delegation);
+        let engine_table_config = warehouse
+            .map(|w| w.engine_table_config.clone())
+            .unwrap_or_default();
         let CatalogLoadTableResult {
             table_id,
-            namespace_id,
+            namespace_id: _,
             table_metadata,
             metadata_location,
             storage_secret_ident,
             storage_profile,
-        } = C::load_table(&warehouse_id, &table, state.v1_state.catalog).await?;
+            access_delegation,
+            policy,
+        } = C::load_table(&source_warehouse_id, &source_table, catalog_state).await?;
+        if share.is_none() {
+            state
+                .v1_state
+                .table_cache
+                .insert(
+                    &warehouse_id,
+                    &table,
+                    CachedTable {
+                        table_id: table_id.clone(),
+                        metadata_location: metadata_location.clone(),
+                    },
+                )
+                .await;
+        }
+        // Remote signing for shared tables relies on read-only signer tokens,
+        // as the consumer cannot sign requests for the owning warehouse otherwise.
+        let access_delegation = if share.is_some() && !signer_tokens_enabled() {
+            Some(AccessDelegation::Disabled)
+        } else {
+            AccessDelegation::or_default(access_delegation, default_access_delegation, &data_access)
+        };
 
         // ToDo: This is a small inefficiency: We fetch the secret even if it might
         // not be required based on the `data_access` parameter.
@@ -293,35 +510,288 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             None
         };
 
+        let mut config = storage_profile
+            .generate_table_config(
+                &source_warehouse_id,
+                table_metadata.location(),
+                &data_access,
+                access_delegation,
+                storage_secret.as_ref(),
+            )
+            .await?;
+        if signer_tokens_enabled() {
+            // Only grant write access to the data files if the caller may commit to the table.
+            // Shares are always read-only.
+            let may_commit = share.is_none() && {
+                let ownership = resolve_ownership::<C>(
+                    &warehouse_id,
+                    OwnedObject::Table(table.clone()),
+                    state.v1_state.catalog.clone(),
+                )
+                .await;
+                A::check_commit_table(
+                    &request_metadata,
+                    &warehouse_id,
+                    Some(&table_id),
+                    Some(&table.namespace),
+                    ownership.as_ref(),
+                    state.v1_state.auth,
+                )
+                .await
+                .is_ok()
+            };
+            let signer_access = if may_commit {
+                SignerAccess::ReadWrite
+            } else {
+                SignerAccess::Read
+            };
+            insert_signer_token(
+                &mut config,
+                &source_warehouse_id,
+                &table_id,
+                signer_access,
+                &request_metadata,
+            )?;
+        }
+        if let Some(policy) = policy {
+            config.extend(policy.table_config());
+        }
+        insert_signer_uri(
+            &mut config,
+            &warehouse_id,
+            signer_uri.as_deref(),
+            pyiceberg_compat.is_some(),
+        );
+        insert_refresh_credentials_endpoint(&mut config, &warehouse_id, &table);
+        engine_table_config.apply(&request_metadata.client_info().engine, &mut config);
+
         let load_table_result = LoadTableResult {
             metadata_location,
             metadata: table_metadata,
-            config: Some(
-                storage_profile
-                    .generate_table_config(
-                        &warehouse_id,
-                        &namespace_id,
-                        &table_id,
-                        &data_access,
-                        storage_secret.as_ref(),
-                    )
-                    .await?,
-            ),
+            config: Some(config),
         };
 
+        count_warehouse_operation::<C>(
+            &warehouse_id,
+            WarehouseOperation::LoadTable,
+            1,
+            &state.v1_state.resolution_cache,
+            state.v1_state.catalog.clone(),
+        )
+        .await;
+        if data_access.vended_credentials {
+            count_warehouse_operation::<C>(
+                &warehouse_id,
+                WarehouseOperation::VendCredentials,
+                1,
+                &state.v1_state.resolution_cache,
+                state.v1_state.catalog,
+            )
+            .await;
+        }
+
         Ok(load_table_result)
     }
 
+    /// Load fresh storage credentials of a table, without its metadata
+    async fn load_table_credentials(
+        parameters: TableParameters,
+        data_access: DataAccess,
+        state: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<LoadCredentialsResponse> {
+        // Credentials are issued exactly like for `loadTable`, including all
+        // validations and access checks. Only the metadata is not returned.
+        let warehouse_id = require_warehouse_id(parameters.prefix.clone())?;
+        let vended_credentials = data_access.vended_credentials;
+        let resolution_cache = state.v1_state.resolution_cache.clone();
+        let catalog_state = state.v1_state.catalog.clone();
+        let LoadTableResult {
+            metadata, config, ..
+        } = Self::load_table(parameters, data_access, state, request_metadata).await?;
+        // Already counted by `load_table` if vended credentials were requested
+        if !vended_credentials {
+            count_warehouse_operation::<C>(
+                &warehouse_id,
+                WarehouseOperation::VendCredentials,
+                1,
+                &resolution_cache,
+                catalog_state,
+            )
+            .await;
+        }
+
+        let config = config
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(key, _)| {
+                STORAGE_CONFIG_PREFIXES
+                    .iter()
+                    .any(|prefix| key.starts_with(prefix))
+            })
+            .collect();
+
+        Ok(LoadCredentialsResponse {
+            storage_credentials: vec![TableCredential {
+                prefix: metadata.location,
+                config,
+            }],
+        })
+    }
+
+    /// Pre-signed URLs to read files of a table
+    async fn presign_table_files(
+        parameters: TableParameters,
+        request: PresignRequest,
+        state: ApiContext<State<A, C, S>>,
+        request_metadata: RequestMetadata,
+    ) -> Result<PresignResponse> {
+        // ------------------- VALIDATIONS -------------------
+        let TableParameters { prefix, table } = parameters;
+        let warehouse_id = require_warehouse_id(prefix)?;
+        let table = normalize_table(&warehouse_id, table, &state.v1_state).await?;
+        validate_table_or_view_ident(&table)?;
+
+        // ------------------- AUTHZ -------------------
+        let unauthorized =
+            require_table_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state)
+                .await?;
+        let catalog_state = state.v1_state.recent_writes.catalog_state::<C>(
+            &warehouse_id,
+            &table,
+            state.v1_state.catalog.clone(),
+        );
+        let table_id = state
+            .v1_state
+            .table_cache
+            .table_id::<C>(&warehouse_id, &table, catalog_state.clone())
+            .await
+            // We can't fail before AuthZ.
+            .ok()
+            .flatten();
+        request_metadata.require_token_table(table_id.as_ref())?;
+
+        A::check_load_table(
+            &request_metadata,
+            &warehouse_id,
+            Some(&table.namespace),
+            table_id.as_ref(),
+            state.v1_state.auth.clone(),
+        )
+        .await
+        .map_err(|e| unauthorized.apply(e, AccessedObject::Table))?;
+
+        // ------------------- BUSINESS LOGIC -------------------
+        let share = resolve_share::<C>(
+            &warehouse_id,
+            &table.namespace,
+            state.v1_state.catalog.clone(),
+        )
+        .await?;
+        let (source_warehouse_id, source_table) = if let Some(share) = &share {
+            let source_table = share.source_table(&table).ok_or_else(|| {
+                ErrorModel::builder()
+                    .code(StatusCode::NOT_FOUND.into())
+                    .message("Table not found".to_string())
+                    .r#type("NoSuchTableError".to_string())
+                    .error_type(ErrorType::NoSuchTable)
+                    .build()
+            })?;
+            (share.warehouse_id.clone(), source_table)
+        } else {
+            (warehouse_id.clone(), table.clone())
+        };
+        let default_access_delegation = state
+            .v1_state
+            .resolution_cache
+            .warehouse::<C>(&warehouse_id, state.v1_state.catalog.clone())
+            .await?
+            .and_then(|w| w.access_delegation);
+        let CatalogLoadTableResult {
+            table_metadata,
+            metadata_location,
+            storage_secret_ident,
+            storage_profile,
+            access_delegation,
+            ..
+        } = C::load_table(&source_warehouse_id, &source_table, catalog_state).await?;
+
+        let no_access_requested = DataAccess {
+            vended_credentials: false,
+            remote_signing: false,
+        };
+        if AccessDelegation::or_default(
+            access_delegation,
+            default_access_delegation,
+            &no_access_requested,
+        ) == Some(AccessDelegation::Disabled)
+        {
+            return Err(ErrorModel::builder()
+                .code(StatusCode::FORBIDDEN.into())
+                .message("Access delegation is disabled for this table".to_string())
+                .r#type("AccessDelegationDisabled".to_string())
+                .build()
+                .into());
+        }
+
+        let locations = if request.locations.is_empty() {
+            metadata_location.into_iter().collect()
+        } else {
+            request.locations
+        };
+        validate_presign_locations(table_metadata.location(), &locations)?;
+
+        let storage_secret = if let Some(secret_id) = storage_secret_ident {
+            Some(
+                S::get_secret_by_id(&secret_id, state.v1_state.secrets)
+                    .await?
+                    .secret,
+            )
+        } else {
+            None
+        };
+        let expires_in = std::time::Duration::from_secs(CONFIG.presigned_url_ttl_seconds);
+        let expires_at_ms = chrono::Utc::now().timestamp_millis()
+            + i64::try_from(expires_in.as_millis()).unwrap_or(i64::MAX);
+        let urls = storage_profile
+            .presign_get(storage_secret.as_ref(), &locations, expires_in)
+            .await?;
+
+        count_warehouse_operation::<C>(
+            &warehouse_id,
+            WarehouseOperation::Sign,
+            urls.len() as u64,
+            &state.v1_state.resolution_cache,
+            state.v1_state.catalog,
+        )
+        .await;
+
+        Ok(PresignResponse {
+            urls: locations
+                .into_iter()
+                .zip(urls)
+                .map(|(location, url)| PresignedUrl { location, url })
+                .collect(),
+            expires_at_ms,
+        })
+    }
+
     /// Commit updates to a table
     #[allow(clippy::too_many_lines)]
     async fn commit_table(
-        parameters: TableParameters,
+        mut parameters: TableParameters,
         mut request: CommitTableRequest,
         state: ApiContext<State<A, C, S>>,
         request_metadata: RequestMetadata,
     ) -> Result<CommitTableResponse> {
         // ------------------- VALIDATIONS -------------------
         let warehouse_id = require_warehouse_id(parameters.prefix.clone())?;
+        parameters.table =
+            normalize_table(&warehouse_id, parameters.table, &state.v1_state).await?;
+        if let Some(identifier) = request.identifier.take() {
+            request.identifier =
+                Some(normalize_table(&warehouse_id, identifier, &state.v1_state).await?);
+        }
 
         if let Some(identifier) = &request.identifier {
             if identifier != &parameters.table {
@@ -391,46 +861,89 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         }
 
         // ------------------- AUTHZ -------------------
+        let unauthorized =
+            require_table_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state)
+                .await?;
         let include_staged = true;
         let table_id = C::table_ident_to_id(
             &warehouse_id,
             &parameters.table,
             include_staged,
-            state.v1_state.catalog.clone(),
+            state.v1_state.recent_writes.catalog_state::<C>(
+                &warehouse_id,
+                &parameters.table,
+                state.v1_state.catalog.clone(),
+            ),
         )
         .await
         // We can't fail before AuthZ.
         .ok()
         .flatten();
+        request_metadata.require_token_table(table_id.as_ref())?;
+        let ownership = resolve_ownership::<C>(
+            &warehouse_id,
+            OwnedObject::Table(parameters.table.clone()),
+            state.v1_state.catalog.clone(),
+        )
+        .await;
 
         A::check_commit_table(
             &request_metadata,
             &warehouse_id,
             table_id.as_ref(),
             Some(&parameters.table.namespace),
-            state.v1_state.auth,
+            ownership.as_ref(),
+            state.v1_state.auth.clone(),
         )
-        .await?;
+        .await
+        .map_err(|e| unauthorized.apply(e, AccessedObject::Table))?;
 
         // ------------------- BUSINESS LOGIC -------------------
+        require_writable_warehouse(&warehouse_id, &state.v1_state).await?;
+        require_locations_within_warehouse(
+            &warehouse_id,
+            &updated_locations(&request.updates),
+            &state.v1_state,
+        )
+        .await?;
         let table_id = table_id.ok_or_else(|| {
             ErrorModel::builder()
                 .code(StatusCode::NOT_FOUND.into())
                 .message(format!("Table does not exist in warehouse {warehouse_id}"))
                 .r#type("TableNotFound".to_string())
+                .error_type(ErrorType::NoSuchTable)
                 .build()
         })?;
 
-        let mut transaction = C::Transaction::begin_write(state.v1_state.catalog).await?;
+        let mut request = request;
+        if CONFIG.enrich_snapshot_summaries {
+            snapshot_summary::enrich(
+                &mut request.updates,
+                request_metadata.principal(),
+                request_metadata.request_id,
+            )?;
+        }
+
+        let _commit_permit = state.v1_state.commit_queue.acquire([table_id]).await?;
+        let table_ids = HashMap::from_iter(vec![(parameters.table.clone(), table_id)]);
+        run_commit_hooks::<C>(
+            &warehouse_id,
+            std::slice::from_mut(&mut request),
+            &table_ids,
+            &state.v1_state.commit_hooks,
+            state.v1_state.catalog.clone(),
+        )
+        .await?;
+
+        let mut transaction = C::Transaction::begin_write(state.v1_state.catalog.clone()).await?;
         // serialize body before moving it
         let body = maybe_body_to_json(&request);
 
-        let updates = updates.clone();
+        let updates = request.updates.clone();
 
         let transaction_request = CommitTransactionRequest {
             table_changes: vec![request],
         };
-        let table_ids = HashMap::from_iter(vec![(parameters.table.clone(), table_id)]);
         let result = C::commit_table_transaction(
             &warehouse_id,
             transaction_request,
@@ -461,6 +974,10 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             .check(&updates, &result.previous_table_metadata)
             .await?
             .into_result()?;
+        TableLimits::from_config().check(
+            &result.previous_table_metadata,
+            &result.commit_response.metadata,
+        )?;
         // We don't commit the transaction yet, first we need to write the metadata file.
         let storage_secret = if let Some(secret_id) = &result.storage_config.storage_secret_ident {
             Some(
@@ -476,7 +993,8 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         let file_io = result
             .storage_config
             .storage_profile
-            .file_io(storage_secret.as_ref())?;
+            .file_io(storage_secret.as_ref())
+            .await?;
         write_metadata_file(
             &result.commit_response.metadata_location,
             &result.commit_response.metadata,
@@ -484,27 +1002,65 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         )
         .await?;
 
+        C::record_table_commits(
+            &[TableCommit::new(
+                table_id,
+                &result,
+                request_metadata.principal(),
+            )],
+            transaction.transaction(),
+        )
+        .await?;
+        let lineage = LineageEvent::from_commit(
+            &result.previous_table_metadata,
+            &result.commit_response.metadata,
+        );
         transaction.commit().await?;
-        emit_change_event(
-            EventMetadata {
+        store_lineage_events(
+            state.v1_state.stores.lineage.as_ref(),
+            &warehouse_id,
+            table_id,
+            &lineage,
+        )
+        .await;
+        state
+            .v1_state
+            .recent_writes
+            .record(&warehouse_id, &parameters.table);
+        count_warehouse_operation::<C>(
+            &warehouse_id,
+            WarehouseOperation::CommitTable,
+            1,
+            &state.v1_state.resolution_cache,
+            state.v1_state.catalog,
+        )
+        .await;
+        let body = with_commit_diff(body, &CommitDiff::from_commit(&result));
+        let event_metadata = EventMetadata {
+            warehouse_id: *warehouse_id.as_uuid(),
+            subject: EventSubject::Table {
                 table_id: *table_id.as_uuid(),
-                warehouse_id: *warehouse_id.as_uuid(),
-                name: parameters.table.name,
                 namespace: parameters.table.namespace.encode_in_url(),
-                prefix: parameters
-                    .prefix
-                    .map(crate::api::iceberg::types::Prefix::into_string)
-                    .unwrap_or_default(),
-
-                num_events: 1,
-                sequence_number: 0,
-                trace_id: request_metadata.request_id,
+                name: parameters.table.name,
             },
+            prefix: parameters
+                .prefix
+                .map(crate::api::iceberg::types::Prefix::into_string)
+                .unwrap_or_default(),
+
+            num_events: 1,
+            sequence_number: 0,
+            trace_id: request_metadata.request_id,
+            tenant_id: request_metadata.tenant_id().map(ToString::to_string),
+        };
+        emit_change_event(
+            event_metadata.clone(),
             body,
-            "updateTable",
+            EventType::TableCommitted,
             state.v1_state.publisher.clone(),
         )
         .await;
+        emit_lineage_events(event_metadata, &lineage, state.v1_state.publisher.clone()).await;
 
         Ok(result.commit_response)
     }
@@ -518,9 +1074,12 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         // ------------------- VALIDATIONS -------------------
         let TableParameters { prefix, table } = parameters;
         let warehouse_id = require_warehouse_id(prefix.clone())?;
+        let table = normalize_table(&warehouse_id, table, &state.v1_state).await?;
         validate_table_or_view_ident(&table)?;
 
         // ------------------- AUTHZ -------------------
+        let unauthorized =
+            require_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state).await?;
         let include_staged = true;
         let table_id = C::table_ident_to_id(
             &warehouse_id,
@@ -532,28 +1091,38 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         // We can't fail before AuthZ.
         .ok()
         .flatten();
+        let ownership = resolve_ownership::<C>(
+            &warehouse_id,
+            OwnedObject::Table(table.clone()),
+            state.v1_state.catalog.clone(),
+        )
+        .await;
 
         A::check_drop_table(
             &request_metadata,
             &warehouse_id,
             table_id.as_ref(),
-            state.v1_state.auth,
+            ownership.as_ref(),
+            state.v1_state.auth.clone(),
         )
-        .await?;
+        .await
+        .map_err(|e| unauthorized.apply(e, AccessedObject::Table))?;
 
         // ------------------- BUSINESS LOGIC -------------------
-        let mut transaction = C::Transaction::begin_write(state.v1_state.catalog).await?;
+        require_writable_warehouse(&warehouse_id, &state.v1_state).await?;
+        let mut transaction = C::Transaction::begin_write(state.v1_state.catalog.clone()).await?;
 
         let table_id = table_id.ok_or_else(|| {
             ErrorModel::builder()
                 .code(StatusCode::NOT_FOUND.into())
                 .message(format!("Table does not exist in warehouse {warehouse_id}"))
                 .r#type("TableNotFound".to_string())
+                .error_type(ErrorType::NoSuchTable)
                 .build()
         })?;
         C::drop_table(&warehouse_id, &table_id, transaction.transaction()).await?;
 
-        // ToDo: Delete metadata files
+        // Metadata files are deleted by the garbage collector once the retention period expired.
         state
             .v1_state
             .contract_verifiers
@@ -562,22 +1131,34 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             .into_result()?;
 
         transaction.commit().await?;
+        state.v1_state.recent_writes.record(&warehouse_id, &table);
+        count_warehouse_operation::<C>(
+            &warehouse_id,
+            WarehouseOperation::DropTable,
+            1,
+            &state.v1_state.resolution_cache,
+            state.v1_state.catalog,
+        )
+        .await;
 
         emit_change_event(
             EventMetadata {
-                table_id: *table_id.as_uuid(),
                 warehouse_id: *warehouse_id.as_uuid(),
-                name: table.name,
-                namespace: table.namespace.encode_in_url(),
+                subject: EventSubject::Table {
+                    table_id: *table_id.as_uuid(),
+                    namespace: table.namespace.encode_in_url(),
+                    name: table.name,
+                },
                 prefix: prefix
                     .map(crate::api::iceberg::types::Prefix::into_string)
                     .unwrap_or_default(),
                 num_events: 1,
                 sequence_number: 0,
                 trace_id: request_metadata.request_id,
+                tenant_id: request_metadata.tenant_id().map(ToString::to_string),
             },
             serde_json::Value::Null,
-            "dropTable",
+            EventType::TableDropped,
             state.v1_state.publisher,
         )
         .await;
@@ -594,20 +1175,25 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         // ------------------- VALIDATIONS -------------------
         let TableParameters { prefix, table } = parameters;
         let warehouse_id = require_warehouse_id(prefix.clone())?;
+        let table = normalize_table(&warehouse_id, table, &state.v1_state).await?;
         validate_table_or_view_ident(&table)?;
 
         // ------------------- AUTHZ -------------------
-        let include_staged = false;
-        let table_id = C::table_ident_to_id(
+        let unauthorized =
+            require_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state).await?;
+        let catalog_state = state.v1_state.recent_writes.catalog_state::<C>(
             &warehouse_id,
             &table,
-            include_staged,
             state.v1_state.catalog.clone(),
-        )
-        .await
-        // We can't fail before AuthZ.
-        .ok()
-        .flatten();
+        );
+        let table_id = state
+            .v1_state
+            .table_cache
+            .table_id::<C>(&warehouse_id, &table, catalog_state.clone())
+            .await
+            // We can't fail before AuthZ.
+            .ok()
+            .flatten();
 
         A::check_table_exists(
             &request_metadata,
@@ -616,29 +1202,34 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             table_id.as_ref(),
             state.v1_state.auth,
         )
-        .await?;
+        .await
+        .map_err(|e| unauthorized.apply(e, AccessedObject::Table))?;
 
         // ------------------- BUSINESS LOGIC -------------------
-        let include_staged = false;
-        C::table_ident_to_id(
-            &warehouse_id,
-            &table,
-            include_staged,
-            state.v1_state.catalog,
-        )
-        .await
-        .map(|r| {
-            if r.is_some() {
-                Ok(())
-            } else {
-                Err(ErrorModel::builder()
-                    .code(StatusCode::NOT_FOUND.into())
-                    .message(format!("Table does not exist in warehouse {warehouse_id}"))
-                    .r#type("TableNotFound".to_string())
-                    .build()
-                    .into())
-            }
-        })?
+        let exists = if state
+            .v1_state
+            .table_cache
+            .get(&warehouse_id, &table)
+            .await
+            .is_some()
+        {
+            true
+        } else {
+            let include_staged = false;
+            C::table_exists(&warehouse_id, &table, include_staged, catalog_state).await?
+        };
+
+        if exists {
+            Ok(())
+        } else {
+            Err(ErrorModel::builder()
+                .code(StatusCode::NOT_FOUND.into())
+                .message(format!("Table does not exist in warehouse {warehouse_id}"))
+                .r#type("TableNotFound".to_string())
+                .error_type(ErrorType::NoSuchTable)
+                .build()
+                .into())
+        }
     }
 
     /// Rename a table
@@ -655,10 +1246,14 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             source,
             destination,
         } = request;
+        let source = normalize_table(&warehouse_id, source, &state.v1_state).await?;
+        let destination = normalize_table(&warehouse_id, destination, &state.v1_state).await?;
         validate_table_or_view_ident(&source)?;
         validate_table_or_view_ident(&destination)?;
 
         // ------------------- AUTHZ -------------------
+        let unauthorized =
+            require_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state).await?;
         let include_staged = false;
         let source_id = C::table_ident_to_id(
             &warehouse_id,
@@ -670,23 +1265,34 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         // We can't fail before AuthZ.
         .ok()
         .flatten();
+        let ownership = resolve_ownership::<C>(
+            &warehouse_id,
+            OwnedObject::Table(source.clone()),
+            state.v1_state.catalog.clone(),
+        )
+        .await;
 
         // We need to be allowed to delete the old table and create the new one
         let rename_check = A::check_rename_table(
             &request_metadata,
             &warehouse_id,
             source_id.as_ref(),
+            ownership.as_ref(),
             state.v1_state.auth.clone(),
         );
         let create_check = A::check_create_table(
             &request_metadata,
             &warehouse_id,
             &destination.namespace,
-            state.v1_state.auth,
+            state.v1_state.auth.clone(),
         );
-        futures::try_join!(rename_check, create_check)?;
+        futures::try_join!(
+            rename_check.map_err(|e| unauthorized.apply(e, AccessedObject::Table)),
+            create_check.map_err(|e| unauthorized.apply(e, AccessedObject::Namespace))
+        )?;
 
         // ------------------- BUSINESS LOGIC -------------------
+        require_writable_warehouse(&warehouse_id, &state.v1_state).await?;
         if source == destination {
             return Ok(());
         }
@@ -700,6 +1306,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
                     "Source table does not exist in warehouse {warehouse_id}"
                 ))
                 .r#type("TableNotFound".to_string())
+                .error_type(ErrorType::NoSuchTable)
                 .build()
         })?;
 
@@ -721,20 +1328,28 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             .into_result()?;
 
         transaction.commit().await?;
+        state.v1_state.recent_writes.record(&warehouse_id, &source);
+        state
+            .v1_state
+            .recent_writes
+            .record(&warehouse_id, &destination);
 
         emit_change_event(
             EventMetadata {
-                table_id: *source_id.as_uuid(),
                 warehouse_id: *warehouse_id.as_uuid(),
-                name: source.name,
-                namespace: source.namespace.encode_in_url(),
+                subject: EventSubject::Table {
+                    table_id: *source_id.as_uuid(),
+                    namespace: source.namespace.encode_in_url(),
+                    name: source.name,
+                },
                 prefix: prefix.map(Prefix::into_string).unwrap_or_default(),
                 num_events: 1,
                 sequence_number: 0,
                 trace_id: request_metadata.request_id,
+                tenant_id: request_metadata.tenant_id().map(ToString::to_string),
             },
             body,
-            "renameTable",
+            EventType::TableRenamed,
             state.v1_state.publisher.clone(),
         )
         .await;
@@ -747,12 +1362,18 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
     // ToDo: Split some of this into helper functions
     async fn commit_transaction(
         prefix: Option<Prefix>,
-        request: CommitTransactionRequest,
+        mut request: CommitTransactionRequest,
         state: ApiContext<State<A, C, S>>,
         request_metadata: RequestMetadata,
     ) -> Result<()> {
         // ------------------- VALIDATIONS -------------------
         let warehouse_id = require_warehouse_id(prefix.clone())?;
+        for change in &mut request.table_changes {
+            if let Some(identifier) = change.identifier.take() {
+                change.identifier =
+                    Some(normalize_table(&warehouse_id, identifier, &state.v1_state).await?);
+            }
+        }
         let CommitTransactionRequest { table_changes } = &request;
         for change in table_changes {
             let CommitTableRequest {
@@ -783,49 +1404,73 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         }
 
         // ------------------- AUTHZ -------------------
+        let unauthorized =
+            require_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state).await?;
         let include_staged = true;
         let identifiers = table_changes
             .iter()
             .filter_map(|change| change.identifier.as_ref())
             .collect::<HashSet<_>>();
+        let catalog_state = if identifiers
+            .iter()
+            .any(|table| state.v1_state.recent_writes.contains(&warehouse_id, table))
+        {
+            C::primary_state(state.v1_state.catalog.clone())
+        } else {
+            state.v1_state.catalog.clone()
+        };
 
-        let table_ids = C::table_idents_to_ids(
-            &warehouse_id,
-            identifiers,
-            include_staged,
-            state.v1_state.catalog.clone(),
-        )
-        .await
-        .map_err(|e| {
-            ErrorModel::builder()
-                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
-                .message("Error fetching table ids".to_string())
-                .r#type("TableIdsFetchError".to_string())
-                .stack(Some(
-                    vec![e.error.message, e.error.r#type]
-                        .into_iter()
-                        .chain(e.error.stack.unwrap_or_default().into_iter())
-                        .collect(),
-                ))
-                .build()
-        })?;
+        let table_ids =
+            C::table_idents_to_ids(&warehouse_id, identifiers, include_staged, catalog_state)
+                .await
+                .map_err(|e| {
+                    ErrorModel::builder()
+                        .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                        .message("Error fetching table ids".to_string())
+                        .r#type("TableIdsFetchError".to_string())
+                        .stack(Some(
+                            vec![e.error.message, e.error.r#type]
+                                .into_iter()
+                                .chain(e.error.stack.unwrap_or_default().into_iter())
+                                .collect(),
+                        ))
+                        .build()
+                })?;
 
+        let ownerships = futures::future::join_all(table_ids.keys().map(|table_ident| {
+            resolve_ownership::<C>(
+                &warehouse_id,
+                OwnedObject::Table(table_ident.clone()),
+                state.v1_state.catalog.clone(),
+            )
+        }))
+        .await;
         let auth_checks = table_ids
             .iter()
-            .map(|(table_ident, table_id)| {
+            .zip(&ownerships)
+            .map(|((table_ident, table_id), ownership)| {
                 A::check_commit_table(
                     &request_metadata,
                     &warehouse_id,
                     table_id.as_ref(),
                     Some(&table_ident.namespace),
+                    ownership.as_ref(),
                     state.v1_state.auth.clone(),
                 )
             })
             .collect::<Vec<_>>();
 
-        futures::future::try_join_all(auth_checks).await?;
+        futures::future::try_join_all(auth_checks)
+            .await
+            .map_err(|e| unauthorized.apply(e, AccessedObject::Table))?;
 
         // ------------------- BUSINESS LOGIC -------------------
+        require_writable_warehouse(&warehouse_id, &state.v1_state).await?;
+        let locations = table_changes
+            .iter()
+            .flat_map(|change| updated_locations(&change.updates))
+            .collect::<Vec<_>>();
+        require_locations_within_warehouse(&warehouse_id, &locations, &state.v1_state).await?;
         let table_ids = table_ids
             .into_iter()
             .map(|(table_ident, table_id)| {
@@ -838,13 +1483,37 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
                             "Table {table_ident:#?} does not exist in warehouse {warehouse_id}"
                         ))
                         .r#type("TableNotFound".to_string())
+                        .error_type(ErrorType::NoSuchTable)
                         .build()
                         .into())
                 }
             })
             .collect::<Result<std::collections::HashMap<_, _>>>()?;
 
-        let mut transaction = C::Transaction::begin_write(state.v1_state.catalog).await?;
+        if CONFIG.enrich_snapshot_summaries {
+            for change in &mut request.table_changes {
+                snapshot_summary::enrich(
+                    &mut change.updates,
+                    request_metadata.principal(),
+                    request_metadata.request_id,
+                )?;
+            }
+        }
+
+        let _commit_permit = state
+            .v1_state
+            .commit_queue
+            .acquire(table_ids.values().copied())
+            .await?;
+        run_commit_hooks::<C>(
+            &warehouse_id,
+            &mut request.table_changes,
+            &table_ids,
+            &state.v1_state.commit_hooks,
+            state.v1_state.catalog.clone(),
+        )
+        .await?;
+        let mut transaction = C::Transaction::begin_write(state.v1_state.catalog.clone()).await?;
 
         // serialize request body before moving it here
         let mut events = vec![];
@@ -883,6 +1552,14 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             .map(ContractVerificationOutcome::into_result)
             .collect::<Result<Vec<()>, ErrorModel>>()?;
 
+        let table_limits = TableLimits::from_config();
+        for response in &commit_response {
+            table_limits.check(
+                &response.previous_table_metadata,
+                &response.commit_response.metadata,
+            )?;
+        }
+
         // We don't commit the transaction yet, first we need to write the metadata file.
         // Fetch all secrets concurrently
         let storage_secrets = futures::future::try_join_all(
@@ -901,23 +1578,20 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             .collect();
 
         // Write metadata files
-        let commit_response_with_io = commit_response
-            .iter()
-            .map(|r| {
+        let commit_response_with_io =
+            futures::future::try_join_all(commit_response.iter().map(|r| async {
                 let storage_secret = r
                     .storage_config
                     .storage_secret_ident
                     .as_ref()
-                    .and_then(|secret_id| storage_secrets.get(secret_id))
-                    .cloned();
-                let file_io = r
-                    .storage_config
+                    .and_then(|secret_id| storage_secrets.get(secret_id));
+                r.storage_config
                     .storage_profile
-                    .file_io(storage_secret.as_ref())
-                    .map(|io| (r, io));
-                file_io
-            })
-            .collect::<Result<Vec<_>>>()?;
+                    .file_io(storage_secret)
+                    .await
+                    .map(|io| (r, io))
+            }))
+            .await?;
 
         let mut write_futures = vec![];
         for response in &commit_response_with_io {
@@ -931,37 +1605,100 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
 
         futures::future::try_join_all(write_futures).await?;
 
+        let table_commits = event_table_ids
+            .iter()
+            .zip(&commit_response)
+            .map(|((_, table_id), response)| {
+                TableCommit::new(*table_id, response, request_metadata.principal())
+            })
+            .collect::<Vec<_>>();
+        C::record_table_commits(&table_commits, transaction.transaction()).await?;
+        let lineage = commit_response
+            .iter()
+            .map(|response| {
+                LineageEvent::from_commit(
+                    &response.previous_table_metadata,
+                    &response.commit_response.metadata,
+                )
+            })
+            .collect::<Vec<_>>();
         transaction.commit().await?;
+        for ((_, table_id), events) in event_table_ids.iter().zip(&lineage) {
+            store_lineage_events(
+                state.v1_state.stores.lineage.as_ref(),
+                &warehouse_id,
+                *table_id,
+                events,
+            )
+            .await;
+        }
+        for (table_ident, _) in &event_table_ids {
+            state
+                .v1_state
+                .recent_writes
+                .record(&warehouse_id, table_ident);
+        }
+        count_warehouse_operation::<C>(
+            &warehouse_id,
+            WarehouseOperation::CommitTable,
+            commit_response.len() as u64,
+            &state.v1_state.resolution_cache,
+            state.v1_state.catalog,
+        )
+        .await;
         let number_of_events = events.len();
 
-        for (event_sequence_number, (body, (table_ident, table_id))) in
-            events.into_iter().zip(event_table_ids).enumerate()
+        for (event_sequence_number, (((body, (table_ident, table_id)), response), lineage)) in
+            events
+                .into_iter()
+                .zip(event_table_ids)
+                .zip(&commit_response)
+                .zip(&lineage)
+                .enumerate()
         {
-            emit_change_event(
-                EventMetadata {
+            let body = with_commit_diff(body, &CommitDiff::from_commit(response));
+            let event_metadata = EventMetadata {
+                warehouse_id: *warehouse_id.as_uuid(),
+                subject: EventSubject::Table {
                     table_id: *table_id.as_uuid(),
-                    warehouse_id: *warehouse_id.as_uuid(),
-                    name: table_ident.name,
                     namespace: table_ident.namespace.encode_in_url(),
-                    prefix: prefix
-                        .clone()
-                        .map(|p| p.as_str().to_string())
-                        .unwrap_or_default(),
-                    num_events: number_of_events,
-                    sequence_number: event_sequence_number,
-                    trace_id: request_metadata.request_id,
+                    name: table_ident.name,
                 },
+                prefix: prefix
+                    .clone()
+                    .map(|p| p.as_str().to_string())
+                    .unwrap_or_default(),
+                num_events: number_of_events,
+                sequence_number: event_sequence_number,
+                trace_id: request_metadata.request_id,
+                tenant_id: request_metadata.tenant_id().map(ToString::to_string),
+            };
+            emit_change_event(
+                event_metadata.clone(),
                 body,
-                "updateTable",
+                EventType::TableCommitted,
                 state.v1_state.publisher.clone(),
             )
             .await;
+            emit_lineage_events(event_metadata, lineage, state.v1_state.publisher.clone()).await;
         }
 
         Ok(())
     }
 }
 
+/// Resolve the share mounted at the first element of `namespace`, if any.
+async fn resolve_share<C: Catalog>(
+    warehouse_id: &WarehouseIdent,
+    namespace: &NamespaceIdent,
+    catalog_state: C::State,
+) -> Result<Option<ResolvedShare>> {
+    let Some(mount_name) = namespace.as_ref().first() else {
+        return Ok(None);
+    };
+    C::resolve_share(warehouse_id, mount_name, catalog_state).await
+}
+
 fn require_no_location_specified(location: &Option<String>) -> Result<()> {
     if location.is_some() {
         return Err(ErrorModel::builder()
@@ -974,6 +1711,167 @@ fn require_no_location_specified(location: &Option<String>) -> Result<()> {
     Ok(())
 }
 
+/// Table properties that make clients write files outside of the table location.
+const LOCATION_PROPERTIES: [&str; 4] = [
+    "write.data.path",
+    "write.metadata.path",
+    "write.object-storage.path",
+    "write.folder-storage.path",
+];
+
+fn location_properties(properties: &HashMap<String, String>) -> Vec<&str> {
+    LOCATION_PROPERTIES
+        .iter()
+        .filter_map(|key| properties.get(*key))
+        .map(String::as_str)
+        .collect()
+}
+
+/// Locations a table is moved to or its files are written to after `updates`.
+fn updated_locations(updates: &[TableUpdate]) -> Vec<&str> {
+    updates
+        .iter()
+        .flat_map(|update| match update {
+            TableUpdate::SetLocation { location } => vec![location.as_str()],
+            TableUpdate::SetProperties { updates } => location_properties(updates),
+            _ => vec![],
+        })
+        .collect()
+}
+
+/// Unless the warehouse allows external locations, tables must stay within
+/// the storage the credentials and signatures of the warehouse are issued for.
+async fn require_locations_within_warehouse<A: AuthZHandler, C: Catalog, S: SecretStore>(
+    warehouse_id: &WarehouseIdent,
+    locations: &[&str],
+    state: &State<A, C, S>,
+) -> Result<()> {
+    if locations.is_empty() {
+        return Ok(());
+    }
+    let Some(warehouse) = state
+        .resolution_cache
+        .warehouse::<C>(warehouse_id, state.catalog.clone())
+        .await?
+    else {
+        return Ok(());
+    };
+    if warehouse.allow_external_locations {
+        return Ok(());
+    }
+    locations
+        .iter()
+        .try_for_each(|location| warehouse.storage_profile.require_location_within(location))
+}
+
+/// Only files below the location of the table may be pre-signed.
+/// Dot segments are rejected, as they would be resolved when building the URL.
+fn validate_presign_locations(table_location: &str, locations: &[String]) -> Result<()> {
+    let prefix = format!("{}/", table_location.trim_end_matches('/'));
+    if let Some(location) = locations.iter().find(|l| {
+        !l.starts_with(&prefix)
+            || l.split('/')
+                .any(|segment| segment == ".." || segment == ".")
+    }) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::FORBIDDEN.into())
+            .message(format!(
+                "Location '{location}' is not below the table location '{table_location}'"
+            ))
+            .r#type("LocationOutsideTable".to_string())
+            .build()
+            .into());
+    }
+    Ok(())
+}
+
+/// Add a short-lived signer token to the table config if remote signing
+/// is enabled for the table and signer tokens are configured.
+fn insert_signer_token(
+    config: &mut HashMap<String, String>,
+    warehouse_id: &WarehouseIdent,
+    table_id: &TableIdentUuid,
+    access: SignerAccess,
+    request_metadata: &RequestMetadata,
+) -> Result<()> {
+    if config.get("s3.remote-signing-enabled").map(String::as_str) != Some("true") {
+        return Ok(());
+    }
+
+    if let Some(token) = mint_signer_token(warehouse_id, table_id, access, request_metadata)? {
+        config.insert(SIGNER_TOKEN_CONFIG_KEY.to_string(), token);
+    }
+
+    Ok(())
+}
+
+/// Prefixes of table config keys that configure the `FileIO` of a table.
+const STORAGE_CONFIG_PREFIXES: &[&str] = &["s3.", "gcs."];
+/// Table config keys of short-lived credentials that need to be refreshed.
+const EXPIRING_CREDENTIAL_KEYS: &[&str] = &[
+    SIGNER_TOKEN_CONFIG_KEY,
+    "s3.session-token",
+    "gcs.oauth2.token",
+];
+const REFRESH_CREDENTIALS_ENABLED_KEY: &str = "client.refresh-credentials-enabled";
+const REFRESH_CREDENTIALS_ENDPOINT_KEY: &str = "client.refresh-credentials-endpoint";
+
+/// Advertise the credentials endpoint of the table if the config contains credentials
+/// that expire, so that long-running jobs can refresh them without reloading the table.
+fn insert_refresh_credentials_endpoint(
+    config: &mut HashMap<String, String>,
+    warehouse_id: &WarehouseIdent,
+    table: &TableIdent,
+) {
+    if !EXPIRING_CREDENTIAL_KEYS
+        .iter()
+        .any(|key| config.contains_key(*key))
+    {
+        return;
+    }
+
+    config.insert(
+        REFRESH_CREDENTIALS_ENABLED_KEY.to_string(),
+        "true".to_string(),
+    );
+    config.insert(
+        REFRESH_CREDENTIALS_ENDPOINT_KEY.to_string(),
+        CONFIG
+            .table_credentials_uri(warehouse_id, table)
+            .to_string(),
+    );
+}
+
+/// Point the `FileIO` of the table to the signer of the warehouse if remote signing is enabled.
+/// `signer_uri` overrides the signer address derived from the base URI of the catalog.
+fn insert_signer_uri(
+    config: &mut HashMap<String, String>,
+    warehouse_id: &WarehouseIdent,
+    signer_uri: Option<&str>,
+    pyiceberg_compat: bool,
+) {
+    if config.get("s3.remote-signing-enabled").map(String::as_str) != Some("true") {
+        return;
+    }
+
+    if let Some(signer_uri) = signer_uri {
+        config.insert("s3.signer.uri".to_string(), signer_uri.to_string());
+    }
+
+    // The fsspec `FileIO` of older pyiceberg versions ignores `s3.signer.uri` and sends
+    // sign requests to `{uri}/v1/aws/s3/sign`. Table config only applies to the `FileIO`
+    // of the table, so `uri` can point to the signer here.
+    if pyiceberg_compat {
+        config.insert(
+            "uri".to_string(),
+            signer_uri.map_or_else(
+                || CONFIG.s3_signer_uri_for_warehouse(warehouse_id).to_string(),
+                ToString::to_string,
+            ),
+        );
+    }
+}
+
 fn require_active_warehouse(status: WarehouseStatus) -> Result<()> {
     if status != WarehouseStatus::Active {
         return Err(ErrorModel::builder()
@@ -989,12 +1887,121 @@ fn require_active_warehouse(status: WarehouseStatus) -> Result<()> {
 async fn emit_change_event(
     parameters: EventMetadata,
     body: serde_json::Value,
-    operation_id: &str,
+    typ: EventType,
     publisher: CloudEventsPublisher,
 ) {
     let _ = publisher
-        .publish(Uuid::now_v7(), operation_id, body, parameters)
+        .publish(Uuid::now_v7(), typ, body, parameters)
+        .await;
+}
+
+/// Run the commit hooks for each change and add their annotations to its updates.
+/// Tables are loaded from the primary, so that hooks see the latest metadata.
+async fn run_commit_hooks<C: Catalog>(
+    warehouse_id: &WarehouseIdent,
+    changes: &mut [CommitTableRequest],
+    table_ids: &HashMap<TableIdent, TableIdentUuid>,
+    hooks: &CommitHooks,
+    catalog_state: C::State,
+) -> Result<()> {
+    if hooks.is_empty() {
+        return Ok(());
+    }
+
+    let tables = C::load_tables(
+        warehouse_id,
+        changes
+            .iter()
+            .filter_map(|change| change.identifier.as_ref())
+            .collect(),
+        C::primary_state(catalog_state),
+    )
+    .await?;
+    let metadata = tables
+        .into_iter()
+        .map(|table| (table.table_id, table.table_metadata))
+        .collect::<HashMap<_, _>>();
+
+    for change in changes {
+        let Some((table, table_id)) = change
+            .identifier
+            .as_ref()
+            .and_then(|identifier| table_ids.get_key_value(identifier))
+        else {
+            continue;
+        };
+        let annotations = hooks
+            .before_commit(&CommitContext {
+                warehouse_id,
+                table_id: *table_id,
+                table,
+                previous_metadata: metadata.get(table_id),
+                requirements: &change.requirements,
+                updates: &change.updates,
+            })
+            .await?;
+        if !annotations.is_empty() {
+            change.updates.push(TableUpdate::SetProperties {
+                updates: annotations,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Publish a `table.lineage` event per snapshot with lineage fields. `num-events` and
+/// `sequence-number` count the lineage events of the table, not the `table.committed` events.
+async fn emit_lineage_events(
+    metadata: EventMetadata,
+    lineage: &[LineageEvent],
+    publisher: CloudEventsPublisher,
+) {
+    for (sequence_number, event) in lineage.iter().enumerate() {
+        emit_change_event(
+            EventMetadata {
+                num_events: lineage.len(),
+                sequence_number,
+                ..metadata.clone()
+            },
+            maybe_body_to_json(event),
+            EventType::TableLineage,
+            publisher.clone(),
+        )
         .await;
+    }
+}
+
+/// Store the lineage of a commit if `store_lineage_events` is enabled.
+/// Runs after the commit, so failures are only logged.
+async fn store_lineage_events(
+    store: &dyn LineageStore,
+    warehouse_id: &WarehouseIdent,
+    table_id: TableIdentUuid,
+    lineage: &[LineageEvent],
+) {
+    if !CONFIG.store_lineage_events {
+        return;
+    }
+    if let Err(e) = store
+        .record_lineage_events(warehouse_id, table_id, lineage)
+        .await
+    {
+        tracing::warn!(
+            "Failed to store lineage events of table {table_id}: {:?}",
+            e.error
+        );
+    }
+}
+
+/// Attach the diff of a commit to the body of its `table.committed` event.
+fn with_commit_diff(body: serde_json::Value, diff: &CommitDiff) -> serde_json::Value {
+    match body {
+        serde_json::Value::Object(mut body) => {
+            body.insert("diff".to_string(), maybe_body_to_json(diff));
+            serde_json::Value::Object(body)
+        }
+        body => body,
+    }
 }
 
 fn validate_table_updates(updates: &Vec<TableUpdate>) -> Result<()> {
@@ -1002,10 +2009,14 @@ fn validate_table_updates(updates: &Vec<TableUpdate>) -> Result<()> {
         match update {
             TableUpdate::SetProperties { updates } => {
                 validate_table_properties(updates.keys())?;
+                AccessDelegation::validate_properties(updates)?;
             }
             TableUpdate::RemoveProperties { removals } => {
                 validate_table_properties(removals)?;
             }
+            TableUpdate::AddSnapshot { snapshot } => {
+                snapshot_summary::validate(snapshot.summary())?;
+            }
             _ => {}
         }
     }
@@ -1,17 +1,26 @@
 use crate::api::iceberg::v1::{
-    ApiContext, CommitViewRequest, CreateViewRequest, ErrorModel, ListTablesResponse,
+    ApiContext, CommitViewRequest, CreateViewRequest, ErrorModel, ErrorType, ListViewsResponse,
     LoadViewResult, NamespaceParameters, PaginationQuery, Prefix, RenameTableRequest, Result,
     TableIdent, ViewParameters,
 };
+use crate::api::{AddViewVersionUpdate, ViewUpdate};
 use crate::request_metadata::RequestMetadata;
 use http::StatusCode;
-use std::vec;
 
 use super::tables::{
     maybe_body_to_json, validate_lowercase_property, validate_table_or_view_ident,
 };
-use super::{namespace::validate_namespace_ident, require_warehouse_id, CatalogServer};
-use crate::service::{auth::AuthZHandler, secrets::SecretStore, Catalog, State};
+use super::{
+    namespace::validate_namespace_ident, require_warehouse_access, require_warehouse_id,
+    CatalogServer,
+};
+use crate::service::{
+    auth::{AccessedObject, AuthZHandler},
+    page_token::PageQuery,
+    secrets::SecretStore,
+    view_validation::validate_view_version,
+    Catalog, State,
+};
 
 #[async_trait::async_trait]
 impl<C: Catalog, A: AuthZHandler, S: SecretStore>
@@ -20,29 +29,42 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
     /// List all view identifiers underneath a given namespace
     async fn list_views(
         parameters: NamespaceParameters,
-        _query: PaginationQuery,
+        query: PaginationQuery,
         state: ApiContext<State<A, C, S>>,
         request_metadata: RequestMetadata,
-    ) -> Result<ListTablesResponse> {
+    ) -> Result<ListViewsResponse> {
         // ------------------- VALIDATIONS -------------------
         let NamespaceParameters { namespace, prefix } = parameters;
         let warehouse_id = require_warehouse_id(prefix)?;
         validate_namespace_ident(&namespace)?;
 
         // ------------------- AUTHZ -------------------
+        let unauthorized =
+            require_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state).await?;
         A::check_list_tables(
             &request_metadata,
             &warehouse_id,
             &namespace,
             state.v1_state.auth,
         )
-        .await?;
+        .await
+        .map_err(|e| unauthorized.apply(e, AccessedObject::Namespace))?;
 
         // ------------------- BUSINESS LOGIC -------------------
-
-        Ok(ListTablesResponse {
-            next_page_token: None,
-            identifiers: vec![],
+        // Views are not stored yet. Paginate anyway, so page tokens are validated as for tables.
+        let page = PageQuery::new(
+            &query.page_token,
+            query.page_size,
+            &[
+                "list_views",
+                &warehouse_id.to_string(),
+                &namespace.clone().inner().join("\u{1f}"),
+            ],
+        )?
+        .into_page(Vec::<TableIdent>::new(), |view| view.name.clone())?;
+        Ok(ListViewsResponse {
+            next_page_token: page.next_page_token,
+            identifiers: page.items,
         })
     }
 
@@ -69,15 +91,19 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
         }
 
         validate_view_properties(request.properties.keys())?;
+        validate_view_version(&request.view_version)?;
 
         // ------------------- AUTHZ -------------------
+        let unauthorized =
+            require_warehouse_access(&request_metadata, &warehouse_id, &state.v1_state).await?;
         A::check_create_table(
             &request_metadata,
             &warehouse_id,
             &namespace,
             state.v1_state.auth,
         )
-        .await?;
+        .await
+        .map_err(|e| unauthorized.apply(e, AccessedObject::Namespace))?;
 
         // ------------------- BUSINESS LOGIC -------------------
 
@@ -103,6 +129,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             .code(StatusCode::NOT_FOUND.into())
             .message("Views are not implemented".to_string())
             .r#type("LoadViewNotSupported".to_string())
+            .error_type(ErrorType::NoSuchView)
             .build()
             .into());
     }
@@ -110,17 +137,23 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
     /// Commit updates to a view
     async fn commit_view(
         parameters: ViewParameters,
-        _request: CommitViewRequest,
+        request: CommitViewRequest,
         _state: ApiContext<State<A, C, S>>,
         _request_metadata: RequestMetadata,
     ) -> Result<LoadViewResult> {
         // ------------------- VALIDATIONS -------------------
         require_warehouse_id(parameters.prefix.clone())?;
+        for update in &request.updates {
+            if let ViewUpdate::AddViewVersion(AddViewVersionUpdate { view_version }) = update {
+                validate_view_version(view_version)?;
+            }
+        }
 
         return Err(ErrorModel::builder()
             .code(StatusCode::NOT_FOUND.into())
             .message("Views are not implemented".to_string())
             .r#type("CommitViewNotSupported".to_string())
+            .error_type(ErrorType::NoSuchView)
             .build()
             .into());
     }
@@ -140,6 +173,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             .code(StatusCode::NOT_FOUND.into())
             .message("Views are not implemented".to_string())
             .r#type("DropViewNotSupported".to_string())
+            .error_type(ErrorType::NoSuchView)
             .build()
             .into());
     }
@@ -159,6 +193,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             .code(StatusCode::NOT_FOUND.into())
             .message("Views are not implemented".to_string())
             .r#type("ViewExistsNotSupported".to_string())
+            .error_type(ErrorType::NoSuchView)
             .build()
             .into());
     }
@@ -184,6 +219,7 @@ impl<C: Catalog, A: AuthZHandler, S: SecretStore>
             .code(StatusCode::NOT_FOUND.into())
             .message("Views are not implemented".to_string())
             .r#type("RenameViewNotSupported".to_string())
+            .error_type(ErrorType::NoSuchView)
             .build()
             .into());
     }
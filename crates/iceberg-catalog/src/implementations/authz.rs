@@ -6,7 +6,7 @@ use crate::{
     implementations::DEFAULT_PROJECT_ID,
     service::{
         auth::{AuthConfigHandler, AuthZHandler, UserWarehouse},
-        TableIdentUuid,
+        ObjectOwnership, OwnedObject, RoleObject, TableIdent, TableIdentUuid,
     },
     ProjectIdent, WarehouseIdent,
 };
@@ -106,6 +106,7 @@ impl AuthZHandler for AllowAllAuthZHandler {
         _: &RequestMetadata,
         _: &WarehouseIdent,
         _: &NamespaceIdent,
+        _: Option<&ObjectOwnership>,
         _: AllowAllAuthState,
     ) -> Result<()> {
         Ok(())
@@ -115,6 +116,7 @@ impl AuthZHandler for AllowAllAuthZHandler {
         _: &RequestMetadata,
         _: &WarehouseIdent,
         _: &NamespaceIdent,
+        _: Option<&ObjectOwnership>,
         _: AllowAllAuthState,
     ) -> Result<()> {
         Ok(())
@@ -142,6 +144,7 @@ impl AuthZHandler for AllowAllAuthZHandler {
         _: &RequestMetadata,
         _: &WarehouseIdent,
         _: Option<&TableIdentUuid>,
+        _: Option<&ObjectOwnership>,
         _: AllowAllAuthState,
     ) -> Result<()> {
         Ok(())
@@ -171,6 +174,7 @@ impl AuthZHandler for AllowAllAuthZHandler {
         _: &RequestMetadata,
         _: &WarehouseIdent,
         _: Option<&TableIdentUuid>,
+        _: Option<&ObjectOwnership>,
         _: AllowAllAuthState,
     ) -> Result<()> {
         Ok(())
@@ -181,6 +185,7 @@ impl AuthZHandler for AllowAllAuthZHandler {
         _: &WarehouseIdent,
         _: Option<&TableIdentUuid>,
         _: Option<&NamespaceIdent>,
+        _: Option<&ObjectOwnership>,
         _: Self::State,
     ) -> Result<()> {
         Ok(())
@@ -214,6 +219,22 @@ impl AuthZHandler for AllowAllAuthZHandler {
         Ok(None)
     }
 
+    async fn check_update_project(
+        _: &RequestMetadata,
+        _: &ProjectIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_delete_project(
+        _: &RequestMetadata,
+        _: &ProjectIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     async fn check_delete_warehouse(
         _: &RequestMetadata,
         _: &WarehouseIdent,
@@ -261,4 +282,160 @@ impl AuthZHandler for AllowAllAuthZHandler {
     ) -> Result<()> {
         Ok(())
     }
+
+    async fn check_set_warehouse_allowlist(
+        _: &RequestMetadata,
+        _: &WarehouseIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_set_engine_table_config(
+        _: &RequestMetadata,
+        _: &WarehouseIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_set_event_filter(
+        _: &RequestMetadata,
+        _: &WarehouseIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_set_maintenance_mode(_: &RequestMetadata, _: Self::State) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_reload_config(_: &RequestMetadata, _: Self::State) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_set_log_level(_: &RequestMetadata, _: Self::State) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_set_warehouse_read_only(
+        _: &RequestMetadata,
+        _: &WarehouseIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_set_warehouse_pyiceberg_compat(
+        _: &RequestMetadata,
+        _: &WarehouseIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_set_warehouse_signer_uri(
+        _: &RequestMetadata,
+        _: &WarehouseIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_set_warehouse_unauthorized_response(
+        _: &RequestMetadata,
+        _: &WarehouseIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_set_warehouse_access_delegation(
+        _: &RequestMetadata,
+        _: &WarehouseIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_set_warehouse_allow_external_locations(
+        _: &RequestMetadata,
+        _: &WarehouseIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_set_warehouse_object_storage_layout(
+        _: &RequestMetadata,
+        _: &WarehouseIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_set_table_policy(
+        _: &RequestMetadata,
+        _: &WarehouseIdent,
+        _: &TableIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_set_warehouse_retention_policy(
+        _: &RequestMetadata,
+        _: &WarehouseIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_set_namespace_retention_policy(
+        _: &RequestMetadata,
+        _: &WarehouseIdent,
+        _: &NamespaceIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_manage_staged_tables(
+        _: &RequestMetadata,
+        _: &WarehouseIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_manage_shares(
+        _: &RequestMetadata,
+        _: &WarehouseIdent,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_transfer_ownership(
+        _: &RequestMetadata,
+        _: &WarehouseIdent,
+        _: &OwnedObject,
+        _: &ObjectOwnership,
+        _: Self::State,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_revoke_token(_: &RequestMetadata, _: Self::State) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_manage_service_keys(_: &RequestMetadata, _: Self::State) -> Result<()> {
+        Ok(())
+    }
+
+    async fn check_manage_roles(_: &RequestMetadata, _: &RoleObject, _: Self::State) -> Result<()> {
+        Ok(())
+    }
 }
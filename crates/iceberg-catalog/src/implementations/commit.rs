@@ -0,0 +1,380 @@
+//! Creation of table metadata and application of table commits, shared by the
+//! catalog implementations. Only reading and writing the rows is left to them.
+use std::collections::{HashMap, HashSet};
+
+use http::StatusCode;
+use iceberg_ext::{
+    spec::{TableMetadata, TableMetadataAggregate},
+    TableRequirement, TableUpdate,
+};
+
+use crate::api::{ErrorModel, ErrorType, Result, TableRequirementExt as _, TableUpdateExt};
+use crate::service::{
+    storage::StorageProfile, CommitTableResponse, CommitTableResponseExt, CommitTransactionRequest,
+    CreateTableRequest, GetStorageConfigResponse, MetadataLogEntry, SnapshotRetentionPolicy,
+    TableIdent, TableIdentUuid,
+};
+use crate::SecretIdent;
+
+/// Metadata of a new table with id `table_id`.
+/// Its `location` is the location of the table.
+pub(crate) fn create_table_metadata(
+    table_id: &TableIdentUuid,
+    request: CreateTableRequest,
+) -> Result<TableMetadata> {
+    let CreateTableRequest {
+        name: _,
+        location,
+        schema,
+        partition_spec,
+        write_order,
+        // Stage-create is already handled in the catalog service.
+        // If stage-create is true, the metadata_location is None,
+        // otherwise, it is the location of the metadata file.
+        stage_create: _,
+        properties,
+    } = request;
+
+    let location = location.ok_or_else(|| {
+        ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("Table location is required".to_string())
+            .r#type("CreateTableLocationRequired".to_string())
+            .build()
+    })?;
+
+    let mut builder = TableMetadataAggregate::new(location, schema);
+    if let Some(partition_spec) = partition_spec {
+        builder.add_partition_spec(partition_spec)?;
+        builder.set_default_partition_spec(-1)?;
+    }
+    if let Some(write_order) = write_order {
+        builder.add_sort_order(write_order)?;
+        builder.set_default_sort_order(-1)?;
+    }
+    builder.set_properties(properties.unwrap_or_default())?;
+    builder.assign_uuid(table_id.as_uuid().to_owned())?;
+
+    Ok(builder.build()?)
+}
+
+pub(crate) fn serialize_metadata(metadata: &TableMetadata) -> Result<serde_json::Value> {
+    Ok(serde_json::to_value(metadata).map_err(|e| {
+        ErrorModel::builder()
+            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+            .message("Error serializing table metadata".to_string())
+            .r#type("TableMetadataSerializationError".to_string())
+            .stack(Some(vec![e.to_string()]))
+            .build()
+    })?)
+}
+
+/// Row of a table locked for a commit.
+#[derive(Debug, Clone)]
+pub(crate) struct CommitRow {
+    pub(crate) table_id: uuid::Uuid,
+    pub(crate) storage_profile: StorageProfile,
+    pub(crate) storage_secret_ident: Option<SecretIdent>,
+    pub(crate) metadata: TableMetadata,
+    pub(crate) metadata_location: Option<String>,
+    pub(crate) metadata_version: i64,
+    pub(crate) retention_policy: Option<SnapshotRetentionPolicy>,
+}
+
+/// Ids of the tables in the order their rows must be locked in, so that concurrent
+/// transactions committing to an overlapping set of tables wait for each other
+/// instead of deadlocking.
+pub(crate) fn lock_order(table_ids: &HashMap<TableIdent, TableIdentUuid>) -> Vec<uuid::Uuid> {
+    let mut sorted_table_ids = table_ids
+        .values()
+        .map(|id| *id.as_uuid())
+        .collect::<Vec<_>>();
+    sorted_table_ids.sort_unstable();
+    sorted_table_ids
+}
+
+/// Pair the changes of `request` with the locked rows of their tables.
+pub(crate) fn commit_contexts(
+    request: CommitTransactionRequest,
+    table_ids: &HashMap<TableIdent, TableIdentUuid>,
+    rows: &[CommitRow],
+) -> Result<Vec<CommitContext>> {
+    let CommitTransactionRequest { table_changes } = request;
+
+    let mut contexts = vec![];
+    for change in table_changes {
+        let table_ident = change.identifier.ok_or(
+            ErrorModel::builder()
+                .code(StatusCode::BAD_REQUEST.into())
+                .message("Table identifier must be specified for all changes".to_string())
+                .r#type("TableIdentifierRequired".to_string())
+                .build(),
+        )?;
+        let table_id = table_ids.get(&table_ident).ok_or_else(|| {
+            ErrorModel::builder()
+                .code(StatusCode::BAD_REQUEST.into())
+                .message("Table identifier not found".to_string())
+                .r#type("TableIdentifierNotFound".to_string())
+                .stack(Some(vec![format!("{:?}", table_ident)]))
+                .build()
+        })?;
+
+        let row = rows
+            .iter()
+            .find(|row| &row.table_id == table_id.as_uuid())
+            .ok_or_else(|| {
+                ErrorModel::builder()
+                    .code(StatusCode::NOT_FOUND.into())
+                    .message("Table not found".to_string())
+                    .r#type("NoSuchTableError".to_string())
+                    .error_type(ErrorType::NoSuchTable)
+                    .stack(Some(vec![format!("Table Ident {:?}", table_ident)]))
+                    .build()
+            })?
+            .clone();
+
+        contexts.push(CommitContext {
+            requirements: change.requirements,
+            updates: change.updates,
+            storage_profile: row.storage_profile,
+            storage_secret_ident: row.storage_secret_ident,
+            metadata: row.metadata,
+            metadata_location: row.metadata_location,
+            metadata_version: row.metadata_version,
+            retention_policy: row.retention_policy,
+        });
+    }
+
+    Ok(contexts)
+}
+
+/// Check the requirements and apply the updates of all tables.
+/// Returns the responses together with the `metadata_version` of each table
+/// the commit is based on. The new metadata may only replace that version.
+pub(crate) fn prepare_commits(
+    contexts: Vec<CommitContext>,
+    max_tables: usize,
+) -> Result<(Vec<CommitTableResponseExt>, HashMap<uuid::Uuid, i64>)> {
+    if contexts.len() > max_tables {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("Too updates in single commit".to_string())
+            .r#type("TooManyTablesForCommit".to_string())
+            .build()
+            .into());
+    }
+
+    assert_requirements(&contexts)?;
+
+    let expected_versions = contexts
+        .iter()
+        .map(|c| (c.metadata.uuid(), c.metadata_version))
+        .collect::<HashMap<_, _>>();
+
+    Ok((apply_commits(contexts)?, expected_versions))
+}
+
+/// Responses in the order their tables were locked in, see [`lock_order`].
+/// Rows are updated in this order.
+pub(crate) fn update_order(responses: &[CommitTableResponseExt]) -> Vec<&CommitTableResponseExt> {
+    let mut sorted_responses = responses.iter().collect::<Vec<_>>();
+    sorted_responses.sort_by_key(|r| r.commit_response.metadata.uuid());
+    sorted_responses
+}
+
+/// Fail with a `409` unless all tables of `expected_versions` were updated, i.e. their
+/// `metadata_version` was still the one the commit is based on.
+pub(crate) fn assert_all_updated(
+    expected_versions: &HashMap<uuid::Uuid, i64>,
+    updated: &HashSet<uuid::Uuid>,
+) -> Result<()> {
+    // The metadata of a table changed since it was read for this commit.
+    let mut conflicting = expected_versions
+        .keys()
+        .filter(|table_id| !updated.contains(table_id))
+        .copied()
+        .collect::<Vec<_>>();
+    if conflicting.is_empty() {
+        return Ok(());
+    }
+    conflicting.sort_unstable();
+    Err(concurrent_modification(conflicting).into())
+}
+
+/// `409` for tables whose metadata changed since it was read for the commit.
+pub(crate) fn concurrent_modification(
+    table_ids: impl IntoIterator<Item = uuid::Uuid>,
+) -> ErrorModel {
+    ErrorModel::builder()
+        .code(StatusCode::CONFLICT.into())
+        .message(
+            "Commit failed because a table was modified concurrently. Refresh the table and retry."
+                .to_string(),
+        )
+        .r#type("CommitFailedException".to_string())
+        .error_type(ErrorType::CommitFailed)
+        .stack(Some(
+            table_ids
+                .into_iter()
+                .map(|table_id| format!("Table {table_id} was modified concurrently"))
+                .collect(),
+        ))
+        .build()
+}
+
+/// Current state of a table together with the changes to commit to it.
+#[derive(Debug)]
+pub(crate) struct CommitContext {
+    pub(crate) requirements: Vec<TableRequirement>,
+    pub(crate) updates: Vec<TableUpdate>,
+    pub(crate) storage_profile: StorageProfile,
+    pub(crate) storage_secret_ident: Option<SecretIdent>,
+    pub(crate) metadata: TableMetadata,
+    pub(crate) metadata_location: Option<String>,
+    pub(crate) metadata_version: i64,
+    pub(crate) retention_policy: Option<SnapshotRetentionPolicy>,
+}
+
+/// Check the requirements of all tables against their current metadata.
+pub(crate) fn assert_requirements(contexts: &[CommitContext]) -> Result<()> {
+    for context in contexts {
+        context
+            .requirements
+            .iter()
+            .map(|r| r.assert(&context.metadata, context.metadata_location.is_some()))
+            .collect::<Result<Vec<_>>>()?;
+    }
+    Ok(())
+}
+
+/// Remove the branches, tags and snapshots of `metadata` that are expired
+/// according to `policy` and the table properties.
+fn apply_retention(
+    metadata: TableMetadata,
+    policy: Option<&SnapshotRetentionPolicy>,
+    now_ms: i64,
+) -> Result<TableMetadata> {
+    let policy = policy
+        .cloned()
+        .unwrap_or_default()
+        .with_table_properties(metadata.properties());
+    if policy.is_empty() {
+        return Ok(metadata);
+    }
+    let expired_refs = policy.expired_refs(&metadata, now_ms);
+    let expired_snapshots = policy.expired_snapshots(&metadata, now_ms);
+    if expired_refs.is_empty() && expired_snapshots.is_empty() {
+        return Ok(metadata);
+    }
+
+    let mut builder = TableMetadataAggregate::new_from_metadata(metadata);
+    for ref_name in &expired_refs {
+        builder.remove_snapshot_by_ref(ref_name)?;
+    }
+    if !expired_snapshots.is_empty() {
+        builder.remove_snapshots(&expired_snapshots)?;
+    }
+    Ok(builder.build()?)
+}
+
+/// Apply the updates of all tables. The responses are in the order of `commits`.
+pub(crate) fn apply_commits(commits: Vec<CommitContext>) -> Result<Vec<CommitTableResponseExt>> {
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let mut responses = vec![];
+    for context in commits {
+        let previous_location = context.metadata.location.clone();
+        let previous_uuid = context.metadata.uuid();
+        let metadata_id = uuid::Uuid::now_v7();
+        let metadata_location = context
+            .storage_profile
+            .metadata_location(&previous_location, &metadata_id);
+        let previous_table_metadata = context.metadata.clone();
+        let mut builder = TableMetadataAggregate::new_from_metadata(context.metadata);
+        for update in context.updates {
+            match &update {
+                TableUpdate::AssignUuid { uuid } => {
+                    if uuid != &previous_uuid {
+                        return Err(ErrorModel::builder()
+                            .code(StatusCode::BAD_REQUEST.into())
+                            .message("Cannot assign a new UUID".to_string())
+                            .r#type("AssignUuidNotAllowed".to_string())
+                            .build()
+                            .into());
+                    }
+                }
+                TableUpdate::SetLocation { location } => {
+                    if location != &previous_location {
+                        return Err(ErrorModel::builder()
+                            .code(StatusCode::BAD_REQUEST.into())
+                            .message("Cannot change table location".to_string())
+                            .r#type("SetLocationNotAllowed".to_string())
+                            .build()
+                            .into());
+                    }
+                }
+                _ => {
+                    TableUpdateExt::apply(update, &mut builder)?;
+                }
+            }
+        }
+        let new_metadata =
+            apply_retention(builder.build()?, context.retention_policy.as_ref(), now_ms)?;
+        responses.push(CommitTableResponseExt {
+            commit_response: CommitTableResponse {
+                metadata_location: metadata_location.clone(),
+                metadata: new_metadata.clone(),
+                config: None,
+            },
+            storage_config: GetStorageConfigResponse {
+                storage_profile: context.storage_profile,
+                storage_secret_ident: context.storage_secret_ident,
+            },
+            previous_table_metadata,
+            previous_metadata_location: context.metadata_location,
+        });
+    }
+
+    Ok(responses)
+}
+
+/// History of metadata files of a table: the locations `recorded` by the catalog with the
+/// time they were recorded, merged with the `metadata-log` of its current `metadata`.
+/// Ordered by time.
+pub(crate) fn merge_metadata_log(
+    recorded: impl IntoIterator<Item = (String, i64)>,
+    metadata: &TableMetadata,
+) -> Vec<MetadataLogEntry> {
+    // Timestamps in the metadata-log are set by the writer of the metadata
+    // and take precedence over the time the catalog recorded the file.
+    let mut entries = recorded.into_iter().collect::<HashMap<_, _>>();
+    entries.extend(
+        metadata
+            .metadata_log
+            .iter()
+            .map(|log| (log.metadata_file.clone(), log.timestamp_ms)),
+    );
+
+    let mut entries = entries
+        .into_iter()
+        .map(|(metadata_location, timestamp_ms)| MetadataLogEntry {
+            metadata_location,
+            timestamp_ms,
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| {
+        a.timestamp_ms
+            .cmp(&b.timestamp_ms)
+            .then_with(|| a.metadata_location.cmp(&b.metadata_location))
+    });
+    entries
+}
+
+/// `404` for dropped tables that don't exist or were already reclaimed.
+pub(crate) fn dropped_table_not_found() -> ErrorModel {
+    ErrorModel::builder()
+        .code(StatusCode::NOT_FOUND.into())
+        .message("Dropped table not found".to_string())
+        .r#type("NoSuchDroppedTableError".to_string())
+        .error_type(ErrorType::NoSuchTable)
+        .build()
+}
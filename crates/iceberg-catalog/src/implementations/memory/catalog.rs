@@ -0,0 +1,2062 @@
+use std::collections::{HashMap, HashSet};
+
+use http::StatusCode;
+use iceberg_ext::spec::TableMetadataAggregate;
+
+use super::data::{
+    error, table_not_found, typed_error, warehouse_not_found, DroppedTableRecord, MemoryData,
+    NamespaceRecord, RoleAssignmentTarget, ShareRecord, ShareTarget, TableRecord, WarehouseRecord,
+};
+use super::{MemoryCatalog, MemoryCatalogState, MemoryTransaction};
+use crate::api::{CatalogConfig, ErrorModel, ErrorType, IcebergErrorResponse};
+use crate::implementations::commit::{
+    apply_commits, assert_all_updated, assert_requirements, dropped_table_not_found,
+    merge_metadata_log, CommitContext,
+};
+use crate::implementations::validation::{validate_project_name, validate_warehouse_name};
+use crate::service::{
+    auth::UnauthorizedResponse,
+    config::ConfigProvider,
+    credential_rotation::CredentialRotation,
+    page_token::PageQuery,
+    storage::{AccessDelegation, StorageProfile},
+    storage_validation::StorageValidation,
+    Catalog, CommitTableResponseExt, CommitTransactionRequest, CreateNamespaceRequest,
+    CreateNamespaceResponse, CreateTableRequest, CreateTableResponse, DroppedTable,
+    EngineTableConfig, EventFilter, GetNamespaceResponse, GetTableMetadataResponse,
+    GetWarehouseResponse, ListNamespacesQuery, ListNamespacesResponse, LoadTableResponse,
+    LoadedTable, MetadataLogEntry, NamespaceIdent, NamespaceIdentUuid, ObjectOwnership,
+    OwnedObject, Project, ProjectIdent, PyicebergCompat, ResolvedShare, Result, RetentionCandidate,
+    SecretIdent, Share, SharedObject, SnapshotRetentionPolicy, StagedTable, TableCommit,
+    TableIdent, TableIdentUuid, TablePolicy, Transaction, UpdateNamespacePropertiesRequest,
+    UpdateNamespacePropertiesResponse, WarehouseAllowlist, WarehouseIdent, WarehouseStatus,
+};
+
+fn namespace_not_found(namespace: &NamespaceIdent) -> IcebergErrorResponse {
+    typed_error(
+        StatusCode::NOT_FOUND,
+        &format!("Namespace not found: {:?}", namespace.as_ref()),
+        "NamespaceNotFound",
+        ErrorType::NoSuchNamespace,
+    )
+}
+
+fn limit_to_usize(limit: i64) -> usize {
+    usize::try_from(limit).unwrap_or_default()
+}
+
+/// Apply `update` to a warehouse with any status.
+fn update_warehouse(
+    transaction: &mut MemoryTransaction,
+    warehouse_id: &WarehouseIdent,
+    update: impl Fn(&mut WarehouseRecord) + Send + Sync + 'static,
+) -> Result<()> {
+    let warehouse_id = warehouse_id.clone();
+    transaction.apply(move |data| {
+        update(data.warehouse_mut(&warehouse_id)?);
+        Ok(())
+    })
+}
+
+fn table_metadata_response(
+    data: &MemoryData,
+    table_id: TableIdentUuid,
+    table: &TableRecord,
+    namespace: &NamespaceRecord,
+    include_staged: bool,
+) -> Result<GetTableMetadataResponse> {
+    if !include_staged && table.is_staged() {
+        return Err(typed_error(
+            StatusCode::NOT_FOUND,
+            "Table is staged and not yet created",
+            "TableStaged",
+            ErrorType::NoSuchTable,
+        ));
+    }
+
+    let warehouse = data.warehouse(&namespace.warehouse_id)?;
+    Ok(GetTableMetadataResponse {
+        table: data.table_ident(table),
+        table_id,
+        warehouse_id: namespace.warehouse_id.clone(),
+        location: table.table_location.clone(),
+        metadata_location: table.metadata_location.clone(),
+        storage_secret_ident: warehouse.warehouse.storage_secret_id.clone(),
+        storage_profile: warehouse.warehouse.storage_profile.clone(),
+        access_delegation: AccessDelegation::resolve(
+            Some(table.metadata.properties()),
+            Some(&namespace.properties),
+        ),
+    })
+}
+
+fn commit_contexts(
+    data: &MemoryData,
+    request: CommitTransactionRequest,
+    table_ids: &HashMap<TableIdent, TableIdentUuid>,
+) -> Result<Vec<CommitContext>> {
+    let CommitTransactionRequest { table_changes } = request;
+
+    let mut contexts = vec![];
+    for change in table_changes {
+        let table_ident = change.identifier.ok_or_else(|| {
+            error(
+                StatusCode::BAD_REQUEST,
+                "Table identifier must be specified for all changes",
+                "TableIdentifierRequired",
+            )
+        })?;
+        let table_id = table_ids.get(&table_ident).ok_or_else(|| {
+            ErrorModel::builder()
+                .code(StatusCode::BAD_REQUEST.into())
+                .message("Table identifier not found".to_string())
+                .r#type("TableIdentifierNotFound".to_string())
+                .stack(Some(vec![format!("{:?}", table_ident)]))
+                .build()
+        })?;
+
+        let (table, namespace) = data
+            .tables
+            .get(table_id)
+            .and_then(|table| {
+                let namespace = data.namespaces.get(&table.namespace_id)?;
+                data.active_warehouse(&namespace.warehouse_id)?;
+                Some((table, namespace))
+            })
+            .ok_or_else(|| {
+                ErrorModel::builder()
+                    .code(StatusCode::NOT_FOUND.into())
+                    .message("Table not found".to_string())
+                    .r#type("NoSuchTableError".to_string())
+                    .error_type(ErrorType::NoSuchTable)
+                    .stack(Some(vec![format!("Table Ident {:?}", table_ident)]))
+                    .build()
+            })?;
+        let warehouse = &data.warehouse(&namespace.warehouse_id)?.warehouse;
+
+        contexts.push(CommitContext {
+            requirements: change.requirements,
+            updates: change.updates,
+            storage_profile: warehouse.storage_profile.clone(),
+            storage_secret_ident: warehouse.storage_secret_id.clone(),
+            metadata: table.metadata.clone(),
+            metadata_location: table.metadata_location.clone(),
+            metadata_version: table.metadata_version,
+            retention_policy: data.retention_policy(namespace),
+        });
+    }
+
+    Ok(contexts)
+}
+
+#[async_trait::async_trait]
+impl ConfigProvider<MemoryCatalog> for MemoryCatalog {
+    async fn get_warehouse_by_name(
+        warehouse_name: &str,
+        project_id: &ProjectIdent,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<WarehouseIdent> {
+        catalog_state
+            .read()
+            .warehouses
+            .iter()
+            .find(|(_, w)| {
+                w.warehouse.name == warehouse_name
+                    && &w.warehouse.project_id == project_id
+                    && w.warehouse.status == WarehouseStatus::Active
+            })
+            .map(|(id, _)| id.clone())
+            .ok_or_else(warehouse_not_found)
+    }
+
+    async fn get_config_for_warehouse(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<CatalogConfig> {
+        let data = catalog_state.read();
+        let warehouse = &data
+            .active_warehouse(warehouse_id)
+            .ok_or_else(warehouse_not_found)?
+            .warehouse;
+
+        let mut config = warehouse
+            .storage_profile
+            .generate_catalog_config(warehouse_id);
+        if let Some(signer_uri) = &warehouse.signer_uri {
+            if config.overrides.contains_key("s3.signer.uri") {
+                config
+                    .overrides
+                    .insert("s3.signer.uri".to_string(), signer_uri.clone());
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+#[async_trait::async_trait]
+impl Catalog for MemoryCatalog {
+    type Transaction = MemoryTransaction;
+    type State = MemoryCatalogState;
+
+    fn primary_state(catalog_state: MemoryCatalogState) -> MemoryCatalogState {
+        catalog_state
+    }
+
+    async fn list_namespaces(
+        warehouse_id: &WarehouseIdent,
+        query: &ListNamespacesQuery,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<ListNamespacesResponse> {
+        let data = catalog_state.read();
+        // Treat empty parent as None
+        let parent = query.parent.as_ref().filter(|p| !p.is_empty());
+
+        let mut namespaces = data
+            .namespaces
+            .values()
+            .filter(|n| {
+                &n.warehouse_id == warehouse_id && data.active_warehouse(warehouse_id).is_some()
+            })
+            .filter_map(|n| match parent {
+                Some(parent) => {
+                    let name = n.namespace.as_ref();
+                    (name.len() == parent.len() + 1 && name.starts_with(parent.as_ref()))
+                        .then(|| NamespaceIdent::new(name[parent.len()].clone()))
+                }
+                None => Some(n.namespace.clone()),
+            })
+            .collect::<Vec<_>>();
+        namespaces.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+
+        Ok(ListNamespacesResponse {
+            next_page_token: None,
+            namespaces,
+        })
+    }
+
+    async fn create_namespace<'a>(
+        warehouse_id: &WarehouseIdent,
+        request: CreateNamespaceRequest,
+        owner: Option<&str>,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<CreateNamespaceResponse> {
+        let CreateNamespaceRequest {
+            namespace,
+            properties,
+        } = request;
+
+        let record = NamespaceRecord {
+            warehouse_id: warehouse_id.clone(),
+            namespace: namespace.clone(),
+            properties: properties.clone().unwrap_or_default(),
+            created_by: owner.map(str::to_string),
+            owner: owner.map(str::to_string),
+            retention_policy: None,
+        };
+        let namespace_id = NamespaceIdentUuid::from(uuid::Uuid::now_v7());
+        transaction.apply(move |data| {
+            if data.active_warehouse(&record.warehouse_id).is_none() {
+                return Err(warehouse_not_found());
+            }
+            if data
+                .namespace_id(&record.warehouse_id, &record.namespace)
+                .is_some()
+            {
+                return Err(typed_error(
+                    StatusCode::CONFLICT,
+                    "Namespace already exists",
+                    "NamespaceAlreadyExists",
+                    ErrorType::AlreadyExists,
+                ));
+            }
+            data.namespaces.insert(namespace_id.clone(), record.clone());
+            Ok(())
+        })?;
+
+        Ok(CreateNamespaceResponse {
+            namespace,
+            // Return None if properties is empty
+            properties: properties.filter(|p| !p.is_empty()),
+        })
+    }
+
+    async fn get_namespace<'a>(
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<GetNamespaceResponse> {
+        let data = transaction.data();
+        let namespace_id = data
+            .namespace_id(warehouse_id, namespace)
+            .ok_or_else(|| namespace_not_found(namespace))?;
+        let record = &data.namespaces[&namespace_id];
+
+        Ok(GetNamespaceResponse {
+            namespace: record.namespace.clone(),
+            namespace_id,
+            warehouse_id: warehouse_id.clone(),
+            properties: Some(record.properties.clone()),
+        })
+    }
+
+    async fn namespace_ident_to_id(
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Option<NamespaceIdentUuid>> {
+        Ok(catalog_state.read().namespace_id(warehouse_id, namespace))
+    }
+
+    async fn drop_namespace<'a>(
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        let warehouse_id = warehouse_id.clone();
+        let namespace = namespace.clone();
+        transaction.apply(move |data| {
+            let namespace_id = data
+                .namespace_id(&warehouse_id, &namespace)
+                .ok_or_else(|| namespace_not_found(&namespace))?;
+            if data.tables.values().any(|t| t.namespace_id == namespace_id) {
+                return Err(typed_error(
+                    StatusCode::CONFLICT,
+                    "Namespace is not empty",
+                    "NamespaceNotEmpty",
+                    ErrorType::NamespaceNotEmpty,
+                ));
+            }
+            data.namespaces.remove(&namespace_id);
+            data.storage_usage.remove(&namespace_id);
+            data.shares
+                .retain(|s| s.target != ShareTarget::Namespace(namespace_id.clone()));
+            data.role_assignments
+                .retain(|a| a.target != RoleAssignmentTarget::Namespace(namespace_id.clone()));
+            Ok(())
+        })
+    }
+
+    async fn update_namespace_properties<'a>(
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        request: UpdateNamespacePropertiesRequest,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<UpdateNamespacePropertiesResponse> {
+        let warehouse_id = warehouse_id.clone();
+        let namespace = namespace.clone();
+        transaction.apply(move |data| {
+            let namespace_id = data
+                .namespace_id(&warehouse_id, &namespace)
+                .ok_or_else(|| namespace_not_found(&namespace))?;
+            let properties = &mut data
+                .namespaces
+                .get_mut(&namespace_id)
+                .ok_or_else(|| namespace_not_found(&namespace))?
+                .properties;
+
+            let mut updated = vec![];
+            let mut removed = vec![];
+            let mut missing = vec![];
+            for key in request.removals.iter().flatten() {
+                if properties.remove(key).is_some() {
+                    removed.push(key.clone());
+                } else {
+                    missing.push(key.clone());
+                }
+            }
+            for (key, value) in request.updates.iter().flatten() {
+                // Push to updated if the value for the key is different.
+                // Also push on insert
+                if properties.insert(key.clone(), value.clone()).as_ref() != Some(value) {
+                    updated.push(key.clone());
+                }
+            }
+
+            Ok(UpdateNamespacePropertiesResponse {
+                updated,
+                removed,
+                missing: if missing.is_empty() {
+                    None
+                } else {
+                    Some(missing)
+                },
+            })
+        })
+    }
+
+    async fn create_table<'a>(
+        namespace_id: &NamespaceIdentUuid,
+        table: &TableIdent,
+        table_id: &TableIdentUuid,
+        request: CreateTableRequest,
+        metadata_location: Option<&String>,
+        owner: Option<&str>,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<CreateTableResponse> {
+        let CreateTableRequest {
+            name: _,
+            location,
+            schema,
+            partition_spec,
+            write_order,
+            // Stage-create is already handled in the catalog service.
+            stage_create: _,
+            properties,
+        } = request;
+
+        let location = location.ok_or_else(|| {
+            error(
+                StatusCode::BAD_REQUEST,
+                "Table location is required",
+                "CreateTableLocationRequired",
+            )
+        })?;
+
+        let mut builder = TableMetadataAggregate::new(location.clone(), schema);
+        if let Some(partition_spec) = partition_spec {
+            builder.add_partition_spec(partition_spec)?;
+            builder.set_default_partition_spec(-1)?;
+        }
+        if let Some(write_order) = write_order {
+            builder.add_sort_order(write_order)?;
+            builder.set_default_sort_order(-1)?;
+        }
+        builder.set_properties(properties.unwrap_or_default())?;
+        builder.assign_uuid(table_id.as_uuid().to_owned())?;
+        let table_metadata = builder.build()?;
+
+        let now = chrono::Utc::now();
+        let record = TableRecord {
+            namespace_id: namespace_id.clone(),
+            name: table.name.clone(),
+            metadata: table_metadata.clone(),
+            metadata_location: metadata_location.cloned(),
+            metadata_version: 0,
+            table_location: location,
+            created_at: now,
+            created_by: owner.map(str::to_string),
+            owner: owner.map(str::to_string),
+            policy: None,
+            metadata_log: metadata_location
+                .map(|metadata_location| MetadataLogEntry {
+                    metadata_location: metadata_location.clone(),
+                    timestamp_ms: now.timestamp_millis(),
+                })
+                .into_iter()
+                .collect(),
+        };
+        let table_id = *table_id;
+        transaction.apply(move |data| {
+            let namespace = data
+                .namespaces
+                .get(&record.namespace_id)
+                .filter(|n| data.active_warehouse(&n.warehouse_id).is_some())
+                .ok_or_else(|| {
+                    typed_error(
+                        StatusCode::NOT_FOUND,
+                        "Namespace not found",
+                        "NoSuchNamespaceError",
+                        ErrorType::NoSuchNamespace,
+                    )
+                })?;
+            let existing = data
+                .table_id(
+                    &namespace.warehouse_id,
+                    &TableIdent::new(namespace.namespace.clone(), record.name.clone()),
+                    true,
+                )
+                .and_then(|id| data.tables.get(&id).map(|t| (id, t)));
+
+            let mut record = record.clone();
+            // A staged table is replaced by the new table.
+            if let Some((existing_id, existing)) = existing {
+                if !existing.is_staged() {
+                    return Err(typed_error(
+                        StatusCode::CONFLICT,
+                        "Table already exists in Namespace",
+                        "TableAlreadyExists",
+                        ErrorType::AlreadyExists,
+                    ));
+                }
+                record.metadata_version = existing.metadata_version + 1;
+                data.remove_table(&existing_id);
+            }
+            data.tables.insert(table_id, record);
+            Ok(())
+        })?;
+
+        Ok(CreateTableResponse { table_metadata })
+    }
+
+    async fn list_tables(
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        include_staged: bool,
+        page: &PageQuery,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Vec<(TableIdentUuid, TableIdent)>> {
+        let data = catalog_state.read();
+        let Some(namespace_id) = data.namespace_id(warehouse_id, namespace) else {
+            return Ok(vec![]);
+        };
+
+        let tables = data
+            .tables
+            .iter()
+            .filter(|(_, t)| t.namespace_id == namespace_id && (include_staged || !t.is_staged()))
+            .map(|(id, t)| (*id, TableIdent::new(namespace.clone(), t.name.clone())))
+            .collect();
+        Ok(page.apply(tables, |(_, table)| table.name.clone()))
+    }
+
+    async fn table_ident_to_id(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        include_staged: bool,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Option<TableIdentUuid>> {
+        Ok(catalog_state
+            .read()
+            .table_id(warehouse_id, table, include_staged))
+    }
+
+    async fn table_exists(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        include_staged: bool,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<bool> {
+        Ok(catalog_state
+            .read()
+            .table_id(warehouse_id, table, include_staged)
+            .is_some())
+    }
+
+    async fn table_idents_to_ids(
+        warehouse_id: &WarehouseIdent,
+        tables: HashSet<&TableIdent>,
+        include_staged: bool,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<HashMap<TableIdent, Option<TableIdentUuid>>> {
+        let data = catalog_state.read();
+        Ok(tables
+            .into_iter()
+            .map(|table| {
+                (
+                    table.clone(),
+                    data.table_id(warehouse_id, table, include_staged),
+                )
+            })
+            .collect())
+    }
+
+    async fn load_table(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<LoadTableResponse> {
+        let data = catalog_state.read();
+        let (table_id, table, namespace) = data
+            .table_id(warehouse_id, table, false)
+            .and_then(|id| data.table(warehouse_id, &id).map(|(t, n)| (id, t, n)))
+            .ok_or_else(table_not_found)?;
+        let warehouse = &data.warehouse(warehouse_id)?.warehouse;
+
+        Ok(LoadTableResponse {
+            table_id,
+            namespace_id: table.namespace_id.clone(),
+            table_metadata: table.metadata.clone(),
+            metadata_location: table.metadata_location.clone(),
+            storage_secret_ident: warehouse.storage_secret_id.clone(),
+            storage_profile: warehouse.storage_profile.clone(),
+            access_delegation: AccessDelegation::resolve(
+                Some(table.metadata.properties()),
+                Some(&namespace.properties),
+            ),
+            policy: table.policy.clone(),
+        })
+    }
+
+    async fn get_table_metadata_by_id(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdentUuid,
+        include_staged: bool,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<GetTableMetadataResponse> {
+        let data = catalog_state.read();
+        let (record, namespace) = data
+            .table(warehouse_id, table)
+            .ok_or_else(table_not_found)?;
+        table_metadata_response(&data, *table, record, namespace, include_staged)
+    }
+
+    async fn get_table_metadata_by_s3_location(
+        warehouse_id: &WarehouseIdent,
+        location: &str,
+        include_staged: bool,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<GetTableMetadataResponse> {
+        let data = catalog_state.read();
+        // Location might also be a subpath of the table location.
+        let (table_id, (table, namespace)) = data
+            .tables
+            .iter()
+            .filter(|(_, t)| location.starts_with(&t.table_location))
+            .filter_map(|(id, _)| Some((*id, data.table(warehouse_id, id)?)))
+            .max_by_key(|(_, (t, _))| t.table_location.len())
+            .ok_or_else(|| {
+                ErrorModel::builder()
+                    .code(StatusCode::NOT_FOUND.into())
+                    .message("Table not found".to_string())
+                    .r#type("NoSuchTableError".to_string())
+                    .error_type(ErrorType::NoSuchTable)
+                    .stack(Some(vec![
+                        location.to_string(),
+                        format!("Warehouse: {}", warehouse_id),
+                    ]))
+                    .build()
+            })?;
+        table_metadata_response(&data, table_id, table, namespace, include_staged)
+    }
+
+    async fn get_table_metadata_log(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdentUuid,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Vec<MetadataLogEntry>> {
+        let data = catalog_state.read();
+        let (table, _) = data
+            .table(warehouse_id, table)
+            .filter(|(t, _)| !t.is_staged())
+            .ok_or_else(table_not_found)?;
+
+        Ok(merge_metadata_log(
+            table
+                .metadata_log
+                .iter()
+                .map(|e| (e.metadata_location.clone(), e.timestamp_ms)),
+            &table.metadata,
+        ))
+    }
+
+    async fn list_table_commits(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdentUuid,
+        before: Option<chrono::DateTime<chrono::Utc>>,
+        limit: i64,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Vec<TableCommit>> {
+        let data = catalog_state.read();
+        data.table(warehouse_id, table)
+            .filter(|(t, _)| !t.is_staged())
+            .ok_or_else(table_not_found)?;
+
+        let mut commits = data
+            .table_commits
+            .iter()
+            .filter(|c| &c.table_id == table && before.map_or(true, |b| c.committed_at < b))
+            .cloned()
+            .collect::<Vec<_>>();
+        commits.sort_by(|a, b| {
+            b.committed_at
+                .cmp(&a.committed_at)
+                .then_with(|| b.metadata_location.cmp(&a.metadata_location))
+        });
+        commits.truncate(limit_to_usize(limit));
+
+        Ok(commits)
+    }
+
+    async fn load_tables(
+        warehouse_id: &WarehouseIdent,
+        tables: HashSet<&TableIdent>,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Vec<LoadedTable>> {
+        let data = catalog_state.read();
+        Ok(tables
+            .into_iter()
+            .filter_map(|ident| {
+                let table_id = data.table_id(warehouse_id, ident, false)?;
+                let table = data.tables.get(&table_id)?;
+                Some(LoadedTable {
+                    table_id,
+                    table: ident.clone(),
+                    metadata_location: table.metadata_location.clone()?,
+                    table_metadata: table.metadata.clone(),
+                })
+            })
+            .collect())
+    }
+
+    async fn list_staged_tables(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Vec<StagedTable>> {
+        let data = catalog_state.read();
+        let mut tables = data
+            .tables
+            .keys()
+            .filter_map(|id| data.table(warehouse_id, id).map(|(t, _)| (id, t)))
+            .filter(|(_, t)| t.is_staged())
+            .map(|(id, t)| StagedTable {
+                table_id: *id,
+                table: data.table_ident(t),
+                table_location: t.table_location.clone(),
+                created_at: t.created_at,
+                created_by: t.created_by.clone(),
+            })
+            .collect::<Vec<_>>();
+        tables.sort_by_key(|t| t.created_at);
+
+        Ok(tables)
+    }
+
+    async fn discard_staged_table<'a>(
+        warehouse_id: &WarehouseIdent,
+        table_id: &TableIdentUuid,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        let warehouse_id = warehouse_id.clone();
+        let table_id = *table_id;
+        transaction.apply(move |data| {
+            data.table(&warehouse_id, &table_id)
+                .filter(|(t, _)| t.is_staged())
+                .ok_or_else(|| {
+                    typed_error(
+                        StatusCode::NOT_FOUND,
+                        "Staged table not found",
+                        "NoSuchStagedTableError",
+                        ErrorType::NoSuchTable,
+                    )
+                })?;
+            data.remove_table(&table_id);
+            Ok(())
+        })
+    }
+
+    async fn rename_table<'a>(
+        warehouse_id: &WarehouseIdent,
+        source_id: &TableIdentUuid,
+        source: &TableIdent,
+        destination: &TableIdent,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        let warehouse_id = warehouse_id.clone();
+        let source_id = *source_id;
+        let source = source.clone();
+        let destination = destination.clone();
+        transaction.apply(move |data| {
+            let namespace_id = if source.namespace == destination.namespace {
+                data.table(&warehouse_id, &source_id)
+                    .map(|(t, _)| t.namespace_id.clone())
+                    .ok_or_else(|| {
+                        typed_error(
+                            StatusCode::NOT_FOUND,
+                            "ID of Table to rename not found",
+                            "RenameTableIdNotFound",
+                            ErrorType::NoSuchTable,
+                        )
+                    })?
+            } else {
+                data.table(&warehouse_id, &source_id)
+                    .filter(|(t, _)| t.name == source.name)
+                    .and_then(|_| data.namespace_id(&warehouse_id, &destination.namespace))
+                    .ok_or_else(|| {
+                        error(
+                            StatusCode::NOT_FOUND,
+                            "ID of Table to rename not found or destination namespace not found",
+                            "RenameTableIdOrNamespaceNotFound",
+                        )
+                    })?
+            };
+            if data
+                .table_id(&warehouse_id, &destination, true)
+                .is_some_and(|id| id != source_id)
+            {
+                return Err(typed_error(
+                    StatusCode::CONFLICT,
+                    "Table already exists in Namespace",
+                    "TableAlreadyExists",
+                    ErrorType::AlreadyExists,
+                ));
+            }
+
+            if let Some(table) = data.tables.get_mut(&source_id) {
+                table.namespace_id = namespace_id;
+                table.name.clone_from(&destination.name);
+            }
+            Ok(())
+        })
+    }
+
+    async fn drop_table<'a>(
+        _: &WarehouseIdent,
+        table_id: &TableIdentUuid,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        let table_id = *table_id;
+        let dropped_at = chrono::Utc::now();
+        transaction.apply(move |data| {
+            let warehouse_id = data
+                .tables
+                .get(&table_id)
+                .and_then(|t| data.namespaces.get(&t.namespace_id))
+                .filter(|n| data.active_warehouse(&n.warehouse_id).is_some())
+                .map(|n| n.warehouse_id.clone())
+                .ok_or_else(table_not_found)?;
+            let ident = data.table_ident(&data.tables[&table_id]);
+            let Some(table) = data.remove_table(&table_id) else {
+                return Err(table_not_found());
+            };
+
+            // Keep the table until it is reclaimed by the garbage collector.
+            // Staged tables have no files and are not kept.
+            if !table.is_staged() {
+                data.dropped_tables.insert(
+                    table_id,
+                    DroppedTableRecord {
+                        warehouse_id,
+                        table: ident,
+                        table_location: table.table_location,
+                        metadata_locations: table
+                            .metadata_log
+                            .into_iter()
+                            .map(|e| e.metadata_location)
+                            .collect(),
+                        dropped_at,
+                        reclaimed_at: None,
+                    },
+                );
+            }
+            Ok(())
+        })
+    }
+
+    async fn list_expired_dropped_tables(
+        dropped_before: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Vec<DroppedTable>> {
+        let data = catalog_state.read();
+        let mut tables = data
+            .dropped_tables
+            .iter()
+            .filter(|(_, d)| d.reclaimed_at.is_none() && d.dropped_at < dropped_before)
+            .filter_map(|(id, d)| {
+                let warehouse = &data.warehouses.get(&d.warehouse_id)?.warehouse;
+                Some(DroppedTable {
+                    table_id: *id,
+                    warehouse_id: d.warehouse_id.clone(),
+                    table: d.table.clone(),
+                    table_location: d.table_location.clone(),
+                    metadata_locations: d.metadata_locations.clone(),
+                    dropped_at: d.dropped_at,
+                    storage_profile: warehouse.storage_profile.clone(),
+                    storage_secret_ident: warehouse.storage_secret_id.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+        tables.sort_by_key(|t| t.dropped_at);
+        tables.truncate(limit_to_usize(limit));
+
+        Ok(tables)
+    }
+
+    async fn reclaim_dropped_table<'a>(
+        warehouse_id: &WarehouseIdent,
+        table_id: &TableIdentUuid,
+        _storage_purged: bool,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        let warehouse_id = warehouse_id.clone();
+        let table_id = *table_id;
+        let reclaimed_at = chrono::Utc::now();
+        transaction.apply(move |data| {
+            let table = data
+                .dropped_tables
+                .get_mut(&table_id)
+                .filter(|d| d.warehouse_id == warehouse_id && d.reclaimed_at.is_none())
+                .ok_or_else(dropped_table_not_found)?;
+            table.reclaimed_at = Some(reclaimed_at);
+            Ok(())
+        })
+    }
+
+    async fn commit_table_transaction<'a>(
+        _: &WarehouseIdent,
+        request: CommitTransactionRequest,
+        table_ids: &HashMap<TableIdent, TableIdentUuid>,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<Vec<CommitTableResponseExt>> {
+        let contexts = commit_contexts(transaction.data(), request, table_ids)?;
+
+        // Check all requirements
+        assert_requirements(&contexts)?;
+
+        let expected_versions = contexts
+            .iter()
+            .map(|c| (c.metadata.uuid(), c.metadata_version))
+            .collect::<HashMap<_, _>>();
+
+        // Apply updates
+        let responses = apply_commits(contexts)?;
+
+        let commits = responses
+            .iter()
+            .map(|r| {
+                (
+                    TableIdentUuid::from(r.commit_response.metadata.uuid()),
+                    r.commit_response.metadata.clone(),
+                    r.commit_response.metadata_location.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let timestamp_ms = chrono::Utc::now().timestamp_millis();
+        transaction.apply(move |data| {
+            // Tables are only updated if all of them still have the expected version.
+            let updatable = commits
+                .iter()
+                .map(|(table_id, _, _)| *table_id.as_uuid())
+                .filter(|table_id| {
+                    data.tables
+                        .get(&TableIdentUuid::from(*table_id))
+                        .map(|t| t.metadata_version)
+                        == expected_versions.get(table_id).copied()
+                })
+                .collect::<HashSet<_>>();
+            assert_all_updated(&expected_versions, &updatable)?;
+
+            for (table_id, metadata, metadata_location) in &commits {
+                let Some(table) = data.tables.get_mut(table_id) else {
+                    continue;
+                };
+                table.metadata = metadata.clone();
+                table.metadata_location = Some(metadata_location.clone());
+                table.metadata_version += 1;
+                if !table
+                    .metadata_log
+                    .iter()
+                    .any(|e| &e.metadata_location == metadata_location)
+                {
+                    table.metadata_log.push(MetadataLogEntry {
+                        metadata_location: metadata_location.clone(),
+                        timestamp_ms,
+                    });
+                }
+            }
+            Ok(())
+        })?;
+
+        Ok(responses)
+    }
+
+    async fn record_table_commits<'a>(
+        commits: &[TableCommit],
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        let commits = commits.to_vec();
+        transaction.apply(move |data| {
+            for commit in &commits {
+                let exists = data.table_commits.iter().any(|c| {
+                    c.table_id == commit.table_id && c.metadata_location == commit.metadata_location
+                });
+                if !exists && data.tables.contains_key(&commit.table_id) {
+                    data.table_commits.push(commit.clone());
+                }
+            }
+            Ok(())
+        })
+    }
+
+    async fn create_warehouse<'a>(
+        warehouse_name: String,
+        project_id: ProjectIdent,
+        storage_profile: StorageProfile,
+        storage_secret_id: Option<SecretIdent>,
+        owner: Option<&str>,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<WarehouseIdent> {
+        validate_warehouse_name(&warehouse_name)?;
+
+        let warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        let warehouse = GetWarehouseResponse {
+            id: warehouse_id.clone(),
+            name: warehouse_name,
+            project_id,
+            storage_profile,
+            storage_secret_id,
+            status: WarehouseStatus::Active,
+            owner: owner.map(str::to_string),
+            created_by: owner.map(str::to_string),
+            allowlist: None,
+            read_only: false,
+            pyiceberg_compat: false,
+            engine_table_config: EngineTableConfig::default(),
+            signer_uri: None,
+            unauthorized_response: None,
+            access_delegation: None,
+            allow_external_locations: false,
+            object_storage_layout: false,
+            credential_rotation: None,
+            storage_validation: None,
+            event_filter: EventFilter::default(),
+        };
+        transaction.apply(move |data| {
+            if data.warehouses.values().any(|w| {
+                w.warehouse.project_id == warehouse.project_id && w.warehouse.name == warehouse.name
+            }) {
+                return Err(typed_error(
+                    StatusCode::CONFLICT,
+                    "Warehouse with this name already exists in the project.",
+                    "WarehouseNameAlreadyExists",
+                    ErrorType::AlreadyExists,
+                ));
+            }
+            // Projects are created with their first warehouse, named after their id.
+            data.projects
+                .entry(warehouse.project_id.clone())
+                .or_insert_with(|| Project::unnamed(warehouse.project_id.clone()));
+            data.warehouses.insert(
+                warehouse.id.clone(),
+                WarehouseRecord {
+                    warehouse: warehouse.clone(),
+                    retention_policy: None,
+                },
+            );
+            Ok(())
+        })?;
+
+        Ok(warehouse_id)
+    }
+
+    async fn list_projects(catalog_state: MemoryCatalogState) -> Result<HashSet<ProjectIdent>> {
+        let data = catalog_state.read();
+        Ok(data
+            .projects
+            .keys()
+            .cloned()
+            .chain(
+                data.warehouses
+                    .values()
+                    .map(|w| w.warehouse.project_id.clone()),
+            )
+            .collect())
+    }
+
+    async fn get_projects(
+        project_ids: Option<&HashSet<ProjectIdent>>,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Vec<Project>> {
+        let mut projects = catalog_state
+            .read()
+            .projects
+            .values()
+            .filter(|p| project_ids.map_or(true, |ids| ids.contains(&p.project_id)))
+            .cloned()
+            .collect::<Vec<_>>();
+        projects.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(projects)
+    }
+
+    async fn get_project_id_by_name(
+        project_name: &str,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Option<ProjectIdent>> {
+        Ok(catalog_state
+            .read()
+            .projects
+            .values()
+            .find(|p| p.name == project_name)
+            .map(|p| p.project_id.clone()))
+    }
+
+    async fn rename_project<'a>(
+        project_id: &ProjectIdent,
+        new_name: &str,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        validate_project_name(project_id, new_name)?;
+
+        let project_id = project_id.clone();
+        let new_name = new_name.to_string();
+        transaction.apply(move |data| {
+            if data
+                .projects
+                .values()
+                .any(|p| p.name == new_name && p.project_id != project_id)
+            {
+                return Err(typed_error(
+                    StatusCode::CONFLICT,
+                    "Project with this name already exists.",
+                    "ProjectNameAlreadyExists",
+                    ErrorType::AlreadyExists,
+                ));
+            }
+            data.projects
+                .entry(project_id.clone())
+                .or_insert_with(|| Project::unnamed(project_id.clone()))
+                .name
+                .clone_from(&new_name);
+            Ok(())
+        })
+    }
+
+    async fn set_project_metadata<'a>(
+        project_id: &ProjectIdent,
+        metadata: &HashMap<String, String>,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        let project_id = project_id.clone();
+        let metadata = metadata.clone();
+        transaction.apply(move |data| {
+            data.projects
+                .entry(project_id.clone())
+                .or_insert_with(|| Project::unnamed(project_id.clone()))
+                .metadata
+                .clone_from(&metadata);
+            Ok(())
+        })
+    }
+
+    async fn delete_project<'a>(
+        project_id: &ProjectIdent,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        let project_id = project_id.clone();
+        transaction.apply(move |data| {
+            if data
+                .warehouses
+                .values()
+                .any(|w| w.warehouse.project_id == project_id)
+            {
+                return Err(error(
+                    StatusCode::CONFLICT,
+                    "Project still has warehouses",
+                    "ProjectNotEmpty",
+                ));
+            }
+            data.projects.remove(&project_id).ok_or_else(|| {
+                error(
+                    StatusCode::NOT_FOUND,
+                    "Project not found",
+                    "ProjectNotFound",
+                )
+            })?;
+            data.role_assignments.retain(|a| a.project_id != project_id);
+            Ok(())
+        })
+    }
+
+    async fn list_warehouses(
+        project_id: &ProjectIdent,
+        include_inactive: Option<Vec<WarehouseStatus>>,
+        warehouse_id_filter: Option<&HashSet<WarehouseIdent>>,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Vec<GetWarehouseResponse>> {
+        let statuses = include_inactive.unwrap_or_else(|| vec![WarehouseStatus::Active]);
+        let mut warehouses = catalog_state
+            .read()
+            .warehouses
+            .values()
+            .map(|w| &w.warehouse)
+            .filter(|w| {
+                &w.project_id == project_id
+                    && statuses.contains(&w.status)
+                    && warehouse_id_filter.map_or(true, |ids| ids.contains(&w.id))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        warehouses.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(warehouses)
+    }
+
+    async fn get_warehouse_id_by_name(
+        project_id: &ProjectIdent,
+        warehouse_name: &str,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Option<WarehouseIdent>> {
+        Ok(catalog_state
+            .read()
+            .warehouses
+            .values()
+            .find(|w| &w.warehouse.project_id == project_id && w.warehouse.name == warehouse_name)
+            .map(|w| w.warehouse.id.clone()))
+    }
+
+    async fn get_warehouse<'a>(
+        warehouse_id: &WarehouseIdent,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<GetWarehouseResponse> {
+        Ok(transaction
+            .data()
+            .warehouse(warehouse_id)?
+            .warehouse
+            .clone())
+    }
+
+    async fn delete_warehouse<'a>(
+        warehouse_id: &WarehouseIdent,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        let warehouse_id = warehouse_id.clone();
+        transaction.apply(move |data| {
+            data.warehouse(&warehouse_id)?;
+            if data
+                .namespaces
+                .values()
+                .any(|n| n.warehouse_id == warehouse_id)
+            {
+                return Err(error(
+                    StatusCode::CONFLICT,
+                    "Warehouse is not empty",
+                    "WarehouseNotEmpty",
+                ));
+            }
+            data.warehouses.remove(&warehouse_id);
+            data.dropped_tables
+                .retain(|_, d| d.warehouse_id != warehouse_id);
+            data.shares.retain(|s| s.warehouse_id != warehouse_id);
+            data.role_assignments
+                .retain(|a| a.target != RoleAssignmentTarget::Warehouse(warehouse_id.clone()));
+            data.client_usage
+                .retain(|(id, _, _), _| id != &warehouse_id);
+            data.api_usage.retain(|(id, _), _| id != &warehouse_id);
+            data.storage_usage
+                .retain(|_, u| u.warehouse_id != warehouse_id);
+            Ok(())
+        })
+    }
+
+    async fn rename_warehouse<'a>(
+        warehouse_id: &WarehouseIdent,
+        new_name: &str,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        validate_warehouse_name(new_name)?;
+
+        let warehouse_id = warehouse_id.clone();
+        let new_name = new_name.to_string();
+        transaction.apply(move |data| {
+            let project_id = data
+                .active_warehouse(&warehouse_id)
+                .ok_or_else(warehouse_not_found)?
+                .warehouse
+                .project_id
+                .clone();
+            if data.warehouses.values().any(|w| {
+                w.warehouse.project_id == project_id
+                    && w.warehouse.name == new_name
+                    && w.warehouse.id != warehouse_id
+            }) {
+                return Err(typed_error(
+                    StatusCode::CONFLICT,
+                    "Warehouse with this name already exists in the project.",
+                    "WarehouseNameAlreadyExists",
+                    ErrorType::AlreadyExists,
+                ));
+            }
+            data.warehouse_mut(&warehouse_id)?
+                .warehouse
+                .name
+                .clone_from(&new_name);
+            Ok(())
+        })
+    }
+
+    async fn set_warehouse_status<'a>(
+        warehouse_id: &WarehouseIdent,
+        status: WarehouseStatus,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        update_warehouse(transaction, warehouse_id, move |w| {
+            w.warehouse.status = status;
+        })
+    }
+
+    async fn update_storage_profile<'a>(
+        warehouse_id: &WarehouseIdent,
+        storage_profile: StorageProfile,
+        storage_secret_id: Option<SecretIdent>,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        let warehouse_id = warehouse_id.clone();
+        transaction.apply(move |data| {
+            data.active_warehouse(&warehouse_id)
+                .ok_or_else(warehouse_not_found)?;
+            let warehouse = &mut data.warehouse_mut(&warehouse_id)?.warehouse;
+            warehouse.storage_profile = storage_profile.clone();
+            warehouse.storage_secret_id.clone_from(&storage_secret_id);
+            Ok(())
+        })
+    }
+
+    async fn get_warehouse_allowlist(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Option<WarehouseAllowlist>> {
+        Ok(catalog_state
+            .read()
+            .warehouses
+            .get(warehouse_id)
+            .and_then(|w| w.warehouse.allowlist.clone()))
+    }
+
+    async fn set_warehouse_allowlist<'a>(
+        warehouse_id: &WarehouseIdent,
+        allowlist: Option<WarehouseAllowlist>,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        update_warehouse(transaction, warehouse_id, move |w| {
+            w.warehouse.allowlist.clone_from(&allowlist);
+        })
+    }
+
+    async fn get_warehouse_signer_uri(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Option<String>> {
+        Ok(catalog_state
+            .read()
+            .warehouses
+            .get(warehouse_id)
+            .and_then(|w| w.warehouse.signer_uri.clone()))
+    }
+
+    async fn set_warehouse_signer_uri<'a>(
+        warehouse_id: &WarehouseIdent,
+        signer_uri: Option<&url::Url>,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        let signer_uri = signer_uri.map(|url| url.as_str().to_string());
+        update_warehouse(transaction, warehouse_id, move |w| {
+            w.warehouse.signer_uri.clone_from(&signer_uri);
+        })
+    }
+
+    async fn set_warehouse_unauthorized_response<'a>(
+        warehouse_id: &WarehouseIdent,
+        unauthorized_response: Option<UnauthorizedResponse>,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        update_warehouse(transaction, warehouse_id, move |w| {
+            w.warehouse.unauthorized_response = unauthorized_response;
+        })
+    }
+
+    async fn rotate_warehouse_storage_secret<'a>(
+        warehouse_id: &WarehouseIdent,
+        current_secret_id: Option<&SecretIdent>,
+        new_secret_id: &SecretIdent,
+        rotated_at: chrono::DateTime<chrono::Utc>,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        let warehouse_id = warehouse_id.clone();
+        let current_secret_id = current_secret_id.cloned();
+        let new_secret_id = new_secret_id.clone();
+        transaction.apply(move |data| {
+            let warehouse = data
+                .warehouses
+                .get_mut(&warehouse_id)
+                .map(|w| &mut w.warehouse)
+                .filter(|w| {
+                    w.status == WarehouseStatus::Active && w.storage_secret_id == current_secret_id
+                })
+                .ok_or_else(|| {
+                    error(
+                        StatusCode::CONFLICT,
+                        "Warehouse is not active or its credential was changed concurrently",
+                        "CredentialRotationConflict",
+                    )
+                })?;
+            warehouse.credential_rotation = Some(CredentialRotation {
+                rotated_at,
+                previous_secret_id: warehouse.storage_secret_id.take(),
+            });
+            warehouse.storage_secret_id = Some(new_secret_id.clone());
+            Ok(())
+        })
+    }
+
+    async fn clear_warehouse_previous_storage_secret(
+        warehouse_id: &WarehouseIdent,
+        previous_secret_id: &SecretIdent,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<bool> {
+        let mut data = catalog_state.write();
+        let rotation = data
+            .warehouses
+            .get_mut(warehouse_id)
+            .and_then(|w| w.warehouse.credential_rotation.as_mut())
+            .filter(|r| r.previous_secret_id.as_ref() == Some(previous_secret_id));
+        Ok(rotation.map(|r| r.previous_secret_id.take()).is_some())
+    }
+
+    async fn set_warehouse_storage_validation(
+        warehouse_id: &WarehouseIdent,
+        validation: &StorageValidation,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<()> {
+        // Warehouses deleted in the meantime are ignored
+        if let Some(warehouse) = catalog_state.write().warehouses.get_mut(warehouse_id) {
+            warehouse.warehouse.storage_validation = Some(validation.clone());
+        }
+        Ok(())
+    }
+
+    async fn set_warehouse_access_delegation<'a>(
+        warehouse_id: &WarehouseIdent,
+        access_delegation: Option<AccessDelegation>,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        update_warehouse(transaction, warehouse_id, move |w| {
+            w.warehouse.access_delegation.clone_from(&access_delegation);
+        })
+    }
+
+    async fn set_warehouse_allow_external_locations<'a>(
+        warehouse_id: &WarehouseIdent,
+        allow_external_locations: bool,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        update_warehouse(transaction, warehouse_id, move |w| {
+            w.warehouse.allow_external_locations = allow_external_locations;
+        })
+    }
+
+    async fn set_warehouse_object_storage_layout<'a>(
+        warehouse_id: &WarehouseIdent,
+        object_storage_layout: bool,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        update_warehouse(transaction, warehouse_id, move |w| {
+            w.warehouse.object_storage_layout = object_storage_layout;
+        })
+    }
+
+    async fn set_warehouse_retention_policy<'a>(
+        warehouse_id: &WarehouseIdent,
+        policy: Option<SnapshotRetentionPolicy>,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        update_warehouse(transaction, warehouse_id, move |w| {
+            w.retention_policy.clone_from(&policy);
+        })
+    }
+
+    async fn get_engine_table_config(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<EngineTableConfig> {
+        Ok(catalog_state
+            .read()
+            .warehouses
+            .get(warehouse_id)
+            .map(|w| w.warehouse.engine_table_config.clone())
+            .unwrap_or_default())
+    }
+
+    async fn set_engine_table_config<'a>(
+        warehouse_id: &WarehouseIdent,
+        config: EngineTableConfig,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        update_warehouse(transaction, warehouse_id, move |w| {
+            w.warehouse.engine_table_config.clone_from(&config);
+        })
+    }
+
+    async fn set_event_filter<'a>(
+        warehouse_id: &WarehouseIdent,
+        filter: EventFilter,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        update_warehouse(transaction, warehouse_id, move |w| {
+            w.warehouse.event_filter.clone_from(&filter);
+        })
+    }
+
+    async fn get_ownership<'a>(
+        warehouse_id: &WarehouseIdent,
+        object: &OwnedObject,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<ObjectOwnership> {
+        let data = transaction.data();
+        let (owner, created_by) = match object {
+            OwnedObject::Warehouse => {
+                let warehouse = &data.warehouse(warehouse_id)?.warehouse;
+                (&warehouse.owner, &warehouse.created_by)
+            }
+            OwnedObject::Namespace(namespace) => {
+                let namespace = data
+                    .namespace_id(warehouse_id, namespace)
+                    .and_then(|id| data.namespaces.get(&id))
+                    .ok_or_else(|| {
+                        typed_error(
+                            StatusCode::NOT_FOUND,
+                            "Namespace not found",
+                            "NamespaceNotFound",
+                            ErrorType::NoSuchNamespace,
+                        )
+                    })?;
+                (&namespace.owner, &namespace.created_by)
+            }
+            OwnedObject::Table(table) => {
+                let table = data
+                    .table_id(warehouse_id, table, true)
+                    .and_then(|id| data.tables.get(&id))
+                    .ok_or_else(table_not_found)?;
+                (&table.owner, &table.created_by)
+            }
+        };
+
+        Ok(ObjectOwnership {
+            owner: owner.clone(),
+            created_by: created_by.clone(),
+        })
+    }
+
+    async fn set_owner<'a>(
+        warehouse_id: &WarehouseIdent,
+        object: &OwnedObject,
+        new_owner: &str,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        if new_owner.is_empty() {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                "New owner cannot be empty",
+                "EmptyOwner",
+            ));
+        }
+
+        let warehouse_id = warehouse_id.clone();
+        let object = object.clone();
+        let new_owner = Some(new_owner.to_string());
+        transaction.apply(move |data| {
+            let owner = match &object {
+                OwnedObject::Warehouse => &mut data.warehouse_mut(&warehouse_id)?.warehouse.owner,
+                OwnedObject::Namespace(namespace) => {
+                    let namespace_id = data.namespace_id(&warehouse_id, namespace);
+                    &mut namespace_id
+                        .and_then(|id| data.namespaces.get_mut(&id))
+                        .ok_or_else(|| {
+                            typed_error(
+                                StatusCode::NOT_FOUND,
+                                "Namespace not found",
+                                "NamespaceNotFound",
+                                ErrorType::NoSuchNamespace,
+                            )
+                        })?
+                        .owner
+                }
+                OwnedObject::Table(table) => {
+                    let table_id = data.table_id(&warehouse_id, table, true);
+                    &mut table_id
+                        .and_then(|id| data.tables.get_mut(&id))
+                        .ok_or_else(table_not_found)?
+                        .owner
+                }
+            };
+            owner.clone_from(&new_owner);
+            Ok(())
+        })
+    }
+
+    async fn is_warehouse_read_only(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<bool> {
+        Ok(catalog_state
+            .read()
+            .warehouses
+            .get(warehouse_id)
+            .is_some_and(|w| w.warehouse.read_only))
+    }
+
+    async fn set_warehouse_read_only<'a>(
+        warehouse_id: &WarehouseIdent,
+        read_only: bool,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        update_warehouse(transaction, warehouse_id, move |w| {
+            w.warehouse.read_only = read_only;
+        })
+    }
+
+    async fn get_pyiceberg_compat(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Option<PyicebergCompat>> {
+        Ok(catalog_state
+            .read()
+            .warehouses
+            .get(warehouse_id)
+            .and_then(|w| w.warehouse.pyiceberg_compat_mode()))
+    }
+
+    async fn set_warehouse_pyiceberg_compat<'a>(
+        warehouse_id: &WarehouseIdent,
+        enabled: bool,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        update_warehouse(transaction, warehouse_id, move |w| {
+            w.warehouse.pyiceberg_compat = enabled;
+        })
+    }
+
+    async fn set_table_policy<'a>(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        policy: Option<TablePolicy>,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        let warehouse_id = warehouse_id.clone();
+        let table = table.clone();
+        transaction.apply(move |data| {
+            let table_id = data
+                .table_id(&warehouse_id, &table, true)
+                .ok_or_else(table_not_found)?;
+            if let Some(table) = data.tables.get_mut(&table_id) {
+                table.policy.clone_from(&policy);
+            }
+            Ok(())
+        })
+    }
+
+    async fn set_namespace_retention_policy<'a>(
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        policy: Option<SnapshotRetentionPolicy>,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        let warehouse_id = warehouse_id.clone();
+        let namespace = namespace.clone();
+        transaction.apply(move |data| {
+            let namespace = data
+                .namespace_id(&warehouse_id, &namespace)
+                .and_then(|id| data.namespaces.get_mut(&id))
+                .ok_or_else(|| {
+                    typed_error(
+                        StatusCode::NOT_FOUND,
+                        "Namespace not found",
+                        "NoSuchNamespaceError",
+                        ErrorType::NoSuchNamespace,
+                    )
+                })?;
+            namespace.retention_policy.clone_from(&policy);
+            Ok(())
+        })
+    }
+
+    async fn list_retention_candidates(
+        after: Option<TableIdentUuid>,
+        limit: i64,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Vec<RetentionCandidate>> {
+        let data = catalog_state.read();
+        let mut candidates = data
+            .tables
+            .iter()
+            .filter(|(id, t)| !t.is_staged() && after.map_or(true, |after| **id > after))
+            .filter_map(|(id, t)| {
+                let namespace = data.namespaces.get(&t.namespace_id)?;
+                data.active_warehouse(&namespace.warehouse_id)
+                    .filter(|w| !w.warehouse.read_only)?;
+                let policy = data.retention_policy(namespace);
+                let has_overrides = SnapshotRetentionPolicy::PROPERTIES
+                    .iter()
+                    .any(|p| t.metadata.properties().contains_key(*p));
+                (policy.is_some() || has_overrides).then(|| RetentionCandidate {
+                    warehouse_id: namespace.warehouse_id.clone(),
+                    table_id: *id,
+                    table: data.table_ident(t),
+                    policy,
+                })
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|c| c.table_id);
+        candidates.truncate(limit_to_usize(limit));
+
+        Ok(candidates)
+    }
+
+    async fn create_share<'a>(
+        warehouse_id: &WarehouseIdent,
+        object: &SharedObject,
+        consumer_project_id: &ProjectIdent,
+        mount_name: &str,
+        created_by: Option<&str>,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<uuid::Uuid> {
+        if mount_name.is_empty() {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                "Mount name cannot be empty",
+                "EmptyMountName",
+            ));
+        }
+
+        let share_id = uuid::Uuid::now_v7();
+        let warehouse_id = warehouse_id.clone();
+        let object = object.clone();
+        let consumer_project_id = consumer_project_id.clone();
+        let mount_name = mount_name.to_string();
+        let created_by = created_by.map(str::to_string);
+        transaction.apply(move |data| {
+            let target = match &object {
+                SharedObject::Namespace(namespace) => ShareTarget::Namespace(
+                    data.namespace_id(&warehouse_id, namespace).ok_or_else(|| {
+                        typed_error(
+                            StatusCode::NOT_FOUND,
+                            "Namespace not found",
+                            "NoSuchNamespaceError",
+                            ErrorType::NoSuchNamespace,
+                        )
+                    })?,
+                ),
+                SharedObject::Table(table) => ShareTarget::Table(
+                    data.table_id(&warehouse_id, table, false)
+                        .ok_or_else(table_not_found)?,
+                ),
+            };
+            if data
+                .shares
+                .iter()
+                .any(|s| s.consumer_project_id == consumer_project_id && s.mount_name == mount_name)
+            {
+                return Err(typed_error(
+                    StatusCode::CONFLICT,
+                    "The consumer project already has a share with this mount name",
+                    "ShareAlreadyExists",
+                    ErrorType::AlreadyExists,
+                ));
+            }
+            data.shares.push(ShareRecord {
+                share_id,
+                warehouse_id: warehouse_id.clone(),
+                target,
+                consumer_project_id: consumer_project_id.clone(),
+                mount_name: mount_name.clone(),
+                created_by: created_by.clone(),
+            });
+            Ok(())
+        })?;
+
+        Ok(share_id)
+    }
+
+    async fn list_shares(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Vec<Share>> {
+        let data = catalog_state.read();
+        Ok(data
+            .shares
+            .iter()
+            .filter(|s| &s.warehouse_id == warehouse_id)
+            .filter_map(|s| {
+                let object = match &s.target {
+                    ShareTarget::Namespace(id) => {
+                        SharedObject::Namespace(data.namespaces.get(id)?.namespace.clone())
+                    }
+                    ShareTarget::Table(id) => {
+                        SharedObject::Table(data.table_ident(data.tables.get(id)?))
+                    }
+                };
+                Some(Share {
+                    share_id: s.share_id,
+                    object,
+                    consumer_project_id: s.consumer_project_id.clone(),
+                    mount_name: s.mount_name.clone(),
+                    created_by: s.created_by.clone(),
+                })
+            })
+            .collect())
+    }
+
+    async fn delete_share<'a>(
+        warehouse_id: &WarehouseIdent,
+        share_id: &uuid::Uuid,
+        transaction: <Self::Transaction as Transaction<MemoryCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        let warehouse_id = warehouse_id.clone();
+        let share_id = *share_id;
+        transaction.apply(move |data| {
+            let index = data
+                .shares
+                .iter()
+                .position(|s| s.warehouse_id == warehouse_id && s.share_id == share_id)
+                .ok_or_else(|| error(StatusCode::NOT_FOUND, "Share not found", "ShareNotFound"))?;
+            data.shares.remove(index);
+            Ok(())
+        })
+    }
+
+    async fn resolve_share(
+        warehouse_id: &WarehouseIdent,
+        mount_name: &str,
+        catalog_state: MemoryCatalogState,
+    ) -> Result<Option<ResolvedShare>> {
+        let data = catalog_state.read();
+        let Some(consumer) = data.warehouses.get(warehouse_id) else {
+            return Ok(None);
+        };
+
+        Ok(data
+            .shares
+            .iter()
+            .filter(|s| {
+                s.consumer_project_id == consumer.warehouse.project_id
+                    && s.mount_name == mount_name
+                    && data.active_warehouse(&s.warehouse_id).is_some()
+            })
+            .find_map(|s| {
+                let (namespace, table_name) = match &s.target {
+                    ShareTarget::Namespace(id) => {
+                        (data.namespaces.get(id)?.namespace.clone(), None)
+                    }
+                    ShareTarget::Table(id) => {
+                        let table = data.table_ident(data.tables.get(id)?);
+                        (table.namespace, Some(table.name))
+                    }
+                };
+                Some(ResolvedShare {
+                    share_id: s.share_id,
+                    warehouse_id: s.warehouse_id.clone(),
+                    namespace,
+                    table_name,
+                })
+            }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use iceberg::spec::{NestedField, PrimitiveType, Schema};
+
+    use super::*;
+    use crate::service::storage::FileProfile;
+
+    async fn initialize_warehouse(state: MemoryCatalogState) -> WarehouseIdent {
+        let mut transaction = MemoryTransaction::begin_write(state).await.unwrap();
+        let warehouse_id = MemoryCatalog::create_warehouse(
+            "test_warehouse".to_string(),
+            ProjectIdent::from(uuid::Uuid::nil()),
+            StorageProfile::File(FileProfile {
+                path: "/tmp/warehouse".to_string(),
+            }),
+            None,
+            Some("alice"),
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+        warehouse_id
+    }
+
+    async fn create_namespace(
+        state: MemoryCatalogState,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+    ) -> Result<()> {
+        let mut transaction = MemoryTransaction::begin_write(state).await?;
+        MemoryCatalog::create_namespace(
+            warehouse_id,
+            CreateNamespaceRequest {
+                namespace: namespace.clone(),
+                properties: None,
+            },
+            None,
+            transaction.transaction(),
+        )
+        .await?;
+        transaction.commit().await
+    }
+
+    fn create_request() -> CreateTableRequest {
+        CreateTableRequest {
+            name: "my_table".to_string(),
+            location: Some("file:///tmp/warehouse/my_table".to_string()),
+            schema: Schema::builder()
+                .with_fields(vec![NestedField::required(
+                    1,
+                    "id",
+                    iceberg::spec::Type::Primitive(PrimitiveType::Int),
+                )
+                .into()])
+                .build()
+                .unwrap(),
+            partition_spec: None,
+            write_order: None,
+            stage_create: None,
+            properties: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_table_lifecycle() {
+        let state = MemoryCatalogState::default();
+        let warehouse_id = initialize_warehouse(state.clone()).await;
+        let namespace = NamespaceIdent::new("my_namespace".to_string());
+        create_namespace(state.clone(), &warehouse_id, &namespace)
+            .await
+            .unwrap();
+        let namespace_id =
+            MemoryCatalog::namespace_ident_to_id(&warehouse_id, &namespace, state.clone())
+                .await
+                .unwrap()
+                .unwrap();
+        let table = TableIdent::new(namespace.clone(), "my_table".to_string());
+
+        // Stage the table, then create it
+        let staged_id = TableIdentUuid::from(uuid::Uuid::now_v7());
+        let mut transaction = MemoryTransaction::begin_write(state.clone()).await.unwrap();
+        MemoryCatalog::create_table(
+            &namespace_id,
+            &table,
+            &staged_id,
+            create_request(),
+            None,
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+        assert!(
+            !MemoryCatalog::table_exists(&warehouse_id, &table, false, state.clone())
+                .await
+                .unwrap()
+        );
+        let staged = MemoryCatalog::list_staged_tables(&warehouse_id, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(staged.len(), 1);
+        assert_eq!(staged[0].table_id, staged_id);
+
+        let table_id = TableIdentUuid::from(uuid::Uuid::now_v7());
+        let metadata_location =
+            "file:///tmp/warehouse/my_table/metadata/v1.metadata.json".to_string();
+        let mut transaction = MemoryTransaction::begin_write(state.clone()).await.unwrap();
+        MemoryCatalog::create_table(
+            &namespace_id,
+            &table,
+            &table_id,
+            create_request(),
+            Some(&metadata_location),
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let loaded = MemoryCatalog::load_table(&warehouse_id, &table, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(loaded.table_id, table_id);
+        assert_eq!(loaded.metadata_location, Some(metadata_location));
+        assert!(
+            MemoryCatalog::list_staged_tables(&warehouse_id, state.clone())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // Namespaces with tables cannot be dropped
+        let mut transaction = MemoryTransaction::begin_write(state.clone()).await.unwrap();
+        let err =
+            MemoryCatalog::drop_namespace(&warehouse_id, &namespace, transaction.transaction())
+                .await
+                .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT);
+
+        MemoryCatalog::drop_table(&warehouse_id, &table_id, transaction.transaction())
+            .await
+            .unwrap();
+        MemoryCatalog::drop_namespace(&warehouse_id, &namespace, transaction.transaction())
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+
+        let dropped = MemoryCatalog::list_expired_dropped_tables(
+            chrono::Utc::now() + chrono::Duration::seconds(1),
+            10,
+            state.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].table_id, table_id);
+        assert!(
+            MemoryCatalog::namespace_ident_to_id(&warehouse_id, &namespace, state)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rollback_discards_changes() {
+        let state = MemoryCatalogState::default();
+        let warehouse_id = initialize_warehouse(state.clone()).await;
+        let namespace = NamespaceIdent::new("my_namespace".to_string());
+
+        let mut transaction = MemoryTransaction::begin_write(state.clone()).await.unwrap();
+        MemoryCatalog::create_namespace(
+            &warehouse_id,
+            CreateNamespaceRequest {
+                namespace: namespace.clone(),
+                properties: None,
+            },
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        // Visible within the transaction
+        MemoryCatalog::get_namespace(&warehouse_id, &namespace, transaction.transaction())
+            .await
+            .unwrap();
+        transaction.rollback().await.unwrap();
+
+        assert!(
+            MemoryCatalog::namespace_ident_to_id(&warehouse_id, &namespace, state)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_transactions_conflict() {
+        let state = MemoryCatalogState::default();
+        let warehouse_id = initialize_warehouse(state.clone()).await;
+        let namespace = NamespaceIdent::new("my_namespace".to_string());
+
+        let mut transaction = MemoryTransaction::begin_write(state.clone()).await.unwrap();
+        MemoryCatalog::create_namespace(
+            &warehouse_id,
+            CreateNamespaceRequest {
+                namespace: namespace.clone(),
+                properties: None,
+            },
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+
+        create_namespace(state.clone(), &warehouse_id, &namespace)
+            .await
+            .unwrap();
+
+        let err = transaction.commit().await.unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT);
+        assert_eq!(err.error.r#type, "NamespaceAlreadyExists");
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use http::StatusCode;
+use iceberg::spec::TableMetadata;
+
+use crate::api::{ErrorModel, ErrorType, IcebergErrorResponse, Result};
+use crate::service::client_usage::ClientUsage;
+use crate::service::lineage::LineageEvent;
+use crate::service::storage_usage::NamespaceStorageUsage;
+use crate::service::token_revocation::TokenRevocation;
+use crate::service::webhook::WebhookDeadLetter;
+use crate::service::{
+    GetWarehouseResponse, MetadataLogEntry, NamespaceIdent, NamespaceIdentUuid, Project,
+    ProjectIdent, Role, RoleObject, SnapshotRetentionPolicy, TableCommit, TableIdent,
+    TableIdentUuid, TablePolicy, WarehouseIdent, WarehouseStatus,
+};
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct MemoryData {
+    pub(super) projects: HashMap<ProjectIdent, Project>,
+    pub(super) warehouses: HashMap<WarehouseIdent, WarehouseRecord>,
+    pub(super) namespaces: HashMap<NamespaceIdentUuid, NamespaceRecord>,
+    pub(super) tables: HashMap<TableIdentUuid, TableRecord>,
+    pub(super) dropped_tables: HashMap<TableIdentUuid, DroppedTableRecord>,
+    pub(super) table_commits: Vec<TableCommit>,
+    pub(super) lineage_events: HashMap<(TableIdentUuid, i64), LineageEvent>,
+    pub(super) shares: Vec<ShareRecord>,
+    pub(super) role_assignments: Vec<RoleAssignmentRecord>,
+    /// Keyed by warehouse, engine and version. A missing version is empty.
+    pub(super) client_usage: HashMap<(WarehouseIdent, String, String), ClientUsage>,
+    /// Request and commit counts per warehouse and hour.
+    pub(super) api_usage: HashMap<(WarehouseIdent, DateTime<Utc>), (i64, i64)>,
+    pub(super) storage_usage: HashMap<NamespaceIdentUuid, NamespaceStorageUsage>,
+    pub(super) token_revocations: Vec<TokenRevocation>,
+    pub(super) webhook_dead_letters: Vec<WebhookDeadLetter>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct WarehouseRecord {
+    pub(super) warehouse: GetWarehouseResponse,
+    pub(super) retention_policy: Option<SnapshotRetentionPolicy>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct NamespaceRecord {
+    pub(super) warehouse_id: WarehouseIdent,
+    pub(super) namespace: NamespaceIdent,
+    pub(super) properties: HashMap<String, String>,
+    pub(super) created_by: Option<String>,
+    pub(super) owner: Option<String>,
+    pub(super) retention_policy: Option<SnapshotRetentionPolicy>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct TableRecord {
+    pub(super) namespace_id: NamespaceIdentUuid,
+    pub(super) name: String,
+    pub(super) metadata: TableMetadata,
+    /// `None` if the table is staged.
+    pub(super) metadata_location: Option<String>,
+    pub(super) metadata_version: i64,
+    pub(super) table_location: String,
+    pub(super) created_at: DateTime<Utc>,
+    pub(super) created_by: Option<String>,
+    pub(super) owner: Option<String>,
+    pub(super) policy: Option<TablePolicy>,
+    /// Metadata files the table pointed to, as recorded by the catalog.
+    pub(super) metadata_log: Vec<MetadataLogEntry>,
+}
+
+impl TableRecord {
+    pub(super) fn is_staged(&self) -> bool {
+        self.metadata_location.is_none()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct DroppedTableRecord {
+    pub(super) warehouse_id: WarehouseIdent,
+    pub(super) table: TableIdent,
+    pub(super) table_location: String,
+    pub(super) metadata_locations: Vec<String>,
+    pub(super) dropped_at: DateTime<Utc>,
+    pub(super) reclaimed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum ShareTarget {
+    Namespace(NamespaceIdentUuid),
+    Table(TableIdentUuid),
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct ShareRecord {
+    pub(super) share_id: uuid::Uuid,
+    pub(super) warehouse_id: WarehouseIdent,
+    pub(super) target: ShareTarget,
+    pub(super) consumer_project_id: ProjectIdent,
+    pub(super) mount_name: String,
+    pub(super) created_by: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum RoleAssignmentTarget {
+    Project(ProjectIdent),
+    Warehouse(WarehouseIdent),
+    Namespace(NamespaceIdentUuid),
+    Table(TableIdentUuid),
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct RoleAssignmentRecord {
+    pub(super) assignment_id: uuid::Uuid,
+    pub(super) principal: String,
+    pub(super) role: Role,
+    pub(super) target: RoleAssignmentTarget,
+    pub(super) project_id: ProjectIdent,
+    pub(super) created_by: Option<String>,
+}
+
+impl MemoryData {
+    /// Warehouse with any status.
+    pub(super) fn warehouse(&self, warehouse_id: &WarehouseIdent) -> Result<&WarehouseRecord> {
+        self.warehouses
+            .get(warehouse_id)
+            .ok_or_else(warehouse_not_found)
+    }
+
+    pub(super) fn warehouse_mut(
+        &mut self,
+        warehouse_id: &WarehouseIdent,
+    ) -> Result<&mut WarehouseRecord> {
+        self.warehouses
+            .get_mut(warehouse_id)
+            .ok_or_else(warehouse_not_found)
+    }
+
+    pub(super) fn active_warehouse(
+        &self,
+        warehouse_id: &WarehouseIdent,
+    ) -> Option<&WarehouseRecord> {
+        self.warehouses
+            .get(warehouse_id)
+            .filter(|w| w.warehouse.status == WarehouseStatus::Active)
+    }
+
+    /// Namespace of an active warehouse.
+    pub(super) fn namespace_id(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+    ) -> Option<NamespaceIdentUuid> {
+        self.active_warehouse(warehouse_id)?;
+        self.namespaces
+            .iter()
+            .find(|(_, n)| &n.warehouse_id == warehouse_id && &n.namespace == namespace)
+            .map(|(id, _)| id.clone())
+    }
+
+    /// Table of an active warehouse.
+    pub(super) fn table_id(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        include_staged: bool,
+    ) -> Option<TableIdentUuid> {
+        let namespace_id = self.namespace_id(warehouse_id, &table.namespace)?;
+        self.tables
+            .iter()
+            .find(|(_, t)| {
+                t.namespace_id == namespace_id
+                    && t.name == table.name
+                    && (include_staged || !t.is_staged())
+            })
+            .map(|(id, _)| *id)
+    }
+
+    /// Table of an active warehouse, together with its namespace.
+    pub(super) fn table(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        table_id: &TableIdentUuid,
+    ) -> Option<(&TableRecord, &NamespaceRecord)> {
+        self.active_warehouse(warehouse_id)?;
+        let table = self.tables.get(table_id)?;
+        let namespace = self.namespaces.get(&table.namespace_id)?;
+        (&namespace.warehouse_id == warehouse_id).then_some((table, namespace))
+    }
+
+    pub(super) fn table_ident(&self, table: &TableRecord) -> TableIdent {
+        TableIdent::new(
+            self.namespaces[&table.namespace_id].namespace.clone(),
+            table.name.clone(),
+        )
+    }
+
+    /// Policy of the closest namespace that has one, or of the warehouse.
+    pub(super) fn retention_policy(
+        &self,
+        namespace: &NamespaceRecord,
+    ) -> Option<SnapshotRetentionPolicy> {
+        self.namespaces
+            .values()
+            .filter(|n| {
+                n.warehouse_id == namespace.warehouse_id
+                    && n.retention_policy.is_some()
+                    && namespace
+                        .namespace
+                        .as_ref()
+                        .starts_with(n.namespace.as_ref())
+            })
+            .max_by_key(|n| n.namespace.len())
+            .and_then(|n| n.retention_policy.clone())
+            .or_else(|| {
+                self.warehouses
+                    .get(&namespace.warehouse_id)
+                    .and_then(|w| w.retention_policy.clone())
+            })
+    }
+
+    /// Remove a table together with its commits, lineage and shares.
+    pub(super) fn remove_table(&mut self, table_id: &TableIdentUuid) -> Option<TableRecord> {
+        let table = self.tables.remove(table_id)?;
+        self.table_commits.retain(|c| &c.table_id != table_id);
+        self.lineage_events.retain(|(id, _), _| id != table_id);
+        self.shares
+            .retain(|s| s.target != ShareTarget::Table(*table_id));
+        self.role_assignments
+            .retain(|a| a.target != RoleAssignmentTarget::Table(*table_id));
+        Some(table)
+    }
+
+    /// Target and project of a role granted on `object`.
+    pub(super) fn role_assignment_target(
+        &self,
+        object: &RoleObject,
+    ) -> Result<(RoleAssignmentTarget, ProjectIdent)> {
+        let project_of = |warehouse_id: &WarehouseIdent| {
+            self.active_warehouse(warehouse_id)
+                .map(|w| w.warehouse.project_id.clone())
+                .ok_or_else(warehouse_not_found)
+        };
+        match object {
+            RoleObject::Project(project_id) => {
+                if !self.projects.contains_key(project_id) {
+                    return Err(error(
+                        StatusCode::NOT_FOUND,
+                        "Project not found",
+                        "ProjectNotFound",
+                    ));
+                }
+                Ok((
+                    RoleAssignmentTarget::Project(project_id.clone()),
+                    project_id.clone(),
+                ))
+            }
+            RoleObject::Warehouse(warehouse_id) => Ok((
+                RoleAssignmentTarget::Warehouse(warehouse_id.clone()),
+                project_of(warehouse_id)?,
+            )),
+            RoleObject::Namespace(warehouse_id, namespace) => {
+                let project_id = project_of(warehouse_id)?;
+                let namespace_id = self.namespace_id(warehouse_id, namespace).ok_or_else(|| {
+                    typed_error(
+                        StatusCode::NOT_FOUND,
+                        "Namespace not found",
+                        "NoSuchNamespaceError",
+                        ErrorType::NoSuchNamespace,
+                    )
+                })?;
+                Ok((RoleAssignmentTarget::Namespace(namespace_id), project_id))
+            }
+            RoleObject::Table(warehouse_id, table) => {
+                let project_id = project_of(warehouse_id)?;
+                let table_id = self
+                    .table_id(warehouse_id, table, false)
+                    .ok_or_else(table_not_found)?;
+                Ok((RoleAssignmentTarget::Table(table_id), project_id))
+            }
+        }
+    }
+
+    /// Object a role was granted on, `None` if it no longer exists.
+    pub(super) fn role_object(&self, target: &RoleAssignmentTarget) -> Option<RoleObject> {
+        Some(match target {
+            RoleAssignmentTarget::Project(project_id) => RoleObject::Project(project_id.clone()),
+            RoleAssignmentTarget::Warehouse(warehouse_id) => {
+                RoleObject::Warehouse(warehouse_id.clone())
+            }
+            RoleAssignmentTarget::Namespace(namespace_id) => {
+                let namespace = self.namespaces.get(namespace_id)?;
+                RoleObject::Namespace(namespace.warehouse_id.clone(), namespace.namespace.clone())
+            }
+            RoleAssignmentTarget::Table(table_id) => {
+                let table = self.tables.get(table_id)?;
+                let namespace = self.namespaces.get(&table.namespace_id)?;
+                RoleObject::Table(namespace.warehouse_id.clone(), self.table_ident(table))
+            }
+        })
+    }
+}
+
+pub(super) fn error(code: StatusCode, message: &str, r#type: &str) -> IcebergErrorResponse {
+    ErrorModel::builder()
+        .code(code.into())
+        .message(message.to_string())
+        .r#type(r#type.to_string())
+        .build()
+        .into()
+}
+
+/// [`error`] with the [`ErrorType`] returned to clients.
+pub(super) fn typed_error(
+    code: StatusCode,
+    message: &str,
+    r#type: &str,
+    error_type: ErrorType,
+) -> IcebergErrorResponse {
+    ErrorModel::builder()
+        .code(code.into())
+        .message(message.to_string())
+        .r#type(r#type.to_string())
+        .error_type(error_type)
+        .build()
+        .into()
+}
+
+pub(super) fn warehouse_not_found() -> IcebergErrorResponse {
+    error(
+        StatusCode::NOT_FOUND,
+        "Warehouse not found",
+        "WarehouseNotFound",
+    )
+}
+
+pub(super) fn table_not_found() -> IcebergErrorResponse {
+    typed_error(
+        StatusCode::NOT_FOUND,
+        "Table not found",
+        "NoSuchTableError",
+        ErrorType::NoSuchTable,
+    )
+}
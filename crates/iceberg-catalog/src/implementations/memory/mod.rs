@@ -0,0 +1,119 @@
+//! Catalog and secret store that keep all data in memory.
+//!
+//! Intended for tests of code built on this crate that should not require a
+//! database, e.g. by serving the management and catalog APIs backed by
+//! [`MemoryCatalog`] and [`MemorySecretStore`]. Nothing is persisted.
+//!
+//! Write transactions work on a copy of the data. On commit, their changes
+//! are applied again to the latest data, so that transactions committed in
+//! the meantime are not overwritten. A change that can no longer be applied,
+//! e.g. because a concurrent transaction created the same namespace, fails
+//! the commit with the error of that change.
+
+mod catalog;
+mod data;
+mod secrets;
+mod stores;
+
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use data::MemoryData;
+pub use secrets::{MemorySecretStore, MemorySecretStoreState};
+
+use crate::api::Result;
+use crate::service::Transaction;
+
+/// Catalog that keeps all data in memory.
+#[derive(Debug, Clone)]
+pub struct MemoryCatalog;
+
+/// Data of a [`MemoryCatalog`]. Clones share the same data.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryCatalogState {
+    data: Arc<RwLock<MemoryData>>,
+}
+
+impl MemoryCatalogState {
+    fn read(&self) -> RwLockReadGuard<'_, MemoryData> {
+        self.data.read().expect("lock poisoned")
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, MemoryData> {
+        self.data.write().expect("lock poisoned")
+    }
+}
+
+type Change = Box<dyn Fn(&mut MemoryData) -> Result<()> + Send + Sync>;
+
+pub struct MemoryTransaction {
+    state: MemoryCatalogState,
+    data: MemoryData,
+    changes: Vec<Change>,
+}
+
+impl std::fmt::Debug for MemoryTransaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryTransaction")
+            .field("changes", &self.changes.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl MemoryTransaction {
+    /// Data as seen by this transaction.
+    fn data(&self) -> &MemoryData {
+        &self.data
+    }
+
+    /// Apply `change` to the data of this transaction and keep it to be
+    /// applied again on commit. Changes must not modify the data if they fail.
+    fn apply<T>(
+        &mut self,
+        change: impl Fn(&mut MemoryData) -> Result<T> + Send + Sync + 'static,
+    ) -> Result<T> {
+        let result = change(&mut self.data)?;
+        self.changes
+            .push(Box::new(move |data| change(data).map(|_| ())));
+        Ok(result)
+    }
+}
+
+#[async_trait::async_trait]
+impl Transaction<MemoryCatalogState> for MemoryTransaction {
+    type Transaction<'a> = &'a mut MemoryTransaction;
+
+    async fn begin_write(db_state: MemoryCatalogState) -> Result<Self> {
+        let data = db_state.read().clone();
+        Ok(Self {
+            state: db_state,
+            data,
+            changes: vec![],
+        })
+    }
+
+    async fn begin_read(db_state: MemoryCatalogState) -> Result<Self> {
+        Self::begin_write(db_state).await
+    }
+
+    async fn commit(self) -> Result<()> {
+        if self.changes.is_empty() {
+            return Ok(());
+        }
+
+        let mut data = self.state.write();
+        let mut updated = data.clone();
+        for change in &self.changes {
+            change(&mut updated)?;
+        }
+        *data = updated;
+        Ok(())
+    }
+
+    async fn rollback(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn transaction(&mut self) -> Self::Transaction<'_> {
+        self
+    }
+}
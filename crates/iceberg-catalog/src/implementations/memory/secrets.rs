@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::api::{ErrorModel, Result};
+use crate::service::secrets::{Secret, SecretInStorage};
+use crate::service::{SecretIdent, SecretStore};
+
+#[derive(Debug, Clone, Default)]
+pub struct MemorySecretStoreState {
+    secrets: Arc<RwLock<HashMap<SecretIdent, Secret<serde_json::Value>>>>,
+}
+
+/// Secret store that keeps secrets in memory, unencrypted.
+#[derive(Debug, Clone)]
+pub struct MemorySecretStore;
+
+#[async_trait::async_trait]
+impl SecretStore for MemorySecretStore {
+    type State = MemorySecretStoreState;
+
+    async fn get_secret_by_id<S: SecretInStorage + for<'de> Deserialize<'de>>(
+        secret_id: &SecretIdent,
+        state: MemorySecretStoreState,
+    ) -> Result<Secret<S>> {
+        let secret = state
+            .secrets
+            .read()
+            .expect("lock poisoned")
+            .get(secret_id)
+            .cloned()
+            .ok_or_else(|| {
+                ErrorModel::builder()
+                    .code(StatusCode::NOT_FOUND.into())
+                    .message("Secret not found")
+                    .r#type("SecretNotFound")
+                    .stack(Some(vec![format!("secret_id: {secret_id}")]))
+                    .build()
+            })?;
+
+        let inner = serde_json::from_value(secret.secret).map_err(|e| {
+            ErrorModel::builder()
+                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message("Error parsing secret")
+                .r#type("SecretParseError")
+                .stack(Some(vec![e.to_string()]))
+                .build()
+        })?;
+
+        Ok(Secret {
+            secret_id: secret.secret_id,
+            secret: inner,
+            created_at: secret.created_at,
+            updated_at: secret.updated_at,
+        })
+    }
+
+    async fn create_secret<S: SecretInStorage + Send + Sync + Serialize + std::fmt::Debug>(
+        secret: S,
+        state: MemorySecretStoreState,
+    ) -> Result<SecretIdent> {
+        let secret = serde_json::to_value(&secret).map_err(|_e| {
+            ErrorModel::builder()
+                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message("Error serializing secret")
+                .r#type("SecretSerializeError")
+                .build()
+        })?;
+        let secret_id = SecretIdent::from(uuid::Uuid::now_v7());
+        state.secrets.write().expect("lock poisoned").insert(
+            secret_id.clone(),
+            Secret {
+                secret_id: secret_id.clone(),
+                secret,
+                created_at: chrono::Utc::now(),
+                updated_at: None,
+            },
+        );
+
+        Ok(secret_id)
+    }
+
+    async fn delete_secret(secret_id: &SecretIdent, state: MemorySecretStoreState) -> Result<()> {
+        state
+            .secrets
+            .write()
+            .expect("lock poisoned")
+            .remove(secret_id);
+        Ok(())
+    }
+}
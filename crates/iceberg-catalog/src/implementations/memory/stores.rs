@@ -0,0 +1,384 @@
+//! The stores of [`crate::service::stores`], backed by the data of the [`MemoryCatalogState`].
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::DurationRound as _;
+use http::StatusCode;
+
+use super::data::{error, RoleAssignmentRecord, RoleAssignmentTarget};
+use super::MemoryCatalogState;
+use crate::service::{
+    api_usage::{ApiUsage, ApiUsageStore, UsageWindow},
+    client_usage::{ClientInfo, ClientUsage, ClientUsageStore},
+    lineage::{LineageEvent, LineageStore},
+    roles::RoleStore,
+    storage_usage::{NamespaceStorageUsage, StorageUsageStore},
+    token_revocation::{RevocationStore, RevocationTarget, TokenRevocation},
+    webhook::{WebhookDeadLetter, WebhookDeadLetterStore},
+    ProjectIdent, Result, Role, RoleAssignment, RoleObject, RoleTarget, TableIdentUuid,
+    WarehouseIdent,
+};
+
+#[async_trait::async_trait]
+impl RoleStore for MemoryCatalogState {
+    async fn grant_role(
+        &self,
+        object: &RoleObject,
+        principal: &str,
+        role: Role,
+        created_by: Option<&str>,
+    ) -> Result<uuid::Uuid> {
+        if principal.is_empty() {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                "Principal cannot be empty",
+                "EmptyPrincipal",
+            ));
+        }
+
+        let mut data = self.write();
+        let (target, project_id) = data.role_assignment_target(object)?;
+        if let Some(assignment) = data
+            .role_assignments
+            .iter_mut()
+            .find(|a| a.principal == principal && a.target == target)
+        {
+            assignment.role = role;
+            return Ok(assignment.assignment_id);
+        }
+        let assignment_id = uuid::Uuid::now_v7();
+        data.role_assignments.push(RoleAssignmentRecord {
+            assignment_id,
+            principal: principal.to_string(),
+            role,
+            target,
+            project_id,
+            created_by: created_by.map(str::to_string),
+        });
+        Ok(assignment_id)
+    }
+
+    async fn revoke_role(&self, object: &RoleObject, principal: &str) -> Result<()> {
+        let mut data = self.write();
+        let (target, _) = data.role_assignment_target(object)?;
+        let index = data
+            .role_assignments
+            .iter()
+            .position(|a| a.principal == principal && a.target == target)
+            .ok_or_else(|| {
+                error(
+                    StatusCode::NOT_FOUND,
+                    "Role assignment not found",
+                    "RoleAssignmentNotFound",
+                )
+            })?;
+        data.role_assignments.remove(index);
+        Ok(())
+    }
+
+    async fn list_role_assignments(
+        &self,
+        project_id: Option<&ProjectIdent>,
+        principal: Option<&str>,
+    ) -> Result<Vec<RoleAssignment>> {
+        let data = self.read();
+        Ok(data
+            .role_assignments
+            .iter()
+            .filter(|a| project_id.map_or(true, |p| &a.project_id == p))
+            .filter(|a| principal.map_or(true, |p| a.principal == p))
+            .filter_map(|a| {
+                Some(RoleAssignment {
+                    assignment_id: a.assignment_id,
+                    principal: a.principal.clone(),
+                    role: a.role,
+                    object: data.role_object(&a.target)?,
+                    project_id: a.project_id.clone(),
+                    created_by: a.created_by.clone(),
+                })
+            })
+            .collect())
+    }
+
+    async fn get_roles(&self, principal: &str, target: &RoleTarget) -> Result<Vec<Role>> {
+        let data = self.read();
+        let (warehouse_id, namespace) = match target {
+            RoleTarget::Project(project_id) => {
+                return Ok(data
+                    .role_assignments
+                    .iter()
+                    .filter(|a| {
+                        a.principal == principal
+                            && a.target == RoleAssignmentTarget::Project(project_id.clone())
+                    })
+                    .map(|a| a.role)
+                    .collect());
+            }
+            RoleTarget::Warehouse(warehouse_id) => (warehouse_id, None),
+            RoleTarget::Namespace(warehouse_id, namespace) => {
+                (warehouse_id, Some(namespace.clone()))
+            }
+            RoleTarget::Table(warehouse_id, table_id) => (
+                warehouse_id,
+                data.table(warehouse_id, table_id)
+                    .map(|(_, namespace)| namespace.namespace.clone()),
+            ),
+        };
+        let Some(warehouse) = data.warehouses.get(warehouse_id) else {
+            return Ok(vec![]);
+        };
+
+        Ok(data
+            .role_assignments
+            .iter()
+            .filter(|a| a.principal == principal)
+            .filter(|a| match &a.target {
+                RoleAssignmentTarget::Project(project_id) => {
+                    project_id == &warehouse.warehouse.project_id
+                }
+                RoleAssignmentTarget::Warehouse(id) => id == warehouse_id,
+                RoleAssignmentTarget::Namespace(id) => data.namespaces.get(id).is_some_and(|n| {
+                    &n.warehouse_id == warehouse_id
+                        && namespace
+                            .as_ref()
+                            .is_some_and(|ns| ns.as_ref().starts_with(n.namespace.as_ref()))
+                }),
+                RoleAssignmentTarget::Table(id) => {
+                    matches!(target, RoleTarget::Table(_, table_id) if table_id == id)
+                }
+            })
+            .map(|a| a.role)
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl RevocationStore for MemoryCatalogState {
+    async fn revoke_token(&self, revocation: &TokenRevocation) -> Result<()> {
+        let (RevocationTarget::TokenId(target) | RevocationTarget::Subject(target)) =
+            &revocation.target;
+        if target.is_empty() {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                "Token id or subject to revoke cannot be empty",
+                "EmptyRevocationTarget",
+            ));
+        }
+
+        self.write().token_revocations.push(revocation.clone());
+        Ok(())
+    }
+
+    async fn list_active_revocations(&self) -> Result<Vec<TokenRevocation>> {
+        let now = chrono::Utc::now();
+        Ok(self
+            .read()
+            .token_revocations
+            .iter()
+            .filter(|r| r.expires_at.map_or(true, |expires_at| expires_at > now))
+            .cloned()
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl ClientUsageStore for MemoryCatalogState {
+    async fn record_client_usage(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        client: &ClientInfo,
+    ) -> Result<()> {
+        let mut data = self.write();
+        data.warehouse(warehouse_id)?;
+
+        let now = chrono::Utc::now();
+        let key = (
+            warehouse_id.clone(),
+            client.engine.clone(),
+            client.version.clone().unwrap_or_default(),
+        );
+        let usage = data.client_usage.entry(key).or_insert_with(|| ClientUsage {
+            engine: client.engine.clone(),
+            version: client.version.clone().filter(|v| !v.is_empty()),
+            session_count: 0,
+            first_seen: now,
+            last_seen: now,
+        });
+        usage.session_count += 1;
+        usage.last_seen = now;
+        Ok(())
+    }
+
+    async fn list_client_usage(&self, warehouse_id: &WarehouseIdent) -> Result<Vec<ClientUsage>> {
+        let mut usage = self
+            .read()
+            .client_usage
+            .iter()
+            .filter(|((id, _, _), _)| id == warehouse_id)
+            .map(|(_, usage)| usage.clone())
+            .collect::<Vec<_>>();
+        usage.sort_by(|a, b| (&a.engine, &a.version).cmp(&(&b.engine, &b.version)));
+
+        Ok(usage)
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiUsageStore for MemoryCatalogState {
+    async fn record_api_usage(&self, usage: Vec<ApiUsage>) -> Result<()> {
+        let mut data = self.write();
+        for usage in usage {
+            // Usage of warehouses deleted in the meantime is discarded.
+            if !data.warehouses.contains_key(&usage.warehouse_id) {
+                continue;
+            }
+            let counts = data
+                .api_usage
+                .entry((usage.warehouse_id, usage.window_start))
+                .or_default();
+            counts.0 += usage.request_count;
+            counts.1 += usage.commit_count;
+        }
+        Ok(())
+    }
+
+    async fn list_api_usage(
+        &self,
+        project_id: &ProjectIdent,
+        warehouse_id_filter: Option<&HashSet<WarehouseIdent>>,
+        window: UsageWindow,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<ApiUsage>> {
+        let data = self.read();
+        let window = match window {
+            UsageWindow::Hour => chrono::Duration::hours(1),
+            UsageWindow::Day => chrono::Duration::days(1),
+        };
+
+        let mut aggregated = BTreeMap::new();
+        for ((warehouse_id, window_start), (request_count, commit_count)) in &data.api_usage {
+            let in_project = data
+                .warehouses
+                .get(warehouse_id)
+                .is_some_and(|w| &w.warehouse.project_id == project_id);
+            if !in_project
+                || warehouse_id_filter.is_some_and(|ids| !ids.contains(warehouse_id))
+                || window_start < &from
+                || window_start >= &to
+            {
+                continue;
+            }
+            let start = window_start.duration_trunc(window).unwrap_or(*window_start);
+            let counts: &mut (i64, i64) =
+                aggregated.entry((start, warehouse_id.clone())).or_default();
+            counts.0 += request_count;
+            counts.1 += commit_count;
+        }
+
+        Ok(aggregated
+            .into_iter()
+            .map(
+                |((window_start, warehouse_id), (request_count, commit_count))| ApiUsage {
+                    warehouse_id,
+                    window_start,
+                    request_count,
+                    commit_count,
+                },
+            )
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageUsageStore for MemoryCatalogState {
+    async fn refresh_storage_usage(&self) -> Result<u64> {
+        let mut data = self.write();
+        let computed_at = chrono::Utc::now();
+
+        let mut usage = HashMap::new();
+        for (namespace_id, namespace) in &data.namespaces {
+            let mut table_count = 0;
+            let mut total_files_size_bytes = 0;
+            let mut tables_without_size = 0;
+            for table in data
+                .tables
+                .values()
+                .filter(|t| &t.namespace_id == namespace_id && !t.is_staged())
+            {
+                table_count += 1;
+                // Tables without a current snapshot are empty.
+                let size = match table.metadata.current_snapshot() {
+                    None => Some(0),
+                    Some(snapshot) => snapshot
+                        .summary()
+                        .other
+                        .get("total-files-size")
+                        .and_then(|size| size.parse::<i64>().ok()),
+                };
+                match size {
+                    Some(size) => total_files_size_bytes += size,
+                    None => tables_without_size += 1,
+                }
+            }
+            usage.insert(
+                namespace_id.clone(),
+                NamespaceStorageUsage {
+                    warehouse_id: namespace.warehouse_id.clone(),
+                    namespace: namespace.namespace.clone(),
+                    table_count,
+                    total_files_size_bytes,
+                    tables_without_size,
+                    computed_at,
+                },
+            );
+        }
+
+        let updated = usage.len() as u64;
+        data.storage_usage = usage;
+        Ok(updated)
+    }
+
+    async fn list_storage_usage(
+        &self,
+        warehouse_id: &WarehouseIdent,
+    ) -> Result<Vec<NamespaceStorageUsage>> {
+        let mut usage = self
+            .read()
+            .storage_usage
+            .values()
+            .filter(|u| &u.warehouse_id == warehouse_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        usage.sort_by(|a, b| a.namespace.as_ref().cmp(b.namespace.as_ref()));
+
+        Ok(usage)
+    }
+}
+
+#[async_trait::async_trait]
+impl LineageStore for MemoryCatalogState {
+    async fn record_lineage_events(
+        &self,
+        _warehouse_id: &WarehouseIdent,
+        table_id: TableIdentUuid,
+        events: &[LineageEvent],
+    ) -> Result<()> {
+        let mut data = self.write();
+        if data.tables.contains_key(&table_id) {
+            for event in events {
+                data.lineage_events
+                    .entry((table_id, event.snapshot_id))
+                    .or_insert_with(|| event.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookDeadLetterStore for MemoryCatalogState {
+    async fn record_webhook_dead_letter(&self, dead_letter: &WebhookDeadLetter) -> Result<()> {
+        self.write().webhook_dead_letters.push(dead_letter.clone());
+        Ok(())
+    }
+}
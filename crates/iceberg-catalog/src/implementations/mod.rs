@@ -1,9 +1,21 @@
 /// Default project ID used for single-project deployments.
 pub const DEFAULT_PROJECT_ID: uuid::Uuid = uuid::uuid!("00000000-0000-0000-0000-000000000000");
 
+#[cfg(feature = "sqlx")]
+pub(crate) mod dbutils;
+#[cfg(feature = "sqlx-mysql")]
+pub mod mysql;
 #[cfg(feature = "sqlx-postgres")]
 pub mod postgres;
 
+pub mod memory;
+
 mod authz;
+pub(crate) mod commit;
+pub mod opa;
+pub mod rbac;
+pub(crate) mod validation;
 
 pub use authz::{AllowAllAuthState, AllowAllAuthZHandler};
+pub use opa::{OpaAuthState, OpaAuthZHandler};
+pub use rbac::{RbacAuthState, RbacAuthZHandler};
@@ -0,0 +1,118 @@
+use std::collections::HashSet;
+
+use super::{dbutils::DBErrorHandler as _, CatalogState};
+use crate::api::Result;
+use crate::service::api_usage::{ApiUsage, UsageWindow};
+use crate::{ProjectIdent, WarehouseIdent};
+use sqlx::Row;
+
+/// Rows inserted per statement, each row binds four parameters.
+const BATCH_SIZE: usize = 1000;
+
+pub(crate) async fn record_api_usage(
+    usage: Vec<ApiUsage>,
+    catalog_state: CatalogState,
+) -> Result<()> {
+    for batch in usage.chunks(BATCH_SIZE) {
+        let mut query_builder = sqlx::QueryBuilder::new(
+            r"
+            INSERT INTO api_usage (warehouse_id, window_start, request_count, commit_count)
+            SELECT u.warehouse_id, u.window_start, u.request_count, u.commit_count
+            FROM (",
+        );
+        for (i, usage) in batch.iter().enumerate() {
+            if i > 0 {
+                query_builder.push(" UNION ALL ");
+            }
+            query_builder.push("SELECT ");
+            query_builder.push_bind(*usage.warehouse_id.as_uuid());
+            query_builder.push(" AS warehouse_id, ");
+            query_builder.push_bind(usage.window_start);
+            query_builder.push(" AS window_start, ");
+            query_builder.push_bind(usage.request_count);
+            query_builder.push(" AS request_count, ");
+            query_builder.push_bind(usage.commit_count);
+            query_builder.push(" AS commit_count");
+        }
+        // Usage of warehouses deleted in the meantime is discarded.
+        query_builder.push(
+            r") u
+            INNER JOIN warehouse w ON w.warehouse_id = u.warehouse_id
+            ON DUPLICATE KEY UPDATE request_count = api_usage.request_count + VALUES(request_count),
+                                    commit_count = api_usage.commit_count + VALUES(commit_count)
+            ",
+        );
+
+        query_builder
+            .build()
+            .execute(&catalog_state.write_pool)
+            .await
+            .map_err(|e| e.into_error_model("Error recording API usage".into()))?;
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn list_api_usage(
+    project_id: &ProjectIdent,
+    warehouse_id_filter: Option<&HashSet<WarehouseIdent>>,
+    window: UsageWindow,
+    from: chrono::DateTime<chrono::Utc>,
+    to: chrono::DateTime<chrono::Utc>,
+    catalog_state: CatalogState,
+) -> Result<Vec<ApiUsage>> {
+    if warehouse_id_filter.is_some_and(HashSet::is_empty) {
+        return Ok(vec![]);
+    }
+
+    let window_start = match window {
+        UsageWindow::Hour => {
+            "TIMESTAMP(DATE(u.window_start), MAKETIME(HOUR(u.window_start), 0, 0))"
+        }
+        UsageWindow::Day => "TIMESTAMP(DATE(u.window_start))",
+    };
+
+    let mut query_builder = sqlx::QueryBuilder::new(format!(
+        r"
+        SELECT
+            u.warehouse_id,
+            {window_start} AS window_start,
+            CAST(SUM(u.request_count) AS SIGNED) AS request_count,
+            CAST(SUM(u.commit_count) AS SIGNED) AS commit_count
+        FROM api_usage u
+        INNER JOIN warehouse w ON w.warehouse_id = u.warehouse_id
+        WHERE w.project_id = "
+    ));
+    query_builder.push_bind(*project_id.as_uuid());
+    query_builder.push(" AND u.window_start >= ");
+    query_builder.push_bind(from);
+    query_builder.push(" AND u.window_start < ");
+    query_builder.push_bind(to);
+    if let Some(warehouse_id_filter) = warehouse_id_filter {
+        query_builder.push(" AND u.warehouse_id IN (");
+        let mut separated = query_builder.separated(", ");
+        for warehouse_id in warehouse_id_filter {
+            separated.push_bind(*warehouse_id.as_uuid());
+        }
+        query_builder.push(")");
+    }
+    query_builder.push(format!(
+        " GROUP BY u.warehouse_id, {window_start} ORDER BY 2, 1"
+    ));
+
+    let usage = query_builder
+        .build()
+        .fetch_all(&catalog_state.read_pool)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching API usage".into()))?;
+
+    Ok(usage
+        .into_iter()
+        .map(|row| ApiUsage {
+            warehouse_id: row.get::<uuid::Uuid, _>("warehouse_id").into(),
+            window_start: row.get("window_start"),
+            request_count: row.get("request_count"),
+            commit_count: row.get("commit_count"),
+        })
+        .collect())
+}
@@ -0,0 +1,682 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    namespace::{
+        create_namespace, drop_namespace, get_namespace, list_namespaces, namespace_ident_to_id,
+        set_namespace_retention_policy, update_namespace_properties,
+    },
+    ownership::{get_ownership, set_owner},
+    share::{create_share, delete_share, list_shares, resolve_share},
+    table::{
+        commit_table_transaction, create_table, discard_staged_table, drop_table,
+        get_table_metadata_by_id, get_table_metadata_by_s3_location, get_table_metadata_log,
+        list_expired_dropped_tables, list_retention_candidates, list_staged_tables,
+        list_table_commits, list_tables, load_table, load_tables, reclaim_dropped_table,
+        record_table_commits, rename_table, set_table_policy, table_exists, table_ident_to_id,
+        table_idents_to_ids,
+    },
+    warehouse::{
+        clear_warehouse_previous_storage_secret, create_warehouse, delete_project,
+        delete_warehouse, get_engine_table_config, get_project_id_by_name, get_projects,
+        get_pyiceberg_compat, get_warehouse, get_warehouse_allowlist, get_warehouse_id_by_name,
+        get_warehouse_signer_uri, is_warehouse_read_only, list_projects, list_warehouses,
+        rename_project, rename_warehouse, rotate_warehouse_storage_secret, set_engine_table_config,
+        set_event_filter, set_project_metadata, set_warehouse_access_delegation,
+        set_warehouse_allow_external_locations, set_warehouse_allowlist,
+        set_warehouse_object_storage_layout, set_warehouse_pyiceberg_compat,
+        set_warehouse_read_only, set_warehouse_retention_policy, set_warehouse_signer_uri,
+        set_warehouse_status, set_warehouse_storage_validation,
+        set_warehouse_unauthorized_response, update_storage_profile,
+    },
+    CatalogState, MySqlTransaction,
+};
+use crate::service::{
+    auth::UnauthorizedResponse, page_token::PageQuery, storage_validation::StorageValidation,
+    CommitTransactionRequest, CreateNamespaceRequest, CreateNamespaceResponse, CreateTableRequest,
+    DroppedTable, EngineTableConfig, GetWarehouseResponse, ListNamespacesQuery,
+    ListNamespacesResponse, LoadedTable, MetadataLogEntry, NamespaceIdent, ObjectOwnership,
+    OwnedObject, Project, PyicebergCompat, ResolvedShare, Result, RetentionCandidate, Share,
+    SharedObject, SnapshotRetentionPolicy, StagedTable, TableCommit, TableIdent, TablePolicy,
+    UpdateNamespacePropertiesRequest, UpdateNamespacePropertiesResponse, WarehouseAllowlist,
+    WarehouseStatus,
+};
+use crate::{
+    service::{
+        storage::{AccessDelegation, StorageProfile},
+        Catalog, CommitTableResponseExt, CreateTableResponse, GetNamespaceResponse,
+        GetTableMetadataResponse, LoadTableResponse, NamespaceIdentUuid, ProjectIdent,
+        TableIdentUuid, Transaction, WarehouseIdent,
+    },
+    SecretIdent,
+};
+
+#[async_trait::async_trait]
+impl Catalog for super::Catalog {
+    type Transaction = MySqlTransaction;
+    type State = CatalogState;
+
+    fn primary_state(catalog_state: CatalogState) -> CatalogState {
+        CatalogState {
+            read_pool: catalog_state.write_pool.clone(),
+            write_pool: catalog_state.write_pool,
+        }
+    }
+
+    async fn create_warehouse<'a>(
+        warehouse_name: String,
+        project_id: ProjectIdent,
+        storage_profile: StorageProfile,
+        storage_secret_id: Option<SecretIdent>,
+        owner: Option<&str>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<WarehouseIdent> {
+        create_warehouse(
+            warehouse_name,
+            project_id,
+            storage_profile,
+            storage_secret_id,
+            owner,
+            transaction,
+        )
+        .await
+    }
+
+    async fn get_warehouse<'a>(
+        warehouse_id: &WarehouseIdent,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<GetWarehouseResponse> {
+        get_warehouse(warehouse_id, transaction).await
+    }
+
+    async fn get_namespace<'a>(
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<GetNamespaceResponse> {
+        get_namespace(warehouse_id, namespace, transaction).await
+    }
+
+    async fn list_namespaces(
+        warehouse_id: &WarehouseIdent,
+        query: &ListNamespacesQuery,
+        catalog_state: CatalogState,
+    ) -> Result<ListNamespacesResponse> {
+        list_namespaces(warehouse_id, query, catalog_state).await
+    }
+
+    async fn create_namespace<'a>(
+        warehouse_id: &WarehouseIdent,
+        request: CreateNamespaceRequest,
+        owner: Option<&str>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<CreateNamespaceResponse> {
+        create_namespace(warehouse_id, request, owner, transaction).await
+    }
+
+    async fn namespace_ident_to_id(
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        catalog_state: CatalogState,
+    ) -> Result<Option<NamespaceIdentUuid>> {
+        namespace_ident_to_id(warehouse_id, namespace, catalog_state).await
+    }
+
+    async fn drop_namespace<'a>(
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        drop_namespace(warehouse_id, namespace, transaction).await
+    }
+
+    async fn update_namespace_properties<'a>(
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        request: UpdateNamespacePropertiesRequest,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<UpdateNamespacePropertiesResponse> {
+        update_namespace_properties(warehouse_id, namespace, request, transaction).await
+    }
+
+    async fn create_table<'a>(
+        namespace_id: &NamespaceIdentUuid,
+        table: &TableIdent,
+        table_id: &TableIdentUuid,
+        request: CreateTableRequest,
+        // Metadata location may be none if stage-create is true
+        metadata_location: Option<&String>,
+        owner: Option<&str>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<CreateTableResponse> {
+        create_table(
+            namespace_id,
+            table,
+            table_id,
+            request,
+            metadata_location,
+            owner,
+            transaction,
+        )
+        .await
+    }
+
+    async fn list_tables(
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        include_staged: bool,
+        page: &PageQuery,
+        catalog_state: CatalogState,
+    ) -> Result<Vec<(TableIdentUuid, TableIdent)>> {
+        list_tables(warehouse_id, namespace, include_staged, page, catalog_state).await
+    }
+
+    async fn load_table(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        catalog_state: CatalogState,
+    ) -> Result<LoadTableResponse> {
+        load_table(warehouse_id, table, catalog_state).await
+    }
+
+    async fn get_table_metadata_by_id(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdentUuid,
+        include_staged: bool,
+        catalog_state: Self::State,
+    ) -> Result<GetTableMetadataResponse> {
+        get_table_metadata_by_id(warehouse_id, table, include_staged, catalog_state).await
+    }
+
+    async fn get_table_metadata_log(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdentUuid,
+        catalog_state: Self::State,
+    ) -> Result<Vec<MetadataLogEntry>> {
+        get_table_metadata_log(warehouse_id, table, catalog_state).await
+    }
+
+    async fn list_table_commits(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdentUuid,
+        before: Option<chrono::DateTime<chrono::Utc>>,
+        limit: i64,
+        catalog_state: Self::State,
+    ) -> Result<Vec<TableCommit>> {
+        list_table_commits(warehouse_id, table, before, limit, catalog_state).await
+    }
+
+    async fn load_tables(
+        warehouse_id: &WarehouseIdent,
+        tables: HashSet<&TableIdent>,
+        catalog_state: Self::State,
+    ) -> Result<Vec<LoadedTable>> {
+        load_tables(warehouse_id, tables, &catalog_state.read_pool).await
+    }
+
+    async fn list_staged_tables(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<Vec<StagedTable>> {
+        list_staged_tables(warehouse_id, catalog_state).await
+    }
+
+    async fn discard_staged_table<'a>(
+        warehouse_id: &WarehouseIdent,
+        table_id: &TableIdentUuid,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        discard_staged_table(warehouse_id, table_id, transaction).await
+    }
+
+    async fn get_table_metadata_by_s3_location(
+        warehouse_id: &WarehouseIdent,
+        location: &str,
+        include_staged: bool,
+        catalog_state: Self::State,
+    ) -> Result<GetTableMetadataResponse> {
+        get_table_metadata_by_s3_location(warehouse_id, location, include_staged, catalog_state)
+            .await
+    }
+
+    async fn table_ident_to_id(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        include_staged: bool,
+        catalog_state: Self::State,
+    ) -> Result<Option<TableIdentUuid>> {
+        table_ident_to_id(
+            warehouse_id,
+            table,
+            include_staged,
+            &catalog_state.read_pool,
+        )
+        .await
+    }
+
+    async fn table_exists(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        include_staged: bool,
+        catalog_state: Self::State,
+    ) -> Result<bool> {
+        table_exists(
+            warehouse_id,
+            table,
+            include_staged,
+            &catalog_state.read_pool,
+        )
+        .await
+    }
+
+    async fn rename_table<'a>(
+        warehouse_id: &WarehouseIdent,
+        source_id: &TableIdentUuid,
+        source: &TableIdent,
+        destination: &TableIdent,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        rename_table(warehouse_id, source_id, source, destination, transaction).await
+    }
+
+    async fn drop_table<'a>(
+        warehouse_id: &WarehouseIdent,
+        table_id: &TableIdentUuid,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        drop_table(warehouse_id, table_id, transaction).await
+    }
+
+    async fn list_expired_dropped_tables(
+        dropped_before: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+        catalog_state: Self::State,
+    ) -> Result<Vec<DroppedTable>> {
+        list_expired_dropped_tables(dropped_before, limit, catalog_state).await
+    }
+
+    async fn reclaim_dropped_table<'a>(
+        warehouse_id: &WarehouseIdent,
+        table_id: &TableIdentUuid,
+        storage_purged: bool,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        reclaim_dropped_table(warehouse_id, table_id, storage_purged, transaction).await
+    }
+
+    async fn table_idents_to_ids(
+        warehouse_id: &WarehouseIdent,
+        tables: HashSet<&TableIdent>,
+        include_staged: bool,
+        catalog_state: Self::State,
+    ) -> Result<HashMap<TableIdent, Option<TableIdentUuid>>> {
+        table_idents_to_ids(
+            warehouse_id,
+            tables,
+            include_staged,
+            &catalog_state.read_pool,
+        )
+        .await
+    }
+
+    async fn commit_table_transaction<'a>(
+        warehouse_id: &WarehouseIdent,
+        request: CommitTransactionRequest,
+        table_ids: &HashMap<TableIdent, TableIdentUuid>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<Vec<CommitTableResponseExt>> {
+        commit_table_transaction(warehouse_id, request, table_ids, transaction).await
+    }
+
+    async fn record_table_commits<'a>(
+        commits: &[TableCommit],
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        record_table_commits(commits, transaction).await
+    }
+
+    // ---------------- Management API ----------------
+    async fn list_projects(catalog_state: Self::State) -> Result<HashSet<ProjectIdent>> {
+        list_projects(catalog_state).await
+    }
+
+    async fn get_projects(
+        project_ids: Option<&HashSet<ProjectIdent>>,
+        catalog_state: Self::State,
+    ) -> Result<Vec<Project>> {
+        get_projects(project_ids, catalog_state).await
+    }
+
+    async fn get_project_id_by_name(
+        project_name: &str,
+        catalog_state: Self::State,
+    ) -> Result<Option<ProjectIdent>> {
+        get_project_id_by_name(project_name, catalog_state).await
+    }
+
+    async fn rename_project<'a>(
+        project_id: &ProjectIdent,
+        new_name: &str,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        rename_project(project_id, new_name, transaction).await
+    }
+
+    async fn set_project_metadata<'a>(
+        project_id: &ProjectIdent,
+        metadata: &HashMap<String, String>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_project_metadata(project_id, metadata, transaction).await
+    }
+
+    async fn delete_project<'a>(
+        project_id: &ProjectIdent,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        delete_project(project_id, transaction).await
+    }
+
+    async fn list_warehouses(
+        project_id: &ProjectIdent,
+        include_inactive: Option<Vec<WarehouseStatus>>,
+        warehouse_id_filter: Option<&HashSet<WarehouseIdent>>,
+        catalog_state: Self::State,
+    ) -> Result<Vec<GetWarehouseResponse>> {
+        list_warehouses(
+            project_id,
+            include_inactive,
+            warehouse_id_filter,
+            catalog_state,
+        )
+        .await
+    }
+
+    async fn get_warehouse_id_by_name(
+        project_id: &ProjectIdent,
+        warehouse_name: &str,
+        catalog_state: Self::State,
+    ) -> Result<Option<WarehouseIdent>> {
+        get_warehouse_id_by_name(project_id, warehouse_name, catalog_state).await
+    }
+
+    async fn delete_warehouse<'a>(
+        warehouse_id: &WarehouseIdent,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        delete_warehouse(warehouse_id, transaction).await
+    }
+
+    async fn rename_warehouse<'a>(
+        warehouse_id: &WarehouseIdent,
+        new_name: &str,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        rename_warehouse(warehouse_id, new_name, transaction).await
+    }
+
+    async fn set_warehouse_status<'a>(
+        warehouse_id: &WarehouseIdent,
+        status: WarehouseStatus,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_warehouse_status(warehouse_id, status, transaction).await
+    }
+
+    async fn update_storage_profile<'a>(
+        warehouse_id: &WarehouseIdent,
+        storage_profile: StorageProfile,
+        storage_secret_id: Option<SecretIdent>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        update_storage_profile(
+            warehouse_id,
+            storage_profile,
+            storage_secret_id,
+            transaction,
+        )
+        .await
+    }
+
+    async fn get_warehouse_allowlist(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<Option<WarehouseAllowlist>> {
+        get_warehouse_allowlist(warehouse_id, catalog_state).await
+    }
+
+    async fn set_warehouse_allowlist<'a>(
+        warehouse_id: &WarehouseIdent,
+        allowlist: Option<WarehouseAllowlist>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_warehouse_allowlist(warehouse_id, allowlist, transaction).await
+    }
+
+    async fn is_warehouse_read_only(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<bool> {
+        is_warehouse_read_only(warehouse_id, catalog_state).await
+    }
+
+    async fn set_warehouse_read_only<'a>(
+        warehouse_id: &WarehouseIdent,
+        read_only: bool,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_warehouse_read_only(warehouse_id, read_only, transaction).await
+    }
+
+    async fn get_warehouse_signer_uri(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<Option<String>> {
+        get_warehouse_signer_uri(warehouse_id, catalog_state).await
+    }
+
+    async fn set_warehouse_signer_uri<'a>(
+        warehouse_id: &WarehouseIdent,
+        signer_uri: Option<&url::Url>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_warehouse_signer_uri(warehouse_id, signer_uri, transaction).await
+    }
+
+    async fn set_warehouse_unauthorized_response<'a>(
+        warehouse_id: &WarehouseIdent,
+        unauthorized_response: Option<UnauthorizedResponse>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_warehouse_unauthorized_response(warehouse_id, unauthorized_response, transaction).await
+    }
+
+    async fn set_warehouse_access_delegation<'a>(
+        warehouse_id: &WarehouseIdent,
+        access_delegation: Option<AccessDelegation>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_warehouse_access_delegation(warehouse_id, access_delegation, transaction).await
+    }
+
+    async fn set_warehouse_allow_external_locations<'a>(
+        warehouse_id: &WarehouseIdent,
+        allow_external_locations: bool,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_warehouse_allow_external_locations(warehouse_id, allow_external_locations, transaction)
+            .await
+    }
+
+    async fn set_warehouse_object_storage_layout<'a>(
+        warehouse_id: &WarehouseIdent,
+        object_storage_layout: bool,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_warehouse_object_storage_layout(warehouse_id, object_storage_layout, transaction).await
+    }
+
+    async fn rotate_warehouse_storage_secret<'a>(
+        warehouse_id: &WarehouseIdent,
+        current_secret_id: Option<&SecretIdent>,
+        new_secret_id: &SecretIdent,
+        rotated_at: chrono::DateTime<chrono::Utc>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        rotate_warehouse_storage_secret(
+            warehouse_id,
+            current_secret_id,
+            new_secret_id,
+            rotated_at,
+            transaction,
+        )
+        .await
+    }
+
+    async fn clear_warehouse_previous_storage_secret(
+        warehouse_id: &WarehouseIdent,
+        previous_secret_id: &SecretIdent,
+        catalog_state: Self::State,
+    ) -> Result<bool> {
+        clear_warehouse_previous_storage_secret(warehouse_id, previous_secret_id, catalog_state)
+            .await
+    }
+
+    async fn set_warehouse_storage_validation(
+        warehouse_id: &WarehouseIdent,
+        validation: &StorageValidation,
+        catalog_state: Self::State,
+    ) -> Result<()> {
+        set_warehouse_storage_validation(warehouse_id, validation, catalog_state).await
+    }
+
+    async fn set_warehouse_retention_policy<'a>(
+        warehouse_id: &WarehouseIdent,
+        policy: Option<SnapshotRetentionPolicy>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_warehouse_retention_policy(warehouse_id, policy, transaction).await
+    }
+
+    async fn get_engine_table_config(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<EngineTableConfig> {
+        get_engine_table_config(warehouse_id, catalog_state).await
+    }
+
+    async fn set_engine_table_config<'a>(
+        warehouse_id: &WarehouseIdent,
+        config: EngineTableConfig,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_engine_table_config(warehouse_id, config, transaction).await
+    }
+
+    async fn set_event_filter<'a>(
+        warehouse_id: &WarehouseIdent,
+        filter: EventFilter,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_event_filter(warehouse_id, filter, transaction).await
+    }
+
+    async fn get_pyiceberg_compat(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<Option<PyicebergCompat>> {
+        get_pyiceberg_compat(warehouse_id, catalog_state).await
+    }
+
+    async fn set_warehouse_pyiceberg_compat<'a>(
+        warehouse_id: &WarehouseIdent,
+        enabled: bool,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_warehouse_pyiceberg_compat(warehouse_id, enabled, transaction).await
+    }
+
+    async fn create_share<'a>(
+        warehouse_id: &WarehouseIdent,
+        object: &SharedObject,
+        consumer_project_id: &ProjectIdent,
+        mount_name: &str,
+        created_by: Option<&str>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<uuid::Uuid> {
+        create_share(
+            warehouse_id,
+            object,
+            consumer_project_id,
+            mount_name,
+            created_by,
+            transaction,
+        )
+        .await
+    }
+
+    async fn list_shares(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<Vec<Share>> {
+        list_shares(warehouse_id, catalog_state).await
+    }
+
+    async fn delete_share<'a>(
+        warehouse_id: &WarehouseIdent,
+        share_id: &uuid::Uuid,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        delete_share(warehouse_id, share_id, transaction).await
+    }
+
+    async fn resolve_share(
+        warehouse_id: &WarehouseIdent,
+        mount_name: &str,
+        catalog_state: Self::State,
+    ) -> Result<Option<ResolvedShare>> {
+        resolve_share(warehouse_id, mount_name, catalog_state).await
+    }
+
+    async fn set_table_policy<'a>(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        policy: Option<TablePolicy>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_table_policy(warehouse_id, table, policy, transaction).await
+    }
+
+    async fn set_namespace_retention_policy<'a>(
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        policy: Option<SnapshotRetentionPolicy>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_namespace_retention_policy(warehouse_id, namespace, policy, transaction).await
+    }
+
+    async fn list_retention_candidates(
+        after: Option<TableIdentUuid>,
+        limit: i64,
+        catalog_state: Self::State,
+    ) -> Result<Vec<RetentionCandidate>> {
+        list_retention_candidates(after, limit, catalog_state).await
+    }
+
+    async fn get_ownership<'a>(
+        warehouse_id: &WarehouseIdent,
+        object: &OwnedObject,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<ObjectOwnership> {
+        get_ownership(warehouse_id, object, transaction).await
+    }
+
+    async fn set_owner<'a>(
+        warehouse_id: &WarehouseIdent,
+        object: &OwnedObject,
+        new_owner: &str,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_owner(warehouse_id, object, new_owner, transaction).await
+    }
+}
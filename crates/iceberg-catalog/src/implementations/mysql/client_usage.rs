@@ -0,0 +1,57 @@
+use super::{dbutils::DBErrorHandler as _, CatalogState};
+use crate::api::Result;
+use crate::service::client_usage::{ClientInfo, ClientUsage};
+use crate::WarehouseIdent;
+use sqlx::Row;
+
+pub(crate) async fn record_client_usage(
+    warehouse_id: &WarehouseIdent,
+    client: &ClientInfo,
+    catalog_state: CatalogState,
+) -> Result<()> {
+    sqlx::query(
+        r"
+        INSERT INTO client_usage (warehouse_id, engine, engine_version, session_count)
+        VALUES (?, ?, ?, 1)
+        ON DUPLICATE KEY UPDATE session_count = session_count + 1, last_seen = CURRENT_TIMESTAMP(6)
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .bind(&client.engine)
+    .bind(client.version.as_deref().unwrap_or_default())
+    .execute(&catalog_state.write_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error recording client usage".into()))?;
+
+    Ok(())
+}
+
+pub(crate) async fn list_client_usage(
+    warehouse_id: &WarehouseIdent,
+    catalog_state: CatalogState,
+) -> Result<Vec<ClientUsage>> {
+    let usage = sqlx::query(
+        r"
+        SELECT engine, engine_version, session_count, first_seen, last_seen
+        FROM client_usage
+        WHERE warehouse_id = ?
+        ORDER BY engine, engine_version
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching client usage".into()))?;
+
+    Ok(usage
+        .into_iter()
+        .map(|row| ClientUsage {
+            engine: row.get("engine"),
+            version: Some(row.get::<String, _>("engine_version"))
+                .filter(|version| !version.is_empty()),
+            session_count: row.get("session_count"),
+            first_seen: row.get("first_seen"),
+            last_seen: row.get("last_seen"),
+        })
+        .collect())
+}
@@ -0,0 +1,186 @@
+//! Catalog backed by MySQL 8.
+//!
+//! Queries are checked at runtime, as offline query metadata is only kept for Postgres.
+//! They are covered by running the conformance suite against MySQL in CI.
+//! Updates rely on the number of matched rows, which `sqlx` reports by connecting with
+//! `CLIENT_FOUND_ROWS`. Secrets are encrypted by the catalog before they are stored, see
+//! [`secrets`].
+pub(crate) mod api_usage;
+mod catalog;
+pub(crate) mod client_usage;
+pub(crate) mod namespace;
+pub(crate) mod ownership;
+pub(crate) mod role_assignment;
+pub(crate) mod secrets;
+pub(crate) mod share;
+pub(crate) mod storage_usage;
+mod stores;
+pub(crate) mod table;
+pub(crate) mod token_revocation;
+pub(crate) mod warehouse;
+pub(crate) mod webhook;
+
+use std::str::FromStr;
+
+use crate::CONFIG;
+
+pub use secrets::Server as SecretsStore;
+
+use crate::api::Result;
+
+pub(crate) use super::dbutils;
+
+use self::dbutils::DBErrorHandler;
+
+/// # Errors
+/// Returns an error if the pool creation fails.
+pub async fn get_reader_pool() -> anyhow::Result<sqlx::MySqlPool> {
+    sqlx::mysql::MySqlPoolOptions::new()
+        .max_connections(CONFIG.mysql_read_pool_connections)
+        .connect_with(connect_options(&CONFIG.mysql_database_url_read)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e).context("Error creating read pool."))
+}
+
+/// # Errors
+/// Returns an error if the pool cannot be created.
+pub async fn get_writer_pool() -> anyhow::Result<sqlx::MySqlPool> {
+    sqlx::mysql::MySqlPoolOptions::new()
+        .max_connections(CONFIG.mysql_write_pool_connections)
+        .connect_with(connect_options(&CONFIG.mysql_database_url_write)?)
+        .await
+        .map_err(|e| anyhow::anyhow!(e).context("Error creating write pool."))
+}
+
+/// Options for `url`, with the password replaced by `mysql_password` if set.
+/// Connections use UTC, so that timestamps are stored and compared in UTC.
+fn connect_options(url: &str) -> anyhow::Result<sqlx::mysql::MySqlConnectOptions> {
+    let mut options = sqlx::mysql::MySqlConnectOptions::from_str(url)
+        .map_err(|e| anyhow::anyhow!(e).context("Invalid database URL."))?
+        .timezone(Some("+00:00".to_string()));
+    if let Some(password) = &CONFIG.mysql_password {
+        options = options.password(password);
+    }
+    Ok(options)
+}
+
+/// # Errors
+/// Returns an error if the migration fails.
+pub async fn migrate(pool: &sqlx::MySqlPool) -> anyhow::Result<()> {
+    sqlx::migrate!("migrations-mysql")
+        .run(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e).context("Error migrating database."))?;
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct Catalog {}
+
+#[derive(Debug)]
+#[allow(clippy::module_name_repetitions)]
+pub struct MySqlTransaction {
+    transaction: sqlx::Transaction<'static, sqlx::MySql>,
+}
+
+#[async_trait::async_trait]
+impl crate::service::Transaction<CatalogState> for MySqlTransaction {
+    type Transaction<'a> = &'a mut sqlx::Transaction<'static, sqlx::MySql>;
+
+    async fn begin_write(db_state: CatalogState) -> Result<Self> {
+        let transaction = db_state
+            .write_pool
+            .begin()
+            .await
+            .map_err(|e| e.into_error_model("Error starting transaction".to_string()))?;
+
+        Ok(Self { transaction })
+    }
+
+    async fn begin_read(db_state: CatalogState) -> Result<Self> {
+        let transaction = db_state
+            .read_pool
+            .begin()
+            .await
+            .map_err(|e| e.into_error_model("Error starting transaction".to_string()))?;
+
+        Ok(Self { transaction })
+    }
+
+    async fn commit(self) -> Result<()> {
+        self.transaction
+            .commit()
+            .await
+            .map_err(|e| e.into_error_model("Error committing transaction".to_string()))?;
+        Ok(())
+    }
+
+    async fn rollback(self) -> Result<()> {
+        self.transaction
+            .rollback()
+            .await
+            .map_err(|e| e.into_error_model("Error rolling back transaction".to_string()))?;
+        Ok(())
+    }
+
+    fn transaction(&mut self) -> Self::Transaction<'_> {
+        &mut self.transaction
+    }
+}
+
+#[derive(Clone, Debug)]
+#[allow(clippy::module_name_repetitions)]
+pub struct CatalogState {
+    pub read_pool: sqlx::MySqlPool,
+    pub write_pool: sqlx::MySqlPool,
+}
+
+#[derive(Clone, Debug)]
+#[allow(clippy::module_name_repetitions)]
+pub struct SecretsState {
+    pub read_pool: sqlx::MySqlPool,
+    pub write_pool: sqlx::MySqlPool,
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod test {
+    use super::{
+        get_reader_pool, get_writer_pool, migrate, Catalog, CatalogState, SecretsState,
+        SecretsStore,
+    };
+    use crate::service::storage::{FileProfile, StorageProfile};
+    use crate::service::stores::Stores;
+    use crate::test_util::conformance::run_conformance_suite;
+
+    /// Requires the databases of `mysql_database_url_read` and `mysql_database_url_write`
+    /// and `file_storage_enabled`, see the `test-mysql` job of the unittests workflow.
+    #[tokio::test]
+    #[ignore = "requires MySQL"]
+    async fn test_conformance() {
+        let write_pool = get_writer_pool().await.unwrap();
+        migrate(&write_pool).await.unwrap();
+        let read_pool = get_reader_pool().await.unwrap();
+        let secrets_state = SecretsState {
+            read_pool: read_pool.clone(),
+            write_pool: write_pool.clone(),
+        };
+        let catalog_state = CatalogState {
+            read_pool,
+            write_pool,
+        };
+        let path = std::env::temp_dir().join(format!("conformance-{}", uuid::Uuid::now_v7()));
+
+        run_conformance_suite::<Catalog, Catalog, SecretsStore>(
+            catalog_state.clone(),
+            secrets_state,
+            Stores::new(catalog_state),
+            StorageProfile::File(FileProfile {
+                path: path.to_string_lossy().to_string(),
+            }),
+            None,
+        )
+        .await
+        .unwrap()
+        .assert_success();
+    }
+}
@@ -0,0 +1,392 @@
+use crate::service::{
+    CreateNamespaceRequest, CreateNamespaceResponse, ErrorModel, ErrorType, GetNamespaceResponse,
+    ListNamespacesQuery, ListNamespacesResponse, NamespaceIdent, Result, SnapshotRetentionPolicy,
+    UpdateNamespacePropertiesRequest, UpdateNamespacePropertiesResponse,
+};
+use http::StatusCode;
+use sqlx::{types::Json, Row};
+use std::collections::HashMap;
+
+use crate::{service::NamespaceIdentUuid, WarehouseIdent};
+
+use super::{dbutils::DBErrorHandler, CatalogState};
+
+/// Separates the elements of a namespace in its `namespace_key`.
+const NAMESPACE_KEY_SEPARATOR: char = '\u{1f}';
+
+/// Unique key of `namespace` within its warehouse.
+pub(super) fn namespace_key(namespace: &NamespaceIdent) -> String {
+    namespace.join(&NAMESPACE_KEY_SEPARATOR.to_string())
+}
+
+/// Keys of `namespace` and all its parents.
+pub(super) fn ancestor_keys(namespace: &NamespaceIdent) -> Vec<String> {
+    (1..=namespace.len())
+        .map(|len| namespace[..len].join(&NAMESPACE_KEY_SEPARATOR.to_string()))
+        .collect()
+}
+
+pub(super) fn parse_namespace(namespace: Json<Vec<String>>) -> Result<NamespaceIdent> {
+    NamespaceIdent::from_vec(namespace.0).map_err(|e| {
+        ErrorModel::builder()
+            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+            .message("Error parsing namespace".to_string())
+            .r#type("NamespaceParseError".to_string())
+            .stack(Some(vec![e.to_string()]))
+            .build()
+            .into()
+    })
+}
+
+/// `LIKE` pattern matching the keys of all namespaces below `parent`.
+fn children_pattern(parent: &NamespaceIdent) -> String {
+    let mut pattern = String::new();
+    for c in namespace_key(parent).chars() {
+        if matches!(c, '!' | '%' | '_') {
+            pattern.push('!');
+        }
+        pattern.push(c);
+    }
+    pattern.push(NAMESPACE_KEY_SEPARATOR);
+    pattern.push('%');
+    pattern
+}
+
+fn namespace_not_found(namespace: &NamespaceIdent) -> ErrorModel {
+    ErrorModel::builder()
+        .code(StatusCode::NOT_FOUND.into())
+        .message(format!("Namespace not found: {:?}", namespace.as_ref()))
+        .r#type("NamespaceNotFound".to_string())
+        .error_type(ErrorType::NoSuchNamespace)
+        .build()
+}
+
+pub(crate) async fn get_namespace(
+    warehouse_id: &WarehouseIdent,
+    namespace: &NamespaceIdent,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<GetNamespaceResponse> {
+    let row = sqlx::query(
+        r"
+        SELECT n.namespace_id, n.namespace_properties
+        FROM namespace n
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE n.warehouse_id = ? AND n.namespace_key = ?
+        AND w.status = 'active'
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .bind(namespace_key(namespace))
+    .fetch_optional(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching namespace".to_string()))?
+    .ok_or_else(|| namespace_not_found(namespace))?;
+
+    let properties = row
+        .try_get::<Json<HashMap<String, String>>, _>("namespace_properties")
+        .map_err(|e| e.into_error_model("Error parsing namespace properties".to_string()))?;
+
+    Ok(GetNamespaceResponse {
+        namespace: namespace.to_owned(),
+        properties: Some(properties.0),
+        namespace_id: row.get::<uuid::Uuid, _>("namespace_id").into(),
+        warehouse_id: warehouse_id.clone(),
+    })
+}
+
+pub(crate) async fn list_namespaces(
+    warehouse_id: &WarehouseIdent,
+    query: &ListNamespacesQuery,
+    catalog_state: CatalogState,
+) -> Result<ListNamespacesResponse> {
+    let ListNamespacesQuery {
+        page_token: _,
+        page_size: _,
+        parent,
+    } = query;
+
+    // Treat empty parent as None
+    let parent = parent.as_ref().filter(|p| !p.is_empty());
+
+    let rows = if let Some(parent) = parent {
+        // Namespaces with length(parent) + 1 elements whose key starts with the key of parent.
+        sqlx::query(
+            r"
+            SELECT n.namespace_name
+            FROM namespace n
+            INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+            WHERE n.warehouse_id = ?
+            AND w.status = 'active'
+            AND n.namespace_key LIKE ? ESCAPE '!'
+            AND JSON_LENGTH(n.namespace_name) = ?
+            ORDER BY n.namespace_key
+            ",
+        )
+        .bind(*warehouse_id.as_uuid())
+        .bind(children_pattern(parent))
+        .bind(u64::try_from(parent.len() + 1).unwrap_or(u64::MAX))
+        .fetch_all(&catalog_state.read_pool)
+        .await
+    } else {
+        sqlx::query(
+            r"
+            SELECT n.namespace_name
+            FROM namespace n
+            INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+            WHERE n.warehouse_id = ?
+            AND w.status = 'active'
+            ORDER BY n.namespace_key
+            ",
+        )
+        .bind(*warehouse_id.as_uuid())
+        .fetch_all(&catalog_state.read_pool)
+        .await
+    };
+    let rows = rows.map_err(|e| e.into_error_model("Error fetching Namespace".into()))?;
+
+    let mut namespaces = Vec::with_capacity(rows.len());
+    for row in rows {
+        let namespace = row
+            .try_get::<Json<Vec<String>>, _>("namespace_name")
+            .map_err(|e| e.into_error_model("Error fetching Namespace".into()))?;
+        let namespace = match parent {
+            // Only the last element is returned for children, as in the Postgres catalog.
+            Some(_) => namespace.0.last().cloned().map(|name| Json(vec![name])),
+            None => Some(namespace),
+        };
+        if let Some(namespace) = namespace {
+            namespaces.push(parse_namespace(namespace)?);
+        }
+    }
+
+    Ok(ListNamespacesResponse {
+        next_page_token: None,
+        namespaces,
+    })
+}
+
+pub(crate) async fn create_namespace(
+    warehouse_id: &WarehouseIdent,
+    request: CreateNamespaceRequest,
+    owner: Option<&str>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<CreateNamespaceResponse> {
+    let CreateNamespaceRequest {
+        namespace,
+        properties,
+    } = request;
+
+    let result = sqlx::query(
+        r"
+        INSERT INTO namespace
+            (namespace_id, warehouse_id, namespace_name, namespace_key, namespace_properties, created_by, `owner`)
+        SELECT ?, ?, ?, ?, ?, ?, ?
+        FROM warehouse
+        WHERE warehouse_id = ? AND status = 'active'
+        ",
+    )
+    .bind(uuid::Uuid::now_v7())
+    .bind(*warehouse_id.as_uuid())
+    .bind(Json(namespace.clone().inner()))
+    .bind(namespace_key(&namespace))
+    .bind(Json(properties.clone().unwrap_or_default()))
+    .bind(owner)
+    .bind(owner)
+    .bind(*warehouse_id.as_uuid())
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db_error) => {
+            if db_error.is_unique_violation() {
+                ErrorModel::builder()
+                    .code(StatusCode::CONFLICT.into())
+                    .message("Namespace already exists".to_string())
+                    .r#type("NamespaceAlreadyExists".to_string())
+                    .error_type(ErrorType::AlreadyExists)
+                    .build()
+            } else if db_error.is_foreign_key_violation() {
+                ErrorModel::builder()
+                    .code(StatusCode::NOT_FOUND.into())
+                    .message("Warehouse not found".to_string())
+                    .r#type("WarehouseNotFound".to_string())
+                    .build()
+            } else {
+                ErrorModel::builder()
+                    .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                    .message("Error creating namespace".to_string())
+                    .r#type("NamespaceCreateError".to_string())
+                    .stack(Some(vec![db_error.to_string()]))
+                    .build()
+            }
+        }
+        _ => e.into_error_model("Error creating Namespace".into()),
+    })?;
+
+    if result.rows_affected() == 0 {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Warehouse not found".to_string())
+            .r#type("WarehouseNotFound".to_string())
+            .build()
+            .into());
+    }
+
+    Ok(CreateNamespaceResponse {
+        namespace,
+        // Return None if properties is empty
+        properties: properties.filter(|p| !p.is_empty()),
+    })
+}
+
+pub(crate) async fn namespace_ident_to_id(
+    warehouse_id: &WarehouseIdent,
+    namespace: &NamespaceIdent,
+    catalog_state: CatalogState,
+) -> Result<Option<NamespaceIdentUuid>> {
+    let namespace_id = sqlx::query_scalar::<_, uuid::Uuid>(
+        r"
+        SELECT n.namespace_id
+        FROM namespace n
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE n.warehouse_id = ? AND n.namespace_key = ?
+        AND w.status = 'active'
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .bind(namespace_key(namespace))
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching namespace".to_string()))?;
+
+    Ok(namespace_id.map(Into::into))
+}
+
+pub(crate) async fn drop_namespace(
+    warehouse_id: &WarehouseIdent,
+    namespace: &NamespaceIdent,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    // Return 404 not found if namespace does not exist
+    let result = sqlx::query(
+        r"
+        DELETE n FROM namespace n
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE n.warehouse_id = ? AND n.namespace_key = ?
+        AND w.status = 'active'
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .bind(namespace_key(namespace))
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db_error) if db_error.is_foreign_key_violation() => {
+            ErrorModel::builder()
+                .code(StatusCode::CONFLICT.into())
+                .message("Namespace is not empty".to_string())
+                .r#type("NamespaceNotEmpty".to_string())
+                .error_type(ErrorType::NamespaceNotEmpty)
+                .build()
+        }
+        _ => e.as_error_model("Error deleting namespace".to_string()),
+    })?;
+
+    if result.rows_affected() == 0 {
+        return Err(namespace_not_found(namespace).into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn set_namespace_retention_policy(
+    warehouse_id: &WarehouseIdent,
+    namespace: &NamespaceIdent,
+    policy: Option<SnapshotRetentionPolicy>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result = sqlx::query(
+        r"
+        UPDATE namespace n
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        SET n.retention_policy = ?
+        WHERE n.warehouse_id = ? AND n.namespace_key = ?
+        AND w.status = 'active'
+        ",
+    )
+    .bind(policy.map(Json))
+    .bind(*warehouse_id.as_uuid())
+    .bind(namespace_key(namespace))
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error setting namespace retention policy".into()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Namespace not found".to_string())
+            .r#type("NoSuchNamespaceError".to_string())
+            .error_type(ErrorType::NoSuchNamespace)
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn update_namespace_properties(
+    warehouse_id: &WarehouseIdent,
+    namespace: &NamespaceIdent,
+    request: UpdateNamespacePropertiesRequest,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<UpdateNamespacePropertiesResponse> {
+    let UpdateNamespacePropertiesRequest { removals, updates } = request;
+
+    let mut properties = get_namespace(warehouse_id, namespace, &mut *transaction)
+        .await?
+        .properties
+        .unwrap_or_default();
+
+    let mut updated = vec![];
+    let mut removed = vec![];
+    let mut missing = vec![];
+
+    for key in removals.into_iter().flatten() {
+        if properties.remove(&key).is_some() {
+            removed.push(key);
+        } else {
+            missing.push(key);
+        }
+    }
+
+    for (key, value) in updates.into_iter().flatten() {
+        // Push to updated if the value for the key is different.
+        // Also push on insert
+        if properties.insert(key.clone(), value.clone()) != Some(value) {
+            updated.push(key);
+        }
+    }
+
+    sqlx::query(
+        r"
+        UPDATE namespace
+        SET namespace_properties = ?
+        WHERE warehouse_id = ? AND namespace_key = ?
+        ",
+    )
+    .bind(Json(properties))
+    .bind(*warehouse_id.as_uuid())
+    .bind(namespace_key(namespace))
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error updating namespace properties".to_string()))?;
+
+    Ok(UpdateNamespacePropertiesResponse {
+        updated,
+        removed,
+        missing: if missing.is_empty() {
+            None
+        } else {
+            Some(missing)
+        },
+    })
+}
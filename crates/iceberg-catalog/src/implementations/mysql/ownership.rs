@@ -0,0 +1,158 @@
+use super::dbutils::DBErrorHandler as _;
+use super::namespace::namespace_key;
+use crate::api::{ErrorModel, ErrorType, Result};
+use crate::service::{ObjectOwnership, OwnedObject, TableIdent};
+use crate::WarehouseIdent;
+use http::StatusCode;
+
+pub(crate) async fn get_ownership(
+    warehouse_id: &WarehouseIdent,
+    object: &OwnedObject,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<ObjectOwnership> {
+    let ownership = match object {
+        OwnedObject::Warehouse => {
+            sqlx::query_as::<_, (Option<String>, Option<String>)>(
+                r"
+            SELECT `owner`, created_by
+            FROM warehouse
+            WHERE warehouse_id = ?
+            ",
+            )
+            .bind(*warehouse_id.as_uuid())
+            .fetch_optional(&mut **transaction)
+            .await
+        }
+        OwnedObject::Namespace(namespace) => {
+            sqlx::query_as::<_, (Option<String>, Option<String>)>(
+                r"
+                SELECT n.`owner`, n.created_by
+                FROM namespace n
+                INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+                WHERE n.warehouse_id = ? AND n.namespace_key = ?
+                AND w.status = 'active'
+                ",
+            )
+            .bind(*warehouse_id.as_uuid())
+            .bind(namespace_key(namespace))
+            .fetch_optional(&mut **transaction)
+            .await
+        }
+        OwnedObject::Table(TableIdent { namespace, name }) => {
+            sqlx::query_as::<_, (Option<String>, Option<String>)>(
+                r"
+                SELECT t.`owner`, t.created_by
+                FROM `table` t
+                INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+                INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+                WHERE n.warehouse_id = ? AND n.namespace_key = ? AND t.table_name = ?
+                AND w.status = 'active'
+                ",
+            )
+            .bind(*warehouse_id.as_uuid())
+            .bind(namespace_key(namespace))
+            .bind(name)
+            .fetch_optional(&mut **transaction)
+            .await
+        }
+    };
+
+    let (owner, created_by) = ownership
+        .map_err(|e| e.into_error_model("Error accessing ownership information".to_string()))?
+        .ok_or_else(|| not_found(object))?;
+
+    Ok(ObjectOwnership { owner, created_by })
+}
+
+pub(crate) async fn set_owner(
+    warehouse_id: &WarehouseIdent,
+    object: &OwnedObject,
+    new_owner: &str,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    if new_owner.is_empty() {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("New owner cannot be empty".to_string())
+            .r#type("EmptyOwner".to_string())
+            .build()
+            .into());
+    }
+
+    let result = match object {
+        OwnedObject::Warehouse => {
+            sqlx::query("UPDATE warehouse SET `owner` = ? WHERE warehouse_id = ?")
+                .bind(new_owner)
+                .bind(*warehouse_id.as_uuid())
+                .execute(&mut **transaction)
+                .await
+        }
+        OwnedObject::Namespace(namespace) => {
+            sqlx::query(
+                r"
+                UPDATE namespace n
+                INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+                SET n.`owner` = ?
+                WHERE n.warehouse_id = ? AND n.namespace_key = ?
+                AND w.status = 'active'
+                ",
+            )
+            .bind(new_owner)
+            .bind(*warehouse_id.as_uuid())
+            .bind(namespace_key(namespace))
+            .execute(&mut **transaction)
+            .await
+        }
+        OwnedObject::Table(TableIdent { namespace, name }) => {
+            sqlx::query(
+                r"
+                UPDATE `table` t
+                INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+                INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+                SET t.`owner` = ?
+                WHERE n.warehouse_id = ? AND n.namespace_key = ? AND t.table_name = ?
+                AND w.status = 'active'
+                ",
+            )
+            .bind(new_owner)
+            .bind(*warehouse_id.as_uuid())
+            .bind(namespace_key(namespace))
+            .bind(name)
+            .execute(&mut **transaction)
+            .await
+        }
+    }
+    .map_err(|e| e.into_error_model("Error accessing ownership information".to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(not_found(object).into());
+    }
+
+    Ok(())
+}
+
+fn not_found(object: &OwnedObject) -> ErrorModel {
+    let (message, r#type, error_type) = match object {
+        OwnedObject::Warehouse => (
+            "Warehouse not found",
+            "WarehouseNotFound",
+            ErrorType::NotFound,
+        ),
+        OwnedObject::Namespace(_) => (
+            "Namespace not found",
+            "NamespaceNotFound",
+            ErrorType::NoSuchNamespace,
+        ),
+        OwnedObject::Table(_) => (
+            "Table not found",
+            "NoSuchTableError",
+            ErrorType::NoSuchTable,
+        ),
+    };
+    ErrorModel::builder()
+        .code(StatusCode::NOT_FOUND.into())
+        .message(message.to_string())
+        .r#type(r#type.to_string())
+        .error_type(error_type)
+        .build()
+}
@@ -0,0 +1,376 @@
+use super::{
+    dbutils::DBErrorHandler as _,
+    namespace::{ancestor_keys, namespace_key, parse_namespace},
+    CatalogState,
+};
+use crate::api::{ErrorModel, ErrorType, Result};
+use crate::service::{Role, RoleAssignment, RoleObject, RoleTarget, TableIdent};
+use crate::{ProjectIdent, WarehouseIdent};
+use http::StatusCode;
+use sqlx::{types::Json, Row};
+
+/// Ids of the object a role is granted on.
+struct ObjectIds {
+    project_id: uuid::Uuid,
+    warehouse_id: Option<uuid::Uuid>,
+    namespace_id: Option<uuid::Uuid>,
+    table_id: Option<uuid::Uuid>,
+}
+
+impl ObjectIds {
+    fn object_id(&self) -> uuid::Uuid {
+        self.table_id
+            .or(self.namespace_id)
+            .or(self.warehouse_id)
+            .unwrap_or(self.project_id)
+    }
+}
+
+fn not_found(message: &str, r#type: &str, error_type: ErrorType) -> ErrorModel {
+    ErrorModel::builder()
+        .code(StatusCode::NOT_FOUND.into())
+        .message(message.to_string())
+        .r#type(r#type.to_string())
+        .error_type(error_type)
+        .build()
+}
+
+async fn resolve_object(
+    object: &RoleObject,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<ObjectIds> {
+    match object {
+        RoleObject::Project(project_id) => {
+            let project_id = sqlx::query_scalar::<_, uuid::Uuid>(
+                "SELECT project_id FROM project WHERE project_id = ?",
+            )
+            .bind(*project_id.as_uuid())
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching project".into()))?
+            .ok_or_else(|| {
+                not_found("Project not found", "ProjectNotFound", ErrorType::NotFound)
+            })?;
+            Ok(ObjectIds {
+                project_id,
+                warehouse_id: None,
+                namespace_id: None,
+                table_id: None,
+            })
+        }
+        RoleObject::Warehouse(warehouse_id) => {
+            let project_id = sqlx::query_scalar::<_, uuid::Uuid>(
+                "SELECT project_id FROM warehouse WHERE warehouse_id = ? AND `status` = 'active'",
+            )
+            .bind(*warehouse_id.as_uuid())
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching warehouse".into()))?
+            .ok_or_else(|| {
+                not_found(
+                    "Warehouse not found",
+                    "WarehouseNotFound",
+                    ErrorType::NotFound,
+                )
+            })?;
+            Ok(ObjectIds {
+                project_id,
+                warehouse_id: Some(*warehouse_id.as_uuid()),
+                namespace_id: None,
+                table_id: None,
+            })
+        }
+        RoleObject::Namespace(warehouse_id, namespace) => {
+            let (project_id, namespace_id) = sqlx::query_as::<_, (uuid::Uuid, uuid::Uuid)>(
+                r"
+                SELECT w.project_id, n.namespace_id
+                FROM namespace n
+                INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+                WHERE n.warehouse_id = ? AND n.namespace_key = ?
+                AND w.status = 'active'
+                ",
+            )
+            .bind(*warehouse_id.as_uuid())
+            .bind(namespace_key(namespace))
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching namespace".into()))?
+            .ok_or_else(|| {
+                not_found(
+                    "Namespace not found",
+                    "NoSuchNamespaceError",
+                    ErrorType::NoSuchNamespace,
+                )
+            })?;
+            Ok(ObjectIds {
+                project_id,
+                warehouse_id: Some(*warehouse_id.as_uuid()),
+                namespace_id: Some(namespace_id),
+                table_id: None,
+            })
+        }
+        RoleObject::Table(warehouse_id, TableIdent { namespace, name }) => {
+            let (project_id, table_id) = sqlx::query_as::<_, (uuid::Uuid, uuid::Uuid)>(
+                r"
+                SELECT w.project_id, t.table_id
+                FROM `table` t
+                INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+                INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+                WHERE n.warehouse_id = ? AND n.namespace_key = ? AND t.table_name = ?
+                AND w.status = 'active'
+                AND t.metadata_location IS NOT NULL
+                ",
+            )
+            .bind(*warehouse_id.as_uuid())
+            .bind(namespace_key(namespace))
+            .bind(name)
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching table".into()))?
+            .ok_or_else(|| {
+                not_found(
+                    "Table not found",
+                    "NoSuchTableError",
+                    ErrorType::NoSuchTable,
+                )
+            })?;
+            Ok(ObjectIds {
+                project_id,
+                warehouse_id: Some(*warehouse_id.as_uuid()),
+                namespace_id: None,
+                table_id: Some(table_id),
+            })
+        }
+    }
+}
+
+pub(crate) async fn grant_role(
+    object: &RoleObject,
+    principal: &str,
+    role: Role,
+    created_by: Option<&str>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<uuid::Uuid> {
+    if principal.is_empty() {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("Principal cannot be empty".to_string())
+            .r#type("EmptyPrincipal".to_string())
+            .build()
+            .into());
+    }
+
+    let ids = resolve_object(object, transaction).await?;
+    sqlx::query(
+        r"
+        INSERT INTO role_assignment (assignment_id, principal, `role`, project_id, warehouse_id, namespace_id, table_id, object_id, created_by)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON DUPLICATE KEY UPDATE `role` = VALUES(`role`)
+        ",
+    )
+    .bind(uuid::Uuid::now_v7())
+    .bind(principal)
+    .bind(role)
+    .bind(ids.project_id)
+    .bind(ids.warehouse_id)
+    .bind(ids.namespace_id)
+    .bind(ids.table_id)
+    .bind(ids.object_id())
+    .bind(created_by)
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error granting role".into()))?;
+
+    sqlx::query_scalar::<_, uuid::Uuid>(
+        "SELECT assignment_id FROM role_assignment WHERE principal = ? AND object_id = ?",
+    )
+    .bind(principal)
+    .bind(ids.object_id())
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error granting role".into()).into())
+}
+
+pub(crate) async fn revoke_role(
+    object: &RoleObject,
+    principal: &str,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let ids = resolve_object(object, transaction).await?;
+    let result = sqlx::query("DELETE FROM role_assignment WHERE principal = ? AND object_id = ?")
+        .bind(principal)
+        .bind(ids.object_id())
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error revoking role".into()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(not_found(
+            "Role assignment not found",
+            "RoleAssignmentNotFound",
+            ErrorType::NotFound,
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn list_role_assignments(
+    project_id: Option<&ProjectIdent>,
+    principal: Option<&str>,
+    catalog_state: CatalogState,
+) -> Result<Vec<RoleAssignment>> {
+    let mut query_builder = sqlx::QueryBuilder::new(
+        r"
+        SELECT
+            ra.assignment_id,
+            ra.principal,
+            ra.`role`,
+            ra.project_id,
+            ra.warehouse_id,
+            ra.created_by,
+            COALESCE(n.namespace_name, tn.namespace_name) AS namespace_name,
+            t.table_name
+        FROM role_assignment ra
+        LEFT JOIN namespace n ON ra.namespace_id = n.namespace_id
+        LEFT JOIN `table` t ON ra.table_id = t.table_id
+        LEFT JOIN namespace tn ON t.namespace_id = tn.namespace_id
+        WHERE true
+        ",
+    );
+    if let Some(project_id) = project_id {
+        query_builder.push(" AND ra.project_id = ");
+        query_builder.push_bind(*project_id.as_uuid());
+    }
+    if let Some(principal) = principal {
+        query_builder.push(" AND ra.principal = ");
+        query_builder.push_bind(principal);
+    }
+    query_builder.push(" ORDER BY ra.created_at");
+
+    let assignments = query_builder
+        .build()
+        .fetch_all(&catalog_state.read_pool)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching role assignments".into()))?;
+
+    assignments
+        .into_iter()
+        .map(|row| {
+            let project_id = row.get::<uuid::Uuid, _>("project_id");
+            let namespace = row
+                .get::<Option<Json<Vec<String>>>, _>("namespace_name")
+                .map(parse_namespace)
+                .transpose()?;
+            let object = match (
+                row.get::<Option<uuid::Uuid>, _>("warehouse_id"),
+                namespace,
+                row.get::<Option<String>, _>("table_name"),
+            ) {
+                (Some(warehouse_id), Some(namespace), Some(name)) => {
+                    RoleObject::Table(warehouse_id.into(), TableIdent::new(namespace, name))
+                }
+                (Some(warehouse_id), Some(namespace), None) => {
+                    RoleObject::Namespace(warehouse_id.into(), namespace)
+                }
+                (Some(warehouse_id), None, _) => RoleObject::Warehouse(warehouse_id.into()),
+                (None, _, _) => RoleObject::Project(project_id.into()),
+            };
+            Ok(RoleAssignment {
+                assignment_id: row.get("assignment_id"),
+                principal: row.get("principal"),
+                role: row.get("role"),
+                object,
+                project_id: project_id.into(),
+                created_by: row.get("created_by"),
+            })
+        })
+        .collect()
+}
+
+pub(crate) async fn get_roles(
+    principal: &str,
+    target: &RoleTarget,
+    catalog_state: CatalogState,
+) -> Result<Vec<Role>> {
+    let (warehouse_id, namespace, table_id) = match target {
+        RoleTarget::Project(project_id) => {
+            return sqlx::query_scalar::<_, Role>(
+                r"
+                SELECT `role`
+                FROM role_assignment
+                WHERE principal = ? AND project_id = ? AND warehouse_id IS NULL
+                ",
+            )
+            .bind(principal)
+            .bind(*project_id.as_uuid())
+            .fetch_all(&catalog_state.read_pool)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching roles".into()).into());
+        }
+        RoleTarget::Warehouse(warehouse_id) => (warehouse_id, None, None),
+        RoleTarget::Namespace(warehouse_id, namespace) => {
+            (warehouse_id, Some(namespace.clone()), None)
+        }
+        RoleTarget::Table(warehouse_id, table_id) => {
+            let namespace = sqlx::query_scalar::<_, Json<Vec<String>>>(
+                r"
+                SELECT n.namespace_name
+                FROM `table` t
+                INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+                WHERE t.table_id = ? AND n.warehouse_id = ?
+                ",
+            )
+            .bind(*table_id.as_uuid())
+            .bind(*warehouse_id.as_uuid())
+            .fetch_optional(&catalog_state.read_pool)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching table".into()))?
+            .map(parse_namespace)
+            .transpose()?;
+            (warehouse_id, namespace, Some(*table_id.as_uuid()))
+        }
+    };
+
+    // Roles on namespaces apply to all namespaces below them.
+    let mut query_builder = sqlx::QueryBuilder::new(
+        r"
+        SELECT ra.`role`
+        FROM role_assignment ra
+        INNER JOIN warehouse w ON w.warehouse_id = ",
+    );
+    query_builder.push_bind(*warehouse_id.as_uuid());
+    query_builder.push(
+        r"
+        LEFT JOIN namespace n ON ra.namespace_id = n.namespace_id
+        WHERE ra.principal = ",
+    );
+    query_builder.push_bind(principal);
+    query_builder.push(
+        r"
+        AND (
+            (ra.project_id = w.project_id AND ra.warehouse_id IS NULL)
+            OR (ra.warehouse_id = w.warehouse_id AND ra.namespace_id IS NULL AND ra.table_id IS NULL)
+        ",
+    );
+    if let Some(namespace) = namespace {
+        query_builder.push(" OR (n.warehouse_id = w.warehouse_id AND n.namespace_key IN (");
+        let mut separated = query_builder.separated(", ");
+        for key in ancestor_keys(&namespace) {
+            separated.push_bind(key);
+        }
+        query_builder.push("))");
+    }
+    if let Some(table_id) = table_id {
+        query_builder.push(" OR ra.table_id = ");
+        query_builder.push_bind(table_id);
+    }
+    query_builder.push(")");
+
+    query_builder
+        .build_query_scalar::<Role>()
+        .fetch_all(&catalog_state.read_pool)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching roles".into()).into())
+}
@@ -0,0 +1,215 @@
+//! Secrets stored in MySQL.
+//!
+//! MySQL has no counterpart of `pgp_sym_encrypt`: `AES_ENCRYPT` uses ECB unless the
+//! `block_encryption_mode` of the session is changed. Secrets are therefore encrypted by the
+//! catalog with AES-256-GCM, keyed by the SHA-256 digest of `mysql_encryption_key`. The id of
+//! the secret is authenticated as well, so that secrets can't be swapped between rows.
+use super::SecretsState;
+use crate::api::{ErrorModel, Result};
+use crate::service::secrets::{Secret, SecretIdent, SecretInStorage, SecretStore};
+use crate::CONFIG;
+use http::StatusCode;
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+#[derive(Debug, Clone)]
+pub struct Server {}
+
+#[async_trait::async_trait]
+impl SecretStore for Server {
+    type State = SecretsState;
+
+    /// Get the secret for a given warehouse.
+    async fn get_secret_by_id<S: SecretInStorage + for<'de> Deserialize<'de>>(
+        secret_id: &SecretIdent,
+        state: SecretsState,
+    ) -> Result<Secret<S>> {
+        let row = sqlx::query(
+            r"
+            SELECT secret, created_at, updated_at
+            FROM secret
+            WHERE secret_id = ?
+            ",
+        )
+        .bind(*secret_id.as_uuid())
+        .fetch_optional(&state.read_pool)
+        .await
+        .map_err(|e| {
+            ErrorModel::builder()
+                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message("Error fetching secret".to_string())
+                .r#type("SecretFetchError".to_string())
+                .stack(Some(vec![
+                    format!("secret_id: {}", secret_id),
+                    e.to_string(),
+                ]))
+                .build()
+        })?
+        .ok_or_else(|| {
+            ErrorModel::builder()
+                .code(StatusCode::NOT_FOUND.into())
+                .message("Secret not found".to_string())
+                .r#type("SecretNotFound".to_string())
+                .stack(Some(vec![format!("secret_id: {}", secret_id)]))
+                .build()
+        })?;
+
+        let inner = decrypt(secret_id, &row.get::<Vec<u8>, _>("secret"))
+            .and_then(|secret| serde_json::from_slice(&secret).ok())
+            .ok_or_else(|| {
+                ErrorModel::builder()
+                    .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                    .message("Error parsing secret".to_string())
+                    .r#type("SecretParseError".to_string())
+                    // We do not add the error here as it might contain sensitive information
+                    .stack(Some(vec![format!("Secret ID: {}", secret_id)]))
+                    .build()
+            })?;
+
+        Ok(Secret {
+            secret_id: secret_id.clone(),
+            secret: inner,
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        })
+    }
+
+    /// Create a new secret
+    async fn create_secret<S: SecretInStorage + Send + Sync + Serialize + std::fmt::Debug>(
+        secret: S,
+        state: SecretsState,
+    ) -> Result<SecretIdent> {
+        let secret_str = serde_json::to_vec(&secret).map_err(|_e| {
+            ErrorModel::builder()
+                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message("Error serializing secret".to_string())
+                .r#type("SecretSerializeError".to_string())
+                // Redacted by veil
+                .stack(Some(vec![format!("secret: {:?}", secret)]))
+                .build()
+        })?;
+
+        let secret_id = SecretIdent::from(uuid::Uuid::now_v7());
+        let encrypted = encrypt(&secret_id, &secret_str).map_err(|e| {
+            ErrorModel::builder()
+                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message("Error encrypting secret".to_string())
+                .r#type("SecretEncryptError".to_string())
+                .stack(Some(vec![e.to_string()]))
+                .build()
+        })?;
+
+        sqlx::query(
+            r"
+            INSERT INTO secret (secret_id, secret)
+            VALUES (?, ?)
+            ",
+        )
+        .bind(*secret_id.as_uuid())
+        .bind(encrypted)
+        .execute(&state.write_pool)
+        .await
+        .map_err(|e| {
+            ErrorModel::builder()
+                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message("Error creating secret".to_string())
+                .r#type("SecretCreateError".to_string())
+                .stack(Some(vec![e.to_string()]))
+                .build()
+        })?;
+
+        Ok(secret_id)
+    }
+
+    /// Delete a secret
+    async fn delete_secret(secret_id: &SecretIdent, state: SecretsState) -> Result<()> {
+        sqlx::query(
+            r"
+            DELETE FROM secret
+            WHERE secret_id = ?
+            ",
+        )
+        .bind(*secret_id.as_uuid())
+        .execute(&state.write_pool)
+        .await
+        .map_err(|e| {
+            ErrorModel::builder()
+                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message("Error deleting secret".to_string())
+                .r#type("SecretDeleteError".to_string())
+                .stack(Some(vec![
+                    format!("secret_id: {}", secret_id),
+                    e.to_string(),
+                ]))
+                .build()
+        })?;
+
+        Ok(())
+    }
+}
+
+fn encryption_key() -> [u8; 32] {
+    openssl::sha::sha256(CONFIG.mysql_encryption_key.as_bytes())
+}
+
+/// Nonce, tag and ciphertext of `secret`.
+fn encrypt(
+    secret_id: &SecretIdent,
+    secret: &[u8],
+) -> std::result::Result<Vec<u8>, openssl::error::ErrorStack> {
+    let mut nonce = [0; NONCE_LEN];
+    openssl::rand::rand_bytes(&mut nonce)?;
+    let mut tag = [0; TAG_LEN];
+    let ciphertext = encrypt_aead(
+        Cipher::aes_256_gcm(),
+        &encryption_key(),
+        Some(&nonce[..]),
+        secret_id.as_uuid().as_bytes(),
+        secret,
+        &mut tag,
+    )?;
+    Ok([&nonce[..], &tag[..], &ciphertext[..]].concat())
+}
+
+/// Inverse of [`encrypt`]. `None` if the secret was modified or encrypted with another key.
+fn decrypt(secret_id: &SecretIdent, encrypted: &[u8]) -> Option<Vec<u8>> {
+    if encrypted.len() < NONCE_LEN + TAG_LEN {
+        return None;
+    }
+    let (nonce, rest) = encrypted.split_at(NONCE_LEN);
+    let (tag, ciphertext) = rest.split_at(TAG_LEN);
+    decrypt_aead(
+        Cipher::aes_256_gcm(),
+        &encryption_key(),
+        Some(nonce),
+        secret_id.as_uuid().as_bytes(),
+        ciphertext,
+        tag,
+    )
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let secret_id = SecretIdent::from(uuid::Uuid::now_v7());
+        let encrypted = encrypt(&secret_id, b"my secret").unwrap();
+        assert_eq!(decrypt(&secret_id, &encrypted).unwrap(), b"my secret");
+
+        // Bound to the row of the secret
+        let other_id = SecretIdent::from(uuid::Uuid::now_v7());
+        assert!(decrypt(&other_id, &encrypted).is_none());
+
+        let mut tampered = encrypted;
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decrypt(&secret_id, &tampered).is_none());
+        assert!(decrypt(&secret_id, &[0; NONCE_LEN]).is_none());
+    }
+}
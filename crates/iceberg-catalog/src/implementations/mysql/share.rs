@@ -0,0 +1,240 @@
+use super::{
+    dbutils::DBErrorHandler as _,
+    namespace::{namespace_key, parse_namespace},
+    CatalogState,
+};
+use crate::api::{ErrorModel, ErrorType, Result};
+use crate::service::{NamespaceIdent, ResolvedShare, Share, SharedObject, TableIdent};
+use crate::{ProjectIdent, WarehouseIdent};
+use http::StatusCode;
+use sqlx::{mysql::MySqlRow, types::Json, Row};
+
+pub(crate) async fn create_share(
+    warehouse_id: &WarehouseIdent,
+    object: &SharedObject,
+    consumer_project_id: &ProjectIdent,
+    mount_name: &str,
+    created_by: Option<&str>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<uuid::Uuid> {
+    if mount_name.is_empty() {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("Mount name cannot be empty".to_string())
+            .r#type("EmptyMountName".to_string())
+            .build()
+            .into());
+    }
+
+    let (namespace_id, table_id) = match object {
+        SharedObject::Namespace(namespace) => {
+            let namespace_id = sqlx::query_scalar::<_, uuid::Uuid>(
+                r"
+                SELECT n.namespace_id
+                FROM namespace n
+                INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+                WHERE n.warehouse_id = ? AND n.namespace_key = ?
+                AND w.status = 'active'
+                ",
+            )
+            .bind(*warehouse_id.as_uuid())
+            .bind(namespace_key(namespace))
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching namespace".into()))?
+            .ok_or_else(|| {
+                ErrorModel::builder()
+                    .code(StatusCode::NOT_FOUND.into())
+                    .message("Namespace not found".to_string())
+                    .r#type("NoSuchNamespaceError".to_string())
+                    .error_type(ErrorType::NoSuchNamespace)
+                    .build()
+            })?;
+            (Some(namespace_id), None)
+        }
+        SharedObject::Table(TableIdent { namespace, name }) => {
+            let table_id = sqlx::query_scalar::<_, uuid::Uuid>(
+                r"
+                SELECT t.table_id
+                FROM `table` t
+                INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+                INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+                WHERE n.warehouse_id = ? AND n.namespace_key = ? AND t.table_name = ?
+                AND w.status = 'active'
+                AND t.metadata_location IS NOT NULL
+                ",
+            )
+            .bind(*warehouse_id.as_uuid())
+            .bind(namespace_key(namespace))
+            .bind(name)
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching table".into()))?
+            .ok_or_else(|| {
+                ErrorModel::builder()
+                    .code(StatusCode::NOT_FOUND.into())
+                    .message("Table not found".to_string())
+                    .r#type("NoSuchTableError".to_string())
+                    .error_type(ErrorType::NoSuchTable)
+                    .build()
+            })?;
+            (None, Some(table_id))
+        }
+    };
+
+    let share_id = uuid::Uuid::now_v7();
+    sqlx::query(
+        r"
+        INSERT INTO `share` (share_id, warehouse_id, namespace_id, table_id, consumer_project_id, mount_name, created_by)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        ",
+    )
+    .bind(share_id)
+    .bind(*warehouse_id.as_uuid())
+    .bind(namespace_id)
+    .bind(table_id)
+    .bind(*consumer_project_id.as_uuid())
+    .bind(mount_name)
+    .bind(created_by)
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(ref db_error) if db_error.is_unique_violation() => {
+            ErrorModel::builder()
+                .code(StatusCode::CONFLICT.into())
+                .message(
+                    "The consumer project already has a share with this mount name".to_string(),
+                )
+                .r#type("ShareAlreadyExists".to_string())
+                .error_type(ErrorType::AlreadyExists)
+                .build()
+        }
+        _ => e.into_error_model("Error creating share".into()),
+    })?;
+
+    Ok(share_id)
+}
+
+pub(crate) async fn list_shares(
+    warehouse_id: &WarehouseIdent,
+    catalog_state: CatalogState,
+) -> Result<Vec<Share>> {
+    let shares = sqlx::query(
+        r"
+        SELECT
+            s.share_id,
+            s.consumer_project_id,
+            s.mount_name,
+            s.created_by,
+            n.namespace_name,
+            tn.namespace_name AS table_namespace_name,
+            t.table_name
+        FROM `share` s
+        LEFT JOIN namespace n ON s.namespace_id = n.namespace_id
+        LEFT JOIN `table` t ON s.table_id = t.table_id
+        LEFT JOIN namespace tn ON t.namespace_id = tn.namespace_id
+        WHERE s.warehouse_id = ?
+        ORDER BY s.created_at
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching shares".into()))?;
+
+    shares
+        .into_iter()
+        .map(|row| {
+            let namespace = shared_namespace(&row)?;
+            let object = match row.get::<Option<String>, _>("table_name") {
+                Some(name) => SharedObject::Table(TableIdent::new(namespace, name)),
+                None => SharedObject::Namespace(namespace),
+            };
+            Ok(Share {
+                share_id: row.get("share_id"),
+                object,
+                consumer_project_id: row.get::<uuid::Uuid, _>("consumer_project_id").into(),
+                mount_name: row.get("mount_name"),
+                created_by: row.get("created_by"),
+            })
+        })
+        .collect()
+}
+
+pub(crate) async fn delete_share(
+    warehouse_id: &WarehouseIdent,
+    share_id: &uuid::Uuid,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result = sqlx::query("DELETE FROM `share` WHERE warehouse_id = ? AND share_id = ?")
+        .bind(*warehouse_id.as_uuid())
+        .bind(share_id)
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error deleting share".into()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Share not found".to_string())
+            .r#type("ShareNotFound".to_string())
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn resolve_share(
+    warehouse_id: &WarehouseIdent,
+    mount_name: &str,
+    catalog_state: CatalogState,
+) -> Result<Option<ResolvedShare>> {
+    let share = sqlx::query(
+        r"
+        SELECT
+            s.share_id,
+            s.warehouse_id,
+            n.namespace_name,
+            tn.namespace_name AS table_namespace_name,
+            t.table_name
+        FROM `share` s
+        INNER JOIN warehouse cw ON cw.project_id = s.consumer_project_id
+        INNER JOIN warehouse w ON s.warehouse_id = w.warehouse_id
+        LEFT JOIN namespace n ON s.namespace_id = n.namespace_id
+        LEFT JOIN `table` t ON s.table_id = t.table_id
+        LEFT JOIN namespace tn ON t.namespace_id = tn.namespace_id
+        WHERE cw.warehouse_id = ? AND s.mount_name = ?
+        AND w.status = 'active'
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .bind(mount_name)
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error resolving share".into()))?;
+
+    share
+        .map(|row| {
+            Ok(ResolvedShare {
+                share_id: row.get("share_id"),
+                warehouse_id: row.get::<uuid::Uuid, _>("warehouse_id").into(),
+                namespace: shared_namespace(&row)?,
+                table_name: row.get("table_name"),
+            })
+        })
+        .transpose()
+}
+
+/// The shared namespace, or the namespace of the shared table.
+fn shared_namespace(row: &MySqlRow) -> Result<NamespaceIdent> {
+    let namespace = row
+        .try_get::<Option<Json<Vec<String>>>, _>("namespace_name")
+        .and_then(|namespace| match namespace {
+            Some(namespace) => Ok(Some(namespace)),
+            None => row.try_get::<Option<Json<Vec<String>>>, _>("table_namespace_name"),
+        })
+        .map_err(|e| e.into_error_model("Error parsing namespace".into()))?
+        .unwrap_or_default();
+    parse_namespace(namespace)
+}
@@ -0,0 +1,108 @@
+use super::{dbutils::DBErrorHandler as _, namespace::parse_namespace, CatalogState};
+use crate::api::Result;
+use crate::service::storage_usage::NamespaceStorageUsage;
+use crate::WarehouseIdent;
+use sqlx::{types::Json, Row};
+
+pub(crate) async fn refresh_storage_usage(catalog_state: CatalogState) -> Result<u64> {
+    let computed_at = chrono::Utc::now();
+
+    // Tables without a current snapshot are empty.
+    // Values that are not numbers are read as NULL instead of failing the statement.
+    sqlx::query(
+        r#"
+        INSERT INTO storage_usage
+            (namespace_id, warehouse_id, table_count, total_files_size_bytes, tables_without_size, computed_at)
+        SELECT
+            n.namespace_id,
+            n.warehouse_id,
+            COUNT(ts.namespace_id),
+            CAST(COALESCE(SUM(ts.size), 0) AS SIGNED),
+            CAST(COALESCE(SUM(ts.namespace_id IS NOT NULL AND ts.size IS NULL), 0) AS SIGNED),
+            ?
+        FROM namespace n
+        LEFT JOIN (
+            SELECT
+                t.namespace_id,
+                CASE
+                    WHEN COALESCE(cs.current_snapshot_id, -1) = -1 THEN 0
+                    ELSE s.total_files_size
+                END AS size
+            FROM `table` t
+            CROSS JOIN JSON_TABLE(
+                t.metadata, '$' COLUMNS (
+                    current_snapshot_id BIGINT PATH '$."current-snapshot-id"' NULL ON ERROR
+                )
+            ) cs
+            LEFT JOIN JSON_TABLE(
+                t.metadata, '$.snapshots[*]' COLUMNS (
+                    snapshot_id BIGINT PATH '$."snapshot-id"' NULL ON ERROR,
+                    total_files_size BIGINT PATH '$.summary."total-files-size"' NULL ON ERROR
+                )
+            ) s ON s.snapshot_id = cs.current_snapshot_id
+            WHERE t.metadata_location IS NOT NULL
+        ) ts ON ts.namespace_id = n.namespace_id
+        GROUP BY n.namespace_id, n.warehouse_id
+        ON DUPLICATE KEY UPDATE
+            table_count = VALUES(table_count),
+            total_files_size_bytes = VALUES(total_files_size_bytes),
+            tables_without_size = VALUES(tables_without_size),
+            computed_at = VALUES(computed_at)
+        "#,
+    )
+    .bind(computed_at)
+    .execute(&catalog_state.write_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error refreshing storage usage".into()))?;
+
+    // Updated rows count twice in the affected rows of MySQL.
+    let refreshed =
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM storage_usage WHERE computed_at = ?")
+            .bind(computed_at)
+            .fetch_one(&catalog_state.write_pool)
+            .await
+            .map_err(|e| e.into_error_model("Error refreshing storage usage".into()))?;
+
+    Ok(u64::try_from(refreshed).unwrap_or_default())
+}
+
+pub(crate) async fn list_storage_usage(
+    warehouse_id: &WarehouseIdent,
+    catalog_state: CatalogState,
+) -> Result<Vec<NamespaceStorageUsage>> {
+    let usage = sqlx::query(
+        r"
+        SELECT
+            n.namespace_name,
+            u.table_count,
+            u.total_files_size_bytes,
+            u.tables_without_size,
+            u.computed_at
+        FROM storage_usage u
+        INNER JOIN namespace n ON n.namespace_id = u.namespace_id
+        WHERE u.warehouse_id = ?
+        ORDER BY n.namespace_key
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching storage usage".into()))?;
+
+    usage
+        .into_iter()
+        .map(|row| {
+            let namespace = row
+                .try_get::<Json<Vec<String>>, _>("namespace_name")
+                .map_err(|e| e.into_error_model("Error parsing namespace name".to_string()))?;
+            Ok(NamespaceStorageUsage {
+                warehouse_id: warehouse_id.clone(),
+                namespace: parse_namespace(namespace)?,
+                table_count: row.get("table_count"),
+                total_files_size_bytes: row.get("total_files_size_bytes"),
+                tables_without_size: row.get("tables_without_size"),
+                computed_at: row.get("computed_at"),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,154 @@
+//! The stores of [`crate::service::stores`], backed by the catalog database.
+use std::collections::HashSet;
+
+use super::{
+    api_usage::{list_api_usage, record_api_usage},
+    client_usage::{list_client_usage, record_client_usage},
+    role_assignment::{get_roles, grant_role, list_role_assignments, revoke_role},
+    storage_usage::{list_storage_usage, refresh_storage_usage},
+    table::record_lineage_events,
+    token_revocation::{list_active_token_revocations, revoke_token},
+    webhook::record_webhook_dead_letter,
+    CatalogState, MySqlTransaction,
+};
+use crate::service::{
+    api_usage::{ApiUsage, ApiUsageStore, UsageWindow},
+    client_usage::{ClientInfo, ClientUsage, ClientUsageStore},
+    lineage::{LineageEvent, LineageStore},
+    roles::RoleStore,
+    storage_usage::{NamespaceStorageUsage, StorageUsageStore},
+    token_revocation::{RevocationStore, TokenRevocation},
+    webhook::{WebhookDeadLetter, WebhookDeadLetterStore},
+    Result, Role, RoleAssignment, RoleObject, RoleTarget, TableIdentUuid, Transaction,
+};
+use crate::{ProjectIdent, WarehouseIdent};
+
+#[async_trait::async_trait]
+impl RoleStore for CatalogState {
+    async fn grant_role(
+        &self,
+        object: &RoleObject,
+        principal: &str,
+        role: Role,
+        created_by: Option<&str>,
+    ) -> Result<uuid::Uuid> {
+        let mut transaction = MySqlTransaction::begin_write(self.clone()).await?;
+        let assignment_id = grant_role(
+            object,
+            principal,
+            role,
+            created_by,
+            transaction.transaction(),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(assignment_id)
+    }
+
+    async fn revoke_role(&self, object: &RoleObject, principal: &str) -> Result<()> {
+        let mut transaction = MySqlTransaction::begin_write(self.clone()).await?;
+        revoke_role(object, principal, transaction.transaction()).await?;
+        transaction.commit().await
+    }
+
+    async fn list_role_assignments(
+        &self,
+        project_id: Option<&ProjectIdent>,
+        principal: Option<&str>,
+    ) -> Result<Vec<RoleAssignment>> {
+        list_role_assignments(project_id, principal, self.clone()).await
+    }
+
+    async fn get_roles(&self, principal: &str, target: &RoleTarget) -> Result<Vec<Role>> {
+        get_roles(principal, target, self.clone()).await
+    }
+}
+
+#[async_trait::async_trait]
+impl RevocationStore for CatalogState {
+    async fn revoke_token(&self, revocation: &TokenRevocation) -> Result<()> {
+        let mut transaction = MySqlTransaction::begin_write(self.clone()).await?;
+        revoke_token(revocation, transaction.transaction()).await?;
+        transaction.commit().await
+    }
+
+    async fn list_active_revocations(&self) -> Result<Vec<TokenRevocation>> {
+        list_active_token_revocations(self.clone()).await
+    }
+}
+
+#[async_trait::async_trait]
+impl ClientUsageStore for CatalogState {
+    async fn record_client_usage(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        client: &ClientInfo,
+    ) -> Result<()> {
+        record_client_usage(warehouse_id, client, self.clone()).await
+    }
+
+    async fn list_client_usage(&self, warehouse_id: &WarehouseIdent) -> Result<Vec<ClientUsage>> {
+        list_client_usage(warehouse_id, self.clone()).await
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiUsageStore for CatalogState {
+    async fn record_api_usage(&self, usage: Vec<ApiUsage>) -> Result<()> {
+        record_api_usage(usage, self.clone()).await
+    }
+
+    async fn list_api_usage(
+        &self,
+        project_id: &ProjectIdent,
+        warehouse_id_filter: Option<&HashSet<WarehouseIdent>>,
+        window: UsageWindow,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<ApiUsage>> {
+        list_api_usage(
+            project_id,
+            warehouse_id_filter,
+            window,
+            from,
+            to,
+            self.clone(),
+        )
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageUsageStore for CatalogState {
+    async fn refresh_storage_usage(&self) -> Result<u64> {
+        refresh_storage_usage(self.clone()).await
+    }
+
+    async fn list_storage_usage(
+        &self,
+        warehouse_id: &WarehouseIdent,
+    ) -> Result<Vec<NamespaceStorageUsage>> {
+        list_storage_usage(warehouse_id, self.clone()).await
+    }
+}
+
+#[async_trait::async_trait]
+impl LineageStore for CatalogState {
+    async fn record_lineage_events(
+        &self,
+        _warehouse_id: &WarehouseIdent,
+        table_id: TableIdentUuid,
+        events: &[LineageEvent],
+    ) -> Result<()> {
+        let mut transaction = MySqlTransaction::begin_write(self.clone()).await?;
+        record_lineage_events(table_id, events, transaction.transaction()).await?;
+        transaction.commit().await
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookDeadLetterStore for CatalogState {
+    async fn record_webhook_dead_letter(&self, dead_letter: &WebhookDeadLetter) -> Result<()> {
+        record_webhook_dead_letter(dead_letter, self.clone()).await
+    }
+}
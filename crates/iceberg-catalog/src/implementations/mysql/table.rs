@@ -0,0 +1,1352 @@
+use super::{
+    dbutils::DBErrorHandler as _,
+    namespace::{namespace_key, parse_namespace},
+    CatalogState,
+};
+use crate::{
+    implementations::commit::{
+        assert_all_updated, commit_contexts, create_table_metadata, dropped_table_not_found,
+        lock_order, merge_metadata_log, prepare_commits, serialize_metadata, update_order,
+        CommitContext, CommitRow,
+    },
+    service::{
+        lineage::LineageEvent,
+        page_token::PageQuery,
+        storage::{AccessDelegation, StorageProfile},
+        CommitTableResponseExt, CommitTransactionRequest, CreateTableRequest, CreateTableResponse,
+        DroppedTable, ErrorModel, ErrorType, GetTableMetadataResponse, LoadTableResponse,
+        LoadedTable, MetadataLogEntry, NamespaceIdentUuid, Result, RetentionCandidate,
+        SnapshotRetentionPolicy, StagedTable, TableCommit, TableIdent, TableIdentUuid, TablePolicy,
+    },
+    SecretIdent, WarehouseIdent,
+};
+
+use http::StatusCode;
+use iceberg_ext::{spec::TableMetadata, NamespaceIdent};
+
+use sqlx::{mysql::MySqlRow, types::Json, Row};
+use std::collections::{HashMap, HashSet};
+
+const MAX_PARAMETERS: usize = 30000;
+
+/// Retention policy of the closest namespace of `n` that has one, including `n` itself.
+/// A namespace is a parent if its key followed by the separator is a prefix of the key of `n`.
+const CLOSEST_NAMESPACE_RETENTION_POLICY: &str = "
+    (
+        SELECT pn.retention_policy
+        FROM namespace pn
+        WHERE pn.warehouse_id = n.warehouse_id
+        AND pn.retention_policy IS NOT NULL
+        AND LEFT(CONCAT(n.namespace_key, '\u{1f}'), CHAR_LENGTH(pn.namespace_key) + 1)
+            = CONCAT(pn.namespace_key, '\u{1f}')
+        ORDER BY JSON_LENGTH(pn.namespace_name) DESC
+        LIMIT 1
+    )";
+
+fn table_not_found() -> ErrorModel {
+    ErrorModel::builder()
+        .code(StatusCode::NOT_FOUND.into())
+        .message("Table not found".to_string())
+        .r#type("NoSuchTableError".to_string())
+        .error_type(ErrorType::NoSuchTable)
+        .build()
+}
+
+fn table_ident(row: &MySqlRow) -> Result<TableIdent> {
+    let namespace = row
+        .try_get::<Json<Vec<String>>, _>("namespace_name")
+        .map_err(|e| e.into_error_model("Error parsing namespace".to_string()))?;
+    Ok(TableIdent::new(
+        parse_namespace(namespace)?,
+        row.get::<String, _>("table_name"),
+    ))
+}
+
+/// Record that `table_id` pointed to `metadata_location`.
+/// Done by a trigger in the Postgres catalog.
+async fn log_metadata_location(
+    table_id: uuid::Uuid,
+    metadata_location: &str,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    sqlx::query(
+        r"
+        INSERT INTO table_metadata_log (table_id, metadata_location)
+        VALUES (?, ?)
+        ON DUPLICATE KEY UPDATE table_id = table_id
+        ",
+    )
+    .bind(table_id)
+    .bind(metadata_location)
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error logging table metadata location".to_string()))?;
+
+    Ok(())
+}
+
+pub(crate) async fn table_ident_to_id<'e, 'c: 'e, E>(
+    warehouse_id: &WarehouseIdent,
+    table: &TableIdent,
+    include_staged: bool,
+    catalog_state: E,
+) -> Result<Option<TableIdentUuid>>
+where
+    E: 'e + sqlx::Executor<'c, Database = sqlx::MySql>,
+{
+    let TableIdent { namespace, name } = table;
+
+    let row = sqlx::query(
+        r"
+        SELECT t.table_id, t.metadata_location
+        FROM `table` t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE n.namespace_key = ? AND t.table_name = ?
+        AND n.warehouse_id = ?
+        AND w.status = 'active'
+        ",
+    )
+    .bind(namespace_key(namespace))
+    .bind(name)
+    .bind(*warehouse_id.as_uuid())
+    .fetch_optional(catalog_state)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching table".to_string()))?;
+
+    Ok(row
+        .filter(|row| include_staged || row.get::<Option<String>, _>("metadata_location").is_some())
+        .map(|row| row.get::<uuid::Uuid, _>("table_id").into()))
+}
+
+/// Only reads the index on the table name, the metadata is never touched.
+pub(crate) async fn table_exists<'e, 'c: 'e, E>(
+    warehouse_id: &WarehouseIdent,
+    table: &TableIdent,
+    include_staged: bool,
+    catalog_state: E,
+) -> Result<bool>
+where
+    E: 'e + sqlx::Executor<'c, Database = sqlx::MySql>,
+{
+    let TableIdent { namespace, name } = table;
+
+    sqlx::query_scalar::<_, i64>(
+        r"
+        SELECT EXISTS (
+            SELECT 1
+            FROM `table` t
+            INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+            INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+            WHERE n.namespace_key = ? AND t.table_name = ?
+            AND n.warehouse_id = ?
+            AND w.status = 'active'
+            AND (? OR t.metadata_location IS NOT NULL)
+        )
+        ",
+    )
+    .bind(namespace_key(namespace))
+    .bind(name)
+    .bind(*warehouse_id.as_uuid())
+    .bind(include_staged)
+    .fetch_one(catalog_state)
+    .await
+    .map(|exists| exists != 0)
+    .map_err(|e| {
+        e.into_error_model("Error checking if table exists".to_string())
+            .into()
+    })
+}
+
+pub(crate) async fn table_idents_to_ids<'e, 'c: 'e, E>(
+    warehouse_id: &WarehouseIdent,
+    tables: HashSet<&TableIdent>,
+    include_staged: bool,
+    catalog_state: E,
+) -> Result<HashMap<TableIdent, Option<TableIdentUuid>>>
+where
+    E: 'e + sqlx::Executor<'c, Database = sqlx::MySql>,
+{
+    if tables.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    if tables.len() > (MAX_PARAMETERS / 2) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("Too many tables to fetch".to_string())
+            .r#type("TooManyTables".to_string())
+            .build()
+            .into());
+    }
+
+    let mut query_builder = sqlx::QueryBuilder::new(
+        r"
+        SELECT t.table_id, n.namespace_name, t.table_name, t.metadata_location
+        FROM `table` t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE w.status = 'active' AND n.warehouse_id = ",
+    );
+    query_builder.push_bind(*warehouse_id.as_uuid());
+    query_builder.push(" AND (n.namespace_key, t.table_name) IN (");
+    let mut separated = query_builder.separated(", ");
+    for TableIdent { namespace, name } in &tables {
+        separated.push("(");
+        separated.push_bind_unseparated(namespace_key(namespace));
+        separated.push_unseparated(", ");
+        separated.push_bind_unseparated(name);
+        separated.push_unseparated(")");
+    }
+    query_builder.push(")");
+
+    let rows = query_builder
+        .build()
+        .fetch_all(catalog_state)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching tables".to_string()))?;
+
+    let mut table_map = HashMap::new();
+    for row in rows {
+        let staged = row.get::<Option<String>, _>("metadata_location").is_none();
+        if !staged || include_staged {
+            table_map.insert(
+                table_ident(&row)?,
+                Some(row.get::<uuid::Uuid, _>("table_id").into()),
+            );
+        }
+    }
+
+    // Missing tables are added with None
+    for table in &tables {
+        if !table_map.contains_key(table) {
+            table_map.insert(table.to_owned().to_owned(), None);
+        }
+    }
+
+    Ok(table_map)
+}
+
+pub(crate) async fn load_tables<'e, 'c: 'e, E>(
+    warehouse_id: &WarehouseIdent,
+    tables: HashSet<&TableIdent>,
+    catalog_state: E,
+) -> Result<Vec<LoadedTable>>
+where
+    E: 'e + sqlx::Executor<'c, Database = sqlx::MySql>,
+{
+    if tables.is_empty() {
+        return Ok(vec![]);
+    }
+
+    if tables.len() > (MAX_PARAMETERS / 2) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("Too many tables to fetch".to_string())
+            .r#type("TooManyTables".to_string())
+            .build()
+            .into());
+    }
+
+    let mut query_builder = sqlx::QueryBuilder::new(
+        r"
+        SELECT t.table_id, n.namespace_name, t.table_name, t.metadata, t.metadata_location
+        FROM `table` t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE w.status = 'active' AND t.metadata_location IS NOT NULL AND n.warehouse_id = ",
+    );
+    query_builder.push_bind(*warehouse_id.as_uuid());
+    query_builder.push(" AND (n.namespace_key, t.table_name) IN (");
+    let mut separated = query_builder.separated(", ");
+    for TableIdent { namespace, name } in &tables {
+        separated.push("(");
+        separated.push_bind_unseparated(namespace_key(namespace));
+        separated.push_unseparated(", ");
+        separated.push_bind_unseparated(name);
+        separated.push_unseparated(")");
+    }
+    query_builder.push(")");
+
+    let rows = query_builder
+        .build()
+        .fetch_all(catalog_state)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching tables".to_string()))?;
+
+    rows.into_iter()
+        .map(|row| {
+            let metadata = row
+                .try_get::<Json<TableMetadata>, _>("metadata")
+                .map_err(|e| e.into_error_model("Error parsing table metadata".to_string()))?;
+
+            Ok(LoadedTable {
+                table_id: row.get::<uuid::Uuid, _>("table_id").into(),
+                table: table_ident(&row)?,
+                metadata_location: row.get::<String, _>("metadata_location"),
+                table_metadata: metadata.0,
+            })
+        })
+        .collect()
+}
+
+pub(crate) async fn create_table(
+    namespace_id: &NamespaceIdentUuid,
+    table: &TableIdent,
+    table_id: &TableIdentUuid,
+    request: CreateTableRequest,
+    // Metadata location may be none if stage-create is true
+    metadata_location: Option<&String>,
+    owner: Option<&str>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<CreateTableResponse> {
+    let TableIdent { namespace: _, name } = table;
+    let table_metadata = create_table_metadata(table_id, request)?;
+    let table_metadata_ser = serialize_metadata(&table_metadata)?;
+
+    let table_already_exists = || {
+        ErrorModel::builder()
+            .code(StatusCode::CONFLICT.into())
+            .message("Table already exists in Namespace".to_string())
+            .r#type("TableAlreadyExists".to_string())
+            .error_type(ErrorType::AlreadyExists)
+            .build()
+    };
+
+    // A staged table with the same name is replaced.
+    let existing = sqlx::query(
+        r"
+        SELECT table_id, metadata_location, metadata_version
+        FROM `table`
+        WHERE namespace_id = ? AND table_name = ?
+        FOR UPDATE
+        ",
+    )
+    .bind(*namespace_id.as_uuid())
+    .bind(name)
+    .fetch_optional(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error creating table".to_string()))?;
+
+    let mut metadata_version = 0;
+    if let Some(existing) = existing {
+        if existing
+            .get::<Option<String>, _>("metadata_location")
+            .is_some()
+        {
+            return Err(table_already_exists().into());
+        }
+        sqlx::query("DELETE FROM `table` WHERE table_id = ?")
+            .bind(existing.get::<uuid::Uuid, _>("table_id"))
+            .execute(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error creating table".to_string()))?;
+        metadata_version = existing.get::<i64, _>("metadata_version") + 1;
+    }
+
+    let result = sqlx::query(
+        r"
+        INSERT INTO `table`
+            (table_id, namespace_id, table_name, metadata, metadata_location, metadata_version, table_location, created_by, `owner`)
+        SELECT ?, ?, ?, ?, ?, ?, ?, ?, ?
+        FROM DUAL
+        WHERE EXISTS (
+            SELECT 1
+            FROM warehouse w
+            INNER JOIN namespace n ON w.warehouse_id = n.warehouse_id
+            WHERE n.namespace_id = ? AND w.status = 'active'
+        )
+        ",
+    )
+    .bind(*table_id.as_uuid())
+    .bind(*namespace_id.as_uuid())
+    .bind(name)
+    .bind(table_metadata_ser)
+    .bind(metadata_location)
+    .bind(metadata_version)
+    .bind(&table_metadata.location)
+    .bind(owner)
+    .bind(owner)
+    .bind(*namespace_id.as_uuid())
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db_error) if db_error.is_unique_violation() => {
+            table_already_exists()
+        }
+        _ => e.as_error_model("Error creating table".to_string()),
+    })?;
+
+    if result.rows_affected() == 0 {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Namespace not found".to_string())
+            .r#type("NoSuchNamespaceError".to_string())
+            .error_type(ErrorType::NoSuchNamespace)
+            .build()
+            .into());
+    }
+
+    if let Some(metadata_location) = metadata_location {
+        log_metadata_location(*table_id.as_uuid(), metadata_location, transaction).await?;
+    }
+
+    Ok(CreateTableResponse { table_metadata })
+}
+
+pub(crate) async fn load_table(
+    warehouse_id: &WarehouseIdent,
+    table: &TableIdent,
+    catalog_state: CatalogState,
+) -> Result<LoadTableResponse> {
+    let TableIdent { namespace, name } = table;
+
+    let row = sqlx::query(
+        r"
+        SELECT
+            t.table_id,
+            t.namespace_id,
+            t.metadata,
+            t.metadata_location,
+            w.storage_profile,
+            w.storage_secret_id,
+            n.namespace_properties,
+            t.`policy`
+        FROM `table` t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE w.warehouse_id = ? AND n.namespace_key = ? AND t.table_name = ?
+        AND w.status = 'active'
+        AND t.metadata_location IS NOT NULL
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .bind(namespace_key(namespace))
+    .bind(name)
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching table".to_string()))?
+    .ok_or_else(table_not_found)?;
+
+    let metadata = row
+        .try_get::<Json<TableMetadata>, _>("metadata")
+        .map_err(|e| e.into_error_model("Error parsing table metadata".to_string()))?;
+    let storage_profile = row
+        .try_get::<Json<StorageProfile>, _>("storage_profile")
+        .map_err(|e| e.into_error_model("Error parsing storage profile".to_string()))?;
+    let namespace_properties = row
+        .try_get::<Json<HashMap<String, String>>, _>("namespace_properties")
+        .map_err(|e| e.into_error_model("Error parsing namespace properties".to_string()))?;
+    let policy = row
+        .try_get::<Option<Json<TablePolicy>>, _>("policy")
+        .map_err(|e| e.into_error_model("Error parsing table policy".to_string()))?;
+
+    Ok(LoadTableResponse {
+        table_id: row.get::<uuid::Uuid, _>("table_id").into(),
+        namespace_id: row.get::<uuid::Uuid, _>("namespace_id").into(),
+        access_delegation: AccessDelegation::resolve(
+            Some(metadata.properties()),
+            Some(&namespace_properties),
+        ),
+        table_metadata: metadata.0,
+        metadata_location: row.get::<Option<String>, _>("metadata_location"),
+        storage_secret_ident: row
+            .get::<Option<uuid::Uuid>, _>("storage_secret_id")
+            .map(SecretIdent::from),
+        storage_profile: storage_profile.0,
+        policy: policy.map(|p| p.0),
+    })
+}
+
+pub(crate) async fn set_table_policy(
+    warehouse_id: &WarehouseIdent,
+    table: &TableIdent,
+    policy: Option<TablePolicy>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let TableIdent { namespace, name } = table;
+
+    let result = sqlx::query(
+        r"
+        UPDATE `table` t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        SET t.`policy` = ?
+        WHERE w.warehouse_id = ? AND n.namespace_key = ? AND t.table_name = ?
+        AND w.status = 'active'
+        ",
+    )
+    .bind(policy.map(Json))
+    .bind(*warehouse_id.as_uuid())
+    .bind(namespace_key(namespace))
+    .bind(name)
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error setting table policy".into()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(table_not_found().into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn list_tables(
+    warehouse_id: &WarehouseIdent,
+    namespace: &NamespaceIdent,
+    include_staged: bool,
+    page: &PageQuery,
+    catalog_state: CatalogState,
+) -> Result<Vec<(TableIdentUuid, TableIdent)>> {
+    // MySQL has no `LIMIT ALL`.
+    let limit = page
+        .fetch_limit()
+        .map_or(i64::MAX, |limit| i64::try_from(limit).unwrap_or(i64::MAX));
+    let rows = sqlx::query(
+        r"
+        SELECT t.table_id, t.table_name, n.namespace_name
+        FROM `table` t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE n.warehouse_id = ?
+            AND n.namespace_key = ?
+            AND w.status = 'active'
+            AND (t.metadata_location IS NOT NULL OR ?)
+            AND (? IS NULL OR t.table_name > ?)
+        ORDER BY t.table_name
+        LIMIT ?
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .bind(namespace_key(namespace))
+    .bind(include_staged)
+    .bind(&page.after)
+    .bind(&page.after)
+    .bind(limit)
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching tables".to_string()))?;
+
+    rows.iter()
+        .map(|row| {
+            Ok((
+                row.get::<uuid::Uuid, _>("table_id").into(),
+                table_ident(row)?,
+            ))
+        })
+        .collect()
+}
+
+/// Build the response from a row with the columns of [`TABLE_METADATA_COLUMNS`].
+fn table_metadata_response(
+    row: &MySqlRow,
+    warehouse_id: &WarehouseIdent,
+    include_staged: bool,
+) -> Result<GetTableMetadataResponse> {
+    let metadata_location = row.get::<Option<String>, _>("metadata_location");
+    if !include_staged && metadata_location.is_none() {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Table is staged and not yet created".to_string())
+            .r#type("TableStaged".to_string())
+            .error_type(ErrorType::NoSuchTable)
+            .build()
+            .into());
+    }
+
+    let metadata = row
+        .try_get::<Json<TableMetadata>, _>("metadata")
+        .map_err(|e| e.into_error_model("Error parsing table metadata".to_string()))?;
+    let storage_profile = row
+        .try_get::<Json<StorageProfile>, _>("storage_profile")
+        .map_err(|e| e.into_error_model("Error parsing storage profile".to_string()))?;
+    let namespace_properties = row
+        .try_get::<Json<HashMap<String, String>>, _>("namespace_properties")
+        .map_err(|e| e.into_error_model("Error parsing namespace properties".to_string()))?;
+
+    Ok(GetTableMetadataResponse {
+        table: table_ident(row)?,
+        table_id: row.get::<uuid::Uuid, _>("table_id").into(),
+        warehouse_id: warehouse_id.clone(),
+        location: row.get::<String, _>("table_location"),
+        metadata_location,
+        storage_secret_ident: row
+            .get::<Option<uuid::Uuid>, _>("storage_secret_id")
+            .map(SecretIdent::from),
+        storage_profile: storage_profile.0,
+        access_delegation: AccessDelegation::resolve(
+            Some(metadata.properties()),
+            Some(&namespace_properties),
+        ),
+    })
+}
+
+/// Columns read by [`table_metadata_response`].
+const TABLE_METADATA_COLUMNS: &str = r"
+    t.table_id,
+    t.table_name,
+    t.table_location,
+    n.namespace_name,
+    t.metadata,
+    t.metadata_location,
+    w.storage_profile,
+    w.storage_secret_id,
+    n.namespace_properties";
+
+pub(crate) async fn get_table_metadata_by_id(
+    warehouse_id: &WarehouseIdent,
+    table: &TableIdentUuid,
+    include_staged: bool,
+    catalog_state: CatalogState,
+) -> Result<GetTableMetadataResponse> {
+    let query = format!(
+        r"
+        SELECT {TABLE_METADATA_COLUMNS}
+        FROM `table` t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE w.warehouse_id = ? AND t.table_id = ?
+        AND w.status = 'active'
+        "
+    );
+    let row = sqlx::query(&query)
+        .bind(*warehouse_id.as_uuid())
+        .bind(*table.as_uuid())
+        .fetch_optional(&catalog_state.read_pool)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching table".to_string()))?
+        .ok_or_else(table_not_found)?;
+
+    table_metadata_response(&row, warehouse_id, include_staged)
+}
+
+pub(crate) async fn get_table_metadata_by_s3_location(
+    warehouse_id: &WarehouseIdent,
+    location: &str,
+    include_staged: bool,
+    catalog_state: CatalogState,
+) -> Result<GetTableMetadataResponse> {
+    // Location might also be a subpath of the table location.
+    // We need to make sure that the location starts with the table location.
+    let query = format!(
+        r"
+        SELECT {TABLE_METADATA_COLUMNS}
+        FROM `table` t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE w.warehouse_id = ?
+            AND t.table_location = LEFT(?, CHAR_LENGTH(t.table_location))
+            AND w.status = 'active'
+        ORDER BY CHAR_LENGTH(t.table_location) DESC
+        LIMIT 1
+        "
+    );
+    let row = sqlx::query(&query)
+        .bind(*warehouse_id.as_uuid())
+        .bind(location)
+        .fetch_optional(&catalog_state.read_pool)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching table".to_string()))?
+        .ok_or_else(|| {
+            ErrorModel::builder()
+                .code(StatusCode::NOT_FOUND.into())
+                .message("Table not found".to_string())
+                .r#type("NoSuchTableError".to_string())
+                .error_type(ErrorType::NoSuchTable)
+                .stack(Some(vec![
+                    location.to_string(),
+                    format!("Warehouse: {}", warehouse_id),
+                ]))
+                .build()
+        })?;
+
+    table_metadata_response(&row, warehouse_id, include_staged)
+}
+
+pub(crate) async fn get_table_metadata_log(
+    warehouse_id: &WarehouseIdent,
+    table_id: &TableIdentUuid,
+    catalog_state: CatalogState,
+) -> Result<Vec<MetadataLogEntry>> {
+    let metadata = sqlx::query_scalar::<_, Json<TableMetadata>>(
+        r"
+        SELECT t.metadata
+        FROM `table` t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE w.warehouse_id = ? AND t.table_id = ?
+        AND w.status = 'active'
+        AND t.metadata_location IS NOT NULL
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .bind(*table_id.as_uuid())
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching table".to_string()))?
+    .ok_or_else(table_not_found)?;
+
+    let history = sqlx::query(
+        r"
+        SELECT metadata_location, created_at
+        FROM table_metadata_log
+        WHERE table_id = ?
+        ",
+    )
+    .bind(*table_id.as_uuid())
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching table metadata log".to_string()))?;
+
+    Ok(merge_metadata_log(
+        history.into_iter().map(|row| {
+            (
+                row.get::<String, _>("metadata_location"),
+                row.get::<chrono::DateTime<chrono::Utc>, _>("created_at")
+                    .timestamp_millis(),
+            )
+        }),
+        &metadata.0,
+    ))
+}
+
+pub(crate) async fn list_table_commits(
+    warehouse_id: &WarehouseIdent,
+    table_id: &TableIdentUuid,
+    before: Option<chrono::DateTime<chrono::Utc>>,
+    limit: i64,
+    catalog_state: CatalogState,
+) -> Result<Vec<TableCommit>> {
+    let exists = sqlx::query(
+        r"
+        SELECT t.table_id
+        FROM `table` t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE w.warehouse_id = ? AND t.table_id = ?
+        AND w.status = 'active'
+        AND t.metadata_location IS NOT NULL
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .bind(*table_id.as_uuid())
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching table".to_string()))?;
+
+    if exists.is_none() {
+        return Err(table_not_found().into());
+    }
+
+    let commits = sqlx::query(
+        r"
+        SELECT metadata_location, committed_at, committed_by, snapshot_id, operation
+        FROM table_commit
+        WHERE table_id = ?
+        AND (? IS NULL OR committed_at < ?)
+        ORDER BY committed_at DESC, metadata_location DESC
+        LIMIT ?
+        ",
+    )
+    .bind(*table_id.as_uuid())
+    .bind(before)
+    .bind(before)
+    .bind(limit)
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching table commits".to_string()))?;
+
+    Ok(commits
+        .into_iter()
+        .map(|row| TableCommit {
+            table_id: *table_id,
+            committed_at: row.get("committed_at"),
+            committed_by: row.get("committed_by"),
+            snapshot_id: row.get("snapshot_id"),
+            operation: row.get("operation"),
+            metadata_location: row.get("metadata_location"),
+        })
+        .collect())
+}
+
+pub(crate) async fn record_table_commits(
+    commits: &[TableCommit],
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    for batch in commits.chunks(MAX_PARAMETERS / 6) {
+        let mut query_builder = sqlx::QueryBuilder::new(
+            r"
+            INSERT INTO table_commit
+                (table_id, metadata_location, committed_at, committed_by, snapshot_id, operation)
+            ",
+        );
+        query_builder.push_values(batch, |mut values, commit| {
+            values
+                .push_bind(*commit.table_id.as_uuid())
+                .push_bind(commit.metadata_location.clone())
+                .push_bind(commit.committed_at)
+                .push_bind(commit.committed_by.clone())
+                .push_bind(commit.snapshot_id)
+                .push_bind(commit.operation.clone());
+        });
+        query_builder.push(" ON DUPLICATE KEY UPDATE table_id = table_id");
+
+        query_builder
+            .build()
+            .execute(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error recording table commits".to_string()))?;
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn record_lineage_events(
+    table_id: TableIdentUuid,
+    events: &[LineageEvent],
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    for batch in events.chunks(MAX_PARAMETERS / 3) {
+        let events = batch
+            .iter()
+            .map(|event| {
+                serde_json::to_value(event)
+                    .map(|value| (event.snapshot_id, value))
+                    .map_err(|e| {
+                        ErrorModel::builder()
+                            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                            .message("Error serializing lineage event".to_string())
+                            .r#type("LineageEventSerializationError".to_string())
+                            .stack(Some(vec![e.to_string()]))
+                            .build()
+                    })
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "INSERT INTO table_lineage_event (table_id, snapshot_id, event) ",
+        );
+        query_builder.push_values(events, |mut values, (snapshot_id, event)| {
+            values
+                .push_bind(*table_id.as_uuid())
+                .push_bind(snapshot_id)
+                .push_bind(event);
+        });
+        query_builder.push(" ON DUPLICATE KEY UPDATE table_id = table_id");
+
+        query_builder
+            .build()
+            .execute(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error recording lineage events".to_string()))?;
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn list_staged_tables(
+    warehouse_id: &WarehouseIdent,
+    catalog_state: CatalogState,
+) -> Result<Vec<StagedTable>> {
+    let rows = sqlx::query(
+        r"
+        SELECT
+            t.table_id,
+            n.namespace_name,
+            t.table_name,
+            t.table_location,
+            t.created_at,
+            t.created_by
+        FROM `table` t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE w.warehouse_id = ?
+        AND w.status = 'active'
+        AND t.metadata_location IS NULL
+        ORDER BY t.created_at
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching staged tables".to_string()))?;
+
+    rows.into_iter()
+        .map(|row| {
+            Ok(StagedTable {
+                table_id: row.get::<uuid::Uuid, _>("table_id").into(),
+                table: table_ident(&row)?,
+                table_location: row.get("table_location"),
+                created_at: row.get("created_at"),
+                created_by: row.get("created_by"),
+            })
+        })
+        .collect()
+}
+
+pub(crate) async fn discard_staged_table(
+    warehouse_id: &WarehouseIdent,
+    table_id: &TableIdentUuid,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result = sqlx::query(
+        r"
+        DELETE t FROM `table` t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE w.warehouse_id = ?
+        AND w.status = 'active'
+        AND t.table_id = ?
+        AND t.metadata_location IS NULL
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .bind(*table_id.as_uuid())
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error discarding staged table".to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Staged table not found".to_string())
+            .r#type("NoSuchStagedTableError".to_string())
+            .error_type(ErrorType::NoSuchTable)
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn rename_table(
+    warehouse_id: &WarehouseIdent,
+    source_id: &TableIdentUuid,
+    source: &TableIdent,
+    destination: &TableIdent,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let TableIdent {
+        namespace: source_namespace,
+        name: source_name,
+    } = source;
+    let TableIdent {
+        namespace: dest_namespace,
+        name: dest_name,
+    } = destination;
+
+    let map_error = |e: sqlx::Error| match &e {
+        sqlx::Error::Database(db_error) if db_error.is_unique_violation() => ErrorModel::builder()
+            .code(StatusCode::CONFLICT.into())
+            .message("Table already exists in Namespace".to_string())
+            .r#type("TableAlreadyExists".to_string())
+            .error_type(ErrorType::AlreadyExists)
+            .build(),
+        _ => e.as_error_model("Error renaming table".to_string()),
+    };
+
+    if source_namespace == dest_namespace {
+        let result = sqlx::query(
+            r"
+            UPDATE `table`
+            SET table_name = ?
+            WHERE table_id = ?
+            AND EXISTS (
+                SELECT 1 FROM warehouse WHERE warehouse_id = ? AND status = 'active'
+            )
+            ",
+        )
+        .bind(dest_name)
+        .bind(*source_id.as_uuid())
+        .bind(*warehouse_id.as_uuid())
+        .execute(&mut **transaction)
+        .await
+        .map_err(map_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(ErrorModel::builder()
+                .code(StatusCode::NOT_FOUND.into())
+                .message("ID of Table to rename not found".to_string())
+                .r#type("RenameTableIdNotFound".to_string())
+                .error_type(ErrorType::NoSuchTable)
+                .build()
+                .into());
+        }
+    } else {
+        let not_found = || {
+            ErrorModel::builder()
+                .code(StatusCode::NOT_FOUND.into())
+                .message(
+                    "ID of Table to rename not found or destination namespace not found"
+                        .to_string(),
+                )
+                .r#type("RenameTableIdOrNamespaceNotFound".to_string())
+                .build()
+        };
+
+        // MySQL can't read the table it updates in a subquery.
+        let dest_namespace_id = sqlx::query_scalar::<_, uuid::Uuid>(
+            r"
+            SELECT namespace_id
+            FROM namespace
+            WHERE warehouse_id = ? AND namespace_key = ?
+            ",
+        )
+        .bind(*warehouse_id.as_uuid())
+        .bind(namespace_key(dest_namespace))
+        .fetch_optional(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error renaming Table".to_string()))?
+        .ok_or_else(not_found)?;
+
+        let result = sqlx::query(
+            r"
+            UPDATE `table`
+            SET table_name = ?, namespace_id = ?
+            WHERE table_id = ?
+            AND table_name = ?
+            AND EXISTS (
+                SELECT 1 FROM warehouse WHERE warehouse_id = ? AND status = 'active'
+            )
+            ",
+        )
+        .bind(dest_name)
+        .bind(dest_namespace_id)
+        .bind(*source_id.as_uuid())
+        .bind(source_name)
+        .bind(*warehouse_id.as_uuid())
+        .execute(&mut **transaction)
+        .await
+        .map_err(map_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(not_found().into());
+        }
+    };
+
+    Ok(())
+}
+
+pub(crate) async fn drop_table<'a>(
+    _: &WarehouseIdent,
+    table_id: &TableIdentUuid,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    // Keep the table until it is reclaimed by the garbage collector.
+    // Staged tables have no files and are not kept.
+    sqlx::query(
+        r"
+        INSERT INTO dropped_table (table_id, warehouse_id, namespace_name, table_name, metadata, metadata_locations, table_location)
+        SELECT
+            t.table_id,
+            n.warehouse_id,
+            n.namespace_name,
+            t.table_name,
+            t.metadata,
+            COALESCE(
+                (SELECT JSON_ARRAYAGG(l.metadata_location) FROM table_metadata_log l WHERE l.table_id = t.table_id),
+                JSON_ARRAY()
+            ),
+            t.table_location
+        FROM `table` t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        WHERE t.table_id = ? AND t.metadata_location IS NOT NULL
+        ",
+    )
+    .bind(*table_id.as_uuid())
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error keeping dropped table".to_string()))?;
+
+    let result = sqlx::query(
+        r"
+        DELETE t FROM `table` t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE t.table_id = ?
+        AND w.status = 'active'
+        ",
+    )
+    .bind(*table_id.as_uuid())
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error dropping table".to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(table_not_found().into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn list_expired_dropped_tables(
+    dropped_before: chrono::DateTime<chrono::Utc>,
+    limit: i64,
+    catalog_state: CatalogState,
+) -> Result<Vec<DroppedTable>> {
+    let rows = sqlx::query(
+        r"
+        SELECT
+            d.table_id,
+            d.warehouse_id,
+            d.namespace_name,
+            d.table_name,
+            d.table_location,
+            d.metadata_locations,
+            d.dropped_at,
+            w.storage_profile,
+            w.storage_secret_id
+        FROM dropped_table d
+        INNER JOIN warehouse w ON d.warehouse_id = w.warehouse_id
+        WHERE d.reclaimed_at IS NULL AND d.dropped_at < ?
+        ORDER BY d.dropped_at
+        LIMIT ?
+        ",
+    )
+    .bind(dropped_before)
+    .bind(limit)
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching dropped tables".to_string()))?;
+
+    rows.into_iter()
+        .map(|row| {
+            let metadata_locations = row
+                .try_get::<Json<Vec<String>>, _>("metadata_locations")
+                .map_err(|e| e.into_error_model("Error parsing metadata locations".to_string()))?;
+            let storage_profile = row
+                .try_get::<Json<StorageProfile>, _>("storage_profile")
+                .map_err(|e| e.into_error_model("Error parsing storage profile".to_string()))?;
+            Ok(DroppedTable {
+                table_id: row.get::<uuid::Uuid, _>("table_id").into(),
+                warehouse_id: row.get::<uuid::Uuid, _>("warehouse_id").into(),
+                table: table_ident(&row)?,
+                table_location: row.get("table_location"),
+                metadata_locations: metadata_locations.0,
+                dropped_at: row.get("dropped_at"),
+                storage_profile: storage_profile.0,
+                storage_secret_ident: row
+                    .get::<Option<uuid::Uuid>, _>("storage_secret_id")
+                    .map(SecretIdent::from),
+            })
+        })
+        .collect()
+}
+
+pub(crate) async fn list_retention_candidates(
+    after: Option<TableIdentUuid>,
+    limit: i64,
+    catalog_state: CatalogState,
+) -> Result<Vec<RetentionCandidate>> {
+    let override_properties =
+        serde_json::to_string(&SnapshotRetentionPolicy::PROPERTIES).map_err(|e| {
+            ErrorModel::builder()
+                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message("Error serializing retention properties".to_string())
+                .r#type("RetentionPropertiesSerializationError".to_string())
+                .stack(Some(vec![e.to_string()]))
+                .build()
+        })?;
+    let after = after.as_ref().map(|id| *id.as_uuid());
+
+    // The policy of the closest namespace applies, including the table's own namespace.
+    // The warehouse policy applies if no namespace has a policy.
+    let query = format!(
+        r"
+        SELECT
+            t.table_id,
+            n.warehouse_id,
+            n.namespace_name,
+            t.table_name,
+            {CLOSEST_NAMESPACE_RETENTION_POLICY} AS namespace_retention_policy,
+            w.retention_policy AS warehouse_retention_policy
+        FROM `table` t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE w.status = 'active' AND NOT w.read_only
+        AND t.metadata_location IS NOT NULL
+        AND (? IS NULL OR t.table_id > ?)
+        AND (
+            {CLOSEST_NAMESPACE_RETENTION_POLICY} IS NOT NULL
+            OR w.retention_policy IS NOT NULL
+            OR JSON_OVERLAPS(JSON_KEYS(t.metadata, '$.properties'), CAST(? AS JSON))
+        )
+        ORDER BY t.table_id
+        LIMIT ?
+        "
+    );
+    let rows = sqlx::query(&query)
+        .bind(after)
+        .bind(after)
+        .bind(override_properties)
+        .bind(limit)
+        .fetch_all(&catalog_state.read_pool)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching tables with retention".to_string()))?;
+
+    rows.into_iter()
+        .map(|row| {
+            Ok(RetentionCandidate {
+                warehouse_id: row.get::<uuid::Uuid, _>("warehouse_id").into(),
+                table_id: row.get::<uuid::Uuid, _>("table_id").into(),
+                table: table_ident(&row)?,
+                policy: retention_policy(&row)?,
+            })
+        })
+        .collect()
+}
+
+/// Policy of the closest namespace, or of the warehouse.
+fn retention_policy(row: &MySqlRow) -> Result<Option<SnapshotRetentionPolicy>> {
+    let parse = |column| {
+        row.try_get::<Option<Json<SnapshotRetentionPolicy>>, _>(column)
+            .map_err(|e| e.into_error_model("Error parsing retention policy".to_string()))
+    };
+    Ok(parse("namespace_retention_policy")?
+        .or(parse("warehouse_retention_policy")?)
+        .map(|p| p.0))
+}
+
+pub(crate) async fn reclaim_dropped_table(
+    warehouse_id: &WarehouseIdent,
+    table_id: &TableIdentUuid,
+    storage_purged: bool,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result = sqlx::query(
+        r"
+        UPDATE dropped_table
+        SET metadata = NULL, reclaimed_at = CURRENT_TIMESTAMP(6), storage_purged = ?
+        WHERE warehouse_id = ? AND table_id = ? AND reclaimed_at IS NULL
+        ",
+    )
+    .bind(storage_purged)
+    .bind(*warehouse_id.as_uuid())
+    .bind(*table_id.as_uuid())
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error reclaiming dropped table".to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(dropped_table_not_found().into());
+    }
+
+    Ok(())
+}
+
+async fn get_commit_context<'a>(
+    request: CommitTransactionRequest,
+    table_ids: &HashMap<TableIdent, TableIdentUuid>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<Vec<CommitContext>> {
+    let sorted_table_ids = lock_order(table_ids);
+
+    let mut query_builder = sqlx::QueryBuilder::new(format!(
+        r"
+        SELECT
+            t.table_id,
+            t.metadata,
+            t.metadata_location,
+            t.metadata_version,
+            w.storage_profile,
+            w.storage_secret_id,
+            {CLOSEST_NAMESPACE_RETENTION_POLICY} AS namespace_retention_policy,
+            w.retention_policy AS warehouse_retention_policy
+        FROM `table` t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE w.status = 'active' AND t.table_id IN ("
+    ));
+    let mut separated = query_builder.separated(", ");
+    for table_id in &sorted_table_ids {
+        separated.push_bind(*table_id);
+    }
+    query_builder.push(") ORDER BY t.table_id FOR UPDATE OF t");
+
+    let rows = if sorted_table_ids.is_empty() {
+        vec![]
+    } else {
+        query_builder
+            .build()
+            .fetch_all(&mut **transaction)
+            .await
+            .map_err(|e| {
+                e.into_error_model("Error fetching table metadata for commit".to_string())
+            })?
+    };
+
+    let rows = rows
+        .iter()
+        .map(|row| {
+            let metadata = row
+                .try_get::<Json<TableMetadata>, _>("metadata")
+                .map_err(|e| e.into_error_model("Error parsing table metadata".to_string()))?;
+            let storage_profile = row
+                .try_get::<Json<StorageProfile>, _>("storage_profile")
+                .map_err(|e| e.into_error_model("Error parsing storage profile".to_string()))?;
+            Ok(CommitRow {
+                table_id: row.get("table_id"),
+                storage_profile: storage_profile.0,
+                storage_secret_ident: row
+                    .get::<Option<uuid::Uuid>, _>("storage_secret_id")
+                    .map(SecretIdent::from),
+                metadata: metadata.0,
+                metadata_location: row.get("metadata_location"),
+                metadata_version: row.get("metadata_version"),
+                retention_policy: retention_policy(row)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    commit_contexts(request, table_ids, &rows)
+}
+
+pub(crate) async fn commit_table_transaction<'a>(
+    // We do not need the warehouse_id here, because table_ids are unique across warehouses
+    _: &WarehouseIdent,
+    request: CommitTransactionRequest,
+    table_ids: &HashMap<TableIdent, TableIdentUuid>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<Vec<CommitTableResponseExt>> {
+    let contexts = get_commit_context(request, table_ids, transaction).await?;
+    let (responses, expected_versions) = prepare_commits(contexts, MAX_PARAMETERS / 4)?;
+    update_table_metadata(&responses, &expected_versions, transaction).await?;
+
+    Ok(responses)
+}
+
+/// Write the new metadata of committed tables. Fails with a `409` if the
+/// `metadata_version` of a table does not match the version the commit is based on.
+async fn update_table_metadata(
+    responses: &[CommitTableResponseExt],
+    expected_versions: &HashMap<uuid::Uuid, i64>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let mut updated = HashSet::new();
+    for response in update_order(responses) {
+        let table_id = response.commit_response.metadata.uuid();
+        let metadata_location = &response.commit_response.metadata_location;
+
+        let result = sqlx::query(
+            r"
+            UPDATE `table`
+            SET metadata = ?, metadata_location = ?, metadata_version = metadata_version + 1
+            WHERE table_id = ? AND metadata_version = ?
+            ",
+        )
+        .bind(serialize_metadata(&response.commit_response.metadata)?)
+        .bind(metadata_location)
+        .bind(table_id)
+        .bind(expected_versions.get(&table_id).copied())
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error committing table updates".to_string()))?;
+
+        if result.rows_affected() > 0 {
+            updated.insert(table_id);
+            log_metadata_location(table_id, metadata_location, transaction).await?;
+        }
+    }
+
+    assert_all_updated(expected_versions, &updated)
+}
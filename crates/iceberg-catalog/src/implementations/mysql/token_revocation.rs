@@ -0,0 +1,77 @@
+use super::dbutils::DBErrorHandler as _;
+use super::CatalogState;
+use crate::api::{ErrorModel, Result};
+use crate::service::token_revocation::{RevocationTarget, TokenRevocation};
+use http::StatusCode;
+use sqlx::Row;
+
+pub(crate) async fn revoke_token(
+    revocation: &TokenRevocation,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let (token_id, subject) = match &revocation.target {
+        RevocationTarget::TokenId(token_id) => (Some(token_id.as_str()), None),
+        RevocationTarget::Subject(subject) => (None, Some(subject.as_str())),
+    };
+    if token_id.or(subject).is_some_and(str::is_empty) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("Token id or subject to revoke cannot be empty".to_string())
+            .r#type("EmptyRevocationTarget".to_string())
+            .build()
+            .into());
+    }
+
+    sqlx::query(
+        r"
+        INSERT INTO token_revocation (token_id, subject, revoked_at, expires_at, reason)
+        VALUES (?, ?, ?, ?, ?)
+        ",
+    )
+    .bind(token_id)
+    .bind(subject)
+    .bind(revocation.revoked_at)
+    .bind(revocation.expires_at)
+    .bind(&revocation.reason)
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error revoking token".into()))?;
+
+    Ok(())
+}
+
+pub(crate) async fn list_active_token_revocations(
+    catalog_state: CatalogState,
+) -> Result<Vec<TokenRevocation>> {
+    let rows = sqlx::query(
+        r"
+        SELECT token_id, subject, revoked_at, expires_at, reason
+        FROM token_revocation
+        WHERE expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP(6)
+        ",
+    )
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching token revocations".into()))?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let target = match (
+                row.get::<Option<String>, _>("token_id"),
+                row.get::<Option<String>, _>("subject"),
+            ) {
+                (Some(token_id), _) => RevocationTarget::TokenId(token_id),
+                (None, Some(subject)) => RevocationTarget::Subject(subject),
+                // Prevented by a check constraint
+                (None, None) => return None,
+            };
+            Some(TokenRevocation {
+                target,
+                revoked_at: row.get("revoked_at"),
+                expires_at: row.get("expires_at"),
+                reason: row.get("reason"),
+            })
+        })
+        .collect())
+}
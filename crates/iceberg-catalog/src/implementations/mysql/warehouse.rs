@@ -0,0 +1,936 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::api::{CatalogConfig, ErrorModel, ErrorType, Result};
+use crate::implementations::validation::{validate_project_name, validate_warehouse_name};
+use crate::service::config::ConfigProvider;
+use crate::service::{
+    auth::UnauthorizedResponse, credential_rotation::CredentialRotation,
+    storage_validation::StorageValidation, EngineTableConfig, EventFilter, GetWarehouseResponse,
+    Project, PyicebergCompat, SnapshotRetentionPolicy, WarehouseAllowlist, WarehouseStatus,
+};
+use crate::{
+    service::storage::{AccessDelegation, StorageProfile},
+    ProjectIdent, SecretIdent, WarehouseIdent,
+};
+use http::StatusCode;
+use sqlx::mysql::MySqlQueryResult;
+
+use super::dbutils::DBErrorHandler as _;
+
+use super::{Catalog, CatalogState};
+use sqlx::types::Json;
+
+fn warehouse_not_found() -> ErrorModel {
+    ErrorModel::builder()
+        .code(StatusCode::NOT_FOUND.into())
+        .message("Warehouse not found".to_string())
+        .r#type("WarehouseNotFound".to_string())
+        .build()
+}
+
+fn project_not_found() -> ErrorModel {
+    ErrorModel::builder()
+        .code(StatusCode::NOT_FOUND.into())
+        .message("Project not found".to_string())
+        .r#type("ProjectNotFound".to_string())
+        .build()
+}
+
+fn project_not_empty() -> ErrorModel {
+    ErrorModel::builder()
+        .code(StatusCode::CONFLICT.into())
+        .message("Project still has warehouses".to_string())
+        .r#type("ProjectNotEmpty".to_string())
+        .build()
+}
+
+/// Fails with a `404` if an update of a single warehouse matched no row.
+fn require_warehouse(result: &MySqlQueryResult) -> Result<()> {
+    if result.rows_affected() == 0 {
+        return Err(warehouse_not_found().into());
+    }
+    Ok(())
+}
+
+fn serialize_storage_profile(storage_profile: StorageProfile) -> Result<serde_json::Value> {
+    Ok(serde_json::to_value(storage_profile).map_err(|e| {
+        ErrorModel::builder()
+            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+            .message("Error serializing storage profile".to_string())
+            .r#type("StorageProfileSerializationError".to_string())
+            .stack(Some(vec![e.to_string()]))
+            .build()
+    })?)
+}
+
+#[async_trait::async_trait]
+impl ConfigProvider<Catalog> for super::Catalog {
+    async fn get_warehouse_by_name(
+        warehouse_name: &str,
+        project_id: &ProjectIdent,
+        catalog_state: CatalogState,
+    ) -> Result<WarehouseIdent> {
+        let warehouse_id = sqlx::query_scalar::<_, uuid::Uuid>(
+            r"
+            SELECT warehouse_id
+            FROM warehouse
+            WHERE warehouse_name = ? AND project_id = ?
+            AND status = 'active'
+            ",
+        )
+        .bind(warehouse_name)
+        .bind(*project_id.as_uuid())
+        .fetch_optional(&catalog_state.read_pool)
+        .await
+        .map_err(|e| {
+            ErrorModel::builder()
+                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message("Error fetching warehouse".to_string())
+                .r#type("WarehouseFetchError".to_string())
+                .stack(Some(vec![e.to_string()]))
+                .build()
+        })?
+        .ok_or_else(warehouse_not_found)?;
+
+        Ok(warehouse_id.into())
+    }
+
+    async fn get_config_for_warehouse(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: CatalogState,
+    ) -> Result<CatalogConfig> {
+        let (storage_profile, signer_uri) =
+            sqlx::query_as::<_, (Json<StorageProfile>, Option<String>)>(
+                r"
+                SELECT storage_profile, signer_uri
+                FROM warehouse
+                WHERE warehouse_id = ?
+                AND status = 'active'
+                ",
+            )
+            .bind(*warehouse_id.as_uuid())
+            .fetch_optional(&catalog_state.read_pool)
+            .await
+            .map_err(|e| {
+                ErrorModel::builder()
+                    .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                    .message("Error fetching warehouse".to_string())
+                    .r#type("WarehouseFetchError".to_string())
+                    .stack(Some(vec![e.to_string()]))
+                    .build()
+            })?
+            .ok_or_else(warehouse_not_found)?;
+
+        let mut config = storage_profile.generate_catalog_config(warehouse_id);
+        if let Some(signer_uri) = signer_uri {
+            if config.overrides.contains_key("s3.signer.uri") {
+                config
+                    .overrides
+                    .insert("s3.signer.uri".to_string(), signer_uri);
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+pub(crate) async fn create_warehouse<'a>(
+    warehouse_name: String,
+    project_id: ProjectIdent,
+    storage_profile: StorageProfile,
+    storage_secret_id: Option<SecretIdent>,
+    owner: Option<&str>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<WarehouseIdent> {
+    validate_warehouse_name(&warehouse_name)?;
+    let storage_profile_ser = serialize_storage_profile(storage_profile)?;
+
+    let warehouse_id = uuid::Uuid::now_v7();
+    sqlx::query(
+        r"
+        INSERT INTO warehouse (warehouse_id, warehouse_name, project_id, storage_profile, storage_secret_id, `status`, created_by, `owner`)
+        VALUES (?, ?, ?, ?, ?, 'active', ?, ?)
+        ",
+    )
+    .bind(warehouse_id)
+    .bind(&warehouse_name)
+    .bind(*project_id.as_uuid())
+    .bind(storage_profile_ser)
+    .bind(storage_secret_id.map(|id| id.into_uuid()))
+    .bind(owner)
+    .bind(owner)
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ErrorModel::builder()
+            .code(StatusCode::CONFLICT.into())
+            .message("Warehouse with this name already exists in the project.".to_string())
+            .r#type("WarehouseNameAlreadyExists".to_string())
+            .error_type(ErrorType::AlreadyExists)
+            .build(),
+        _ => e.as_error_model("Error creating Warehouse".into()),
+    })?;
+
+    // Projects are created with their first warehouse, named after their id.
+    sqlx::query(
+        r"
+        INSERT IGNORE INTO project (project_id, project_name)
+        VALUES (?, ?)
+        ",
+    )
+    .bind(*project_id.as_uuid())
+    .bind(project_id.to_string())
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error creating project".into()))?;
+
+    Ok(warehouse_id.into())
+}
+
+#[derive(sqlx::FromRow, Debug, PartialEq)]
+struct WarehouseRecord {
+    warehouse_id: uuid::Uuid,
+    warehouse_name: String,
+    project_id: uuid::Uuid,
+    storage_profile: Json<StorageProfile>,
+    storage_secret_id: Option<uuid::Uuid>,
+    status: WarehouseStatus,
+    owner: Option<String>,
+    created_by: Option<String>,
+    allowlist: Option<Json<WarehouseAllowlist>>,
+    read_only: bool,
+    pyiceberg_compat: bool,
+    engine_table_config: Json<EngineTableConfig>,
+    signer_uri: Option<String>,
+    unauthorized_response: Option<UnauthorizedResponse>,
+    access_delegation: Option<AccessDelegation>,
+    allow_external_locations: bool,
+    object_storage_layout: bool,
+    storage_validated_at: Option<chrono::DateTime<chrono::Utc>>,
+    storage_validation_error: Option<String>,
+    previous_storage_secret_id: Option<uuid::Uuid>,
+    storage_secret_rotated_at: Option<chrono::DateTime<chrono::Utc>>,
+    event_filter: Json<EventFilter>,
+}
+
+/// Columns of [`WarehouseRecord`].
+const WAREHOUSE_COLUMNS: &str = r"
+    SELECT
+        warehouse_id,
+        warehouse_name,
+        project_id,
+        storage_profile,
+        storage_secret_id,
+        `status`,
+        `owner`,
+        created_by,
+        allowlist,
+        read_only,
+        pyiceberg_compat,
+        engine_table_config,
+        signer_uri,
+        unauthorized_response,
+        access_delegation,
+        allow_external_locations,
+        object_storage_layout,
+        storage_validated_at,
+        storage_validation_error,
+        previous_storage_secret_id,
+        storage_secret_rotated_at,
+        event_filter
+    FROM warehouse";
+
+impl From<WarehouseRecord> for GetWarehouseResponse {
+    fn from(warehouse: WarehouseRecord) -> Self {
+        GetWarehouseResponse {
+            id: warehouse.warehouse_id.into(),
+            name: warehouse.warehouse_name,
+            project_id: warehouse.project_id.into(),
+            storage_profile: warehouse.storage_profile.0,
+            storage_secret_id: warehouse.storage_secret_id.map(std::convert::Into::into),
+            status: warehouse.status,
+            owner: warehouse.owner,
+            created_by: warehouse.created_by,
+            allowlist: warehouse.allowlist.map(|a| a.0),
+            read_only: warehouse.read_only,
+            pyiceberg_compat: warehouse.pyiceberg_compat,
+            engine_table_config: warehouse.engine_table_config.0,
+            signer_uri: warehouse.signer_uri,
+            unauthorized_response: warehouse.unauthorized_response,
+            access_delegation: warehouse.access_delegation,
+            allow_external_locations: warehouse.allow_external_locations,
+            object_storage_layout: warehouse.object_storage_layout,
+            credential_rotation: warehouse.storage_secret_rotated_at.map(|rotated_at| {
+                CredentialRotation {
+                    rotated_at,
+                    previous_secret_id: warehouse.previous_storage_secret_id.map(Into::into),
+                }
+            }),
+            storage_validation: warehouse.storage_validated_at.map(|validated_at| {
+                StorageValidation {
+                    validated_at,
+                    error: warehouse.storage_validation_error,
+                }
+            }),
+            event_filter: warehouse.event_filter.0,
+        }
+    }
+}
+
+pub(crate) async fn list_warehouses(
+    project_id: &ProjectIdent,
+    include_status: Option<Vec<WarehouseStatus>>,
+    warehouse_id_filter: Option<&HashSet<WarehouseIdent>>,
+    catalog_state: CatalogState,
+) -> Result<Vec<GetWarehouseResponse>> {
+    let include_status = include_status.unwrap_or_else(|| vec![WarehouseStatus::Active]);
+    if include_status.is_empty() || warehouse_id_filter.is_some_and(HashSet::is_empty) {
+        return Ok(vec![]);
+    }
+
+    let mut query_builder = sqlx::QueryBuilder::new(WAREHOUSE_COLUMNS);
+    query_builder.push(" WHERE project_id = ");
+    query_builder.push_bind(*project_id.as_uuid());
+    query_builder.push(" AND `status` IN (");
+    let mut separated = query_builder.separated(", ");
+    for status in include_status {
+        separated.push_bind(status);
+    }
+    query_builder.push(")");
+    if let Some(warehouse_id_filter) = warehouse_id_filter {
+        query_builder.push(" AND warehouse_id IN (");
+        let mut separated = query_builder.separated(", ");
+        for warehouse_id in warehouse_id_filter {
+            separated.push_bind(*warehouse_id.as_uuid());
+        }
+        query_builder.push(")");
+    }
+
+    let warehouses = query_builder
+        .build_query_as::<WarehouseRecord>()
+        .fetch_all(&catalog_state.read_pool)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching warehouses".into()))?;
+
+    Ok(warehouses.into_iter().map(Into::into).collect())
+}
+
+pub(crate) async fn get_warehouse_id_by_name(
+    project_id: &ProjectIdent,
+    warehouse_name: &str,
+    catalog_state: CatalogState,
+) -> Result<Option<WarehouseIdent>> {
+    let warehouse_id = sqlx::query_scalar::<_, uuid::Uuid>(
+        r"
+        SELECT warehouse_id
+        FROM warehouse
+        WHERE project_id = ? AND warehouse_name = ?
+        ",
+    )
+    .bind(*project_id.as_uuid())
+    .bind(warehouse_name)
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching warehouse".into()))?;
+
+    Ok(warehouse_id.map(Into::into))
+}
+
+pub(crate) async fn get_warehouse<'a>(
+    warehouse_id: &WarehouseIdent,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<GetWarehouseResponse> {
+    let query = format!("{WAREHOUSE_COLUMNS} WHERE warehouse_id = ?");
+    let warehouse = sqlx::query_as::<_, WarehouseRecord>(&query)
+        .bind(*warehouse_id.as_uuid())
+        .fetch_optional(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching warehouse".into()))?
+        .ok_or_else(warehouse_not_found)?;
+
+    Ok(warehouse.into())
+}
+
+pub(crate) async fn get_warehouse_allowlist(
+    warehouse_id: &WarehouseIdent,
+    catalog_state: CatalogState,
+) -> Result<Option<WarehouseAllowlist>> {
+    let allowlist = sqlx::query_scalar::<_, Option<Json<WarehouseAllowlist>>>(
+        r"
+        SELECT allowlist
+        FROM warehouse
+        WHERE warehouse_id = ?
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching warehouse allowlist".into()))?;
+
+    Ok(allowlist.flatten().map(|a| a.0))
+}
+
+pub(crate) async fn set_warehouse_allowlist<'a>(
+    warehouse_id: &WarehouseIdent,
+    allowlist: Option<WarehouseAllowlist>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result = sqlx::query("UPDATE warehouse SET allowlist = ? WHERE warehouse_id = ?")
+        .bind(allowlist.map(Json))
+        .bind(*warehouse_id.as_uuid())
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error setting warehouse allowlist".into()))?;
+
+    require_warehouse(&result)
+}
+
+pub(crate) async fn is_warehouse_read_only(
+    warehouse_id: &WarehouseIdent,
+    catalog_state: CatalogState,
+) -> Result<bool> {
+    let read_only =
+        sqlx::query_scalar::<_, bool>("SELECT read_only FROM warehouse WHERE warehouse_id = ?")
+            .bind(*warehouse_id.as_uuid())
+            .fetch_optional(&catalog_state.read_pool)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching warehouse".into()))?;
+
+    Ok(read_only.unwrap_or(false))
+}
+
+pub(crate) async fn set_warehouse_read_only(
+    warehouse_id: &WarehouseIdent,
+    read_only: bool,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result = sqlx::query("UPDATE warehouse SET read_only = ? WHERE warehouse_id = ?")
+        .bind(read_only)
+        .bind(*warehouse_id.as_uuid())
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error setting warehouse read-only mode".into()))?;
+
+    require_warehouse(&result)
+}
+
+pub(crate) async fn get_warehouse_signer_uri(
+    warehouse_id: &WarehouseIdent,
+    catalog_state: CatalogState,
+) -> Result<Option<String>> {
+    let signer_uri = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT signer_uri FROM warehouse WHERE warehouse_id = ?",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching warehouse signer uri".into()))?;
+
+    Ok(signer_uri.flatten())
+}
+
+pub(crate) async fn set_warehouse_signer_uri(
+    warehouse_id: &WarehouseIdent,
+    signer_uri: Option<&url::Url>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result = sqlx::query("UPDATE warehouse SET signer_uri = ? WHERE warehouse_id = ?")
+        .bind(signer_uri.map(url::Url::as_str))
+        .bind(*warehouse_id.as_uuid())
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error setting warehouse signer uri".into()))?;
+
+    require_warehouse(&result)
+}
+
+pub(crate) async fn set_warehouse_unauthorized_response(
+    warehouse_id: &WarehouseIdent,
+    unauthorized_response: Option<UnauthorizedResponse>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result =
+        sqlx::query("UPDATE warehouse SET unauthorized_response = ? WHERE warehouse_id = ?")
+            .bind(unauthorized_response)
+            .bind(*warehouse_id.as_uuid())
+            .execute(&mut **transaction)
+            .await
+            .map_err(|e| {
+                e.into_error_model("Error setting warehouse unauthorized response".into())
+            })?;
+
+    require_warehouse(&result)
+}
+
+pub(crate) async fn set_warehouse_access_delegation(
+    warehouse_id: &WarehouseIdent,
+    access_delegation: Option<AccessDelegation>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result = sqlx::query("UPDATE warehouse SET access_delegation = ? WHERE warehouse_id = ?")
+        .bind(access_delegation)
+        .bind(*warehouse_id.as_uuid())
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error setting warehouse access delegation".into()))?;
+
+    require_warehouse(&result)
+}
+
+pub(crate) async fn set_warehouse_allow_external_locations(
+    warehouse_id: &WarehouseIdent,
+    allow_external_locations: bool,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result =
+        sqlx::query("UPDATE warehouse SET allow_external_locations = ? WHERE warehouse_id = ?")
+            .bind(allow_external_locations)
+            .bind(*warehouse_id.as_uuid())
+            .execute(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error setting warehouse external locations".into()))?;
+
+    require_warehouse(&result)
+}
+
+pub(crate) async fn set_warehouse_object_storage_layout(
+    warehouse_id: &WarehouseIdent,
+    object_storage_layout: bool,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result =
+        sqlx::query("UPDATE warehouse SET object_storage_layout = ? WHERE warehouse_id = ?")
+            .bind(object_storage_layout)
+            .bind(*warehouse_id.as_uuid())
+            .execute(&mut **transaction)
+            .await
+            .map_err(|e| {
+                e.into_error_model("Error setting warehouse object storage layout".into())
+            })?;
+
+    require_warehouse(&result)
+}
+
+pub(crate) async fn rotate_warehouse_storage_secret(
+    warehouse_id: &WarehouseIdent,
+    current_secret_id: Option<&SecretIdent>,
+    new_secret_id: &SecretIdent,
+    rotated_at: chrono::DateTime<chrono::Utc>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    // Assignments are evaluated from left to right, the previous secret has to be set first.
+    let result = sqlx::query(
+        r"
+        UPDATE warehouse
+        SET previous_storage_secret_id = storage_secret_id,
+            storage_secret_id = ?,
+            storage_secret_rotated_at = ?
+        WHERE warehouse_id = ?
+        AND `status` = 'active'
+        AND storage_secret_id <=> ?
+        ",
+    )
+    .bind(*new_secret_id.as_uuid())
+    .bind(rotated_at)
+    .bind(*warehouse_id.as_uuid())
+    .bind(current_secret_id.map(SecretIdent::into_uuid))
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error rotating warehouse storage secret".into()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::CONFLICT.into())
+            .message(
+                "Warehouse is not active or its credential was changed concurrently".to_string(),
+            )
+            .r#type("CredentialRotationConflict".to_string())
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn clear_warehouse_previous_storage_secret(
+    warehouse_id: &WarehouseIdent,
+    previous_secret_id: &SecretIdent,
+    catalog_state: CatalogState,
+) -> Result<bool> {
+    let result = sqlx::query(
+        r"
+        UPDATE warehouse
+        SET previous_storage_secret_id = NULL
+        WHERE warehouse_id = ? AND previous_storage_secret_id = ?
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .bind(*previous_secret_id.as_uuid())
+    .execute(&catalog_state.write_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error clearing previous warehouse storage secret".into()))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub(crate) async fn set_warehouse_storage_validation(
+    warehouse_id: &WarehouseIdent,
+    validation: &StorageValidation,
+    catalog_state: CatalogState,
+) -> Result<()> {
+    // Warehouses deleted in the meantime are ignored
+    sqlx::query(
+        r"
+        UPDATE warehouse
+        SET storage_validated_at = ?, storage_validation_error = ?
+        WHERE warehouse_id = ?
+        ",
+    )
+    .bind(validation.validated_at)
+    .bind(&validation.error)
+    .bind(*warehouse_id.as_uuid())
+    .execute(&catalog_state.write_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error storing warehouse storage validation".into()))?;
+
+    Ok(())
+}
+
+pub(crate) async fn set_warehouse_retention_policy(
+    warehouse_id: &WarehouseIdent,
+    policy: Option<SnapshotRetentionPolicy>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result = sqlx::query("UPDATE warehouse SET retention_policy = ? WHERE warehouse_id = ?")
+        .bind(policy.map(Json))
+        .bind(*warehouse_id.as_uuid())
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error setting warehouse retention policy".into()))?;
+
+    require_warehouse(&result)
+}
+
+pub(crate) async fn get_engine_table_config(
+    warehouse_id: &WarehouseIdent,
+    catalog_state: CatalogState,
+) -> Result<EngineTableConfig> {
+    let config = sqlx::query_scalar::<_, Json<EngineTableConfig>>(
+        "SELECT engine_table_config FROM warehouse WHERE warehouse_id = ?",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching engine table config".into()))?;
+
+    Ok(config.map(|c| c.0).unwrap_or_default())
+}
+
+pub(crate) async fn set_engine_table_config(
+    warehouse_id: &WarehouseIdent,
+    config: EngineTableConfig,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result = sqlx::query("UPDATE warehouse SET engine_table_config = ? WHERE warehouse_id = ?")
+        .bind(Json(config))
+        .bind(*warehouse_id.as_uuid())
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error setting engine table config".into()))?;
+
+    require_warehouse(&result)
+}
+
+pub(crate) async fn set_event_filter(
+    warehouse_id: &WarehouseIdent,
+    filter: EventFilter,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result = sqlx::query("UPDATE warehouse SET event_filter = ? WHERE warehouse_id = ?")
+        .bind(Json(filter))
+        .bind(*warehouse_id.as_uuid())
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error setting event filter".into()))?;
+
+    require_warehouse(&result)
+}
+
+pub(crate) async fn get_pyiceberg_compat(
+    warehouse_id: &WarehouseIdent,
+    catalog_state: CatalogState,
+) -> Result<Option<PyicebergCompat>> {
+    let warehouse_name = sqlx::query_scalar::<_, String>(
+        r"
+        SELECT warehouse_name
+        FROM warehouse
+        WHERE warehouse_id = ? AND pyiceberg_compat
+        ",
+    )
+    .bind(*warehouse_id.as_uuid())
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching warehouse".into()))?;
+
+    Ok(warehouse_name.map(|warehouse_name| PyicebergCompat { warehouse_name }))
+}
+
+pub(crate) async fn set_warehouse_pyiceberg_compat(
+    warehouse_id: &WarehouseIdent,
+    enabled: bool,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result = sqlx::query("UPDATE warehouse SET pyiceberg_compat = ? WHERE warehouse_id = ?")
+        .bind(enabled)
+        .bind(*warehouse_id.as_uuid())
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| {
+            e.into_error_model("Error setting warehouse pyiceberg compatibility".into())
+        })?;
+
+    require_warehouse(&result)
+}
+
+pub(crate) async fn list_projects(catalog_state: CatalogState) -> Result<HashSet<ProjectIdent>> {
+    let projects = sqlx::query_scalar::<_, uuid::Uuid>(
+        r"
+        SELECT project_id FROM project
+        UNION
+        SELECT project_id FROM warehouse
+        ",
+    )
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching projects".into()))?;
+
+    Ok(projects.into_iter().map(ProjectIdent::from).collect())
+}
+
+pub(crate) async fn get_projects(
+    project_ids: Option<&HashSet<ProjectIdent>>,
+    catalog_state: CatalogState,
+) -> Result<Vec<Project>> {
+    if project_ids.is_some_and(HashSet::is_empty) {
+        return Ok(vec![]);
+    }
+
+    let mut query_builder =
+        sqlx::QueryBuilder::new("SELECT project_id, project_name, metadata FROM project");
+    if let Some(project_ids) = project_ids {
+        query_builder.push(" WHERE project_id IN (");
+        let mut separated = query_builder.separated(", ");
+        for project_id in project_ids {
+            separated.push_bind(*project_id.as_uuid());
+        }
+        query_builder.push(")");
+    }
+    query_builder.push(" ORDER BY project_name");
+
+    let projects = query_builder
+        .build_query_as::<(uuid::Uuid, String, Json<HashMap<String, String>>)>()
+        .fetch_all(&catalog_state.read_pool)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching projects".into()))?;
+
+    Ok(projects
+        .into_iter()
+        .map(|(project_id, name, metadata)| Project {
+            project_id: project_id.into(),
+            name,
+            metadata: metadata.0,
+        })
+        .collect())
+}
+
+pub(crate) async fn get_project_id_by_name(
+    project_name: &str,
+    catalog_state: CatalogState,
+) -> Result<Option<ProjectIdent>> {
+    let project_id = sqlx::query_scalar::<_, uuid::Uuid>(
+        "SELECT project_id FROM project WHERE project_name = ?",
+    )
+    .bind(project_name)
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching project".into()))?;
+
+    Ok(project_id.map(Into::into))
+}
+
+pub(crate) async fn rename_project<'a>(
+    project_id: &ProjectIdent,
+    new_name: &str,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    validate_project_name(project_id, new_name)?;
+
+    let map_error = |e: sqlx::Error| match &e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ErrorModel::builder()
+            .code(StatusCode::CONFLICT.into())
+            .message("Project with this name already exists.".to_string())
+            .r#type("ProjectNameAlreadyExists".to_string())
+            .error_type(ErrorType::AlreadyExists)
+            .build(),
+        _ => e.as_error_model("Error renaming project".into()),
+    };
+
+    // `ON DUPLICATE KEY UPDATE` would also update on a conflicting name,
+    // the project is updated first and created if it doesn't exist.
+    let result = sqlx::query("UPDATE project SET project_name = ? WHERE project_id = ?")
+        .bind(new_name)
+        .bind(*project_id.as_uuid())
+        .execute(&mut **transaction)
+        .await
+        .map_err(map_error)?;
+
+    if result.rows_affected() == 0 {
+        sqlx::query("INSERT INTO project (project_id, project_name) VALUES (?, ?)")
+            .bind(*project_id.as_uuid())
+            .bind(new_name)
+            .execute(&mut **transaction)
+            .await
+            .map_err(map_error)?;
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn set_project_metadata<'a>(
+    project_id: &ProjectIdent,
+    metadata: &HashMap<String, String>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result = sqlx::query("UPDATE project SET metadata = ? WHERE project_id = ?")
+        .bind(Json(metadata))
+        .bind(*project_id.as_uuid())
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error setting project metadata".into()))?;
+
+    if result.rows_affected() == 0 {
+        sqlx::query("INSERT INTO project (project_id, project_name, metadata) VALUES (?, ?, ?)")
+            .bind(*project_id.as_uuid())
+            .bind(project_id.to_string())
+            .bind(Json(metadata))
+            .execute(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error setting project metadata".into()))?;
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn delete_project<'a>(
+    project_id: &ProjectIdent,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let has_warehouses = sqlx::query_scalar::<_, i64>(
+        "SELECT EXISTS (SELECT 1 FROM warehouse WHERE project_id = ?)",
+    )
+    .bind(*project_id.as_uuid())
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching warehouses".into()))?;
+    if has_warehouses != 0 {
+        return Err(project_not_empty().into());
+    }
+
+    let result = sqlx::query("DELETE FROM project WHERE project_id = ?")
+        .bind(*project_id.as_uuid())
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error deleting project".into()))?;
+    if result.rows_affected() == 0 {
+        return Err(project_not_found().into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn delete_warehouse<'a>(
+    warehouse_id: &WarehouseIdent,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result = sqlx::query("DELETE FROM warehouse WHERE warehouse_id = ?")
+        .bind(*warehouse_id.as_uuid())
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db_error) if db_error.is_foreign_key_violation() => {
+                ErrorModel::builder()
+                    .code(StatusCode::CONFLICT.into())
+                    .message("Warehouse is not empty".to_string())
+                    .r#type("WarehouseNotEmpty".to_string())
+                    .build()
+            }
+            _ => e.as_error_model("Error deleting warehouse".into()),
+        })?;
+
+    require_warehouse(&result)
+}
+
+pub(crate) async fn rename_warehouse<'a>(
+    warehouse_id: &WarehouseIdent,
+    new_name: &str,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    validate_warehouse_name(new_name)?;
+
+    let result = sqlx::query(
+        r"
+        UPDATE warehouse
+        SET warehouse_name = ?
+        WHERE warehouse_id = ?
+        AND `status` = 'active'
+        ",
+    )
+    .bind(new_name)
+    .bind(*warehouse_id.as_uuid())
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error renaming warehouse".into()))?;
+
+    require_warehouse(&result)
+}
+
+pub(crate) async fn set_warehouse_status<'a>(
+    warehouse_id: &WarehouseIdent,
+    status: WarehouseStatus,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let result = sqlx::query("UPDATE warehouse SET `status` = ? WHERE warehouse_id = ?")
+        .bind(status)
+        .bind(*warehouse_id.as_uuid())
+        .execute(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error setting warehouse status".into()))?;
+
+    require_warehouse(&result)
+}
+
+pub(crate) async fn update_storage_profile<'a>(
+    warehouse_id: &WarehouseIdent,
+    storage_profile: StorageProfile,
+    storage_secret_id: Option<SecretIdent>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+) -> Result<()> {
+    let storage_profile_ser = serialize_storage_profile(storage_profile)?;
+
+    let result = sqlx::query(
+        r"
+        UPDATE warehouse
+        SET storage_profile = ?, storage_secret_id = ?
+        WHERE warehouse_id = ?
+        AND `status` = 'active'
+        ",
+    )
+    .bind(storage_profile_ser)
+    .bind(storage_secret_id.map(|id| id.into_uuid()))
+    .bind(*warehouse_id.as_uuid())
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error updating storage profile".into()))?;
+
+    require_warehouse(&result)
+}
@@ -0,0 +1,27 @@
+use super::dbutils::DBErrorHandler as _;
+use super::CatalogState;
+use crate::api::Result;
+use crate::service::webhook::WebhookDeadLetter;
+
+pub(crate) async fn record_webhook_dead_letter(
+    dead_letter: &WebhookDeadLetter,
+    catalog_state: CatalogState,
+) -> Result<()> {
+    sqlx::query(
+        r"
+        INSERT INTO webhook_dead_letter (event_id, url, event, attempts, error, failed_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ",
+    )
+    .bind(&dead_letter.event_id)
+    .bind(dead_letter.url.as_str())
+    .bind(&dead_letter.event)
+    .bind(dead_letter.attempts)
+    .bind(&dead_letter.error)
+    .bind(dead_letter.failed_at)
+    .execute(&catalog_state.write_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error storing webhook dead letter".into()))?;
+
+    Ok(())
+}
@@ -0,0 +1,1046 @@
+//! [`AuthZHandler`] delegating all access checks to an [Open Policy Agent](https://www.openpolicyagent.org/).
+//!
+//! Every check is sent as a `POST` to the configured decision endpoint, e.g.
+//! `http://opa:8181/v1/data/iceberg/allow`, with a body of the form
+//! `{"input": {"action": "create-table", "principal": "...", "warehouse-id": "...", ...}}`.
+//! The action is the name of the checked operation, the other fields describe the principal
+//! and the object the operation is performed on. If `openid_scope_permissions` is configured,
+//! `permissions` contains the permissions granted by the scopes of the token.
+//! Fields without a value are omitted.
+//!
+//! The policy must return either a boolean (`{"result": true}`) or an object with an `allow`
+//! field. For `list-projects` and `list-warehouse-in-project` the object may additionally
+//! contain `projects` or `warehouses`, the ids of the objects the principal may see. An
+//! undefined result denies access. If OPA can't be reached, requests fail with `503`.
+//!
+//! With the `wasm-policies` feature, decisions can instead be made by a WASM module
+//! configured via `wasm_authz_module`. Its `authorize` export receives the same input and
+//! returns the same result, see [`crate::service::wasm_policy`].
+use std::collections::HashSet;
+use std::time::Duration;
+
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use url::Url;
+use uuid::Uuid;
+
+use crate::api::{iceberg::v1::NamespaceIdent, ErrorModel, Result};
+use crate::request_metadata::RequestMetadata;
+#[cfg(feature = "wasm-policies")]
+use crate::service::wasm_policy::WasmAuthorizer;
+use crate::{
+    implementations::DEFAULT_PROJECT_ID,
+    service::{
+        auth::{AuthConfigHandler, AuthZHandler, UserWarehouse},
+        token_verification::Permission,
+        ObjectOwnership, OwnedObject, RoleObject, TableIdent, TableIdentUuid,
+    },
+    ProjectIdent, WarehouseIdent, CONFIG,
+};
+
+#[derive(Clone, Debug)]
+pub struct OpaAuthState {
+    decision_point: DecisionPoint,
+}
+
+/// Where decisions are made.
+#[derive(Clone, Debug)]
+enum DecisionPoint {
+    Http {
+        client: reqwest::Client,
+        url: Url,
+    },
+    #[cfg(feature = "wasm-policies")]
+    Wasm(WasmAuthorizer),
+}
+
+impl OpaAuthState {
+    /// State for the `opa_url` or `wasm_authz_module` of the config, `None` if neither
+    /// is configured.
+    ///
+    /// # Errors
+    /// Fails if the HTTP client can't be built or the module can't be compiled.
+    pub fn from_config() -> anyhow::Result<Option<Self>> {
+        #[cfg(feature = "wasm-policies")]
+        if let Some(authorizer) = WasmAuthorizer::from_config()? {
+            return Ok(Some(Self::wasm(authorizer)));
+        }
+        let Some(url) = CONFIG.opa_url.clone() else {
+            return Ok(None);
+        };
+        let client = crate::service::http_client::client_builder()?
+            .timeout(Duration::from_millis(CONFIG.opa_timeout_ms))
+            .build()?;
+        Ok(Some(Self::new(client, url)))
+    }
+
+    #[must_use]
+    pub fn new(client: reqwest::Client, url: Url) -> Self {
+        Self {
+            decision_point: DecisionPoint::Http { client, url },
+        }
+    }
+
+    /// Let a WASM module make the decisions instead of an Open Policy Agent.
+    #[cfg(feature = "wasm-policies")]
+    #[must_use]
+    pub fn wasm(authorizer: WasmAuthorizer) -> Self {
+        Self {
+            decision_point: DecisionPoint::Wasm(authorizer),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// Asks an Open Policy Agent for every decision.
+pub struct OpaAuthZHandler;
+
+#[derive(Debug, Serialize)]
+struct DecisionRequest<'a> {
+    input: Input<'a>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct Input<'a> {
+    action: String,
+    principal: Option<&'a str>,
+    groups: Vec<&'a str>,
+    /// Permissions granted by the scopes of the token, see `openid_scope_permissions`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    permissions: Option<Vec<Permission>>,
+    request_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tenant_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_agent: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    project_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warehouse_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    namespace: Option<&'a NamespaceIdent>,
+    /// Name of the table, if the object is identified by name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    table_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_by: Option<&'a str>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DecisionResponse {
+    #[serde(default)]
+    result: Option<DecisionResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum DecisionResult {
+    Allow(bool),
+    Decision(Decision),
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+struct Decision {
+    #[serde(default)]
+    allow: bool,
+    #[serde(default)]
+    projects: Option<Vec<Uuid>>,
+    #[serde(default)]
+    warehouses: Option<Vec<Uuid>>,
+}
+
+impl From<DecisionResponse> for Decision {
+    fn from(response: DecisionResponse) -> Self {
+        match response.result {
+            Some(DecisionResult::Allow(allow)) => Decision {
+                allow,
+                ..Decision::default()
+            },
+            Some(DecisionResult::Decision(decision)) => decision,
+            None => Decision::default(),
+        }
+    }
+}
+
+impl<'a> Input<'a> {
+    fn new(action: &str, metadata: &'a RequestMetadata) -> Self {
+        Self {
+            action: action.replace('_', "-"),
+            principal: metadata.principal(),
+            groups: metadata.groups(),
+            permissions: metadata.permissions().map(|permissions| {
+                let mut permissions = Vec::from_iter(permissions);
+                permissions.sort();
+                permissions
+            }),
+            request_id: metadata.request_id,
+            tenant_id: metadata.tenant_id(),
+            user_agent: metadata.user_agent.as_deref(),
+            project_id: metadata.project_id.as_ref().map(|p| *p.as_uuid()),
+            warehouse_id: None,
+            namespace: None,
+            name: None,
+            table_id: None,
+            owner: None,
+            created_by: None,
+        }
+    }
+
+    /// Add the current owner and creator of the object.
+    fn with_ownership(self, ownership: Option<&'a ObjectOwnership>) -> Self {
+        Self {
+            owner: ownership.and_then(|o| o.owner.as_deref()),
+            created_by: ownership.and_then(|o| o.created_by.as_deref()),
+            ..self
+        }
+    }
+
+    /// Ask OPA for a decision, failing if access is denied.
+    async fn decide(self, state: &OpaAuthState) -> Result<Decision> {
+        let action = self.action.clone();
+        let response = match &state.decision_point {
+            DecisionPoint::Http { client, url } => client
+                .post(url.clone())
+                .json(&DecisionRequest { input: self })
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .map_err(|e| unavailable(&e))?
+                .json::<DecisionResponse>()
+                .await
+                .map_err(|e| unavailable(&e))?,
+            #[cfg(feature = "wasm-policies")]
+            DecisionPoint::Wasm(authorizer) => {
+                let input = serde_json::to_vec(&self).map_err(|e| unavailable(&e))?;
+                let output = authorizer
+                    .authorize(input)
+                    .await
+                    .map_err(|e| unavailable(&e))?;
+                // The module returns what OPA returns as `result`.
+                DecisionResponse {
+                    result: serde_json::from_slice(&output).map_err(|e| unavailable(&e))?,
+                }
+            }
+        };
+
+        let decision = Decision::from(response);
+        if decision.allow {
+            Ok(decision)
+        } else {
+            Err(ErrorModel::builder()
+                .code(StatusCode::FORBIDDEN.into())
+                .message(format!("Not allowed to {action}"))
+                .r#type("ActionForbidden".to_string())
+                .build()
+                .into())
+        }
+    }
+
+    async fn require(self, state: &OpaAuthState) -> Result<()> {
+        self.decide(state).await.map(|_| ())
+    }
+}
+
+fn unavailable(error: &dyn std::fmt::Display) -> ErrorModel {
+    tracing::error!("Failed to get authorization decision: {error}");
+    ErrorModel::builder()
+        .code(StatusCode::SERVICE_UNAVAILABLE.into())
+        .message("Authorization service unavailable".to_string())
+        .r#type("AuthorizationUnavailable".to_string())
+        .build()
+}
+
+#[async_trait::async_trait]
+impl AuthConfigHandler<OpaAuthZHandler> for OpaAuthZHandler {
+    async fn get_and_validate_user_warehouse(
+        state: OpaAuthState,
+        metadata: &RequestMetadata,
+    ) -> Result<UserWarehouse> {
+        let project_id = metadata
+            .project_id
+            .clone()
+            .unwrap_or_else(|| ProjectIdent::from(DEFAULT_PROJECT_ID));
+        <Self as AuthConfigHandler<Self>>::check_list_warehouse_in_project(
+            state,
+            &project_id,
+            metadata,
+        )
+        .await?;
+        Ok(UserWarehouse {
+            project_id: Some(project_id),
+            warehouse_id: None,
+        })
+    }
+
+    async fn exchange_token_for_warehouse(
+        _: OpaAuthState,
+        _: &RequestMetadata,
+        _: &ProjectIdent,
+        _: &WarehouseIdent,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    async fn check_list_warehouse_in_project(
+        state: OpaAuthState,
+        project_id: &ProjectIdent,
+        metadata: &RequestMetadata,
+    ) -> Result<()> {
+        Input {
+            project_id: Some(*project_id.as_uuid()),
+            ..Input::new("list_warehouse_in_project", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_user_get_config_for_warehouse(
+        state: OpaAuthState,
+        warehouse_id: &WarehouseIdent,
+        metadata: &RequestMetadata,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("get_config", metadata)
+        }
+        .require(&state)
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthZHandler for OpaAuthZHandler {
+    type State = OpaAuthState;
+
+    async fn check_list_namespace(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        parent: Option<&NamespaceIdent>,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            namespace: parent,
+            ..Input::new("list_namespace", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_create_namespace(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        parent: Option<&NamespaceIdent>,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            namespace: parent,
+            ..Input::new("create_namespace", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_load_namespace_metadata(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            namespace: Some(namespace),
+            ..Input::new("load_namespace_metadata", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_namespace_exists(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            namespace: Some(namespace),
+            ..Input::new("namespace_exists", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_drop_namespace(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        ownership: Option<&ObjectOwnership>,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            namespace: Some(namespace),
+            ..Input::new("drop_namespace", metadata)
+        }
+        .with_ownership(ownership)
+        .require(&state)
+        .await
+    }
+
+    async fn check_update_namespace_properties(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        ownership: Option<&ObjectOwnership>,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            namespace: Some(namespace),
+            ..Input::new("update_namespace_properties", metadata)
+        }
+        .with_ownership(ownership)
+        .require(&state)
+        .await
+    }
+
+    async fn check_create_table(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            namespace: Some(namespace),
+            ..Input::new("create_table", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_list_tables(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            namespace: Some(namespace),
+            ..Input::new("list_tables", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_load_table(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        table: Option<&TableIdentUuid>,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            namespace,
+            table_id: table.map(|t| *t.as_uuid()),
+            ..Input::new("load_table", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_rename_table(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        source: Option<&TableIdentUuid>,
+        ownership: Option<&ObjectOwnership>,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            table_id: source.map(|t| *t.as_uuid()),
+            ..Input::new("rename_table", metadata)
+        }
+        .with_ownership(ownership)
+        .require(&state)
+        .await
+    }
+
+    async fn check_table_exists(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        table: Option<&TableIdentUuid>,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            namespace,
+            table_id: table.map(|t| *t.as_uuid()),
+            ..Input::new("table_exists", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_drop_table(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        table: Option<&TableIdentUuid>,
+        ownership: Option<&ObjectOwnership>,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            table_id: table.map(|t| *t.as_uuid()),
+            ..Input::new("drop_table", metadata)
+        }
+        .with_ownership(ownership)
+        .require(&state)
+        .await
+    }
+
+    async fn check_commit_table(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        table: Option<&TableIdentUuid>,
+        namespace: Option<&NamespaceIdent>,
+        ownership: Option<&ObjectOwnership>,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            table_id: table.map(|t| *t.as_uuid()),
+            namespace,
+            ..Input::new("commit_table", metadata)
+        }
+        .with_ownership(ownership)
+        .require(&state)
+        .await
+    }
+
+    async fn check_create_warehouse(
+        metadata: &RequestMetadata,
+        project_id: &ProjectIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            project_id: Some(*project_id.as_uuid()),
+            ..Input::new("create_warehouse", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_list_projects(
+        metadata: &RequestMetadata,
+        state: OpaAuthState,
+    ) -> Result<Option<HashSet<ProjectIdent>>> {
+        let decision = Input::new("list_projects", metadata).decide(&state).await?;
+        Ok(decision
+            .projects
+            .map(|projects| projects.into_iter().map(ProjectIdent::from).collect()))
+    }
+
+    async fn check_list_warehouse_in_project(
+        metadata: &RequestMetadata,
+        project_id: &ProjectIdent,
+        state: OpaAuthState,
+    ) -> Result<Option<HashSet<WarehouseIdent>>> {
+        let decision = Input {
+            project_id: Some(*project_id.as_uuid()),
+            ..Input::new("list_warehouse_in_project", metadata)
+        }
+        .decide(&state)
+        .await?;
+        Ok(decision
+            .warehouses
+            .map(|warehouses| warehouses.into_iter().map(WarehouseIdent::from).collect()))
+    }
+
+    async fn check_update_project(
+        metadata: &RequestMetadata,
+        project_id: &ProjectIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            project_id: Some(*project_id.as_uuid()),
+            ..Input::new("update_project", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_delete_project(
+        metadata: &RequestMetadata,
+        project_id: &ProjectIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            project_id: Some(*project_id.as_uuid()),
+            ..Input::new("delete_project", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_delete_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("delete_warehouse", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_get_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("get_warehouse", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_rename_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("rename_warehouse", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_deactivate_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("deactivate_warehouse", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_activate_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("activate_warehouse", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_update_storage(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("update_storage", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_set_warehouse_allowlist(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("set_warehouse_allowlist", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_set_engine_table_config(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("set_engine_table_config", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_set_event_filter(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("set_event_filter", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_set_maintenance_mode(
+        metadata: &RequestMetadata,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input::new("set_maintenance_mode", metadata)
+            .require(&state)
+            .await
+    }
+
+    async fn check_reload_config(metadata: &RequestMetadata, state: OpaAuthState) -> Result<()> {
+        Input::new("reload_config", metadata).require(&state).await
+    }
+
+    async fn check_set_log_level(metadata: &RequestMetadata, state: OpaAuthState) -> Result<()> {
+        Input::new("set_log_level", metadata).require(&state).await
+    }
+
+    async fn check_set_warehouse_read_only(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("set_warehouse_read_only", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_set_warehouse_pyiceberg_compat(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("set_warehouse_pyiceberg_compat", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_set_warehouse_signer_uri(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("set_warehouse_signer_uri", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_set_warehouse_unauthorized_response(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("set_warehouse_unauthorized_response", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_set_warehouse_access_delegation(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("set_warehouse_access_delegation", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_set_warehouse_allow_external_locations(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("set_warehouse_allow_external_locations", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_set_warehouse_object_storage_layout(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("set_warehouse_object_storage_layout", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_set_table_policy(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            namespace: Some(&table.namespace),
+            name: Some(table.name.as_str()),
+            ..Input::new("set_table_policy", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_set_warehouse_retention_policy(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("set_warehouse_retention_policy", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_set_namespace_retention_policy(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            namespace: Some(namespace),
+            ..Input::new("set_namespace_retention_policy", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_manage_staged_tables(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("manage_staged_tables", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_manage_shares(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            ..Input::new("manage_shares", metadata)
+        }
+        .require(&state)
+        .await
+    }
+
+    async fn check_transfer_ownership(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        object: &OwnedObject,
+        ownership: &ObjectOwnership,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        let (namespace, name) = match object {
+            OwnedObject::Warehouse => (None, None),
+            OwnedObject::Namespace(namespace) => (Some(namespace), None),
+            OwnedObject::Table(table) => (Some(&table.namespace), Some(table.name.as_str())),
+        };
+        Input {
+            warehouse_id: Some(*warehouse_id.as_uuid()),
+            namespace,
+            name,
+            ..Input::new("transfer_ownership", metadata)
+        }
+        .with_ownership(Some(ownership))
+        .require(&state)
+        .await
+    }
+
+    async fn check_revoke_token(metadata: &RequestMetadata, state: OpaAuthState) -> Result<()> {
+        Input::new("revoke_token", metadata).require(&state).await
+    }
+
+    async fn check_manage_service_keys(
+        metadata: &RequestMetadata,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        Input::new("manage_service_keys", metadata)
+            .require(&state)
+            .await
+    }
+
+    async fn check_manage_roles(
+        metadata: &RequestMetadata,
+        object: &RoleObject,
+        state: OpaAuthState,
+    ) -> Result<()> {
+        let input = Input::new("manage_roles", metadata);
+        match object {
+            RoleObject::Project(project_id) => Input {
+                project_id: Some(*project_id.as_uuid()),
+                ..input
+            },
+            RoleObject::Warehouse(warehouse_id) => Input {
+                warehouse_id: Some(*warehouse_id.as_uuid()),
+                ..input
+            },
+            RoleObject::Namespace(warehouse_id, namespace) => Input {
+                warehouse_id: Some(*warehouse_id.as_uuid()),
+                namespace: Some(namespace),
+                ..input
+            },
+            RoleObject::Table(warehouse_id, table) => Input {
+                warehouse_id: Some(*warehouse_id.as_uuid()),
+                namespace: Some(&table.namespace),
+                name: Some(table.name.as_str()),
+                ..input
+            },
+        }
+        .require(&state)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decision(body: serde_json::Value) -> Decision {
+        serde_json::from_value::<DecisionResponse>(body)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn test_decision_response() {
+        assert!(decision(serde_json::json!({"result": true})).allow);
+        assert!(!decision(serde_json::json!({"result": false})).allow);
+        assert!(!decision(serde_json::json!({})).allow);
+
+        let project_id = Uuid::now_v7();
+        assert_eq!(
+            decision(serde_json::json!({"result": {"allow": true, "projects": [project_id]}})),
+            Decision {
+                allow: true,
+                projects: Some(vec![project_id]),
+                warehouses: None,
+            }
+        );
+    }
+
+    /// Module whose `authorize` returns `result`.
+    #[cfg(feature = "wasm-policies")]
+    fn wasm_state(result: &str) -> OpaAuthState {
+        let wat = format!(
+            r#"(module
+                 (memory (export "memory") 1)
+                 (data (i32.const 0) "{}")
+                 (func (export "alloc") (param i32) (result i32) i32.const 1024)
+                 (func (export "authorize") (param i32 i32) (result i64) i64.const {}))"#,
+            result.replace('"', "\\\""),
+            result.len()
+        );
+        OpaAuthState::wasm(WasmAuthorizer::new(wat).unwrap())
+    }
+
+    #[cfg(feature = "wasm-policies")]
+    #[tokio::test]
+    async fn test_wasm_decision() {
+        let metadata = RequestMetadata::new_random();
+        let project_id = ProjectIdent::from(Uuid::now_v7());
+
+        OpaAuthZHandler::check_create_warehouse(&metadata, &project_id, wasm_state("true"))
+            .await
+            .unwrap();
+        let err = OpaAuthZHandler::check_create_warehouse(
+            &metadata,
+            &project_id,
+            wasm_state(r#"{"allow":false}"#),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::FORBIDDEN.as_u16());
+    }
+
+    #[test]
+    fn test_input() {
+        let metadata = RequestMetadata::new_random();
+        let warehouse_id = Uuid::now_v7();
+        let namespace = NamespaceIdent::from_vec(vec!["a".to_string(), "b".to_string()]).unwrap();
+        let input = Input {
+            warehouse_id: Some(warehouse_id),
+            namespace: Some(&namespace),
+            name: Some("t"),
+            ..Input::new("drop_table", &metadata)
+        };
+        assert_eq!(
+            serde_json::to_value(DecisionRequest { input }).unwrap(),
+            serde_json::json!({
+                "input": {
+                    "action": "drop-table",
+                    "principal": null,
+                    "groups": [],
+                    "request-id": metadata.request_id,
+                    "warehouse-id": warehouse_id,
+                    "namespace": ["a", "b"],
+                    "name": "t",
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+
+use super::{dbutils::DBErrorHandler as _, CatalogState};
+use crate::api::Result;
+use crate::service::api_usage::{ApiUsage, UsageWindow};
+use crate::{ProjectIdent, WarehouseIdent};
+
+pub(crate) async fn record_api_usage(
+    usage: Vec<ApiUsage>,
+    catalog_state: CatalogState,
+) -> Result<()> {
+    let mut warehouse_ids = Vec::with_capacity(usage.len());
+    let mut window_starts = Vec::with_capacity(usage.len());
+    let mut request_counts = Vec::with_capacity(usage.len());
+    let mut commit_counts = Vec::with_capacity(usage.len());
+    for usage in usage {
+        warehouse_ids.push(usage.warehouse_id.into_uuid());
+        window_starts.push(usage.window_start);
+        request_counts.push(usage.request_count);
+        commit_counts.push(usage.commit_count);
+    }
+
+    // Usage of warehouses deleted in the meantime is discarded.
+    sqlx::query!(
+        r#"
+        INSERT INTO api_usage (warehouse_id, window_start, request_count, commit_count)
+        SELECT u.warehouse_id, u.window_start, u.request_count, u.commit_count
+        FROM UNNEST($1::uuid[], $2::timestamptz[], $3::bigint[], $4::bigint[])
+            AS u(warehouse_id, window_start, request_count, commit_count)
+        INNER JOIN warehouse w ON w.warehouse_id = u.warehouse_id
+        ON CONFLICT (warehouse_id, window_start)
+        DO UPDATE SET request_count = api_usage.request_count + EXCLUDED.request_count,
+                      commit_count = api_usage.commit_count + EXCLUDED.commit_count
+        "#,
+        &warehouse_ids,
+        &window_starts,
+        &request_counts,
+        &commit_counts
+    )
+    .execute(&catalog_state.write_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error recording API usage".into()))?;
+
+    Ok(())
+}
+
+pub(crate) async fn list_api_usage(
+    project_id: &ProjectIdent,
+    warehouse_id_filter: Option<&HashSet<WarehouseIdent>>,
+    window: UsageWindow,
+    from: chrono::DateTime<chrono::Utc>,
+    to: chrono::DateTime<chrono::Utc>,
+    catalog_state: CatalogState,
+) -> Result<Vec<ApiUsage>> {
+    let warehouse_ids: Option<Vec<uuid::Uuid>> =
+        warehouse_id_filter.map(|filter| filter.iter().map(WarehouseIdent::into_uuid).collect());
+
+    let usage = sqlx::query!(
+        r#"
+        SELECT
+            u.warehouse_id,
+            date_trunc($2, u.window_start, 'UTC') AS "window_start!",
+            sum(u.request_count)::bigint AS "request_count!",
+            sum(u.commit_count)::bigint AS "commit_count!"
+        FROM api_usage u
+        INNER JOIN warehouse w ON w.warehouse_id = u.warehouse_id
+        WHERE w.project_id = $1
+        AND ($3::uuid[] IS NULL OR u.warehouse_id = ANY($3))
+        AND u.window_start >= $4 AND u.window_start < $5
+        GROUP BY u.warehouse_id, date_trunc($2, u.window_start, 'UTC')
+        ORDER BY 2, 1
+        "#,
+        project_id.as_uuid(),
+        window.as_str(),
+        warehouse_ids.as_deref(),
+        from,
+        to
+    )
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching API usage".into()))?;
+
+    Ok(usage
+        .into_iter()
+        .map(|usage| ApiUsage {
+            warehouse_id: usage.warehouse_id.into(),
+            window_start: usage.window_start,
+            request_count: usage.request_count,
+            commit_count: usage.commit_count,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::warehouse::test::initialize_warehouse;
+    use super::*;
+    use chrono::TimeZone;
+
+    #[sqlx::test]
+    async fn test_api_usage(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let project_id = ProjectIdent::from(uuid::Uuid::now_v7());
+        let warehouse_id = initialize_warehouse(state.clone(), None, Some(&project_id)).await;
+
+        let hour = |h| chrono::Utc.with_ymd_and_hms(2026, 10, 16, h, 0, 0).unwrap();
+        let usage = |h, request_count, commit_count| ApiUsage {
+            warehouse_id: warehouse_id.clone(),
+            window_start: hour(h),
+            request_count,
+            commit_count,
+        };
+        record_api_usage(vec![usage(8, 10, 1), usage(9, 5, 0)], state.clone())
+            .await
+            .unwrap();
+        record_api_usage(vec![usage(8, 2, 2)], state.clone())
+            .await
+            .unwrap();
+
+        let hourly = list_api_usage(
+            &project_id,
+            None,
+            UsageWindow::Hour,
+            hour(0),
+            hour(23),
+            state.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(hourly, vec![usage(8, 12, 3), usage(9, 5, 0)]);
+
+        let daily = list_api_usage(
+            &project_id,
+            None,
+            UsageWindow::Day,
+            hour(0),
+            hour(23),
+            state.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(daily, vec![usage(0, 17, 3)]);
+
+        let filtered = list_api_usage(
+            &project_id,
+            Some(&HashSet::new()),
+            UsageWindow::Hour,
+            hour(0),
+            hour(23),
+            state.clone(),
+        )
+        .await
+        .unwrap();
+        assert!(filtered.is_empty());
+    }
+}
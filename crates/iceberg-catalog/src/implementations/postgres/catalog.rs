@@ -1,32 +1,54 @@
 use std::collections::{HashMap, HashSet};
 
+use http::StatusCode;
+
 use super::{
     namespace::{
         create_namespace, drop_namespace, get_namespace, list_namespaces, namespace_ident_to_id,
-        update_namespace_properties,
+        set_namespace_retention_policy, update_namespace_properties,
     },
+    ownership::{get_ownership, set_owner},
+    share::{create_share, delete_share, list_shares, resolve_share},
     table::{
-        commit_table_transaction, create_table, drop_table, get_table_metadata_by_id,
-        get_table_metadata_by_s3_location, list_tables, load_table, rename_table,
-        table_ident_to_id, table_idents_to_ids,
+        commit_table_transaction, create_table, discard_staged_table, drop_table,
+        get_table_metadata_by_id, get_table_metadata_by_s3_location, get_table_metadata_log,
+        list_expired_dropped_tables, list_retention_candidates, list_staged_tables,
+        list_table_commits, list_tables, load_table, load_tables, reclaim_dropped_table,
+        record_table_commits, rename_table, set_table_policy, table_exists, table_ident_to_id,
+        table_idents_to_ids,
     },
     warehouse::{
-        create_warehouse, delete_warehouse, get_warehouse, list_projects, list_warehouses,
-        rename_warehouse, set_warehouse_status, update_storage_profile,
+        clear_warehouse_previous_storage_secret, create_warehouse, delete_project,
+        delete_warehouse, get_engine_table_config, get_project_id_by_name, get_projects,
+        get_pyiceberg_compat, get_warehouse, get_warehouse_allowlist, get_warehouse_id_by_name,
+        get_warehouse_signer_uri, is_warehouse_read_only, list_projects, list_warehouses,
+        rename_project, rename_warehouse, rotate_warehouse_storage_secret, set_engine_table_config,
+        set_event_filter, set_project_metadata, set_warehouse_access_delegation,
+        set_warehouse_allow_external_locations, set_warehouse_allowlist,
+        set_warehouse_object_storage_layout, set_warehouse_pyiceberg_compat,
+        set_warehouse_read_only, set_warehouse_retention_policy, set_warehouse_signer_uri,
+        set_warehouse_status, set_warehouse_storage_validation,
+        set_warehouse_unauthorized_response, update_storage_profile,
     },
     CatalogState, PostgresTransaction,
 };
+use crate::api::{ErrorModel, ErrorType};
 use crate::service::{
+    auth::UnauthorizedResponse, page_token::PageQuery, storage_validation::StorageValidation,
     CommitTransactionRequest, CreateNamespaceRequest, CreateNamespaceResponse, CreateTableRequest,
-    GetWarehouseResponse, ListNamespacesQuery, ListNamespacesResponse, NamespaceIdent, Result,
-    TableIdent, UpdateNamespacePropertiesRequest, UpdateNamespacePropertiesResponse,
+    DroppedTable, EngineTableConfig, EventFilter, GetWarehouseResponse, ListNamespacesQuery,
+    ListNamespacesResponse, LoadedTable, MetadataLogEntry, NamespaceIdent, ObjectOwnership,
+    OwnedObject, Project, PyicebergCompat, ResolvedShare, Result, RetentionCandidate, Share,
+    SharedObject, SnapshotRetentionPolicy, StagedTable, TableCommit, TableIdent, TablePolicy,
+    UpdateNamespacePropertiesRequest, UpdateNamespacePropertiesResponse, WarehouseAllowlist,
     WarehouseStatus,
 };
 use crate::{
     service::{
-        storage::StorageProfile, Catalog, CommitTableResponseExt, CreateTableResponse,
-        GetNamespaceResponse, GetTableMetadataResponse, LoadTableResponse, NamespaceIdentUuid,
-        ProjectIdent, TableIdentUuid, Transaction, WarehouseIdent,
+        storage::{AccessDelegation, StorageProfile},
+        Catalog, CommitTableResponseExt, CreateTableResponse, GetNamespaceResponse,
+        GetTableMetadataResponse, LoadTableResponse, NamespaceIdentUuid, ProjectIdent,
+        TableIdentUuid, Transaction, WarehouseIdent,
     },
     SecretIdent,
 };
@@ -36,27 +58,39 @@ impl Catalog for super::Catalog {
     type Transaction = PostgresTransaction;
     type State = CatalogState;
 
+    fn primary_state(catalog_state: CatalogState) -> CatalogState {
+        catalog_state.primary()
+    }
+
     async fn create_warehouse<'a>(
         warehouse_name: String,
         project_id: ProjectIdent,
         storage_profile: StorageProfile,
         storage_secret_id: Option<SecretIdent>,
+        owner: Option<&str>,
         transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
     ) -> Result<WarehouseIdent> {
-        create_warehouse(
+        transaction.create_project(&project_id).await?;
+        let warehouse_id = create_warehouse(
             warehouse_name,
-            project_id,
+            project_id.clone(),
             storage_profile,
             storage_secret_id,
+            owner,
             transaction,
         )
-        .await
+        .await?;
+        transaction
+            .register_warehouse(&warehouse_id, &project_id)
+            .await?;
+        Ok(warehouse_id)
     }
 
     async fn get_warehouse<'a>(
         warehouse_id: &WarehouseIdent,
         transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
     ) -> Result<GetWarehouseResponse> {
+        transaction.route_to_warehouse(warehouse_id).await?;
         get_warehouse(warehouse_id, transaction).await
     }
 
@@ -65,6 +99,7 @@ impl Catalog for super::Catalog {
         namespace: &NamespaceIdent,
         transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
     ) -> Result<GetNamespaceResponse> {
+        transaction.route_to_warehouse(warehouse_id).await?;
         get_namespace(warehouse_id, namespace, transaction).await
     }
 
@@ -73,15 +108,18 @@ impl Catalog for super::Catalog {
         query: &ListNamespacesQuery,
         catalog_state: CatalogState,
     ) -> Result<ListNamespacesResponse> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
         list_namespaces(warehouse_id, query, catalog_state).await
     }
 
     async fn create_namespace<'a>(
         warehouse_id: &WarehouseIdent,
         request: CreateNamespaceRequest,
+        owner: Option<&str>,
         transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
     ) -> Result<CreateNamespaceResponse> {
-        create_namespace(warehouse_id, request, transaction).await
+        transaction.route_to_warehouse(warehouse_id).await?;
+        create_namespace(warehouse_id, request, owner, transaction).await
     }
 
     async fn namespace_ident_to_id(
@@ -89,6 +127,7 @@ impl Catalog for super::Catalog {
         namespace: &NamespaceIdent,
         catalog_state: CatalogState,
     ) -> Result<Option<NamespaceIdentUuid>> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
         namespace_ident_to_id(warehouse_id, namespace, catalog_state).await
     }
 
@@ -97,6 +136,7 @@ impl Catalog for super::Catalog {
         namespace: &NamespaceIdent,
         transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
     ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
         drop_namespace(warehouse_id, namespace, transaction).await
     }
 
@@ -106,6 +146,7 @@ impl Catalog for super::Catalog {
         request: UpdateNamespacePropertiesRequest,
         transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
     ) -> Result<UpdateNamespacePropertiesResponse> {
+        transaction.route_to_warehouse(warehouse_id).await?;
         update_namespace_properties(warehouse_id, namespace, request, transaction).await
     }
 
@@ -116,14 +157,17 @@ impl Catalog for super::Catalog {
         request: CreateTableRequest,
         // Metadata location may be none if stage-create is true
         metadata_location: Option<&String>,
+        owner: Option<&str>,
         transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
     ) -> Result<CreateTableResponse> {
+        // Routed to the project of the namespace by the lookups of the warehouse before
         create_table(
             namespace_id,
             table,
             table_id,
             request,
             metadata_location,
+            owner,
             transaction,
         )
         .await
@@ -133,9 +177,11 @@ impl Catalog for super::Catalog {
         warehouse_id: &WarehouseIdent,
         namespace: &NamespaceIdent,
         include_staged: bool,
+        page: &PageQuery,
         catalog_state: CatalogState,
-    ) -> Result<HashMap<TableIdentUuid, TableIdent>> {
-        list_tables(warehouse_id, namespace, include_staged, catalog_state).await
+    ) -> Result<Vec<(TableIdentUuid, TableIdent)>> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
+        list_tables(warehouse_id, namespace, include_staged, page, catalog_state).await
     }
 
     async fn load_table(
@@ -143,6 +189,7 @@ impl Catalog for super::Catalog {
         table: &TableIdent,
         catalog_state: CatalogState,
     ) -> Result<LoadTableResponse> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
         load_table(warehouse_id, table, catalog_state).await
     }
 
@@ -152,15 +199,63 @@ impl Catalog for super::Catalog {
         include_staged: bool,
         catalog_state: Self::State,
     ) -> Result<GetTableMetadataResponse> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
         get_table_metadata_by_id(warehouse_id, table, include_staged, catalog_state).await
     }
 
+    async fn get_table_metadata_log(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdentUuid,
+        catalog_state: Self::State,
+    ) -> Result<Vec<MetadataLogEntry>> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
+        get_table_metadata_log(warehouse_id, table, catalog_state).await
+    }
+
+    async fn list_table_commits(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdentUuid,
+        before: Option<chrono::DateTime<chrono::Utc>>,
+        limit: i64,
+        catalog_state: Self::State,
+    ) -> Result<Vec<TableCommit>> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
+        list_table_commits(warehouse_id, table, before, limit, catalog_state).await
+    }
+
+    async fn load_tables(
+        warehouse_id: &WarehouseIdent,
+        tables: HashSet<&TableIdent>,
+        catalog_state: Self::State,
+    ) -> Result<Vec<LoadedTable>> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
+        load_tables(warehouse_id, tables, &catalog_state.read_pool).await
+    }
+
+    async fn list_staged_tables(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<Vec<StagedTable>> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
+        list_staged_tables(warehouse_id, catalog_state).await
+    }
+
+    async fn discard_staged_table<'a>(
+        warehouse_id: &WarehouseIdent,
+        table_id: &TableIdentUuid,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        discard_staged_table(warehouse_id, table_id, transaction).await
+    }
+
     async fn get_table_metadata_by_s3_location(
         warehouse_id: &WarehouseIdent,
         location: &str,
         include_staged: bool,
         catalog_state: Self::State,
     ) -> Result<GetTableMetadataResponse> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
         get_table_metadata_by_s3_location(warehouse_id, location, include_staged, catalog_state)
             .await
     }
@@ -171,6 +266,7 @@ impl Catalog for super::Catalog {
         include_staged: bool,
         catalog_state: Self::State,
     ) -> Result<Option<TableIdentUuid>> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
         table_ident_to_id(
             warehouse_id,
             table,
@@ -180,6 +276,22 @@ impl Catalog for super::Catalog {
         .await
     }
 
+    async fn table_exists(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        include_staged: bool,
+        catalog_state: Self::State,
+    ) -> Result<bool> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
+        table_exists(
+            warehouse_id,
+            table,
+            include_staged,
+            &catalog_state.read_pool,
+        )
+        .await
+    }
+
     async fn rename_table<'a>(
         warehouse_id: &WarehouseIdent,
         source_id: &TableIdentUuid,
@@ -187,6 +299,7 @@ impl Catalog for super::Catalog {
         destination: &TableIdent,
         transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
     ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
         rename_table(warehouse_id, source_id, source, destination, transaction).await
     }
 
@@ -195,15 +308,41 @@ impl Catalog for super::Catalog {
         table_id: &TableIdentUuid,
         transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
     ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
         drop_table(warehouse_id, table_id, transaction).await
     }
 
+    async fn list_expired_dropped_tables(
+        dropped_before: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+        catalog_state: Self::State,
+    ) -> Result<Vec<DroppedTable>> {
+        let mut tables = vec![];
+        for catalog_state in catalog_state.project_states().await? {
+            tables.extend(list_expired_dropped_tables(dropped_before, limit, catalog_state).await?);
+        }
+        tables.sort_by_key(|t| t.dropped_at);
+        tables.truncate(usize::try_from(limit).unwrap_or(0));
+        Ok(tables)
+    }
+
+    async fn reclaim_dropped_table<'a>(
+        warehouse_id: &WarehouseIdent,
+        table_id: &TableIdentUuid,
+        storage_purged: bool,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        reclaim_dropped_table(warehouse_id, table_id, storage_purged, transaction).await
+    }
+
     async fn table_idents_to_ids(
         warehouse_id: &WarehouseIdent,
         tables: HashSet<&TableIdent>,
         include_staged: bool,
         catalog_state: Self::State,
     ) -> Result<HashMap<TableIdent, Option<TableIdentUuid>>> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
         table_idents_to_ids(
             warehouse_id,
             tables,
@@ -219,14 +358,64 @@ impl Catalog for super::Catalog {
         table_ids: &HashMap<TableIdent, TableIdentUuid>,
         transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
     ) -> Result<Vec<CommitTableResponseExt>> {
+        transaction.route_to_warehouse(warehouse_id).await?;
         commit_table_transaction(warehouse_id, request, table_ids, transaction).await
     }
 
+    async fn record_table_commits<'a>(
+        commits: &[TableCommit],
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        // Routed to the project of the tables by the commit before
+        record_table_commits(commits, transaction).await
+    }
+
     // ---------------- Management API ----------------
     async fn list_projects(catalog_state: Self::State) -> Result<HashSet<ProjectIdent>> {
         list_projects(catalog_state).await
     }
 
+    async fn get_projects(
+        project_ids: Option<&HashSet<ProjectIdent>>,
+        catalog_state: Self::State,
+    ) -> Result<Vec<Project>> {
+        get_projects(project_ids, catalog_state).await
+    }
+
+    async fn get_project_id_by_name(
+        project_name: &str,
+        catalog_state: Self::State,
+    ) -> Result<Option<ProjectIdent>> {
+        get_project_id_by_name(project_name, catalog_state).await
+    }
+
+    async fn rename_project<'a>(
+        project_id: &ProjectIdent,
+        new_name: &str,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        rename_project(project_id, new_name, transaction).await?;
+        transaction.create_project(project_id).await
+    }
+
+    async fn set_project_metadata<'a>(
+        project_id: &ProjectIdent,
+        metadata: &HashMap<String, String>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        set_project_metadata(project_id, metadata, transaction).await?;
+        transaction.create_project(project_id).await
+    }
+
+    async fn delete_project<'a>(
+        project_id: &ProjectIdent,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_project(project_id).await?;
+        delete_project(project_id, transaction).await?;
+        transaction.drop_project_schema(project_id).await
+    }
+
     async fn list_warehouses(
         project_id: &ProjectIdent,
         include_inactive: Option<Vec<WarehouseStatus>>,
@@ -237,7 +426,20 @@ impl Catalog for super::Catalog {
             project_id,
             include_inactive,
             warehouse_id_filter,
-            catalog_state,
+            catalog_state.for_project(project_id),
+        )
+        .await
+    }
+
+    async fn get_warehouse_id_by_name(
+        project_id: &ProjectIdent,
+        warehouse_name: &str,
+        catalog_state: Self::State,
+    ) -> Result<Option<WarehouseIdent>> {
+        get_warehouse_id_by_name(
+            project_id,
+            warehouse_name,
+            catalog_state.for_project(project_id),
         )
         .await
     }
@@ -246,7 +448,9 @@ impl Catalog for super::Catalog {
         warehouse_id: &WarehouseIdent,
         transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
     ) -> Result<()> {
-        delete_warehouse(warehouse_id, transaction).await
+        transaction.route_to_warehouse(warehouse_id).await?;
+        delete_warehouse(warehouse_id, transaction).await?;
+        transaction.unregister_warehouse(warehouse_id).await
     }
 
     async fn rename_warehouse<'a>(
@@ -254,6 +458,7 @@ impl Catalog for super::Catalog {
         new_name: &str,
         transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
     ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
         rename_warehouse(warehouse_id, new_name, transaction).await
     }
 
@@ -262,6 +467,7 @@ impl Catalog for super::Catalog {
         status: WarehouseStatus,
         transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
     ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
         set_warehouse_status(warehouse_id, status, transaction).await
     }
 
@@ -271,6 +477,7 @@ impl Catalog for super::Catalog {
         storage_secret_id: Option<SecretIdent>,
         transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
     ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
         update_storage_profile(
             warehouse_id,
             storage_profile,
@@ -279,4 +486,296 @@ impl Catalog for super::Catalog {
         )
         .await
     }
+
+    async fn get_warehouse_allowlist(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<Option<WarehouseAllowlist>> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
+        get_warehouse_allowlist(warehouse_id, catalog_state).await
+    }
+
+    async fn set_warehouse_allowlist<'a>(
+        warehouse_id: &WarehouseIdent,
+        allowlist: Option<WarehouseAllowlist>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        set_warehouse_allowlist(warehouse_id, allowlist, transaction).await
+    }
+
+    async fn is_warehouse_read_only(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<bool> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
+        is_warehouse_read_only(warehouse_id, catalog_state).await
+    }
+
+    async fn set_warehouse_read_only<'a>(
+        warehouse_id: &WarehouseIdent,
+        read_only: bool,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        set_warehouse_read_only(warehouse_id, read_only, transaction).await
+    }
+
+    async fn get_warehouse_signer_uri(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<Option<String>> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
+        get_warehouse_signer_uri(warehouse_id, catalog_state).await
+    }
+
+    async fn set_warehouse_signer_uri<'a>(
+        warehouse_id: &WarehouseIdent,
+        signer_uri: Option<&url::Url>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        set_warehouse_signer_uri(warehouse_id, signer_uri, transaction).await
+    }
+
+    async fn set_warehouse_unauthorized_response<'a>(
+        warehouse_id: &WarehouseIdent,
+        unauthorized_response: Option<UnauthorizedResponse>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        set_warehouse_unauthorized_response(warehouse_id, unauthorized_response, transaction).await
+    }
+
+    async fn set_warehouse_access_delegation<'a>(
+        warehouse_id: &WarehouseIdent,
+        access_delegation: Option<AccessDelegation>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        set_warehouse_access_delegation(warehouse_id, access_delegation, transaction).await
+    }
+
+    async fn set_warehouse_allow_external_locations<'a>(
+        warehouse_id: &WarehouseIdent,
+        allow_external_locations: bool,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        set_warehouse_allow_external_locations(warehouse_id, allow_external_locations, transaction)
+            .await
+    }
+
+    async fn set_warehouse_object_storage_layout<'a>(
+        warehouse_id: &WarehouseIdent,
+        object_storage_layout: bool,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        set_warehouse_object_storage_layout(warehouse_id, object_storage_layout, transaction).await
+    }
+
+    async fn rotate_warehouse_storage_secret<'a>(
+        warehouse_id: &WarehouseIdent,
+        current_secret_id: Option<&SecretIdent>,
+        new_secret_id: &SecretIdent,
+        rotated_at: chrono::DateTime<chrono::Utc>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        rotate_warehouse_storage_secret(
+            warehouse_id,
+            current_secret_id,
+            new_secret_id,
+            rotated_at,
+            transaction,
+        )
+        .await
+    }
+
+    async fn clear_warehouse_previous_storage_secret(
+        warehouse_id: &WarehouseIdent,
+        previous_secret_id: &SecretIdent,
+        catalog_state: Self::State,
+    ) -> Result<bool> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
+        clear_warehouse_previous_storage_secret(warehouse_id, previous_secret_id, catalog_state)
+            .await
+    }
+
+    async fn set_warehouse_storage_validation(
+        warehouse_id: &WarehouseIdent,
+        validation: &StorageValidation,
+        catalog_state: Self::State,
+    ) -> Result<()> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
+        set_warehouse_storage_validation(warehouse_id, validation, catalog_state).await
+    }
+
+    async fn set_warehouse_retention_policy<'a>(
+        warehouse_id: &WarehouseIdent,
+        policy: Option<SnapshotRetentionPolicy>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        set_warehouse_retention_policy(warehouse_id, policy, transaction).await
+    }
+
+    async fn get_engine_table_config(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<EngineTableConfig> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
+        get_engine_table_config(warehouse_id, catalog_state).await
+    }
+
+    async fn set_engine_table_config<'a>(
+        warehouse_id: &WarehouseIdent,
+        config: EngineTableConfig,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        set_engine_table_config(warehouse_id, config, transaction).await
+    }
+
+    async fn set_event_filter<'a>(
+        warehouse_id: &WarehouseIdent,
+        filter: EventFilter,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        set_event_filter(warehouse_id, filter, transaction).await
+    }
+
+    async fn get_pyiceberg_compat(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<Option<PyicebergCompat>> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
+        get_pyiceberg_compat(warehouse_id, catalog_state).await
+    }
+
+    async fn set_warehouse_pyiceberg_compat<'a>(
+        warehouse_id: &WarehouseIdent,
+        enabled: bool,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        set_warehouse_pyiceberg_compat(warehouse_id, enabled, transaction).await
+    }
+
+    async fn create_share<'a>(
+        warehouse_id: &WarehouseIdent,
+        object: &SharedObject,
+        consumer_project_id: &ProjectIdent,
+        mount_name: &str,
+        created_by: Option<&str>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<uuid::Uuid> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        if transaction.is_schema_per_project() {
+            // The share would be resolved in the schema of the consumer project
+            let provider_project_id = get_warehouse(warehouse_id, transaction).await?.project_id;
+            if &provider_project_id != consumer_project_id {
+                return Err(ErrorModel::builder()
+                    .code(StatusCode::BAD_REQUEST.into())
+                    .message(
+                        "Shares with other projects are not supported with a schema per project"
+                            .to_string(),
+                    )
+                    .r#type("CrossProjectShareNotSupported".to_string())
+                    .error_type(ErrorType::BadRequest)
+                    .build()
+                    .into());
+            }
+        }
+        create_share(
+            warehouse_id,
+            object,
+            consumer_project_id,
+            mount_name,
+            created_by,
+            transaction,
+        )
+        .await
+    }
+
+    async fn list_shares(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<Vec<Share>> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
+        list_shares(warehouse_id, catalog_state).await
+    }
+
+    async fn delete_share<'a>(
+        warehouse_id: &WarehouseIdent,
+        share_id: &uuid::Uuid,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        delete_share(warehouse_id, share_id, transaction).await
+    }
+
+    async fn resolve_share(
+        warehouse_id: &WarehouseIdent,
+        mount_name: &str,
+        catalog_state: Self::State,
+    ) -> Result<Option<ResolvedShare>> {
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
+        resolve_share(warehouse_id, mount_name, catalog_state).await
+    }
+
+    async fn set_table_policy<'a>(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        policy: Option<TablePolicy>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        set_table_policy(warehouse_id, table, policy, transaction).await
+    }
+
+    async fn set_namespace_retention_policy<'a>(
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        policy: Option<SnapshotRetentionPolicy>,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        set_namespace_retention_policy(warehouse_id, namespace, policy, transaction).await
+    }
+
+    async fn list_retention_candidates(
+        after: Option<TableIdentUuid>,
+        limit: i64,
+        catalog_state: Self::State,
+    ) -> Result<Vec<RetentionCandidate>> {
+        let mut candidates = vec![];
+        for catalog_state in catalog_state.project_states().await? {
+            candidates.extend(list_retention_candidates(after, limit, catalog_state).await?);
+        }
+        candidates.sort_by_key(|c| c.table_id);
+        candidates.truncate(usize::try_from(limit).unwrap_or(0));
+        Ok(candidates)
+    }
+
+    async fn get_ownership<'a>(
+        warehouse_id: &WarehouseIdent,
+        object: &OwnedObject,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<ObjectOwnership> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        get_ownership(warehouse_id, object, transaction).await
+    }
+
+    async fn set_owner<'a>(
+        warehouse_id: &WarehouseIdent,
+        object: &OwnedObject,
+        new_owner: &str,
+        transaction: <Self::Transaction as Transaction<CatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        transaction.route_to_warehouse(warehouse_id).await?;
+        set_owner(warehouse_id, object, new_owner, transaction).await
+    }
 }
@@ -0,0 +1,92 @@
+use super::{dbutils::DBErrorHandler as _, CatalogState};
+use crate::api::Result;
+use crate::service::client_usage::{ClientInfo, ClientUsage};
+use crate::WarehouseIdent;
+
+pub(crate) async fn record_client_usage(
+    warehouse_id: &WarehouseIdent,
+    client: &ClientInfo,
+    catalog_state: CatalogState,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO client_usage (warehouse_id, engine, engine_version, session_count)
+        VALUES ($1, $2, $3, 1)
+        ON CONFLICT (warehouse_id, engine, engine_version)
+        DO UPDATE SET session_count = client_usage.session_count + 1, last_seen = now()
+        "#,
+        warehouse_id.as_uuid(),
+        client.engine,
+        client.version.as_deref().unwrap_or_default()
+    )
+    .execute(&catalog_state.write_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error recording client usage".into()))?;
+
+    Ok(())
+}
+
+pub(crate) async fn list_client_usage(
+    warehouse_id: &WarehouseIdent,
+    catalog_state: CatalogState,
+) -> Result<Vec<ClientUsage>> {
+    let usage = sqlx::query!(
+        r#"
+        SELECT engine, engine_version, session_count, first_seen, last_seen
+        FROM client_usage
+        WHERE warehouse_id = $1
+        ORDER BY engine, engine_version
+        "#,
+        warehouse_id.as_uuid()
+    )
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching client usage".into()))?;
+
+    Ok(usage
+        .into_iter()
+        .map(|usage| ClientUsage {
+            engine: usage.engine,
+            version: Some(usage.engine_version).filter(|version| !version.is_empty()),
+            session_count: usage.session_count,
+            first_seen: usage.first_seen,
+            last_seen: usage.last_seen,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::warehouse::test::initialize_warehouse;
+    use super::*;
+
+    #[sqlx::test]
+    async fn test_client_usage(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+
+        let pyiceberg = ClientInfo::from_headers(Some("PyIceberg/0.7.1"), None);
+        let unknown = ClientInfo::from_headers(None, None);
+        record_client_usage(&warehouse_id, &pyiceberg, state.clone())
+            .await
+            .unwrap();
+        record_client_usage(&warehouse_id, &unknown, state.clone())
+            .await
+            .unwrap();
+        record_client_usage(&warehouse_id, &pyiceberg, state.clone())
+            .await
+            .unwrap();
+
+        let usage = list_client_usage(&warehouse_id, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].engine, "pyiceberg");
+        assert_eq!(usage[0].version.as_deref(), Some("0.7.1"));
+        assert_eq!(usage[0].session_count, 2);
+        assert!(usage[0].first_seen <= usage[0].last_seen);
+        assert_eq!(usage[1].engine, ClientInfo::UNKNOWN_ENGINE);
+        assert_eq!(usage[1].version, None);
+        assert_eq!(usage[1].session_count, 1);
+    }
+}
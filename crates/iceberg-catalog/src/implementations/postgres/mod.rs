@@ -1,12 +1,28 @@
+pub(crate) mod api_usage;
 mod catalog;
-pub(crate) mod dbutils;
+pub(crate) mod client_usage;
+pub(crate) use super::dbutils;
 pub(crate) mod namespace;
+pub(crate) mod ownership;
+pub(crate) mod project_schema;
+#[cfg(feature = "rds-iam")]
+mod rds_iam;
+pub(crate) mod role_assignment;
+pub(crate) mod share;
+pub(crate) mod storage_usage;
+mod stores;
 pub(crate) mod table;
+pub(crate) mod token_revocation;
 pub(crate) mod warehouse;
+pub(crate) mod webhook;
 
+#[cfg(feature = "postgres-secrets")]
 pub(crate) mod secrets;
+use std::str::FromStr;
+
 use crate::CONFIG;
 
+#[cfg(feature = "postgres-secrets")]
 pub use secrets::Server as SecretsStore;
 
 use crate::api::Result;
@@ -18,9 +34,10 @@ use self::dbutils::DBErrorHandler;
 pub async fn get_reader_pool() -> anyhow::Result<sqlx::PgPool> {
     let pool = sqlx::postgres::PgPoolOptions::new()
         .max_connections(CONFIG.pg_read_pool_connections)
-        .connect(&CONFIG.pg_database_url_read)
+        .connect_with(connect_options(&CONFIG.pg_database_url_read)?)
         .await
         .map_err(|e| anyhow::anyhow!(e).context("Error creating read pool."))?;
+    refresh_iam_token(&pool, &CONFIG.pg_database_url_read);
     Ok(pool)
 }
 
@@ -29,22 +46,121 @@ pub async fn get_reader_pool() -> anyhow::Result<sqlx::PgPool> {
 pub async fn get_writer_pool() -> anyhow::Result<sqlx::PgPool> {
     let pool = sqlx::postgres::PgPoolOptions::new()
         .max_connections(CONFIG.pg_write_pool_connections)
-        .connect(&CONFIG.pg_database_url_write)
+        .connect_with(connect_options(&CONFIG.pg_database_url_write)?)
         .await
         .map_err(|e| anyhow::anyhow!(e).context("Error creating write pool."))?;
+    refresh_iam_token(&pool, &CONFIG.pg_database_url_write);
     Ok(pool)
 }
 
+/// Options for `url`, with the password replaced by `pg_password` or an RDS IAM token
+/// and the TLS settings replaced by `pg_ssl_mode` and `pg_ssl_root_cert` if set.
+/// The statement cache is disabled in `pg_transaction_pooling` mode.
+fn connect_options(url: &str) -> anyhow::Result<sqlx::postgres::PgConnectOptions> {
+    let mut options = sqlx::postgres::PgConnectOptions::from_str(url)
+        .map_err(|e| anyhow::anyhow!(e).context("Invalid database URL."))?;
+    if let Some(password) = &CONFIG.pg_password {
+        options = options.password(password);
+    }
+    if let Some(ssl_mode) = &CONFIG.pg_ssl_mode {
+        options = options.ssl_mode(
+            sqlx::postgres::PgSslMode::from_str(ssl_mode)
+                .map_err(|e| anyhow::anyhow!(e).context("Invalid pg_ssl_mode."))?,
+        );
+    }
+    if let Some(root_cert) = &CONFIG.pg_ssl_root_cert {
+        options = options.ssl_root_cert(root_cert);
+    }
+    if CONFIG.pg_iam_auth {
+        options = iam_auth(options)?;
+    }
+    if CONFIG.pg_transaction_pooling {
+        // Statements are prepared unnamed on every execution instead
+        options = options.statement_cache_capacity(0);
+    }
+    if CONFIG.pg_schema.is_some() {
+        options = options.options([("search_path", search_path(&[]))]);
+    }
+    Ok(options)
+}
+
+/// Search path with `schemas` and `pg_schema` in front of `public`. `public` is kept,
+/// as it usually holds the extensions used by the catalog tables.
+fn search_path(schemas: &[&str]) -> String {
+    schemas
+        .iter()
+        .copied()
+        .chain(CONFIG.pg_schema.as_deref())
+        .chain(["public"])
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(feature = "rds-iam")]
+fn iam_auth(
+    options: sqlx::postgres::PgConnectOptions,
+) -> anyhow::Result<sqlx::postgres::PgConnectOptions> {
+    use sqlx::postgres::PgSslMode;
+
+    // RDS rejects IAM authentication over unencrypted connections
+    let options = match options.get_ssl_mode() {
+        PgSslMode::Disable | PgSslMode::Allow | PgSslMode::Prefer => {
+            options.ssl_mode(PgSslMode::Require)
+        }
+        PgSslMode::Require | PgSslMode::VerifyCa | PgSslMode::VerifyFull => options,
+    };
+    let token = rds_iam::generate_token(&options)?;
+    Ok(options.password(&token))
+}
+
+#[cfg(not(feature = "rds-iam"))]
+fn iam_auth(
+    _options: sqlx::postgres::PgConnectOptions,
+) -> anyhow::Result<sqlx::postgres::PgConnectOptions> {
+    Err(anyhow::anyhow!(
+        "pg_iam_auth is enabled, but the server was built without the `rds-iam` feature."
+    ))
+}
+
+/// Keep replacing the RDS IAM token of `pool` before it expires.
+#[cfg_attr(not(feature = "rds-iam"), allow(unused_variables))]
+fn refresh_iam_token(pool: &sqlx::PgPool, url: &str) {
+    #[cfg(feature = "rds-iam")]
+    if CONFIG.pg_iam_auth {
+        rds_iam::spawn_refresh(pool.clone(), url.to_string());
+    }
+}
+
 /// # Errors
 /// Returns an error if the migration fails.
 pub async fn migrate(pool: &sqlx::PgPool) -> anyhow::Result<()> {
-    sqlx::migrate!()
+    if let Some(schema) = &CONFIG.pg_schema {
+        // Validated to be a plain identifier
+        sqlx::query(&format!(r#"CREATE SCHEMA IF NOT EXISTS "{schema}""#))
+            .execute(pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e).context(format!("Error creating schema {schema}.")))?;
+    }
+    migrator()
         .run(pool)
         .await
         .map_err(|e| anyhow::anyhow!(e).context("Error migrating database."))?;
+    if CONFIG.pg_schema_per_project {
+        project_schema::migrate_project_schemas(pool).await?;
+    }
     Ok(())
 }
 
+fn migrator() -> sqlx::migrate::Migrator {
+    let mut migrator = sqlx::migrate!();
+    if CONFIG.pg_transaction_pooling {
+        // The advisory lock would be released with the transaction of the pooler.
+        // Migrations must not run concurrently.
+        migrator.set_locking(false);
+    }
+    migrator
+}
+
 #[derive(Debug, Clone)]
 pub struct Catalog {}
 
@@ -52,11 +168,28 @@ pub struct Catalog {}
 #[allow(clippy::module_name_repetitions)]
 pub struct PostgresTransaction {
     transaction: sqlx::Transaction<'static, sqlx::Postgres>,
+    /// Begun on a state with [`CatalogState::with_schema_per_project`],
+    /// queries are routed with [`PostgresTransaction::route_to_warehouse`].
+    schema_per_project: bool,
+}
+
+impl std::ops::Deref for PostgresTransaction {
+    type Target = sqlx::Transaction<'static, sqlx::Postgres>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.transaction
+    }
+}
+
+impl std::ops::DerefMut for PostgresTransaction {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.transaction
+    }
 }
 
 #[async_trait::async_trait]
 impl crate::service::Transaction<CatalogState> for PostgresTransaction {
-    type Transaction<'a> = &'a mut sqlx::Transaction<'static, sqlx::Postgres>;
+    type Transaction<'a> = &'a mut PostgresTransaction;
 
     async fn begin_write(db_state: CatalogState) -> Result<Self> {
         let transaction = db_state
@@ -65,7 +198,10 @@ impl crate::service::Transaction<CatalogState> for PostgresTransaction {
             .await
             .map_err(|e| e.into_error_model("Error starting transaction".to_string()))?;
 
-        Ok(Self { transaction })
+        Ok(Self {
+            transaction,
+            schema_per_project: db_state.is_schema_per_project(),
+        })
     }
 
     async fn begin_read(db_state: CatalogState) -> Result<Self> {
@@ -75,7 +211,10 @@ impl crate::service::Transaction<CatalogState> for PostgresTransaction {
             .await
             .map_err(|e| e.into_error_model("Error starting transaction".to_string()))?;
 
-        Ok(Self { transaction })
+        Ok(Self {
+            transaction,
+            schema_per_project: db_state.is_schema_per_project(),
+        })
     }
 
     async fn commit(self) -> Result<()> {
@@ -95,7 +234,7 @@ impl crate::service::Transaction<CatalogState> for PostgresTransaction {
     }
 
     fn transaction(&mut self) -> Self::Transaction<'_> {
-        &mut self.transaction
+        self
     }
 }
 
@@ -106,13 +245,36 @@ pub struct CatalogState {
     pub read_pool: sqlx::PgPool,
     #[cfg(feature = "sqlx-postgres")]
     pub write_pool: sqlx::PgPool,
+    /// Set if each project has its own schema, see [`project_schema`].
+    projects: Option<project_schema::ProjectSchemas>,
+}
+
+impl CatalogState {
+    /// State of a catalog whose projects share the catalog tables.
+    #[must_use]
+    pub fn new(read_pool: sqlx::PgPool, write_pool: sqlx::PgPool) -> Self {
+        Self {
+            read_pool,
+            write_pool,
+            projects: None,
+        }
+    }
+
+    /// State reading from the write pool, see [`crate::service::Catalog::primary_state`].
+    #[must_use]
+    pub(crate) fn primary(self) -> Self {
+        Self {
+            read_pool: self.write_pool.clone(),
+            write_pool: self.write_pool,
+            projects: self.projects.map(project_schema::ProjectSchemas::primary),
+        }
+    }
 }
 
+#[cfg(feature = "postgres-secrets")]
 #[derive(Clone, Debug)]
 #[allow(clippy::module_name_repetitions)]
 pub struct SecretsState {
-    #[cfg(feature = "sqlx-postgres")]
     pub read_pool: sqlx::PgPool,
-    #[cfg(feature = "sqlx-postgres")]
     pub write_pool: sqlx::PgPool,
 }
@@ -1,6 +1,6 @@
 use crate::service::{
-    CreateNamespaceRequest, CreateNamespaceResponse, ErrorModel, GetNamespaceResponse,
-    ListNamespacesQuery, ListNamespacesResponse, NamespaceIdent, Result,
+    CreateNamespaceRequest, CreateNamespaceResponse, ErrorModel, ErrorType, GetNamespaceResponse,
+    ListNamespacesQuery, ListNamespacesResponse, NamespaceIdent, Result, SnapshotRetentionPolicy,
     UpdateNamespacePropertiesRequest, UpdateNamespacePropertiesResponse,
 };
 use http::StatusCode;
@@ -37,6 +37,7 @@ pub(crate) async fn get_namespace(
             .code(StatusCode::NOT_FOUND.into())
             .message(format!("Namespace not found: {:?}", namespace.as_ref()))
             .r#type("NamespaceNotFound".to_string())
+            .error_type(ErrorType::NoSuchNamespace)
             .build(),
         _ => e.into_error_model("Error fetching namespace".to_string()),
     })?;
@@ -137,6 +138,7 @@ pub(crate) async fn list_namespaces(
 pub(crate) async fn create_namespace(
     warehouse_id: &WarehouseIdent,
     request: CreateNamespaceRequest,
+    owner: Option<&str>,
     transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
 ) -> Result<CreateNamespaceResponse> {
     let CreateNamespaceRequest {
@@ -146,9 +148,9 @@ pub(crate) async fn create_namespace(
 
     let _namespace_id = sqlx::query_scalar!(
         r#"
-        INSERT INTO namespace (warehouse_id, namespace_name, namespace_properties)
+        INSERT INTO namespace (warehouse_id, namespace_name, namespace_properties, created_by, "owner")
         (
-            SELECT $1, $2, $3
+            SELECT $1, $2, $3, $4, $4
             WHERE EXISTS (
                 SELECT 1
                 FROM warehouse
@@ -166,7 +168,8 @@ pub(crate) async fn create_namespace(
                 .r#type("NamespacePropertiesSerializationError".to_string())
                 .stack(Some(vec![e.to_string()]))
                 .build()
-        })?
+        })?,
+        owner
     )
     .fetch_one(&mut **transaction)
     .await
@@ -177,6 +180,7 @@ pub(crate) async fn create_namespace(
                     .code(StatusCode::CONFLICT.into())
                     .message("Namespace already exists".to_string())
                     .r#type("NamespaceAlreadyExists".to_string())
+                    .error_type(ErrorType::AlreadyExists)
                     .build()
             } else if db_error.is_foreign_key_violation() {
                 ErrorModel::builder()
@@ -269,6 +273,7 @@ pub(crate) async fn drop_namespace(
             .code(StatusCode::NOT_FOUND.into())
             .message(format!("Namespace not found: {:?}", namespace.as_ref()))
             .r#type("NamespaceNotFound".to_string())
+            .error_type(ErrorType::NoSuchNamespace)
             .build(),
         sqlx::Error::Database(db_error) => {
             if db_error.is_foreign_key_violation() {
@@ -276,6 +281,7 @@ pub(crate) async fn drop_namespace(
                     .code(StatusCode::CONFLICT.into())
                     .message("Namespace is not empty".to_string())
                     .r#type("NamespaceNotEmpty".to_string())
+                    .error_type(ErrorType::NamespaceNotEmpty)
                     .build()
             } else {
                 e.into_error_model("Error deleting namespace".to_string())
@@ -289,6 +295,56 @@ pub(crate) async fn drop_namespace(
             .code(StatusCode::NOT_FOUND.into())
             .message(format!("Namespace not found: {:?}", namespace.as_ref()))
             .r#type("NamespaceNotFound".to_string())
+            .error_type(ErrorType::NoSuchNamespace)
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn set_namespace_retention_policy(
+    warehouse_id: &WarehouseIdent,
+    namespace: &NamespaceIdent,
+    policy: Option<SnapshotRetentionPolicy>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let policy_ser = policy
+        .map(|p| {
+            serde_json::to_value(p).map_err(|e| {
+                ErrorModel::builder()
+                    .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                    .message("Error serializing retention policy".to_string())
+                    .r#type("RetentionPolicySerializationError".to_string())
+                    .stack(Some(vec![e.to_string()]))
+                    .build()
+            })
+        })
+        .transpose()?;
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE namespace
+        SET retention_policy = $1
+        WHERE warehouse_id = $2 AND namespace_name = $3
+        AND warehouse_id IN (
+            SELECT warehouse_id FROM warehouse WHERE status = 'active'
+        )
+        "#,
+        policy_ser,
+        warehouse_id.as_uuid(),
+        &**namespace
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error setting namespace retention policy".into()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Namespace not found".to_string())
+            .r#type("NoSuchNamespaceError".to_string())
+            .error_type(ErrorType::NoSuchNamespace)
             .build()
             .into());
     }
@@ -396,6 +452,7 @@ pub(crate) mod tests {
                 namespace: namespace.clone(),
                 properties: properties.clone(),
             },
+            None,
             transaction.transaction(),
         )
         .await
@@ -408,10 +465,7 @@ pub(crate) mod tests {
 
     #[sqlx::test]
     async fn test_namespace_lifecycle(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
+        let state = CatalogState::new(pool.clone(), pool.clone());
 
         let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
 
@@ -503,10 +557,7 @@ pub(crate) mod tests {
 
     #[sqlx::test]
     async fn test_cannot_drop_nonempty_namespace(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
+        let state = CatalogState::new(pool.clone(), pool.clone());
 
         let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
         let staged = false;
@@ -524,10 +575,7 @@ pub(crate) mod tests {
 
     #[sqlx::test]
     async fn test_case_insensitive_but_preserve_case(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
+        let state = CatalogState::new(pool.clone(), pool.clone());
 
         let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
         let namespace_1 = NamespaceIdent::from_vec(vec!["Test".to_string()]).unwrap();
@@ -543,6 +591,7 @@ pub(crate) mod tests {
                 namespace: namespace_1.clone(),
                 properties: None,
             },
+            None,
             transaction.transaction(),
         )
         .await
@@ -562,6 +611,7 @@ pub(crate) mod tests {
                 namespace: namespace_2.clone(),
                 properties: None,
             },
+            None,
             transaction.transaction(),
         )
         .await
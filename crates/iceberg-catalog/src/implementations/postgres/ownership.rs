@@ -0,0 +1,228 @@
+use super::dbutils::DBErrorHandler as _;
+use crate::api::{ErrorModel, ErrorType, Result};
+use crate::service::{ObjectOwnership, OwnedObject, TableIdent};
+use crate::WarehouseIdent;
+use http::StatusCode;
+
+pub(crate) async fn get_ownership(
+    warehouse_id: &WarehouseIdent,
+    object: &OwnedObject,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<ObjectOwnership> {
+    let ownership = match object {
+        OwnedObject::Warehouse => sqlx::query_as!(
+            ObjectOwnership,
+            r#"
+            SELECT "owner", created_by
+            FROM warehouse
+            WHERE warehouse_id = $1
+            "#,
+            warehouse_id.as_uuid()
+        )
+        .fetch_one(&mut **transaction)
+        .await
+        .map_err(|e| not_found_or_internal(e, object))?,
+        OwnedObject::Namespace(namespace) => sqlx::query_as!(
+            ObjectOwnership,
+            r#"
+            SELECT n."owner", n.created_by
+            FROM namespace n
+            INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+            WHERE n.warehouse_id = $1 AND n.namespace_name = $2
+            AND w.status = 'active'
+            "#,
+            warehouse_id.as_uuid(),
+            &**namespace
+        )
+        .fetch_one(&mut **transaction)
+        .await
+        .map_err(|e| not_found_or_internal(e, object))?,
+        OwnedObject::Table(TableIdent { namespace, name }) => sqlx::query_as!(
+            ObjectOwnership,
+            r#"
+            SELECT t."owner", t.created_by
+            FROM "table" t
+            INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+            INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+            WHERE n.warehouse_id = $1 AND n.namespace_name = $2 AND t.table_name = $3
+            AND w.status = 'active'
+            "#,
+            warehouse_id.as_uuid(),
+            &**namespace,
+            &**name
+        )
+        .fetch_one(&mut **transaction)
+        .await
+        .map_err(|e| not_found_or_internal(e, object))?,
+    };
+
+    Ok(ownership)
+}
+
+pub(crate) async fn set_owner(
+    warehouse_id: &WarehouseIdent,
+    object: &OwnedObject,
+    new_owner: &str,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    if new_owner.is_empty() {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("New owner cannot be empty".to_string())
+            .r#type("EmptyOwner".to_string())
+            .build()
+            .into());
+    }
+
+    let _ = match object {
+        OwnedObject::Warehouse => sqlx::query_scalar!(
+            r#"
+            UPDATE warehouse
+            SET "owner" = $1
+            WHERE warehouse_id = $2
+            RETURNING warehouse_id
+            "#,
+            new_owner,
+            warehouse_id.as_uuid()
+        )
+        .fetch_one(&mut **transaction)
+        .await
+        .map_err(|e| not_found_or_internal(e, object))?,
+        OwnedObject::Namespace(namespace) => sqlx::query_scalar!(
+            r#"
+            UPDATE namespace
+            SET "owner" = $1
+            WHERE warehouse_id = $2 AND namespace_name = $3
+            AND warehouse_id IN (
+                SELECT warehouse_id FROM warehouse WHERE status = 'active'
+            )
+            RETURNING namespace_id
+            "#,
+            new_owner,
+            warehouse_id.as_uuid(),
+            &**namespace
+        )
+        .fetch_one(&mut **transaction)
+        .await
+        .map_err(|e| not_found_or_internal(e, object))?,
+        OwnedObject::Table(TableIdent { namespace, name }) => sqlx::query_scalar!(
+            r#"
+            UPDATE "table"
+            SET "owner" = $1
+            WHERE table_name = $4
+            AND namespace_id = (
+                SELECT n.namespace_id
+                FROM namespace n
+                INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+                WHERE n.warehouse_id = $2 AND n.namespace_name = $3
+                AND w.status = 'active'
+            )
+            RETURNING table_id
+            "#,
+            new_owner,
+            warehouse_id.as_uuid(),
+            &**namespace,
+            &**name
+        )
+        .fetch_one(&mut **transaction)
+        .await
+        .map_err(|e| not_found_or_internal(e, object))?,
+    };
+
+    Ok(())
+}
+
+fn not_found_or_internal(e: sqlx::Error, object: &OwnedObject) -> ErrorModel {
+    match e {
+        sqlx::Error::RowNotFound => {
+            let (message, r#type, error_type) = match object {
+                OwnedObject::Warehouse => (
+                    "Warehouse not found",
+                    "WarehouseNotFound",
+                    ErrorType::NotFound,
+                ),
+                OwnedObject::Namespace(_) => (
+                    "Namespace not found",
+                    "NamespaceNotFound",
+                    ErrorType::NoSuchNamespace,
+                ),
+                OwnedObject::Table(_) => (
+                    "Table not found",
+                    "NoSuchTableError",
+                    ErrorType::NoSuchTable,
+                ),
+            };
+            ErrorModel::builder()
+                .code(StatusCode::NOT_FOUND.into())
+                .message(message.to_string())
+                .r#type(r#type.to_string())
+                .error_type(error_type)
+                .build()
+        }
+        _ => e.into_error_model("Error accessing ownership information".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::namespace::tests::initialize_namespace;
+    use super::super::table::tests::initialize_table;
+    use super::super::warehouse::test::initialize_warehouse;
+    use super::super::CatalogState;
+    use super::*;
+    use crate::service::NamespaceIdent;
+
+    #[sqlx::test]
+    async fn test_transfer_ownership(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let namespace = NamespaceIdent::from_vec(vec!["my_namespace".to_string()]).unwrap();
+        initialize_namespace(state.clone(), &warehouse_id, &namespace, None).await;
+        let table = initialize_table(&warehouse_id, state.clone(), false).await;
+
+        for object in [
+            OwnedObject::Warehouse,
+            OwnedObject::Namespace(namespace.clone()),
+            OwnedObject::Table(table.table_ident.clone()),
+        ] {
+            let mut transaction = pool.begin().await.unwrap();
+            let ownership = get_ownership(&warehouse_id, &object, &mut transaction)
+                .await
+                .unwrap();
+            assert_eq!(ownership.owner, None);
+            assert_eq!(ownership.created_by, None);
+
+            set_owner(&warehouse_id, &object, "new-owner", &mut transaction)
+                .await
+                .unwrap();
+            transaction.commit().await.unwrap();
+
+            let mut transaction = pool.begin().await.unwrap();
+            let ownership = get_ownership(&warehouse_id, &object, &mut transaction)
+                .await
+                .unwrap();
+            assert_eq!(ownership.owner.as_deref(), Some("new-owner"));
+            assert_eq!(ownership.created_by, None);
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_transfer_ownership_of_missing_table(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let namespace = NamespaceIdent::from_vec(vec!["my_namespace".to_string()]).unwrap();
+        initialize_namespace(state.clone(), &warehouse_id, &namespace, None).await;
+
+        let object = OwnedObject::Table(TableIdent {
+            namespace,
+            name: "missing".to_string(),
+        });
+        let mut transaction = pool.begin().await.unwrap();
+        let err = set_owner(&warehouse_id, &object, "new-owner", &mut transaction)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+    }
+}
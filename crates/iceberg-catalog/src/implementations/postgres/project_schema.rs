@@ -0,0 +1,516 @@
+//! Catalog tables of each project in a Postgres schema of its own, see `pg_schema_per_project`.
+//!
+//! The shared schema (`pg_schema`, or `public`) holds the projects and the project of each
+//! warehouse in `project_warehouse`. The schema of a project is created and migrated with
+//! the project, i.e. its first warehouse or name, and dropped with the project.
+//!
+//! States are routed by connecting with the schema of the project in front of the
+//! search path, see [`CatalogState::for_project`]. Transactions are begun before the
+//! project is known and are routed with `SET LOCAL search_path` instead, see
+//! [`PostgresTransaction::route_to_warehouse`]. Warehouses without a project schema are
+//! resolved in the shared schema, whose catalog tables stay empty in this mode.
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use http::StatusCode;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::PgPool;
+
+use super::dbutils::DBErrorHandler as _;
+use super::{search_path, CatalogState, PostgresTransaction};
+use crate::api::{ErrorModel, Result};
+use crate::{ProjectIdent, WarehouseIdent, CONFIG};
+
+const PROJECT_SCHEMA_PREFIX: &str = "project_";
+
+/// Name of the schema of `project_id`.
+pub(crate) fn project_schema(project_id: &ProjectIdent) -> String {
+    format!("{PROJECT_SCHEMA_PREFIX}{}", project_id.as_uuid().simple())
+}
+
+/// Schema of the projects and of `project_warehouse`.
+fn shared_schema() -> &'static str {
+    CONFIG.pg_schema.as_deref().unwrap_or("public")
+}
+
+/// Pools of the projects, created on first use.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ProjectSchemas {
+    read_pools: Arc<RwLock<HashMap<ProjectIdent, PgPool>>>,
+    write_pools: Arc<RwLock<HashMap<ProjectIdent, PgPool>>>,
+    warehouses: Arc<RwLock<HashMap<WarehouseIdent, ProjectIdent>>>,
+    /// Read from the write pools, see [`CatalogState::primary`].
+    primary: bool,
+}
+
+impl ProjectSchemas {
+    pub(crate) fn primary(self) -> Self {
+        Self {
+            primary: true,
+            ..self
+        }
+    }
+}
+
+/// Pool of `project_id` in `pools`, connecting like `base` to the schema of the project.
+fn project_pool(
+    pools: &RwLock<HashMap<ProjectIdent, PgPool>>,
+    project_id: &ProjectIdent,
+    base: &PgPool,
+    max_connections: u32,
+) -> PgPool {
+    let schema = project_schema(project_id);
+    if let Some(pool) = pools.read().expect("lock poisoned").get(project_id) {
+        if CONFIG.pg_iam_auth {
+            // Pick up the token the base pool was refreshed with
+            pool.set_connect_options(routed_options(&base.connect_options(), &schema));
+        }
+        return pool.clone();
+    }
+
+    pools
+        .write()
+        .expect("lock poisoned")
+        .entry(project_id.clone())
+        .or_insert_with(|| {
+            PgPoolOptions::new()
+                .max_connections(max_connections)
+                .connect_lazy_with(routed_options(&base.connect_options(), &schema))
+        })
+        .clone()
+}
+
+fn routed_options(base: &PgConnectOptions, schema: &str) -> PgConnectOptions {
+    base.clone()
+        .options([("search_path", search_path(&[schema]))])
+}
+
+impl CatalogState {
+    /// Keep the catalog tables of each project in a schema of its own.
+    #[must_use]
+    pub fn with_schema_per_project(self) -> Self {
+        Self {
+            projects: Some(ProjectSchemas::default()),
+            ..self
+        }
+    }
+
+    pub(crate) fn is_schema_per_project(&self) -> bool {
+        self.projects.is_some()
+    }
+
+    /// State resolving the catalog tables in the schema of `project_id`.
+    pub(crate) fn for_project(&self, project_id: &ProjectIdent) -> Self {
+        let Some(projects) = &self.projects else {
+            return self.clone();
+        };
+
+        let write_pool = project_pool(
+            &projects.write_pools,
+            project_id,
+            &self.write_pool,
+            CONFIG.pg_write_pool_connections,
+        );
+        let read_pool = if projects.primary {
+            write_pool.clone()
+        } else {
+            project_pool(
+                &projects.read_pools,
+                project_id,
+                &self.read_pool,
+                CONFIG.pg_read_pool_connections,
+            )
+        };
+        Self {
+            read_pool,
+            write_pool,
+            projects: None,
+        }
+    }
+
+    /// State resolving the catalog tables in the schema of the project of `warehouse_id`.
+    pub(crate) async fn for_warehouse(&self, warehouse_id: &WarehouseIdent) -> Result<Self> {
+        let Some(projects) = &self.projects else {
+            return Ok(self.clone());
+        };
+
+        let cached = projects
+            .warehouses
+            .read()
+            .expect("lock poisoned")
+            .get(warehouse_id)
+            .cloned();
+        let project_id = if let Some(project_id) = cached {
+            project_id
+        } else {
+            // Read from the primary, warehouses are used right after their creation.
+            let project_id = sqlx::query_scalar::<_, uuid::Uuid>(
+                "SELECT project_id FROM project_warehouse WHERE warehouse_id = $1",
+            )
+            .bind(*warehouse_id.as_uuid())
+            .fetch_optional(&self.write_pool)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching project of warehouse".into()))?;
+            let Some(project_id) = project_id.map(ProjectIdent::from) else {
+                return Ok(self.clone());
+            };
+            projects
+                .warehouses
+                .write()
+                .expect("lock poisoned")
+                .insert(warehouse_id.clone(), project_id.clone());
+            project_id
+        };
+
+        Ok(self.for_project(&project_id))
+    }
+
+    /// States of all projects with a schema, or the state itself if projects share
+    /// the catalog tables. Used by jobs processing all projects at once.
+    pub(crate) async fn project_states(&self) -> Result<Vec<Self>> {
+        if self.projects.is_none() {
+            return Ok(vec![self.clone()]);
+        }
+
+        let project_ids = project_ids_with_schema(&self.read_pool)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching projects".into()))?;
+        Ok(project_ids
+            .iter()
+            .map(|project_id| self.for_project(project_id))
+            .collect())
+    }
+}
+
+impl PostgresTransaction {
+    pub(crate) fn is_schema_per_project(&self) -> bool {
+        self.schema_per_project
+    }
+
+    /// Resolve the catalog tables in the schema of the project of `warehouse_id`
+    /// for the rest of the transaction.
+    pub(crate) async fn route_to_warehouse(&mut self, warehouse_id: &WarehouseIdent) -> Result<()> {
+        if !self.schema_per_project {
+            return Ok(());
+        }
+
+        sqlx::query(&format!(
+            r#"
+            SELECT set_config(
+                'search_path',
+                '{PROJECT_SCHEMA_PREFIX}' || replace(project_id::text, '-', '') || ',' || $2,
+                true
+            )
+            FROM "{}".project_warehouse
+            WHERE warehouse_id = $1
+            "#,
+            shared_schema()
+        ))
+        .bind(*warehouse_id.as_uuid())
+        .bind(search_path(&[]))
+        .execute(&mut *self.transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error routing to project of warehouse".into()))?;
+        Ok(())
+    }
+
+    /// Resolve the catalog tables in the schema of `project_id` for the rest of the transaction.
+    pub(crate) async fn route_to_project(&mut self, project_id: &ProjectIdent) -> Result<()> {
+        if !self.schema_per_project {
+            return Ok(());
+        }
+
+        set_search_path(&mut self.transaction, &project_schema(project_id))
+            .await
+            .map_err(|e| e.into_error_model("Error routing to project".into()))
+    }
+
+    /// Create `project_id` in the shared schema and create and migrate the schema of the
+    /// project if they don't exist yet. Routes the rest of the transaction to the project.
+    pub(crate) async fn create_project(&mut self, project_id: &ProjectIdent) -> Result<()> {
+        if !self.schema_per_project {
+            return Ok(());
+        }
+
+        let schema = project_schema(project_id);
+        // Concurrent creations of a new project wait for each other on the inserted row.
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO "{}".project (project_id, project_name)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            shared_schema()
+        ))
+        .bind(*project_id.as_uuid())
+        .bind(project_id.to_string())
+        .execute(&mut *self.transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error creating project".into()))?;
+
+        let exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM pg_namespace WHERE nspname = $1)",
+        )
+        .bind(&schema)
+        .fetch_one(&mut *self.transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching project schema".into()))?;
+        if exists {
+            set_search_path(&mut self.transaction, &schema)
+                .await
+                .map_err(|e| e.into_error_model("Error routing to project".into()))?;
+        } else {
+            migrate_project_schema(&mut self.transaction, &schema)
+                .await
+                .map_err(|e| {
+                    ErrorModel::builder()
+                        .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                        .message("Error creating project schema".to_string())
+                        .r#type("ProjectSchemaCreationError".to_string())
+                        .stack(Some(vec![format!("{e:?}")]))
+                        .build()
+                })?;
+        }
+
+        // Roles on the project reference the project in its own schema.
+        sqlx::query(
+            r#"
+            INSERT INTO project (project_id, project_name)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(*project_id.as_uuid())
+        .bind(project_id.to_string())
+        .execute(&mut *self.transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error creating project".into()))?;
+        Ok(())
+    }
+
+    /// Drop the schema of `project_id` with all its catalog tables and delete the
+    /// project from the shared schema.
+    pub(crate) async fn drop_project_schema(&mut self, project_id: &ProjectIdent) -> Result<()> {
+        if !self.schema_per_project {
+            return Ok(());
+        }
+
+        sqlx::query(&format!(
+            r#"DELETE FROM "{}".project WHERE project_id = $1"#,
+            shared_schema()
+        ))
+        .bind(*project_id.as_uuid())
+        .execute(&mut *self.transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error deleting project".into()))?;
+        sqlx::query(&format!(
+            r#"DROP SCHEMA IF EXISTS "{}" CASCADE"#,
+            project_schema(project_id)
+        ))
+        .execute(&mut *self.transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error dropping project schema".into()))?;
+        Ok(())
+    }
+
+    /// Record the project of a new warehouse in the shared schema.
+    pub(crate) async fn register_warehouse(
+        &mut self,
+        warehouse_id: &WarehouseIdent,
+        project_id: &ProjectIdent,
+    ) -> Result<()> {
+        if !self.schema_per_project {
+            return Ok(());
+        }
+
+        sqlx::query(&format!(
+            r#"INSERT INTO "{}".project_warehouse (warehouse_id, project_id) VALUES ($1, $2)"#,
+            shared_schema()
+        ))
+        .bind(*warehouse_id.as_uuid())
+        .bind(*project_id.as_uuid())
+        .execute(&mut *self.transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error registering warehouse".into()))?;
+        Ok(())
+    }
+
+    /// Remove a deleted warehouse from the shared schema.
+    pub(crate) async fn unregister_warehouse(
+        &mut self,
+        warehouse_id: &WarehouseIdent,
+    ) -> Result<()> {
+        if !self.schema_per_project {
+            return Ok(());
+        }
+
+        sqlx::query(&format!(
+            r#"DELETE FROM "{}".project_warehouse WHERE warehouse_id = $1"#,
+            shared_schema()
+        ))
+        .bind(*warehouse_id.as_uuid())
+        .execute(&mut *self.transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error unregistering warehouse".into()))?;
+        Ok(())
+    }
+}
+
+/// `SET LOCAL search_path` to `schema` followed by the shared schemas.
+async fn set_search_path(connection: &mut sqlx::PgConnection, schema: &str) -> sqlx::Result<()> {
+    sqlx::query("SELECT set_config('search_path', $1, true)")
+        .bind(search_path(&[schema]))
+        .execute(connection)
+        .await?;
+    Ok(())
+}
+
+/// Create `schema` and run the migrations of the catalog in it. Must be called
+/// within a transaction, which stays routed to the schema.
+async fn migrate_project_schema(
+    connection: &mut sqlx::PgConnection,
+    schema: &str,
+) -> anyhow::Result<()> {
+    // Derived from a uuid, safe to interpolate
+    sqlx::query(&format!(r#"CREATE SCHEMA IF NOT EXISTS "{schema}""#))
+        .execute(&mut *connection)
+        .await
+        .map_err(|e| anyhow::anyhow!(e).context(format!("Error creating schema {schema}.")))?;
+    set_search_path(&mut *connection, schema).await?;
+    super::migrator()
+        .run(&mut *connection)
+        .await
+        .map_err(|e| anyhow::anyhow!(e).context(format!("Error migrating schema {schema}.")))?;
+    Ok(())
+}
+
+async fn project_ids_with_schema(pool: &PgPool) -> sqlx::Result<Vec<ProjectIdent>> {
+    let project_ids = sqlx::query_scalar::<_, uuid::Uuid>(
+        r#"
+        SELECT project_id FROM project
+        WHERE EXISTS (
+            SELECT 1 FROM pg_namespace
+            WHERE nspname = $1 || replace(project_id::text, '-', '')
+        )
+        ORDER BY project_id
+        "#,
+    )
+    .bind(PROJECT_SCHEMA_PREFIX)
+    .fetch_all(pool)
+    .await?;
+    Ok(project_ids.into_iter().map(ProjectIdent::from).collect())
+}
+
+/// Migrate the schemas of all projects, see [`super::migrate`].
+pub(super) async fn migrate_project_schemas(pool: &PgPool) -> anyhow::Result<()> {
+    let project_ids = project_ids_with_schema(pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e).context("Error fetching project schemas."))?;
+    for project_id in project_ids {
+        let mut transaction = pool.begin().await?;
+        migrate_project_schema(&mut transaction, &project_schema(&project_id)).await?;
+        transaction.commit().await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::namespace::tests::initialize_namespace;
+    use super::super::warehouse::test::initialize_warehouse;
+    use super::super::Catalog;
+    use super::*;
+    use crate::service::{Catalog as _, NamespaceIdent, Transaction as _};
+
+    async fn schema_exists(pool: &PgPool, schema: &str) -> bool {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM pg_namespace WHERE nspname = $1)",
+        )
+        .bind(schema)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    async fn namespace_count(pool: &PgPool, schema: &str) -> i64 {
+        sqlx::query_scalar::<_, i64>(&format!(r#"SELECT count(*) FROM "{schema}".namespace"#))
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_project_schema() {
+        let project_id = ProjectIdent::from(
+            uuid::Uuid::parse_str("0191b1a4-3f8c-7d1e-9c2b-5a6f7e8d9c0b").unwrap(),
+        );
+        assert_eq!(
+            project_schema(&project_id),
+            "project_0191b1a43f8c7d1e9c2b5a6f7e8d9c0b"
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_schema_per_project(pool: PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone()).with_schema_per_project();
+        let project_a = ProjectIdent::from(uuid::Uuid::now_v7());
+        let project_b = ProjectIdent::from(uuid::Uuid::now_v7());
+
+        let warehouse_a = initialize_warehouse(state.clone(), None, Some(&project_a)).await;
+        let warehouse_b = initialize_warehouse(state.clone(), None, Some(&project_b)).await;
+        assert!(schema_exists(&pool, &project_schema(&project_a)).await);
+        assert!(schema_exists(&pool, &project_schema(&project_b)).await);
+
+        // Catalog tables of the project are used, the shared ones stay empty
+        let namespace = NamespaceIdent::from_vec(vec!["ns".to_string()]).unwrap();
+        initialize_namespace(state.clone(), &warehouse_a, &namespace, None).await;
+        assert_eq!(namespace_count(&pool, &project_schema(&project_a)).await, 1);
+        assert_eq!(namespace_count(&pool, &project_schema(&project_b)).await, 0);
+        assert_eq!(namespace_count(&pool, shared_schema()).await, 0);
+        assert!(
+            Catalog::namespace_ident_to_id(&warehouse_a, &namespace, state.clone())
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            Catalog::namespace_ident_to_id(&warehouse_b, &namespace, state.clone())
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // Projects are listed from the shared schema
+        let projects = Catalog::list_projects(state.clone()).await.unwrap();
+        assert!(projects.contains(&project_a) && projects.contains(&project_b));
+
+        // Projects with warehouses can't be deleted
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        let err = Catalog::delete_project(&project_a, transaction.transaction())
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT);
+        transaction.rollback().await.unwrap();
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        Catalog::drop_namespace(&warehouse_a, &namespace, transaction.transaction())
+            .await
+            .unwrap();
+        Catalog::delete_warehouse(&warehouse_a, transaction.transaction())
+            .await
+            .unwrap();
+        Catalog::delete_project(&project_a, transaction.transaction())
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+
+        assert!(!schema_exists(&pool, &project_schema(&project_a)).await);
+        assert!(schema_exists(&pool, &project_schema(&project_b)).await);
+        let projects = Catalog::list_projects(state.clone()).await.unwrap();
+        assert!(!projects.contains(&project_a) && projects.contains(&project_b));
+    }
+}
@@ -0,0 +1,132 @@
+//! Authentication with AWS RDS IAM tokens.
+//!
+//! A token is a presigned `connect` request for the database user and is used as
+//! password. Tokens expire after 15 minutes, so the password of the pools is replaced
+//! periodically. Open connections are not affected by the expiry.
+use std::time::{Duration, SystemTime};
+
+use aws_sigv4::http_request::{
+    sign, SignableBody, SignableRequest, SignatureLocation, SigningSettings,
+};
+use aws_sigv4::sign::v4;
+use sqlx::postgres::PgConnectOptions;
+
+use crate::CONFIG;
+
+/// Lifetime of a token.
+const TOKEN_EXPIRY: Duration = Duration::from_secs(900);
+/// Interval in which the password of the pools is replaced.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Generate a token for the host, port and user of `options`.
+pub(super) fn generate_token(options: &PgConnectOptions) -> anyhow::Result<String> {
+    let region = CONFIG
+        .pg_iam_region
+        .clone()
+        .or_else(|| std::env::var("AWS_REGION").ok())
+        .ok_or_else(|| anyhow::anyhow!("No region configured for RDS IAM authentication."))?;
+    let credentials = credentials_from_env()?;
+
+    let mut url = url::Url::parse(&format!(
+        "https://{}:{}/",
+        options.get_host(),
+        options.get_port()
+    ))?;
+    url.query_pairs_mut()
+        .append_pair("Action", "connect")
+        .append_pair("DBUser", options.get_username());
+
+    let mut settings = SigningSettings::default();
+    settings.signature_location = SignatureLocation::QueryParams;
+    settings.expires_in = Some(TOKEN_EXPIRY);
+    let identity = credentials.into();
+    let params = v4::SigningParams::builder()
+        .identity(&identity)
+        .region(&region)
+        .name("rds-db")
+        .time(SystemTime::now())
+        .settings(settings)
+        .build()
+        .map_err(|e| anyhow::anyhow!(e).context("Failed to create signing params."))?
+        .into();
+    let request = SignableRequest::new(
+        "GET",
+        url.as_str(),
+        std::iter::empty(),
+        SignableBody::Bytes(&[]),
+    )
+    .map_err(|e| anyhow::anyhow!(e).context("Failed to create signable request."))?;
+    let (instructions, _signature) = sign(request, &params)
+        .map_err(|e| anyhow::anyhow!(e).context("Failed to sign RDS IAM token."))?
+        .into_parts();
+
+    for (key, value) in instructions.params() {
+        url.query_pairs_mut().append_pair(key, value);
+    }
+    // The token is the presigned URL without scheme
+    Ok(url.as_str().trim_start_matches("https://").to_string())
+}
+
+fn credentials_from_env() -> anyhow::Result<aws_credential_types::Credentials> {
+    let access_key_id = std::env::var("AWS_ACCESS_KEY_ID").map_err(|_| {
+        anyhow::anyhow!("AWS_ACCESS_KEY_ID is required for RDS IAM authentication.")
+    })?;
+    let secret_access_key = std::env::var("AWS_SECRET_ACCESS_KEY").map_err(|_| {
+        anyhow::anyhow!("AWS_SECRET_ACCESS_KEY is required for RDS IAM authentication.")
+    })?;
+    Ok(aws_credential_types::Credentials::new(
+        access_key_id,
+        secret_access_key,
+        std::env::var("AWS_SESSION_TOKEN").ok(),
+        None,
+        "environment",
+    ))
+}
+
+/// Replace the password of `pool` with a new token every [`REFRESH_INTERVAL`].
+pub(super) fn spawn_refresh(pool: sqlx::PgPool, url: String) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        // The first tick completes immediately, the pool was just created with a new token.
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match super::connect_options(&url) {
+                Ok(options) => pool.set_connect_options(options),
+                Err(e) => tracing::error!("Failed to refresh RDS IAM token: {e:?}"),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_generate_token() {
+        std::env::set_var("AWS_ACCESS_KEY_ID", "AKIDEXAMPLE");
+        std::env::set_var(
+            "AWS_SECRET_ACCESS_KEY",
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+        );
+        std::env::set_var("AWS_REGION", "eu-central-1");
+        let options =
+            PgConnectOptions::from_str("postgres://iceberg@db.example.com:5432/iceberg").unwrap();
+
+        let token = generate_token(&options).unwrap();
+
+        assert!(
+            token.starts_with("db.example.com:5432/?Action=connect&DBUser=iceberg&"),
+            "{token}"
+        );
+        assert!(token.contains("X-Amz-Expires=900"), "{token}");
+        assert!(
+            token.contains("%2Feu-central-1%2Frds-db%2Faws4_request"),
+            "{token}"
+        );
+        assert!(token.contains("X-Amz-Signature="), "{token}");
+    }
+}
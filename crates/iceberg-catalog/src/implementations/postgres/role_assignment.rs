@@ -0,0 +1,439 @@
+use super::{dbutils::DBErrorHandler as _, CatalogState};
+use crate::api::{ErrorModel, ErrorType, Result};
+use crate::service::{NamespaceIdent, Role, RoleAssignment, RoleObject, RoleTarget, TableIdent};
+use crate::{ProjectIdent, WarehouseIdent};
+use http::StatusCode;
+
+/// Ids of the object a role is granted on.
+struct ObjectIds {
+    project_id: uuid::Uuid,
+    warehouse_id: Option<uuid::Uuid>,
+    namespace_id: Option<uuid::Uuid>,
+    table_id: Option<uuid::Uuid>,
+}
+
+impl ObjectIds {
+    fn object_id(&self) -> uuid::Uuid {
+        self.table_id
+            .or(self.namespace_id)
+            .or(self.warehouse_id)
+            .unwrap_or(self.project_id)
+    }
+}
+
+async fn resolve_object(
+    object: &RoleObject,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<ObjectIds> {
+    let not_found = |message: &str, r#type: &str, error_type: ErrorType| {
+        ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message(message.to_string())
+            .r#type(r#type.to_string())
+            .error_type(error_type)
+            .build()
+    };
+
+    match object {
+        RoleObject::Project(project_id) => {
+            let project_id = sqlx::query_scalar!(
+                r#"SELECT project_id FROM project WHERE project_id = $1"#,
+                project_id.as_uuid()
+            )
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching project".into()))?
+            .ok_or_else(|| {
+                not_found("Project not found", "ProjectNotFound", ErrorType::NotFound)
+            })?;
+            Ok(ObjectIds {
+                project_id,
+                warehouse_id: None,
+                namespace_id: None,
+                table_id: None,
+            })
+        }
+        RoleObject::Warehouse(warehouse_id) => {
+            let project_id = sqlx::query_scalar!(
+                r#"
+                SELECT project_id FROM warehouse
+                WHERE warehouse_id = $1 AND status = 'active'
+                "#,
+                warehouse_id.as_uuid()
+            )
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching warehouse".into()))?
+            .ok_or_else(|| {
+                not_found(
+                    "Warehouse not found",
+                    "WarehouseNotFound",
+                    ErrorType::NotFound,
+                )
+            })?;
+            Ok(ObjectIds {
+                project_id,
+                warehouse_id: Some(*warehouse_id.as_uuid()),
+                namespace_id: None,
+                table_id: None,
+            })
+        }
+        RoleObject::Namespace(warehouse_id, namespace) => {
+            let ids = sqlx::query!(
+                r#"
+                SELECT w.project_id, n.namespace_id
+                FROM namespace n
+                INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+                WHERE n.warehouse_id = $1 AND n.namespace_name = $2
+                AND w.status = 'active'
+                "#,
+                warehouse_id.as_uuid(),
+                &**namespace
+            )
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching namespace".into()))?
+            .ok_or_else(|| {
+                not_found(
+                    "Namespace not found",
+                    "NoSuchNamespaceError",
+                    ErrorType::NoSuchNamespace,
+                )
+            })?;
+            Ok(ObjectIds {
+                project_id: ids.project_id,
+                warehouse_id: Some(*warehouse_id.as_uuid()),
+                namespace_id: Some(ids.namespace_id),
+                table_id: None,
+            })
+        }
+        RoleObject::Table(warehouse_id, TableIdent { namespace, name }) => {
+            let ids = sqlx::query!(
+                r#"
+                SELECT w.project_id, t.table_id
+                FROM "table" t
+                INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+                INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+                WHERE n.warehouse_id = $1 AND n.namespace_name = $2 AND t.table_name = $3
+                AND w.status = 'active'
+                AND t."metadata_location" IS NOT NULL
+                "#,
+                warehouse_id.as_uuid(),
+                &**namespace,
+                &**name
+            )
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching table".into()))?
+            .ok_or_else(|| {
+                not_found(
+                    "Table not found",
+                    "NoSuchTableError",
+                    ErrorType::NoSuchTable,
+                )
+            })?;
+            Ok(ObjectIds {
+                project_id: ids.project_id,
+                warehouse_id: Some(*warehouse_id.as_uuid()),
+                namespace_id: None,
+                table_id: Some(ids.table_id),
+            })
+        }
+    }
+}
+
+pub(crate) async fn grant_role(
+    object: &RoleObject,
+    principal: &str,
+    role: Role,
+    created_by: Option<&str>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<uuid::Uuid> {
+    if principal.is_empty() {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("Principal cannot be empty".to_string())
+            .r#type("EmptyPrincipal".to_string())
+            .build()
+            .into());
+    }
+
+    let ids = resolve_object(object, transaction).await?;
+    let assignment_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO role_assignment (principal, "role", project_id, warehouse_id, namespace_id, table_id, object_id, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (principal, object_id) DO UPDATE SET "role" = EXCLUDED."role"
+        RETURNING assignment_id
+        "#,
+        principal,
+        role as Role,
+        ids.project_id,
+        ids.warehouse_id,
+        ids.namespace_id,
+        ids.table_id,
+        ids.object_id(),
+        created_by
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error granting role".into()))?;
+
+    Ok(assignment_id)
+}
+
+pub(crate) async fn revoke_role(
+    object: &RoleObject,
+    principal: &str,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let ids = resolve_object(object, transaction).await?;
+    let result = sqlx::query!(
+        r#"DELETE FROM role_assignment WHERE principal = $1 AND object_id = $2"#,
+        principal,
+        ids.object_id()
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error revoking role".into()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Role assignment not found".to_string())
+            .r#type("RoleAssignmentNotFound".to_string())
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn list_role_assignments(
+    project_id: Option<&ProjectIdent>,
+    principal: Option<&str>,
+    catalog_state: CatalogState,
+) -> Result<Vec<RoleAssignment>> {
+    let assignments = sqlx::query!(
+        r#"
+        SELECT
+            ra.assignment_id,
+            ra.principal,
+            ra."role" as "role: Role",
+            ra.project_id,
+            ra.warehouse_id,
+            ra.created_by,
+            COALESCE(n.namespace_name, tn.namespace_name) as "namespace_name?",
+            t.table_name as "table_name?"
+        FROM role_assignment ra
+        LEFT JOIN namespace n ON ra.namespace_id = n.namespace_id
+        LEFT JOIN "table" t ON ra.table_id = t.table_id
+        LEFT JOIN namespace tn ON t.namespace_id = tn.namespace_id
+        WHERE ($1::uuid IS NULL OR ra.project_id = $1)
+        AND ($2::text IS NULL OR ra.principal = $2)
+        ORDER BY ra.created_at
+        "#,
+        project_id.map(ProjectIdent::as_uuid),
+        principal
+    )
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching role assignments".into()))?;
+
+    assignments
+        .into_iter()
+        .map(|row| {
+            let namespace = row
+                .namespace_name
+                .map(|namespace| {
+                    NamespaceIdent::from_vec(namespace).map_err(|e| {
+                        ErrorModel::builder()
+                            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                            .message("Error parsing namespace".to_string())
+                            .r#type("NamespaceParseError".to_string())
+                            .stack(Some(vec![e.to_string()]))
+                            .build()
+                    })
+                })
+                .transpose()?;
+            let object = match (row.warehouse_id, namespace, row.table_name) {
+                (Some(warehouse_id), Some(namespace), Some(name)) => {
+                    RoleObject::Table(warehouse_id.into(), TableIdent::new(namespace, name))
+                }
+                (Some(warehouse_id), Some(namespace), None) => {
+                    RoleObject::Namespace(warehouse_id.into(), namespace)
+                }
+                (Some(warehouse_id), None, _) => RoleObject::Warehouse(warehouse_id.into()),
+                (None, _, _) => RoleObject::Project(row.project_id.into()),
+            };
+            Ok(RoleAssignment {
+                assignment_id: row.assignment_id,
+                principal: row.principal,
+                role: row.role,
+                object,
+                project_id: row.project_id.into(),
+                created_by: row.created_by,
+            })
+        })
+        .collect()
+}
+
+pub(crate) async fn get_roles(
+    principal: &str,
+    target: &RoleTarget,
+    catalog_state: CatalogState,
+) -> Result<Vec<Role>> {
+    let (warehouse_id, namespace, table_id) = match target {
+        RoleTarget::Project(project_id) => {
+            return sqlx::query_scalar!(
+                r#"
+                SELECT "role" as "role: Role"
+                FROM role_assignment
+                WHERE principal = $1 AND project_id = $2 AND warehouse_id IS NULL
+                "#,
+                principal,
+                project_id.as_uuid()
+            )
+            .fetch_all(&catalog_state.read_pool)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching roles".into()).into());
+        }
+        RoleTarget::Warehouse(warehouse_id) => (warehouse_id, None, None),
+        RoleTarget::Namespace(warehouse_id, namespace) => (warehouse_id, Some(namespace), None),
+        RoleTarget::Table(warehouse_id, table_id) => {
+            (warehouse_id, None, Some(*table_id.as_uuid()))
+        }
+    };
+
+    // Roles on namespaces apply to all namespaces below them, which share their name as prefix.
+    sqlx::query_scalar!(
+        r#"
+        WITH target AS (
+            SELECT
+                w.project_id,
+                w.warehouse_id,
+                COALESCE(
+                    (
+                        SELECT n.namespace_name
+                        FROM "table" t
+                        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+                        WHERE t.table_id = $4 AND n.warehouse_id = w.warehouse_id
+                    ),
+                    $3::text[]
+                ) AS namespace_name
+            FROM warehouse w
+            WHERE w.warehouse_id = $2
+        )
+        SELECT ra."role" as "role!: Role"
+        FROM role_assignment ra, target
+        WHERE ra.principal = $1
+        AND (
+            (ra.project_id = target.project_id AND ra.warehouse_id IS NULL)
+            OR (ra.warehouse_id = target.warehouse_id AND ra.namespace_id IS NULL AND ra.table_id IS NULL)
+            OR ra.namespace_id IN (
+                SELECT n.namespace_id
+                FROM namespace n
+                WHERE n.warehouse_id = target.warehouse_id
+                AND n.namespace_name = target.namespace_name[1:cardinality(n.namespace_name)]
+            )
+            OR ra.table_id = $4
+        )
+        "#,
+        principal,
+        warehouse_id.as_uuid(),
+        namespace.map(|namespace| &**namespace) as Option<&Vec<String>>,
+        table_id
+    )
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching roles".into()).into())
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::table::tests::initialize_table;
+    use super::super::warehouse::test::initialize_warehouse;
+    use super::*;
+
+    #[sqlx::test]
+    async fn test_role_assignments(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let table = initialize_table(&warehouse_id, state.clone(), false).await;
+
+        let mut transaction = pool.begin().await.unwrap();
+        let namespace = RoleObject::Namespace(warehouse_id.clone(), table.namespace.clone());
+        let assignment_id = grant_role(&namespace, "alice", Role::Reader, None, &mut transaction)
+            .await
+            .unwrap();
+        // Granting again replaces the role
+        let assignment_id_2 = grant_role(
+            &namespace,
+            "alice",
+            Role::Writer,
+            Some("bob"),
+            &mut transaction,
+        )
+        .await
+        .unwrap();
+        assert_eq!(assignment_id, assignment_id_2);
+        grant_role(
+            &RoleObject::Warehouse(warehouse_id.clone()),
+            "bob",
+            Role::Admin,
+            None,
+            &mut transaction,
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let roles = get_roles(
+            "alice",
+            &RoleTarget::Table(warehouse_id.clone(), table.table_id),
+            state.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(roles, vec![Role::Writer]);
+        let roles = get_roles(
+            "alice",
+            &RoleTarget::Warehouse(warehouse_id.clone()),
+            state.clone(),
+        )
+        .await
+        .unwrap();
+        assert!(roles.is_empty());
+        let roles = get_roles(
+            "bob",
+            &RoleTarget::Namespace(warehouse_id.clone(), table.namespace.clone()),
+            state.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(roles, vec![Role::Admin]);
+
+        let assignments = list_role_assignments(None, Some("alice"), state.clone())
+            .await
+            .unwrap();
+        assert_eq!(assignments.len(), 1);
+        assert_eq!(assignments[0].object, namespace);
+        assert_eq!(assignments[0].role, Role::Writer);
+
+        let mut transaction = pool.begin().await.unwrap();
+        revoke_role(&namespace, "alice", &mut transaction)
+            .await
+            .unwrap();
+        let err = revoke_role(&namespace, "alice", &mut transaction)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+        transaction.commit().await.unwrap();
+
+        assert!(list_role_assignments(None, Some("alice"), state)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
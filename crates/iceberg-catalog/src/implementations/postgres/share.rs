@@ -0,0 +1,332 @@
+use super::{dbutils::DBErrorHandler as _, CatalogState};
+use crate::api::{ErrorModel, ErrorType, Result};
+use crate::service::{NamespaceIdent, ResolvedShare, Share, SharedObject, TableIdent};
+use crate::{ProjectIdent, WarehouseIdent};
+use http::StatusCode;
+
+pub(crate) async fn create_share(
+    warehouse_id: &WarehouseIdent,
+    object: &SharedObject,
+    consumer_project_id: &ProjectIdent,
+    mount_name: &str,
+    created_by: Option<&str>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<uuid::Uuid> {
+    if mount_name.is_empty() {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("Mount name cannot be empty".to_string())
+            .r#type("EmptyMountName".to_string())
+            .build()
+            .into());
+    }
+
+    let (namespace_id, table_id) = match object {
+        SharedObject::Namespace(namespace) => {
+            let namespace_id = sqlx::query_scalar!(
+                r#"
+                SELECT n.namespace_id
+                FROM namespace n
+                INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+                WHERE n.warehouse_id = $1 AND n.namespace_name = $2
+                AND w.status = 'active'
+                "#,
+                warehouse_id.as_uuid(),
+                &**namespace
+            )
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching namespace".into()))?
+            .ok_or_else(|| {
+                ErrorModel::builder()
+                    .code(StatusCode::NOT_FOUND.into())
+                    .message("Namespace not found".to_string())
+                    .r#type("NoSuchNamespaceError".to_string())
+                    .error_type(ErrorType::NoSuchNamespace)
+                    .build()
+            })?;
+            (Some(namespace_id), None)
+        }
+        SharedObject::Table(TableIdent { namespace, name }) => {
+            let table_id = sqlx::query_scalar!(
+                r#"
+                SELECT t.table_id
+                FROM "table" t
+                INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+                INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+                WHERE n.warehouse_id = $1 AND n.namespace_name = $2 AND t.table_name = $3
+                AND w.status = 'active'
+                AND t."metadata_location" IS NOT NULL
+                "#,
+                warehouse_id.as_uuid(),
+                &**namespace,
+                &**name
+            )
+            .fetch_optional(&mut **transaction)
+            .await
+            .map_err(|e| e.into_error_model("Error fetching table".into()))?
+            .ok_or_else(|| {
+                ErrorModel::builder()
+                    .code(StatusCode::NOT_FOUND.into())
+                    .message("Table not found".to_string())
+                    .r#type("NoSuchTableError".to_string())
+                    .error_type(ErrorType::NoSuchTable)
+                    .build()
+            })?;
+            (None, Some(table_id))
+        }
+    };
+
+    let share_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO share (warehouse_id, namespace_id, table_id, consumer_project_id, mount_name, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING share_id
+        "#,
+        warehouse_id.as_uuid(),
+        namespace_id,
+        table_id,
+        consumer_project_id.as_uuid(),
+        mount_name,
+        created_by
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(ref db_error) if db_error.is_unique_violation() => {
+            ErrorModel::builder()
+                .code(StatusCode::CONFLICT.into())
+                .message("The consumer project already has a share with this mount name".to_string())
+                .r#type("ShareAlreadyExists".to_string())
+                .error_type(ErrorType::AlreadyExists)
+                .build()
+        }
+        _ => e.into_error_model("Error creating share".into()),
+    })?;
+
+    Ok(share_id)
+}
+
+pub(crate) async fn list_shares(
+    warehouse_id: &WarehouseIdent,
+    catalog_state: CatalogState,
+) -> Result<Vec<Share>> {
+    let shares = sqlx::query!(
+        r#"
+        SELECT
+            s.share_id,
+            s.consumer_project_id,
+            s.mount_name,
+            s.created_by,
+            COALESCE(n.namespace_name, tn.namespace_name) as "namespace_name!",
+            t.table_name as "table_name?"
+        FROM share s
+        LEFT JOIN namespace n ON s.namespace_id = n.namespace_id
+        LEFT JOIN "table" t ON s.table_id = t.table_id
+        LEFT JOIN namespace tn ON t.namespace_id = tn.namespace_id
+        WHERE s.warehouse_id = $1
+        ORDER BY s.created_at
+        "#,
+        warehouse_id.as_uuid()
+    )
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching shares".into()))?;
+
+    shares
+        .into_iter()
+        .map(|share| {
+            let namespace = parse_namespace(share.namespace_name)?;
+            let object = match share.table_name {
+                Some(name) => SharedObject::Table(TableIdent::new(namespace, name)),
+                None => SharedObject::Namespace(namespace),
+            };
+            Ok(Share {
+                share_id: share.share_id,
+                object,
+                consumer_project_id: share.consumer_project_id.into(),
+                mount_name: share.mount_name,
+                created_by: share.created_by,
+            })
+        })
+        .collect()
+}
+
+pub(crate) async fn delete_share(
+    warehouse_id: &WarehouseIdent,
+    share_id: &uuid::Uuid,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let row_count = sqlx::query_scalar!(
+        r#"
+        WITH deleted AS (
+            DELETE FROM share
+            WHERE warehouse_id = $1 AND share_id = $2
+            RETURNING share_id
+        )
+        SELECT count(share_id) FROM deleted
+        "#,
+        warehouse_id.as_uuid(),
+        share_id
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error deleting share".into()))?;
+
+    if row_count == Some(0) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Share not found".to_string())
+            .r#type("ShareNotFound".to_string())
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn resolve_share(
+    warehouse_id: &WarehouseIdent,
+    mount_name: &str,
+    catalog_state: CatalogState,
+) -> Result<Option<ResolvedShare>> {
+    let share = sqlx::query!(
+        r#"
+        SELECT
+            s.share_id,
+            s.warehouse_id,
+            COALESCE(n.namespace_name, tn.namespace_name) as "namespace_name!",
+            t.table_name as "table_name?"
+        FROM share s
+        INNER JOIN warehouse cw ON cw.project_id = s.consumer_project_id
+        INNER JOIN warehouse w ON s.warehouse_id = w.warehouse_id
+        LEFT JOIN namespace n ON s.namespace_id = n.namespace_id
+        LEFT JOIN "table" t ON s.table_id = t.table_id
+        LEFT JOIN namespace tn ON t.namespace_id = tn.namespace_id
+        WHERE cw.warehouse_id = $1 AND s.mount_name = $2
+        AND w.status = 'active'
+        "#,
+        warehouse_id.as_uuid(),
+        mount_name
+    )
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error resolving share".into()))?;
+
+    share
+        .map(|share| {
+            Ok(ResolvedShare {
+                share_id: share.share_id,
+                warehouse_id: share.warehouse_id.into(),
+                namespace: parse_namespace(share.namespace_name)?,
+                table_name: share.table_name,
+            })
+        })
+        .transpose()
+}
+
+fn parse_namespace(namespace: Vec<String>) -> Result<NamespaceIdent> {
+    NamespaceIdent::from_vec(namespace).map_err(|e| {
+        ErrorModel::builder()
+            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+            .message("Error parsing namespace".to_string())
+            .r#type("NamespaceParseError".to_string())
+            .stack(Some(vec![e.to_string()]))
+            .build()
+            .into()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::table::tests::initialize_table;
+    use super::super::warehouse::test::initialize_warehouse;
+    use super::*;
+
+    #[sqlx::test]
+    async fn test_share_lifecycle(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let table = initialize_table(&warehouse_id, state.clone(), false).await;
+        let consumer_project_id = ProjectIdent::from(uuid::Uuid::now_v7());
+        let consumer_warehouse_id =
+            initialize_warehouse(state.clone(), None, Some(&consumer_project_id)).await;
+
+        let mut transaction = pool.begin().await.unwrap();
+        let share_id = create_share(
+            &warehouse_id,
+            &SharedObject::Table(table.table_ident.clone()),
+            &consumer_project_id,
+            "shared",
+            Some("alice"),
+            &mut transaction,
+        )
+        .await
+        .unwrap();
+        let err = create_share(
+            &warehouse_id,
+            &SharedObject::Namespace(table.namespace.clone()),
+            &consumer_project_id,
+            "Shared",
+            None,
+            &mut transaction,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT);
+        transaction.rollback().await.unwrap();
+
+        let mut transaction = pool.begin().await.unwrap();
+        let share_id_2 = create_share(
+            &warehouse_id,
+            &SharedObject::Table(table.table_ident.clone()),
+            &consumer_project_id,
+            "shared",
+            Some("alice"),
+            &mut transaction,
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+        assert_ne!(share_id, share_id_2);
+
+        let shares = list_shares(&warehouse_id, state.clone()).await.unwrap();
+        assert_eq!(
+            shares,
+            vec![Share {
+                share_id: share_id_2,
+                object: SharedObject::Table(table.table_ident.clone()),
+                consumer_project_id: consumer_project_id.clone(),
+                mount_name: "shared".to_string(),
+                created_by: Some("alice".to_string()),
+            }]
+        );
+
+        let resolved = resolve_share(&consumer_warehouse_id, "SHARED", state.clone())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(resolved.warehouse_id, warehouse_id);
+        assert_eq!(resolved.namespace, table.namespace);
+        assert_eq!(resolved.table_name, Some(table.table_ident.name.clone()));
+
+        // Not mounted in warehouses of other projects
+        assert!(resolve_share(&warehouse_id, "shared", state.clone())
+            .await
+            .unwrap()
+            .is_none());
+
+        let mut transaction = pool.begin().await.unwrap();
+        delete_share(&warehouse_id, &share_id_2, &mut transaction)
+            .await
+            .unwrap();
+        let err = delete_share(&warehouse_id, &share_id_2, &mut transaction)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+        transaction.commit().await.unwrap();
+
+        assert!(list_shares(&warehouse_id, state).await.unwrap().is_empty());
+    }
+}
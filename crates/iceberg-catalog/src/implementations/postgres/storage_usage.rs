@@ -0,0 +1,129 @@
+use super::{dbutils::DBErrorHandler as _, CatalogState};
+use crate::api::Result;
+use crate::service::storage_usage::NamespaceStorageUsage;
+use crate::service::NamespaceIdent;
+use crate::WarehouseIdent;
+
+pub(crate) async fn refresh_storage_usage(catalog_state: CatalogState) -> Result<u64> {
+    // Tables without a current snapshot are empty.
+    let result = sqlx::query!(
+        r#"
+        WITH table_size AS (
+            SELECT
+                t.namespace_id,
+                CASE
+                    WHEN coalesce((t.metadata->>'current-snapshot-id')::bigint, -1) = -1 THEN 0
+                    ELSE (
+                        SELECT (s->'summary'->>'total-files-size')::bigint
+                        FROM jsonb_array_elements(t.metadata->'snapshots') s
+                        WHERE (s->>'snapshot-id')::bigint = (t.metadata->>'current-snapshot-id')::bigint
+                    )
+                END AS size
+            FROM "table" t
+            WHERE t.metadata_location IS NOT NULL
+        )
+        INSERT INTO storage_usage
+            (namespace_id, warehouse_id, table_count, total_files_size_bytes, tables_without_size, computed_at)
+        SELECT
+            n.namespace_id,
+            n.warehouse_id,
+            count(ts.namespace_id),
+            coalesce(sum(ts.size), 0)::bigint,
+            count(ts.namespace_id) FILTER (WHERE ts.size IS NULL),
+            now()
+        FROM namespace n
+        LEFT JOIN table_size ts ON ts.namespace_id = n.namespace_id
+        GROUP BY n.namespace_id, n.warehouse_id
+        ON CONFLICT (namespace_id) DO UPDATE SET
+            table_count = EXCLUDED.table_count,
+            total_files_size_bytes = EXCLUDED.total_files_size_bytes,
+            tables_without_size = EXCLUDED.tables_without_size,
+            computed_at = EXCLUDED.computed_at
+        "#
+    )
+    .execute(&catalog_state.write_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error refreshing storage usage".into()))?;
+
+    Ok(result.rows_affected())
+}
+
+pub(crate) async fn list_storage_usage(
+    warehouse_id: &WarehouseIdent,
+    catalog_state: CatalogState,
+) -> Result<Vec<NamespaceStorageUsage>> {
+    let usage = sqlx::query!(
+        r#"
+        SELECT
+            n.namespace_name,
+            u.table_count,
+            u.total_files_size_bytes,
+            u.tables_without_size,
+            u.computed_at
+        FROM storage_usage u
+        INNER JOIN namespace n ON n.namespace_id = u.namespace_id
+        WHERE u.warehouse_id = $1
+        ORDER BY n.namespace_name
+        "#,
+        warehouse_id.as_uuid()
+    )
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching storage usage".into()))?;
+
+    usage
+        .into_iter()
+        .map(|usage| {
+            Ok(NamespaceStorageUsage {
+                warehouse_id: warehouse_id.clone(),
+                namespace: NamespaceIdent::from_vec(usage.namespace_name)
+                    .map_err(|e| e.into_error_model("Error parsing namespace name".to_string()))?,
+                table_count: usage.table_count,
+                total_files_size_bytes: usage.total_files_size_bytes,
+                tables_without_size: usage.tables_without_size,
+                computed_at: usage.computed_at,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::table::tests::initialize_table;
+    use super::super::warehouse::test::initialize_warehouse;
+    use super::*;
+
+    #[sqlx::test]
+    async fn test_storage_usage(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let table = initialize_table(&warehouse_id, state.clone(), false).await;
+        let staged = initialize_table(&warehouse_id, state.clone(), true).await;
+
+        assert!(list_storage_usage(&warehouse_id, state.clone())
+            .await
+            .unwrap()
+            .is_empty());
+
+        let refreshed = refresh_storage_usage(state.clone()).await.unwrap();
+        assert_eq!(refreshed, 2);
+
+        let usage = list_storage_usage(&warehouse_id, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(usage.len(), 2);
+        let table_usage = usage
+            .iter()
+            .find(|u| u.namespace == table.namespace)
+            .unwrap();
+        // Tables without a snapshot are empty.
+        assert_eq!(table_usage.table_count, 1);
+        assert_eq!(table_usage.total_files_size_bytes, 0);
+        assert_eq!(table_usage.tables_without_size, 0);
+        let staged_usage = usage
+            .iter()
+            .find(|u| u.namespace == staged.namespace)
+            .unwrap();
+        assert_eq!(staged_usage.table_count, 0);
+    }
+}
@@ -0,0 +1,212 @@
+//! The stores of [`crate::service::stores`], backed by the catalog database.
+//!
+//! Data of warehouses and projects is kept with the catalog tables of the project,
+//! see [`super::project_schema`]. Revocations and dead letters are not bound to a project.
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    api_usage::{list_api_usage, record_api_usage},
+    client_usage::{list_client_usage, record_client_usage},
+    role_assignment::{get_roles, grant_role, list_role_assignments, revoke_role},
+    storage_usage::{list_storage_usage, refresh_storage_usage},
+    table::record_lineage_events,
+    token_revocation::{list_active_token_revocations, revoke_token},
+    webhook::record_webhook_dead_letter,
+    CatalogState, PostgresTransaction,
+};
+use crate::service::{
+    api_usage::{ApiUsage, ApiUsageStore, UsageWindow},
+    client_usage::{ClientInfo, ClientUsage, ClientUsageStore},
+    lineage::{LineageEvent, LineageStore},
+    roles::RoleStore,
+    storage_usage::{NamespaceStorageUsage, StorageUsageStore},
+    token_revocation::{RevocationStore, TokenRevocation},
+    webhook::{WebhookDeadLetter, WebhookDeadLetterStore},
+    Result, Role, RoleAssignment, RoleObject, RoleTarget, TableIdentUuid, Transaction,
+};
+use crate::{ProjectIdent, WarehouseIdent};
+
+/// Route the rest of `transaction` to the project of `object`.
+async fn route_to_object(transaction: &mut PostgresTransaction, object: &RoleObject) -> Result<()> {
+    match object {
+        RoleObject::Project(project_id) => transaction.route_to_project(project_id).await,
+        RoleObject::Warehouse(warehouse_id)
+        | RoleObject::Namespace(warehouse_id, _)
+        | RoleObject::Table(warehouse_id, _) => transaction.route_to_warehouse(warehouse_id).await,
+    }
+}
+
+#[async_trait::async_trait]
+impl RoleStore for CatalogState {
+    async fn grant_role(
+        &self,
+        object: &RoleObject,
+        principal: &str,
+        role: Role,
+        created_by: Option<&str>,
+    ) -> Result<uuid::Uuid> {
+        let mut transaction = PostgresTransaction::begin_write(self.clone()).await?;
+        route_to_object(&mut transaction, object).await?;
+        let assignment_id = grant_role(
+            object,
+            principal,
+            role,
+            created_by,
+            transaction.transaction(),
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(assignment_id)
+    }
+
+    async fn revoke_role(&self, object: &RoleObject, principal: &str) -> Result<()> {
+        let mut transaction = PostgresTransaction::begin_write(self.clone()).await?;
+        route_to_object(&mut transaction, object).await?;
+        revoke_role(object, principal, transaction.transaction()).await?;
+        transaction.commit().await
+    }
+
+    async fn list_role_assignments(
+        &self,
+        project_id: Option<&ProjectIdent>,
+        principal: Option<&str>,
+    ) -> Result<Vec<RoleAssignment>> {
+        if let Some(project_id) = project_id {
+            return list_role_assignments(
+                Some(project_id),
+                principal,
+                self.for_project(project_id),
+            )
+            .await;
+        }
+
+        let mut assignments = vec![];
+        for state in self.project_states().await? {
+            assignments.extend(list_role_assignments(None, principal, state).await?);
+        }
+        Ok(assignments)
+    }
+
+    async fn get_roles(&self, principal: &str, target: &RoleTarget) -> Result<Vec<Role>> {
+        let state = match target {
+            RoleTarget::Project(project_id) => self.for_project(project_id),
+            RoleTarget::Warehouse(warehouse_id)
+            | RoleTarget::Namespace(warehouse_id, _)
+            | RoleTarget::Table(warehouse_id, _) => self.for_warehouse(warehouse_id).await?,
+        };
+        get_roles(principal, target, state).await
+    }
+}
+
+#[async_trait::async_trait]
+impl RevocationStore for CatalogState {
+    async fn revoke_token(&self, revocation: &TokenRevocation) -> Result<()> {
+        let mut transaction = PostgresTransaction::begin_write(self.clone()).await?;
+        revoke_token(revocation, transaction.transaction()).await?;
+        transaction.commit().await
+    }
+
+    async fn list_active_revocations(&self) -> Result<Vec<TokenRevocation>> {
+        list_active_token_revocations(self.clone()).await
+    }
+}
+
+#[async_trait::async_trait]
+impl ClientUsageStore for CatalogState {
+    async fn record_client_usage(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        client: &ClientInfo,
+    ) -> Result<()> {
+        record_client_usage(
+            warehouse_id,
+            client,
+            self.for_warehouse(warehouse_id).await?,
+        )
+        .await
+    }
+
+    async fn list_client_usage(&self, warehouse_id: &WarehouseIdent) -> Result<Vec<ClientUsage>> {
+        list_client_usage(warehouse_id, self.for_warehouse(warehouse_id).await?).await
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiUsageStore for CatalogState {
+    async fn record_api_usage(&self, usage: Vec<ApiUsage>) -> Result<()> {
+        if !self.is_schema_per_project() {
+            return record_api_usage(usage, self.clone()).await;
+        }
+
+        let mut usage_by_warehouse = HashMap::<_, Vec<_>>::new();
+        for usage in usage {
+            usage_by_warehouse
+                .entry(usage.warehouse_id.clone())
+                .or_default()
+                .push(usage);
+        }
+        for (warehouse_id, usage) in usage_by_warehouse {
+            record_api_usage(usage, self.for_warehouse(&warehouse_id).await?).await?;
+        }
+        Ok(())
+    }
+
+    async fn list_api_usage(
+        &self,
+        project_id: &ProjectIdent,
+        warehouse_id_filter: Option<&HashSet<WarehouseIdent>>,
+        window: UsageWindow,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<ApiUsage>> {
+        list_api_usage(
+            project_id,
+            warehouse_id_filter,
+            window,
+            from,
+            to,
+            self.for_project(project_id),
+        )
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageUsageStore for CatalogState {
+    async fn refresh_storage_usage(&self) -> Result<u64> {
+        let mut updated = 0;
+        for state in self.project_states().await? {
+            updated += refresh_storage_usage(state).await?;
+        }
+        Ok(updated)
+    }
+
+    async fn list_storage_usage(
+        &self,
+        warehouse_id: &WarehouseIdent,
+    ) -> Result<Vec<NamespaceStorageUsage>> {
+        list_storage_usage(warehouse_id, self.for_warehouse(warehouse_id).await?).await
+    }
+}
+
+#[async_trait::async_trait]
+impl LineageStore for CatalogState {
+    async fn record_lineage_events(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        table_id: TableIdentUuid,
+        events: &[LineageEvent],
+    ) -> Result<()> {
+        let mut transaction = PostgresTransaction::begin_write(self.clone()).await?;
+        transaction.route_to_warehouse(warehouse_id).await?;
+        record_lineage_events(table_id, events, transaction.transaction()).await?;
+        transaction.commit().await
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookDeadLetterStore for CatalogState {
+    async fn record_webhook_dead_letter(&self, dead_letter: &WebhookDeadLetter) -> Result<()> {
+        record_webhook_dead_letter(dead_letter, self.clone()).await
+    }
+}
@@ -1,21 +1,25 @@
 use super::{dbutils::DBErrorHandler as _, CatalogState};
 use crate::{
+    implementations::commit::{
+        assert_all_updated, commit_contexts, create_table_metadata, dropped_table_not_found,
+        lock_order, merge_metadata_log, prepare_commits, serialize_metadata, update_order,
+        CommitContext, CommitRow,
+    },
     service::{
-        storage::StorageProfile, CommitTableResponse, CommitTableResponseExt,
-        CommitTransactionRequest, CreateTableRequest, CreateTableResponse, ErrorModel,
-        GetStorageConfigResponse, GetTableMetadataResponse, LoadTableResponse, NamespaceIdentUuid,
-        Result, TableIdent, TableIdentUuid,
+        lineage::LineageEvent,
+        page_token::PageQuery,
+        storage::{AccessDelegation, StorageProfile},
+        CommitTableResponseExt, CommitTransactionRequest, CreateTableRequest, CreateTableResponse,
+        DroppedTable, ErrorModel, ErrorType, GetTableMetadataResponse, LoadTableResponse,
+        LoadedTable, MetadataLogEntry, NamespaceIdentUuid, Result, RetentionCandidate,
+        SnapshotRetentionPolicy, StagedTable, TableCommit, TableIdent, TableIdentUuid, TablePolicy,
     },
     SecretIdent, WarehouseIdent,
 };
 
 use http::StatusCode;
-use iceberg_ext::{
-    spec::{TableMetadata, TableMetadataAggregate},
-    NamespaceIdent, TableRequirement, TableUpdate,
-};
+use iceberg_ext::{spec::TableMetadata, NamespaceIdent};
 
-use crate::api::{TableRequirementExt as _, TableUpdateExt};
 use sqlx::{types::Json, Row};
 use std::default::Default;
 use std::{
@@ -71,6 +75,66 @@ where
     }
 }
 
+/// Only reads the index on the table name, the metadata is never touched.
+pub(crate) async fn table_exists<'e, 'c: 'e, E>(
+    warehouse_id: &WarehouseIdent,
+    table: &TableIdent,
+    include_staged: bool,
+    catalog_state: E,
+) -> Result<bool>
+where
+    E: 'e + sqlx::Executor<'c, Database = sqlx::Postgres>,
+{
+    let TableIdent { namespace, name } = table;
+
+    // Separate queries, so that the planner can use the partial index for non-staged tables.
+    let exists = if include_staged {
+        sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1
+                FROM "table" t
+                INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+                INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+                WHERE n.namespace_name = $1 AND t.table_name = $2
+                AND n.warehouse_id = $3
+                AND w.status = 'active'
+            ) as "exists!"
+            "#,
+            &**namespace,
+            &**name,
+            warehouse_id.as_uuid()
+        )
+        .fetch_one(catalog_state)
+        .await
+    } else {
+        sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1
+                FROM "table" t
+                INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+                INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+                WHERE n.namespace_name = $1 AND t.table_name = $2
+                AND n.warehouse_id = $3
+                AND w.status = 'active'
+                AND t.metadata_location IS NOT NULL
+            ) as "exists!"
+            "#,
+            &**namespace,
+            &**name,
+            warehouse_id.as_uuid()
+        )
+        .fetch_one(catalog_state)
+        .await
+    };
+
+    exists.map_err(|e| {
+        e.into_error_model("Error checking if table exists".to_string())
+            .into()
+    })
+}
+
 pub(crate) async fn table_idents_to_ids<'e, 'c: 'e, E>(
     warehouse_id: &WarehouseIdent,
     tables: HashSet<&TableIdent>,
@@ -168,6 +232,81 @@ where
     Ok(table_map)
 }
 
+pub(crate) async fn load_tables<'e, 'c: 'e, E>(
+    warehouse_id: &WarehouseIdent,
+    tables: HashSet<&TableIdent>,
+    catalog_state: E,
+) -> Result<Vec<LoadedTable>>
+where
+    E: 'e + sqlx::Executor<'c, Database = sqlx::Postgres>,
+{
+    if tables.is_empty() {
+        return Ok(vec![]);
+    }
+
+    if tables.len() > (MAX_PARAMETERS / 2) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("Too many tables to fetch".to_string())
+            .r#type("TooManyTables".to_string())
+            .build()
+            .into());
+    }
+
+    let mut query_builder = sqlx::QueryBuilder::new(
+        r#"
+        SELECT t."table_id", n.namespace_name as "namespace", t.table_name, t."metadata", t."metadata_location"
+        FROM "table" t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE w.status = 'active' AND t."metadata_location" IS NOT NULL AND n."warehouse_id" = "#,
+    );
+    query_builder.push_bind(warehouse_id.as_uuid());
+    query_builder.push(r" AND (n.namespace_name, t.table_name) IN (");
+    let mut separated = query_builder.separated(", ");
+    for TableIdent { namespace, name } in &tables {
+        separated.push("(");
+        separated.push_bind_unseparated(namespace.clone().inner());
+        separated.push_unseparated(", ");
+        separated.push_bind_unseparated(name);
+        separated.push_unseparated(")");
+    }
+    query_builder.push(")");
+
+    let rows = query_builder
+        .build()
+        .fetch_all(catalog_state)
+        .await
+        .map_err(|e| e.into_error_model("Error fetching tables".to_string()))?;
+
+    rows.into_iter()
+        .map(|row| {
+            let namespace = NamespaceIdent::from_vec(row.get::<Vec<String>, _>("namespace"))
+                .map_err(|e| {
+                    ErrorModel::builder()
+                        .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                        .message("Error parsing namespace".to_string())
+                        .r#type("NamespaceParseError".to_string())
+                        .stack(Some(vec![e.to_string()]))
+                        .build()
+                })?;
+            let metadata = row
+                .try_get::<Json<TableMetadata>, _>("metadata")
+                .map_err(|e| e.into_error_model("Error parsing table metadata".to_string()))?;
+
+            Ok(LoadedTable {
+                table_id: row.get::<uuid::Uuid, _>("table_id").into(),
+                table: TableIdent {
+                    namespace,
+                    name: row.get::<String, _>("table_name"),
+                },
+                metadata_location: row.get::<String, _>("metadata_location"),
+                table_metadata: metadata.0,
+            })
+        })
+        .collect()
+}
+
 pub(crate) async fn create_table(
     namespace_id: &NamespaceIdentUuid,
     table: &TableIdent,
@@ -175,59 +314,19 @@ pub(crate) async fn create_table(
     request: CreateTableRequest,
     // Metadata location may be none if stage-create is true
     metadata_location: Option<&String>,
+    owner: Option<&str>,
     transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
 ) -> Result<CreateTableResponse> {
     let TableIdent { namespace: _, name } = table;
-    let CreateTableRequest {
-        name: _,
-        location,
-        schema,
-        partition_spec,
-        write_order,
-        // Stage-create is already handled in the catalog service.
-        // If stage-create is true, the metadata_location is None,
-        // otherwise, it is the location of the metadata file.
-        stage_create: _,
-        properties,
-    } = request;
-
-    let location = location.ok_or_else(|| {
-        ErrorModel::builder()
-            .code(StatusCode::CONFLICT.into())
-            .message("Table location is required".to_string())
-            .r#type("CreateTableLocationRequired".to_string())
-            .build()
-    })?;
-
-    let mut builder = TableMetadataAggregate::new(location.clone(), schema);
-    if let Some(partition_spec) = partition_spec {
-        builder.add_partition_spec(partition_spec)?;
-        builder.set_default_partition_spec(-1)?;
-    }
-    if let Some(write_order) = write_order {
-        builder.add_sort_order(write_order)?;
-        builder.set_default_sort_order(-1)?;
-    }
-    builder.set_properties(properties.unwrap_or_default())?;
-    builder.assign_uuid(table_id.as_uuid().to_owned())?;
-
-    let table_metadata = builder.build()?;
-
-    let table_metadata_ser = serde_json::to_value(table_metadata.clone()).map_err(|e| {
-        ErrorModel::builder()
-            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
-            .message("Error serializing table metadata".to_string())
-            .r#type("TableMetadataSerializationError".to_string())
-            .stack(Some(vec![e.to_string()]))
-            .build()
-    })?;
+    let table_metadata = create_table_metadata(table_id, request)?;
+    let table_metadata_ser = serialize_metadata(&table_metadata)?;
 
     // ToDo: Should we keep the old table_id?
     let _update_result = sqlx::query!(
         r#"
-        INSERT INTO "table" (table_id, namespace_id, "table_name", "metadata", "metadata_location", "table_location")
+        INSERT INTO "table" (table_id, namespace_id, "table_name", "metadata", "metadata_location", "table_location", created_by, "owner")
         (
-            SELECT $1, $2, $3, $4, $5, $6
+            SELECT $1, $2, $3, $4, $5, $6, $7, $7
             WHERE EXISTS (
                 SELECT 1
                 FROM warehouse w
@@ -235,7 +334,7 @@ pub(crate) async fn create_table(
                 WHERE n.namespace_id = $2 AND w.status = 'active'
         ))
         ON CONFLICT ON CONSTRAINT unique_table_name_per_namespace
-        DO UPDATE SET table_id= $1, "metadata" = $4, "metadata_location" = $5, "table_location" = $6
+        DO UPDATE SET table_id= $1, "metadata" = $4, "metadata_location" = $5, "table_location" = $6, created_by = $7, "owner" = $7, "metadata_version" = "table"."metadata_version" + 1
         WHERE "table"."metadata_location" IS NULL
         RETURNING "table_id"
         "#,
@@ -244,7 +343,8 @@ pub(crate) async fn create_table(
         name,
         table_metadata_ser,
         metadata_location,
-        location
+        table_metadata.location,
+        owner
     )
     .fetch_one(&mut **transaction)
     .await
@@ -254,6 +354,7 @@ pub(crate) async fn create_table(
                 .code(StatusCode::CONFLICT.into())
                 .message("Table already exists in Namespace".to_string())
                 .r#type("TableAlreadyExists".to_string())
+                .error_type(ErrorType::AlreadyExists)
                 .build(),
         _ => e.as_error_model("Error creating table".to_string()),
     }})?;
@@ -276,7 +377,9 @@ pub(crate) async fn load_table(
             t."metadata" as "metadata: Json<TableMetadata>",
             t."metadata_location",
             w.storage_profile as "storage_profile: Json<StorageProfile>",
-            w."storage_secret_id"
+            w."storage_secret_id",
+            n.namespace_properties as "namespace_properties: Json<HashMap<String, String>>",
+            t."policy" as "policy: Json<TablePolicy>"
         FROM "table" t
         INNER JOIN namespace n ON t.namespace_id = n.namespace_id
         INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
@@ -295,6 +398,7 @@ pub(crate) async fn load_table(
             .code(StatusCode::NOT_FOUND.into())
             .message("Table not found".to_string())
             .r#type("NoSuchTableError".to_string())
+            .error_type(ErrorType::NoSuchTable)
             .build(),
         _ => e.into_error_model("Error fetching table".to_string()),
     })?;
@@ -306,15 +410,82 @@ pub(crate) async fn load_table(
         metadata_location: table.metadata_location,
         storage_secret_ident: table.storage_secret_id.map(SecretIdent::from),
         storage_profile: table.storage_profile.deref().clone(),
+        access_delegation: AccessDelegation::resolve(
+            Some(table.metadata.properties()),
+            Some(&table.namespace_properties),
+        ),
+        policy: table.policy.map(|p| p.0),
     })
 }
 
+pub(crate) async fn set_table_policy(
+    warehouse_id: &WarehouseIdent,
+    table: &TableIdent,
+    policy: Option<TablePolicy>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let TableIdent { namespace, name } = table;
+
+    let policy_ser = policy
+        .map(|p| {
+            serde_json::to_value(p).map_err(|e| {
+                ErrorModel::builder()
+                    .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                    .message("Error serializing table policy".to_string())
+                    .r#type("TablePolicySerializationError".to_string())
+                    .stack(Some(vec![e.to_string()]))
+                    .build()
+            })
+        })
+        .transpose()?;
+
+    let row_count = sqlx::query_scalar!(
+        r#"
+        with update as (
+            UPDATE "table" t
+            SET policy = $1
+            FROM namespace n, warehouse w
+            WHERE t.namespace_id = n.namespace_id
+            AND n.warehouse_id = w.warehouse_id
+            AND w.warehouse_id = $2 AND n.namespace_name = $3 AND t.table_name = $4
+            AND w.status = 'active'
+            RETURNING t.table_id
+        )
+
+        SELECT count(*) FROM update
+        "#,
+        policy_ser,
+        warehouse_id.as_uuid(),
+        &**namespace,
+        &**name
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error setting table policy".into()))?;
+
+    if row_count == Some(0) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Table not found".to_string())
+            .r#type("NoSuchTableError".to_string())
+            .error_type(ErrorType::NoSuchTable)
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
 pub(crate) async fn list_tables(
     warehouse_id: &WarehouseIdent,
     namespace: &NamespaceIdent,
     include_staged: bool,
+    page: &PageQuery,
     catalog_state: CatalogState,
-) -> Result<HashMap<TableIdentUuid, TableIdent>> {
+) -> Result<Vec<(TableIdentUuid, TableIdent)>> {
+    let limit = page
+        .fetch_limit()
+        .map(|limit| i64::try_from(limit).unwrap_or(i64::MAX));
     let tables = sqlx::query!(
         r#"
         SELECT
@@ -328,18 +499,23 @@ pub(crate) async fn list_tables(
             AND namespace_name = $2
             AND w.status = 'active'
             AND (t."metadata_location" IS NOT NULL OR $3)
+            AND ($4::text IS NULL OR table_name > $4)
+        ORDER BY table_name
+        LIMIT $5
         "#,
         warehouse_id.as_uuid(),
         &**namespace,
-        include_staged
+        include_staged,
+        page.after,
+        limit
     )
     .fetch_all(&catalog_state.read_pool)
     .await
     .map_err(|e| e.into_error_model("Error fetching tables".to_string()))?;
 
-    let mut table_map = HashMap::new();
+    let mut table_list = Vec::with_capacity(tables.len());
     for table in tables {
-        table_map.insert(
+        table_list.push((
             table.table_id.into(),
             TableIdent {
                 namespace: NamespaceIdent::from_vec(table.namespace_name).map_err(|e| {
@@ -352,10 +528,10 @@ pub(crate) async fn list_tables(
                 })?,
                 name: table.table_name,
             },
-        );
+        ));
     }
 
-    Ok(table_map)
+    Ok(table_list)
 }
 
 pub(crate) async fn get_table_metadata_by_id(
@@ -374,7 +550,8 @@ pub(crate) async fn get_table_metadata_by_id(
             t."metadata" as "metadata: Json<TableMetadata>",
             t."metadata_location",
             w.storage_profile as "storage_profile: Json<StorageProfile>",
-            w."storage_secret_id"
+            w."storage_secret_id",
+            n.namespace_properties as "namespace_properties: Json<HashMap<String, String>>"
         FROM "table" t
         INNER JOIN namespace n ON t.namespace_id = n.namespace_id
         INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
@@ -391,6 +568,7 @@ pub(crate) async fn get_table_metadata_by_id(
             .code(StatusCode::NOT_FOUND.into())
             .message("Table not found".to_string())
             .r#type("NoSuchTableError".to_string())
+            .error_type(ErrorType::NoSuchTable)
             .build(),
         _ => e.into_error_model("Error fetching table".to_string()),
     })?;
@@ -400,6 +578,7 @@ pub(crate) async fn get_table_metadata_by_id(
             .code(StatusCode::NOT_FOUND.into())
             .message("Table is staged and not yet created".to_string())
             .r#type("TableStaged".to_string())
+            .error_type(ErrorType::NoSuchTable)
             .build()
             .into());
     }
@@ -424,6 +603,10 @@ pub(crate) async fn get_table_metadata_by_id(
         metadata_location: table.metadata_location,
         storage_secret_ident: table.storage_secret_id.map(SecretIdent::from),
         storage_profile: table.storage_profile.deref().clone(),
+        access_delegation: AccessDelegation::resolve(
+            Some(table.metadata.properties()),
+            Some(&table.namespace_properties),
+        ),
     })
 }
 
@@ -445,7 +628,8 @@ pub(crate) async fn get_table_metadata_by_s3_location(
             t."metadata" as "metadata: Json<TableMetadata>",
             t."metadata_location",
             w.storage_profile as "storage_profile: Json<StorageProfile>",
-            w."storage_secret_id"
+            w."storage_secret_id",
+            n.namespace_properties as "namespace_properties: Json<HashMap<String, String>>"
         FROM "table" t
         INNER JOIN namespace n ON t.namespace_id = n.namespace_id
         INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
@@ -465,6 +649,7 @@ pub(crate) async fn get_table_metadata_by_s3_location(
             .code(StatusCode::NOT_FOUND.into())
             .message("Table not found".to_string())
             .r#type("NoSuchTableError".to_string())
+            .error_type(ErrorType::NoSuchTable)
             .stack(Some(vec![
                 location.to_string(),
                 format!("Warehouse: {}", warehouse_id),
@@ -478,6 +663,7 @@ pub(crate) async fn get_table_metadata_by_s3_location(
             .code(StatusCode::NOT_FOUND.into())
             .message("Table is staged and not yet created".to_string())
             .r#type("TableStaged".to_string())
+            .error_type(ErrorType::NoSuchTable)
             .build()
             .into());
     }
@@ -502,992 +688,2070 @@ pub(crate) async fn get_table_metadata_by_s3_location(
         metadata_location: table.metadata_location,
         storage_secret_ident: table.storage_secret_id.map(SecretIdent::from),
         storage_profile: table.storage_profile.deref().clone(),
+        access_delegation: AccessDelegation::resolve(
+            Some(table.metadata.properties()),
+            Some(&table.namespace_properties),
+        ),
     })
 }
 
 /// Rename a table. Tables may be moved across namespaces.
-pub(crate) async fn rename_table(
+pub(crate) async fn get_table_metadata_log(
     warehouse_id: &WarehouseIdent,
-    source_id: &TableIdentUuid,
-    source: &TableIdent,
-    destination: &TableIdent,
-    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
-) -> Result<()> {
-    let TableIdent {
-        namespace: source_namespace,
-        name: source_name,
-    } = source;
-    let TableIdent {
-        namespace: dest_namespace,
-        name: dest_name,
-    } = destination;
-
-    if source_namespace == dest_namespace {
-        let _ = sqlx::query_scalar!(
-            r#"
-            UPDATE "table"
-            SET table_name = $1
-            WHERE table_id = $2
-            AND $3 IN (
-                SELECT warehouse_id FROM warehouse WHERE status = 'active'
-            )
-            RETURNING table_id
-            "#,
-            &**dest_name,
-            source_id.as_uuid(),
-            warehouse_id.as_uuid(),
-        )
-        .fetch_one(&mut **transaction)
-        .await
-        .map_err(|e| match e {
-            sqlx::Error::RowNotFound => ErrorModel::builder()
-                .code(StatusCode::NOT_FOUND.into())
-                .message("ID of Table to rename not found".to_string())
-                .r#type("RenameTableIdNotFound".to_string())
-                .build(),
-            _ => e.into_error_model("Error renaming table".to_string()),
-        })?;
-    } else {
-        let _ = sqlx::query_scalar!(
-            r#"
-            UPDATE "table"
-            SET table_name = $1, "namespace_id" = (
-                SELECT namespace_id
-                FROM namespace
-                WHERE warehouse_id = $2 AND namespace_name = $3
-            )
-            WHERE "table_id" = $4
-            AND table_name = $5
-            AND $2 IN (
-                SELECT warehouse_id FROM warehouse WHERE status = 'active'
-            )
-            RETURNING "table_id"
-            "#,
-            &**dest_name,
-            warehouse_id.as_uuid(),
-            &**dest_namespace,
-            source_id.as_uuid(),
-            &**source_name,
-        )
-        .fetch_one(&mut **transaction)
-        .await
-        .map_err(|e| match e {
-            sqlx::Error::RowNotFound => ErrorModel::builder()
-                .code(StatusCode::NOT_FOUND.into())
-                .message(
-                    "ID of Table to rename not found or destination namespace not found"
-                        .to_string(),
-                )
-                .r#type("RenameTableIdOrNamespaceNotFound".to_string())
-                .build(),
-            _ => e.into_error_model("Error renaming Table".to_string()),
-        })?;
-    };
-
-    Ok(())
-}
-
-// ToDo: Switch to a soft delete
-pub(crate) async fn drop_table<'a>(
-    _: &WarehouseIdent,
     table_id: &TableIdentUuid,
-    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
-) -> Result<()> {
-    let _ = sqlx::query!(
+    catalog_state: CatalogState,
+) -> Result<Vec<MetadataLogEntry>> {
+    let table = sqlx::query!(
         r#"
-        DELETE FROM "table"
-        WHERE "table_id" = $1
-        AND "namespace_id" IN (
-            SELECT "namespace_id"
-            FROM namespace
-            WHERE "warehouse_id" IN (
-                SELECT "warehouse_id"
-                FROM warehouse
-                WHERE status = 'active'
-            )
-        )
-        RETURNING "table_id"
+        SELECT
+            t."metadata" as "metadata: Json<TableMetadata>"
+        FROM "table" t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE w.warehouse_id = $1 AND t."table_id" = $2
+        AND w.status = 'active'
+        AND t."metadata_location" IS NOT NULL
         "#,
+        warehouse_id.as_uuid(),
         table_id.as_uuid()
     )
-    .fetch_one(&mut **transaction)
+    .fetch_optional(&catalog_state.read_pool)
     .await
-    .map_err(|e| match e {
-        sqlx::Error::RowNotFound => ErrorModel::builder()
+    .map_err(|e| e.into_error_model("Error fetching table".to_string()))?
+    .ok_or_else(|| {
+        ErrorModel::builder()
             .code(StatusCode::NOT_FOUND.into())
             .message("Table not found".to_string())
             .r#type("NoSuchTableError".to_string())
-            .build(),
-        _ => e.into_error_model("Error dropping table".to_string()),
+            .error_type(ErrorType::NoSuchTable)
+            .build()
     })?;
 
-    Ok(())
-}
-
-#[derive(Debug)]
-struct CommitContext {
-    requirements: Vec<TableRequirement>,
-    updates: Vec<TableUpdate>,
-    storage_profile: StorageProfile,
-    storage_secret_ident: Option<SecretIdent>,
-    #[allow(dead_code)]
-    namespace_id: NamespaceIdentUuid,
-    metadata: TableMetadata,
-    metadata_location: Option<String>,
+    let history = sqlx::query!(
+        r#"
+        SELECT
+            metadata_location,
+            (extract(epoch from created_at) * 1000)::bigint as "timestamp_ms!"
+        FROM table_metadata_log
+        WHERE table_id = $1
+        "#,
+        table_id.as_uuid()
+    )
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching table metadata log".to_string()))?;
+
+    Ok(merge_metadata_log(
+        history
+            .into_iter()
+            .map(|r| (r.metadata_location, r.timestamp_ms)),
+        &table.metadata.0,
+    ))
 }
 
-async fn get_commit_context<'a>(
-    request: CommitTransactionRequest,
-    table_ids: &HashMap<TableIdent, TableIdentUuid>,
-    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
-) -> Result<Vec<CommitContext>> {
-    let CommitTransactionRequest { table_changes } = request;
-
-    let metadata = sqlx::query!(
+pub(crate) async fn list_table_commits(
+    warehouse_id: &WarehouseIdent,
+    table_id: &TableIdentUuid,
+    before: Option<chrono::DateTime<chrono::Utc>>,
+    limit: i64,
+    catalog_state: CatalogState,
+) -> Result<Vec<TableCommit>> {
+    let exists = sqlx::query!(
         r#"
-        SELECT 
-            t."table_id", 
-            t."metadata" as "metadata: Json<TableMetadata>", 
-            t."metadata_location",
-            w.storage_profile as "storage_profile: Json<StorageProfile>",
-            w."storage_secret_id",
-            n.namespace_id
+        SELECT t."table_id"
         FROM "table" t
         INNER JOIN namespace n ON t.namespace_id = n.namespace_id
         INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
-        WHERE "table_id" = ANY($1)
+        WHERE w.warehouse_id = $1 AND t."table_id" = $2
         AND w.status = 'active'
+        AND t."metadata_location" IS NOT NULL
         "#,
-        &table_ids
-            .values()
-            .map(|id| id.as_uuid().clone())
-            .collect::<Vec<_>>()
+        warehouse_id.as_uuid(),
+        table_id.as_uuid()
     )
-    .fetch_all(&mut **transaction)
+    .fetch_optional(&catalog_state.read_pool)
     .await
-    .map_err(|e| e.into_error_model("Error fetching table metadata for commit".to_string()))?;
-
-    let mut contexts = vec![];
-    for change in table_changes {
-        let table_ident = change.identifier.ok_or(
-            ErrorModel::builder()
-                .code(StatusCode::BAD_REQUEST.into())
-                .message("Table identifier must be specified for all changes".to_string())
-                .r#type("TableIdentifierRequired".to_string())
-                .build(),
-        )?;
-        let table_id = table_ids.get(&table_ident).ok_or_else(|| {
-            ErrorModel::builder()
-                .code(StatusCode::BAD_REQUEST.into())
-                .message("Table identifier not found".to_string())
-                .r#type("TableIdentifierNotFound".to_string())
-                .stack(Some(vec![format!("{:?}", table_ident)]))
-                .build()
-        })?;
-
-        let record = metadata
-            .iter()
-            .find(|m| &m.table_id == table_id.as_uuid())
-            .ok_or_else(|| {
-                ErrorModel::builder()
-                    .code(StatusCode::NOT_FOUND.into())
-                    .message("Table not found".to_string())
-                    .r#type("NoSuchTableError".to_string())
-                    .stack(Some(vec![format!("Table Ident {:?}", table_ident)]))
-                    .build()
-            })?;
+    .map_err(|e| e.into_error_model("Error fetching table".to_string()))?;
 
-        contexts.push(CommitContext {
-            requirements: change.requirements,
-            updates: change.updates,
-            storage_profile: record.storage_profile.deref().clone(),
-            metadata: record.metadata.deref().clone(),
-            metadata_location: record.metadata_location.clone(),
-            storage_secret_ident: record.storage_secret_id.map(SecretIdent::from),
-            namespace_id: record.namespace_id.into(),
-        });
+    if exists.is_none() {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Table not found".to_string())
+            .r#type("NoSuchTableError".to_string())
+            .error_type(ErrorType::NoSuchTable)
+            .build()
+            .into());
     }
 
-    Ok(contexts)
+    let commits = sqlx::query!(
+        r#"
+        SELECT
+            metadata_location,
+            committed_at,
+            committed_by,
+            snapshot_id,
+            operation
+        FROM table_commit
+        WHERE table_id = $1
+        AND ($2::timestamptz IS NULL OR committed_at < $2)
+        ORDER BY committed_at DESC, metadata_location DESC
+        LIMIT $3
+        "#,
+        table_id.as_uuid(),
+        before,
+        limit
+    )
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching table commits".to_string()))?;
+
+    Ok(commits
+        .into_iter()
+        .map(|commit| TableCommit {
+            table_id: *table_id,
+            committed_at: commit.committed_at,
+            committed_by: commit.committed_by,
+            snapshot_id: commit.snapshot_id,
+            operation: commit.operation,
+            metadata_location: commit.metadata_location,
+        })
+        .collect())
 }
 
-fn apply_commits(commits: Vec<CommitContext>) -> Result<Vec<CommitTableResponseExt>> {
-    // ToDo: Set default snapshot retention
-    let mut responses = vec![];
-    for context in commits {
-        let previous_location = context.metadata.location.clone();
-        let previous_uuid = context.metadata.uuid();
-        let metadata_id = uuid::Uuid::now_v7();
-        let metadata_location = context
-            .storage_profile
-            .metadata_location(&previous_location, &metadata_id);
-        let previous_table_metadata = context.metadata.clone();
-        let mut builder = TableMetadataAggregate::new_from_metadata(context.metadata);
-        for update in context.updates {
-            match &update {
-                TableUpdate::AssignUuid { uuid } => {
-                    if uuid != &previous_uuid {
-                        return Err(ErrorModel::builder()
-                            .code(StatusCode::BAD_REQUEST.into())
-                            .message("Cannot assign a new UUID".to_string())
-                            .r#type("AssignUuidNotAllowed".to_string())
-                            .build()
-                            .into());
-                    }
-                }
-                TableUpdate::SetLocation { location } => {
-                    if location != &previous_location {
-                        return Err(ErrorModel::builder()
-                            .code(StatusCode::BAD_REQUEST.into())
-                            .message("Cannot change table location".to_string())
-                            .r#type("SetLocationNotAllowed".to_string())
-                            .build()
-                            .into());
-                    }
-                }
-                _ => {
-                    TableUpdateExt::apply(update, &mut builder)?;
-                }
-            }
-        }
-        let new_metadata = builder.build()?;
-        responses.push(CommitTableResponseExt {
-            commit_response: CommitTableResponse {
-                metadata_location: metadata_location.clone(),
-                metadata: new_metadata.clone(),
-                config: None,
-            },
-            storage_config: GetStorageConfigResponse {
-                storage_profile: context.storage_profile,
-                storage_secret_ident: context.storage_secret_ident,
-            },
-            previous_table_metadata,
-        });
+pub(crate) async fn record_table_commits(
+    commits: &[TableCommit],
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    if commits.is_empty() {
+        return Ok(());
     }
 
-    Ok(responses)
+    let mut table_ids = Vec::with_capacity(commits.len());
+    let mut metadata_locations = Vec::with_capacity(commits.len());
+    let mut committed_ats = Vec::with_capacity(commits.len());
+    let mut committed_bys = Vec::with_capacity(commits.len());
+    let mut snapshot_ids = Vec::with_capacity(commits.len());
+    let mut operations = Vec::with_capacity(commits.len());
+    for commit in commits {
+        table_ids.push(*commit.table_id.as_uuid());
+        metadata_locations.push(commit.metadata_location.clone());
+        committed_ats.push(commit.committed_at);
+        committed_bys.push(commit.committed_by.clone());
+        snapshot_ids.push(commit.snapshot_id);
+        operations.push(commit.operation.clone());
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO table_commit
+            (table_id, metadata_location, committed_at, committed_by, snapshot_id, operation)
+        SELECT * FROM UNNEST(
+            $1::uuid[], $2::text[], $3::timestamptz[], $4::text[], $5::bigint[], $6::text[]
+        )
+        ON CONFLICT (table_id, metadata_location) DO NOTHING
+        "#,
+        &table_ids,
+        &metadata_locations,
+        &committed_ats,
+        &committed_bys as _,
+        &snapshot_ids as _,
+        &operations as _,
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error recording table commits".to_string()))?;
+
+    Ok(())
 }
 
-pub(crate) async fn commit_table_transaction<'a>(
-    // We do not need the warehouse_id here, because table_ids are unique across warehouses
-    _: &WarehouseIdent,
-    request: CommitTransactionRequest,
-    table_ids: &HashMap<TableIdent, TableIdentUuid>,
+pub(crate) async fn record_lineage_events(
+    table_id: TableIdentUuid,
+    events: &[LineageEvent],
     transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
-) -> Result<Vec<CommitTableResponseExt>> {
-    let contexts = get_commit_context(request, table_ids, transaction).await?;
-
-    if contexts.len() > (MAX_PARAMETERS / 4) {
-        return Err(ErrorModel::builder()
-            .code(StatusCode::BAD_REQUEST.into())
-            .message("Too updates in single commit".to_string())
-            .r#type("TooManyTablesForCommit".to_string())
-            .build()
-            .into());
+) -> Result<()> {
+    if events.is_empty() {
+        return Ok(());
     }
 
-    // Check all requirements
-    for context in &contexts {
-        context
-            .requirements
-            .iter()
-            .map(|r| r.assert(&context.metadata, context.metadata_location.is_some()))
-            .collect::<Result<Vec<_>>>()?;
-    }
+    let snapshot_ids = events.iter().map(|e| e.snapshot_id).collect::<Vec<_>>();
+    let events = events
+        .iter()
+        .map(|event| {
+            serde_json::to_value(event).map_err(|e| {
+                ErrorModel::builder()
+                    .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                    .message("Error serializing lineage event".to_string())
+                    .r#type("LineageEventSerializationError".to_string())
+                    .stack(Some(vec![e.to_string()]))
+                    .build()
+            })
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
 
-    // Apply updates
-    let responses = apply_commits(contexts)?;
+    sqlx::query!(
+        r#"
+        INSERT INTO table_lineage_event (table_id, snapshot_id, event)
+        SELECT $1, * FROM UNNEST($2::bigint[], $3::jsonb[])
+        ON CONFLICT (table_id, snapshot_id) DO NOTHING
+        "#,
+        table_id.as_uuid(),
+        &snapshot_ids,
+        &events
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error recording lineage events".to_string()))?;
 
-    let mut query_builder = sqlx::QueryBuilder::new(
+    Ok(())
+}
+
+pub(crate) async fn list_staged_tables(
+    warehouse_id: &WarehouseIdent,
+    catalog_state: CatalogState,
+) -> Result<Vec<StagedTable>> {
+    let tables = sqlx::query!(
         r#"
-        UPDATE "table" as t
-        SET "metadata" = c."metadata", "metadata_location" = c."metadata_location"
-        FROM (VALUES
+        SELECT
+            t."table_id",
+            n.namespace_name,
+            t.table_name,
+            t."table_location",
+            t.created_at,
+            t.created_by
+        FROM "table" t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        WHERE w.warehouse_id = $1
+        AND w.status = 'active'
+        AND t."metadata_location" IS NULL
+        ORDER BY t.created_at
         "#,
-    );
+        warehouse_id.as_uuid()
+    )
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching staged tables".to_string()))?;
 
-    for (i, response) in responses.iter().enumerate() {
-        let metadata_ser =
-            serde_json::to_value(&response.commit_response.metadata).map_err(|e| {
+    tables
+        .into_iter()
+        .map(|table| {
+            let namespace = NamespaceIdent::from_vec(table.namespace_name).map_err(|e| {
                 ErrorModel::builder()
                     .code(StatusCode::INTERNAL_SERVER_ERROR.into())
-                    .message("Error serializing table metadata".to_string())
-                    .r#type("TableMetadataSerializationError".to_string())
+                    .message("Error parsing namespace".to_string())
+                    .r#type("NamespaceParseError".to_string())
                     .stack(Some(vec![e.to_string()]))
                     .build()
             })?;
+            Ok(StagedTable {
+                table_id: table.table_id.into(),
+                table: TableIdent::new(namespace, table.table_name),
+                table_location: table.table_location,
+                created_at: table.created_at,
+                created_by: table.created_by,
+            })
+        })
+        .collect()
+}
 
-        query_builder.push("(");
-        query_builder.push_bind(response.commit_response.metadata.uuid());
-        query_builder.push(", ");
-        query_builder.push_bind(metadata_ser);
-        query_builder.push(", ");
-        query_builder.push_bind(response.commit_response.metadata_location.clone());
-        query_builder.push(")");
-        if i != responses.len() - 1 {
-            query_builder.push(", ");
-        }
-    }
-
-    query_builder
-        .push(") as c(table_id, metadata, metadata_location) WHERE c.table_id = t.table_id");
-    query_builder.push(" RETURNING t.table_id");
-    let query = query_builder.build();
-
-    let updated = query
-        .fetch_all(&mut **transaction)
-        .await
-        .map_err(|e| e.into_error_model("Error committing table updates".to_string()))?;
+pub(crate) async fn discard_staged_table(
+    warehouse_id: &WarehouseIdent,
+    table_id: &TableIdentUuid,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let row_count = sqlx::query_scalar!(
+        r#"
+        WITH deleted AS (
+            DELETE FROM "table" t
+            USING namespace n, warehouse w
+            WHERE t.namespace_id = n.namespace_id
+            AND n.warehouse_id = w.warehouse_id
+            AND w.warehouse_id = $1
+            AND w.status = 'active'
+            AND t."table_id" = $2
+            AND t."metadata_location" IS NULL
+            RETURNING t."table_id"
+        )
+        SELECT count("table_id") FROM deleted
+        "#,
+        warehouse_id.as_uuid(),
+        table_id.as_uuid()
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error discarding staged table".to_string()))?;
 
-    if updated.len() != responses.len() {
+    if row_count == Some(0) {
         return Err(ErrorModel::builder()
-            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
-            .message("Error committing table updates".to_string())
-            .r#type("CommitTableUpdateError".to_string())
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Staged table not found".to_string())
+            .r#type("NoSuchStagedTableError".to_string())
+            .error_type(ErrorType::NoSuchTable)
             .build()
             .into());
     }
 
-    Ok(responses)
+    Ok(())
 }
 
-#[cfg(test)]
-pub(crate) mod tests {
-    // Desired behaviour:
-    // - Stage-Create => Load fails with 404
-    // - No Stage-Create => Next create fails with 409, load succeeds
-    // - Stage-Create => Next stage-create works & overwrites
-    // - Stage-Create => Next regular create works & overwrites
-
-    use crate::api::management::v1::warehouse::WarehouseStatus;
-    use crate::api::CommitTableRequest;
-    use iceberg::spec::{NestedField, PrimitiveType, Schema, UnboundPartitionSpec};
-    use iceberg::NamespaceIdent;
-
-    use super::super::namespace::tests::initialize_namespace;
-    use super::super::warehouse::test::initialize_warehouse;
-    use super::*;
-
-    fn create_request(stage_create: Option<bool>) -> (CreateTableRequest, Option<String>) {
-        let metadata_location = if let Some(stage_create) = stage_create {
-            if stage_create {
-                None
-            } else {
-                Some("s3://my_bucket/my_table/metadata/foo".to_string())
-            }
-        } else {
-            Some("s3://my_bucket/my_table/metadata/bar".to_string())
-        };
-
-        (
-            CreateTableRequest {
-                name: "my_table".to_string(),
-                location: Some("s3://my_bucket/my_table".to_string()),
-                schema: Schema::builder()
-                    .with_fields(vec![
-                        NestedField::required(
-                            1,
-                            "id",
-                            iceberg::spec::Type::Primitive(PrimitiveType::Int),
-                        )
-                        .into(),
-                        NestedField::required(
-                            1,
-                            "name",
-                            iceberg::spec::Type::Primitive(PrimitiveType::String),
-                        )
-                        .into(),
-                    ])
-                    .build()
-                    .unwrap(),
-                partition_spec: Some(UnboundPartitionSpec {
-                    spec_id: None,
-                    fields: vec![],
-                }),
-                write_order: None,
-                stage_create,
-                properties: None,
-            },
-            metadata_location,
-        )
-    }
+pub(crate) async fn rename_table(
+    warehouse_id: &WarehouseIdent,
+    source_id: &TableIdentUuid,
+    source: &TableIdent,
+    destination: &TableIdent,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let TableIdent {
+        namespace: source_namespace,
+        name: source_name,
+    } = source;
+    let TableIdent {
+        namespace: dest_namespace,
+        name: dest_name,
+    } = destination;
 
-    async fn get_namespace_id(
-        state: CatalogState,
-        warehouse_id: &WarehouseIdent,
-        namespace: &NamespaceIdent,
-    ) -> NamespaceIdentUuid {
-        let namespace = sqlx::query!(
+    if source_namespace == dest_namespace {
+        let _ = sqlx::query_scalar!(
             r#"
-            SELECT namespace_id
-            FROM namespace
-            WHERE warehouse_id = $1 AND namespace_name = $2
+            UPDATE "table"
+            SET table_name = $1
+            WHERE table_id = $2
+            AND $3 IN (
+                SELECT warehouse_id FROM warehouse WHERE status = 'active'
+            )
+            RETURNING table_id
             "#,
+            &**dest_name,
+            source_id.as_uuid(),
             warehouse_id.as_uuid(),
-            &**namespace
         )
-        .fetch_one(&state.read_pool)
+        .fetch_one(&mut **transaction)
         .await
-        .unwrap();
-        namespace.namespace_id.into()
-    }
-
-    pub(crate) struct InitializedTable {
-        #[allow(dead_code)]
-        pub(crate) namespace_id: NamespaceIdentUuid,
-        pub(crate) namespace: NamespaceIdent,
-        pub(crate) table_id: TableIdentUuid,
-        pub(crate) table_ident: TableIdent,
-    }
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ErrorModel::builder()
+                .code(StatusCode::NOT_FOUND.into())
+                .message("ID of Table to rename not found".to_string())
+                .r#type("RenameTableIdNotFound".to_string())
+                .error_type(ErrorType::NoSuchTable)
+                .build(),
+            _ => e.into_error_model("Error renaming table".to_string()),
+        })?;
+    } else {
+        let _ = sqlx::query_scalar!(
+            r#"
+            UPDATE "table"
+            SET table_name = $1, "namespace_id" = (
+                SELECT namespace_id
+                FROM namespace
+                WHERE warehouse_id = $2 AND namespace_name = $3
+            )
+            WHERE "table_id" = $4
+            AND table_name = $5
+            AND $2 IN (
+                SELECT warehouse_id FROM warehouse WHERE status = 'active'
+            )
+            RETURNING "table_id"
+            "#,
+            &**dest_name,
+            warehouse_id.as_uuid(),
+            &**dest_namespace,
+            source_id.as_uuid(),
+            &**source_name,
+        )
+        .fetch_one(&mut **transaction)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ErrorModel::builder()
+                .code(StatusCode::NOT_FOUND.into())
+                .message(
+                    "ID of Table to rename not found or destination namespace not found"
+                        .to_string(),
+                )
+                .r#type("RenameTableIdOrNamespaceNotFound".to_string())
+                .build(),
+            _ => e.into_error_model("Error renaming Table".to_string()),
+        })?;
+    };
+
+    Ok(())
+}
+
+pub(crate) async fn drop_table<'a>(
+    _: &WarehouseIdent,
+    table_id: &TableIdentUuid,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    // Keep the table until it is reclaimed by the garbage collector.
+    // Staged tables have no files and are not kept.
+    sqlx::query!(
+        r#"
+        INSERT INTO dropped_table (table_id, warehouse_id, namespace_name, table_name, "metadata", metadata_locations, table_location)
+        SELECT
+            t.table_id,
+            n.warehouse_id,
+            n.namespace_name,
+            t.table_name,
+            t."metadata",
+            ARRAY(SELECT l.metadata_location FROM table_metadata_log l WHERE l.table_id = t.table_id),
+            t.table_location
+        FROM "table" t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        WHERE t.table_id = $1 AND t."metadata_location" IS NOT NULL
+        "#,
+        table_id.as_uuid()
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error keeping dropped table".to_string()))?;
+
+    let _ = sqlx::query!(
+        r#"
+        DELETE FROM "table"
+        WHERE "table_id" = $1
+        AND "namespace_id" IN (
+            SELECT "namespace_id"
+            FROM namespace
+            WHERE "warehouse_id" IN (
+                SELECT "warehouse_id"
+                FROM warehouse
+                WHERE status = 'active'
+            )
+        )
+        RETURNING "table_id"
+        "#,
+        table_id.as_uuid()
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::RowNotFound => ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Table not found".to_string())
+            .r#type("NoSuchTableError".to_string())
+            .error_type(ErrorType::NoSuchTable)
+            .build(),
+        _ => e.into_error_model("Error dropping table".to_string()),
+    })?;
+
+    Ok(())
+}
+
+pub(crate) async fn list_expired_dropped_tables(
+    dropped_before: chrono::DateTime<chrono::Utc>,
+    limit: i64,
+    catalog_state: CatalogState,
+) -> Result<Vec<DroppedTable>> {
+    let tables = sqlx::query!(
+        r#"
+        SELECT
+            d.table_id,
+            d.warehouse_id,
+            d.namespace_name,
+            d.table_name,
+            d.table_location,
+            d.metadata_locations,
+            d.dropped_at,
+            w.storage_profile as "storage_profile: Json<StorageProfile>",
+            w."storage_secret_id"
+        FROM dropped_table d
+        INNER JOIN warehouse w ON d.warehouse_id = w.warehouse_id
+        WHERE d.reclaimed_at IS NULL AND d.dropped_at < $1
+        ORDER BY d.dropped_at
+        LIMIT $2
+        "#,
+        dropped_before,
+        limit
+    )
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching dropped tables".to_string()))?;
+
+    tables
+        .into_iter()
+        .map(|table| {
+            let namespace = NamespaceIdent::from_vec(table.namespace_name).map_err(|e| {
+                ErrorModel::builder()
+                    .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                    .message("Error parsing namespace".to_string())
+                    .r#type("NamespaceParseError".to_string())
+                    .stack(Some(vec![e.to_string()]))
+                    .build()
+            })?;
+            Ok(DroppedTable {
+                table_id: table.table_id.into(),
+                warehouse_id: table.warehouse_id.into(),
+                table: TableIdent::new(namespace, table.table_name),
+                table_location: table.table_location,
+                metadata_locations: table.metadata_locations,
+                dropped_at: table.dropped_at,
+                storage_profile: table.storage_profile.deref().clone(),
+                storage_secret_ident: table.storage_secret_id.map(SecretIdent::from),
+            })
+        })
+        .collect()
+}
+
+pub(crate) async fn list_retention_candidates(
+    after: Option<TableIdentUuid>,
+    limit: i64,
+    catalog_state: CatalogState,
+) -> Result<Vec<RetentionCandidate>> {
+    let override_properties = SnapshotRetentionPolicy::PROPERTIES.map(ToString::to_string);
+    // The policy of the closest namespace applies, including the table's own namespace.
+    // The warehouse policy applies if no namespace has a policy.
+    let tables = sqlx::query!(
+        r#"
+        SELECT
+            t.table_id,
+            n.warehouse_id,
+            n.namespace_name,
+            t.table_name,
+            coalesce(p.retention_policy, w.retention_policy) as "policy: Json<SnapshotRetentionPolicy>"
+        FROM "table" t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        LEFT JOIN LATERAL (
+            SELECT pn.retention_policy
+            FROM namespace pn
+            WHERE pn.warehouse_id = n.warehouse_id
+            AND pn.retention_policy IS NOT NULL
+            AND n.namespace_name[1:cardinality(pn.namespace_name)] = pn.namespace_name
+            ORDER BY cardinality(pn.namespace_name) DESC
+            LIMIT 1
+        ) p ON true
+        WHERE w.status = 'active' AND NOT w.read_only
+        AND t.metadata_location IS NOT NULL
+        AND ($1::uuid IS NULL OR t.table_id > $1)
+        AND (
+            p.retention_policy IS NOT NULL
+            OR w.retention_policy IS NOT NULL
+            OR t.metadata->'properties' ?| $2
+        )
+        ORDER BY t.table_id
+        LIMIT $3
+        "#,
+        after.as_ref().map(TableIdentUuid::as_uuid),
+        &override_properties[..],
+        limit
+    )
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching tables with retention".to_string()))?;
+
+    tables
+        .into_iter()
+        .map(|table| {
+            let namespace = NamespaceIdent::from_vec(table.namespace_name).map_err(|e| {
+                ErrorModel::builder()
+                    .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                    .message("Error parsing namespace".to_string())
+                    .r#type("NamespaceParseError".to_string())
+                    .stack(Some(vec![e.to_string()]))
+                    .build()
+            })?;
+            Ok(RetentionCandidate {
+                warehouse_id: table.warehouse_id.into(),
+                table_id: table.table_id.into(),
+                table: TableIdent::new(namespace, table.table_name),
+                policy: table.policy.map(|p| p.0),
+            })
+        })
+        .collect()
+}
+
+pub(crate) async fn reclaim_dropped_table(
+    warehouse_id: &WarehouseIdent,
+    table_id: &TableIdentUuid,
+    storage_purged: bool,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let row_count = sqlx::query_scalar!(
+        r#"
+        WITH reclaimed AS (
+            UPDATE dropped_table
+            SET "metadata" = NULL, reclaimed_at = now(), storage_purged = $2
+            WHERE table_id = $1 AND warehouse_id = $3 AND reclaimed_at IS NULL
+            RETURNING table_id
+        )
+        SELECT count(table_id) FROM reclaimed
+        "#,
+        table_id.as_uuid(),
+        storage_purged,
+        warehouse_id.as_uuid()
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error reclaiming dropped table".to_string()))?;
+
+    if row_count == Some(0) {
+        return Err(dropped_table_not_found().into());
+    }
+
+    Ok(())
+}
+
+async fn get_commit_context<'a>(
+    request: CommitTransactionRequest,
+    table_ids: &HashMap<TableIdent, TableIdentUuid>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<Vec<CommitContext>> {
+    let sorted_table_ids = lock_order(table_ids);
+
+    let metadata = sqlx::query!(
+        r#"
+        SELECT 
+            t."table_id", 
+            t."metadata" as "metadata: Json<TableMetadata>", 
+            t."metadata_location",
+            t."metadata_version",
+            w.storage_profile as "storage_profile: Json<StorageProfile>",
+            w."storage_secret_id",
+            coalesce(p.retention_policy, w.retention_policy) as "retention_policy: Json<SnapshotRetentionPolicy>"
+        FROM "table" t
+        INNER JOIN namespace n ON t.namespace_id = n.namespace_id
+        INNER JOIN warehouse w ON n.warehouse_id = w.warehouse_id
+        LEFT JOIN LATERAL (
+            SELECT pn.retention_policy
+            FROM namespace pn
+            WHERE pn.warehouse_id = n.warehouse_id
+            AND pn.retention_policy IS NOT NULL
+            AND n.namespace_name[1:cardinality(pn.namespace_name)] = pn.namespace_name
+            ORDER BY cardinality(pn.namespace_name) DESC
+            LIMIT 1
+        ) p ON true
+        WHERE "table_id" = ANY($1)
+        AND w.status = 'active'
+        ORDER BY t."table_id"
+        FOR UPDATE OF t
+        "#,
+        &sorted_table_ids
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching table metadata for commit".to_string()))?;
+
+    let rows = metadata
+        .into_iter()
+        .map(|record| CommitRow {
+            table_id: record.table_id,
+            storage_profile: record.storage_profile.0,
+            storage_secret_ident: record.storage_secret_id.map(SecretIdent::from),
+            metadata: record.metadata.0,
+            metadata_location: record.metadata_location,
+            metadata_version: record.metadata_version,
+            retention_policy: record.retention_policy.map(|p| p.0),
+        })
+        .collect::<Vec<_>>();
+
+    commit_contexts(request, table_ids, &rows)
+}
+
+pub(crate) async fn commit_table_transaction<'a>(
+    // We do not need the warehouse_id here, because table_ids are unique across warehouses
+    _: &WarehouseIdent,
+    request: CommitTransactionRequest,
+    table_ids: &HashMap<TableIdent, TableIdentUuid>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<Vec<CommitTableResponseExt>> {
+    let contexts = get_commit_context(request, table_ids, transaction).await?;
+    let (responses, expected_versions) = prepare_commits(contexts, MAX_PARAMETERS / 4)?;
+    update_table_metadata(&responses, &expected_versions, transaction).await?;
+
+    Ok(responses)
+}
+
+/// Write the new metadata of committed tables. Fails with a `409` if the
+/// `metadata_version` of a table does not match the version the commit is based on.
+async fn update_table_metadata(
+    responses: &[CommitTableResponseExt],
+    expected_versions: &HashMap<uuid::Uuid, i64>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let mut query_builder = sqlx::QueryBuilder::new(
+        r#"
+        UPDATE "table" as t
+        SET "metadata" = c."metadata", "metadata_location" = c."metadata_location", "metadata_version" = t."metadata_version" + 1
+        FROM (VALUES
+        "#,
+    );
+
+    for (i, response) in update_order(responses).iter().enumerate() {
+        let metadata_ser = serialize_metadata(&response.commit_response.metadata)?;
+
+        query_builder.push("(");
+        query_builder.push_bind(response.commit_response.metadata.uuid());
+        query_builder.push(", ");
+        query_builder.push_bind(metadata_ser);
+        query_builder.push(", ");
+        query_builder.push_bind(response.commit_response.metadata_location.clone());
+        query_builder.push(", ");
+        query_builder.push_bind(
+            expected_versions
+                .get(&response.commit_response.metadata.uuid())
+                .copied(),
+        );
+        query_builder.push("::bigint)");
+        if i != responses.len() - 1 {
+            query_builder.push(", ");
+        }
+    }
+
+    query_builder.push(
+        ") as c(table_id, metadata, metadata_location, metadata_version) WHERE c.table_id = t.table_id AND c.metadata_version = t.metadata_version",
+    );
+    query_builder.push(" RETURNING t.table_id");
+    let query = query_builder.build();
+
+    let updated = query
+        .fetch_all(&mut **transaction)
+        .await
+        .map_err(|e| e.into_error_model("Error committing table updates".to_string()))?
+        .into_iter()
+        .map(|row| row.get::<uuid::Uuid, _>("table_id"))
+        .collect::<HashSet<_>>();
+
+    assert_all_updated(expected_versions, &updated)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    // Desired behaviour:
+    // - Stage-Create => Load fails with 404
+    // - No Stage-Create => Next create fails with 409, load succeeds
+    // - Stage-Create => Next stage-create works & overwrites
+    // - Stage-Create => Next regular create works & overwrites
+
+    use crate::api::management::v1::warehouse::WarehouseStatus;
+    use crate::api::CommitTableRequest;
+    use iceberg::spec::{NestedField, PrimitiveType, Schema, UnboundPartitionSpec};
+    use iceberg::NamespaceIdent;
+    use iceberg_ext::{TableRequirement, TableUpdate};
+
+    use super::super::namespace::tests::initialize_namespace;
+    use super::super::warehouse::test::initialize_warehouse;
+    use super::*;
+
+    fn create_request(stage_create: Option<bool>) -> (CreateTableRequest, Option<String>) {
+        let metadata_location = if let Some(stage_create) = stage_create {
+            if stage_create {
+                None
+            } else {
+                Some("s3://my_bucket/my_table/metadata/foo".to_string())
+            }
+        } else {
+            Some("s3://my_bucket/my_table/metadata/bar".to_string())
+        };
+
+        (
+            CreateTableRequest {
+                name: "my_table".to_string(),
+                location: Some("s3://my_bucket/my_table".to_string()),
+                schema: Schema::builder()
+                    .with_fields(vec![
+                        NestedField::required(
+                            1,
+                            "id",
+                            iceberg::spec::Type::Primitive(PrimitiveType::Int),
+                        )
+                        .into(),
+                        NestedField::required(
+                            1,
+                            "name",
+                            iceberg::spec::Type::Primitive(PrimitiveType::String),
+                        )
+                        .into(),
+                    ])
+                    .build()
+                    .unwrap(),
+                partition_spec: Some(UnboundPartitionSpec {
+                    spec_id: None,
+                    fields: vec![],
+                }),
+                write_order: None,
+                stage_create,
+                properties: None,
+            },
+            metadata_location,
+        )
+    }
+
+    async fn get_namespace_id(
+        state: CatalogState,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+    ) -> NamespaceIdentUuid {
+        let namespace = sqlx::query!(
+            r#"
+            SELECT namespace_id
+            FROM namespace
+            WHERE warehouse_id = $1 AND namespace_name = $2
+            "#,
+            warehouse_id.as_uuid(),
+            &**namespace
+        )
+        .fetch_one(&state.read_pool)
+        .await
+        .unwrap();
+        namespace.namespace_id.into()
+    }
+
+    pub(crate) struct InitializedTable {
+        #[allow(dead_code)]
+        pub(crate) namespace_id: NamespaceIdentUuid,
+        pub(crate) namespace: NamespaceIdent,
+        pub(crate) table_id: TableIdentUuid,
+        pub(crate) table_ident: TableIdent,
+    }
+
+    pub(crate) async fn initialize_table(
+        warehouse_id: &WarehouseIdent,
+        state: CatalogState,
+        staged: bool,
+    ) -> InitializedTable {
+        // my_namespace_<uuid>
+        let namespace =
+            NamespaceIdent::from_vec(vec![format!("my_namespace_{}", uuid::Uuid::now_v7())])
+                .unwrap();
+        initialize_namespace(state.clone(), warehouse_id, &namespace, None).await;
+        let namespace_id = get_namespace_id(state.clone(), warehouse_id, &namespace).await;
+
+        let (request, metadata_location) = create_request(Some(staged));
+        let table_ident = TableIdent {
+            namespace: namespace.clone(),
+            name: request.name.clone(),
+        };
+
+        let mut transaction = state.write_pool.begin().await.unwrap();
+        let table_id = uuid::Uuid::now_v7().into();
+        let _create_result = create_table(
+            &namespace_id,
+            &table_ident,
+            &table_id,
+            request.clone(),
+            metadata_location.as_ref(),
+            None,
+            &mut transaction,
+        )
+        .await
+        .unwrap();
+
+        transaction.commit().await.unwrap();
+
+        InitializedTable {
+            namespace_id,
+            namespace,
+            table_id,
+            table_ident,
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_final_create(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let namespace = NamespaceIdent::from_vec(vec!["my_namespace".to_string()]).unwrap();
+        initialize_namespace(state.clone(), &warehouse_id, &namespace, None).await;
+        let namespace_id = get_namespace_id(state.clone(), &warehouse_id, &namespace).await;
+
+        let (request, metadata_location) = create_request(None);
+        let table_ident = TableIdent {
+            namespace: namespace.clone(),
+            name: request.name.clone(),
+        };
+
+        let mut transaction = pool.begin().await.unwrap();
+        let table_id = uuid::Uuid::now_v7().into();
+        let create_result = create_table(
+            &namespace_id,
+            &table_ident,
+            &table_id,
+            request.clone(),
+            metadata_location.as_ref(),
+            None,
+            &mut transaction,
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let mut transaction = pool.begin().await.unwrap();
+        // Second create should fail
+        let table_id = uuid::Uuid::now_v7().into();
+        let create_err = create_table(
+            &namespace_id,
+            &table_ident,
+            &table_id,
+            request,
+            metadata_location.as_ref(),
+            None,
+            &mut transaction,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(create_err.error.code, StatusCode::CONFLICT);
+
+        // Load should succeed
+        let load_result = load_table(&warehouse_id, &table_ident, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(load_result.table_metadata, create_result.table_metadata);
+    }
+
+    #[sqlx::test]
+    async fn test_stage_create(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let namespace = NamespaceIdent::from_vec(vec!["my_namespace".to_string()]).unwrap();
+        initialize_namespace(state.clone(), &warehouse_id, &namespace, None).await;
+        let namespace_id = get_namespace_id(state.clone(), &warehouse_id, &namespace).await;
+
+        let (request, metadata_location) = create_request(Some(true));
+        let table_ident = TableIdent {
+            namespace: namespace.clone(),
+            name: request.name.clone(),
+        };
+
+        let mut transaction = pool.begin().await.unwrap();
+        let table_id = uuid::Uuid::now_v7().into();
+        let _create_result = create_table(
+            &namespace_id,
+            &table_ident,
+            &table_id,
+            request.clone(),
+            metadata_location.as_ref(),
+            None,
+            &mut transaction,
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        // Load should fail
+        let load_err = load_table(&warehouse_id, &table_ident, state.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(load_err.error.code, StatusCode::NOT_FOUND);
+
+        // Second create should succeed
+        let mut transaction = pool.begin().await.unwrap();
+        let table_id = uuid::Uuid::now_v7().into();
+        let create_result = create_table(
+            &namespace_id,
+            &table_ident,
+            &table_id,
+            request,
+            metadata_location.as_ref(),
+            None,
+            &mut transaction,
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        assert_eq!(create_result.table_metadata, create_result.table_metadata);
+
+        // We can overwrite the table with a regular create
+        let (request, metadata_location) = create_request(Some(false));
+        let mut transaction = pool.begin().await.unwrap();
+        let create_result = create_table(
+            &namespace_id,
+            &table_ident,
+            &table_id,
+            request,
+            metadata_location.as_ref(),
+            None,
+            &mut transaction,
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let load_result = load_table(&warehouse_id, &table_ident, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(load_result.table_metadata, create_result.table_metadata);
+    }
+
+    #[sqlx::test]
+    fn test_to_id(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let namespace = NamespaceIdent::from_vec(vec!["my_namespace".to_string()]).unwrap();
+        initialize_namespace(state.clone(), &warehouse_id, &namespace, None).await;
+
+        let table_ident = TableIdent {
+            namespace: namespace.clone(),
+            name: "my_table".to_string(),
+        };
+
+        let exists = table_ident_to_id(&warehouse_id, &table_ident, false, &state.read_pool)
+            .await
+            .unwrap();
+        assert!(exists.is_none());
+        drop(table_ident);
+
+        let table = initialize_table(&warehouse_id, state.clone(), true).await;
+
+        // Table is staged - no result if include_staged is false
+        let exists = table_ident_to_id(&warehouse_id, &table.table_ident, false, &state.read_pool)
+            .await
+            .unwrap();
+        assert!(exists.is_none());
+
+        let exists = table_ident_to_id(&warehouse_id, &table.table_ident, true, &state.read_pool)
+            .await
+            .unwrap();
+        assert_eq!(exists, Some(table.table_id));
+    }
+
+    #[sqlx::test]
+    async fn test_load_tables(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let staged = initialize_table(&warehouse_id, state.clone(), true).await;
+        let table1 = initialize_table(&warehouse_id, state.clone(), false).await;
+        let table2 = initialize_table(&warehouse_id, state.clone(), false).await;
+        let missing = TableIdent {
+            namespace: table1.table_ident.namespace.clone(),
+            name: "missing".to_string(),
+        };
+
+        let loaded = load_tables(
+            &warehouse_id,
+            HashSet::from([
+                &staged.table_ident,
+                &table1.table_ident,
+                &table2.table_ident,
+                &missing,
+            ]),
+            &state.read_pool,
+        )
+        .await
+        .unwrap();
+
+        let loaded = loaded
+            .into_iter()
+            .map(|t| (t.table, t.table_id))
+            .collect::<HashMap<_, _>>();
+        assert_eq!(
+            loaded,
+            HashMap::from([
+                (table1.table_ident, table1.table_id),
+                (table2.table_ident, table2.table_id),
+            ])
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_table_exists(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let staged = initialize_table(&warehouse_id, state.clone(), true).await;
+        let table = initialize_table(&warehouse_id, state.clone(), false).await;
+
+        assert!(
+            !table_exists(&warehouse_id, &staged.table_ident, false, &state.read_pool)
+                .await
+                .unwrap()
+        );
+        assert!(
+            table_exists(&warehouse_id, &staged.table_ident, true, &state.read_pool)
+                .await
+                .unwrap()
+        );
+        assert!(
+            table_exists(&warehouse_id, &table.table_ident, false, &state.read_pool)
+                .await
+                .unwrap()
+        );
 
-    pub(crate) async fn initialize_table(
-        warehouse_id: &WarehouseIdent,
-        state: CatalogState,
-        staged: bool,
-    ) -> InitializedTable {
-        // my_namespace_<uuid>
-        let namespace =
-            NamespaceIdent::from_vec(vec![format!("my_namespace_{}", uuid::Uuid::now_v7())])
-                .unwrap();
-        initialize_namespace(state.clone(), warehouse_id, &namespace, None).await;
-        let namespace_id = get_namespace_id(state.clone(), warehouse_id, &namespace).await;
+        let missing = TableIdent {
+            namespace: table.table_ident.namespace.clone(),
+            name: "missing".to_string(),
+        };
+        assert!(
+            !table_exists(&warehouse_id, &missing, true, &state.read_pool)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_to_ids(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let namespace = NamespaceIdent::from_vec(vec!["my_namespace".to_string()]).unwrap();
+        initialize_namespace(state.clone(), &warehouse_id, &namespace, None).await;
 
-        let (request, metadata_location) = create_request(Some(staged));
         let table_ident = TableIdent {
             namespace: namespace.clone(),
-            name: request.name.clone(),
+            name: "my_table".to_string(),
         };
 
-        let mut transaction = state.write_pool.begin().await.unwrap();
-        let table_id = uuid::Uuid::now_v7().into();
-        let _create_result = create_table(
-            &namespace_id,
-            &table_ident,
-            &table_id,
-            request.clone(),
-            metadata_location.as_ref(),
+        let exists = table_idents_to_ids(
+            &warehouse_id,
+            vec![&table_ident].into_iter().collect(),
+            false,
+            &state.read_pool,
+        )
+        .await
+        .unwrap();
+        assert!(exists.len() == 1 && exists.get(&table_ident).unwrap().is_none());
+        drop(table_ident);
+
+        let table_1 = initialize_table(&warehouse_id, state.clone(), true).await;
+        let mut tables = HashSet::new();
+        tables.insert(&table_1.table_ident);
+
+        // Table is staged - no result if include_staged is false
+        let exists = table_idents_to_ids(&warehouse_id, tables.clone(), false, &state.read_pool)
+            .await
+            .unwrap();
+        assert_eq!(exists.len(), 1);
+        assert!(exists.get(&table_1.table_ident).unwrap().is_none());
+
+        let exists = table_idents_to_ids(&warehouse_id, tables.clone(), true, &state.read_pool)
+            .await
+            .unwrap();
+        assert_eq!(exists.len(), 1);
+        assert_eq!(
+            exists.get(&table_1.table_ident).unwrap(),
+            &Some(table_1.table_id)
+        );
+
+        // Second Table
+        let table_2 = initialize_table(&warehouse_id, state.clone(), false).await;
+        tables.insert(&table_2.table_ident);
+
+        let exists = table_idents_to_ids(&warehouse_id, tables.clone(), false, &state.read_pool)
+            .await
+            .unwrap();
+        assert_eq!(exists.len(), 2);
+        assert!(exists.get(&table_1.table_ident).unwrap().is_none());
+        assert_eq!(
+            exists.get(&table_2.table_ident).unwrap(),
+            &Some(table_2.table_id)
+        );
+
+        let exists = table_idents_to_ids(&warehouse_id, tables.clone(), true, &state.read_pool)
+            .await
+            .unwrap();
+        assert_eq!(exists.len(), 2);
+        assert_eq!(
+            exists.get(&table_1.table_ident).unwrap(),
+            &Some(table_1.table_id)
+        );
+        assert_eq!(
+            exists.get(&table_2.table_ident).unwrap(),
+            &Some(table_2.table_id)
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_rename_without_namespace(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let table = initialize_table(&warehouse_id, state.clone(), false).await;
+
+        let new_table_ident = TableIdent {
+            namespace: table.namespace.clone(),
+            name: "new_table".to_string(),
+        };
+
+        let mut transaction = pool.begin().await.unwrap();
+        rename_table(
+            &warehouse_id,
+            &table.table_id,
+            &table.table_ident,
+            &new_table_ident,
+            &mut transaction,
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let exists = table_ident_to_id(&warehouse_id, &table.table_ident, false, &state.read_pool)
+            .await
+            .unwrap();
+        assert!(exists.is_none());
+
+        let exists = table_ident_to_id(&warehouse_id, &new_table_ident, false, &state.read_pool)
+            .await
+            .unwrap();
+        // Table id should be the same
+        assert_eq!(exists, Some(table.table_id));
+    }
+
+    #[sqlx::test]
+    async fn test_rename_with_namespace(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let table = initialize_table(&warehouse_id, state.clone(), false).await;
+
+        let new_namespace = NamespaceIdent::from_vec(vec!["new_namespace".to_string()]).unwrap();
+        initialize_namespace(state.clone(), &warehouse_id, &new_namespace, None).await;
+
+        let new_table_ident = TableIdent {
+            namespace: new_namespace.clone(),
+            name: "new_table".to_string(),
+        };
+
+        let mut transaction = pool.begin().await.unwrap();
+        rename_table(
+            &warehouse_id,
+            &table.table_id,
+            &table.table_ident,
+            &new_table_ident,
             &mut transaction,
         )
         .await
         .unwrap();
+        transaction.commit().await.unwrap();
+
+        let exists = table_ident_to_id(&warehouse_id, &table.table_ident, false, &state.read_pool)
+            .await
+            .unwrap();
+        assert!(exists.is_none());
+
+        let exists = table_ident_to_id(&warehouse_id, &new_table_ident, false, &state.read_pool)
+            .await
+            .unwrap();
+        assert_eq!(exists, Some(table.table_id));
+    }
+
+    #[sqlx::test]
+    async fn test_list_tables(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let all = PageQuery::all();
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let namespace = NamespaceIdent::from_vec(vec!["my_namespace".to_string()]).unwrap();
+        initialize_namespace(state.clone(), &warehouse_id, &namespace, None).await;
+        let tables = list_tables(&warehouse_id, &namespace, false, &all, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(tables.len(), 0);
+
+        let table1 = initialize_table(&warehouse_id, state.clone(), false).await;
+
+        let tables = list_tables(&warehouse_id, &table1.namespace, false, &all, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables, vec![(table1.table_id, table1.table_ident.clone())]);
+
+        let table2 = initialize_table(&warehouse_id, state.clone(), true).await;
+        let tables = list_tables(&warehouse_id, &table2.namespace, false, &all, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(tables.len(), 0);
+        let tables = list_tables(&warehouse_id, &table2.namespace, true, &all, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables, vec![(table2.table_id, table2.table_ident)]);
+    }
+
+    #[sqlx::test]
+    async fn test_commit_transaction(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let table1 = initialize_table(&warehouse_id, state.clone(), true).await;
+        let table2 = initialize_table(&warehouse_id, state.clone(), false).await;
+
+        let request = CommitTransactionRequest {
+            table_changes: vec![
+                CommitTableRequest {
+                    identifier: Some(table1.table_ident.clone()),
+                    requirements: vec![TableRequirement::NotExist {}],
+                    updates: vec![TableUpdate::SetProperties {
+                        updates: HashMap::from_iter(vec![(
+                            "t1_key".to_string(),
+                            "t1_value".to_string(),
+                        )]),
+                    }],
+                },
+                CommitTableRequest {
+                    identifier: Some(table2.table_ident.clone()),
+                    requirements: vec![TableRequirement::UuidMatch {
+                        uuid: table2.table_id.as_uuid().to_owned(),
+                    }],
+                    updates: vec![TableUpdate::SetProperties {
+                        updates: HashMap::from_iter(vec![(
+                            "t2_key".to_string(),
+                            "t2_value".to_string(),
+                        )]),
+                    }],
+                },
+            ],
+        };
+
+        let table_ids = table_idents_to_ids(
+            &warehouse_id,
+            vec![&table1.table_ident, &table2.table_ident]
+                .into_iter()
+                .collect(),
+            true,
+            &state.read_pool,
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(k, v)| (k, v.unwrap()))
+        .collect();
+
+        let mut transaction = pool.begin().await.unwrap();
+        let responses =
+            commit_table_transaction(&warehouse_id, request, &table_ids, &mut transaction)
+                .await
+                .unwrap();
+        transaction.commit().await.unwrap();
+
+        assert_eq!(responses.len(), 2);
+
+        let response1 = responses
+            .iter()
+            .find(|r| &r.commit_response.metadata.uuid() == table1.table_id.as_uuid())
+            .unwrap();
+        let response2 = responses
+            .iter()
+            .find(|r| &r.commit_response.metadata.uuid() == table2.table_id.as_uuid())
+            .unwrap();
+
+        assert_eq!(
+            response1.commit_response.metadata.properties,
+            HashMap::from_iter(vec![("t1_key".to_string(), "t1_value".to_string())])
+        );
+        assert_eq!(
+            response2.commit_response.metadata.properties,
+            HashMap::from_iter(vec![("t2_key".to_string(), "t2_value".to_string())])
+        );
+    }
 
-        transaction.commit().await.unwrap();
+    fn set_property_request(tables: &[&InitializedTable], key: &str) -> CommitTransactionRequest {
+        CommitTransactionRequest {
+            table_changes: tables
+                .iter()
+                .map(|table| CommitTableRequest {
+                    identifier: Some(table.table_ident.clone()),
+                    requirements: vec![],
+                    updates: vec![TableUpdate::SetProperties {
+                        updates: HashMap::from_iter(vec![(key.to_string(), "value".to_string())]),
+                    }],
+                })
+                .collect(),
+        }
+    }
 
-        InitializedTable {
-            namespace_id,
-            namespace,
-            table_id,
-            table_ident,
+    /// Commit a snapshot that is `age_ms` old and point `refs` to it.
+    async fn commit_snapshot(
+        pool: &sqlx::PgPool,
+        warehouse_id: &WarehouseIdent,
+        table: &InitializedTable,
+        (snapshot_id, age_ms): (i64, i64),
+        refs: &[serde_json::Value],
+    ) -> TableMetadata {
+        let timestamp_ms = chrono::Utc::now().timestamp_millis() - age_ms;
+        let mut updates = vec![serde_json::from_value(serde_json::json!({
+            "action": "add-snapshot",
+            "snapshot": {
+                "snapshot-id": snapshot_id,
+                "sequence-number": snapshot_id,
+                "timestamp-ms": timestamp_ms,
+                "manifest-list": format!("s3://my_bucket/my_table/metadata/snap-{snapshot_id}.avro"),
+                "summary": {"operation": "append"}
+            }
+        }))
+        .unwrap()];
+        for reference in refs {
+            let mut reference = reference.clone();
+            reference["action"] = "set-snapshot-ref".into();
+            reference["snapshot-id"] = snapshot_id.into();
+            updates.push(serde_json::from_value(reference).unwrap());
         }
+
+        let request = CommitTransactionRequest {
+            table_changes: vec![CommitTableRequest {
+                identifier: Some(table.table_ident.clone()),
+                requirements: vec![],
+                updates,
+            }],
+        };
+        let table_ids = HashMap::from_iter(vec![(table.table_ident.clone(), table.table_id)]);
+        let mut transaction = pool.begin().await.unwrap();
+        let responses =
+            commit_table_transaction(warehouse_id, request, &table_ids, &mut transaction)
+                .await
+                .unwrap();
+        transaction.commit().await.unwrap();
+        responses[0].commit_response.metadata.clone()
     }
 
     #[sqlx::test]
-    async fn test_final_create(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
+    async fn test_commit_applies_retention(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
 
         let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
-        let namespace = NamespaceIdent::from_vec(vec!["my_namespace".to_string()]).unwrap();
-        initialize_namespace(state.clone(), &warehouse_id, &namespace, None).await;
-        let namespace_id = get_namespace_id(state.clone(), &warehouse_id, &namespace).await;
-
-        let (request, metadata_location) = create_request(None);
-        let table_ident = TableIdent {
-            namespace: namespace.clone(),
-            name: request.name.clone(),
-        };
-
+        let table = initialize_table(&warehouse_id, state.clone(), false).await;
         let mut transaction = pool.begin().await.unwrap();
-        let table_id = uuid::Uuid::now_v7().into();
-        let create_result = create_table(
-            &namespace_id,
-            &table_ident,
-            &table_id,
-            request.clone(),
-            metadata_location.as_ref(),
+        super::super::namespace::set_namespace_retention_policy(
+            &warehouse_id,
+            &table.namespace,
+            Some(SnapshotRetentionPolicy {
+                max_snapshot_age_ms: Some(60_000),
+                max_ref_age_ms: Some(60_000),
+                ..Default::default()
+            }),
             &mut transaction,
         )
         .await
         .unwrap();
         transaction.commit().await.unwrap();
 
+        let hour = 3_600_000;
+        let main = serde_json::json!({"ref-name": "main", "type": "branch"});
+        let snapshot_ids = |metadata: &TableMetadata| {
+            let mut ids = metadata
+                .snapshots()
+                .map(|s| s.snapshot_id())
+                .collect::<Vec<_>>();
+            ids.sort_unstable();
+            ids
+        };
+
+        // The current snapshot is kept, even though it is older than the policy allows
+        let metadata =
+            commit_snapshot(&pool, &warehouse_id, &table, (1, 3 * hour), &[main.clone()]).await;
+        assert_eq!(metadata.current_snapshot_id, Some(1));
+        assert_eq!(snapshot_ids(&metadata), vec![1]);
+
+        // Once it is replaced, it expires together with its entry in the snapshot log.
+        // Snapshots referenced by a tag with a longer `max-ref-age-ms` are kept.
+        let keep =
+            serde_json::json!({"ref-name": "keep", "type": "tag", "max-ref-age-ms": 10 * hour});
+        let metadata = commit_snapshot(
+            &pool,
+            &warehouse_id,
+            &table,
+            (2, 2 * hour),
+            &[main.clone(), keep],
+        )
+        .await;
+        assert_eq!(metadata.current_snapshot_id, Some(2));
+        assert_eq!(snapshot_ids(&metadata), vec![2]);
+        assert!(metadata.snapshot_log.iter().all(|l| l.snapshot_id == 2));
+        assert!(!metadata.snapshot_log.is_empty());
+
+        // Tags without their own `max-ref-age-ms` expire after the one of the
+        // policy and are removed together with their snapshot.
+        let old = serde_json::json!({"ref-name": "old", "type": "tag"});
+        let metadata = commit_snapshot(&pool, &warehouse_id, &table, (3, hour), &[old]).await;
+        assert_eq!(snapshot_ids(&metadata), vec![2]);
+        assert_eq!(metadata.current_snapshot_id, Some(2));
+        let mut refs = metadata.refs.keys().cloned().collect::<Vec<_>>();
+        refs.sort();
+        assert_eq!(refs, vec!["keep", "main"]);
+
+        // Without a policy, snapshots are kept.
         let mut transaction = pool.begin().await.unwrap();
-        // Second create should fail
-        let table_id = uuid::Uuid::now_v7().into();
-        let create_err = create_table(
-            &namespace_id,
-            &table_ident,
-            &table_id,
-            request,
-            metadata_location.as_ref(),
+        super::super::namespace::set_namespace_retention_policy(
+            &warehouse_id,
+            &table.namespace,
+            None,
             &mut transaction,
         )
         .await
-        .unwrap_err();
-        assert_eq!(create_err.error.code, StatusCode::CONFLICT);
-
-        // Load should succeed
-        let load_result = load_table(&warehouse_id, &table_ident, state.clone())
-            .await
-            .unwrap();
-        assert_eq!(load_result.table_metadata, create_result.table_metadata);
+        .unwrap();
+        transaction.commit().await.unwrap();
+        let metadata =
+            commit_snapshot(&pool, &warehouse_id, &table, (4, hour), &[main.clone()]).await;
+        assert_eq!(snapshot_ids(&metadata), vec![2, 4]);
     }
 
     #[sqlx::test]
-    async fn test_stage_create(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
+    async fn test_commit_with_stale_metadata_version(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
 
         let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
-        let namespace = NamespaceIdent::from_vec(vec!["my_namespace".to_string()]).unwrap();
-        initialize_namespace(state.clone(), &warehouse_id, &namespace, None).await;
-        let namespace_id = get_namespace_id(state.clone(), &warehouse_id, &namespace).await;
-
-        let (request, metadata_location) = create_request(Some(true));
-        let table_ident = TableIdent {
-            namespace: namespace.clone(),
-            name: request.name.clone(),
-        };
+        let table = initialize_table(&warehouse_id, state.clone(), false).await;
+        let table_ids = HashMap::from_iter(vec![(table.table_ident.clone(), table.table_id)]);
 
         let mut transaction = pool.begin().await.unwrap();
-        let table_id = uuid::Uuid::now_v7().into();
-        let _create_result = create_table(
-            &namespace_id,
-            &table_ident,
-            &table_id,
-            request.clone(),
-            metadata_location.as_ref(),
+        let responses = commit_table_transaction(
+            &warehouse_id,
+            set_property_request(&[&table], "a"),
+            &table_ids,
             &mut transaction,
         )
         .await
         .unwrap();
         transaction.commit().await.unwrap();
 
-        // Load should fail
-        let load_err = load_table(&warehouse_id, &table_ident, state.clone())
-            .await
-            .unwrap_err();
-        assert_eq!(load_err.error.code, StatusCode::NOT_FOUND);
-
-        // Second create should succeed
-        let mut transaction = pool.begin().await.unwrap();
-        let table_id = uuid::Uuid::now_v7().into();
-        let create_result = create_table(
-            &namespace_id,
-            &table_ident,
-            &table_id,
-            request,
-            metadata_location.as_ref(),
-            &mut transaction,
+        let version = sqlx::query_scalar!(
+            r#"SELECT metadata_version FROM "table" WHERE table_id = $1"#,
+            table.table_id.as_uuid()
         )
+        .fetch_one(&pool)
         .await
         .unwrap();
-        transaction.commit().await.unwrap();
+        assert_eq!(version, 1);
 
-        assert_eq!(create_result.table_metadata, create_result.table_metadata);
-
-        // We can overwrite the table with a regular create
-        let (request, metadata_location) = create_request(Some(false));
+        // Writing a commit based on the version before the first commit fails.
         let mut transaction = pool.begin().await.unwrap();
-        let create_result = create_table(
-            &namespace_id,
-            &table_ident,
-            &table_id,
-            request,
-            metadata_location.as_ref(),
+        let err = update_table_metadata(
+            &responses,
+            &HashMap::from([(*table.table_id.as_uuid(), 0)]),
             &mut transaction,
         )
         .await
-        .unwrap();
-        transaction.commit().await.unwrap();
-
-        let load_result = load_table(&warehouse_id, &table_ident, state.clone())
-            .await
-            .unwrap();
-        assert_eq!(load_result.table_metadata, create_result.table_metadata);
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT.as_u16());
+        assert_eq!(err.error.r#type, "CommitFailedException");
     }
 
     #[sqlx::test]
-    fn test_to_id(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
+    async fn test_interleaved_commit_transactions(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
 
         let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
-        let namespace = NamespaceIdent::from_vec(vec!["my_namespace".to_string()]).unwrap();
-        initialize_namespace(state.clone(), &warehouse_id, &namespace, None).await;
+        let table1 = initialize_table(&warehouse_id, state.clone(), false).await;
+        let table2 = initialize_table(&warehouse_id, state.clone(), false).await;
+        let table_ids = HashMap::from_iter(vec![
+            (table1.table_ident.clone(), table1.table_id),
+            (table2.table_ident.clone(), table2.table_id),
+        ]);
 
-        let table_ident = TableIdent {
-            namespace: namespace.clone(),
-            name: "my_table".to_string(),
-        };
+        let mut transaction_a = pool.begin().await.unwrap();
+        commit_table_transaction(
+            &warehouse_id,
+            set_property_request(&[&table1, &table2], "a"),
+            &table_ids,
+            &mut transaction_a,
+        )
+        .await
+        .unwrap();
 
-        let exists = table_ident_to_id(&warehouse_id, &table_ident, false, &state.read_pool)
-            .await
-            .unwrap();
-        assert!(exists.is_none());
-        drop(table_ident);
+        // Touches the same tables in the opposite order and has to wait for `a`.
+        let transaction_b = tokio::spawn({
+            let pool = pool.clone();
+            let warehouse_id = warehouse_id.clone();
+            let table_ids = table_ids.clone();
+            let request = set_property_request(&[&table2, &table1], "b");
+            async move {
+                let mut transaction = pool.begin().await.unwrap();
+                let responses =
+                    commit_table_transaction(&warehouse_id, request, &table_ids, &mut transaction)
+                        .await?;
+                transaction.commit().await.unwrap();
+                Result::Ok(responses)
+            }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(!transaction_b.is_finished());
 
-        let table = initialize_table(&warehouse_id, state.clone(), true).await;
+        transaction_a.commit().await.unwrap();
+        let responses = transaction_b.await.unwrap().unwrap();
 
-        // Table is staged - no result if include_staged is false
-        let exists = table_ident_to_id(&warehouse_id, &table.table_ident, false, &state.read_pool)
-            .await
-            .unwrap();
-        assert!(exists.is_none());
+        // `b` is applied on top of `a` instead of overwriting it.
+        assert_eq!(responses.len(), 2);
+        for response in responses {
+            let properties = &response.commit_response.metadata.properties;
+            assert_eq!(properties.get("a"), Some(&"value".to_string()));
+            assert_eq!(properties.get("b"), Some(&"value".to_string()));
+        }
+    }
 
-        let exists = table_ident_to_id(&warehouse_id, &table.table_ident, true, &state.read_pool)
-            .await
-            .unwrap();
-        assert_eq!(exists, Some(table.table_id));
+    #[sqlx::test]
+    async fn test_concurrent_commit_transactions_do_not_deadlock(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let table1 = initialize_table(&warehouse_id, state.clone(), false).await;
+        let table2 = initialize_table(&warehouse_id, state.clone(), false).await;
+        let table3 = initialize_table(&warehouse_id, state.clone(), false).await;
+        let table_ids = HashMap::from_iter(vec![
+            (table1.table_ident.clone(), table1.table_id),
+            (table2.table_ident.clone(), table2.table_id),
+            (table3.table_ident.clone(), table3.table_id),
+        ]);
+
+        let orders = [
+            [&table1, &table2, &table3],
+            [&table3, &table2, &table1],
+            [&table2, &table3, &table1],
+            [&table1, &table3, &table2],
+        ];
+        let handles = (0..20)
+            .map(|i| {
+                let pool = pool.clone();
+                let warehouse_id = warehouse_id.clone();
+                let table_ids = table_ids.clone();
+                let request = set_property_request(&orders[i % orders.len()], &format!("key_{i}"));
+                tokio::spawn(async move {
+                    let mut transaction = pool.begin().await.unwrap();
+                    commit_table_transaction(&warehouse_id, request, &table_ids, &mut transaction)
+                        .await?;
+                    transaction.commit().await.unwrap();
+                    Result::Ok(())
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+
+        let loaded = load_tables(
+            &warehouse_id,
+            HashSet::from([
+                &table1.table_ident,
+                &table2.table_ident,
+                &table3.table_ident,
+            ]),
+            &state.read_pool,
+        )
+        .await
+        .unwrap();
+        assert_eq!(loaded.len(), 3);
+        for table in loaded {
+            assert_eq!(table.table_metadata.properties.len(), 20);
+        }
     }
 
     #[sqlx::test]
-    async fn test_to_ids(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
+    async fn test_get_metadata_by_location(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
 
         let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
-        let namespace = NamespaceIdent::from_vec(vec!["my_namespace".to_string()]).unwrap();
-        initialize_namespace(state.clone(), &warehouse_id, &namespace, None).await;
+        let table = initialize_table(&warehouse_id, state.clone(), false).await;
 
-        let table_ident = TableIdent {
-            namespace: namespace.clone(),
-            name: "my_table".to_string(),
-        };
+        let metadata =
+            get_table_metadata_by_id(&warehouse_id, &table.table_id, false, state.clone())
+                .await
+                .unwrap();
 
-        let exists = table_idents_to_ids(
+        // Exact path works
+        let metadata = get_table_metadata_by_s3_location(
             &warehouse_id,
-            vec![&table_ident].into_iter().collect(),
+            &metadata.location,
             false,
-            &state.read_pool,
+            state.clone(),
         )
         .await
         .unwrap();
-        assert!(exists.len() == 1 && exists.get(&table_ident).unwrap().is_none());
-        drop(table_ident);
 
-        let table_1 = initialize_table(&warehouse_id, state.clone(), true).await;
-        let mut tables = HashSet::new();
-        tables.insert(&table_1.table_ident);
+        assert_eq!(metadata.table, table.table_ident);
+        assert_eq!(metadata.table_id, table.table_id);
 
-        // Table is staged - no result if include_staged is false
-        let exists = table_idents_to_ids(&warehouse_id, tables.clone(), false, &state.read_pool)
-            .await
-            .unwrap();
-        assert_eq!(exists.len(), 1);
-        assert!(exists.get(&table_1.table_ident).unwrap().is_none());
+        // Subpath works
+        let metadata = get_table_metadata_by_s3_location(
+            &warehouse_id,
+            &format!("{}/data/foo.parquet", &metadata.location),
+            false,
+            state.clone(),
+        )
+        .await
+        .unwrap();
 
-        let exists = table_idents_to_ids(&warehouse_id, tables.clone(), true, &state.read_pool)
-            .await
-            .unwrap();
-        assert_eq!(exists.len(), 1);
-        assert_eq!(
-            exists.get(&table_1.table_ident).unwrap(),
-            &Some(table_1.table_id)
-        );
+        // Shorter path does not work
+        get_table_metadata_by_s3_location(
+            &warehouse_id,
+            &metadata.location[0..metadata.location.len() - 1],
+            false,
+            state.clone(),
+        )
+        .await
+        .unwrap_err();
+    }
 
-        // Second Table
-        let table_2 = initialize_table(&warehouse_id, state.clone(), false).await;
-        tables.insert(&table_2.table_ident);
+    #[sqlx::test]
+    async fn test_cannot_get_table_of_inactive_warehouse(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
 
-        let exists = table_idents_to_ids(&warehouse_id, tables.clone(), false, &state.read_pool)
-            .await
-            .unwrap();
-        assert_eq!(exists.len(), 2);
-        assert!(exists.get(&table_1.table_ident).unwrap().is_none());
-        assert_eq!(
-            exists.get(&table_2.table_ident).unwrap(),
-            &Some(table_2.table_id)
-        );
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let table = initialize_table(&warehouse_id, state.clone(), false).await;
+        let mut transaction = pool.begin().await.unwrap();
+        super::super::warehouse::set_warehouse_status(
+            &warehouse_id,
+            WarehouseStatus::Inactive,
+            &mut transaction,
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
 
-        let exists = table_idents_to_ids(&warehouse_id, tables.clone(), true, &state.read_pool)
+        let err = get_table_metadata_by_id(&warehouse_id, &table.table_id, false, state.clone())
             .await
-            .unwrap();
-        assert_eq!(exists.len(), 2);
-        assert_eq!(
-            exists.get(&table_1.table_ident).unwrap(),
-            &Some(table_1.table_id)
-        );
-        assert_eq!(
-            exists.get(&table_2.table_ident).unwrap(),
-            &Some(table_2.table_id)
-        );
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
     }
 
     #[sqlx::test]
-    async fn test_rename_without_namespace(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
+    async fn test_set_table_policy(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
 
         let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
         let table = initialize_table(&warehouse_id, state.clone(), false).await;
 
-        let new_table_ident = TableIdent {
-            namespace: table.namespace.clone(),
-            name: "new_table".to_string(),
-        };
+        let loaded = load_table(&warehouse_id, &table.table_ident, state.clone())
+            .await
+            .unwrap();
+        assert!(loaded.policy.is_none());
 
+        let policy = TablePolicy {
+            classification: Some("confidential".to_string()),
+            column_sensitivity: HashMap::from([("id".to_string(), "pii".to_string())]),
+            retention_class: Some("short".to_string()),
+        };
         let mut transaction = pool.begin().await.unwrap();
-        rename_table(
+        set_table_policy(
             &warehouse_id,
-            &table.table_id,
             &table.table_ident,
-            &new_table_ident,
+            Some(policy.clone()),
             &mut transaction,
         )
         .await
         .unwrap();
         transaction.commit().await.unwrap();
 
-        let exists = table_ident_to_id(&warehouse_id, &table.table_ident, false, &state.read_pool)
+        let loaded = load_table(&warehouse_id, &table.table_ident, state.clone())
             .await
             .unwrap();
-        assert!(exists.is_none());
+        assert_eq!(loaded.policy, Some(policy));
 
-        let exists = table_ident_to_id(&warehouse_id, &new_table_ident, false, &state.read_pool)
+        let mut transaction = pool.begin().await.unwrap();
+        set_table_policy(&warehouse_id, &table.table_ident, None, &mut transaction)
             .await
             .unwrap();
-        // Table id should be the same
-        assert_eq!(exists, Some(table.table_id));
+        let err = set_table_policy(
+            &warehouse_id,
+            &TableIdent::new(table.namespace.clone(), "missing".to_string()),
+            None,
+            &mut transaction,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
     }
 
     #[sqlx::test]
-    async fn test_rename_with_namespace(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
+    async fn test_list_retention_candidates(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
 
         let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
         let table = initialize_table(&warehouse_id, state.clone(), false).await;
+        assert!(list_retention_candidates(None, 10, state.clone())
+            .await
+            .unwrap()
+            .is_empty());
 
-        let new_namespace = NamespaceIdent::from_vec(vec!["new_namespace".to_string()]).unwrap();
-        initialize_namespace(state.clone(), &warehouse_id, &new_namespace, None).await;
-
-        let new_table_ident = TableIdent {
-            namespace: new_namespace.clone(),
-            name: "new_table".to_string(),
+        let policy = SnapshotRetentionPolicy {
+            max_snapshots: Some(5),
+            ..Default::default()
         };
-
         let mut transaction = pool.begin().await.unwrap();
-        rename_table(
+        super::super::namespace::set_namespace_retention_policy(
             &warehouse_id,
-            &table.table_id,
-            &table.table_ident,
-            &new_table_ident,
+            &table.namespace,
+            Some(policy.clone()),
             &mut transaction,
         )
         .await
         .unwrap();
         transaction.commit().await.unwrap();
 
-        let exists = table_ident_to_id(&warehouse_id, &table.table_ident, false, &state.read_pool)
+        let candidates = list_retention_candidates(None, 10, state.clone())
             .await
             .unwrap();
-        assert!(exists.is_none());
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].table_id, table.table_id);
+        assert_eq!(candidates[0].table, table.table_ident);
+        assert_eq!(candidates[0].policy, Some(policy));
+        assert!(
+            list_retention_candidates(Some(table.table_id), 10, state.clone())
+                .await
+                .unwrap()
+                .is_empty()
+        );
 
-        let exists = table_ident_to_id(&warehouse_id, &new_table_ident, false, &state.read_pool)
-            .await
-            .unwrap();
-        assert_eq!(exists, Some(table.table_id));
+        let mut transaction = pool.begin().await.unwrap();
+        let err = super::super::namespace::set_namespace_retention_policy(
+            &warehouse_id,
+            &NamespaceIdent::new("unknown".to_string()),
+            None,
+            &mut transaction,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
     }
 
     #[sqlx::test]
-    async fn test_list_tables(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
+    async fn test_get_table_metadata_log(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
 
         let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
-        let namespace = NamespaceIdent::from_vec(vec!["my_namespace".to_string()]).unwrap();
-        initialize_namespace(state.clone(), &warehouse_id, &namespace, None).await;
-        let tables = list_tables(&warehouse_id, &namespace, false, state.clone())
+        let table = initialize_table(&warehouse_id, state.clone(), false).await;
+
+        let log = get_table_metadata_log(&warehouse_id, &table.table_id, state.clone())
             .await
             .unwrap();
-        assert_eq!(tables.len(), 0);
-
-        let table1 = initialize_table(&warehouse_id, state.clone(), false).await;
+        assert_eq!(log.len(), 1);
+        let initial_location = log[0].metadata_location.clone();
 
-        let tables = list_tables(&warehouse_id, &table1.namespace, false, state.clone())
+        sqlx::query(r#"UPDATE "table" SET metadata_location = $1 WHERE table_id = $2"#)
+            .bind("s3://my_bucket/my_table/metadata/next.metadata.json")
+            .bind(table.table_id.as_uuid())
+            .execute(&pool)
             .await
             .unwrap();
-        assert_eq!(tables.len(), 1);
-        assert_eq!(tables.get(&table1.table_id), Some(&table1.table_ident));
 
-        let table2 = initialize_table(&warehouse_id, state.clone(), true).await;
-        let tables = list_tables(&warehouse_id, &table2.namespace, false, state.clone())
+        let log = get_table_metadata_log(&warehouse_id, &table.table_id, state.clone())
             .await
             .unwrap();
-        assert_eq!(tables.len(), 0);
-        let tables = list_tables(&warehouse_id, &table2.namespace, true, state.clone())
+        assert_eq!(
+            log.iter()
+                .map(|e| e.metadata_location.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                initial_location.as_str(),
+                "s3://my_bucket/my_table/metadata/next.metadata.json"
+            ]
+        );
+
+        let staged = initialize_table(&warehouse_id, state.clone(), true).await;
+        let err = get_table_metadata_log(&warehouse_id, &staged.table_id, state)
             .await
-            .unwrap();
-        assert_eq!(tables.len(), 1);
-        assert_eq!(tables.get(&table2.table_id), Some(&table2.table_ident));
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
     }
 
     #[sqlx::test]
-    async fn test_commit_transaction(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
+    async fn test_table_commits(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
 
         let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
-        let table1 = initialize_table(&warehouse_id, state.clone(), true).await;
-        let table2 = initialize_table(&warehouse_id, state.clone(), false).await;
+        let table = initialize_table(&warehouse_id, state.clone(), false).await;
 
-        let request = CommitTransactionRequest {
-            table_changes: vec![
-                CommitTableRequest {
-                    identifier: Some(table1.table_ident.clone()),
-                    requirements: vec![TableRequirement::NotExist {}],
-                    updates: vec![TableUpdate::SetProperties {
-                        updates: HashMap::from_iter(vec![(
-                            "t1_key".to_string(),
-                            "t1_value".to_string(),
-                        )]),
-                    }],
-                },
-                CommitTableRequest {
-                    identifier: Some(table2.table_ident.clone()),
-                    requirements: vec![TableRequirement::UuidMatch {
-                        uuid: table2.table_id.as_uuid().to_owned(),
-                    }],
-                    updates: vec![TableUpdate::SetProperties {
-                        updates: HashMap::from_iter(vec![(
-                            "t2_key".to_string(),
-                            "t2_value".to_string(),
-                        )]),
-                    }],
-                },
-            ],
+        let commits = list_table_commits(&warehouse_id, &table.table_id, None, 10, state.clone())
+            .await
+            .unwrap();
+        assert!(commits.is_empty());
+
+        let first = TableCommit {
+            table_id: table.table_id,
+            committed_at: chrono::Utc::now() - chrono::Duration::seconds(10),
+            committed_by: Some("peter".to_string()),
+            snapshot_id: Some(1),
+            operation: Some("append".to_string()),
+            metadata_location: "s3://my_bucket/my_table/metadata/1.metadata.json".to_string(),
+        };
+        let second = TableCommit {
+            committed_at: chrono::Utc::now(),
+            committed_by: None,
+            snapshot_id: None,
+            operation: None,
+            metadata_location: "s3://my_bucket/my_table/metadata/2.metadata.json".to_string(),
+            ..first.clone()
         };
+        let mut transaction = pool.begin().await.unwrap();
+        record_table_commits(&[first.clone(), second.clone()], &mut transaction)
+            .await
+            .unwrap();
+        // Recording the same commit again is a no-op
+        record_table_commits(&[first.clone()], &mut transaction)
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
 
-        let table_ids = table_idents_to_ids(
+        let commits = list_table_commits(&warehouse_id, &table.table_id, None, 10, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            commits
+                .iter()
+                .map(|c| c.metadata_location.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                second.metadata_location.as_str(),
+                first.metadata_location.as_str()
+            ]
+        );
+        assert_eq!(commits[1].committed_by.as_deref(), Some("peter"));
+        assert_eq!(commits[1].snapshot_id, Some(1));
+        assert_eq!(commits[1].operation.as_deref(), Some("append"));
+
+        let commits = list_table_commits(&warehouse_id, &table.table_id, None, 1, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].metadata_location, second.metadata_location);
+
+        let commits = list_table_commits(
             &warehouse_id,
-            vec![&table1.table_ident, &table2.table_ident]
-                .into_iter()
-                .collect(),
-            true,
-            &state.read_pool,
+            &table.table_id,
+            Some(second.committed_at),
+            10,
+            state.clone(),
         )
         .await
-        .unwrap()
-        .into_iter()
-        .map(|(k, v)| (k, v.unwrap()))
-        .collect();
+        .unwrap();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].metadata_location, first.metadata_location);
 
-        let mut transaction = pool.begin().await.unwrap();
-        let responses =
-            commit_table_transaction(&warehouse_id, request, &table_ids, &mut transaction)
-                .await
-                .unwrap();
-        transaction.commit().await.unwrap();
+        let staged = initialize_table(&warehouse_id, state.clone(), true).await;
+        let err = list_table_commits(&warehouse_id, &staged.table_id, None, 10, state)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+    }
 
-        assert_eq!(responses.len(), 2);
+    #[sqlx::test]
+    async fn test_record_lineage_events(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
 
-        let response1 = responses
-            .iter()
-            .find(|r| &r.commit_response.metadata.uuid() == table1.table_id.as_uuid())
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let table = initialize_table(&warehouse_id, state.clone(), false).await;
+
+        let event = LineageEvent {
+            snapshot_id: 1,
+            parent_snapshot_id: None,
+            timestamp_ms: 1_700_000_000_000,
+            operation: "append".to_string(),
+            spark_app_id: Some("local-1700000000000".to_string()),
+            wap_id: None,
+            wap_published: false,
+            source_tables: vec!["db.orders".to_string()],
+            engine_name: None,
+            engine_version: None,
+        };
+        let mut transaction = pool.begin().await.unwrap();
+        record_lineage_events(table.table_id, &[event.clone()], &mut transaction)
+            .await
             .unwrap();
-        let response2 = responses
-            .iter()
-            .find(|r| &r.commit_response.metadata.uuid() == table2.table_id.as_uuid())
+        record_lineage_events(table.table_id, &[event.clone()], &mut transaction)
+            .await
             .unwrap();
+        transaction.commit().await.unwrap();
 
+        let stored: Vec<(i64, serde_json::Value)> = sqlx::query_as(
+            "SELECT snapshot_id, event FROM table_lineage_event WHERE table_id = $1",
+        )
+        .bind(table.table_id.as_uuid())
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].0, 1);
         assert_eq!(
-            response1.commit_response.metadata.properties,
-            HashMap::from_iter(vec![("t1_key".to_string(), "t1_value".to_string())])
-        );
-        assert_eq!(
-            response2.commit_response.metadata.properties,
-            HashMap::from_iter(vec![("t2_key".to_string(), "t2_value".to_string())])
+            serde_json::from_value::<LineageEvent>(stored[0].1.clone()).unwrap(),
+            event
         );
     }
 
     #[sqlx::test]
-    async fn test_get_metadata_by_location(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
+    async fn test_dropped_table_lifecycle(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
 
         let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
         let table = initialize_table(&warehouse_id, state.clone(), false).await;
+        let staged = initialize_table(&warehouse_id, state.clone(), true).await;
 
-        let metadata =
-            get_table_metadata_by_id(&warehouse_id, &table.table_id, false, state.clone())
-                .await
-                .unwrap();
+        let mut transaction = pool.begin().await.unwrap();
+        drop_table(&warehouse_id, &table.table_id, &mut transaction)
+            .await
+            .unwrap();
+        drop_table(&warehouse_id, &staged.table_id, &mut transaction)
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
 
-        // Exact path works
-        let metadata = get_table_metadata_by_s3_location(
-            &warehouse_id,
-            &metadata.location,
-            false,
+        // Not expired yet
+        let dropped = list_expired_dropped_tables(
+            chrono::Utc::now() - chrono::Duration::hours(1),
+            10,
             state.clone(),
         )
         .await
         .unwrap();
+        assert!(dropped.is_empty());
 
-        assert_eq!(metadata.table, table.table_ident);
-        assert_eq!(metadata.table_id, table.table_id);
-
-        // Subpath works
-        let metadata = get_table_metadata_by_s3_location(
-            &warehouse_id,
-            &format!("{}/data/foo.parquet", &metadata.location),
-            false,
-            state.clone(),
-        )
-        .await
-        .unwrap();
+        // Staged tables are not kept
+        let dropped_before = chrono::Utc::now() + chrono::Duration::hours(1);
+        let dropped = list_expired_dropped_tables(dropped_before, 10, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].table_id, table.table_id);
+        assert_eq!(dropped[0].table, table.table_ident);
+        assert_eq!(
+            dropped[0].metadata_locations,
+            vec!["s3://my_bucket/my_table/metadata/foo".to_string()]
+        );
 
-        // Shorter path does not work
-        get_table_metadata_by_s3_location(
-            &warehouse_id,
-            &metadata.location[0..metadata.location.len() - 1],
+        let mut transaction = pool.begin().await.unwrap();
+        // Tables are only reclaimed in their own warehouse
+        let other_warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        let err = reclaim_dropped_table(
+            &other_warehouse_id,
+            &table.table_id,
             false,
-            state.clone(),
+            &mut transaction,
         )
         .await
         .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+        reclaim_dropped_table(&warehouse_id, &table.table_id, false, &mut transaction)
+            .await
+            .unwrap();
+        let err = reclaim_dropped_table(&warehouse_id, &table.table_id, false, &mut transaction)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+        transaction.commit().await.unwrap();
+
+        assert!(list_expired_dropped_tables(dropped_before, 10, state)
+            .await
+            .unwrap()
+            .is_empty());
     }
 
     #[sqlx::test]
-    async fn test_cannot_get_table_of_inactive_warehouse(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
+    async fn test_staged_tables(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
 
         let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
         let table = initialize_table(&warehouse_id, state.clone(), false).await;
-        let mut transaction = pool.begin().await.unwrap();
-        super::super::warehouse::set_warehouse_status(
-            &warehouse_id,
-            WarehouseStatus::Inactive,
-            &mut transaction,
-        )
-        .await
-        .unwrap();
-        transaction.commit().await.unwrap();
+        let staged = initialize_table(&warehouse_id, state.clone(), true).await;
 
-        let err = get_table_metadata_by_id(&warehouse_id, &table.table_id, false, state.clone())
+        let tables = list_staged_tables(&warehouse_id, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].table_id, staged.table_id);
+        assert_eq!(tables[0].table, staged.table_ident);
+
+        let mut transaction = pool.begin().await.unwrap();
+        let err = discard_staged_table(&warehouse_id, &table.table_id, &mut transaction)
             .await
             .unwrap_err();
         assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+        discard_staged_table(&warehouse_id, &staged.table_id, &mut transaction)
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+
+        assert!(list_staged_tables(&warehouse_id, state)
+            .await
+            .unwrap()
+            .is_empty());
     }
 }
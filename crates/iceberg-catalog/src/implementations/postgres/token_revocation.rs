@@ -0,0 +1,135 @@
+use super::dbutils::DBErrorHandler as _;
+use super::CatalogState;
+use crate::api::{ErrorModel, Result};
+use crate::service::token_revocation::{RevocationTarget, TokenRevocation};
+use http::StatusCode;
+
+pub(crate) async fn revoke_token(
+    revocation: &TokenRevocation,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let (token_id, subject) = match &revocation.target {
+        RevocationTarget::TokenId(token_id) => (Some(token_id.as_str()), None),
+        RevocationTarget::Subject(subject) => (None, Some(subject.as_str())),
+    };
+
+    if token_id.or(subject).is_some_and(str::is_empty) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("Token id or subject to revoke cannot be empty".to_string())
+            .r#type("EmptyRevocationTarget".to_string())
+            .build()
+            .into());
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO token_revocation (token_id, subject, revoked_at, expires_at, reason)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        token_id,
+        subject,
+        revocation.revoked_at,
+        revocation.expires_at,
+        revocation.reason
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error revoking token".into()))?;
+
+    Ok(())
+}
+
+pub(crate) async fn list_active_token_revocations(
+    catalog_state: CatalogState,
+) -> Result<Vec<TokenRevocation>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT token_id, subject, revoked_at, expires_at, reason
+        FROM token_revocation
+        WHERE expires_at IS NULL OR expires_at > now()
+        "#
+    )
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching token revocations".into()))?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let target = match (row.token_id, row.subject) {
+                (Some(token_id), _) => RevocationTarget::TokenId(token_id),
+                (None, Some(subject)) => RevocationTarget::Subject(subject),
+                // Prevented by a check constraint
+                (None, None) => return None,
+            };
+            Some(TokenRevocation {
+                target,
+                revoked_at: row.revoked_at,
+                expires_at: row.expires_at,
+                reason: row.reason,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::Utc;
+
+    #[sqlx::test]
+    async fn test_revoke_token(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let revocations = [
+            TokenRevocation {
+                target: RevocationTarget::TokenId("token-1".to_string()),
+                revoked_at: Utc::now(),
+                expires_at: Some(Utc::now() + chrono::Duration::hours(1)),
+                reason: Some("leaked".to_string()),
+            },
+            TokenRevocation {
+                target: RevocationTarget::Subject("alice".to_string()),
+                revoked_at: Utc::now(),
+                expires_at: None,
+                reason: None,
+            },
+            TokenRevocation {
+                target: RevocationTarget::TokenId("expired".to_string()),
+                revoked_at: Utc::now() - chrono::Duration::hours(2),
+                expires_at: Some(Utc::now() - chrono::Duration::hours(1)),
+                reason: None,
+            },
+        ];
+
+        let mut transaction = pool.begin().await.unwrap();
+        for revocation in &revocations {
+            revoke_token(revocation, &mut transaction).await.unwrap();
+        }
+        transaction.commit().await.unwrap();
+
+        let active = list_active_token_revocations(state).await.unwrap();
+        assert_eq!(active.len(), 2);
+        for revocation in &revocations[..2] {
+            assert!(active.iter().any(|r| r.target == revocation.target));
+        }
+    }
+
+    #[sqlx::test]
+    async fn test_revoke_empty_subject(pool: sqlx::PgPool) {
+        let mut transaction = pool.begin().await.unwrap();
+        let err = revoke_token(
+            &TokenRevocation {
+                target: RevocationTarget::Subject(String::new()),
+                revoked_at: Utc::now(),
+                expires_at: None,
+                reason: None,
+            },
+            &mut transaction,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::BAD_REQUEST);
+    }
+}
@@ -1,10 +1,18 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ops::Deref as _;
 
-use crate::api::{CatalogConfig, ErrorModel, Result};
+use crate::api::{CatalogConfig, ErrorModel, ErrorType, Result};
+use crate::implementations::validation::{validate_project_name, validate_warehouse_name};
 use crate::service::config::ConfigProvider;
-use crate::service::{GetWarehouseResponse, WarehouseStatus};
-use crate::{service::storage::StorageProfile, ProjectIdent, SecretIdent, WarehouseIdent};
+use crate::service::{
+    auth::UnauthorizedResponse, credential_rotation::CredentialRotation,
+    storage_validation::StorageValidation, EngineTableConfig, EventFilter, GetWarehouseResponse,
+    Project, PyicebergCompat, SnapshotRetentionPolicy, WarehouseAllowlist, WarehouseStatus,
+};
+use crate::{
+    service::storage::{AccessDelegation, StorageProfile},
+    ProjectIdent, SecretIdent, WarehouseIdent,
+};
 use http::StatusCode;
 
 use super::dbutils::DBErrorHandler as _;
@@ -19,6 +27,7 @@ impl ConfigProvider<Catalog> for super::Catalog {
         project_id: &ProjectIdent,
         catalog_state: CatalogState,
     ) -> Result<WarehouseIdent> {
+        let catalog_state = catalog_state.for_project(project_id);
         let warehouse_id = sqlx::query_scalar!(
             r#"
             SELECT 
@@ -53,10 +62,12 @@ impl ConfigProvider<Catalog> for super::Catalog {
         warehouse_id: &WarehouseIdent,
         catalog_state: CatalogState,
     ) -> Result<CatalogConfig> {
-        let storage_profile = sqlx::query_scalar!(
+        let catalog_state = catalog_state.for_warehouse(warehouse_id).await?;
+        let warehouse = sqlx::query!(
             r#"
             SELECT 
-                storage_profile as "storage_profile: Json<StorageProfile>"
+                storage_profile as "storage_profile: Json<StorageProfile>",
+                signer_uri
             FROM warehouse
             WHERE warehouse_id = $1
             AND status = 'active'
@@ -79,7 +90,18 @@ impl ConfigProvider<Catalog> for super::Catalog {
                 .build(),
         })?;
 
-        Ok(storage_profile.generate_catalog_config(warehouse_id))
+        let mut config = warehouse
+            .storage_profile
+            .generate_catalog_config(warehouse_id);
+        if let Some(signer_uri) = warehouse.signer_uri {
+            if config.overrides.contains_key("s3.signer.uri") {
+                config
+                    .overrides
+                    .insert("s3.signer.uri".to_string(), signer_uri);
+            }
+        }
+
+        Ok(config)
     }
 }
 
@@ -88,6 +110,7 @@ pub(crate) async fn create_warehouse<'a>(
     project_id: ProjectIdent,
     storage_profile: StorageProfile,
     storage_secret_id: Option<SecretIdent>,
+    owner: Option<&str>,
     transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
 ) -> Result<WarehouseIdent> {
     validate_warehouse_name(&warehouse_name)?;
@@ -102,14 +125,15 @@ pub(crate) async fn create_warehouse<'a>(
 
     let warehouse_id = sqlx::query_scalar!(
         r#"
-        INSERT INTO warehouse (warehouse_name, project_id, storage_profile, storage_secret_id, "status")
-        VALUES ($1, $2, $3, $4, 'active')
+        INSERT INTO warehouse (warehouse_name, project_id, storage_profile, storage_secret_id, "status", created_by, "owner")
+        VALUES ($1, $2, $3, $4, 'active', $5, $5)
         RETURNING warehouse_id
         "#,
         warehouse_name,
         project_id.as_uuid(),
         storage_profile_ser,
-        storage_secret_id.map(|id| id.into_uuid())
+        storage_secret_id.map(|id| id.into_uuid()),
+        owner
     )
     .fetch_one(&mut **transaction)
     .await
@@ -120,6 +144,7 @@ pub(crate) async fn create_warehouse<'a>(
                 .code(StatusCode::CONFLICT.into())
                 .message("Warehouse with this name already exists in the project.".to_string())
                 .r#type("WarehouseNameAlreadyExists".to_string())
+                .error_type(ErrorType::AlreadyExists)
                 .build(),
             _ => e.into_error_model("Error creating Warehouse".into()),
         },
@@ -131,6 +156,20 @@ pub(crate) async fn create_warehouse<'a>(
         _ => e.into_error_model("Error creating Warehouse".into()),
     })?;
 
+    // Projects are created with their first warehouse, named after their id.
+    sqlx::query!(
+        r#"
+        INSERT INTO project (project_id, project_name)
+        VALUES ($1, $2)
+        ON CONFLICT DO NOTHING
+        "#,
+        project_id.as_uuid(),
+        project_id.to_string()
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error creating project".into()))?;
+
     Ok(warehouse_id.into())
 }
 
@@ -147,6 +186,22 @@ pub(crate) async fn list_warehouses(
         storage_profile: Json<StorageProfile>,
         storage_secret_id: Option<uuid::Uuid>,
         status: WarehouseStatus,
+        owner: Option<String>,
+        created_by: Option<String>,
+        allowlist: Option<Json<WarehouseAllowlist>>,
+        read_only: bool,
+        pyiceberg_compat: bool,
+        engine_table_config: Json<EngineTableConfig>,
+        signer_uri: Option<String>,
+        unauthorized_response: Option<UnauthorizedResponse>,
+        access_delegation: Option<AccessDelegation>,
+        allow_external_locations: bool,
+        object_storage_layout: bool,
+        storage_validated_at: Option<chrono::DateTime<chrono::Utc>>,
+        storage_validation_error: Option<String>,
+        previous_storage_secret_id: Option<uuid::Uuid>,
+        storage_secret_rotated_at: Option<chrono::DateTime<chrono::Utc>>,
+        event_filter: Json<EventFilter>,
     }
 
     let include_status = include_status.unwrap_or_else(|| vec![WarehouseStatus::Active]);
@@ -163,7 +218,23 @@ pub(crate) async fn list_warehouses(
                 warehouse_name,
                 storage_profile as "storage_profile: Json<StorageProfile>",
                 storage_secret_id,
-                status AS "status: WarehouseStatus"
+                status AS "status: WarehouseStatus",
+                "owner",
+                created_by,
+                allowlist as "allowlist: Json<WarehouseAllowlist>",
+                read_only,
+                pyiceberg_compat,
+                engine_table_config as "engine_table_config: Json<EngineTableConfig>",
+                signer_uri,
+                unauthorized_response as "unauthorized_response: UnauthorizedResponse",
+                access_delegation as "access_delegation: AccessDelegation",
+                allow_external_locations,
+                object_storage_layout,
+                storage_validated_at,
+                storage_validation_error,
+                previous_storage_secret_id,
+                storage_secret_rotated_at,
+                event_filter as "event_filter: Json<EventFilter>"
             FROM warehouse
             WHERE project_id = $1 AND warehouse_id = ANY($2)
             AND status = ANY($3)
@@ -184,7 +255,23 @@ pub(crate) async fn list_warehouses(
                 warehouse_name,
                 storage_profile as "storage_profile: Json<StorageProfile>",
                 storage_secret_id,
-                status AS "status: WarehouseStatus"
+                status AS "status: WarehouseStatus",
+                "owner",
+                created_by,
+                allowlist as "allowlist: Json<WarehouseAllowlist>",
+                read_only,
+                pyiceberg_compat,
+                engine_table_config as "engine_table_config: Json<EngineTableConfig>",
+                signer_uri,
+                unauthorized_response as "unauthorized_response: UnauthorizedResponse",
+                access_delegation as "access_delegation: AccessDelegation",
+                allow_external_locations,
+                object_storage_layout,
+                storage_validated_at,
+                storage_validation_error,
+                previous_storage_secret_id,
+                storage_secret_rotated_at,
+                event_filter as "event_filter: Json<EventFilter>"
             FROM warehouse
             WHERE project_id = $1
             AND status = ANY($2)
@@ -206,10 +293,55 @@ pub(crate) async fn list_warehouses(
             storage_profile: warehouse.storage_profile.deref().clone(),
             storage_secret_id: warehouse.storage_secret_id.map(std::convert::Into::into),
             status: warehouse.status,
+            owner: warehouse.owner,
+            created_by: warehouse.created_by,
+            allowlist: warehouse.allowlist.map(|a| a.0),
+            read_only: warehouse.read_only,
+            pyiceberg_compat: warehouse.pyiceberg_compat,
+            engine_table_config: warehouse.engine_table_config.0,
+            signer_uri: warehouse.signer_uri,
+            unauthorized_response: warehouse.unauthorized_response,
+            access_delegation: warehouse.access_delegation,
+            allow_external_locations: warehouse.allow_external_locations,
+            object_storage_layout: warehouse.object_storage_layout,
+            credential_rotation: warehouse.storage_secret_rotated_at.map(|rotated_at| {
+                CredentialRotation {
+                    rotated_at,
+                    previous_secret_id: warehouse.previous_storage_secret_id.map(Into::into),
+                }
+            }),
+            storage_validation: warehouse.storage_validated_at.map(|validated_at| {
+                StorageValidation {
+                    validated_at,
+                    error: warehouse.storage_validation_error,
+                }
+            }),
+            event_filter: warehouse.event_filter.0,
         })
         .collect())
 }
 
+pub(crate) async fn get_warehouse_id_by_name(
+    project_id: &ProjectIdent,
+    warehouse_name: &str,
+    catalog_state: CatalogState,
+) -> Result<Option<WarehouseIdent>> {
+    let warehouse_id = sqlx::query_scalar!(
+        r#"
+        SELECT warehouse_id
+        FROM warehouse
+        WHERE project_id = $1 AND warehouse_name = $2
+        "#,
+        project_id.as_uuid(),
+        warehouse_name
+    )
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching warehouse".into()))?;
+
+    Ok(warehouse_id.map(Into::into))
+}
+
 pub(crate) async fn get_warehouse<'a>(
     warehouse_id: &WarehouseIdent,
     transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
@@ -221,7 +353,23 @@ pub(crate) async fn get_warehouse<'a>(
             project_id,
             storage_profile as "storage_profile: Json<StorageProfile>",
             storage_secret_id,
-            status AS "status: WarehouseStatus"
+            status AS "status: WarehouseStatus",
+            "owner",
+            created_by,
+            allowlist as "allowlist: Json<WarehouseAllowlist>",
+            read_only,
+            pyiceberg_compat,
+            engine_table_config as "engine_table_config: Json<EngineTableConfig>",
+            signer_uri,
+            unauthorized_response as "unauthorized_response: UnauthorizedResponse",
+            access_delegation as "access_delegation: AccessDelegation",
+            allow_external_locations,
+            object_storage_layout,
+            storage_validated_at,
+            storage_validation_error,
+            previous_storage_secret_id,
+            storage_secret_rotated_at,
+            event_filter as "event_filter: Json<EventFilter>"
         FROM warehouse
         WHERE warehouse_id = $1
         "#,
@@ -245,59 +393,87 @@ pub(crate) async fn get_warehouse<'a>(
         storage_profile: warehouse.storage_profile.deref().clone(),
         storage_secret_id: warehouse.storage_secret_id.map(std::convert::Into::into),
         status: warehouse.status,
+        owner: warehouse.owner,
+        created_by: warehouse.created_by,
+        allowlist: warehouse.allowlist.map(|a| a.0),
+        read_only: warehouse.read_only,
+        pyiceberg_compat: warehouse.pyiceberg_compat,
+        engine_table_config: warehouse.engine_table_config.0,
+        signer_uri: warehouse.signer_uri,
+        unauthorized_response: warehouse.unauthorized_response,
+        access_delegation: warehouse.access_delegation,
+        allow_external_locations: warehouse.allow_external_locations,
+        object_storage_layout: warehouse.object_storage_layout,
+        credential_rotation: warehouse.storage_secret_rotated_at.map(|rotated_at| {
+            CredentialRotation {
+                rotated_at,
+                previous_secret_id: warehouse.previous_storage_secret_id.map(Into::into),
+            }
+        }),
+        storage_validation: warehouse
+            .storage_validated_at
+            .map(|validated_at| StorageValidation {
+                validated_at,
+                error: warehouse.storage_validation_error,
+            }),
+        event_filter: warehouse.event_filter.0,
     })
 }
 
-pub(crate) async fn list_projects(catalog_state: CatalogState) -> Result<HashSet<ProjectIdent>> {
-    let projects = sqlx::query!(
+pub(crate) async fn get_warehouse_allowlist(
+    warehouse_id: &WarehouseIdent,
+    catalog_state: CatalogState,
+) -> Result<Option<WarehouseAllowlist>> {
+    let allowlist = sqlx::query_scalar!(
         r#"
-        SELECT DISTINCT
-            project_id
+        SELECT allowlist as "allowlist: Json<WarehouseAllowlist>"
         FROM warehouse
+        WHERE warehouse_id = $1
         "#,
+        warehouse_id.as_uuid()
     )
-    .fetch_all(&catalog_state.read_pool)
+    .fetch_optional(&catalog_state.read_pool)
     .await
-    .map_err(|e| e.into_error_model("Error fetching projects".into()))?;
+    .map_err(|e| e.into_error_model("Error fetching warehouse allowlist".into()))?;
 
-    Ok(projects
-        .into_iter()
-        .map(|project| ProjectIdent::from(project.project_id))
-        .collect())
+    Ok(allowlist.flatten().map(|a| a.0))
 }
 
-pub(crate) async fn delete_warehouse<'a>(
+pub(crate) async fn set_warehouse_allowlist<'a>(
     warehouse_id: &WarehouseIdent,
+    allowlist: Option<WarehouseAllowlist>,
     transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
 ) -> Result<()> {
+    let allowlist_ser = allowlist
+        .map(|a| {
+            serde_json::to_value(a).map_err(|e| {
+                ErrorModel::builder()
+                    .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                    .message("Error serializing warehouse allowlist".to_string())
+                    .r#type("WarehouseAllowlistSerializationError".to_string())
+                    .stack(Some(vec![e.to_string()]))
+                    .build()
+            })
+        })
+        .transpose()?;
+
     let row_count = sqlx::query_scalar!(
         r#"
-        With deleted as (
-            DELETE FROM warehouse
-            WHERE warehouse_id = $1
-            Returning *
+        with update as (
+            UPDATE warehouse
+            SET allowlist = $1
+            WHERE warehouse_id = $2
+            RETURNING *
         )
 
-        SELECT count(*) FROM deleted
+        SELECT count(*) FROM update
         "#,
+        allowlist_ser,
         warehouse_id.as_uuid()
     )
     .fetch_one(&mut **transaction)
     .await
-    .map_err(|e| match &e {
-        sqlx::Error::Database(db_error) => {
-            if db_error.is_foreign_key_violation() {
-                ErrorModel::builder()
-                    .code(StatusCode::CONFLICT.into())
-                    .message("Warehouse is not empty".to_string())
-                    .r#type("WarehouseNotEmpty".to_string())
-                    .build()
-            } else {
-                e.into_error_model("Error deleting warehouse".into())
-            }
-        }
-        _ => e.into_error_model("Error deleting warehouse".into()),
-    })?;
+    .map_err(|e| e.into_error_model("Error setting warehouse allowlist".into()))?;
 
     if row_count == Some(0) {
         return Err(ErrorModel::builder()
@@ -311,31 +487,47 @@ pub(crate) async fn delete_warehouse<'a>(
     Ok(())
 }
 
-pub(crate) async fn rename_warehouse<'a>(
+pub(crate) async fn is_warehouse_read_only(
     warehouse_id: &WarehouseIdent,
-    new_name: &str,
+    catalog_state: CatalogState,
+) -> Result<bool> {
+    let read_only = sqlx::query_scalar!(
+        r#"
+        SELECT read_only
+        FROM warehouse
+        WHERE warehouse_id = $1
+        "#,
+        warehouse_id.as_uuid()
+    )
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching warehouse".into()))?;
+
+    Ok(read_only.unwrap_or(false))
+}
+
+pub(crate) async fn set_warehouse_read_only(
+    warehouse_id: &WarehouseIdent,
+    read_only: bool,
     transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
 ) -> Result<()> {
-    validate_warehouse_name(new_name)?;
-
     let row_count = sqlx::query_scalar!(
         r#"
         with update as (
             UPDATE warehouse
-            SET warehouse_name = $1
+            SET read_only = $1
             WHERE warehouse_id = $2
-            AND status = 'active'
             RETURNING *
         )
 
         SELECT count(*) FROM update
         "#,
-        new_name,
+        read_only,
         warehouse_id.as_uuid()
     )
     .fetch_one(&mut **transaction)
     .await
-    .map_err(|e| e.into_error_model("Error renaming warehouse".into()))?;
+    .map_err(|e| e.into_error_model("Error setting warehouse read-only mode".into()))?;
 
     if row_count == Some(0) {
         return Err(ErrorModel::builder()
@@ -349,28 +541,47 @@ pub(crate) async fn rename_warehouse<'a>(
     Ok(())
 }
 
-pub(crate) async fn set_warehouse_status<'a>(
+pub(crate) async fn get_warehouse_signer_uri(
     warehouse_id: &WarehouseIdent,
-    status: WarehouseStatus,
+    catalog_state: CatalogState,
+) -> Result<Option<String>> {
+    let signer_uri = sqlx::query_scalar!(
+        r#"
+        SELECT signer_uri
+        FROM warehouse
+        WHERE warehouse_id = $1
+        "#,
+        warehouse_id.as_uuid()
+    )
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching warehouse signer uri".into()))?;
+
+    Ok(signer_uri.flatten())
+}
+
+pub(crate) async fn set_warehouse_signer_uri(
+    warehouse_id: &WarehouseIdent,
+    signer_uri: Option<&url::Url>,
     transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
 ) -> Result<()> {
     let row_count = sqlx::query_scalar!(
         r#"
         with update as (
             UPDATE warehouse
-            SET status = $1
+            SET signer_uri = $1
             WHERE warehouse_id = $2
             RETURNING *
         )
 
         SELECT count(*) FROM update
         "#,
-        status as WarehouseStatus,
+        signer_uri.map(url::Url::as_str),
         warehouse_id.as_uuid()
     )
     .fetch_one(&mut **transaction)
     .await
-    .map_err(|e| e.into_error_model("Error setting warehouse status".into()))?;
+    .map_err(|e| e.into_error_model("Error setting warehouse signer uri".into()))?;
 
     if row_count == Some(0) {
         return Err(ErrorModel::builder()
@@ -384,40 +595,28 @@ pub(crate) async fn set_warehouse_status<'a>(
     Ok(())
 }
 
-pub(crate) async fn update_storage_profile<'a>(
+pub(crate) async fn set_warehouse_unauthorized_response(
     warehouse_id: &WarehouseIdent,
-    storage_profile: StorageProfile,
-    storage_secret_id: Option<SecretIdent>,
+    unauthorized_response: Option<UnauthorizedResponse>,
     transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
 ) -> Result<()> {
-    let storage_profile_ser = serde_json::to_value(storage_profile).map_err(|e| {
-        ErrorModel::builder()
-            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
-            .message("Error serializing storage profile".to_string())
-            .r#type("StorageProfileSerializationError".to_string())
-            .stack(Some(vec![e.to_string()]))
-            .build()
-    })?;
-
     let row_count = sqlx::query_scalar!(
         r#"
         with update as (
             UPDATE warehouse
-            SET storage_profile = $1, storage_secret_id = $2
-            WHERE warehouse_id = $3
-            AND status = 'active'
+            SET unauthorized_response = $1
+            WHERE warehouse_id = $2
             RETURNING *
         )
 
         SELECT count(*) FROM update
         "#,
-        storage_profile_ser,
-        storage_secret_id.map(|id| id.into_uuid()),
+        unauthorized_response as Option<UnauthorizedResponse>,
         warehouse_id.as_uuid()
     )
     .fetch_one(&mut **transaction)
     .await
-    .map_err(|e| e.into_error_model("Error updating storage profile".into()))?;
+    .map_err(|e| e.into_error_model("Error setting warehouse unauthorized response".into()))?;
 
     if row_count == Some(0) {
         return Err(ErrorModel::builder()
@@ -431,192 +630,1547 @@ pub(crate) async fn update_storage_profile<'a>(
     Ok(())
 }
 
-fn validate_warehouse_name(warehouse_name: &str) -> Result<()> {
-    if warehouse_name.is_empty() {
+pub(crate) async fn set_warehouse_access_delegation(
+    warehouse_id: &WarehouseIdent,
+    access_delegation: Option<AccessDelegation>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let row_count = sqlx::query_scalar!(
+        r#"
+        with update as (
+            UPDATE warehouse
+            SET access_delegation = $1
+            WHERE warehouse_id = $2
+            RETURNING *
+        )
+
+        SELECT count(*) FROM update
+        "#,
+        access_delegation as Option<AccessDelegation>,
+        warehouse_id.as_uuid()
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error setting warehouse access delegation".into()))?;
+
+    if row_count == Some(0) {
         return Err(ErrorModel::builder()
-            .code(StatusCode::BAD_REQUEST.into())
-            .message("Warehouse name cannot be empty".to_string())
-            .r#type("EmptyWarehouseName".to_string())
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Warehouse not found".to_string())
+            .r#type("WarehouseNotFound".to_string())
             .build()
             .into());
     }
+
     Ok(())
 }
 
-#[cfg(test)]
-pub(crate) mod test {
-    use super::*;
-    use crate::{
-        implementations::postgres::PostgresTransaction,
-        service::{storage::S3Profile, Catalog as _, Transaction as _},
-    };
-
-    pub(crate) async fn initialize_warehouse(
-        state: CatalogState,
-        storage_profile: Option<StorageProfile>,
-        project_id: Option<&ProjectIdent>,
-    ) -> crate::WarehouseIdent {
-        let project_id = project_id.map_or(
-            ProjectIdent::from(uuid::Uuid::nil()),
-            std::borrow::ToOwned::to_owned,
-        );
-        let mut transaction = PostgresTransaction::begin_write(state.clone())
-            .await
-            .unwrap();
-
-        let storage_profile = storage_profile.unwrap_or(StorageProfile::S3(S3Profile {
-            bucket: "test_bucket".to_string(),
-            endpoint: None,
-            region: "us-east-1".to_string(),
-            assume_role_arn: None,
-            path_style_access: None,
-            key_prefix: None,
-        }));
-
-        let warehouse_id = Catalog::create_warehouse(
-            "test_warehouse".to_string(),
-            project_id,
-            storage_profile,
-            None,
-            transaction.transaction(),
+pub(crate) async fn set_warehouse_allow_external_locations(
+    warehouse_id: &WarehouseIdent,
+    allow_external_locations: bool,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let row_count = sqlx::query_scalar!(
+        r#"
+        with update as (
+            UPDATE warehouse
+            SET allow_external_locations = $1
+            WHERE warehouse_id = $2
+            RETURNING *
         )
-        .await
-        .unwrap();
 
-        transaction.commit().await.unwrap();
-        warehouse_id
+        SELECT count(*) FROM update
+        "#,
+        allow_external_locations,
+        warehouse_id.as_uuid()
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error setting warehouse external locations".into()))?;
+
+    if row_count == Some(0) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Warehouse not found".to_string())
+            .r#type("WarehouseNotFound".to_string())
+            .build()
+            .into());
     }
 
-    #[sqlx::test]
-    async fn test_get_warehouse_by_name(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
-        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+    Ok(())
+}
 
-        let fetched_warehouse_id = Catalog::get_warehouse_by_name(
-            "test_warehouse",
-            &ProjectIdent::from(uuid::Uuid::nil()),
-            state.clone(),
+pub(crate) async fn set_warehouse_object_storage_layout(
+    warehouse_id: &WarehouseIdent,
+    object_storage_layout: bool,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let row_count = sqlx::query_scalar!(
+        r#"
+        with update as (
+            UPDATE warehouse
+            SET object_storage_layout = $1
+            WHERE warehouse_id = $2
+            RETURNING *
         )
-        .await
-        .unwrap();
 
-        assert_eq!(warehouse_id, fetched_warehouse_id);
-    }
+        SELECT count(*) FROM update
+        "#,
+        object_storage_layout,
+        warehouse_id.as_uuid()
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error setting warehouse object storage layout".into()))?;
 
-    #[sqlx::test]
-    async fn test_list_projects(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
-        let project_id_1 = ProjectIdent::from(uuid::Uuid::new_v4());
-        initialize_warehouse(state.clone(), None, Some(&project_id_1)).await;
+    if row_count == Some(0) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Warehouse not found".to_string())
+            .r#type("WarehouseNotFound".to_string())
+            .build()
+            .into());
+    }
 
-        let projects = Catalog::list_projects(state.clone()).await.unwrap();
-        assert_eq!(projects.len(), 1);
-        assert!(projects.contains(&project_id_1));
+    Ok(())
+}
 
-        let project_id_2 = ProjectIdent::from(uuid::Uuid::new_v4());
-        initialize_warehouse(state.clone(), None, Some(&project_id_2)).await;
+pub(crate) async fn rotate_warehouse_storage_secret(
+    warehouse_id: &WarehouseIdent,
+    current_secret_id: Option<&SecretIdent>,
+    new_secret_id: &SecretIdent,
+    rotated_at: chrono::DateTime<chrono::Utc>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let row_count = sqlx::query_scalar!(
+        r#"
+        with update as (
+            UPDATE warehouse
+            SET previous_storage_secret_id = storage_secret_id,
+                storage_secret_id = $1,
+                storage_secret_rotated_at = $2
+            WHERE warehouse_id = $3
+            AND status = 'active'
+            AND storage_secret_id IS NOT DISTINCT FROM $4
+            RETURNING *
+        )
+
+        SELECT count(*) FROM update
+        "#,
+        new_secret_id.as_uuid(),
+        rotated_at,
+        warehouse_id.as_uuid(),
+        current_secret_id.map(SecretIdent::into_uuid)
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error rotating warehouse storage secret".into()))?;
+
+    if row_count == Some(0) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::CONFLICT.into())
+            .message(
+                "Warehouse is not active or its credential was changed concurrently".to_string(),
+            )
+            .r#type("CredentialRotationConflict".to_string())
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn clear_warehouse_previous_storage_secret(
+    warehouse_id: &WarehouseIdent,
+    previous_secret_id: &SecretIdent,
+    catalog_state: CatalogState,
+) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE warehouse
+        SET previous_storage_secret_id = NULL
+        WHERE warehouse_id = $1 AND previous_storage_secret_id = $2
+        "#,
+        warehouse_id.as_uuid(),
+        previous_secret_id.as_uuid()
+    )
+    .execute(&catalog_state.write_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error clearing previous warehouse storage secret".into()))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub(crate) async fn set_warehouse_storage_validation(
+    warehouse_id: &WarehouseIdent,
+    validation: &StorageValidation,
+    catalog_state: CatalogState,
+) -> Result<()> {
+    // Warehouses deleted in the meantime are ignored
+    sqlx::query!(
+        r#"
+        UPDATE warehouse
+        SET storage_validated_at = $1, storage_validation_error = $2
+        WHERE warehouse_id = $3
+        "#,
+        validation.validated_at,
+        validation.error,
+        warehouse_id.as_uuid()
+    )
+    .execute(&catalog_state.write_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error storing warehouse storage validation".into()))?;
+
+    Ok(())
+}
+
+pub(crate) async fn set_warehouse_retention_policy(
+    warehouse_id: &WarehouseIdent,
+    policy: Option<SnapshotRetentionPolicy>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let policy_ser = policy
+        .map(|p| {
+            serde_json::to_value(p).map_err(|e| {
+                ErrorModel::builder()
+                    .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                    .message("Error serializing retention policy".to_string())
+                    .r#type("RetentionPolicySerializationError".to_string())
+                    .stack(Some(vec![e.to_string()]))
+                    .build()
+            })
+        })
+        .transpose()?;
+
+    let row_count = sqlx::query_scalar!(
+        r#"
+        with update as (
+            UPDATE warehouse
+            SET retention_policy = $1
+            WHERE warehouse_id = $2
+            RETURNING *
+        )
+
+        SELECT count(*) FROM update
+        "#,
+        policy_ser,
+        warehouse_id.as_uuid()
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error setting warehouse retention policy".into()))?;
+
+    if row_count == Some(0) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Warehouse not found".to_string())
+            .r#type("WarehouseNotFound".to_string())
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn get_engine_table_config(
+    warehouse_id: &WarehouseIdent,
+    catalog_state: CatalogState,
+) -> Result<EngineTableConfig> {
+    let config = sqlx::query_scalar!(
+        r#"
+        SELECT engine_table_config as "engine_table_config: Json<EngineTableConfig>"
+        FROM warehouse
+        WHERE warehouse_id = $1
+        "#,
+        warehouse_id.as_uuid()
+    )
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching engine table config".into()))?;
+
+    Ok(config.map(|c| c.0).unwrap_or_default())
+}
+
+pub(crate) async fn set_engine_table_config(
+    warehouse_id: &WarehouseIdent,
+    config: EngineTableConfig,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let config_ser = serde_json::to_value(config).map_err(|e| {
+        ErrorModel::builder()
+            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+            .message("Error serializing engine table config".to_string())
+            .r#type("EngineTableConfigSerializationError".to_string())
+            .stack(Some(vec![e.to_string()]))
+            .build()
+    })?;
+
+    let row_count = sqlx::query_scalar!(
+        r#"
+        with update as (
+            UPDATE warehouse
+            SET engine_table_config = $1
+            WHERE warehouse_id = $2
+            RETURNING *
+        )
+
+        SELECT count(*) FROM update
+        "#,
+        config_ser,
+        warehouse_id.as_uuid()
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error setting engine table config".into()))?;
+
+    if row_count == Some(0) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Warehouse not found".to_string())
+            .r#type("WarehouseNotFound".to_string())
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn set_event_filter(
+    warehouse_id: &WarehouseIdent,
+    filter: EventFilter,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let filter_ser = serde_json::to_value(filter).map_err(|e| {
+        ErrorModel::builder()
+            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+            .message("Error serializing event filter".to_string())
+            .r#type("EventFilterSerializationError".to_string())
+            .stack(Some(vec![e.to_string()]))
+            .build()
+    })?;
+
+    let row_count = sqlx::query_scalar!(
+        r#"
+        with update as (
+            UPDATE warehouse
+            SET event_filter = $1
+            WHERE warehouse_id = $2
+            RETURNING *
+        )
+
+        SELECT count(*) FROM update
+        "#,
+        filter_ser,
+        warehouse_id.as_uuid()
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error setting event filter".into()))?;
+
+    if row_count == Some(0) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Warehouse not found".to_string())
+            .r#type("WarehouseNotFound".to_string())
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn get_pyiceberg_compat(
+    warehouse_id: &WarehouseIdent,
+    catalog_state: CatalogState,
+) -> Result<Option<PyicebergCompat>> {
+    let warehouse_name = sqlx::query_scalar!(
+        r#"
+        SELECT warehouse_name
+        FROM warehouse
+        WHERE warehouse_id = $1 AND pyiceberg_compat
+        "#,
+        warehouse_id.as_uuid()
+    )
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching warehouse".into()))?;
+
+    Ok(warehouse_name.map(|warehouse_name| PyicebergCompat { warehouse_name }))
+}
+
+pub(crate) async fn set_warehouse_pyiceberg_compat(
+    warehouse_id: &WarehouseIdent,
+    enabled: bool,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let row_count = sqlx::query_scalar!(
+        r#"
+        with update as (
+            UPDATE warehouse
+            SET pyiceberg_compat = $1
+            WHERE warehouse_id = $2
+            RETURNING *
+        )
+
+        SELECT count(*) FROM update
+        "#,
+        enabled,
+        warehouse_id.as_uuid()
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error setting warehouse pyiceberg compatibility".into()))?;
+
+    if row_count == Some(0) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Warehouse not found".to_string())
+            .r#type("WarehouseNotFound".to_string())
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn list_projects(catalog_state: CatalogState) -> Result<HashSet<ProjectIdent>> {
+    let projects = sqlx::query!(
+        r#"
+        SELECT project_id AS "project_id!" FROM project
+        UNION
+        SELECT project_id FROM warehouse
+        "#,
+    )
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching projects".into()))?;
+
+    Ok(projects
+        .into_iter()
+        .map(|project| ProjectIdent::from(project.project_id))
+        .collect())
+}
+
+pub(crate) async fn get_projects(
+    project_ids: Option<&HashSet<ProjectIdent>>,
+    catalog_state: CatalogState,
+) -> Result<Vec<Project>> {
+    let project_ids = project_ids.map(|ids| {
+        ids.iter()
+            .map(ProjectIdent::into_uuid)
+            .collect::<Vec<uuid::Uuid>>()
+    });
+    let projects = sqlx::query!(
+        r#"
+        SELECT project_id, project_name, metadata as "metadata: Json<HashMap<String, String>>"
+        FROM project
+        WHERE $1::uuid[] IS NULL OR project_id = ANY($1)
+        ORDER BY project_name
+        "#,
+        project_ids.as_deref()
+    )
+    .fetch_all(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching projects".into()))?;
+
+    Ok(projects
+        .into_iter()
+        .map(|project| Project {
+            project_id: project.project_id.into(),
+            name: project.project_name,
+            metadata: project.metadata.0,
+        })
+        .collect())
+}
+
+pub(crate) async fn get_project_id_by_name(
+    project_name: &str,
+    catalog_state: CatalogState,
+) -> Result<Option<ProjectIdent>> {
+    let project_id = sqlx::query_scalar!(
+        r#"
+        SELECT project_id
+        FROM project
+        WHERE project_name = $1
+        "#,
+        project_name
+    )
+    .fetch_optional(&catalog_state.read_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching project".into()))?;
+
+    Ok(project_id.map(Into::into))
+}
+
+pub(crate) async fn rename_project<'a>(
+    project_id: &ProjectIdent,
+    new_name: &str,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    validate_project_name(project_id, new_name)?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO project (project_id, project_name)
+        VALUES ($1, $2)
+        ON CONFLICT (project_id) DO UPDATE SET project_name = EXCLUDED.project_name
+        "#,
+        project_id.as_uuid(),
+        new_name
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db_err) if db_err.constraint() == Some("unique_project_name") => {
+            ErrorModel::builder()
+                .code(StatusCode::CONFLICT.into())
+                .message("Project with this name already exists.".to_string())
+                .r#type("ProjectNameAlreadyExists".to_string())
+                .error_type(ErrorType::AlreadyExists)
+                .build()
+        }
+        _ => e.into_error_model("Error renaming project".into()),
+    })?;
+
+    Ok(())
+}
+
+pub(crate) async fn set_project_metadata<'a>(
+    project_id: &ProjectIdent,
+    metadata: &HashMap<String, String>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO project (project_id, project_name, metadata)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (project_id) DO UPDATE SET metadata = EXCLUDED.metadata
+        "#,
+        project_id.as_uuid(),
+        project_id.to_string(),
+        Json(metadata) as _
+    )
+    .execute(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error setting project metadata".into()))?;
+
+    Ok(())
+}
+
+pub(crate) async fn delete_project<'a>(
+    project_id: &ProjectIdent,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let has_warehouses = sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM warehouse WHERE project_id = $1) as "exists!""#,
+        project_id.as_uuid()
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error fetching warehouses of project".into()))?;
+    if has_warehouses {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::CONFLICT.into())
+            .message("Project still has warehouses".to_string())
+            .r#type("ProjectNotEmpty".to_string())
+            .error_type(ErrorType::Conflict)
+            .build()
+            .into());
+    }
+
+    // Roles on the project are deleted by the foreign key
+    let row_count = sqlx::query_scalar!(
+        r#"
+        WITH deleted AS (
+            DELETE FROM project
+            WHERE project_id = $1
+            RETURNING project_id
+        )
+        SELECT count(project_id) FROM deleted
+        "#,
+        project_id.as_uuid()
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error deleting project".into()))?;
+
+    if row_count == Some(0) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Project not found".to_string())
+            .r#type("ProjectNotFound".to_string())
+            .error_type(ErrorType::NotFound)
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn delete_warehouse<'a>(
+    warehouse_id: &WarehouseIdent,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let row_count = sqlx::query_scalar!(
+        r#"
+        With deleted as (
+            DELETE FROM warehouse
+            WHERE warehouse_id = $1
+            Returning *
+        )
+
+        SELECT count(*) FROM deleted
+        "#,
+        warehouse_id.as_uuid()
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db_error) => {
+            if db_error.is_foreign_key_violation() {
+                ErrorModel::builder()
+                    .code(StatusCode::CONFLICT.into())
+                    .message("Warehouse is not empty".to_string())
+                    .r#type("WarehouseNotEmpty".to_string())
+                    .build()
+            } else {
+                e.into_error_model("Error deleting warehouse".into())
+            }
+        }
+        _ => e.into_error_model("Error deleting warehouse".into()),
+    })?;
+
+    if row_count == Some(0) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Warehouse not found".to_string())
+            .r#type("WarehouseNotFound".to_string())
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn rename_warehouse<'a>(
+    warehouse_id: &WarehouseIdent,
+    new_name: &str,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    validate_warehouse_name(new_name)?;
+
+    let row_count = sqlx::query_scalar!(
+        r#"
+        with update as (
+            UPDATE warehouse
+            SET warehouse_name = $1
+            WHERE warehouse_id = $2
+            AND status = 'active'
+            RETURNING *
+        )
+
+        SELECT count(*) FROM update
+        "#,
+        new_name,
+        warehouse_id.as_uuid()
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error renaming warehouse".into()))?;
+
+    if row_count == Some(0) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Warehouse not found".to_string())
+            .r#type("WarehouseNotFound".to_string())
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn set_warehouse_status<'a>(
+    warehouse_id: &WarehouseIdent,
+    status: WarehouseStatus,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let row_count = sqlx::query_scalar!(
+        r#"
+        with update as (
+            UPDATE warehouse
+            SET status = $1
+            WHERE warehouse_id = $2
+            RETURNING *
+        )
+
+        SELECT count(*) FROM update
+        "#,
+        status as WarehouseStatus,
+        warehouse_id.as_uuid()
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error setting warehouse status".into()))?;
+
+    if row_count == Some(0) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Warehouse not found".to_string())
+            .r#type("WarehouseNotFound".to_string())
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn update_storage_profile<'a>(
+    warehouse_id: &WarehouseIdent,
+    storage_profile: StorageProfile,
+    storage_secret_id: Option<SecretIdent>,
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> Result<()> {
+    let storage_profile_ser = serde_json::to_value(storage_profile).map_err(|e| {
+        ErrorModel::builder()
+            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+            .message("Error serializing storage profile".to_string())
+            .r#type("StorageProfileSerializationError".to_string())
+            .stack(Some(vec![e.to_string()]))
+            .build()
+    })?;
+
+    let row_count = sqlx::query_scalar!(
+        r#"
+        with update as (
+            UPDATE warehouse
+            SET storage_profile = $1, storage_secret_id = $2
+            WHERE warehouse_id = $3
+            AND status = 'active'
+            RETURNING *
+        )
+
+        SELECT count(*) FROM update
+        "#,
+        storage_profile_ser,
+        storage_secret_id.map(|id| id.into_uuid()),
+        warehouse_id.as_uuid()
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .map_err(|e| e.into_error_model("Error updating storage profile".into()))?;
+
+    if row_count == Some(0) {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::NOT_FOUND.into())
+            .message("Warehouse not found".to_string())
+            .r#type("WarehouseNotFound".to_string())
+            .build()
+            .into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::{
+        implementations::postgres::PostgresTransaction,
+        service::{storage::S3Profile, Catalog as _, Transaction as _},
+        CONFIG,
+    };
+    use std::collections::HashMap;
+
+    pub(crate) async fn initialize_warehouse(
+        state: CatalogState,
+        storage_profile: Option<StorageProfile>,
+        project_id: Option<&ProjectIdent>,
+    ) -> crate::WarehouseIdent {
+        let project_id = project_id.map_or(
+            ProjectIdent::from(uuid::Uuid::nil()),
+            std::borrow::ToOwned::to_owned,
+        );
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+
+        let storage_profile = storage_profile.unwrap_or(StorageProfile::S3(S3Profile {
+            bucket: "test_bucket".to_string(),
+            endpoint: None,
+            region: "us-east-1".to_string(),
+            assume_role_arn: None,
+            external_id: None,
+            path_style_access: None,
+            sts_enabled: false,
+            flavor: S3Flavor::Aws,
+            sse_algorithm: None,
+            kms_key_arn: None,
+            key_prefix: None,
+        }));
+
+        let warehouse_id = Catalog::create_warehouse(
+            "test_warehouse".to_string(),
+            project_id,
+            storage_profile,
+            None,
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+
+        transaction.commit().await.unwrap();
+        warehouse_id
+    }
+
+    #[sqlx::test]
+    async fn test_get_warehouse_by_name(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+
+        let fetched_warehouse_id = Catalog::get_warehouse_by_name(
+            "test_warehouse",
+            &ProjectIdent::from(uuid::Uuid::nil()),
+            state.clone(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(warehouse_id, fetched_warehouse_id);
+    }
+
+    #[sqlx::test]
+    async fn test_get_warehouse_id_by_name(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let project_id = ProjectIdent::from(uuid::Uuid::new_v4());
+        let warehouse_id = initialize_warehouse(state.clone(), None, Some(&project_id)).await;
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        Catalog::set_warehouse_status(
+            &warehouse_id,
+            WarehouseStatus::Inactive,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        // Inactive warehouses are resolved as well.
+        let fetched =
+            Catalog::get_warehouse_id_by_name(&project_id, "test_warehouse", state.clone())
+                .await
+                .unwrap();
+        assert_eq!(fetched, Some(warehouse_id));
+
+        let other_project = ProjectIdent::from(uuid::Uuid::new_v4());
+        assert!(
+            Catalog::get_warehouse_id_by_name(&other_project, "test_warehouse", state.clone())
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_list_projects(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let project_id_1 = ProjectIdent::from(uuid::Uuid::new_v4());
+        initialize_warehouse(state.clone(), None, Some(&project_id_1)).await;
+
+        let projects = Catalog::list_projects(state.clone()).await.unwrap();
+        assert_eq!(projects.len(), 1);
+        assert!(projects.contains(&project_id_1));
+
+        let project_id_2 = ProjectIdent::from(uuid::Uuid::new_v4());
+        initialize_warehouse(state.clone(), None, Some(&project_id_2)).await;
+
+        let projects = Catalog::list_projects(state.clone()).await.unwrap();
+        assert_eq!(projects.len(), 2);
+        assert!(projects.contains(&project_id_1));
+        assert!(projects.contains(&project_id_2));
+    }
+
+    #[sqlx::test]
+    async fn test_rename_project(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let project_id = ProjectIdent::from(uuid::Uuid::new_v4());
+        initialize_warehouse(state.clone(), None, Some(&project_id)).await;
+
+        // Projects are named after their id until renamed.
+        let projects = Catalog::get_projects(None, state.clone()).await.unwrap();
+        assert_eq!(projects, vec![Project::unnamed(project_id.clone())]);
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        Catalog::rename_project(&project_id, "Analytics", transaction.transaction())
+            .await
+            .unwrap();
+        let metadata = HashMap::from_iter([("team".to_string(), "data".to_string())]);
+        Catalog::set_project_metadata(&project_id, &metadata, transaction.transaction())
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+
+        // Lookups by name are case insensitive.
+        let fetched = Catalog::get_project_id_by_name("analytics", state.clone())
+            .await
+            .unwrap();
+        assert_eq!(fetched, Some(project_id.clone()));
+        let projects = Catalog::get_projects(None, state.clone()).await.unwrap();
+        assert_eq!(projects[0].name, "Analytics");
+        assert_eq!(projects[0].metadata, metadata);
+
+        // Names are unique, also for projects without warehouses.
+        let other_project = ProjectIdent::from(uuid::Uuid::new_v4());
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        let err = Catalog::rename_project(&other_project, "ANALYTICS", transaction.transaction())
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT);
+        transaction.rollback().await.unwrap();
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        let err = Catalog::rename_project(
+            &other_project,
+            &project_id.to_string(),
+            transaction.transaction(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::BAD_REQUEST);
+        transaction.rollback().await.unwrap();
+    }
+
+    #[sqlx::test]
+    async fn test_delete_project(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let project_id = ProjectIdent::from(uuid::Uuid::new_v4());
+        let warehouse_id = initialize_warehouse(state.clone(), None, Some(&project_id)).await;
+
+        // Projects with warehouses can't be deleted.
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        let err = Catalog::delete_project(&project_id, transaction.transaction())
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT);
+        transaction.rollback().await.unwrap();
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        Catalog::delete_warehouse(&warehouse_id, transaction.transaction())
+            .await
+            .unwrap();
+        Catalog::delete_project(&project_id, transaction.transaction())
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+        assert!(Catalog::get_projects(None, state.clone())
+            .await
+            .unwrap()
+            .is_empty());
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        let err = Catalog::delete_project(&project_id, transaction.transaction())
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+        transaction.rollback().await.unwrap();
+    }
+
+    #[sqlx::test]
+    async fn test_list_warehouses(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let project_id = ProjectIdent::from(uuid::Uuid::new_v4());
+        let warehouse_id_1 = initialize_warehouse(state.clone(), None, Some(&project_id)).await;
+
+        let warehouses = Catalog::list_warehouses(&project_id, None, None, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(warehouses.len(), 1);
+        // Check ids
+        assert!(warehouses.iter().any(|w| w.id == warehouse_id_1));
+    }
+
+    #[sqlx::test]
+    async fn test_list_warehouses_active_filter(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let project_id = ProjectIdent::from(uuid::Uuid::new_v4());
+        let warehouse_id_1 = initialize_warehouse(state.clone(), None, Some(&project_id)).await;
+
+        // Rename warehouse 1
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        Catalog::rename_warehouse(&warehouse_id_1, "new_name", transaction.transaction())
+            .await
+            .unwrap();
+        Catalog::set_warehouse_status(
+            &warehouse_id_1,
+            WarehouseStatus::Inactive,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        // Create warehouse 2
+        let warehouse_id_2 = initialize_warehouse(state.clone(), None, Some(&project_id)).await;
+
+        // Assert active whs
+        let warehouses = Catalog::list_warehouses(
+            &project_id,
+            Some(vec![WarehouseStatus::Active, WarehouseStatus::Inactive]),
+            None,
+            state.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(warehouses.len(), 2);
+        assert!(warehouses.iter().any(|w| w.id == warehouse_id_1));
+        assert!(warehouses.iter().any(|w| w.id == warehouse_id_2));
+
+        // Assert only active whs
+        let warehouses = Catalog::list_warehouses(&project_id, None, None, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(warehouses.len(), 1);
+        assert!(warehouses.iter().any(|w| w.id == warehouse_id_2));
+    }
+
+    #[sqlx::test]
+    async fn test_rename_warehouse(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let project_id = ProjectIdent::from(uuid::Uuid::new_v4());
+        let warehouse_id = initialize_warehouse(state.clone(), None, Some(&project_id)).await;
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        Catalog::rename_warehouse(&warehouse_id, "new_name", transaction.transaction())
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+
+        let mut read_transaction = PostgresTransaction::begin_read(state.clone())
+            .await
+            .unwrap();
+        let warehouse = Catalog::get_warehouse(&warehouse_id, read_transaction.transaction())
+            .await
+            .unwrap();
+        assert_eq!(warehouse.name, "new_name");
+    }
+
+    #[sqlx::test]
+    async fn test_warehouse_allowlist(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+
+        let allowlist = Catalog::get_warehouse_allowlist(&warehouse_id, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(allowlist, None);
+
+        let new_allowlist = WarehouseAllowlist {
+            principals: vec!["alice".to_string()],
+            groups: vec![],
+        };
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        Catalog::set_warehouse_allowlist(
+            &warehouse_id,
+            Some(new_allowlist.clone()),
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
 
-        let projects = Catalog::list_projects(state.clone()).await.unwrap();
-        assert_eq!(projects.len(), 2);
-        assert!(projects.contains(&project_id_1));
-        assert!(projects.contains(&project_id_2));
+        let allowlist = Catalog::get_warehouse_allowlist(&warehouse_id, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(allowlist, Some(new_allowlist));
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        Catalog::set_warehouse_allowlist(&warehouse_id, None, transaction.transaction())
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+
+        let allowlist = Catalog::get_warehouse_allowlist(&warehouse_id, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(allowlist, None);
     }
 
     #[sqlx::test]
-    async fn test_list_warehouses(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
-        let project_id = ProjectIdent::from(uuid::Uuid::new_v4());
-        let warehouse_id_1 = initialize_warehouse(state.clone(), None, Some(&project_id)).await;
+    async fn test_warehouse_read_only(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
 
-        let warehouses = Catalog::list_warehouses(&project_id, None, None, state.clone())
+        assert!(
+            !Catalog::is_warehouse_read_only(&warehouse_id, state.clone())
+                .await
+                .unwrap()
+        );
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
             .await
             .unwrap();
-        assert_eq!(warehouses.len(), 1);
-        // Check ids
-        assert!(warehouses.iter().any(|w| w.id == warehouse_id_1));
+        Catalog::set_warehouse_read_only(&warehouse_id, true, transaction.transaction())
+            .await
+            .unwrap();
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
+            .await
+            .unwrap();
+        assert!(warehouse.read_only);
+        transaction.commit().await.unwrap();
+
+        assert!(
+            Catalog::is_warehouse_read_only(&warehouse_id, state.clone())
+                .await
+                .unwrap()
+        );
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        let err = Catalog::set_warehouse_read_only(
+            &uuid::Uuid::now_v7().into(),
+            true,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
     }
 
     #[sqlx::test]
-    async fn test_list_warehouses_active_filter(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
-        let project_id = ProjectIdent::from(uuid::Uuid::new_v4());
-        let warehouse_id_1 = initialize_warehouse(state.clone(), None, Some(&project_id)).await;
+    async fn test_warehouse_pyiceberg_compat(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+
+        assert_eq!(
+            Catalog::get_pyiceberg_compat(&warehouse_id, state.clone())
+                .await
+                .unwrap(),
+            None
+        );
 
-        // Rename warehouse 1
         let mut transaction = PostgresTransaction::begin_write(state.clone())
             .await
             .unwrap();
-        Catalog::rename_warehouse(&warehouse_id_1, "new_name", transaction.transaction())
+        Catalog::set_warehouse_pyiceberg_compat(&warehouse_id, true, transaction.transaction())
             .await
             .unwrap();
-        Catalog::set_warehouse_status(
-            &warehouse_id_1,
-            WarehouseStatus::Inactive,
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
+            .await
+            .unwrap();
+        assert!(warehouse.pyiceberg_compat);
+        transaction.commit().await.unwrap();
+
+        assert_eq!(
+            Catalog::get_pyiceberg_compat(&warehouse_id, state.clone())
+                .await
+                .unwrap(),
+            Some(PyicebergCompat {
+                warehouse_name: warehouse.name
+            })
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_engine_table_config(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+
+        assert_eq!(
+            Catalog::get_engine_table_config(&warehouse_id, state.clone())
+                .await
+                .unwrap(),
+            EngineTableConfig::default()
+        );
+
+        let config = EngineTableConfig(HashMap::from_iter(vec![(
+            "trino".to_string(),
+            HashMap::from_iter(vec![(
+                "s3.path-style-access".to_string(),
+                "true".to_string(),
+            )]),
+        )]));
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        Catalog::set_engine_table_config(&warehouse_id, config.clone(), transaction.transaction())
+            .await
+            .unwrap();
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
+            .await
+            .unwrap();
+        assert_eq!(warehouse.engine_table_config, config);
+        transaction.commit().await.unwrap();
+
+        assert_eq!(
+            Catalog::get_engine_table_config(&warehouse_id, state.clone())
+                .await
+                .unwrap(),
+            config
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_event_filter(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
+            .await
+            .unwrap();
+        assert_eq!(warehouse.event_filter, EventFilter::default());
+
+        let filter = EventFilter {
+            enabled: true,
+            disabled_event_types: vec!["table.committed".to_string()],
+        };
+        Catalog::set_event_filter(&warehouse_id, filter.clone(), transaction.transaction())
+            .await
+            .unwrap();
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
+            .await
+            .unwrap();
+        assert_eq!(warehouse.event_filter, filter);
+
+        let err = Catalog::set_event_filter(
+            &uuid::Uuid::now_v7().into(),
+            filter,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test]
+    async fn test_warehouse_signer_uri(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+
+        let config = Catalog::get_config_for_warehouse(&warehouse_id, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            config.overrides["s3.signer.uri"],
+            CONFIG
+                .s3_signer_uri_for_warehouse(&warehouse_id)
+                .to_string()
+        );
+
+        let signer_uri = url::Url::parse("https://signer.internal/v1/my-warehouse").unwrap();
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        Catalog::set_warehouse_signer_uri(
+            &warehouse_id,
+            Some(&signer_uri),
             transaction.transaction(),
         )
         .await
         .unwrap();
         transaction.commit().await.unwrap();
 
-        // Create warehouse 2
-        let warehouse_id_2 = initialize_warehouse(state.clone(), None, Some(&project_id)).await;
+        assert_eq!(
+            Catalog::get_warehouse_signer_uri(&warehouse_id, state.clone())
+                .await
+                .unwrap()
+                .as_deref(),
+            Some(signer_uri.as_str())
+        );
+        let config = Catalog::get_config_for_warehouse(&warehouse_id, state.clone())
+            .await
+            .unwrap();
+        assert_eq!(config.overrides["s3.signer.uri"], signer_uri.as_str());
+    }
 
-        // Assert active whs
-        let warehouses = Catalog::list_warehouses(
-            &project_id,
-            Some(vec![WarehouseStatus::Active, WarehouseStatus::Inactive]),
+    #[sqlx::test]
+    async fn test_warehouse_unauthorized_response(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
+            .await
+            .unwrap();
+        assert_eq!(warehouse.unauthorized_response, None);
+        assert_eq!(
+            warehouse.effective_unauthorized_response(),
+            CONFIG.unauthorized_response
+        );
+
+        Catalog::set_warehouse_unauthorized_response(
+            &warehouse_id,
+            Some(UnauthorizedResponse::NotFound),
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
+            .await
+            .unwrap();
+        assert_eq!(
+            warehouse.effective_unauthorized_response(),
+            UnauthorizedResponse::NotFound
+        );
+
+        let err = Catalog::set_warehouse_unauthorized_response(
+            &uuid::Uuid::now_v7().into(),
             None,
-            state.clone(),
+            transaction.transaction(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test]
+    async fn test_warehouse_access_delegation(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
+            .await
+            .unwrap();
+        assert_eq!(warehouse.access_delegation, None);
+
+        Catalog::set_warehouse_access_delegation(
+            &warehouse_id,
+            Some(AccessDelegation::VendedCredentials),
+            transaction.transaction(),
         )
         .await
         .unwrap();
-        assert_eq!(warehouses.len(), 2);
-        assert!(warehouses.iter().any(|w| w.id == warehouse_id_1));
-        assert!(warehouses.iter().any(|w| w.id == warehouse_id_2));
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
+            .await
+            .unwrap();
+        assert_eq!(
+            warehouse.access_delegation,
+            Some(AccessDelegation::VendedCredentials)
+        );
 
-        // Assert only active whs
-        let warehouses = Catalog::list_warehouses(&project_id, None, None, state.clone())
+        let err = Catalog::set_warehouse_access_delegation(
+            &uuid::Uuid::now_v7().into(),
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test]
+    async fn test_warehouse_allow_external_locations(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
             .await
             .unwrap();
-        assert_eq!(warehouses.len(), 1);
-        assert!(warehouses.iter().any(|w| w.id == warehouse_id_2));
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
+            .await
+            .unwrap();
+        assert!(!warehouse.allow_external_locations);
+
+        Catalog::set_warehouse_allow_external_locations(
+            &warehouse_id,
+            true,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
+            .await
+            .unwrap();
+        assert!(warehouse.allow_external_locations);
+
+        let err = Catalog::set_warehouse_allow_external_locations(
+            &uuid::Uuid::now_v7().into(),
+            true,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
     }
 
     #[sqlx::test]
-    async fn test_rename_warehouse(pool: sqlx::PgPool) {
-        let state = CatalogState {
-            read_pool: pool.clone(),
-            write_pool: pool.clone(),
-        };
-        let project_id = ProjectIdent::from(uuid::Uuid::new_v4());
-        let warehouse_id = initialize_warehouse(state.clone(), None, Some(&project_id)).await;
+    async fn test_warehouse_object_storage_layout(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
 
         let mut transaction = PostgresTransaction::begin_write(state.clone())
             .await
             .unwrap();
-        Catalog::rename_warehouse(&warehouse_id, "new_name", transaction.transaction())
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
+            .await
+            .unwrap();
+        assert!(!warehouse.object_storage_layout);
+
+        Catalog::set_warehouse_object_storage_layout(
+            &warehouse_id,
+            true,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
+            .await
+            .unwrap();
+        assert!(warehouse.object_storage_layout);
+
+        let err = Catalog::set_warehouse_object_storage_layout(
+            &uuid::Uuid::now_v7().into(),
+            true,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test]
+    async fn test_rotate_warehouse_storage_secret(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let rotated_at = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+
+        let mut transaction = PostgresTransaction::begin_write(state.clone())
+            .await
+            .unwrap();
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
+            .await
+            .unwrap();
+        assert_eq!(warehouse.credential_rotation, None);
+        let first_secret_id = SecretIdent::from(uuid::Uuid::now_v7());
+        let second_secret_id = SecretIdent::from(uuid::Uuid::now_v7());
+
+        // The current secret must match
+        let err = Catalog::rotate_warehouse_storage_secret(
+            &warehouse_id,
+            Some(&second_secret_id),
+            &first_secret_id,
+            rotated_at,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT);
+
+        Catalog::rotate_warehouse_storage_secret(
+            &warehouse_id,
+            warehouse.storage_secret_id.as_ref(),
+            &first_secret_id,
+            rotated_at,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        Catalog::rotate_warehouse_storage_secret(
+            &warehouse_id,
+            Some(&first_secret_id),
+            &second_secret_id,
+            rotated_at,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
             .await
             .unwrap();
+        assert_eq!(warehouse.storage_secret_id, Some(second_secret_id.clone()));
+        assert_eq!(
+            warehouse.credential_rotation,
+            Some(CredentialRotation {
+                rotated_at,
+                previous_secret_id: Some(first_secret_id.clone()),
+            })
+        );
         transaction.commit().await.unwrap();
 
-        let mut read_transaction = PostgresTransaction::begin_read(state.clone())
+        // Only the current previous secret is cleared
+        assert!(!Catalog::clear_warehouse_previous_storage_secret(
+            &warehouse_id,
+            &second_secret_id,
+            state.clone()
+        )
+        .await
+        .unwrap());
+        assert!(Catalog::clear_warehouse_previous_storage_secret(
+            &warehouse_id,
+            &first_secret_id,
+            state.clone()
+        )
+        .await
+        .unwrap());
+        let mut transaction = PostgresTransaction::begin_read(state.clone())
             .await
             .unwrap();
-        let warehouse = Catalog::get_warehouse(&warehouse_id, read_transaction.transaction())
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
             .await
             .unwrap();
-        assert_eq!(warehouse.name, "new_name");
+        assert_eq!(
+            warehouse.credential_rotation,
+            Some(CredentialRotation {
+                rotated_at,
+                previous_secret_id: None,
+            })
+        );
+    }
+
+    #[sqlx::test]
+    async fn test_warehouse_storage_validation(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+        let warehouse_id = initialize_warehouse(state.clone(), None, None).await;
+        let mut transaction = PostgresTransaction::begin_read(state.clone())
+            .await
+            .unwrap();
+        let warehouse = Catalog::get_warehouse(&warehouse_id, transaction.transaction())
+            .await
+            .unwrap();
+        assert_eq!(warehouse.storage_validation, None);
+
+        let validation = StorageValidation {
+            validated_at: chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            error: Some("InvalidCredentials: Access denied".to_string()),
+        };
+        Catalog::set_warehouse_storage_validation(&warehouse_id, &validation, state.clone())
+            .await
+            .unwrap();
+        let warehouses = Catalog::list_warehouses(&warehouse.project_id, None, None, state)
+            .await
+            .unwrap();
+        let warehouse = warehouses.iter().find(|w| w.id == warehouse_id).unwrap();
+        assert_eq!(warehouse.storage_validation, Some(validation));
     }
 }
@@ -0,0 +1,58 @@
+use super::dbutils::DBErrorHandler as _;
+use super::CatalogState;
+use crate::api::Result;
+use crate::service::webhook::WebhookDeadLetter;
+
+pub(crate) async fn record_webhook_dead_letter(
+    dead_letter: &WebhookDeadLetter,
+    catalog_state: CatalogState,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO webhook_dead_letter (event_id, url, event, attempts, error, failed_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+        dead_letter.event_id,
+        dead_letter.url.as_str(),
+        dead_letter.event,
+        i32::try_from(dead_letter.attempts).unwrap_or(i32::MAX),
+        dead_letter.error,
+        dead_letter.failed_at
+    )
+    .execute(&catalog_state.write_pool)
+    .await
+    .map_err(|e| e.into_error_model("Error storing webhook dead letter".into()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[sqlx::test]
+    async fn test_record_webhook_dead_letter(pool: sqlx::PgPool) {
+        let state = CatalogState::new(pool.clone(), pool.clone());
+
+        let dead_letter = WebhookDeadLetter {
+            event_id: uuid::Uuid::now_v7().to_string(),
+            url: "https://example.com/hook".parse().unwrap(),
+            event: serde_json::json!({"specversion": "1.0", "type": "table.committed"}),
+            attempts: 5,
+            error: "Webhook responded with status 503 Service Unavailable".to_string(),
+            failed_at: chrono::Utc::now(),
+        };
+        record_webhook_dead_letter(&dead_letter, state)
+            .await
+            .unwrap();
+
+        let (event_id, attempts) = sqlx::query_as::<_, (String, i32)>(
+            "SELECT event_id, attempts FROM webhook_dead_letter",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(event_id, dead_letter.event_id);
+        assert_eq!(attempts, 5);
+    }
+}
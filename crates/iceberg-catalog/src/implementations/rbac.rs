@@ -0,0 +1,1159 @@
+//! [`AuthZHandler`] enforcing roles granted via the management API.
+//!
+//! Principals are granted one of the roles `reader`, `writer` or `admin` on a project,
+//! warehouse, namespace or table. A role applies to the object and everything within it,
+//! the effective role of a principal is the highest role on the accessed object or any
+//! object containing it:
+//!
+//! * `reader` may list and load namespaces and tables and get warehouses.
+//! * `writer` may additionally create, update, rename and drop namespaces and tables.
+//! * `admin` may additionally change settings of the object and grant roles on it.
+//!   Admins of a project may create warehouses in it.
+//!
+//! The owner of a namespace or table may drop and alter it without a role on it.
+//!
+//! Principals listed in `rbac_admins` may perform all operations, including those not
+//! scoped to a project such as maintenance mode, log levels and service keys.
+//! Requests without a principal are rejected. Tokens restricted by `openid_scope_permissions`
+//! additionally need the `read`, `write` or `manage` permission matching the required role.
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use http::StatusCode;
+
+use crate::api::{iceberg::v1::NamespaceIdent, ErrorModel, Result};
+use crate::request_metadata::RequestMetadata;
+use crate::{
+    implementations::DEFAULT_PROJECT_ID,
+    service::{
+        auth::{AuthConfigHandler, AuthZHandler, UserWarehouse},
+        roles::RoleStore,
+        token_verification::Permission,
+        Catalog, ObjectOwnership, OwnedObject, Role, RoleObject, RoleTarget, TableIdent,
+        TableIdentUuid,
+    },
+    ProjectIdent, WarehouseIdent, CONFIG,
+};
+
+#[derive(Clone)]
+pub struct RbacAuthState<C: Catalog> {
+    catalog_state: C::State,
+    roles: Arc<dyn RoleStore>,
+    admins: Arc<HashSet<String>>,
+}
+
+impl<C: Catalog> RbacAuthState<C> {
+    /// State with the `rbac_admins` of the config.
+    #[must_use]
+    pub fn from_config(catalog_state: C::State, roles: Arc<dyn RoleStore>) -> Self {
+        Self::new(
+            catalog_state,
+            roles,
+            CONFIG.rbac_admins.iter().cloned().collect(),
+        )
+    }
+
+    #[must_use]
+    pub fn new(
+        catalog_state: C::State,
+        roles: Arc<dyn RoleStore>,
+        admins: HashSet<String>,
+    ) -> Self {
+        Self {
+            catalog_state,
+            roles,
+            admins: Arc::new(admins),
+        }
+    }
+
+    /// Principal of the request, `None` if the principal is an admin.
+    fn principal<'a>(&self, metadata: &'a RequestMetadata) -> Result<Option<&'a str>> {
+        match metadata.principal() {
+            Some(principal) if self.admins.contains(principal) => Ok(None),
+            Some(principal) => Ok(Some(principal)),
+            None => Err(ErrorModel::builder()
+                .code(StatusCode::FORBIDDEN.into())
+                .message(
+                    "Role based access control requires an authenticated principal".to_string(),
+                )
+                .r#type("ActionForbidden".to_string())
+                .build()
+                .into()),
+        }
+    }
+
+    /// Fail unless the principal has at least `required` on `target`.
+    async fn require(
+        &self,
+        metadata: &RequestMetadata,
+        target: RoleTarget,
+        required: Role,
+    ) -> Result<()> {
+        let principal = self.principal(metadata)?;
+        metadata.require_permission(permission(required))?;
+        let Some(principal) = principal else {
+            return Ok(());
+        };
+        let role = self
+            .roles
+            .get_roles(principal, &target)
+            .await?
+            .into_iter()
+            .max();
+        if role.is_some_and(|role| role >= required) {
+            Ok(())
+        } else {
+            Err(forbidden(required))
+        }
+    }
+
+    /// Fail unless the principal owns the object or has at least `required` on `target`.
+    async fn require_or_owner(
+        &self,
+        metadata: &RequestMetadata,
+        target: RoleTarget,
+        required: Role,
+        ownership: Option<&ObjectOwnership>,
+    ) -> Result<()> {
+        let owner = ownership.and_then(|o| o.owner.as_deref());
+        match self.principal(metadata)? {
+            Some(principal) if owner == Some(principal) => {
+                metadata.require_permission(permission(required))
+            }
+            _ => self.require(metadata, target, required).await,
+        }
+    }
+
+    /// Fail unless the principal is an admin.
+    fn require_admin(&self, metadata: &RequestMetadata) -> Result<()> {
+        let principal = self.principal(metadata)?;
+        metadata.require_permission(Permission::Manage)?;
+        match principal {
+            None => Ok(()),
+            Some(_) => Err(ErrorModel::builder()
+                .code(StatusCode::FORBIDDEN.into())
+                .message("Only RBAC admins may perform this action".to_string())
+                .r#type("ActionForbidden".to_string())
+                .build()
+                .into()),
+        }
+    }
+
+    /// The table if it exists, its namespace otherwise.
+    async fn table_target(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+    ) -> Result<RoleTarget> {
+        let table_id =
+            C::table_ident_to_id(warehouse_id, table, true, self.catalog_state.clone()).await?;
+        Ok(match table_id {
+            Some(table_id) => RoleTarget::Table(warehouse_id.clone(), table_id),
+            None => RoleTarget::Namespace(warehouse_id.clone(), table.namespace.clone()),
+        })
+    }
+}
+
+impl<C: Catalog> Debug for RbacAuthState<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RbacAuthState")
+            .field("admins", &self.admins)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Grants access based on the roles of the [`RoleStore`].
+pub struct RbacAuthZHandler<C: Catalog>(PhantomData<C>);
+
+impl<C: Catalog> Clone for RbacAuthZHandler<C> {
+    fn clone(&self) -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<C: Catalog> Debug for RbacAuthZHandler<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RbacAuthZHandler").finish()
+    }
+}
+
+/// Permission a token needs for operations that require `role`.
+fn permission(role: Role) -> Permission {
+    match role {
+        Role::Reader => Permission::Read,
+        Role::Writer => Permission::Write,
+        Role::Admin => Permission::Manage,
+    }
+}
+
+fn forbidden(required: Role) -> crate::api::IcebergErrorResponse {
+    ErrorModel::builder()
+        .code(StatusCode::FORBIDDEN.into())
+        .message(format!(
+            "Requires the {} role or higher",
+            format!("{required:?}").to_lowercase()
+        ))
+        .r#type("ActionForbidden".to_string())
+        .build()
+        .into()
+}
+
+/// The most specific object that is known of a namespace or table.
+fn target(
+    warehouse_id: &WarehouseIdent,
+    namespace: Option<&NamespaceIdent>,
+    table: Option<&TableIdentUuid>,
+) -> RoleTarget {
+    match (table, namespace) {
+        (Some(table_id), _) => RoleTarget::Table(warehouse_id.clone(), table_id.clone()),
+        (None, Some(namespace)) => RoleTarget::Namespace(warehouse_id.clone(), namespace.clone()),
+        (None, None) => RoleTarget::Warehouse(warehouse_id.clone()),
+    }
+}
+
+#[async_trait::async_trait]
+impl<C: Catalog> AuthConfigHandler<RbacAuthZHandler<C>> for RbacAuthZHandler<C> {
+    async fn get_and_validate_user_warehouse(
+        state: RbacAuthState<C>,
+        metadata: &RequestMetadata,
+    ) -> Result<UserWarehouse> {
+        let project_id = metadata
+            .project_id
+            .clone()
+            .unwrap_or_else(|| ProjectIdent::from(DEFAULT_PROJECT_ID));
+        <Self as AuthConfigHandler<Self>>::check_list_warehouse_in_project(
+            state,
+            &project_id,
+            metadata,
+        )
+        .await?;
+        Ok(UserWarehouse {
+            project_id: Some(project_id),
+            warehouse_id: None,
+        })
+    }
+
+    async fn exchange_token_for_warehouse(
+        _: RbacAuthState<C>,
+        _: &RequestMetadata,
+        _: &ProjectIdent,
+        _: &WarehouseIdent,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    async fn check_list_warehouse_in_project(
+        state: RbacAuthState<C>,
+        project_id: &ProjectIdent,
+        metadata: &RequestMetadata,
+    ) -> Result<()> {
+        let warehouses =
+            <Self as AuthZHandler>::check_list_warehouse_in_project(metadata, project_id, state)
+                .await?;
+        if warehouses.is_some_and(|warehouses| warehouses.is_empty()) {
+            return Err(forbidden(Role::Reader));
+        }
+        Ok(())
+    }
+
+    async fn check_user_get_config_for_warehouse(
+        state: RbacAuthState<C>,
+        warehouse_id: &WarehouseIdent,
+        metadata: &RequestMetadata,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Reader,
+            )
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl<C: Catalog> AuthZHandler for RbacAuthZHandler<C> {
+    type State = RbacAuthState<C>;
+
+    async fn check_list_namespace(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        parent: Option<&NamespaceIdent>,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(metadata, target(warehouse_id, parent, None), Role::Reader)
+            .await
+    }
+
+    async fn check_create_namespace(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        parent: Option<&NamespaceIdent>,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(metadata, target(warehouse_id, parent, None), Role::Writer)
+            .await
+    }
+
+    async fn check_load_namespace_metadata(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                target(warehouse_id, Some(namespace), None),
+                Role::Reader,
+            )
+            .await
+    }
+
+    async fn check_namespace_exists(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                target(warehouse_id, Some(namespace), None),
+                Role::Reader,
+            )
+            .await
+    }
+
+    async fn check_drop_namespace(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        ownership: Option<&ObjectOwnership>,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require_or_owner(
+                metadata,
+                target(warehouse_id, Some(namespace), None),
+                Role::Writer,
+                ownership,
+            )
+            .await
+    }
+
+    async fn check_update_namespace_properties(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        ownership: Option<&ObjectOwnership>,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require_or_owner(
+                metadata,
+                target(warehouse_id, Some(namespace), None),
+                Role::Writer,
+                ownership,
+            )
+            .await
+    }
+
+    async fn check_create_table(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                target(warehouse_id, Some(namespace), None),
+                Role::Writer,
+            )
+            .await
+    }
+
+    async fn check_list_tables(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                target(warehouse_id, Some(namespace), None),
+                Role::Reader,
+            )
+            .await
+    }
+
+    async fn check_load_table(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        table: Option<&TableIdentUuid>,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                target(warehouse_id, namespace, table),
+                Role::Reader,
+            )
+            .await
+    }
+
+    async fn check_rename_table(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        source: Option<&TableIdentUuid>,
+        ownership: Option<&ObjectOwnership>,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require_or_owner(
+                metadata,
+                target(warehouse_id, None, source),
+                Role::Writer,
+                ownership,
+            )
+            .await
+    }
+
+    async fn check_table_exists(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: Option<&NamespaceIdent>,
+        table: Option<&TableIdentUuid>,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                target(warehouse_id, namespace, table),
+                Role::Reader,
+            )
+            .await
+    }
+
+    async fn check_drop_table(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        table: Option<&TableIdentUuid>,
+        ownership: Option<&ObjectOwnership>,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require_or_owner(
+                metadata,
+                target(warehouse_id, None, table),
+                Role::Writer,
+                ownership,
+            )
+            .await
+    }
+
+    async fn check_commit_table(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        table: Option<&TableIdentUuid>,
+        namespace: Option<&NamespaceIdent>,
+        ownership: Option<&ObjectOwnership>,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require_or_owner(
+                metadata,
+                target(warehouse_id, namespace, table),
+                Role::Writer,
+                ownership,
+            )
+            .await
+    }
+
+    async fn check_create_warehouse(
+        metadata: &RequestMetadata,
+        project_id: &ProjectIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Project(project_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_list_projects(
+        metadata: &RequestMetadata,
+        state: RbacAuthState<C>,
+    ) -> Result<Option<HashSet<ProjectIdent>>> {
+        let principal = state.principal(metadata)?;
+        metadata.require_permission(Permission::Read)?;
+        let Some(principal) = principal else {
+            return Ok(None);
+        };
+        let assignments = state
+            .roles
+            .list_role_assignments(None, Some(principal))
+            .await?;
+        Ok(Some(
+            assignments
+                .into_iter()
+                .map(|assignment| assignment.project_id)
+                .collect(),
+        ))
+    }
+
+    async fn check_list_warehouse_in_project(
+        metadata: &RequestMetadata,
+        project_id: &ProjectIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<Option<HashSet<WarehouseIdent>>> {
+        let principal = state.principal(metadata)?;
+        metadata.require_permission(Permission::Read)?;
+        let Some(principal) = principal else {
+            return Ok(None);
+        };
+        let assignments = state
+            .roles
+            .list_role_assignments(Some(project_id), Some(principal))
+            .await?;
+        if assignments
+            .iter()
+            .any(|assignment| matches!(assignment.object, RoleObject::Project(_)))
+        {
+            return Ok(None);
+        }
+        Ok(Some(
+            assignments
+                .iter()
+                .filter_map(|assignment| assignment.object.warehouse_id().cloned())
+                .collect(),
+        ))
+    }
+
+    async fn check_update_project(
+        metadata: &RequestMetadata,
+        project_id: &ProjectIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Project(project_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_delete_project(
+        metadata: &RequestMetadata,
+        project_id: &ProjectIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Project(project_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_delete_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_get_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Reader,
+            )
+            .await
+    }
+
+    async fn check_rename_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_deactivate_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_activate_warehouse(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_update_storage(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_set_warehouse_allowlist(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_set_engine_table_config(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_set_event_filter(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_set_maintenance_mode(
+        metadata: &RequestMetadata,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state.require_admin(metadata)
+    }
+
+    async fn check_reload_config(
+        metadata: &RequestMetadata,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state.require_admin(metadata)
+    }
+
+    async fn check_set_log_level(
+        metadata: &RequestMetadata,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state.require_admin(metadata)
+    }
+
+    async fn check_set_warehouse_read_only(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_set_warehouse_pyiceberg_compat(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_set_warehouse_signer_uri(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_set_warehouse_unauthorized_response(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_set_warehouse_access_delegation(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_set_warehouse_allow_external_locations(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_set_warehouse_object_storage_layout(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_set_table_policy(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        let target = state.table_target(warehouse_id, table).await?;
+        state.require(metadata, target, Role::Admin).await
+    }
+
+    async fn check_set_warehouse_retention_policy(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_set_namespace_retention_policy(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                target(warehouse_id, Some(namespace), None),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_manage_staged_tables(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Writer,
+            )
+            .await
+    }
+
+    async fn check_manage_shares(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state
+            .require(
+                metadata,
+                RoleTarget::Warehouse(warehouse_id.clone()),
+                Role::Admin,
+            )
+            .await
+    }
+
+    async fn check_transfer_ownership(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        object: &OwnedObject,
+        _ownership: &ObjectOwnership,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        let target = match object {
+            OwnedObject::Warehouse => RoleTarget::Warehouse(warehouse_id.clone()),
+            OwnedObject::Namespace(namespace) => {
+                RoleTarget::Namespace(warehouse_id.clone(), namespace.clone())
+            }
+            OwnedObject::Table(table) => state.table_target(warehouse_id, table).await?,
+        };
+        state.require(metadata, target, Role::Admin).await
+    }
+
+    async fn check_revoke_token(metadata: &RequestMetadata, state: RbacAuthState<C>) -> Result<()> {
+        state.require_admin(metadata)
+    }
+
+    async fn check_manage_service_keys(
+        metadata: &RequestMetadata,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        state.require_admin(metadata)
+    }
+
+    async fn check_manage_roles(
+        metadata: &RequestMetadata,
+        object: &RoleObject,
+        state: RbacAuthState<C>,
+    ) -> Result<()> {
+        let target = match object {
+            RoleObject::Project(project_id) => RoleTarget::Project(project_id.clone()),
+            RoleObject::Warehouse(warehouse_id) => RoleTarget::Warehouse(warehouse_id.clone()),
+            RoleObject::Namespace(warehouse_id, namespace) => {
+                RoleTarget::Namespace(warehouse_id.clone(), namespace.clone())
+            }
+            RoleObject::Table(warehouse_id, table) => {
+                state.table_target(warehouse_id, table).await?
+            }
+        };
+        state.require(metadata, target, Role::Admin).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::implementations::memory::{MemoryCatalog, MemoryCatalogState};
+    use crate::service::storage::{FileProfile, StorageProfile};
+    use crate::service::token_verification::{Aud, AuthDetails, Claims};
+    use crate::service::{CreateNamespaceRequest, Transaction};
+
+    type Handler = RbacAuthZHandler<MemoryCatalog>;
+
+    fn metadata(principal: &str) -> RequestMetadata {
+        RequestMetadata {
+            auth_details: Some(AuthDetails::JWT(Claims {
+                sub: principal.to_string(),
+                iss: "iss".to_string(),
+                aud: Aud::String("aud".to_string()),
+                exp: 0,
+                iat: 0,
+                jti: None,
+                other: serde_json::json!({}),
+            })),
+            ..RequestMetadata::new_random()
+        }
+    }
+
+    async fn setup() -> (RbacAuthState<MemoryCatalog>, WarehouseIdent, NamespaceIdent) {
+        let catalog_state = MemoryCatalogState::default();
+        let mut transaction =
+            <MemoryCatalog as Catalog>::Transaction::begin_write(catalog_state.clone())
+                .await
+                .unwrap();
+        let warehouse_id = MemoryCatalog::create_warehouse(
+            "test_warehouse".to_string(),
+            ProjectIdent::from(uuid::Uuid::nil()),
+            StorageProfile::File(FileProfile {
+                path: "/tmp/warehouse".to_string(),
+            }),
+            None,
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        let namespace = NamespaceIdent::from_vec(vec!["sales".to_string()]).unwrap();
+        MemoryCatalog::create_namespace(
+            &warehouse_id,
+            CreateNamespaceRequest {
+                namespace: namespace.clone(),
+                properties: None,
+            },
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let state = RbacAuthState::new(
+            catalog_state.clone(),
+            Arc::new(catalog_state),
+            HashSet::from(["root".to_string()]),
+        );
+        (state, warehouse_id, namespace)
+    }
+
+    async fn grant(state: &RbacAuthState<MemoryCatalog>, object: RoleObject, role: Role) {
+        state
+            .roles
+            .grant_role(&object, "alice", role, None)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_roles_apply_to_contained_objects() {
+        let (state, warehouse_id, namespace) = setup().await;
+        let alice = metadata("alice");
+        let child =
+            NamespaceIdent::from_vec(vec!["sales".to_string(), "gold".to_string()]).unwrap();
+
+        let err = Handler::check_list_tables(&alice, &warehouse_id, &namespace, state.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::FORBIDDEN);
+
+        grant(
+            &state,
+            RoleObject::Namespace(warehouse_id.clone(), namespace.clone()),
+            Role::Reader,
+        )
+        .await;
+        Handler::check_list_tables(&alice, &warehouse_id, &namespace, state.clone())
+            .await
+            .unwrap();
+        Handler::check_load_namespace_metadata(&alice, &warehouse_id, &child, state.clone())
+            .await
+            .unwrap();
+        Handler::check_create_table(&alice, &warehouse_id, &child, state.clone())
+            .await
+            .unwrap_err();
+        Handler::check_get_warehouse(&alice, &warehouse_id, state.clone())
+            .await
+            .unwrap_err();
+
+        grant(
+            &state,
+            RoleObject::Warehouse(warehouse_id.clone()),
+            Role::Writer,
+        )
+        .await;
+        Handler::check_create_table(&alice, &warehouse_id, &child, state.clone())
+            .await
+            .unwrap();
+        Handler::check_delete_warehouse(&alice, &warehouse_id, state.clone())
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_admins_and_anonymous() {
+        let (state, warehouse_id, _) = setup().await;
+
+        Handler::check_delete_warehouse(&metadata("root"), &warehouse_id, state.clone())
+            .await
+            .unwrap();
+        Handler::check_set_log_level(&metadata("root"), state.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            Handler::check_list_projects(&metadata("root"), state.clone())
+                .await
+                .unwrap(),
+            None
+        );
+
+        let err = Handler::check_get_warehouse(
+            &RequestMetadata::new_random(),
+            &warehouse_id,
+            state.clone(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_owner_may_drop_table() {
+        let (state, warehouse_id, _) = setup().await;
+        let table_id = TableIdentUuid::from(uuid::Uuid::now_v7());
+        let ownership = ObjectOwnership {
+            owner: Some("bob".to_string()),
+            created_by: Some("bob".to_string()),
+        };
+
+        Handler::check_drop_table(
+            &metadata("bob"),
+            &warehouse_id,
+            Some(&table_id),
+            Some(&ownership),
+            state.clone(),
+        )
+        .await
+        .unwrap();
+        let err = Handler::check_drop_table(
+            &metadata("alice"),
+            &warehouse_id,
+            Some(&table_id),
+            Some(&ownership),
+            state.clone(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::FORBIDDEN);
+        Handler::check_drop_table(
+            &metadata("bob"),
+            &warehouse_id,
+            Some(&table_id),
+            None,
+            state.clone(),
+        )
+        .await
+        .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_list_warehouses() {
+        let (state, warehouse_id, namespace) = setup().await;
+        let alice = metadata("alice");
+        let project_id = ProjectIdent::from(uuid::Uuid::nil());
+
+        assert_eq!(
+            Handler::check_list_warehouse_in_project(&alice, &project_id, state.clone())
+                .await
+                .unwrap(),
+            Some(HashSet::new())
+        );
+
+        grant(
+            &state,
+            RoleObject::Namespace(warehouse_id.clone(), namespace),
+            Role::Reader,
+        )
+        .await;
+        assert_eq!(
+            Handler::check_list_warehouse_in_project(&alice, &project_id, state.clone())
+                .await
+                .unwrap(),
+            Some(HashSet::from([warehouse_id]))
+        );
+        assert_eq!(
+            Handler::check_list_projects(&alice, state.clone())
+                .await
+                .unwrap(),
+            Some(HashSet::from([project_id.clone()]))
+        );
+
+        grant(
+            &state,
+            RoleObject::Project(project_id.clone()),
+            Role::Reader,
+        )
+        .await;
+        assert_eq!(
+            Handler::check_list_warehouse_in_project(&alice, &project_id, state.clone())
+                .await
+                .unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,46 @@
+//! Validation of names, shared by the catalog implementations.
+use http::StatusCode;
+
+use crate::api::{ErrorModel, Result};
+use crate::ProjectIdent;
+
+/// Project names are accepted wherever a project id is, so names that are ids
+/// can only be used for the project with that id.
+pub(crate) fn validate_project_name(project_id: &ProjectIdent, project_name: &str) -> Result<()> {
+    let error = |message: &str, r#type: &str| {
+        Err(ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message(message.to_string())
+            .r#type(r#type.to_string())
+            .build()
+            .into())
+    };
+    if project_name.trim().is_empty() {
+        return error("Project name cannot be empty", "EmptyProjectName");
+    }
+    if project_name.len() > 128 {
+        return error(
+            "Project name must not exceed 128 characters",
+            "ProjectNameTooLong",
+        );
+    }
+    if uuid::Uuid::parse_str(project_name).is_ok_and(|id| id != *project_id.as_uuid()) {
+        return error(
+            "Project name must not be the id of another project",
+            "InvalidProjectName",
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_warehouse_name(warehouse_name: &str) -> Result<()> {
+    if warehouse_name.is_empty() {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("Warehouse name cannot be empty".to_string())
+            .r#type("EmptyWarehouseName".to_string())
+            .build()
+            .into());
+    }
+    Ok(())
+}
@@ -13,10 +13,12 @@ mod config;
 pub mod service;
 pub use service::{ProjectIdent, SecretIdent, WarehouseIdent};
 
-pub use config::CONFIG;
+pub use config::{CatalogBackend, CONFIG};
 
 pub mod implementations;
 #[cfg(feature = "router")]
 mod request_metadata;
+#[cfg(feature = "test-util")]
+pub mod test_util;
 #[cfg(feature = "router")]
 pub(crate) mod tracing;
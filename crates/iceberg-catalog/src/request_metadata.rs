@@ -1,32 +1,374 @@
-use crate::service::token_verification::AuthDetails;
+use crate::api::{ErrorModel, IcebergErrorResponse};
+use crate::service::catalog_token::TokenScope;
+use crate::service::client_usage::ClientInfo;
+use crate::service::token_verification::{AuthDetails, Permission};
+use crate::service::TableIdentUuid;
+use crate::{ProjectIdent, WarehouseIdent};
 use axum::middleware::Next;
-use axum::response::Response;
-use http::HeaderMap;
+use axum::response::{IntoResponse, Response};
+use http::{HeaderMap, HeaderName};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::marker::PhantomData;
 use std::str::FromStr;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// A struct to hold metadata about a request.
 ///
-/// Currently, it only holds the `request_id`, later it can be expanded to hold more metadata for
-/// Authz etc.
+/// Deployment specific context, such as the tenant of a request, is stored in
+/// [`Self::extensions`] by the configured [`RequestMetadataExtractor`]s and can be read by
+/// `AuthZHandler`s, event publishers and custom layers with [`Self::extension`].
 #[derive(Debug, Clone)]
 pub struct RequestMetadata {
     pub request_id: Uuid,
     pub auth_details: Option<AuthDetails>,
+    /// Value of the `User-Agent` header.
+    pub user_agent: Option<String>,
+    /// Value of the `X-Client-Version` header.
+    pub client_version: Option<String>,
+    /// Project selected with the `X-Project-Id` header.
+    pub project_id: Option<ProjectIdent>,
+    /// Trace id of the W3C `traceparent` header, if the request is traced.
+    pub trace_id: Option<String>,
+    /// Typed values added by [`RequestMetadataExtractor`]s.
+    pub extensions: http::Extensions,
 }
 
+/// JWT claim listing the projects a principal belongs to.
+const PROJECTS_CLAIM: &str = "projects";
+
 impl RequestMetadata {
-    #[cfg(test)]
+    #[cfg(any(test, feature = "test-util"))]
     #[must_use]
     pub fn new_random() -> Self {
         Self {
             request_id: Uuid::new_v4(),
             auth_details: None,
+            user_agent: None,
+            client_version: None,
+            project_id: None,
+            trace_id: None,
+            extensions: http::Extensions::new(),
+        }
+    }
+
+    /// Value of type `T` added by a [`RequestMetadataExtractor`], if any.
+    #[must_use]
+    pub fn extension<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.extensions.get::<T>()
+    }
+
+    /// Tenant of the request, see [`TenantId`].
+    #[must_use]
+    pub fn tenant_id(&self) -> Option<&str> {
+        self.extension::<TenantId>().map(|t| t.0.as_str())
+    }
+
+    /// Engine and version of the client that sent the request.
+    #[must_use]
+    pub fn client_info(&self) -> ClientInfo {
+        ClientInfo::from_headers(self.user_agent.as_deref(), self.client_version.as_deref())
+    }
+
+    /// Identity of the authenticated principal, if any.
+    ///
+    /// For JWT authentication this is the `sub` claim of the token,
+    /// for `SigV4` and service keys the principal configured for the key.
+    #[must_use]
+    pub fn principal(&self) -> Option<&str> {
+        match &self.auth_details {
+            Some(AuthDetails::JWT(claims)) => Some(claims.sub.as_str()),
+            Some(AuthDetails::SigV4(claims)) => Some(claims.principal.as_str()),
+            Some(AuthDetails::ServiceKey(claims)) => Some(claims.principal.as_str()),
+            Some(AuthDetails::SignerToken(_)) | None => None,
+        }
+    }
+
+    /// Groups of the authenticated principal, if any.
+    ///
+    /// For JWT authentication these are taken from the claim configured
+    /// as `openid_groups_claim`, `groups` by default.
+    #[must_use]
+    pub fn groups(&self) -> Vec<&str> {
+        match &self.auth_details {
+            Some(AuthDetails::JWT(claims)) => claims.strings(&crate::CONFIG.openid_groups_claim),
+            Some(
+                AuthDetails::SignerToken(_) | AuthDetails::SigV4(_) | AuthDetails::ServiceKey(_),
+            )
+            | None => vec![],
+        }
+    }
+
+    /// Permissions granted by the scopes of the token, `None` if the request
+    /// is not restricted by scopes.
+    ///
+    /// Only JWTs are restricted, and only if `openid_scope_permissions` is configured.
+    #[must_use]
+    pub fn permissions(&self) -> Option<HashSet<Permission>> {
+        self.permissions_for(&crate::CONFIG.openid_scope_permissions)
+    }
+
+    fn permissions_for(
+        &self,
+        scope_permissions: &HashMap<String, Permission>,
+    ) -> Option<HashSet<Permission>> {
+        match &self.auth_details {
+            Some(AuthDetails::JWT(claims)) if !scope_permissions.is_empty() => Some(
+                claims
+                    .scopes()
+                    .into_iter()
+                    .filter_map(|scope| scope_permissions.get(scope).copied())
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Check that the scopes of the token grant `permission` or a higher one.
+    /// `AuthZHandler`s call this before their own checks.
+    ///
+    /// # Errors
+    /// Fails with `403` if the token is restricted to lower permissions.
+    pub fn require_permission(&self, permission: Permission) -> crate::api::Result<()> {
+        Self::check_permission(self.permissions(), permission)
+    }
+
+    fn check_permission(
+        granted: Option<HashSet<Permission>>,
+        permission: Permission,
+    ) -> crate::api::Result<()> {
+        match granted {
+            Some(granted) if !granted.iter().any(|p| *p >= permission) => {
+                Err(ErrorModel::builder()
+                    .code(http::StatusCode::FORBIDDEN.into())
+                    .message(format!(
+                        "Token scopes do not grant the {permission} permission"
+                    ))
+                    .r#type("InsufficientScope")
+                    .build()
+                    .into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Warehouse and table the token was downscoped to by token exchange.
+    #[must_use]
+    pub fn token_scope(&self) -> TokenScope {
+        match &self.auth_details {
+            Some(AuthDetails::JWT(claims)) => TokenScope::from_claims(claims),
+            Some(
+                AuthDetails::SignerToken(_) | AuthDetails::SigV4(_) | AuthDetails::ServiceKey(_),
+            )
+            | None => TokenScope::default(),
+        }
+    }
+
+    /// Check that the token may be used for `warehouse_id`.
+    /// Tokens downscoped to a table are only accepted for `table_operation`s, which
+    /// must check the table with [`Self::require_token_table`].
+    ///
+    /// # Errors
+    /// Fails with `403` if the token is scoped to another warehouse or to a table.
+    pub fn require_token_warehouse(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        table_operation: bool,
+    ) -> crate::api::Result<()> {
+        let scope = self.token_scope();
+        if !scope.allows_warehouse(warehouse_id) {
+            return Err(token_scope_mismatch(format!(
+                "Token is not valid for warehouse {warehouse_id}"
+            )));
+        }
+        if scope.table_id.is_some() && !table_operation {
+            return Err(token_scope_mismatch(
+                "Token is scoped to a table and can't be used for this operation".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check that the token may be used for the table with `table_id`, `None` if
+    /// the table does not exist.
+    ///
+    /// # Errors
+    /// Fails with `403` if the token is scoped to another table.
+    pub fn require_token_table(&self, table_id: Option<&TableIdentUuid>) -> crate::api::Result<()> {
+        if self.token_scope().allows_table(table_id) {
+            Ok(())
+        } else {
+            Err(token_scope_mismatch(
+                "Token is not valid for this table".to_string(),
+            ))
         }
     }
+
+    /// Projects listed in the `projects` claim of the token, if present.
+    #[must_use]
+    pub fn token_projects(&self) -> Option<Vec<&str>> {
+        match &self.auth_details {
+            Some(AuthDetails::JWT(claims)) => claims
+                .other
+                .get(PROJECTS_CLAIM)
+                .and_then(serde_json::Value::as_array)
+                .map(|projects| {
+                    projects
+                        .iter()
+                        .filter_map(serde_json::Value::as_str)
+                        .collect()
+                }),
+            Some(
+                AuthDetails::SignerToken(_) | AuthDetails::SigV4(_) | AuthDetails::ServiceKey(_),
+            )
+            | None => None,
+        }
+    }
+
+    /// Check that the project selected via `X-Project-Id` is listed in the token.
+    /// Tokens without a `projects` claim may select any project, access is then
+    /// governed by the authorization backend.
+    ///
+    /// # Errors
+    /// Fails if the token lists projects that do not include the selected project.
+    pub fn validate_project(&self) -> crate::api::Result<()> {
+        let (Some(project_id), Some(token_projects)) = (&self.project_id, self.token_projects())
+        else {
+            return Ok(());
+        };
+        let project_id = project_id.to_string();
+        if token_projects
+            .iter()
+            .any(|p| p.eq_ignore_ascii_case(&project_id))
+        {
+            return Ok(());
+        }
+        Err(ErrorModel::builder()
+            .code(http::StatusCode::FORBIDDEN.into())
+            .message(format!("Token is not valid for project {project_id}"))
+            .r#type("ProjectNotInToken")
+            .build()
+            .into())
+    }
+}
+fn token_scope_mismatch(message: String) -> IcebergErrorResponse {
+    ErrorModel::builder()
+        .code(http::StatusCode::FORBIDDEN.into())
+        .message(message)
+        .r#type("TokenScopeMismatch")
+        .build()
+        .into()
+}
+
+/// Adds typed values to the [`RequestMetadata`] of each request.
+///
+/// Extractors run before authentication, in the order they are registered, and must not fail:
+/// requests without the expected headers are served without the extension.
+pub trait RequestMetadataExtractor: Debug + Send + Sync + 'static {
+    fn extract(&self, headers: &HeaderMap, extensions: &mut http::Extensions);
+}
+
+/// Tenant of the request, taken from the header configured as `tenant_header`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TenantId(pub String);
+
+impl From<String> for TenantId {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+/// Region the client prefers to be served from, taken from the header configured as
+/// `region_affinity_header`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionAffinity(pub String);
+
+impl From<String> for RegionAffinity {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+/// Stores the value of a header as `T`. Empty and non-ASCII values are ignored.
+#[derive(Debug, Clone)]
+pub struct HeaderExtractor<T> {
+    header: HeaderName,
+    phantom: PhantomData<fn() -> T>,
 }
+
+impl<T> HeaderExtractor<T> {
+    #[must_use]
+    pub fn new(header: HeaderName) -> Self {
+        Self {
+            header,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: From<String> + Clone + Debug + Send + Sync + 'static> RequestMetadataExtractor
+    for HeaderExtractor<T>
+{
+    fn extract(&self, headers: &HeaderMap, extensions: &mut http::Extensions) {
+        if let Some(value) = headers
+            .get(&self.header)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+        {
+            extensions.insert(T::from(value.to_string()));
+        }
+    }
+}
+
+/// Extractors run for every request.
+#[derive(Debug, Clone, Default)]
+pub struct RequestMetadataExtractors(Arc<Vec<Arc<dyn RequestMetadataExtractor>>>);
+
+impl RequestMetadataExtractors {
+    #[must_use]
+    pub fn new(extractors: Vec<Arc<dyn RequestMetadataExtractor>>) -> Self {
+        Self(Arc::new(extractors))
+    }
+
+    /// Extractors for the `tenant_header` and `region_affinity_header` of [`CONFIG`](crate::CONFIG).
+    #[must_use]
+    pub fn from_config() -> Self {
+        let mut extractors: Vec<Arc<dyn RequestMetadataExtractor>> = vec![];
+        if let Some(header) = crate::CONFIG.tenant_header.as_deref() {
+            if let Ok(header) = HeaderName::from_str(header) {
+                extractors.push(Arc::new(HeaderExtractor::<TenantId>::new(header)));
+            }
+        }
+        if let Some(header) = crate::CONFIG.region_affinity_header.as_deref() {
+            if let Ok(header) = HeaderName::from_str(header) {
+                extractors.push(Arc::new(HeaderExtractor::<RegionAffinity>::new(header)));
+            }
+        }
+        Self::new(extractors)
+    }
+
+    /// A copy with `extractor` appended.
+    #[must_use]
+    pub fn with(&self, extractor: Arc<dyn RequestMetadataExtractor>) -> Self {
+        let mut extractors = self.0.as_ref().clone();
+        extractors.push(extractor);
+        Self::new(extractors)
+    }
+
+    fn extract(&self, headers: &HeaderMap) -> http::Extensions {
+        let mut extensions = http::Extensions::new();
+        for extractor in self.0.iter() {
+            extractor.extract(headers, &mut extensions);
+        }
+        extensions
+    }
+}
+
 #[cfg(feature = "router")]
 pub(crate) async fn create_request_metadata_with_trace_id_fn(
+    axum::extract::State(extractors): axum::extract::State<RequestMetadataExtractors>,
     headers: HeaderMap,
     mut request: axum::extract::Request,
     next: Next,
@@ -42,9 +384,274 @@ pub(crate) async fn create_request_metadata_with_trace_id_fn(
                 .flatten()
         })
         .unwrap_or(Uuid::now_v7());
+    let header_value = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|hv| hv.to_str().ok())
+            .map(ToString::to_string)
+    };
+    let trace_id = header_value("traceparent").and_then(|v| parse_trace_id(&v));
+    let project_id = match header_value("x-project-id")
+        .map(|p| ProjectIdent::from_str(&p))
+        .transpose()
+    {
+        Ok(project_id) => project_id,
+        Err(e) => {
+            let mut response = e.into_response();
+            add_ids_to_error_response(&mut response, request_id, trace_id.as_deref());
+            return response;
+        }
+    };
     request.extensions_mut().insert(RequestMetadata {
         request_id,
         auth_details: None,
+        user_agent: header_value(http::header::USER_AGENT.as_str()),
+        client_version: header_value("x-client-version"),
+        project_id,
+        trace_id: trace_id.clone(),
+        extensions: extractors.extract(&headers),
     });
-    next.run(request).await
+    let mut response = next.run(request).await;
+    add_ids_to_error_response(&mut response, request_id, trace_id.as_deref());
+    response
+}
+
+/// Trace id of a `traceparent` header: `{version}-{trace-id}-{parent-id}-{flags}`.
+fn parse_trace_id(traceparent: &str) -> Option<String> {
+    let trace_id = traceparent.trim().split('-').nth(1)?;
+    (trace_id.len() == 32
+        && trace_id.bytes().all(|b| b.is_ascii_hexdigit())
+        && trace_id.bytes().any(|b| b != b'0'))
+    .then(|| trace_id.to_ascii_lowercase())
+}
+
+/// Add the request id and trace id to the stack of error responses,
+/// so that users can quote them and operators can find the corresponding logs.
+fn add_ids_to_error_response(response: &mut Response, request_id: Uuid, trace_id: Option<&str>) {
+    let Some(mut error) = response.extensions_mut().remove::<IcebergErrorResponse>() else {
+        return;
+    };
+    error
+        .error
+        .push_to_stack(format!("Request ID: {request_id}"));
+    if let Some(trace_id) = trace_id {
+        error.error.push_to_stack(format!("Trace ID: {trace_id}"));
+    }
+    match serde_json::to_vec(&error) {
+        Ok(body) => {
+            response.headers_mut().remove(http::header::CONTENT_LENGTH);
+            *response.body_mut() = axum::body::Body::from(body);
+        }
+        Err(e) => tracing::warn!("Failed to add request id to error response: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::service::token_verification::{Aud, Claims};
+
+    fn with_token_projects(projects: Option<serde_json::Value>) -> RequestMetadata {
+        let mut other = serde_json::json!({});
+        if let Some(projects) = projects {
+            other[PROJECTS_CLAIM] = projects;
+        }
+        RequestMetadata {
+            auth_details: Some(AuthDetails::JWT(Claims {
+                sub: "user".to_string(),
+                iss: "iss".to_string(),
+                aud: Aud::String("aud".to_string()),
+                exp: 0,
+                iat: 0,
+                jti: None,
+                other,
+            })),
+            ..RequestMetadata::new_random()
+        }
+    }
+
+    #[test]
+    fn test_validate_project() {
+        let project_id = ProjectIdent::from(Uuid::now_v7());
+
+        let metadata = RequestMetadata {
+            project_id: Some(project_id.clone()),
+            ..with_token_projects(Some(serde_json::json!([project_id.to_string()])))
+        };
+        metadata.validate_project().unwrap();
+
+        let metadata = RequestMetadata {
+            project_id: Some(ProjectIdent::from(Uuid::now_v7())),
+            ..with_token_projects(Some(serde_json::json!([project_id.to_string()])))
+        };
+        let err = metadata.validate_project().unwrap_err();
+        assert_eq!(err.error.code, http::StatusCode::FORBIDDEN);
+
+        // Without a projects claim, access is left to the authorization backend.
+        let metadata = RequestMetadata {
+            project_id: Some(project_id),
+            ..with_token_projects(None)
+        };
+        metadata.validate_project().unwrap();
+    }
+
+    #[test]
+    fn test_token_scope() {
+        let warehouse_id = WarehouseIdent::from(Uuid::now_v7());
+        let table_id = TableIdentUuid::from(Uuid::now_v7());
+
+        let metadata = with_token_projects(None);
+        metadata
+            .require_token_warehouse(&warehouse_id, false)
+            .unwrap();
+        metadata.require_token_table(None).unwrap();
+
+        let mut metadata = with_token_projects(None);
+        if let Some(AuthDetails::JWT(claims)) = &mut metadata.auth_details {
+            claims.other = serde_json::json!({
+                "warehouse_id": warehouse_id.to_string(),
+                "table_id": table_id.to_string(),
+            });
+        }
+        metadata
+            .require_token_warehouse(&warehouse_id, true)
+            .unwrap();
+        metadata.require_token_table(Some(&table_id)).unwrap();
+        // Table tokens can't list or create tables
+        let err = metadata
+            .require_token_warehouse(&warehouse_id, false)
+            .unwrap_err();
+        assert_eq!(err.error.code, http::StatusCode::FORBIDDEN);
+        metadata
+            .require_token_warehouse(&WarehouseIdent::from(Uuid::now_v7()), true)
+            .unwrap_err();
+        metadata
+            .require_token_table(Some(&TableIdentUuid::from(Uuid::now_v7())))
+            .unwrap_err();
+        metadata.require_token_table(None).unwrap_err();
+    }
+
+    #[test]
+    fn test_scope_permissions() {
+        let scope_permissions = HashMap::from([
+            ("catalog:read".to_string(), Permission::Read),
+            ("catalog:write".to_string(), Permission::Write),
+        ]);
+        let with_claims = |other: serde_json::Value| RequestMetadata {
+            auth_details: Some(AuthDetails::JWT(Claims {
+                sub: "user".to_string(),
+                iss: "iss".to_string(),
+                aud: Aud::String("aud".to_string()),
+                exp: 0,
+                iat: 0,
+                jti: None,
+                other,
+            })),
+            ..RequestMetadata::new_random()
+        };
+
+        let metadata = with_claims(serde_json::json!({"scope": "openid catalog:write"}));
+        let granted = metadata.permissions_for(&scope_permissions);
+        assert_eq!(granted, Some(HashSet::from([Permission::Write])));
+        RequestMetadata::check_permission(granted.clone(), Permission::Read).unwrap();
+        let err = RequestMetadata::check_permission(granted, Permission::Manage).unwrap_err();
+        assert_eq!(err.error.code, http::StatusCode::FORBIDDEN);
+
+        let metadata = with_claims(serde_json::json!({"scp": ["catalog:read"]}));
+        assert_eq!(
+            metadata.permissions_for(&scope_permissions),
+            Some(HashSet::from([Permission::Read]))
+        );
+
+        // Tokens without mapped scopes can't be used at all.
+        let granted = with_claims(serde_json::json!({})).permissions_for(&scope_permissions);
+        RequestMetadata::check_permission(granted, Permission::Read).unwrap_err();
+
+        // Without a mapping, tokens are not restricted.
+        assert_eq!(metadata.permissions_for(&HashMap::new()), None);
+        RequestMetadata::check_permission(None, Permission::Manage).unwrap();
+    }
+
+    #[test]
+    fn test_parse_trace_id() {
+        assert_eq!(
+            parse_trace_id("00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01").as_deref(),
+            Some("4bf92f3577b34da6a3ce929d0e0e4736")
+        );
+        assert_eq!(
+            parse_trace_id("00-00000000000000000000000000000000-00f067aa0ba902b7-01"),
+            None
+        );
+        assert_eq!(parse_trace_id("invalid"), None);
+    }
+
+    #[test]
+    fn test_extractors() {
+        let extractors = RequestMetadataExtractors::new(vec![
+            Arc::new(HeaderExtractor::<TenantId>::new(HeaderName::from_static(
+                "x-tenant",
+            ))),
+            Arc::new(HeaderExtractor::<RegionAffinity>::new(
+                HeaderName::from_static("x-region"),
+            )),
+        ]);
+        let mut headers = HeaderMap::new();
+        headers.insert("x-tenant", " acme ".parse().unwrap());
+        headers.insert("x-region", "".parse().unwrap());
+
+        let metadata = RequestMetadata {
+            extensions: extractors.extract(&headers),
+            ..RequestMetadata::new_random()
+        };
+        assert_eq!(metadata.tenant_id(), Some("acme"));
+        assert!(metadata.extension::<RegionAffinity>().is_none());
+    }
+
+    #[cfg(feature = "router")]
+    #[tokio::test]
+    async fn test_error_response_contains_request_id() {
+        use tower::ServiceExt;
+
+        let router = axum::Router::new()
+            .route(
+                "/",
+                axum::routing::get(|| async {
+                    IcebergErrorResponse::from(
+                        ErrorModel::builder()
+                            .code(http::StatusCode::NOT_FOUND.into())
+                            .message("Table not found")
+                            .r#type("NoSuchTableError")
+                            .build(),
+                    )
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                RequestMetadataExtractors::default(),
+                create_request_metadata_with_trace_id_fn,
+            ));
+        let request_id = Uuid::now_v7();
+        let response = router
+            .oneshot(
+                http::Request::builder()
+                    .uri("/")
+                    .header("x-request-id", request_id.to_string())
+                    .header(
+                        "traceparent",
+                        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+                    )
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+        let body = http_body_util::BodyExt::collect(response.into_body())
+            .await
+            .unwrap()
+            .to_bytes();
+        let error: IcebergErrorResponse = serde_json::from_slice(&body).unwrap();
+        let stack = error.error.stack.unwrap();
+        assert!(stack.contains(&format!("Request ID: {request_id}")));
+        assert!(stack.contains(&"Trace ID: 4bf92f3577b34da6a3ce929d0e0e4736".to_string()));
+    }
 }
@@ -0,0 +1,261 @@
+//! Request and commit counts per warehouse.
+//!
+//! Requests are counted in memory per warehouse and hour by
+//! [`api_usage_middleware_fn`] and periodically written to the [`ApiUsageStore`] by
+//! [`ApiUsageRecorder::run`], so that counting does not add a database
+//! round trip to each request. Requests that do not address a warehouse,
+//! e.g. `GET /config`, are not counted. Counts not yet flushed are lost
+//! if the instance crashes.
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::Response;
+use chrono::{DateTime, DurationRound, Utc};
+use http::Method;
+
+use crate::api::Result;
+use crate::{ProjectIdent, WarehouseIdent};
+
+/// Requests and commits of a warehouse within one window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiUsage {
+    pub warehouse_id: WarehouseIdent,
+    pub window_start: DateTime<Utc>,
+    pub request_count: i64,
+    /// Successful table and transaction commits.
+    pub commit_count: i64,
+}
+
+/// Granularity of aggregated usage.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum UsageWindow {
+    #[default]
+    Hour,
+    Day,
+}
+
+impl UsageWindow {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Hour => "hour",
+            Self::Day => "day",
+        }
+    }
+}
+
+#[async_trait::async_trait]
+pub trait ApiUsageStore: std::fmt::Debug + Send + Sync {
+    /// Add request and commit counts to the stored usage.
+    /// Usage of warehouses that no longer exist is discarded.
+    async fn record_api_usage(&self, usage: Vec<ApiUsage>) -> Result<()>;
+
+    /// Aggregate the usage of all warehouses in `project_id` per `window`,
+    /// counting hourly windows starting in `[from, to)`.
+    /// If `warehouse_id_filter` is set, only these warehouses are included.
+    async fn list_api_usage(
+        &self,
+        project_id: &ProjectIdent,
+        warehouse_id_filter: Option<&HashSet<WarehouseIdent>>,
+        window: UsageWindow,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<ApiUsage>>;
+}
+
+type Counters = HashMap<(WarehouseIdent, DateTime<Utc>), (i64, i64)>;
+
+/// Counts requests per warehouse and hour until they are flushed to the store.
+#[derive(Debug, Clone, Default)]
+pub struct ApiUsageRecorder {
+    counters: Arc<Mutex<Counters>>,
+}
+
+impl ApiUsageRecorder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a request to `warehouse_id`.
+    pub fn record(&self, warehouse_id: &WarehouseIdent, is_commit: bool) {
+        let window_start = Utc::now()
+            .duration_trunc(chrono::Duration::hours(1))
+            .unwrap_or_else(|_| Utc::now());
+        let mut counters = self.counters.lock().expect("api usage lock poisoned");
+        let (requests, commits) = counters
+            .entry((warehouse_id.clone(), window_start))
+            .or_default();
+        *requests += 1;
+        if is_commit {
+            *commits += 1;
+        }
+    }
+
+    fn take(&self) -> Vec<ApiUsage> {
+        let counters = std::mem::take(&mut *self.counters.lock().expect("api usage lock poisoned"));
+        counters
+            .into_iter()
+            .map(
+                |((warehouse_id, window_start), (request_count, commit_count))| ApiUsage {
+                    warehouse_id,
+                    window_start,
+                    request_count,
+                    commit_count,
+                },
+            )
+            .collect()
+    }
+
+    fn restore(&self, usage: Vec<ApiUsage>) {
+        let mut counters = self.counters.lock().expect("api usage lock poisoned");
+        for usage in usage {
+            let (requests, commits) = counters
+                .entry((usage.warehouse_id, usage.window_start))
+                .or_default();
+            *requests += usage.request_count;
+            *commits += usage.commit_count;
+        }
+    }
+
+    /// Write all counts to `store`.
+    /// If writing fails, the counts are kept for the next flush.
+    ///
+    /// # Errors
+    /// Fails if the counts cannot be written.
+    pub async fn flush(&self, store: &dyn ApiUsageStore) -> Result<()> {
+        let usage = self.take();
+        if usage.is_empty() {
+            return Ok(());
+        }
+        if let Err(e) = store.record_api_usage(usage.clone()).await {
+            self.restore(usage);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Flush the counts every `interval` until the task is aborted.
+    pub async fn run(self, store: Arc<dyn ApiUsageStore>, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.flush(store.as_ref()).await {
+                tracing::warn!("Failed to write API usage statistics: {:?}", e.error);
+            }
+        }
+    }
+}
+
+/// Warehouse addressed by a request to the REST or management API.
+fn warehouse_from_path(path: &str) -> Option<WarehouseIdent> {
+    let segment = path
+        .strip_prefix("/catalog/v1/")
+        .or_else(|| path.strip_prefix("/management/v1/warehouse/"))?
+        .split('/')
+        .next()?;
+    WarehouseIdent::from_str(segment).ok()
+}
+
+/// Whether the request commits to a table or transaction.
+fn is_commit(method: &Method, path: &str) -> bool {
+    if method != Method::POST {
+        return false;
+    }
+    let Some(rest) = path.strip_prefix("/catalog/v1/") else {
+        return false;
+    };
+    let segments = rest.split('/').collect::<Vec<_>>();
+    match segments.as_slice() {
+        [_, "transactions", "commit"] => true,
+        [_, "namespaces", _, "tables", table] => !table.is_empty(),
+        _ => false,
+    }
+}
+
+pub(crate) async fn api_usage_middleware_fn(
+    State(recorder): State<ApiUsageRecorder>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_string();
+    let Some(warehouse_id) = warehouse_from_path(&path) else {
+        return next.run(request).await;
+    };
+    let commit = is_commit(request.method(), &path);
+
+    let response = next.run(request).await;
+    recorder.record(&warehouse_id, commit && response.status().is_success());
+    response
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_warehouse_from_path() {
+        let warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        assert_eq!(
+            warehouse_from_path(&format!("/catalog/v1/{warehouse_id}/namespaces")),
+            Some(warehouse_id.clone())
+        );
+        assert_eq!(
+            warehouse_from_path(&format!("/management/v1/warehouse/{warehouse_id}/rename")),
+            Some(warehouse_id.clone())
+        );
+        assert_eq!(warehouse_from_path("/catalog/v1/config"), None);
+        assert_eq!(warehouse_from_path("/management/v1/project"), None);
+
+        let prefix = format!("/catalog/v1/{warehouse_id}");
+        assert!(is_commit(
+            &Method::POST,
+            &format!("{prefix}/namespaces/ns/tables/t")
+        ));
+        assert!(is_commit(
+            &Method::POST,
+            &format!("{prefix}/transactions/commit")
+        ));
+        assert!(!is_commit(
+            &Method::POST,
+            &format!("{prefix}/namespaces/ns/tables")
+        ));
+        assert!(!is_commit(
+            &Method::GET,
+            &format!("{prefix}/namespaces/ns/tables/t")
+        ));
+    }
+
+    #[test]
+    fn test_recorder_aggregates_per_window() {
+        let recorder = ApiUsageRecorder::new();
+        let warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        recorder.record(&warehouse_id, false);
+        recorder.record(&warehouse_id, true);
+
+        let usage = recorder.take();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].request_count, 2);
+        assert_eq!(usage[0].commit_count, 1);
+        assert!(recorder.take().is_empty());
+
+        recorder.restore(usage.clone());
+        assert_eq!(recorder.take(), usage);
+    }
+}
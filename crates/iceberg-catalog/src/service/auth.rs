@@ -1,7 +1,11 @@
 use std::collections::HashSet;
 
-use super::{ProjectIdent, TableIdentUuid, WarehouseIdent};
+use super::{
+    ObjectOwnership, OwnedObject, ProjectIdent, RoleObject, TableIdent, TableIdentUuid,
+    WarehouseIdent,
+};
 use crate::api::iceberg::v1::{NamespaceIdent, Result};
+use crate::api::{ErrorModel, ErrorType, IcebergErrorResponse};
 use crate::request_metadata::RequestMetadata;
 
 #[derive(Debug, Clone)]
@@ -10,6 +14,82 @@ pub struct UserWarehouse {
     pub warehouse_id: Option<WarehouseIdent>,
 }
 
+/// Response to requests for objects the principal is not allowed to access.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(
+    feature = "sqlx",
+    sqlx(type_name = "unauthorized_response", rename_all = "kebab-case")
+)]
+pub enum UnauthorizedResponse {
+    /// Respond with `403 Forbidden`, revealing that the object exists.
+    #[default]
+    Forbidden,
+    /// Respond as if the object did not exist.
+    NotFound,
+}
+
+/// Object an access check is performed for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessedObject {
+    Warehouse,
+    Namespace,
+    Table,
+    View,
+}
+
+impl UnauthorizedResponse {
+    /// Replace a `403` returned by an access check for `object`
+    /// by a `404` if existence of inaccessible objects is hidden.
+    #[must_use]
+    pub fn apply(
+        self,
+        error: IcebergErrorResponse,
+        object: AccessedObject,
+    ) -> IcebergErrorResponse {
+        if self == Self::Forbidden || error.error.code != http::StatusCode::FORBIDDEN.as_u16() {
+            return error;
+        }
+
+        let (message, r#type, error_type) = match object {
+            AccessedObject::Warehouse => (
+                "Warehouse not found",
+                "WarehouseNotFound",
+                ErrorType::NotFound,
+            ),
+            AccessedObject::Namespace => (
+                "Namespace not found",
+                "NoSuchNamespaceError",
+                ErrorType::NoSuchNamespace,
+            ),
+            AccessedObject::Table => (
+                "Table not found",
+                "NoSuchTableError",
+                ErrorType::NoSuchTable,
+            ),
+            AccessedObject::View => ("View not found", "NoSuchViewError", ErrorType::NoSuchView),
+        };
+        ErrorModel::builder()
+            .code(http::StatusCode::NOT_FOUND.into())
+            .message(message.to_string())
+            .r#type(r#type.to_string())
+            .error_type(error_type)
+            .build()
+            .into()
+    }
+}
+
 #[async_trait::async_trait]
 #[allow(clippy::module_name_repetitions)]
 pub trait AuthZHandler
@@ -48,17 +128,23 @@ where
         state: Self::State,
     ) -> Result<()>;
 
+    /// `ownership` contains the current owner and creator of the namespace,
+    /// `None` if the namespace does not exist.
     async fn check_drop_namespace(
         metadata: &RequestMetadata,
         warehouse_id: &WarehouseIdent,
         namespace: &NamespaceIdent,
+        ownership: Option<&ObjectOwnership>,
         state: Self::State,
     ) -> Result<()>;
 
+    /// `ownership` contains the current owner and creator of the namespace,
+    /// `None` if the namespace does not exist.
     async fn check_update_namespace_properties(
         metadata: &RequestMetadata,
         warehouse_id: &WarehouseIdent,
         namespace: &NamespaceIdent,
+        ownership: Option<&ObjectOwnership>,
         state: Self::State,
     ) -> Result<()>;
 
@@ -98,10 +184,14 @@ where
     /// This should check if the user is allowed to rename the table.
     /// For rename to work, also "check_create_table" must pass
     /// for the destination namespace.
+    ///
+    /// `ownership` contains the current owner and creator of the source table,
+    /// `None` if the table does not exist.
     async fn check_rename_table(
         metadata: &RequestMetadata,
         warehouse_id: &WarehouseIdent,
         source: Option<&TableIdentUuid>,
+        ownership: Option<&ObjectOwnership>,
         state: Self::State,
     ) -> Result<()>;
 
@@ -113,18 +203,24 @@ where
         state: Self::State,
     ) -> Result<()>;
 
+    /// `ownership` contains the current owner and creator of the table,
+    /// `None` if the table does not exist.
     async fn check_drop_table(
         metadata: &RequestMetadata,
         warehouse_id: &WarehouseIdent,
         table: Option<&TableIdentUuid>,
+        ownership: Option<&ObjectOwnership>,
         state: Self::State,
     ) -> Result<()>;
 
+    /// `ownership` contains the current owner and creator of the table,
+    /// `None` if the table does not exist or was not resolved, e.g. when signing S3 requests.
     async fn check_commit_table(
         metadata: &RequestMetadata,
         warehouse_id: &WarehouseIdent,
         table: Option<&TableIdentUuid>,
         namespace: Option<&NamespaceIdent>,
+        ownership: Option<&ObjectOwnership>,
         state: Self::State,
     ) -> Result<()>;
 
@@ -150,6 +246,19 @@ where
         state: Self::State,
     ) -> Result<Option<HashSet<WarehouseIdent>>>;
 
+    /// Rename a project or replace its metadata.
+    async fn check_update_project(
+        metadata: &RequestMetadata,
+        project_id: &ProjectIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_delete_project(
+        metadata: &RequestMetadata,
+        project_id: &ProjectIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
     async fn check_delete_warehouse(
         metadata: &RequestMetadata,
         warehouse_id: &WarehouseIdent,
@@ -185,6 +294,144 @@ where
         warehouse_id: &WarehouseIdent,
         state: Self::State,
     ) -> Result<()>;
+
+    async fn check_set_warehouse_allowlist(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_set_engine_table_config(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_set_event_filter(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_set_maintenance_mode(
+        metadata: &RequestMetadata,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_reload_config(metadata: &RequestMetadata, state: Self::State) -> Result<()>;
+
+    async fn check_set_log_level(metadata: &RequestMetadata, state: Self::State) -> Result<()>;
+
+    async fn check_set_warehouse_read_only(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_set_warehouse_pyiceberg_compat(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_set_warehouse_signer_uri(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_set_warehouse_unauthorized_response(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_set_warehouse_access_delegation(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_set_warehouse_allow_external_locations(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_set_warehouse_object_storage_layout(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_set_table_policy(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_set_warehouse_retention_policy(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    async fn check_set_namespace_retention_policy(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    /// Check if the user is allowed to list and discard staged tables of `warehouse_id`.
+    async fn check_manage_staged_tables(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    /// Check if the user is allowed to create, list and delete shares
+    /// of objects in `warehouse_id` with other projects.
+    async fn check_manage_shares(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        state: Self::State,
+    ) -> Result<()>;
+
+    /// Check if the user is allowed to transfer ownership of `object`.
+    ///
+    /// `ownership` contains the current owner and creator of the object,
+    /// so that implementations can i.e. only allow the current owner
+    /// to hand the object over to someone else. The principal of the
+    /// requesting user is available via `metadata.principal()`.
+    async fn check_transfer_ownership(
+        metadata: &RequestMetadata,
+        warehouse_id: &WarehouseIdent,
+        object: &OwnedObject,
+        ownership: &ObjectOwnership,
+        state: Self::State,
+    ) -> Result<()>;
+
+    /// Check if the user is allowed to revoke tokens.
+    /// Revocations are not scoped to a warehouse.
+    async fn check_revoke_token(metadata: &RequestMetadata, state: Self::State) -> Result<()>;
+
+    /// Check if the user is allowed to create and delete service keys.
+    /// Service keys are not scoped to a warehouse.
+    async fn check_manage_service_keys(
+        metadata: &RequestMetadata,
+        state: Self::State,
+    ) -> Result<()>;
+
+    /// Check if the user is allowed to grant and revoke roles on `object`.
+    /// Listing the role assignments of a project requires this permission
+    /// on the project.
+    async fn check_manage_roles(
+        metadata: &RequestMetadata,
+        object: &RoleObject,
+        state: Self::State,
+    ) -> Result<()>;
 }
 
 /// Interface to provide Auth-related functions to the config gateway.
@@ -1,4 +1,4 @@
-use iceberg::spec::TableMetadata;
+use iceberg::spec::{SnapshotRetention, TableMetadata, MAIN_BRANCH};
 pub use iceberg_ext::catalog::rest::{
     CommitTableResponse, CommitTransactionRequest, CreateTableRequest,
 };
@@ -7,8 +7,12 @@ use std::collections::{HashMap, HashSet};
 use crate::SecretIdent;
 
 use super::{
-    storage::StorageProfile, NamespaceIdentUuid, ProjectIdent, TableIdentUuid, WarehouseIdent,
-    WarehouseStatus,
+    auth::UnauthorizedResponse,
+    credential_rotation::CredentialRotation,
+    page_token::PageQuery,
+    storage::{AccessDelegation, StorageProfile},
+    storage_validation::StorageValidation,
+    NamespaceIdentUuid, ProjectIdent, TableIdentUuid, WarehouseIdent, WarehouseStatus,
 };
 pub use crate::api::iceberg::v1::{
     CreateNamespaceRequest, CreateNamespaceResponse, ListNamespacesQuery, ListNamespacesResponse,
@@ -21,7 +25,7 @@ pub trait Transaction<D>
 where
     Self: Sized + Send + Sync,
 {
-    type Transaction<'a>
+    type Transaction<'a>: Send
     where
         Self: 'a;
 
@@ -50,6 +54,15 @@ pub struct CreateTableResponse {
     pub table_metadata: TableMetadata,
 }
 
+/// Current metadata of a table, as returned by bulk loads.
+#[derive(Debug, Clone)]
+pub struct LoadedTable {
+    pub table_id: TableIdentUuid,
+    pub table: TableIdent,
+    pub metadata_location: String,
+    pub table_metadata: TableMetadata,
+}
+
 #[derive(Debug)]
 pub struct LoadTableResponse {
     pub table_id: TableIdentUuid,
@@ -58,6 +71,10 @@ pub struct LoadTableResponse {
     pub metadata_location: Option<String>,
     pub storage_secret_ident: Option<SecretIdent>,
     pub storage_profile: StorageProfile,
+    /// Access delegation forced by table or namespace properties.
+    pub access_delegation: Option<AccessDelegation>,
+    /// Governance policy attached to the table.
+    pub policy: Option<TablePolicy>,
 }
 
 #[derive(Debug)]
@@ -69,6 +86,8 @@ pub struct GetTableMetadataResponse {
     pub metadata_location: Option<String>,
     pub storage_secret_ident: Option<SecretIdent>,
     pub storage_profile: StorageProfile,
+    /// Access delegation forced by table or namespace properties.
+    pub access_delegation: Option<AccessDelegation>,
 }
 
 #[derive(Debug)]
@@ -83,6 +102,32 @@ pub struct CommitTableResponseExt {
     pub commit_response: CommitTableResponse,
     pub storage_config: GetStorageConfigResponse,
     pub previous_table_metadata: TableMetadata,
+    /// `None` if the table was staged before the commit.
+    pub previous_metadata_location: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Project {
+    /// ID of the project.
+    pub project_id: ProjectIdent,
+    /// Name of the project, unique in the catalog.
+    /// Defaults to the id of the project.
+    pub name: String,
+    /// Free-form metadata of the project.
+    pub metadata: HashMap<String, String>,
+}
+
+impl Project {
+    /// A project without a stored name and metadata, such as a project
+    /// that has no warehouses yet.
+    #[must_use]
+    pub fn unnamed(project_id: ProjectIdent) -> Self {
+        Self {
+            name: project_id.to_string(),
+            project_id,
+            metadata: HashMap::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -99,6 +144,612 @@ pub struct GetWarehouseResponse {
     pub storage_secret_id: Option<SecretIdent>,
     /// Whether the warehouse is active.
     pub status: WarehouseStatus,
+    /// Principal that currently owns the warehouse.
+    pub owner: Option<String>,
+    /// Principal that created the warehouse.
+    pub created_by: Option<String>,
+    /// Principals and groups allowed to access the warehouse.
+    /// `None` if access is not restricted.
+    pub allowlist: Option<WarehouseAllowlist>,
+    /// Whether the warehouse rejects creates, commits, drops and renames.
+    pub read_only: bool,
+    /// Whether deviations of older pyiceberg clients are tolerated.
+    pub pyiceberg_compat: bool,
+    /// Extra table config per client engine.
+    pub engine_table_config: EngineTableConfig,
+    /// Address of the S3 signer returned to clients.
+    /// `None` if it is derived from the base URI of the catalog.
+    pub signer_uri: Option<String>,
+    /// Response to requests for objects the principal is not allowed to access.
+    /// `None` if the server default applies.
+    pub unauthorized_response: Option<UnauthorizedResponse>,
+    /// Access delegation used if a client does not request a mode.
+    /// `None` if the default of the storage profile applies.
+    pub access_delegation: Option<AccessDelegation>,
+    /// Whether tables may be located outside of the storage profile.
+    pub allow_external_locations: bool,
+    /// Whether new tables are located below a hash prefix, unless their
+    /// namespace or the table itself sets `write.object-storage.enabled`.
+    pub object_storage_layout: bool,
+    /// Last rotation of the storage credential.
+    /// `None` if the credential was never rotated.
+    pub credential_rotation: Option<CredentialRotation>,
+    /// Result of the last check of the storage profile and credential.
+    /// `None` if the warehouse was not checked yet.
+    pub storage_validation: Option<StorageValidation>,
+    /// Events of the warehouse that are published.
+    pub event_filter: EventFilter,
+}
+
+impl GetWarehouseResponse {
+    /// Compatibility settings for older pyiceberg clients,
+    /// `None` if compatibility mode is disabled.
+    #[must_use]
+    pub fn pyiceberg_compat_mode(&self) -> Option<PyicebergCompat> {
+        self.pyiceberg_compat.then(|| PyicebergCompat {
+            warehouse_name: self.name.clone(),
+        })
+    }
+
+    /// Response to requests for objects the principal is not allowed to access,
+    /// falling back to the server default.
+    #[must_use]
+    pub fn effective_unauthorized_response(&self) -> UnauthorizedResponse {
+        self.unauthorized_response
+            .unwrap_or(crate::CONFIG.unauthorized_response)
+    }
+}
+
+/// Principals and groups that may access a warehouse at all.
+///
+/// The allowlist is checked before the `AuthZHandler` is consulted.
+/// A request is let through if either its principal or one of its
+/// groups is listed.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, utoipa::ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub struct WarehouseAllowlist {
+    /// Principals (i.e. the `sub` claim of a token) that are allowed.
+    #[serde(default)]
+    pub principals: Vec<String>,
+    /// Groups (i.e. the `groups` claim of a token) that are allowed.
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+impl WarehouseAllowlist {
+    #[must_use]
+    pub fn allows(&self, principal: Option<&str>, groups: &[&str]) -> bool {
+        principal.is_some_and(|p| self.principals.iter().any(|allowed| allowed == p))
+            || groups
+                .iter()
+                .any(|g| self.groups.iter().any(|allowed| allowed == g))
+    }
+}
+
+/// Governance policy attached to a table.
+///
+/// The catalog does not enforce the policy itself. It is returned as
+/// part of the `loadTable` config so that engines and scanners can enforce it.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, utoipa::ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub struct TablePolicy {
+    /// Classification of the table, i.e. `public`, `internal` or `confidential`.
+    pub classification: Option<String>,
+    /// Sensitivity hints per column name, i.e. `pii`.
+    #[serde(default)]
+    pub column_sensitivity: HashMap<String, String>,
+    /// Retention class that housekeeping jobs apply to the table.
+    pub retention_class: Option<String>,
+}
+
+impl TablePolicy {
+    /// Prefix of all `loadTable` config keys that carry the policy.
+    pub const CONFIG_PREFIX: &'static str = "policy.";
+
+    /// Render the policy as `loadTable` config entries.
+    #[must_use]
+    pub fn table_config(&self) -> HashMap<String, String> {
+        let mut config = HashMap::new();
+        if let Some(classification) = &self.classification {
+            config.insert(
+                format!("{}classification", Self::CONFIG_PREFIX),
+                classification.clone(),
+            );
+        }
+        if let Some(retention_class) = &self.retention_class {
+            config.insert(
+                format!("{}retention-class", Self::CONFIG_PREFIX),
+                retention_class.clone(),
+            );
+        }
+        for (column, sensitivity) in &self.column_sensitivity {
+            config.insert(
+                format!("{}column-sensitivity.{column}", Self::CONFIG_PREFIX),
+                sensitivity.clone(),
+            );
+        }
+        config
+    }
+}
+
+/// Snapshot retention of the tables in a warehouse or namespace.
+///
+/// The policy of the closest namespace applies, falling back to the policy of
+/// the warehouse. It is applied on every commit and by the snapshot expiration
+/// job. Tables override individual settings with the `history.expire.*`
+/// properties. Snapshots referenced by a branch or tag are never expired,
+/// but branches and tags other than `main` expire after `max-ref-age-ms`.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, utoipa::ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub struct SnapshotRetentionPolicy {
+    /// Snapshots older than this are expired.
+    pub max_snapshot_age_ms: Option<i64>,
+    /// Only this number of most recent snapshots is kept.
+    pub max_snapshots: Option<u32>,
+    /// This number of most recent snapshots is kept regardless of their age.
+    #[serde(default)]
+    pub min_snapshots_to_keep: Option<u32>,
+    /// Branches and tags pointing to snapshots older than this are removed,
+    /// unless the reference sets its own `max-ref-age-ms`.
+    #[serde(default)]
+    pub max_ref_age_ms: Option<i64>,
+}
+
+impl SnapshotRetentionPolicy {
+    pub const MAX_SNAPSHOT_AGE_MS_PROPERTY: &'static str = "history.expire.max-snapshot-age-ms";
+    pub const MAX_SNAPSHOTS_PROPERTY: &'static str = "history.expire.max-snapshots";
+    pub const MIN_SNAPSHOTS_TO_KEEP_PROPERTY: &'static str = "history.expire.min-snapshots-to-keep";
+    pub const MAX_REF_AGE_MS_PROPERTY: &'static str = "history.expire.max-ref-age-ms";
+
+    /// Table properties that override the policy.
+    pub const PROPERTIES: [&'static str; 4] = [
+        Self::MAX_SNAPSHOT_AGE_MS_PROPERTY,
+        Self::MAX_SNAPSHOTS_PROPERTY,
+        Self::MIN_SNAPSHOTS_TO_KEEP_PROPERTY,
+        Self::MAX_REF_AGE_MS_PROPERTY,
+    ];
+
+    /// Whether the policy expires any snapshots or references.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.max_snapshot_age_ms.is_none()
+            && self.max_snapshots.is_none()
+            && self.max_ref_age_ms.is_none()
+    }
+
+    /// Apply the overrides in `properties` of a table.
+    /// Invalid property values are ignored.
+    #[must_use]
+    pub fn with_table_properties(&self, properties: &HashMap<String, String>) -> Self {
+        fn parse<T: std::str::FromStr>(
+            properties: &HashMap<String, String>,
+            key: &str,
+            default: Option<T>,
+        ) -> Option<T> {
+            properties.get(key).and_then(|v| v.parse().ok()).or(default)
+        }
+
+        Self {
+            max_snapshot_age_ms: parse(
+                properties,
+                Self::MAX_SNAPSHOT_AGE_MS_PROPERTY,
+                self.max_snapshot_age_ms,
+            ),
+            max_snapshots: parse(properties, Self::MAX_SNAPSHOTS_PROPERTY, self.max_snapshots),
+            min_snapshots_to_keep: parse(
+                properties,
+                Self::MIN_SNAPSHOTS_TO_KEEP_PROPERTY,
+                self.min_snapshots_to_keep,
+            ),
+            max_ref_age_ms: parse(
+                properties,
+                Self::MAX_REF_AGE_MS_PROPERTY,
+                self.max_ref_age_ms,
+            ),
+        }
+    }
+
+    /// Names of the branches and tags of `metadata` that are expired at `now_ms`.
+    /// The `main` branch never expires.
+    #[must_use]
+    pub fn expired_refs(&self, metadata: &TableMetadata, now_ms: i64) -> Vec<String> {
+        metadata
+            .refs
+            .iter()
+            .filter(|(name, _)| name.as_str() != MAIN_BRANCH)
+            .filter_map(|(name, reference)| {
+                let max_ref_age_ms = match &reference.retention {
+                    SnapshotRetention::Branch { max_ref_age_ms, .. }
+                    | SnapshotRetention::Tag { max_ref_age_ms } => *max_ref_age_ms,
+                }
+                .or(self.max_ref_age_ms)?;
+                let timestamp_ms = metadata
+                    .snapshot_by_id(reference.snapshot_id)?
+                    .timestamp()
+                    .timestamp_millis();
+                (timestamp_ms < now_ms.saturating_sub(max_ref_age_ms)).then(|| name.clone())
+            })
+            .collect()
+    }
+
+    /// Ids of the snapshots of `metadata` that are expired at `now_ms`,
+    /// assuming that the references returned by [`Self::expired_refs`] are removed.
+    #[must_use]
+    pub fn expired_snapshots(&self, metadata: &TableMetadata, now_ms: i64) -> Vec<i64> {
+        let expired_refs = self.expired_refs(metadata, now_ms);
+        let referenced = metadata
+            .refs
+            .iter()
+            .filter(|(name, _)| !expired_refs.contains(name))
+            .map(|(_, r)| r.snapshot_id)
+            .collect::<HashSet<_>>();
+        let snapshots = metadata
+            .snapshots()
+            .map(|s| (s.snapshot_id(), s.timestamp().timestamp_millis()))
+            .collect();
+        self.select_expired(snapshots, &referenced, now_ms)
+    }
+
+    fn select_expired(
+        &self,
+        mut snapshots: Vec<(i64, i64)>,
+        referenced: &HashSet<i64>,
+        now_ms: i64,
+    ) -> Vec<i64> {
+        // Newest first
+        snapshots.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(b.0.cmp(&a.0)));
+        let to_usize =
+            |n: Option<u32>, default| n.and_then(|n| usize::try_from(n).ok()).unwrap_or(default);
+        let max_snapshots = to_usize(self.max_snapshots, usize::MAX);
+        let min_snapshots_to_keep = to_usize(self.min_snapshots_to_keep, 0);
+        let min_timestamp_ms = self
+            .max_snapshot_age_ms
+            .map_or(i64::MIN, |age| now_ms.saturating_sub(age));
+
+        snapshots
+            .into_iter()
+            .enumerate()
+            .filter(|(i, (id, timestamp_ms))| {
+                !referenced.contains(id)
+                    && (*i >= max_snapshots
+                        || (*i >= min_snapshots_to_keep && *timestamp_ms < min_timestamp_ms))
+            })
+            .map(|(_, (id, _))| id)
+            .collect()
+    }
+}
+
+/// A table subject to snapshot retention.
+#[derive(Debug, Clone)]
+pub struct RetentionCandidate {
+    pub warehouse_id: WarehouseIdent,
+    pub table_id: TableIdentUuid,
+    pub table: TableIdent,
+    /// Policy of the closest namespace that has one, or of the warehouse.
+    /// Not set if only the table properties define a retention.
+    pub policy: Option<SnapshotRetentionPolicy>,
+}
+
+/// An object of a warehouse that carries ownership information.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OwnedObject {
+    Warehouse,
+    Namespace(NamespaceIdent),
+    Table(TableIdent),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectOwnership {
+    /// Principal that currently owns the object.
+    pub owner: Option<String>,
+    /// Principal that created the object.
+    pub created_by: Option<String>,
+}
+
+/// A namespace or table shared read-only with another project.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SharedObject {
+    /// The namespace including all tables and child namespaces.
+    Namespace(NamespaceIdent),
+    Table(TableIdent),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Share {
+    pub share_id: uuid::Uuid,
+    pub object: SharedObject,
+    /// Project that may read the shared object.
+    pub consumer_project_id: ProjectIdent,
+    /// Name of the virtual namespace the object is mounted under in
+    /// all warehouses of the consumer project.
+    pub mount_name: String,
+    pub created_by: Option<String>,
+}
+
+/// Role of a principal on an object, used by the built-in role based access control.
+/// A role on an object applies to all objects it contains.
+/// Ordered by privileges, every role includes the privileges of the roles below it.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(
+    feature = "sqlx",
+    sqlx(type_name = "rbac_role", rename_all = "kebab-case")
+)]
+pub enum Role {
+    /// Read namespaces and tables.
+    Reader,
+    /// Create, modify and drop namespaces and tables.
+    Writer,
+    /// Change settings of the object and grant roles on it.
+    Admin,
+}
+
+/// Object a role is granted on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RoleObject {
+    Project(ProjectIdent),
+    Warehouse(WarehouseIdent),
+    Namespace(WarehouseIdent, NamespaceIdent),
+    Table(WarehouseIdent, TableIdent),
+}
+
+impl RoleObject {
+    #[must_use]
+    pub fn warehouse_id(&self) -> Option<&WarehouseIdent> {
+        match self {
+            RoleObject::Project(_) => None,
+            RoleObject::Warehouse(warehouse_id)
+            | RoleObject::Namespace(warehouse_id, _)
+            | RoleObject::Table(warehouse_id, _) => Some(warehouse_id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoleAssignment {
+    pub assignment_id: uuid::Uuid,
+    pub principal: String,
+    pub role: Role,
+    pub object: RoleObject,
+    /// Project of the object.
+    pub project_id: ProjectIdent,
+    pub created_by: Option<String>,
+}
+
+/// Object an access check is performed on, see [`RoleStore::get_roles`](super::roles::RoleStore::get_roles).
+#[derive(Debug, Clone, PartialEq)]
+pub enum RoleTarget {
+    Project(ProjectIdent),
+    Warehouse(WarehouseIdent),
+    /// A namespace of the warehouse, which doesn't need to exist.
+    Namespace(WarehouseIdent, NamespaceIdent),
+    Table(WarehouseIdent, TableIdentUuid),
+}
+
+/// A table that was created with `stage-create` but never committed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StagedTable {
+    pub table_id: TableIdentUuid,
+    pub table: TableIdent,
+    pub table_location: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub created_by: Option<String>,
+}
+
+/// A dropped table that has not been reclaimed yet.
+#[derive(Debug, Clone)]
+pub struct DroppedTable {
+    pub table_id: TableIdentUuid,
+    pub warehouse_id: WarehouseIdent,
+    pub table: TableIdent,
+    pub table_location: String,
+    /// All metadata files the table pointed to while it existed.
+    pub metadata_locations: Vec<String>,
+    pub dropped_at: chrono::DateTime<chrono::Utc>,
+    pub storage_profile: StorageProfile,
+    pub storage_secret_ident: Option<SecretIdent>,
+}
+
+/// A metadata file a table pointed to at some point in time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataLogEntry {
+    pub metadata_location: String,
+    /// Milliseconds since epoch at which the file became the current metadata.
+    pub timestamp_ms: i64,
+}
+
+/// A commit to a table through the catalog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableCommit {
+    pub table_id: TableIdentUuid,
+    pub committed_at: chrono::DateTime<chrono::Utc>,
+    /// Principal that committed. `None` if the request was not authenticated.
+    pub committed_by: Option<String>,
+    /// Snapshot that became the current snapshot with the commit.
+    /// `None` if the commit did not change the current snapshot.
+    pub snapshot_id: Option<i64>,
+    /// `operation` of the summary of `snapshot_id`, i.e. `append`.
+    pub operation: Option<String>,
+    /// Metadata file written by the commit.
+    pub metadata_location: String,
+}
+
+impl TableCommit {
+    #[must_use]
+    pub fn new(
+        table_id: TableIdentUuid,
+        commit: &CommitTableResponseExt,
+        committed_by: Option<&str>,
+    ) -> Self {
+        let metadata = &commit.commit_response.metadata;
+        let snapshot_id = metadata
+            .current_snapshot_id
+            .filter(|id| commit.previous_table_metadata.current_snapshot_id != Some(*id));
+        let operation = snapshot_id
+            .and_then(|id| metadata.snapshots.get(&id))
+            .and_then(|snapshot| serde_json::to_value(&snapshot.summary().operation).ok())
+            .and_then(|operation| operation.as_str().map(str::to_string));
+        Self {
+            table_id,
+            committed_at: chrono::Utc::now(),
+            committed_by: committed_by.map(str::to_string),
+            snapshot_id,
+            operation,
+            metadata_location: commit.commit_response.metadata_location.clone(),
+        }
+    }
+}
+
+/// A share as seen from a warehouse of the consumer project.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedShare {
+    pub share_id: uuid::Uuid,
+    /// Warehouse that owns the shared object.
+    pub warehouse_id: WarehouseIdent,
+    /// Namespace in the owning warehouse that the mount point maps to.
+    pub namespace: NamespaceIdent,
+    /// Set if only a single table is shared.
+    pub table_name: Option<String>,
+}
+
+impl ResolvedShare {
+    /// Map a namespace below the mount point of the consumer
+    /// to the namespace in the owning warehouse.
+    /// The first element of `namespace` is the mount name.
+    #[must_use]
+    pub fn source_namespace(&self, namespace: &NamespaceIdent) -> Option<NamespaceIdent> {
+        let suffix = namespace.as_ref().get(1..)?;
+        if self.table_name.is_some() && !suffix.is_empty() {
+            return None;
+        }
+        let mut source = self.namespace.as_ref().clone();
+        source.extend_from_slice(suffix);
+        NamespaceIdent::from_vec(source).ok()
+    }
+
+    /// Map a table below the mount point of the consumer
+    /// to the table in the owning warehouse.
+    #[must_use]
+    pub fn source_table(&self, table: &TableIdent) -> Option<TableIdent> {
+        if let Some(table_name) = &self.table_name {
+            if !table_name.eq_ignore_ascii_case(&table.name) {
+                return None;
+            }
+        }
+        Some(TableIdent::new(
+            self.source_namespace(&table.namespace)?,
+            table.name.clone(),
+        ))
+    }
+}
+
+/// Extra table config returned by `loadTable` and `createTable`, keyed by client engine.
+///
+/// Engines are detected from the request headers,
+/// see [`ClientInfo`](super::client_usage::ClientInfo). The config only provides defaults:
+/// keys generated by the catalog, such as credentials or signer settings, take precedence.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, utoipa::ToSchema,
+)]
+#[serde(transparent)]
+pub struct EngineTableConfig(pub HashMap<String, HashMap<String, String>>);
+
+impl EngineTableConfig {
+    /// Add the config of `engine` to `config` without overwriting existing keys.
+    pub fn apply(&self, engine: &str, config: &mut HashMap<String, String>) {
+        let Some((_, defaults)) = self
+            .0
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(engine))
+        else {
+            return;
+        };
+
+        for (key, value) in defaults {
+            config.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+}
+
+/// Events of a warehouse that are published to the event sinks, e.g. NATS or webhooks.
+///
+/// Events are still used internally, e.g. to invalidate caches, if they are not published.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct EventFilter {
+    /// Whether events of the warehouse are published at all.
+    pub enabled: bool,
+    /// Types of events that are not published, e.g. `table.committed`.
+    pub disabled_event_types: Vec<String>,
+}
+
+impl Default for EventFilter {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            disabled_event_types: vec![],
+        }
+    }
+}
+
+impl EventFilter {
+    /// Whether events of type `typ` are published.
+    #[must_use]
+    pub fn allows(&self, typ: &str) -> bool {
+        self.enabled && !self.disabled_event_types.iter().any(|t| t == typ)
+    }
+}
+
+/// Compatibility mode for older pyiceberg clients.
+///
+/// Depending on the version and the configured catalog name, pyiceberg
+/// either prepends the warehouse name to namespaces or strips the first
+/// namespace segment if it matches the catalog name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PyicebergCompat {
+    pub warehouse_name: String,
+}
+
+impl PyicebergCompat {
+    /// Remove the warehouse name if it was prepended to `namespace`.
+    #[must_use]
+    pub fn strip_warehouse_name(&self, namespace: NamespaceIdent) -> NamespaceIdent {
+        match namespace.as_ref().split_first() {
+            Some((first, rest))
+                if !rest.is_empty() && first.eq_ignore_ascii_case(&self.warehouse_name) =>
+            {
+                NamespaceIdent::from_vec(rest.to_vec()).unwrap_or(namespace)
+            }
+            _ => namespace,
+        }
+    }
+
+    /// Restore a first namespace segment that was stripped by the client.
+    #[must_use]
+    pub fn prepend_warehouse_name(&self, namespace: &NamespaceIdent) -> NamespaceIdent {
+        let mut prepended = vec![self.warehouse_name.clone()];
+        prepended.extend_from_slice(namespace.as_ref());
+        NamespaceIdent::from_vec(prepended).unwrap_or_else(|_| namespace.clone())
+    }
 }
 
 #[async_trait::async_trait]
@@ -110,6 +761,10 @@ where
     type Transaction: Transaction<Self::State>;
     type State: Clone + Send + Sync + 'static;
 
+    /// State whose reads are served by the primary, so that they see all committed writes.
+    /// Used for reads of recently written tables, see [`super::recent_writes::RecentWrites`].
+    fn primary_state(catalog_state: Self::State) -> Self::State;
+
     // Should only return namespaces if the warehouse is active.
     async fn list_namespaces(
         warehouse_id: &WarehouseIdent,
@@ -117,9 +772,12 @@ where
         catalog_state: Self::State,
     ) -> Result<ListNamespacesResponse>;
 
+    /// `owner` is the principal creating the namespace. It is stored
+    /// both as creator and as initial owner.
     async fn create_namespace<'a>(
         warehouse_id: &WarehouseIdent,
         request: CreateNamespaceRequest,
+        owner: Option<&str>,
         transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
     ) -> Result<CreateNamespaceResponse>;
 
@@ -161,21 +819,24 @@ where
         request: CreateTableRequest,
         // Metadata location may be none if stage-create is true
         metadata_location: Option<&String>,
+        owner: Option<&str>,
         transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
     ) -> Result<CreateTableResponse>;
 
+    /// List the tables of a namespace, ordered by name and restricted to `page`.
+    /// If include_staged is true, also return staged tables.
     async fn list_tables(
         warehouse_id: &WarehouseIdent,
         namespace: &NamespaceIdent,
         include_staged: bool,
+        page: &PageQuery,
         catalog_state: Self::State,
-    ) -> Result<HashMap<TableIdentUuid, TableIdent>>;
+    ) -> Result<Vec<(TableIdentUuid, TableIdent)>>;
 
     /// Return Err only on unexpected errors, not if the table does not exist.
     /// If include_staged is true, also return staged tables.
     /// If the table does not exist, return Ok(None).
     ///
-    /// We use this function also to handle the `table_exists` endpoint.
     /// Also return Ok(None) if the warehouse is not active.
     async fn table_ident_to_id(
         warehouse_id: &WarehouseIdent,
@@ -184,6 +845,19 @@ where
         catalog_state: Self::State,
     ) -> Result<Option<TableIdentUuid>>;
 
+    /// Check if a table exists without loading it.
+    /// If include_staged is true, staged tables count as existing.
+    ///
+    /// Used by the `table_exists` endpoint, which is called very frequently
+    /// by query planners, so implementations should keep this cheap.
+    /// Return Ok(false) if the warehouse is not active.
+    async fn table_exists(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        include_staged: bool,
+        catalog_state: Self::State,
+    ) -> Result<bool>;
+
     /// Same as `table_ident_to_id`, but for multiple tables.
     async fn table_idents_to_ids(
         warehouse_id: &WarehouseIdent,
@@ -216,6 +890,46 @@ where
         catalog_state: Self::State,
     ) -> Result<GetTableMetadataResponse>;
 
+    /// Get all metadata files of a table, oldest first.
+    /// Combines the `metadata-log` of the current metadata with the
+    /// history recorded by the catalog. Includes the current metadata file.
+    async fn get_table_metadata_log(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdentUuid,
+        catalog_state: Self::State,
+    ) -> Result<Vec<MetadataLogEntry>>;
+
+    /// Commits to a table through the catalog, newest first.
+    /// Only commits before `before` are returned if set.
+    async fn list_table_commits(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdentUuid,
+        before: Option<chrono::DateTime<chrono::Utc>>,
+        limit: i64,
+        catalog_state: Self::State,
+    ) -> Result<Vec<TableCommit>>;
+
+    /// Load the current metadata of many tables at once.
+    /// Tables that do not exist or are staged are omitted from the result.
+    async fn load_tables(
+        warehouse_id: &WarehouseIdent,
+        tables: HashSet<&TableIdent>,
+        catalog_state: Self::State,
+    ) -> Result<Vec<LoadedTable>>;
+
+    /// List all staged tables of a warehouse, oldest first.
+    async fn list_staged_tables(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<Vec<StagedTable>>;
+
+    /// Discard a staged table. Fails if the table does not exist or is not staged.
+    async fn discard_staged_table<'a>(
+        warehouse_id: &WarehouseIdent,
+        table_id: &TableIdentUuid,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
     /// Rename a table. Tables may be moved across namespaces.
     async fn rename_table<'a>(
         warehouse_id: &WarehouseIdent,
@@ -228,6 +942,8 @@ where
     /// Drop a table.
     /// Should drop staged and non-staged tables.
     ///
+    /// Non-staged tables must be returned by `list_expired_dropped_tables`
+    /// until they are reclaimed.
     /// Consider in your implementation to implement an UNDROP feature.
     async fn drop_table<'a>(
         warehouse_id: &WarehouseIdent,
@@ -235,6 +951,23 @@ where
         transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
     ) -> Result<()>;
 
+    /// List at most `limit` dropped tables that were dropped before
+    /// `dropped_before` and have not been reclaimed yet, oldest first.
+    async fn list_expired_dropped_tables(
+        dropped_before: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+        catalog_state: Self::State,
+    ) -> Result<Vec<DroppedTable>>;
+
+    /// Remove all remaining catalog data of a dropped table.
+    /// A record that the table was reclaimed is kept.
+    async fn reclaim_dropped_table<'a>(
+        warehouse_id: &WarehouseIdent,
+        table_id: &TableIdentUuid,
+        storage_purged: bool,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
     /// Commit changes to a table.
     /// The table might be staged or not.
     async fn commit_table_transaction<'a>(
@@ -244,6 +977,12 @@ where
         transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
     ) -> Result<Vec<CommitTableResponseExt>>;
 
+    /// Record commits, in the same transaction as the commits themselves.
+    async fn record_table_commits<'a>(
+        commits: &[TableCommit],
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
     // ---------------- Warehouse Management API ----------------
 
     /// Create a warehouse.
@@ -252,12 +991,47 @@ where
         project_id: ProjectIdent,
         storage_profile: StorageProfile,
         storage_secret_id: Option<SecretIdent>,
+        owner: Option<&str>,
         transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
     ) -> Result<WarehouseIdent>;
 
     /// Return a list of all project ids in the catalog
     async fn list_projects(catalog_state: Self::State) -> Result<HashSet<ProjectIdent>>;
 
+    /// Get the name and metadata of projects.
+    /// If `project_ids` is `None`, all projects are returned.
+    /// Requested projects that are not known to the catalog are omitted.
+    async fn get_projects(
+        project_ids: Option<&HashSet<ProjectIdent>>,
+        catalog_state: Self::State,
+    ) -> Result<Vec<Project>>;
+
+    /// Resolve the name of a project to its id. `None` if no such project exists.
+    async fn get_project_id_by_name(
+        project_name: &str,
+        catalog_state: Self::State,
+    ) -> Result<Option<ProjectIdent>>;
+
+    /// Rename a project. Project names are unique in the catalog.
+    async fn rename_project<'a>(
+        project_id: &ProjectIdent,
+        new_name: &str,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Replace the metadata of a project.
+    async fn set_project_metadata<'a>(
+        project_id: &ProjectIdent,
+        metadata: &HashMap<String, String>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Delete a project including its roles. Fails if the project still has warehouses.
+    async fn delete_project<'a>(
+        project_id: &ProjectIdent,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
     /// Return a list of all warehouse in a project
     async fn list_warehouses(
         project_id: &ProjectIdent,
@@ -270,6 +1044,14 @@ where
         catalog_state: Self::State,
     ) -> Result<Vec<GetWarehouseResponse>>;
 
+    /// Resolve the name of a warehouse in a project to its id,
+    /// regardless of the status of the warehouse. `None` if no such warehouse exists.
+    async fn get_warehouse_id_by_name(
+        project_id: &ProjectIdent,
+        warehouse_name: &str,
+        catalog_state: Self::State,
+    ) -> Result<Option<WarehouseIdent>>;
+
     /// Get the warehouse metadata - should only return active warehouses.
     async fn get_warehouse<'a>(
         warehouse_id: &WarehouseIdent,
@@ -302,4 +1084,392 @@ where
         storage_secret_id: Option<SecretIdent>,
         transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
     ) -> Result<()>;
+
+    /// Get the allowlist of a warehouse.
+    /// Return Ok(None) if the warehouse does not exist or has no allowlist.
+    async fn get_warehouse_allowlist(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<Option<WarehouseAllowlist>>;
+
+    /// Set or remove (`None`) the allowlist of a warehouse.
+    async fn set_warehouse_allowlist<'a>(
+        warehouse_id: &WarehouseIdent,
+        allowlist: Option<WarehouseAllowlist>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Returns `None` if the signer address is not overridden or the warehouse does not exist.
+    async fn get_warehouse_signer_uri(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<Option<String>>;
+
+    /// Set or remove (`None`) the signer address override of a warehouse.
+    async fn set_warehouse_signer_uri<'a>(
+        warehouse_id: &WarehouseIdent,
+        signer_uri: Option<&url::Url>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Set or remove (`None`) the response of a warehouse to requests for
+    /// objects the principal is not allowed to access.
+    async fn set_warehouse_unauthorized_response<'a>(
+        warehouse_id: &WarehouseIdent,
+        unauthorized_response: Option<UnauthorizedResponse>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Replace the storage secret of an active warehouse by `new_secret_id` and keep the
+    /// current secret as the previous secret. Fails with a conflict if the current secret
+    /// is not `current_secret_id`, i.e. if the credential was changed concurrently.
+    async fn rotate_warehouse_storage_secret<'a>(
+        warehouse_id: &WarehouseIdent,
+        current_secret_id: Option<&SecretIdent>,
+        new_secret_id: &SecretIdent,
+        rotated_at: chrono::DateTime<chrono::Utc>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Forget the previous storage secret of a warehouse if it is still `previous_secret_id`.
+    /// Returns whether it was forgotten. The secret itself is not deleted.
+    async fn clear_warehouse_previous_storage_secret(
+        warehouse_id: &WarehouseIdent,
+        previous_secret_id: &SecretIdent,
+        catalog_state: Self::State,
+    ) -> Result<bool>;
+
+    /// Store the result of the last check of the storage of a warehouse.
+    async fn set_warehouse_storage_validation(
+        warehouse_id: &WarehouseIdent,
+        validation: &StorageValidation,
+        catalog_state: Self::State,
+    ) -> Result<()>;
+
+    /// Set or remove (`None`) the access delegation used if a client of a warehouse
+    /// does not request a mode.
+    async fn set_warehouse_access_delegation<'a>(
+        warehouse_id: &WarehouseIdent,
+        access_delegation: Option<AccessDelegation>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Allow or forbid locations of tables outside of the storage profile of a warehouse.
+    async fn set_warehouse_allow_external_locations<'a>(
+        warehouse_id: &WarehouseIdent,
+        allow_external_locations: bool,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Enable or disable the hashed location layout for new tables of a warehouse.
+    async fn set_warehouse_object_storage_layout<'a>(
+        warehouse_id: &WarehouseIdent,
+        object_storage_layout: bool,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Set or remove (`None`) the default snapshot retention policy of a warehouse.
+    async fn set_warehouse_retention_policy<'a>(
+        warehouse_id: &WarehouseIdent,
+        policy: Option<SnapshotRetentionPolicy>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Returns an empty config if the warehouse does not exist.
+    async fn get_engine_table_config(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<EngineTableConfig>;
+
+    /// Replace the engine specific table config of a warehouse.
+    async fn set_engine_table_config<'a>(
+        warehouse_id: &WarehouseIdent,
+        config: EngineTableConfig,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Replace the event filter of a warehouse.
+    async fn set_event_filter<'a>(
+        warehouse_id: &WarehouseIdent,
+        filter: EventFilter,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Get owner and creator of a warehouse, namespace or table.
+    async fn get_ownership<'a>(
+        warehouse_id: &WarehouseIdent,
+        object: &OwnedObject,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<ObjectOwnership>;
+
+    /// Transfer ownership of a warehouse, namespace or table to `new_owner`.
+    async fn set_owner<'a>(
+        warehouse_id: &WarehouseIdent,
+        object: &OwnedObject,
+        new_owner: &str,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Returns `false` if the warehouse does not exist.
+    async fn is_warehouse_read_only(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<bool>;
+
+    async fn set_warehouse_read_only<'a>(
+        warehouse_id: &WarehouseIdent,
+        read_only: bool,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Returns `None` if compatibility mode is disabled or the warehouse does not exist.
+    async fn get_pyiceberg_compat(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<Option<PyicebergCompat>>;
+
+    async fn set_warehouse_pyiceberg_compat<'a>(
+        warehouse_id: &WarehouseIdent,
+        enabled: bool,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Attach a governance policy to a table or remove it (`None`).
+    async fn set_table_policy<'a>(
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        policy: Option<TablePolicy>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Set the snapshot retention policy of a namespace or remove it (`None`).
+    async fn set_namespace_retention_policy<'a>(
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        policy: Option<SnapshotRetentionPolicy>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// List at most `limit` tables of active, writable warehouses that are subject to
+    /// snapshot retention, either by a namespace policy or by table properties.
+    /// Tables are ordered by id, starting after `after`.
+    async fn list_retention_candidates(
+        after: Option<TableIdentUuid>,
+        limit: i64,
+        catalog_state: Self::State,
+    ) -> Result<Vec<RetentionCandidate>>;
+
+    /// Share a namespace or table of `warehouse_id` read-only with another project.
+    async fn create_share<'a>(
+        warehouse_id: &WarehouseIdent,
+        object: &SharedObject,
+        consumer_project_id: &ProjectIdent,
+        mount_name: &str,
+        created_by: Option<&str>,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<uuid::Uuid>;
+
+    /// List all shares of objects in `warehouse_id`.
+    async fn list_shares(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: Self::State,
+    ) -> Result<Vec<Share>>;
+
+    async fn delete_share<'a>(
+        warehouse_id: &WarehouseIdent,
+        share_id: &uuid::Uuid,
+        transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+    ) -> Result<()>;
+
+    /// Find the share mounted as `mount_name` in the project of `warehouse_id`.
+    /// Returns `None` if there is no such share or the owning warehouse is inactive.
+    async fn resolve_share(
+        warehouse_id: &WarehouseIdent,
+        mount_name: &str,
+        catalog_state: Self::State,
+    ) -> Result<Option<ResolvedShare>>;
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        EngineTableConfig, EventFilter, NamespaceIdent, PyicebergCompat, ResolvedShare,
+        SnapshotRetentionPolicy, TableIdent, TablePolicy, WarehouseAllowlist,
+    };
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn test_allowlist() {
+        let allowlist = WarehouseAllowlist {
+            principals: vec!["alice".to_string()],
+            groups: vec!["engineers".to_string()],
+        };
+        assert!(allowlist.allows(Some("alice"), &[]));
+        assert!(allowlist.allows(Some("bob"), &["engineers"]));
+        assert!(allowlist.allows(None, &["engineers"]));
+        assert!(!allowlist.allows(Some("bob"), &["sales"]));
+        assert!(!allowlist.allows(None, &[]));
+        assert!(!WarehouseAllowlist::default().allows(Some("alice"), &["engineers"]));
+    }
+
+    #[test]
+    fn test_table_policy_config() {
+        let policy = TablePolicy {
+            classification: Some("confidential".to_string()),
+            column_sensitivity: std::collections::HashMap::from([(
+                "email".to_string(),
+                "pii".to_string(),
+            )]),
+            retention_class: None,
+        };
+        let config = policy.table_config();
+        assert_eq!(config.len(), 2);
+        assert_eq!(config["policy.classification"], "confidential");
+        assert_eq!(config["policy.column-sensitivity.email"], "pii");
+    }
+
+    #[test]
+    fn test_snapshot_retention() {
+        // (snapshot_id, timestamp_ms)
+        let snapshots = vec![(1, 100), (2, 200), (3, 300), (4, 400)];
+        let referenced = HashSet::from([4, 1]);
+        let policy = SnapshotRetentionPolicy {
+            max_snapshots: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.select_expired(snapshots.clone(), &referenced, 500),
+            vec![2]
+        );
+
+        let mut policy = SnapshotRetentionPolicy {
+            max_snapshot_age_ms: Some(250),
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.select_expired(snapshots.clone(), &referenced, 500),
+            vec![2]
+        );
+        policy.min_snapshots_to_keep = Some(3);
+        assert_eq!(
+            policy.select_expired(snapshots.clone(), &HashSet::new(), 500),
+            vec![1]
+        );
+        policy.min_snapshots_to_keep = None;
+        assert!(SnapshotRetentionPolicy::default()
+            .select_expired(snapshots, &referenced, 500)
+            .is_empty());
+
+        let policy = policy.with_table_properties(&HashMap::from([
+            (
+                SnapshotRetentionPolicy::MAX_SNAPSHOTS_PROPERTY.to_string(),
+                "1".to_string(),
+            ),
+            (
+                SnapshotRetentionPolicy::MAX_SNAPSHOT_AGE_MS_PROPERTY.to_string(),
+                "invalid".to_string(),
+            ),
+        ]));
+        assert_eq!(policy.max_snapshots, Some(1));
+        assert_eq!(policy.max_snapshot_age_ms, Some(250));
+    }
+
+    #[test]
+    fn test_resolved_share_mapping() {
+        let ns = |v: &[&str]| {
+            NamespaceIdent::from_vec(v.iter().map(ToString::to_string).collect()).unwrap()
+        };
+        let share = ResolvedShare {
+            share_id: uuid::Uuid::now_v7(),
+            warehouse_id: uuid::Uuid::now_v7().into(),
+            namespace: ns(&["sales", "gold"]),
+            table_name: None,
+        };
+        assert_eq!(
+            share.source_table(&TableIdent::new(ns(&["mnt", "eu"]), "orders".to_string())),
+            Some(TableIdent::new(
+                ns(&["sales", "gold", "eu"]),
+                "orders".to_string()
+            ))
+        );
+
+        let share = ResolvedShare {
+            table_name: Some("Orders".to_string()),
+            ..share
+        };
+        assert_eq!(
+            share.source_table(&TableIdent::new(ns(&["mnt"]), "orders".to_string())),
+            Some(TableIdent::new(
+                ns(&["sales", "gold"]),
+                "orders".to_string()
+            ))
+        );
+        assert!(share
+            .source_table(&TableIdent::new(ns(&["mnt"]), "customers".to_string()))
+            .is_none());
+        assert!(share.source_namespace(&ns(&["mnt", "eu"])).is_none());
+    }
+
+    #[test]
+    fn test_pyiceberg_compat() {
+        let ns = |v: &[&str]| {
+            NamespaceIdent::from_vec(v.iter().map(ToString::to_string).collect()).unwrap()
+        };
+        let compat = PyicebergCompat {
+            warehouse_name: "lake".to_string(),
+        };
+        assert_eq!(
+            compat.strip_warehouse_name(ns(&["Lake", "sales"])),
+            ns(&["sales"])
+        );
+        // A namespace that only consists of the warehouse name is kept.
+        assert_eq!(compat.strip_warehouse_name(ns(&["lake"])), ns(&["lake"]));
+        assert_eq!(compat.strip_warehouse_name(ns(&["sales"])), ns(&["sales"]));
+        assert_eq!(
+            compat.prepend_warehouse_name(&ns(&["sales"])),
+            ns(&["lake", "sales"])
+        );
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let filter = EventFilter {
+            enabled: true,
+            disabled_event_types: vec!["table.committed".to_string()],
+        };
+        assert!(filter.allows("table.created"));
+        assert!(!filter.allows("table.committed"));
+        assert!(!EventFilter {
+            enabled: false,
+            ..filter
+        }
+        .allows("table.created"));
+
+        let filter: EventFilter = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(filter, EventFilter::default());
+        assert!(filter.allows("table.committed"));
+    }
+
+    #[test]
+    fn test_engine_table_config() {
+        let engine_config = EngineTableConfig(HashMap::from_iter(vec![(
+            "Spark".to_string(),
+            HashMap::from_iter(vec![
+                ("s3.path-style-access".to_string(), "true".to_string()),
+                ("s3.remote-signing-enabled".to_string(), "false".to_string()),
+            ]),
+        )]));
+
+        let mut config = HashMap::from_iter(vec![(
+            "s3.remote-signing-enabled".to_string(),
+            "true".to_string(),
+        )]);
+        engine_config.apply("trino", &mut config);
+        assert_eq!(config.len(), 1);
+
+        engine_config.apply("spark", &mut config);
+        assert_eq!(config["s3.path-style-access"], "true");
+        // Keys generated by the catalog are not overwritten.
+        assert_eq!(config["s3.remote-signing-enabled"], "true");
+    }
 }
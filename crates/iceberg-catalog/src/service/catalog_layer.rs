@@ -0,0 +1,1004 @@
+//! Decorators for [`Catalog`] implementations.
+//!
+//! [`Layered<C, L>`](Layered) is a [`Catalog`] that runs every call to the wrapped catalog `C`
+//! through the [`CatalogLayer`] `L`. Layers receive the typed arguments of each call as a
+//! [`CatalogCall`] and its result as a [`CatalogResponse`]. They can reject a call, answer it
+//! without the wrapped catalog, e.g. from a cache, and observe or replace its result, e.g. to
+//! mirror writes to a shadow catalog during a migration. This composes cross-cutting behavior
+//! without modifying the backend:
+//!
+//! ```rust,ignore
+//! // `State<A, Layered<Layered<PostgresCatalog, MetricsLayer>, MyCache>, S>`
+//! let state = state.layer(MetricsLayer).layer(MyCache::new());
+//! ```
+//!
+//! Services that build the router with [`RouterBuilder`](crate::api::router::RouterBuilder)
+//! pass `LayeredState::new(catalog_state, layer)` as catalog state and `Layered<C, L>` as
+//! catalog and config provider.
+#![allow(clippy::module_name_repetitions)]
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use http::StatusCode;
+use iceberg_ext::catalog::rest::{CatalogConfig, ErrorModel, IcebergErrorResponse};
+
+use super::{
+    auth::UnauthorizedResponse,
+    config::ConfigProvider,
+    metrics::CATALOG_OPERATION_DURATION_SECONDS,
+    page_token::PageQuery,
+    storage::{AccessDelegation, StorageProfile},
+    storage_validation::StorageValidation,
+    Catalog, CommitTableResponseExt, CommitTransactionRequest, CreateNamespaceRequest,
+    CreateNamespaceResponse, CreateTableRequest, CreateTableResponse, DroppedTable,
+    EngineTableConfig, EventFilter, GetNamespaceResponse, GetTableMetadataResponse,
+    GetWarehouseResponse, ListNamespacesQuery, ListNamespacesResponse, LoadTableResponse,
+    LoadedTable, MetadataLogEntry, NamespaceIdent, NamespaceIdentUuid, ObjectOwnership,
+    OwnedObject, Project, ProjectIdent, PyicebergCompat, ResolvedShare, Result, RetentionCandidate,
+    Share, SharedObject, SnapshotRetentionPolicy, StagedTable, TableCommit, TableIdent,
+    TableIdentUuid, TablePolicy, Transaction, UpdateNamespacePropertiesRequest,
+    UpdateNamespacePropertiesResponse, WarehouseAllowlist, WarehouseIdent, WarehouseStatus,
+};
+use crate::SecretIdent;
+
+/// Decorator of a [`Catalog`], see the [module documentation](self).
+#[async_trait]
+pub trait CatalogLayer: Debug + Send + Sync + 'static {
+    fn name(&self) -> &'static str;
+
+    /// Called before the wrapped catalog.
+    ///
+    /// Errors are returned to the caller without calling the catalog. A response answers
+    /// the call instead of the catalog and must be the variant of `call`, in which case
+    /// `after` is not called.
+    async fn before(&self, _call: &CatalogCall<'_>) -> Result<Option<CatalogResponse>> {
+        Ok(None)
+    }
+
+    /// Called after the wrapped catalog returned. The result can be replaced,
+    /// a response must be the variant of `call`.
+    async fn after(
+        &self,
+        _call: &CatalogCall<'_>,
+        _elapsed: Duration,
+        _result: &mut Result<CatalogResponse>,
+    ) {
+    }
+}
+
+/// `true` for calls that modify the catalog.
+macro_rules! is_write {
+    (read) => {
+        false
+    };
+    (write) => {
+        true
+    };
+}
+
+/// Warehouse of a call, bound by the pattern of its variant.
+macro_rules! call_warehouse_id {
+    () => {
+        None
+    };
+    ($warehouse_id:ident) => {
+        Some(*$warehouse_id)
+    };
+}
+
+/// Field of a [`CatalogCall`] for an argument.
+macro_rules! call_field {
+    (copy $arg:ident) => {
+        $arg
+    };
+    (clone $arg:ident) => {
+        &$arg
+    };
+}
+
+/// Argument passed on to the wrapped catalog.
+macro_rules! call_arg {
+    (copy $arg:ident) => {
+        $arg
+    };
+    (clone $arg:ident) => {
+        $arg.clone()
+    };
+}
+
+/// Defines [`CatalogCall`], [`CatalogResponse`] and the [`Catalog`] implementation of
+/// [`Layered`] with one entry per method of the [`Catalog`] trait, grouped by whether
+/// the method takes the catalog state or a transaction.
+///
+/// Arguments that are `Copy`, such as references, are listed as `copy name: Type as Field`.
+/// Other arguments are listed as `clone name: Type as Field`: the call borrows them and the
+/// wrapped catalog receives a clone.
+macro_rules! catalog_calls {
+    (
+        state {
+            $(
+                $s_kind:ident $s_name:ident => $s_variant:ident [$($s_warehouse_id:ident)?] (
+                    $($s_mode:ident $s_arg:ident: $s_ty:ty as $s_field:ty),* $(,)?
+                ) -> $s_out:ty;
+            )*
+        }
+        transaction {
+            $(
+                $t_kind:ident $t_name:ident => $t_variant:ident [$($t_warehouse_id:ident)?] (
+                    $($t_mode:ident $t_arg:ident: $t_ty:ty as $t_field:ty),* $(,)?
+                ) -> $t_out:ty;
+            )*
+        }
+    ) => {
+        /// A call to a [`Catalog`] method with its arguments, except for the catalog state
+        /// or transaction.
+        #[derive(Debug, Clone, Copy)]
+        #[non_exhaustive]
+        pub enum CatalogCall<'c> {
+            $(
+                #[doc = concat!("[`Catalog::", stringify!($s_name), "`]")]
+                $s_variant { $($s_arg: $s_field),* },
+            )*
+            $(
+                #[doc = concat!("[`Catalog::", stringify!($t_name), "`]")]
+                $t_variant { $($t_arg: $t_field),* },
+            )*
+        }
+
+        /// Result of a [`CatalogCall`], in the variant of the same name.
+        #[derive(Debug)]
+        #[non_exhaustive]
+        #[allow(clippy::large_enum_variant)]
+        pub enum CatalogResponse {
+            $(
+                #[doc = concat!("[`Catalog::", stringify!($s_name), "`]")]
+                $s_variant($s_out),
+            )*
+            $(
+                #[doc = concat!("[`Catalog::", stringify!($t_name), "`]")]
+                $t_variant($t_out),
+            )*
+        }
+
+        #[allow(clippy::match_same_arms)]
+        impl CatalogCall<'_> {
+            /// Name of the [`Catalog`] method, i.e. `load_table`.
+            #[must_use]
+            pub fn operation(&self) -> &'static str {
+                match self {
+                    $(Self::$s_variant { .. } => stringify!($s_name),)*
+                    $(Self::$t_variant { .. } => stringify!($t_name),)*
+                }
+            }
+
+            /// Whether the call modifies the catalog.
+            #[must_use]
+            pub fn is_write(&self) -> bool {
+                match self {
+                    $(Self::$s_variant { .. } => is_write!($s_kind),)*
+                    $(Self::$t_variant { .. } => is_write!($t_kind),)*
+                }
+            }
+
+            /// `None` for calls not bound to a warehouse.
+            #[must_use]
+            pub fn warehouse_id(&self) -> Option<&WarehouseIdent> {
+                match self {
+                    $(
+                        Self::$s_variant { $($s_warehouse_id,)? .. } => {
+                            call_warehouse_id!($($s_warehouse_id)?)
+                        }
+                    )*
+                    $(
+                        Self::$t_variant { $($t_warehouse_id,)? .. } => {
+                            call_warehouse_id!($($t_warehouse_id)?)
+                        }
+                    )*
+                }
+            }
+        }
+
+        #[async_trait]
+        impl<C: Catalog, L: CatalogLayer> Catalog for Layered<C, L> {
+            type Transaction = LayeredTransaction<C, L>;
+            type State = LayeredState<C::State, L>;
+
+            fn primary_state(catalog_state: Self::State) -> Self::State {
+                let LayeredState { inner, layer } = catalog_state;
+                LayeredState {
+                    inner: C::primary_state(inner),
+                    layer,
+                }
+            }
+
+            $(
+                async fn $s_name(
+                    $($s_arg: $s_ty,)*
+                    catalog_state: Self::State,
+                ) -> Result<$s_out> {
+                    let LayeredState { inner, layer } = catalog_state;
+                    let call = CatalogCall::$s_variant { $($s_arg: call_field!($s_mode $s_arg)),* };
+                    call_layer(
+                        &*layer,
+                        &call,
+                        C::$s_name($(call_arg!($s_mode $s_arg),)* inner),
+                        CatalogResponse::$s_variant,
+                        |response| match response {
+                            CatalogResponse::$s_variant(response) => Some(response),
+                            _ => None,
+                        },
+                    )
+                    .await
+                }
+            )*
+
+            $(
+                async fn $t_name<'a>(
+                    $($t_arg: $t_ty,)*
+                    transaction: <Self::Transaction as Transaction<Self::State>>::Transaction<'a>,
+                ) -> Result<$t_out> {
+                    let LayeredTransactionRef { inner, layer } = transaction;
+                    let call = CatalogCall::$t_variant { $($t_arg: call_field!($t_mode $t_arg)),* };
+                    call_layer(
+                        layer,
+                        &call,
+                        C::$t_name($(call_arg!($t_mode $t_arg),)* inner),
+                        CatalogResponse::$t_variant,
+                        |response| match response {
+                            CatalogResponse::$t_variant(response) => Some(response),
+                            _ => None,
+                        },
+                    )
+                    .await
+                }
+            )*
+        }
+    };
+}
+
+catalog_calls! {
+    state {
+        read list_namespaces => ListNamespaces [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy query: &ListNamespacesQuery as &'c ListNamespacesQuery,
+        ) -> ListNamespacesResponse;
+        read namespace_ident_to_id => NamespaceIdentToId [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy namespace: &NamespaceIdent as &'c NamespaceIdent,
+        ) -> Option<NamespaceIdentUuid>;
+        read list_tables => ListTables [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy namespace: &NamespaceIdent as &'c NamespaceIdent,
+            copy include_staged: bool as bool,
+            copy page: &PageQuery as &'c PageQuery,
+        ) -> Vec<(TableIdentUuid, TableIdent)>;
+        read table_ident_to_id => TableIdentToId [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy table: &TableIdent as &'c TableIdent,
+            copy include_staged: bool as bool,
+        ) -> Option<TableIdentUuid>;
+        read table_exists => TableExists [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy table: &TableIdent as &'c TableIdent,
+            copy include_staged: bool as bool,
+        ) -> bool;
+        read table_idents_to_ids => TableIdentsToIds [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            clone tables: HashSet<&TableIdent> as &'c HashSet<&'c TableIdent>,
+            copy include_staged: bool as bool,
+        ) -> HashMap<TableIdent, Option<TableIdentUuid>>;
+        read load_table => LoadTable [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy table: &TableIdent as &'c TableIdent,
+        ) -> LoadTableResponse;
+        read get_table_metadata_by_id => GetTableMetadataById [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy table: &TableIdentUuid as &'c TableIdentUuid,
+            copy include_staged: bool as bool,
+        ) -> GetTableMetadataResponse;
+        read get_table_metadata_by_s3_location => GetTableMetadataByS3Location [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy location: &str as &'c str,
+            copy include_staged: bool as bool,
+        ) -> GetTableMetadataResponse;
+        read get_table_metadata_log => GetTableMetadataLog [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy table: &TableIdentUuid as &'c TableIdentUuid,
+        ) -> Vec<MetadataLogEntry>;
+        read list_table_commits => ListTableCommits [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy table: &TableIdentUuid as &'c TableIdentUuid,
+            copy before: Option<chrono::DateTime<chrono::Utc>> as Option<chrono::DateTime<chrono::Utc>>,
+            copy limit: i64 as i64,
+        ) -> Vec<TableCommit>;
+        read load_tables => LoadTables [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            clone tables: HashSet<&TableIdent> as &'c HashSet<&'c TableIdent>,
+        ) -> Vec<LoadedTable>;
+        read list_staged_tables => ListStagedTables [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+        ) -> Vec<StagedTable>;
+        read list_expired_dropped_tables => ListExpiredDroppedTables [] (
+            copy dropped_before: chrono::DateTime<chrono::Utc> as chrono::DateTime<chrono::Utc>,
+            copy limit: i64 as i64,
+        ) -> Vec<DroppedTable>;
+        read list_projects => ListProjects [] () -> HashSet<ProjectIdent>;
+        read get_projects => GetProjects [] (
+            copy project_ids: Option<&HashSet<ProjectIdent>> as Option<&'c HashSet<ProjectIdent>>,
+        ) -> Vec<Project>;
+        read get_project_id_by_name => GetProjectIdByName [] (
+            copy project_name: &str as &'c str,
+        ) -> Option<ProjectIdent>;
+        read list_warehouses => ListWarehouses [] (
+            copy project_id: &ProjectIdent as &'c ProjectIdent,
+            clone include_inactive: Option<Vec<WarehouseStatus>> as &'c Option<Vec<WarehouseStatus>>,
+            copy warehouse_id_filter: Option<&HashSet<WarehouseIdent>> as Option<&'c HashSet<WarehouseIdent>>,
+        ) -> Vec<GetWarehouseResponse>;
+        read get_warehouse_id_by_name => GetWarehouseIdByName [] (
+            copy project_id: &ProjectIdent as &'c ProjectIdent,
+            copy warehouse_name: &str as &'c str,
+        ) -> Option<WarehouseIdent>;
+        read get_warehouse_allowlist => GetWarehouseAllowlist [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+        ) -> Option<WarehouseAllowlist>;
+        read get_warehouse_signer_uri => GetWarehouseSignerUri [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+        ) -> Option<String>;
+        write clear_warehouse_previous_storage_secret => ClearWarehousePreviousStorageSecret [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy previous_secret_id: &SecretIdent as &'c SecretIdent,
+        ) -> bool;
+        write set_warehouse_storage_validation => SetWarehouseStorageValidation [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy validation: &StorageValidation as &'c StorageValidation,
+        ) -> ();
+        read get_engine_table_config => GetEngineTableConfig [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+        ) -> EngineTableConfig;
+        read is_warehouse_read_only => IsWarehouseReadOnly [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+        ) -> bool;
+        read get_pyiceberg_compat => GetPyicebergCompat [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+        ) -> Option<PyicebergCompat>;
+        read list_retention_candidates => ListRetentionCandidates [] (
+            copy after: Option<TableIdentUuid> as Option<TableIdentUuid>,
+            copy limit: i64 as i64,
+        ) -> Vec<RetentionCandidate>;
+        read list_shares => ListShares [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+        ) -> Vec<Share>;
+        read resolve_share => ResolveShare [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy mount_name: &str as &'c str,
+        ) -> Option<ResolvedShare>;
+    }
+    transaction {
+        write create_namespace => CreateNamespace [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            clone request: CreateNamespaceRequest as &'c CreateNamespaceRequest,
+            copy owner: Option<&str> as Option<&'c str>,
+        ) -> CreateNamespaceResponse;
+        read get_namespace => GetNamespace [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy namespace: &NamespaceIdent as &'c NamespaceIdent,
+        ) -> GetNamespaceResponse;
+        write drop_namespace => DropNamespace [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy namespace: &NamespaceIdent as &'c NamespaceIdent,
+        ) -> ();
+        write update_namespace_properties => UpdateNamespaceProperties [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy namespace: &NamespaceIdent as &'c NamespaceIdent,
+            clone request: UpdateNamespacePropertiesRequest as &'c UpdateNamespacePropertiesRequest,
+        ) -> UpdateNamespacePropertiesResponse;
+        write create_table => CreateTable [] (
+            copy namespace_id: &NamespaceIdentUuid as &'c NamespaceIdentUuid,
+            copy table: &TableIdent as &'c TableIdent,
+            copy table_id: &TableIdentUuid as &'c TableIdentUuid,
+            clone request: CreateTableRequest as &'c CreateTableRequest,
+            copy metadata_location: Option<&String> as Option<&'c String>,
+            copy owner: Option<&str> as Option<&'c str>,
+        ) -> CreateTableResponse;
+        write discard_staged_table => DiscardStagedTable [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy table_id: &TableIdentUuid as &'c TableIdentUuid,
+        ) -> ();
+        write rename_table => RenameTable [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy source_id: &TableIdentUuid as &'c TableIdentUuid,
+            copy source: &TableIdent as &'c TableIdent,
+            copy destination: &TableIdent as &'c TableIdent,
+        ) -> ();
+        write drop_table => DropTable [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy table_id: &TableIdentUuid as &'c TableIdentUuid,
+        ) -> ();
+        write reclaim_dropped_table => ReclaimDroppedTable [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy table_id: &TableIdentUuid as &'c TableIdentUuid,
+            copy storage_purged: bool as bool,
+        ) -> ();
+        write commit_table_transaction => CommitTableTransaction [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            clone request: CommitTransactionRequest as &'c CommitTransactionRequest,
+            copy table_ids: &HashMap<TableIdent, TableIdentUuid> as &'c HashMap<TableIdent, TableIdentUuid>,
+        ) -> Vec<CommitTableResponseExt>;
+        write record_table_commits => RecordTableCommits [] (
+            copy commits: &[TableCommit] as &'c [TableCommit],
+        ) -> ();
+        write create_warehouse => CreateWarehouse [] (
+            clone warehouse_name: String as &'c String,
+            clone project_id: ProjectIdent as &'c ProjectIdent,
+            clone storage_profile: StorageProfile as &'c StorageProfile,
+            clone storage_secret_id: Option<SecretIdent> as &'c Option<SecretIdent>,
+            copy owner: Option<&str> as Option<&'c str>,
+        ) -> WarehouseIdent;
+        write rename_project => RenameProject [] (
+            copy project_id: &ProjectIdent as &'c ProjectIdent,
+            copy new_name: &str as &'c str,
+        ) -> ();
+        write set_project_metadata => SetProjectMetadata [] (
+            copy project_id: &ProjectIdent as &'c ProjectIdent,
+            copy metadata: &HashMap<String, String> as &'c HashMap<String, String>,
+        ) -> ();
+        write delete_project => DeleteProject [] (
+            copy project_id: &ProjectIdent as &'c ProjectIdent,
+        ) -> ();
+        read get_warehouse => GetWarehouse [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+        ) -> GetWarehouseResponse;
+        write delete_warehouse => DeleteWarehouse [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+        ) -> ();
+        write rename_warehouse => RenameWarehouse [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy new_name: &str as &'c str,
+        ) -> ();
+        write set_warehouse_status => SetWarehouseStatus [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy status: WarehouseStatus as WarehouseStatus,
+        ) -> ();
+        write update_storage_profile => UpdateStorageProfile [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            clone storage_profile: StorageProfile as &'c StorageProfile,
+            clone storage_secret_id: Option<SecretIdent> as &'c Option<SecretIdent>,
+        ) -> ();
+        write set_warehouse_allowlist => SetWarehouseAllowlist [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            clone allowlist: Option<WarehouseAllowlist> as &'c Option<WarehouseAllowlist>,
+        ) -> ();
+        write set_warehouse_signer_uri => SetWarehouseSignerUri [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy signer_uri: Option<&url::Url> as Option<&'c url::Url>,
+        ) -> ();
+        write set_warehouse_unauthorized_response => SetWarehouseUnauthorizedResponse [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy unauthorized_response: Option<UnauthorizedResponse> as Option<UnauthorizedResponse>,
+        ) -> ();
+        write rotate_warehouse_storage_secret => RotateWarehouseStorageSecret [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy current_secret_id: Option<&SecretIdent> as Option<&'c SecretIdent>,
+            copy new_secret_id: &SecretIdent as &'c SecretIdent,
+            copy rotated_at: chrono::DateTime<chrono::Utc> as chrono::DateTime<chrono::Utc>,
+        ) -> ();
+        write set_warehouse_access_delegation => SetWarehouseAccessDelegation [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy access_delegation: Option<AccessDelegation> as Option<AccessDelegation>,
+        ) -> ();
+        write set_warehouse_allow_external_locations => SetWarehouseAllowExternalLocations [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy allow_external_locations: bool as bool,
+        ) -> ();
+        write set_warehouse_object_storage_layout => SetWarehouseObjectStorageLayout [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy object_storage_layout: bool as bool,
+        ) -> ();
+        write set_warehouse_retention_policy => SetWarehouseRetentionPolicy [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            clone policy: Option<SnapshotRetentionPolicy> as &'c Option<SnapshotRetentionPolicy>,
+        ) -> ();
+        write set_engine_table_config => SetEngineTableConfig [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            clone config: EngineTableConfig as &'c EngineTableConfig,
+        ) -> ();
+        write set_event_filter => SetEventFilter [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            clone filter: EventFilter as &'c EventFilter,
+        ) -> ();
+        read get_ownership => GetOwnership [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy object: &OwnedObject as &'c OwnedObject,
+        ) -> ObjectOwnership;
+        write set_owner => SetOwner [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy object: &OwnedObject as &'c OwnedObject,
+            copy new_owner: &str as &'c str,
+        ) -> ();
+        write set_warehouse_read_only => SetWarehouseReadOnly [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy read_only: bool as bool,
+        ) -> ();
+        write set_warehouse_pyiceberg_compat => SetWarehousePyicebergCompat [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy enabled: bool as bool,
+        ) -> ();
+        write set_table_policy => SetTablePolicy [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy table: &TableIdent as &'c TableIdent,
+            clone policy: Option<TablePolicy> as &'c Option<TablePolicy>,
+        ) -> ();
+        write set_namespace_retention_policy => SetNamespaceRetentionPolicy [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy namespace: &NamespaceIdent as &'c NamespaceIdent,
+            clone policy: Option<SnapshotRetentionPolicy> as &'c Option<SnapshotRetentionPolicy>,
+        ) -> ();
+        write create_share => CreateShare [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy object: &SharedObject as &'c SharedObject,
+            copy consumer_project_id: &ProjectIdent as &'c ProjectIdent,
+            copy mount_name: &str as &'c str,
+            copy created_by: Option<&str> as Option<&'c str>,
+        ) -> uuid::Uuid;
+        write delete_share => DeleteShare [warehouse_id] (
+            copy warehouse_id: &WarehouseIdent as &'c WarehouseIdent,
+            copy share_id: &uuid::Uuid as &'c uuid::Uuid,
+        ) -> ();
+    }
+}
+
+/// Run `operation` of the wrapped catalog through `layer`.
+async fn call_layer<L: CatalogLayer + ?Sized, T: Send>(
+    layer: &L,
+    call: &CatalogCall<'_>,
+    operation: impl Future<Output = Result<T>> + Send,
+    into_response: fn(T) -> CatalogResponse,
+    from_response: fn(CatalogResponse) -> Option<T>,
+) -> Result<T> {
+    if let Some(response) = layer.before(call).await? {
+        return from_response(response).ok_or_else(|| mismatched_response(layer.name(), call));
+    }
+
+    let start = Instant::now();
+    let mut result = operation.await.map(into_response);
+    layer.after(call, start.elapsed(), &mut result).await;
+    result.and_then(|response| {
+        from_response(response).ok_or_else(|| mismatched_response(layer.name(), call))
+    })
+}
+
+fn mismatched_response(layer: &str, call: &CatalogCall<'_>) -> IcebergErrorResponse {
+    ErrorModel::builder()
+        .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+        .message(format!(
+            "Catalog layer {layer} returned a response of another operation than {}",
+            call.operation()
+        ))
+        .r#type("CatalogLayerError".to_string())
+        .build()
+        .into()
+}
+
+/// Records the duration of every catalog call in [`CATALOG_OPERATION_DURATION_SECONDS`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetricsLayer;
+
+#[async_trait]
+impl CatalogLayer for MetricsLayer {
+    fn name(&self) -> &'static str {
+        "MetricsLayer"
+    }
+
+    async fn after(
+        &self,
+        call: &CatalogCall<'_>,
+        elapsed: Duration,
+        result: &mut Result<CatalogResponse>,
+    ) {
+        metrics::histogram!(
+            CATALOG_OPERATION_DURATION_SECONDS,
+            "operation" => call.operation(),
+            "status" => if result.is_err() { "error" } else { "ok" },
+        )
+        .record(elapsed.as_secs_f64());
+    }
+}
+
+/// Catalog `C` decorated with the layer `L`. Nest to apply multiple layers,
+/// the outermost layer sees each call first.
+pub struct Layered<C, L>(PhantomData<fn() -> (C, L)>);
+
+impl<C, L> Clone for Layered<C, L> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C, L> Copy for Layered<C, L> {}
+
+impl<C, L> Default for Layered<C, L> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<C, L> Debug for Layered<C, L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Layered<{}, {}>",
+            std::any::type_name::<C>(),
+            std::any::type_name::<L>()
+        )
+    }
+}
+
+/// The configuration is not part of the [`Catalog`] and is served by the wrapped catalog.
+#[async_trait]
+impl<CP, C, L> ConfigProvider<Layered<C, L>> for Layered<CP, L>
+where
+    CP: ConfigProvider<C>,
+    C: Catalog,
+    L: CatalogLayer,
+{
+    async fn get_warehouse_by_name(
+        warehouse_name: &str,
+        project_id: &ProjectIdent,
+        catalog_state: LayeredState<C::State, L>,
+    ) -> Result<WarehouseIdent> {
+        CP::get_warehouse_by_name(warehouse_name, project_id, catalog_state.inner).await
+    }
+
+    async fn get_config_for_warehouse(
+        warehouse_id: &WarehouseIdent,
+        catalog_state: LayeredState<C::State, L>,
+    ) -> Result<CatalogConfig> {
+        CP::get_config_for_warehouse(warehouse_id, catalog_state.inner).await
+    }
+}
+
+/// State of a [`Layered`] catalog: the state of the wrapped catalog and the layer.
+#[derive(Debug)]
+pub struct LayeredState<S, L> {
+    inner: S,
+    layer: Arc<L>,
+}
+
+impl<S: Clone, L> Clone for LayeredState<S, L> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            layer: self.layer.clone(),
+        }
+    }
+}
+
+impl<S, L> LayeredState<S, L> {
+    #[must_use]
+    pub fn new(inner: S, layer: L) -> Self {
+        Self {
+            inner,
+            layer: Arc::new(layer),
+        }
+    }
+
+    #[must_use]
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    #[must_use]
+    pub fn layer(&self) -> &L {
+        &self.layer
+    }
+}
+
+pub struct LayeredTransaction<C: Catalog, L> {
+    inner: C::Transaction,
+    layer: Arc<L>,
+}
+
+impl<C: Catalog, L: Debug> Debug for LayeredTransaction<C, L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LayeredTransaction")
+            .field("layer", &self.layer)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Transaction passed to the methods of a [`Layered`] catalog.
+pub struct LayeredTransactionRef<'a, C: Catalog, L>
+where
+    C::Transaction: 'a,
+{
+    inner: <C::Transaction as Transaction<C::State>>::Transaction<'a>,
+    layer: &'a L,
+}
+
+impl<C: Catalog, L: Debug> Debug for LayeredTransactionRef<'_, C, L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LayeredTransactionRef")
+            .field("layer", &self.layer)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<C: Catalog, L: CatalogLayer> Transaction<LayeredState<C::State, L>>
+    for LayeredTransaction<C, L>
+{
+    type Transaction<'a>
+        = LayeredTransactionRef<'a, C, L>
+    where
+        Self: 'a;
+
+    async fn begin_write(db_state: LayeredState<C::State, L>) -> Result<Self> {
+        let LayeredState { inner, layer } = db_state;
+        Ok(Self {
+            inner: <C::Transaction as Transaction<C::State>>::begin_write(inner).await?,
+            layer,
+        })
+    }
+
+    async fn begin_read(db_state: LayeredState<C::State, L>) -> Result<Self> {
+        let LayeredState { inner, layer } = db_state;
+        Ok(Self {
+            inner: <C::Transaction as Transaction<C::State>>::begin_read(inner).await?,
+            layer,
+        })
+    }
+
+    async fn commit(self) -> Result<()> {
+        self.inner.commit().await
+    }
+
+    async fn rollback(self) -> Result<()> {
+        self.inner.rollback().await
+    }
+
+    fn transaction(&mut self) -> Self::Transaction<'_> {
+        LayeredTransactionRef {
+            inner: self.inner.transaction(),
+            layer: &self.layer,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::api::iceberg::types::PageToken;
+    use crate::api::iceberg::v1::namespace::Service as _;
+    use crate::api::ApiContext;
+    use crate::catalog::CatalogServer;
+    use crate::implementations::memory::{MemoryCatalog, MemoryCatalogState, MemoryTransaction};
+    use crate::service::storage::FileProfile;
+    use crate::service::stores::Stores;
+    use crate::test_util::{
+        api_context, AllowAllAuthState, AllowAllAuthZHandler, MemorySecretStore,
+        MemorySecretStoreState, RecordingEventBackend, RequestMetadata,
+    };
+    use std::sync::Mutex;
+
+    /// Records the calls it sees.
+    #[derive(Debug, Default)]
+    struct Record(Mutex<Vec<(String, Option<WarehouseIdent>, bool)>>);
+
+    #[async_trait]
+    impl CatalogLayer for Record {
+        fn name(&self) -> &'static str {
+            "Record"
+        }
+
+        async fn after(
+            &self,
+            call: &CatalogCall<'_>,
+            _: Duration,
+            result: &mut Result<CatalogResponse>,
+        ) {
+            self.0.lock().unwrap().push((
+                format!(
+                    "{} {}",
+                    if call.is_write() { "write" } else { "read" },
+                    call.operation()
+                ),
+                call.warehouse_id().cloned(),
+                result.is_ok(),
+            ));
+        }
+    }
+
+    /// Hides namespaces starting with `_` from listings.
+    #[derive(Debug)]
+    struct HideInternal;
+
+    #[async_trait]
+    impl CatalogLayer for HideInternal {
+        fn name(&self) -> &'static str {
+            "HideInternal"
+        }
+
+        async fn after(
+            &self,
+            _: &CatalogCall<'_>,
+            _: Duration,
+            result: &mut Result<CatalogResponse>,
+        ) {
+            if let Ok(CatalogResponse::ListNamespaces(response)) = result {
+                response
+                    .namespaces
+                    .retain(|namespace| !namespace.as_ref()[0].starts_with('_'));
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    struct ReadOnly;
+
+    #[async_trait]
+    impl CatalogLayer for ReadOnly {
+        fn name(&self) -> &'static str {
+            "ReadOnly"
+        }
+
+        async fn before(&self, call: &CatalogCall<'_>) -> Result<Option<CatalogResponse>> {
+            if !call.is_write() {
+                return Ok(None);
+            }
+            Err(ErrorModel::builder()
+                .code(StatusCode::SERVICE_UNAVAILABLE.into())
+                .message("Catalog is being migrated".to_string())
+                .r#type("CatalogReadOnly".to_string())
+                .build()
+                .into())
+        }
+    }
+
+    /// Answers whether a warehouse is read-only without the catalog,
+    /// other reads with the response of another operation.
+    #[derive(Debug)]
+    struct Answer;
+
+    #[async_trait]
+    impl CatalogLayer for Answer {
+        fn name(&self) -> &'static str {
+            "Answer"
+        }
+
+        async fn before(&self, call: &CatalogCall<'_>) -> Result<Option<CatalogResponse>> {
+            Ok(match call {
+                CatalogCall::IsWarehouseReadOnly { .. } => {
+                    Some(CatalogResponse::IsWarehouseReadOnly(true))
+                }
+                _ if !call.is_write() => Some(CatalogResponse::ListProjects(HashSet::new())),
+                _ => None,
+            })
+        }
+    }
+
+    type Server = CatalogServer<
+        Layered<Layered<MemoryCatalog, Record>, HideInternal>,
+        AllowAllAuthZHandler,
+        MemorySecretStore,
+    >;
+
+    async fn create_warehouse(catalog_state: MemoryCatalogState) -> WarehouseIdent {
+        let mut transaction = MemoryTransaction::begin_write(catalog_state).await.unwrap();
+        let warehouse_id = MemoryCatalog::create_warehouse(
+            "test_warehouse".to_string(),
+            ProjectIdent::from(uuid::Uuid::nil()),
+            StorageProfile::File(FileProfile {
+                path: "/tmp/warehouse".to_string(),
+            }),
+            None,
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+        warehouse_id
+    }
+
+    #[tokio::test]
+    async fn test_layers_observe_and_change_results() {
+        let catalog_state = MemoryCatalogState::default();
+        let warehouse_id = create_warehouse(catalog_state.clone()).await;
+        let (_backend, publisher) = RecordingEventBackend::publisher();
+        let context = api_context::<AllowAllAuthZHandler, MemoryCatalog, MemorySecretStore>(
+            AllowAllAuthState,
+            catalog_state.clone(),
+            MemorySecretStoreState::default(),
+            Stores::new(catalog_state),
+            publisher,
+        );
+        let context = ApiContext {
+            v1_state: context
+                .v1_state
+                .layer(Record::default())
+                .layer(HideInternal),
+        };
+
+        let prefix = Some(warehouse_id.to_string().into());
+        for namespace in ["_staging", "sales"] {
+            Server::create_namespace(
+                prefix.clone(),
+                CreateNamespaceRequest {
+                    namespace: NamespaceIdent::new(namespace.to_string()),
+                    properties: None,
+                },
+                context.clone(),
+                RequestMetadata::new_random(),
+            )
+            .await
+            .unwrap();
+        }
+        let response = Server::list_namespaces(
+            prefix,
+            ListNamespacesQuery {
+                page_token: PageToken::NotSpecified,
+                page_size: None,
+                parent: None,
+            },
+            context.clone(),
+            RequestMetadata::new_random(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            response.namespaces,
+            vec![NamespaceIdent::new("sales".to_string())]
+        );
+
+        let calls = context.v1_state.catalog.inner().layer().0.lock().unwrap();
+        for call in ["write create_namespace", "read list_namespaces"] {
+            assert!(
+                calls.contains(&(call.to_string(), Some(warehouse_id.clone()), true)),
+                "{call} not in {calls:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_layers_reject_and_answer_calls() {
+        let catalog_state = MemoryCatalogState::default();
+        let warehouse_id = create_warehouse(catalog_state.clone()).await;
+
+        let state = LayeredState::new(catalog_state.clone(), ReadOnly);
+        assert!(!Layered::<MemoryCatalog, ReadOnly>::is_warehouse_read_only(
+            &warehouse_id,
+            state.clone()
+        )
+        .await
+        .unwrap());
+        let mut transaction = LayeredTransaction::<MemoryCatalog, ReadOnly>::begin_write(state)
+            .await
+            .unwrap();
+        let err = Layered::<MemoryCatalog, ReadOnly>::rename_warehouse(
+            &warehouse_id,
+            "renamed",
+            transaction.transaction(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::SERVICE_UNAVAILABLE);
+
+        let state = LayeredState::new(catalog_state, Answer);
+        assert!(Layered::<MemoryCatalog, Answer>::is_warehouse_read_only(
+            &warehouse_id,
+            state.clone()
+        )
+        .await
+        .unwrap());
+        let err = Layered::<MemoryCatalog, Answer>::get_warehouse_signer_uri(&warehouse_id, state)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(err.error.r#type, "CatalogLayerError");
+    }
+}
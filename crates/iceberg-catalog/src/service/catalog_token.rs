@@ -0,0 +1,573 @@
+//! Short-lived access tokens issued by the `/v1/oauth/tokens` endpoint of the catalog.
+//!
+//! Engines that only speak the OAuth flow of the Iceberg REST spec exchange the id and
+//! secret of a service key for a catalog token with the `client_credentials` grant.
+//! Catalog tokens are HMAC-signed with `CONFIG.oauth_token_secret`, so the auth middleware
+//! can introspect them without an identity provider. They decode to regular [`Claims`]
+//! and are subject to token revocation like tokens of the identity provider.
+//!
+//! With the token exchange grant, clients trade a token for a catalog token downscoped to a
+//! warehouse or table by requesting the scopes `warehouse:<warehouse-id>` and
+//! `table:<table-id>`. See [`TokenScope`] for where these tokens are accepted.
+use std::collections::HashMap;
+
+use http::StatusCode;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+use super::token_verification::{Claims, Permission};
+use super::{TableIdentUuid, WarehouseIdent};
+use crate::api::{ErrorModel, Result};
+use crate::CONFIG;
+
+/// Key id in the header of catalog tokens. Used to tell them apart from
+/// tokens of the identity provider.
+pub const CATALOG_TOKEN_KEY_ID: &str = "iceberg-catalog-token";
+/// Issuer and audience of catalog tokens.
+const CATALOG_TOKEN_ISSUER: &str = "iceberg-catalog";
+pub const WAREHOUSE_SCOPE_PREFIX: &str = "warehouse:";
+pub const TABLE_SCOPE_PREFIX: &str = "table:";
+/// Scope requested by Iceberg clients by default. It does not grant any permission.
+pub const CATALOG_SCOPE: &str = "catalog";
+const WAREHOUSE_ID_CLAIM: &str = "warehouse_id";
+const TABLE_ID_CLAIM: &str = "table_id";
+/// Claims of the subject token that are not carried over by token exchange.
+const REPLACED_CLAIMS: [&str; 7] = [
+    "client_id",
+    "scope",
+    "scp",
+    WAREHOUSE_ID_CLAIM,
+    TABLE_ID_CLAIM,
+    "nbf",
+    "azp",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CatalogTokenClaims {
+    pub iss: String,
+    pub aud: String,
+    /// Principal the token was issued to.
+    pub sub: String,
+    /// Id of the client that requested the token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// Space separated scopes requested by the client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warehouse_id: Option<uuid::Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table_id: Option<uuid::Uuid>,
+    pub jti: String,
+    pub iat: usize,
+    pub exp: usize,
+    /// Claims of the subject token carried over by token exchange, such as groups and projects.
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Warehouse and table a catalog token was downscoped to by token exchange.
+///
+/// Tokens scoped to a warehouse are only accepted by the catalog API of that warehouse.
+/// Tokens scoped to a table are only accepted by `loadTable`, `loadCredentials`,
+/// `commitTable`, `presign` and the S3 signer of that table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenScope {
+    pub warehouse_id: Option<WarehouseIdent>,
+    pub table_id: Option<TableIdentUuid>,
+}
+
+impl TokenScope {
+    /// Scope of a token, taken from its `warehouse_id` and `table_id` claims.
+    #[must_use]
+    pub fn from_claims(claims: &Claims) -> Self {
+        let id = |claim: &str| {
+            claims
+                .other
+                .get(claim)
+                .and_then(serde_json::Value::as_str)
+                .and_then(|id| uuid::Uuid::parse_str(id).ok())
+        };
+        Self {
+            warehouse_id: id(WAREHOUSE_ID_CLAIM).map(WarehouseIdent::from),
+            table_id: id(TABLE_ID_CLAIM).map(TableIdentUuid::from),
+        }
+    }
+
+    #[must_use]
+    pub fn is_unrestricted(&self) -> bool {
+        self.warehouse_id.is_none() && self.table_id.is_none()
+    }
+
+    #[must_use]
+    pub fn allows_warehouse(&self, warehouse_id: &WarehouseIdent) -> bool {
+        self.warehouse_id.is_none() || self.warehouse_id.as_ref() == Some(warehouse_id)
+    }
+
+    #[must_use]
+    pub fn allows_table(&self, table_id: Option<&TableIdentUuid>) -> bool {
+        self.table_id.is_none() || self.table_id.as_ref() == table_id
+    }
+
+    /// Narrow the scope to the `warehouse:<id>` and `table:<id>` entries of `scopes`.
+    /// Returns the narrowed scope and the remaining scopes.
+    ///
+    /// # Errors
+    /// Fails if an entry is malformed, conflicts with another one or would widen the scope.
+    pub fn narrow<'a>(
+        &self,
+        scopes: impl IntoIterator<Item = &'a str>,
+    ) -> std::result::Result<(Self, Vec<&'a str>), String> {
+        let mut narrowed = self.clone();
+        let mut remaining = vec![];
+        let mut requested_warehouse = None;
+        let mut requested_table = None;
+        for scope in scopes {
+            if let Some(id) = scope.strip_prefix(WAREHOUSE_SCOPE_PREFIX) {
+                let id = parse_scope_id(scope, id)?;
+                if requested_warehouse
+                    .replace(id)
+                    .is_some_and(|previous| previous != id)
+                    || self
+                        .warehouse_id
+                        .as_ref()
+                        .is_some_and(|w| w.into_uuid() != id)
+                {
+                    return Err(format!("Scope '{scope}' conflicts with the token scope."));
+                }
+                narrowed.warehouse_id = Some(id.into());
+            } else if let Some(id) = scope.strip_prefix(TABLE_SCOPE_PREFIX) {
+                let id = parse_scope_id(scope, id)?;
+                if requested_table
+                    .replace(id)
+                    .is_some_and(|previous| previous != id)
+                    || self.table_id.is_some_and(|t| t.into_uuid() != id)
+                {
+                    return Err(format!("Scope '{scope}' conflicts with the token scope."));
+                }
+                narrowed.table_id = Some(id.into());
+            } else {
+                remaining.push(scope);
+            }
+        }
+        Ok((narrowed, remaining))
+    }
+
+    /// The `warehouse:<id>` and `table:<id>` scopes of this scope.
+    #[must_use]
+    pub fn scopes(&self) -> Vec<String> {
+        self.warehouse_id
+            .iter()
+            .map(|id| format!("{WAREHOUSE_SCOPE_PREFIX}{id}"))
+            .chain(
+                self.table_id
+                    .iter()
+                    .map(|id| format!("{TABLE_SCOPE_PREFIX}{id}")),
+            )
+            .collect()
+    }
+}
+
+fn parse_scope_id(scope: &str, id: &str) -> std::result::Result<uuid::Uuid, String> {
+    uuid::Uuid::parse_str(id).map_err(|_| format!("Scope '{scope}' does not contain a valid id."))
+}
+
+/// Scopes of a token issued by token exchange, apart from its [`TokenScope`].
+///
+/// Scopes mapped to a permission by `scope_permissions` must be granted to the subject, so
+/// that the exchanged token can't have more permissions. If none of them is requested,
+/// those of the subject are carried over.
+///
+/// # Errors
+/// Fails if a requested scope grants a permission the subject does not have.
+pub fn exchanged_scopes(
+    requested: &[&str],
+    subject: &Claims,
+    scope_permissions: &HashMap<String, Permission>,
+) -> std::result::Result<Vec<String>, String> {
+    let subject_scopes = subject.scopes();
+    if scope_permissions.is_empty() {
+        let scopes = if requested.is_empty() {
+            subject_scopes.as_slice()
+        } else {
+            requested
+        };
+        return Ok(scopes.iter().map(ToString::to_string).collect());
+    }
+
+    if let Some(scope) = requested
+        .iter()
+        .find(|s| scope_permissions.contains_key(**s) && !subject_scopes.contains(*s))
+    {
+        return Err(format!(
+            "Scope '{scope}' is not granted to the subject token."
+        ));
+    }
+    let mut scopes = requested
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    if !requested.iter().any(|s| scope_permissions.contains_key(*s)) {
+        scopes.extend(
+            subject_scopes
+                .into_iter()
+                .filter(|s| scope_permissions.contains_key(*s))
+                .map(ToString::to_string),
+        );
+    }
+    Ok(scopes)
+}
+
+/// Scopes of a catalog token issued to a service key by the `client_credentials` grant.
+///
+/// Requested scopes must be mapped to a permission by `scope_permissions`, apart from
+/// [`CATALOG_SCOPE`]. Requests signed with a service key are not restricted by scopes,
+/// so the key may hold all mapped scopes. If none of them is requested, all of them are
+/// granted, so that the token is not restricted either.
+///
+/// # Errors
+/// Fails if a requested scope is unknown.
+pub fn client_credentials_scopes(
+    requested: &[&str],
+    scope_permissions: &HashMap<String, Permission>,
+) -> std::result::Result<Vec<String>, String> {
+    if let Some(scope) = requested
+        .iter()
+        .find(|s| **s != CATALOG_SCOPE && !scope_permissions.contains_key(**s))
+    {
+        return Err(format!("Scope '{scope}' is not supported."));
+    }
+
+    let mut scopes = requested
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    scopes.dedup();
+    if !requested.iter().any(|s| scope_permissions.contains_key(*s)) {
+        let mut mapped = scope_permissions.keys().cloned().collect::<Vec<_>>();
+        mapped.sort();
+        scopes.extend(mapped);
+    }
+    Ok(scopes)
+}
+
+/// A minted catalog token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogToken {
+    pub token: String,
+    /// Lifetime of the token in seconds.
+    pub expires_in: u64,
+}
+
+/// Returns true if an `oauth_token_secret` is configured.
+#[must_use]
+pub fn catalog_tokens_enabled() -> bool {
+    CONFIG.oauth_token_secret.is_some()
+}
+
+/// Returns true if the token header identifies the token as catalog token.
+/// The token is not validated.
+#[must_use]
+pub fn is_catalog_token(token: &str) -> bool {
+    jsonwebtoken::decode_header(token)
+        .is_ok_and(|header| header.kid.as_deref() == Some(CATALOG_TOKEN_KEY_ID))
+}
+
+/// Mint a catalog token for `principal`.
+/// Returns `Ok(None)` if no `oauth_token_secret` is configured.
+///
+/// # Errors
+/// Fails if the token cannot be encoded.
+pub fn mint_catalog_token(
+    principal: &str,
+    client_id: &str,
+    scope: Option<&str>,
+) -> Result<Option<CatalogToken>> {
+    let Some(secret) = CONFIG.oauth_token_secret.as_deref() else {
+        return Ok(None);
+    };
+
+    let iat = usize::try_from(chrono::Utc::now().timestamp()).unwrap_or_default();
+    let ttl = usize::try_from(CONFIG.oauth_token_ttl_seconds).unwrap_or(usize::MAX);
+    let claims = CatalogTokenClaims {
+        iss: CATALOG_TOKEN_ISSUER.to_string(),
+        aud: CATALOG_TOKEN_ISSUER.to_string(),
+        sub: principal.to_string(),
+        client_id: Some(client_id.to_string()),
+        scope: scope.map(ToString::to_string),
+        warehouse_id: None,
+        table_id: None,
+        jti: uuid::Uuid::now_v7().to_string(),
+        iat,
+        exp: iat.saturating_add(ttl),
+        other: serde_json::Map::new(),
+    };
+
+    Ok(Some(CatalogToken {
+        token: encode(&claims, secret)?,
+        expires_in: CONFIG.oauth_token_ttl_seconds,
+    }))
+}
+
+/// Mint a catalog token for the subject of a token exchange, restricted to `token_scope`.
+/// Claims of the subject such as groups and projects are carried over and the token
+/// expires no later than the subject token.
+/// Returns `Ok(None)` if no `oauth_token_secret` is configured.
+///
+/// # Errors
+/// Fails if the token cannot be encoded.
+pub fn mint_exchanged_token(
+    subject: &Claims,
+    scope: Option<&str>,
+    token_scope: &TokenScope,
+) -> Result<Option<CatalogToken>> {
+    let Some(secret) = CONFIG.oauth_token_secret.as_deref() else {
+        return Ok(None);
+    };
+
+    let iat = usize::try_from(chrono::Utc::now().timestamp()).unwrap_or_default();
+    let ttl = usize::try_from(CONFIG.oauth_token_ttl_seconds).unwrap_or(usize::MAX);
+    let exp = iat.saturating_add(ttl).min(subject.exp);
+    let mut other = subject.other.as_object().cloned().unwrap_or_default();
+    other.retain(|claim, _| !REPLACED_CLAIMS.contains(&claim.as_str()));
+    let claims = CatalogTokenClaims {
+        iss: CATALOG_TOKEN_ISSUER.to_string(),
+        aud: CATALOG_TOKEN_ISSUER.to_string(),
+        sub: subject.sub.clone(),
+        client_id: subject
+            .other
+            .get("client_id")
+            .and_then(serde_json::Value::as_str)
+            .map(ToString::to_string),
+        scope: scope.map(ToString::to_string),
+        warehouse_id: token_scope
+            .warehouse_id
+            .as_ref()
+            .map(WarehouseIdent::into_uuid),
+        table_id: token_scope.table_id.as_ref().map(TableIdentUuid::into_uuid),
+        jti: uuid::Uuid::now_v7().to_string(),
+        iat,
+        exp,
+        other,
+    };
+
+    Ok(Some(CatalogToken {
+        token: encode(&claims, secret)?,
+        expires_in: u64::try_from(exp.saturating_sub(iat)).unwrap_or_default(),
+    }))
+}
+
+/// Validate a catalog token and return its claims.
+///
+/// # Errors
+/// Fails with 401 if catalog tokens are disabled or the token is invalid or expired.
+pub fn verify_catalog_token(token: &str) -> Result<Claims> {
+    let Some(secret) = CONFIG.oauth_token_secret.as_deref() else {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::UNAUTHORIZED.into())
+            .message("Catalog tokens are not enabled.".to_string())
+            .r#type("UnauthorizedError".to_string())
+            .build()
+            .into());
+    };
+
+    decode(token, secret)
+}
+
+fn encode(claims: &CatalogTokenClaims, secret: &str) -> Result<String> {
+    let header = Header {
+        kid: Some(CATALOG_TOKEN_KEY_ID.to_string()),
+        ..Header::new(Algorithm::HS256)
+    };
+
+    jsonwebtoken::encode(
+        &header,
+        claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .map_err(|e| {
+        ErrorModel::builder()
+            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+            .message("Failed to create catalog token.".to_string())
+            .r#type("CatalogTokenCreationFailed".to_string())
+            .stack(Some(vec![e.to_string()]))
+            .build()
+            .into()
+    })
+}
+
+fn decode(token: &str, secret: &str) -> Result<Claims> {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.set_audience(&[CATALOG_TOKEN_ISSUER]);
+    validation.set_issuer(&[CATALOG_TOKEN_ISSUER]);
+
+    jsonwebtoken::decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &validation,
+    )
+    .map(|data| data.claims)
+    .map_err(|e| {
+        ErrorModel::builder()
+            .code(StatusCode::UNAUTHORIZED.into())
+            .message("Invalid catalog token.".to_string())
+            .r#type("UnauthorizedError".to_string())
+            .stack(Some(vec![e.to_string()]))
+            .build()
+            .into()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn claims(exp_offset: i64) -> CatalogTokenClaims {
+        let now = chrono::Utc::now().timestamp();
+        CatalogTokenClaims {
+            iss: CATALOG_TOKEN_ISSUER.to_string(),
+            aud: CATALOG_TOKEN_ISSUER.to_string(),
+            sub: "spark".to_string(),
+            client_id: Some(uuid::Uuid::now_v7().to_string()),
+            scope: Some("catalog:read".to_string()),
+            warehouse_id: None,
+            table_id: Some(uuid::Uuid::now_v7()),
+            jti: uuid::Uuid::now_v7().to_string(),
+            iat: usize::try_from(now).unwrap(),
+            exp: usize::try_from(now + exp_offset).unwrap(),
+            other: serde_json::json!({"groups": ["analysts"]})
+                .as_object()
+                .cloned()
+                .unwrap(),
+        }
+    }
+
+    fn subject(scope: &str) -> Claims {
+        serde_json::from_value(serde_json::json!({
+            "sub": "alice",
+            "iat": 22,
+            "aud": "catalog",
+            "iss": "https://example.com",
+            "exp": 9022,
+            "scope": scope
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_catalog_token_roundtrip() {
+        let claims = claims(600);
+        let token = encode(&claims, "secret").unwrap();
+        assert!(is_catalog_token(&token));
+        assert!(!crate::service::signer_token::is_signer_token(&token));
+
+        let decoded = decode(&token, "secret").unwrap();
+        assert_eq!(decoded.sub, "spark");
+        assert_eq!(decoded.jti.as_deref(), Some(claims.jti.as_str()));
+        assert_eq!(decoded.scopes(), vec!["catalog:read"]);
+        assert_eq!(decoded.strings("groups"), vec!["analysts"]);
+        assert_eq!(
+            TokenScope::from_claims(&decoded),
+            TokenScope {
+                warehouse_id: None,
+                table_id: claims.table_id.map(TableIdentUuid::from),
+            }
+        );
+        assert!(decode(&token, "other-secret").is_err());
+    }
+
+    #[test]
+    fn test_narrow_token_scope() {
+        let warehouse_id = uuid::Uuid::now_v7();
+        let table_id = uuid::Uuid::now_v7();
+        let warehouse_scope = format!("warehouse:{warehouse_id}");
+        let table_scope = format!("table:{table_id}");
+
+        let (scope, remaining) = TokenScope::default()
+            .narrow([warehouse_scope.as_str(), "catalog", table_scope.as_str()])
+            .unwrap();
+        assert_eq!(remaining, vec!["catalog"]);
+        assert_eq!(scope.scopes(), vec![warehouse_scope.clone(), table_scope]);
+        assert!(scope.allows_warehouse(&warehouse_id.into()));
+        assert!(!scope.allows_warehouse(&uuid::Uuid::now_v7().into()));
+        assert!(scope.allows_table(Some(&table_id.into())));
+        assert!(!scope.allows_table(None));
+
+        // A downscoped token can't be exchanged for another warehouse or table
+        let other_table = format!("table:{}", uuid::Uuid::now_v7());
+        assert!(scope.narrow([other_table.as_str()]).is_err());
+        let (narrowed, _) = scope.narrow(["catalog"]).unwrap();
+        assert_eq!(narrowed, scope);
+
+        assert!(TokenScope::default().narrow(["warehouse:abc"]).is_err());
+        assert!(TokenScope::default()
+            .narrow([warehouse_scope.as_str(), other_table.as_str()])
+            .is_ok());
+    }
+
+    #[test]
+    fn test_exchanged_scopes() {
+        let subject = subject("openid catalog:read");
+        assert_eq!(
+            exchanged_scopes(&[], &subject, &HashMap::new()).unwrap(),
+            vec!["openid", "catalog:read"]
+        );
+        assert_eq!(
+            exchanged_scopes(&["catalog"], &subject, &HashMap::new()).unwrap(),
+            vec!["catalog"]
+        );
+
+        let permissions = HashMap::from([
+            ("catalog:read".to_string(), Permission::Read),
+            ("catalog:write".to_string(), Permission::Write),
+        ]);
+        // Permissions of the subject are carried over
+        assert_eq!(
+            exchanged_scopes(&["catalog"], &subject, &permissions).unwrap(),
+            vec!["catalog", "catalog:read"]
+        );
+        assert_eq!(
+            exchanged_scopes(&["catalog:read"], &subject, &permissions).unwrap(),
+            vec!["catalog:read"]
+        );
+        assert!(exchanged_scopes(&["catalog:write"], &subject, &permissions).is_err());
+    }
+
+    #[test]
+    fn test_client_credentials_scopes() {
+        assert_eq!(
+            client_credentials_scopes(&["catalog"], &HashMap::new()).unwrap(),
+            vec!["catalog"]
+        );
+        assert!(client_credentials_scopes(&[], &HashMap::new())
+            .unwrap()
+            .is_empty());
+        assert!(client_credentials_scopes(&["catalog:read"], &HashMap::new()).is_err());
+
+        let permissions = HashMap::from([
+            ("catalog:read".to_string(), Permission::Read),
+            ("catalog:write".to_string(), Permission::Write),
+        ]);
+        assert_eq!(
+            client_credentials_scopes(&["catalog:read"], &permissions).unwrap(),
+            vec!["catalog:read"]
+        );
+        // Without a mapped scope, the token is as unrestricted as the key
+        assert_eq!(
+            client_credentials_scopes(&["catalog"], &permissions).unwrap(),
+            vec!["catalog", "catalog:read", "catalog:write"]
+        );
+        for scope in ["catalog:admin", "warehouse:abc", "table:abc", "openid"] {
+            assert!(
+                client_credentials_scopes(&[scope], &permissions).is_err(),
+                "{scope}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_expired_catalog_token() {
+        let token = encode(&claims(-600), "secret").unwrap();
+        assert!(decode(&token, "secret").is_err());
+    }
+}
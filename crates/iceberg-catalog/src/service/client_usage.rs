@@ -0,0 +1,148 @@
+//! Usage statistics of client engines per warehouse.
+//!
+//! Clients are identified by their `User-Agent` and `X-Client-Version` headers.
+//! Usage is recorded once per catalog session, i.e. for every `GET /config` request,
+//! so that operators know which engines and versions connect to a warehouse.
+
+use crate::api::Result;
+use crate::WarehouseIdent;
+
+/// Well-known engines, matched case-insensitively against the product
+/// names in the `User-Agent` header.
+const KNOWN_ENGINES: &[&str] = &[
+    "pyiceberg",
+    "spark",
+    "trino",
+    "flink",
+    "starrocks",
+    "dremio",
+    "duckdb",
+    "iceberg-rust",
+];
+/// Prefix of the `X-Client-Version` header sent by the Java REST client.
+const ICEBERG_JAVA_VERSION_PREFIX: &str = "Apache Iceberg ";
+
+/// Engine and version of a client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientInfo {
+    /// Lowercase name of the engine, `unknown` if it could not be determined.
+    pub engine: String,
+    pub version: Option<String>,
+}
+
+impl ClientInfo {
+    pub const UNKNOWN_ENGINE: &'static str = "unknown";
+
+    #[must_use]
+    pub fn from_headers(user_agent: Option<&str>, client_version: Option<&str>) -> Self {
+        let products = user_agent
+            .unwrap_or_default()
+            .split_whitespace()
+            .filter_map(|token| {
+                let (name, version) = token.split_once('/').unwrap_or((token, ""));
+                (!name.is_empty() && !name.starts_with('(')).then_some((name, version))
+            })
+            .collect::<Vec<_>>();
+
+        for (name, version) in &products {
+            let name = name.to_lowercase();
+            if let Some(engine) = KNOWN_ENGINES.iter().find(|engine| name.contains(*engine)) {
+                return Self {
+                    engine: (*engine).to_string(),
+                    version: non_empty(version),
+                };
+            }
+        }
+
+        // The Java client does not identify the engine, only the Iceberg version.
+        if let Some(version) =
+            client_version.and_then(|v| v.strip_prefix(ICEBERG_JAVA_VERSION_PREFIX))
+        {
+            return Self {
+                engine: "iceberg-java".to_string(),
+                version: version.split_whitespace().next().and_then(non_empty),
+            };
+        }
+
+        match products.first() {
+            Some((name, version)) => Self {
+                engine: name.to_lowercase(),
+                version: non_empty(version),
+            },
+            None => Self {
+                engine: Self::UNKNOWN_ENGINE.to_string(),
+                version: None,
+            },
+        }
+    }
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    (!s.is_empty()).then(|| s.to_string())
+}
+
+/// Aggregated usage of a warehouse by one engine version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientUsage {
+    pub engine: String,
+    pub version: Option<String>,
+    /// Number of catalog sessions started by this client.
+    pub session_count: i64,
+    pub first_seen: chrono::DateTime<chrono::Utc>,
+    pub last_seen: chrono::DateTime<chrono::Utc>,
+}
+
+#[async_trait::async_trait]
+pub trait ClientUsageStore: std::fmt::Debug + Send + Sync {
+    /// Count a new catalog session of `client` in `warehouse_id`.
+    async fn record_client_usage(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        client: &ClientInfo,
+    ) -> Result<()>;
+
+    /// List the usage of `warehouse_id` per client engine and version.
+    async fn list_client_usage(&self, warehouse_id: &WarehouseIdent) -> Result<Vec<ClientUsage>>;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_client_info_from_headers() {
+        let cases = [
+            (
+                Some("PyIceberg/0.7.1"),
+                Some("0.14.1"),
+                "pyiceberg",
+                Some("0.7.1"),
+            ),
+            (
+                Some("Trino/451 Apache-HttpClient/5.2.1 (Java/17)"),
+                Some("Apache Iceberg 1.5.2 (commit cbb8530)"),
+                "trino",
+                Some("451"),
+            ),
+            (
+                Some("Apache-HttpClient/5.2.1 (Java/17)"),
+                Some("Apache Iceberg 1.5.2 (commit cbb8530)"),
+                "iceberg-java",
+                Some("1.5.2"),
+            ),
+            (Some("curl/8.4.0"), None, "curl", Some("8.4.0")),
+            (None, None, ClientInfo::UNKNOWN_ENGINE, None),
+        ];
+
+        for (user_agent, client_version, engine, version) in cases {
+            assert_eq!(
+                ClientInfo::from_headers(user_agent, client_version),
+                ClientInfo {
+                    engine: engine.to_string(),
+                    version: version.map(ToString::to_string),
+                },
+                "{user_agent:?}"
+            );
+        }
+    }
+}
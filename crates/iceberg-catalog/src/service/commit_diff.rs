@@ -0,0 +1,227 @@
+//! Summary of the changes a commit applied to a table.
+//!
+//! Attached to `table.committed` events so that consumers don't have to diff
+//! two (potentially large) metadata files themselves. The applied updates are
+//! part of the event body already, as they are sent in the commit request.
+use iceberg::spec::Summary;
+use iceberg_ext::spec::TableMetadata;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::CommitTableResponseExt;
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CommitDiff {
+    /// Current schema id before the commit.
+    pub previous_schema_id: i32,
+    /// Current schema id after the commit.
+    pub current_schema_id: i32,
+    /// Ids of schemas added by the commit.
+    pub added_schema_ids: Vec<i32>,
+    /// Properties that were added or changed, with their new value.
+    pub updated_properties: BTreeMap<String, String>,
+    /// Properties that were removed.
+    pub removed_properties: Vec<String>,
+    /// Ids of snapshots added by the commit.
+    pub added_snapshot_ids: Vec<i64>,
+    /// Ids of snapshots removed by the commit.
+    pub removed_snapshot_ids: Vec<i64>,
+    /// Current snapshot id before the commit.
+    pub previous_snapshot_id: Option<i64>,
+    /// Current snapshot id after the commit.
+    pub current_snapshot_id: Option<i64>,
+    /// Summary of the current snapshot if the commit added it, including its
+    /// `operation` and counts such as `added-data-files` and `added-records`.
+    pub snapshot_summary: Option<BTreeMap<String, String>>,
+    /// Location of the metadata file before the commit, `None` for staged tables.
+    pub previous_metadata_location: Option<String>,
+    /// Location of the metadata file written by the commit.
+    pub metadata_location: Option<String>,
+}
+
+impl CommitDiff {
+    #[must_use]
+    pub fn new(previous: &TableMetadata, current: &TableMetadata) -> Self {
+        let updated_properties = current
+            .properties
+            .iter()
+            .filter(|(key, value)| previous.properties.get(*key) != Some(*value))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        let mut removed_properties = previous
+            .properties
+            .keys()
+            .filter(|key| !current.properties.contains_key(*key))
+            .cloned()
+            .collect::<Vec<_>>();
+        removed_properties.sort();
+        let snapshot_summary = current
+            .current_snapshot_id
+            .filter(|snapshot_id| !previous.snapshots.contains_key(snapshot_id))
+            .and_then(|snapshot_id| current.snapshots.get(&snapshot_id))
+            .map(|snapshot| summary_fields(snapshot.summary()));
+
+        Self {
+            previous_schema_id: previous.current_schema_id,
+            current_schema_id: current.current_schema_id,
+            added_schema_ids: added_keys(&previous.schemas, &current.schemas),
+            updated_properties,
+            removed_properties,
+            added_snapshot_ids: added_keys(&previous.snapshots, &current.snapshots),
+            removed_snapshot_ids: added_keys(&current.snapshots, &previous.snapshots),
+            previous_snapshot_id: previous.current_snapshot_id,
+            current_snapshot_id: current.current_snapshot_id,
+            snapshot_summary,
+            previous_metadata_location: None,
+            metadata_location: None,
+        }
+    }
+
+    /// Diff of a commit including the locations of its metadata files.
+    #[must_use]
+    pub fn from_commit(commit: &CommitTableResponseExt) -> Self {
+        Self {
+            previous_metadata_location: commit.previous_metadata_location.clone(),
+            metadata_location: Some(commit.commit_response.metadata_location.clone()),
+            ..Self::new(
+                &commit.previous_table_metadata,
+                &commit.commit_response.metadata,
+            )
+        }
+    }
+}
+
+/// Fields of `summary` as they are serialized, with the `operation` next to the counts.
+fn summary_fields(summary: &Summary) -> BTreeMap<String, String> {
+    let mut fields = summary
+        .other
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect::<BTreeMap<_, _>>();
+    if let Some(operation) = serde_json::to_value(&summary.operation)
+        .ok()
+        .and_then(|operation| operation.as_str().map(str::to_string))
+    {
+        fields.insert("operation".to_string(), operation);
+    }
+    fields
+}
+
+/// Sorted keys of `current` that are not in `previous`.
+fn added_keys<K, V>(previous: &HashMap<K, V>, current: &HashMap<K, V>) -> Vec<K>
+where
+    K: Copy + Ord + std::hash::Hash,
+{
+    let previous = previous.keys().collect::<HashSet<_>>();
+    let mut added = current
+        .keys()
+        .filter(|key| !previous.contains(key))
+        .copied()
+        .collect::<Vec<_>>();
+    added.sort_unstable();
+    added
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use iceberg::spec::{NestedField, PrimitiveType, Schema, Snapshot, Type};
+    use iceberg_ext::spec::TableMetadataAggregate;
+    use std::sync::Arc;
+
+    fn schema(schema_id: i32, fields: &[&str]) -> Schema {
+        Schema::builder()
+            .with_schema_id(schema_id)
+            .with_fields(
+                fields
+                    .iter()
+                    .zip(1..)
+                    .map(|(name, id)| {
+                        NestedField::required(id, *name, Type::Primitive(PrimitiveType::Int)).into()
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_commit_diff() {
+        let mut builder =
+            TableMetadataAggregate::new("s3://bucket/table".to_string(), schema(0, &["id"]));
+        builder
+            .set_properties(HashMap::from_iter(vec![
+                ("owner".to_string(), "alice".to_string()),
+                ("comment".to_string(), "old".to_string()),
+            ]))
+            .unwrap();
+        let previous = builder.build().unwrap();
+
+        let mut builder = TableMetadataAggregate::new_from_metadata(previous.clone());
+        builder
+            .set_properties(HashMap::from_iter(vec![(
+                "comment".to_string(),
+                "new".to_string(),
+            )]))
+            .unwrap();
+        builder.remove_properties(&["owner".to_string()]).unwrap();
+        builder
+            .add_schema(schema(1, &["id", "name"]), None)
+            .unwrap();
+        builder.set_current_schema(-1).unwrap();
+        let current = builder.build().unwrap();
+
+        let diff = CommitDiff::new(&previous, &current);
+        assert_eq!(diff.previous_schema_id, 0);
+        assert_eq!(diff.current_schema_id, 1);
+        assert_eq!(diff.added_schema_ids, vec![1]);
+        assert_eq!(
+            diff.updated_properties,
+            BTreeMap::from_iter(vec![("comment".to_string(), "new".to_string())])
+        );
+        assert_eq!(diff.removed_properties, vec!["owner".to_string()]);
+        assert!(diff.added_snapshot_ids.is_empty());
+        assert!(diff.removed_snapshot_ids.is_empty());
+        assert_eq!(diff.current_snapshot_id, None);
+        assert_eq!(diff.snapshot_summary, None);
+    }
+
+    #[test]
+    fn test_commit_diff_snapshot_summary() {
+        let previous =
+            TableMetadataAggregate::new("s3://bucket/table".to_string(), schema(0, &["id"]))
+                .build()
+                .unwrap();
+        let snapshot: Snapshot = serde_json::from_value(serde_json::json!({
+            "snapshot-id": 2,
+            "sequence-number": 1,
+            "timestamp-ms": 1_700_000_000_000_i64,
+            "manifest-list": "s3://bucket/table/metadata/snap-2.avro",
+            "summary": {
+                "operation": "append",
+                "added-data-files": "3",
+                "added-records": "100"
+            },
+            "schema-id": 0
+        }))
+        .unwrap();
+        let mut current = previous.clone();
+        current.snapshots.insert(2, Arc::new(snapshot));
+        current.current_snapshot_id = Some(2);
+
+        let diff = CommitDiff::new(&previous, &current);
+        assert_eq!(diff.added_snapshot_ids, vec![2]);
+        assert_eq!(
+            diff.snapshot_summary,
+            Some(BTreeMap::from_iter(vec![
+                ("operation".to_string(), "append".to_string()),
+                ("added-data-files".to_string(), "3".to_string()),
+                ("added-records".to_string(), "100".to_string()),
+            ]))
+        );
+
+        // The current snapshot was not added by a commit that only changes properties
+        let diff = CommitDiff::new(&current, &current);
+        assert_eq!(diff.snapshot_summary, None);
+    }
+}
@@ -0,0 +1,221 @@
+#![allow(clippy::module_name_repetitions)]
+use crate::service::TableIdentUuid;
+use crate::WarehouseIdent;
+use async_trait::async_trait;
+use iceberg::spec::TableMetadata;
+use iceberg::{TableIdent, TableRequirement, TableUpdate};
+use iceberg_ext::catalog::rest::ErrorModel;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// A commit about to be applied to a table.
+#[derive(Debug, Clone, Copy)]
+pub struct CommitContext<'a> {
+    pub warehouse_id: &'a WarehouseIdent,
+    pub table_id: TableIdentUuid,
+    pub table: &'a TableIdent,
+    /// Metadata the commit is applied to. `None` for staged tables.
+    ///
+    /// Loaded before the commit without locking the table, so a concurrent commit may
+    /// change the table in between. Such commits are only rejected if the requirements
+    /// of the commit assert the state the hook saw.
+    pub previous_metadata: Option<&'a TableMetadata>,
+    pub requirements: &'a [TableRequirement],
+    pub updates: &'a [TableUpdate],
+}
+
+/// A hook invoked before a commit is applied to a table, i.e. to enforce naming or
+/// partitioning standards of a deployment.
+///
+/// Unlike [`ContractVerification`](super::contract_verification::ContractVerification),
+/// which checks the result of a commit, hooks run before the updates are applied and
+/// can annotate the commit with table properties.
+///
+/// # Example
+///
+/// ```rust
+///     use async_trait::async_trait;
+///     use iceberg::TableUpdate;
+///     use iceberg_catalog::service::commit_hook::{CommitContext, CommitHook, CommitHookOutcome};
+///     use iceberg_ext::catalog::rest::ErrorModel;
+///
+///     #[derive(Debug)]
+///     pub struct NoSchemaChanges;
+///
+///     #[async_trait]
+///     impl CommitHook for NoSchemaChanges {
+///         fn name(&self) -> &'static str {
+///             "NoSchemaChanges"
+///         }
+///
+///         async fn before_commit(
+///             &self,
+///             context: &CommitContext<'_>,
+///         ) -> Result<CommitHookOutcome, ErrorModel> {
+///             if context
+///                 .updates
+///                 .iter()
+///                 .any(|u| matches!(u, TableUpdate::AddSchema { .. }))
+///             {
+///                 return Ok(CommitHookOutcome::Reject {
+///                     error_model: ErrorModel::builder()
+///                         .code(409)
+///                         .message("Schema changes require a review")
+///                         .r#type("SchemaChangeRejected".to_string())
+///                         .build(),
+///                 });
+///             }
+///             Ok(CommitHookOutcome::Accept {})
+///         }
+///     }
+/// ```
+#[async_trait]
+pub trait CommitHook: Debug {
+    fn name(&self) -> &'static str;
+
+    async fn before_commit(
+        &self,
+        context: &CommitContext<'_>,
+    ) -> Result<CommitHookOutcome, ErrorModel>;
+}
+
+#[derive(Debug)]
+pub enum CommitHookOutcome {
+    Accept {},
+    /// Accept the commit and set `properties` on the table with it.
+    Annotate {
+        properties: HashMap<String, String>,
+    },
+    Reject {
+        error_model: ErrorModel,
+    },
+}
+
+/// Hooks invoked in order. The first hook rejecting a commit stops the chain.
+#[derive(Debug, Clone, Default)]
+pub struct CommitHooks {
+    hooks: Vec<Arc<dyn CommitHook + Sync + Send>>,
+}
+
+impl CommitHooks {
+    #[must_use]
+    pub fn new(hooks: Vec<Arc<dyn CommitHook + Sync + Send>>) -> Self {
+        Self { hooks }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Run all hooks and collect their annotations. Later hooks overwrite
+    /// properties annotated by earlier ones.
+    ///
+    /// # Errors
+    /// Fails with the error of the first hook that rejects the commit or fails itself.
+    pub async fn before_commit(
+        &self,
+        context: &CommitContext<'_>,
+    ) -> Result<HashMap<String, String>, ErrorModel> {
+        let mut annotations = HashMap::new();
+        for hook in &self.hooks {
+            match hook.before_commit(context).await {
+                Ok(CommitHookOutcome::Accept {}) => {}
+                Ok(CommitHookOutcome::Annotate { properties }) => annotations.extend(properties),
+                Ok(CommitHookOutcome::Reject { error_model }) => {
+                    tracing::info!(
+                        "CommitHook '{}' rejected commit to table '{}'",
+                        hook.name(),
+                        context.table_id
+                    );
+                    return Err(error_model);
+                }
+                Err(error) => {
+                    tracing::warn!("CommitHook '{}' failed", hook.name());
+                    return Err(error);
+                }
+            }
+        }
+        Ok(annotations)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Annotate(&'static str, &'static str);
+
+    #[async_trait]
+    impl CommitHook for Annotate {
+        fn name(&self) -> &'static str {
+            "Annotate"
+        }
+
+        async fn before_commit(
+            &self,
+            _: &CommitContext<'_>,
+        ) -> Result<CommitHookOutcome, ErrorModel> {
+            Ok(CommitHookOutcome::Annotate {
+                properties: HashMap::from_iter([(self.0.to_string(), self.1.to_string())]),
+            })
+        }
+    }
+
+    #[derive(Debug)]
+    struct Reject;
+
+    #[async_trait]
+    impl CommitHook for Reject {
+        fn name(&self) -> &'static str {
+            "Reject"
+        }
+
+        async fn before_commit(
+            &self,
+            _: &CommitContext<'_>,
+        ) -> Result<CommitHookOutcome, ErrorModel> {
+            Ok(CommitHookOutcome::Reject {
+                error_model: ErrorModel::builder()
+                    .code(409)
+                    .message("Rejected")
+                    .r#type("Rejected".to_string())
+                    .build(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chain() {
+        let warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        let table = TableIdent::from_strs(["ns", "tbl"]).unwrap();
+        let context = CommitContext {
+            warehouse_id: &warehouse_id,
+            table_id: uuid::Uuid::now_v7().into(),
+            table: &table,
+            previous_metadata: None,
+            requirements: &[],
+            updates: &[],
+        };
+
+        let annotations = CommitHooks::new(vec![
+            Arc::new(Annotate("owner", "alice")),
+            Arc::new(Annotate("owner", "bob")),
+            Arc::new(Annotate("team", "data")),
+        ])
+        .before_commit(&context)
+        .await
+        .unwrap();
+        assert_eq!(annotations.get("owner").map(String::as_str), Some("bob"));
+        assert_eq!(annotations.get("team").map(String::as_str), Some("data"));
+
+        let err = CommitHooks::new(vec![Arc::new(Reject), Arc::new(Annotate("owner", "alice"))])
+            .before_commit(&context)
+            .await
+            .unwrap_err();
+        assert_eq!(err.r#type, "Rejected");
+        assert!(CommitHooks::default().is_empty());
+    }
+}
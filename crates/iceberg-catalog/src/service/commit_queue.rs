@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use http::StatusCode;
+use tokio::sync::OwnedMutexGuard;
+
+use super::TableIdentUuid;
+use crate::api::{ErrorModel, Result};
+
+type Tables = HashMap<TableIdentUuid, (Arc<tokio::sync::Mutex<()>>, usize)>;
+
+/// Serializes commits to the same table within this instance.
+///
+/// Concurrent commits to one table otherwise all read the same metadata, and
+/// all but one fail on the row lock or the requirements check and have to be
+/// retried by the client. Queued commits are applied one after another in
+/// arrival order instead. At most `capacity` commits per table are queued,
+/// including the one being applied; further commits are rejected with a `503`.
+/// Commits through other instances are not queued. A capacity of zero
+/// disables the queue.
+#[derive(Debug, Clone)]
+pub struct CommitQueue {
+    tables: Arc<Mutex<Tables>>,
+    capacity: usize,
+}
+
+/// Permission to commit to a set of tables. Releases the tables when dropped.
+#[derive(Debug)]
+pub struct CommitPermit {
+    queue: Option<CommitQueue>,
+    table_ids: Vec<TableIdentUuid>,
+    guards: Vec<OwnedMutexGuard<()>>,
+}
+
+impl CommitQueue {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            tables: Arc::new(Mutex::new(HashMap::new())),
+            capacity,
+        }
+    }
+
+    /// A queue that never waits.
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self::new(0)
+    }
+
+    /// Wait until all previously queued commits to any of `table_ids` are done.
+    /// Tables are locked in the order of their ids, so that transactions
+    /// spanning multiple tables cannot deadlock.
+    ///
+    /// # Errors
+    /// Fails if the queue of any of the tables is full.
+    pub async fn acquire(
+        &self,
+        table_ids: impl IntoIterator<Item = TableIdentUuid>,
+    ) -> Result<CommitPermit> {
+        if self.capacity == 0 {
+            return Ok(CommitPermit {
+                queue: None,
+                table_ids: vec![],
+                guards: vec![],
+            });
+        }
+
+        let mut table_ids = table_ids.into_iter().collect::<Vec<_>>();
+        table_ids.sort_unstable();
+        table_ids.dedup();
+
+        let locks = {
+            let mut tables = self.tables.lock().expect("commit queue lock poisoned");
+            if let Some(table_id) = table_ids.iter().find(|table_id| {
+                tables
+                    .get(table_id)
+                    .is_some_and(|(_, queued)| *queued >= self.capacity)
+            }) {
+                return Err(ErrorModel::builder()
+                    .code(StatusCode::SERVICE_UNAVAILABLE.into())
+                    .message(format!(
+                        "Too many concurrent commits to table {table_id}. Retry later."
+                    ))
+                    .r#type("CommitQueueFull".to_string())
+                    .build()
+                    .into());
+            }
+            table_ids
+                .iter()
+                .map(|table_id| {
+                    let (lock, queued) = tables.entry(*table_id).or_default();
+                    *queued += 1;
+                    lock.clone()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Created before waiting, so that the tables are released if the request is cancelled.
+        let mut permit = CommitPermit {
+            queue: Some(self.clone()),
+            table_ids,
+            guards: Vec::with_capacity(locks.len()),
+        };
+        for lock in locks {
+            // `tokio::sync::Mutex` is fair, waiters are served in FIFO order.
+            permit.guards.push(lock.lock_owned().await);
+        }
+        Ok(permit)
+    }
+
+    #[cfg(test)]
+    fn queued(&self, table_id: &TableIdentUuid) -> usize {
+        self.tables
+            .lock()
+            .expect("commit queue lock poisoned")
+            .get(table_id)
+            .map_or(0, |(_, queued)| *queued)
+    }
+}
+
+impl Drop for CommitPermit {
+    fn drop(&mut self) {
+        let Some(queue) = &self.queue else {
+            return;
+        };
+        let mut tables = queue.tables.lock().expect("commit queue lock poisoned");
+        for table_id in &self.table_ids {
+            if let Some((_, queued)) = tables.get_mut(table_id) {
+                *queued -= 1;
+                if *queued == 0 {
+                    tables.remove(table_id);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_commit_queue() {
+        let queue = CommitQueue::new(2);
+        let table_1 = TableIdentUuid::from(uuid::Uuid::now_v7());
+        let table_2 = TableIdentUuid::from(uuid::Uuid::now_v7());
+
+        let permit = queue.acquire([table_1, table_2]).await.unwrap();
+        let waiting = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.acquire([table_2]).await.map(|_| ()) }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+        assert_eq!(queue.queued(&table_2), 2);
+
+        let err = queue.acquire([table_2]).await.unwrap_err();
+        assert_eq!(err.error.r#type, "CommitQueueFull");
+        // Other tables are not affected
+        let table_3 = TableIdentUuid::from(uuid::Uuid::now_v7());
+        drop(queue.acquire([table_3]).await.unwrap());
+
+        drop(permit);
+        waiting.await.unwrap().unwrap();
+        assert_eq!(queue.queued(&table_1), 0);
+        assert_eq!(queue.queued(&table_2), 0);
+
+        let disabled = CommitQueue::disabled();
+        let _permit = disabled.acquire([table_1]).await.unwrap();
+        let _permit = disabled.acquire([table_1]).await.unwrap();
+    }
+}
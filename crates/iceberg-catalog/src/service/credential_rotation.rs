@@ -0,0 +1,136 @@
+//! Rotation of the storage credential of warehouses.
+//!
+//! A rotation stores the new credential, validates it against the storage and swaps
+//! it in atomically. The replaced credential is kept as the previous credential of the
+//! warehouse for a grace period, so that it can be restored if the new one turns out
+//! to be broken. Once the grace period has passed, the [`CredentialRotationJob`]
+//! validates the new credential again and deletes the previous one if the storage is
+//! still accessible.
+use std::fmt::Debug;
+use std::time::Duration;
+
+use super::storage_validation::validate_storage;
+use super::{Catalog, GetWarehouseResponse, SecretStore};
+use crate::api::Result;
+use crate::SecretIdent;
+
+/// Last rotation of the storage credential of a warehouse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CredentialRotation {
+    /// Time of the rotation.
+    pub rotated_at: chrono::DateTime<chrono::Utc>,
+    /// Secret replaced by the rotation. `None` once it has been deleted
+    /// or if the warehouse had no credential before.
+    pub previous_secret_id: Option<SecretIdent>,
+}
+
+pub struct CredentialRotationJob<C: Catalog, S: SecretStore> {
+    catalog_state: C::State,
+    secrets_state: S::State,
+    grace_period: Duration,
+}
+
+impl<C: Catalog, S: SecretStore> Debug for CredentialRotationJob<C, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CredentialRotationJob")
+            .field("grace_period", &self.grace_period)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: Catalog, S: SecretStore> CredentialRotationJob<C, S> {
+    #[must_use]
+    pub fn new(catalog_state: C::State, secrets_state: S::State, grace_period: Duration) -> Self {
+        Self {
+            catalog_state,
+            secrets_state,
+            grace_period,
+        }
+    }
+
+    /// Retire previous credentials every `interval` until the task is aborted.
+    pub async fn run(self, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match self.retire_expired().await {
+                Ok(0) => tracing::debug!("No previous storage credentials to retire."),
+                Ok(retired) => tracing::info!("Retired {retired} previous storage credentials."),
+                Err(e) => tracing::warn!("Credential rotation job failed: {:?}", e.error),
+            }
+        }
+    }
+
+    /// Retire the previous credential of all active warehouses whose grace period
+    /// has passed. Returns the number of retired credentials.
+    ///
+    /// # Errors
+    /// Fails if the warehouses cannot be listed.
+    pub async fn retire_expired(&self) -> Result<usize> {
+        let Some(cutoff) = chrono::Duration::from_std(self.grace_period)
+            .ok()
+            .and_then(|grace_period| chrono::Utc::now().checked_sub_signed(grace_period))
+        else {
+            return Ok(0);
+        };
+        let mut retired = 0;
+        for project_id in C::list_projects(self.catalog_state.clone()).await? {
+            let warehouses =
+                C::list_warehouses(&project_id, None, None, self.catalog_state.clone()).await?;
+            for warehouse in warehouses {
+                let Some(previous_secret_id) = warehouse
+                    .credential_rotation
+                    .as_ref()
+                    .filter(|r| r.rotated_at <= cutoff)
+                    .and_then(|r| r.previous_secret_id.clone())
+                else {
+                    continue;
+                };
+                match self.retire(&warehouse, &previous_secret_id).await {
+                    Ok(true) => retired += 1,
+                    Ok(false) => {}
+                    Err(e) => tracing::warn!(
+                        "Failed to retire previous credential of warehouse {}: {:?}",
+                        warehouse.id,
+                        e.error
+                    ),
+                }
+            }
+        }
+        Ok(retired)
+    }
+
+    /// Validate the current credential of the warehouse again and delete the previous
+    /// one if the storage is accessible. Returns whether the previous one was deleted.
+    async fn retire(
+        &self,
+        warehouse: &GetWarehouseResponse,
+        previous_secret_id: &SecretIdent,
+    ) -> Result<bool> {
+        let validation = validate_storage::<S>(warehouse, self.secrets_state.clone()).await;
+        C::set_warehouse_storage_validation(&warehouse.id, &validation, self.catalog_state.clone())
+            .await?;
+        if let Some(error) = &validation.error {
+            tracing::warn!(
+                "Rotated credential of warehouse {} ({}) is invalid, keeping the previous credential: {error}",
+                warehouse.name,
+                warehouse.id
+            );
+            return Ok(false);
+        }
+
+        // The warehouse may have been rotated again in the meantime
+        if !C::clear_warehouse_previous_storage_secret(
+            &warehouse.id,
+            previous_secret_id,
+            self.catalog_state.clone(),
+        )
+        .await?
+        {
+            return Ok(false);
+        }
+        S::delete_secret(previous_secret_id, self.secrets_state.clone()).await?;
+        Ok(true)
+    }
+}
@@ -1,9 +1,22 @@
+//! Publishing of catalog events as `CloudEvents` 1.0.
+//!
+//! Every event carries the attributes `id`, `source`, `type`, `subject` and `time`, and the
+//! extensions `warehouse-id`, `prefix`, `num-events`, `sequence-number`, `trace-id` and, if
+//! known, `tenant-id`. Events about namespaces and tables additionally carry `namespace`,
+//! events about tables `table-id` and `name`. The `type` is one of [`EventType`], the
+//! `subject` the path of the object, see [`EventSubject`]. Sinks receive the same [`Event`],
+//! so NATS, webhooks and other backends emit identical envelopes.
 use async_trait::async_trait;
-use cloudevents::Event;
+use chrono::{DateTime, Utc};
+use cloudevents::{AttributesReader, Event};
+use iceberg_ext::redaction::redact_json;
 use std::fmt::Debug;
 use std::sync::Arc;
 use uuid::Uuid;
 
+use super::resolution_cache::ResolutionCache;
+use super::Catalog;
+
 #[derive(Debug, Clone)]
 pub struct CloudEventsPublisher {
     tx: tokio::sync::mpsc::Sender<Message>,
@@ -24,21 +37,25 @@ impl CloudEventsPublisher {
         Self { tx, timeout }
     }
 
+    /// Secrets in `data`, i.e. credentials set as table properties, are redacted.
+    ///
     /// # Errors
     ///
     /// Returns an error if the event cannot be sent to the channel due to capacity / timeout.
     pub async fn publish(
         &self,
         id: Uuid,
-        typ: &str,
-        data: serde_json::Value,
+        typ: EventType,
+        mut data: serde_json::Value,
         metadata: EventMetadata,
     ) -> anyhow::Result<()> {
+        redact_json(&mut data);
         self.tx
             .send_timeout(
                 Message::Event(Payload {
                     id,
-                    typ: typ.to_string(),
+                    typ,
+                    time: Utc::now(),
                     data,
                     metadata,
                 }),
@@ -53,22 +70,149 @@ impl CloudEventsPublisher {
     }
 }
 
+/// Type of an event, the `type` attribute of its `CloudEvent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventType {
+    TableCreated,
+    TableCommitted,
+    TableRenamed,
+    TableDropped,
+    /// A dropped table was removed after its retention period.
+    TableReclaimed,
+    TableSnapshotsExpired,
+    /// A snapshot with lineage information was committed, one event per snapshot.
+    TableLineage,
+    NamespaceCreated,
+    NamespacePropertiesUpdated,
+    NamespaceDropped,
+    WarehouseCreated,
+    WarehouseRenamed,
+    WarehouseActivated,
+    WarehouseDeactivated,
+    WarehouseStorageUpdated,
+    WarehouseDeleted,
+}
+
+impl EventType {
+    pub const ALL: [EventType; 16] = [
+        Self::TableCreated,
+        Self::TableCommitted,
+        Self::TableRenamed,
+        Self::TableDropped,
+        Self::TableReclaimed,
+        Self::TableSnapshotsExpired,
+        Self::TableLineage,
+        Self::NamespaceCreated,
+        Self::NamespacePropertiesUpdated,
+        Self::NamespaceDropped,
+        Self::WarehouseCreated,
+        Self::WarehouseRenamed,
+        Self::WarehouseActivated,
+        Self::WarehouseDeactivated,
+        Self::WarehouseStorageUpdated,
+        Self::WarehouseDeleted,
+    ];
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::TableCreated => "table.created",
+            Self::TableCommitted => "table.committed",
+            Self::TableRenamed => "table.renamed",
+            Self::TableDropped => "table.dropped",
+            Self::TableReclaimed => "table.reclaimed",
+            Self::TableSnapshotsExpired => "table.snapshots-expired",
+            Self::TableLineage => "table.lineage",
+            Self::NamespaceCreated => "namespace.created",
+            Self::NamespacePropertiesUpdated => "namespace.properties-updated",
+            Self::NamespaceDropped => "namespace.dropped",
+            Self::WarehouseCreated => "warehouse.created",
+            Self::WarehouseRenamed => "warehouse.renamed",
+            Self::WarehouseActivated => "warehouse.activated",
+            Self::WarehouseDeactivated => "warehouse.deactivated",
+            Self::WarehouseStorageUpdated => "warehouse.storage-updated",
+            Self::WarehouseDeleted => "warehouse.deleted",
+        }
+    }
+}
+
+impl std::fmt::Display for EventType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Object an event is about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventSubject {
+    Warehouse,
+    Namespace {
+        /// Namespace as encoded in URLs.
+        namespace: String,
+    },
+    Table {
+        table_id: Uuid,
+        /// Namespace as encoded in URLs.
+        namespace: String,
+        name: String,
+    },
+}
+
+impl EventSubject {
+    /// The `subject` attribute: `warehouses/{id}`, followed by `/namespaces/{namespace}`
+    /// for namespaces and tables and `/tables/{name}` for tables.
+    #[must_use]
+    pub fn path(&self, warehouse_id: Uuid) -> String {
+        match self {
+            Self::Warehouse => format!("warehouses/{warehouse_id}"),
+            Self::Namespace { namespace } => {
+                format!("warehouses/{warehouse_id}/namespaces/{namespace}")
+            }
+            Self::Table {
+                namespace, name, ..
+            } => format!("warehouses/{warehouse_id}/namespaces/{namespace}/tables/{name}"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct EventMetadata {
-    pub table_id: Uuid,
     pub warehouse_id: Uuid,
-    pub name: String,
-    pub namespace: String,
+    pub subject: EventSubject,
     pub prefix: String,
     pub num_events: usize,
     pub sequence_number: usize,
     pub trace_id: Uuid,
+    /// Tenant of the request that caused the event, see `TenantId`.
+    pub tenant_id: Option<String>,
+}
+
+impl EventMetadata {
+    /// Metadata of a single event about `subject` caused by a request.
+    #[must_use]
+    pub fn single(
+        warehouse_id: Uuid,
+        subject: EventSubject,
+        request_metadata: &crate::request_metadata::RequestMetadata,
+    ) -> Self {
+        Self {
+            warehouse_id,
+            subject,
+            prefix: warehouse_id.to_string(),
+            num_events: 1,
+            sequence_number: 0,
+            trace_id: request_metadata.request_id,
+            tenant_id: request_metadata.tenant_id().map(ToString::to_string),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct Payload {
     pub id: Uuid,
-    pub typ: String,
+    pub typ: EventType,
+    /// Time the event occurred.
+    pub time: DateTime<Utc>,
     pub data: serde_json::Value,
     pub metadata: EventMetadata,
 }
@@ -92,12 +236,23 @@ impl CloudEventsPublisherBackgroundTask {
         while let Some(Message::Event(Payload {
             id,
             typ,
+            time,
             data,
             metadata,
         })) = self.source.recv().await
         {
             use cloudevents::{EventBuilder, EventBuilderV10};
 
+            let EventMetadata {
+                warehouse_id,
+                subject,
+                prefix,
+                num_events,
+                sequence_number,
+                trace_id,
+                tenant_id,
+            } = metadata;
+
             let event_builder = EventBuilderV10::new()
                 .id(id.to_string())
                 .source(format!(
@@ -106,26 +261,28 @@ impl CloudEventsPublisherBackgroundTask {
                         .map(|os| os.to_string_lossy().to_string())
                         .unwrap_or("hostname-unavailable".into())
                 ))
-                .ty(typ)
+                .ty(typ.as_str())
+                .subject(subject.path(warehouse_id))
+                .time(time)
                 .data("application/json", data);
 
-            let EventMetadata {
-                table_id,
-                warehouse_id,
-                name,
-                namespace,
-                prefix,
-                num_events,
-                sequence_number,
-                trace_id,
-            } = metadata;
-            // TODO: this could be more elegant with a proc macro to give us IntoIter for EventMetadata
+            let event_builder = match subject {
+                EventSubject::Warehouse => event_builder,
+                EventSubject::Namespace { namespace } => {
+                    event_builder.extension("namespace", namespace)
+                }
+                EventSubject::Table {
+                    table_id,
+                    namespace,
+                    name,
+                } => event_builder
+                    .extension("table-id", table_id.to_string())
+                    .extension("name", name)
+                    .extension("namespace", namespace),
+            };
             let event = event_builder
-                .extension("table-id", table_id.to_string())
                 .extension("warehouse-id", warehouse_id.to_string())
-                .extension("name", name.to_string())
-                .extension("namespace", namespace.to_string())
-                .extension("prefix", prefix.to_string())
+                .extension("prefix", prefix)
                 // TODO: decide what to do with these numbbers, likely they are never anywhere close to
                 // saturating the respective int types, so probably a non-issue. Still we are converting
                 // the numbers to_string here to avoid usize -> i64 which is what EventBuilderV10
@@ -134,8 +291,12 @@ impl CloudEventsPublisherBackgroundTask {
                 .extension("num-events", num_events.to_string())
                 .extension("sequence-number", sequence_number.to_string())
                 // Implement distributed tracing: https://github.com/hansetag/iceberg-catalog/issues/63
-                .extension("trace-id", trace_id.to_string())
-                .build()?;
+                .extension("trace-id", trace_id.to_string());
+            let event = match tenant_id {
+                Some(tenant_id) => event.extension("tenant-id", tenant_id),
+                None => event,
+            }
+            .build()?;
 
             for sink in &self.sinks {
                 if let Err(e) = sink.publish(event.clone()).await {
@@ -196,3 +357,240 @@ impl CloudEventBackend for TracingPublisher {
         "tracing-publisher"
     }
 }
+
+/// [`CloudEventBackend`] forwarding only the events the [`EventFilter`](super::EventFilter)
+/// of their warehouse allows.
+///
+/// Wraps the sinks that publish events outside of the catalog, internal sinks such as
+/// cache invalidation must receive all events. Events of warehouses that can't be
+/// loaded are forwarded.
+pub struct WarehouseFilteredBackend<C: Catalog> {
+    inner: Arc<dyn CloudEventBackend + Sync + Send>,
+    resolution_cache: ResolutionCache,
+    catalog_state: C::State,
+}
+
+impl<C: Catalog> WarehouseFilteredBackend<C> {
+    #[must_use]
+    pub fn new(
+        inner: Arc<dyn CloudEventBackend + Sync + Send>,
+        resolution_cache: ResolutionCache,
+        catalog_state: C::State,
+    ) -> Self {
+        Self {
+            inner,
+            resolution_cache,
+            catalog_state,
+        }
+    }
+
+    async fn allows(&self, event: &Event) -> bool {
+        let Some(warehouse_id) = event
+            .extension("warehouse-id")
+            .and_then(|id| Uuid::parse_str(&id.to_string()).ok())
+        else {
+            return true;
+        };
+        match self
+            .resolution_cache
+            .warehouse::<C>(&warehouse_id.into(), self.catalog_state.clone())
+            .await
+        {
+            Ok(Some(warehouse)) => warehouse.event_filter.allows(event.ty()),
+            Ok(None) => true,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load event filter of warehouse '{warehouse_id}': {:?}",
+                    e.error
+                );
+                true
+            }
+        }
+    }
+}
+
+impl<C: Catalog> Debug for WarehouseFilteredBackend<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WarehouseFilteredBackend")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<C: Catalog> CloudEventBackend for WarehouseFilteredBackend<C> {
+    async fn publish(&self, event: Event) -> anyhow::Result<()> {
+        if self.allows(&event).await {
+            self.inner.publish(event).await
+        } else {
+            Ok(())
+        }
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn table_metadata() -> EventMetadata {
+        EventMetadata {
+            warehouse_id: Uuid::nil(),
+            subject: EventSubject::Table {
+                table_id: Uuid::nil(),
+                namespace: "ns".to_string(),
+                name: "table".to_string(),
+            },
+            prefix: String::new(),
+            num_events: 1,
+            sequence_number: 0,
+            trace_id: Uuid::nil(),
+            tenant_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_envelope() {
+        let (backend, publisher) = crate::test_util::RecordingEventBackend::publisher();
+        let before = Utc::now();
+        publisher
+            .publish(
+                Uuid::now_v7(),
+                EventType::TableCommitted,
+                serde_json::json!({}),
+                table_metadata(),
+            )
+            .await
+            .unwrap();
+        publisher
+            .publish(
+                Uuid::now_v7(),
+                EventType::NamespaceCreated,
+                serde_json::json!({}),
+                EventMetadata {
+                    subject: EventSubject::Namespace {
+                        namespace: "ns".to_string(),
+                    },
+                    ..table_metadata()
+                },
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+        let events = backend.events();
+        assert_eq!(events.len(), 2);
+        let warehouse = format!("warehouses/{}", Uuid::nil());
+        assert_eq!(events[0].ty(), "table.committed");
+        assert_eq!(
+            events[0].subject(),
+            Some(format!("{warehouse}/namespaces/ns/tables/table").as_str())
+        );
+        assert!(events[0].time().is_some_and(|time| *time >= before));
+        assert!(events[0].extension("table-id").is_some());
+        assert_eq!(events[1].ty(), "namespace.created");
+        assert_eq!(
+            events[1].subject(),
+            Some(format!("{warehouse}/namespaces/ns").as_str())
+        );
+        assert!(events[1].extension("table-id").is_none());
+        assert!(events[1].extension("namespace").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_warehouse_filtered_backend() {
+        use crate::implementations::memory::{
+            MemoryCatalog, MemoryCatalogState, MemoryTransaction,
+        };
+        use crate::service::storage::{FileProfile, StorageProfile};
+        use crate::service::{EventFilter, Transaction as _};
+        use cloudevents::{EventBuilder, EventBuilderV10};
+
+        let state = MemoryCatalogState::default();
+        let mut transaction = MemoryTransaction::begin_write(state.clone()).await.unwrap();
+        let warehouse_id = MemoryCatalog::create_warehouse(
+            "test_warehouse".to_string(),
+            Uuid::nil().into(),
+            StorageProfile::File(FileProfile {
+                path: "/tmp/warehouse".to_string(),
+            }),
+            None,
+            None,
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        MemoryCatalog::set_event_filter(
+            &warehouse_id,
+            EventFilter {
+                enabled: true,
+                disabled_event_types: vec!["table.committed".to_string()],
+            },
+            transaction.transaction(),
+        )
+        .await
+        .unwrap();
+        transaction.commit().await.unwrap();
+
+        let recording = crate::test_util::RecordingEventBackend::default();
+        let backend = WarehouseFilteredBackend::<MemoryCatalog>::new(
+            Arc::new(recording.clone()),
+            ResolutionCache::new(std::time::Duration::ZERO),
+            state,
+        );
+        let event = |typ: &str, warehouse_id: Uuid| {
+            EventBuilderV10::new()
+                .id(Uuid::now_v7().to_string())
+                .source("test")
+                .ty(typ)
+                .extension("warehouse-id", warehouse_id.to_string())
+                .build()
+                .unwrap()
+        };
+        for event in [
+            event("table.committed", *warehouse_id.as_uuid()),
+            event("table.created", *warehouse_id.as_uuid()),
+            // Other warehouses are not affected
+            event("table.committed", Uuid::now_v7()),
+        ] {
+            backend.publish(event).await.unwrap();
+        }
+
+        let types = recording
+            .events()
+            .iter()
+            .map(|event| event.ty().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(types, vec!["table.created", "table.committed"]);
+    }
+
+    #[tokio::test]
+    async fn test_published_data_is_redacted() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let publisher = CloudEventsPublisher::new(tx);
+        let data = serde_json::json!({
+            "updates": [{
+                "action": "set-properties",
+                "updates": {"s3.secret-access-key": "wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY"}
+            }]
+        });
+        publisher
+            .publish(
+                Uuid::now_v7(),
+                EventType::TableCommitted,
+                data,
+                table_metadata(),
+            )
+            .await
+            .unwrap();
+
+        let Some(Message::Event(payload)) = rx.recv().await else {
+            panic!("Expected an event");
+        };
+        assert!(!payload.data.to_string().contains("tnFEMI"));
+        assert!(!format!("{payload:?}").contains("tnFEMI"));
+    }
+}
@@ -0,0 +1,462 @@
+//! Shared-secret authentication for service-to-service calls.
+//!
+//! Intended for internal deployments without an identity provider. Service keys are
+//! created via the management API and stored in the [`SecretStore`]; the id of the
+//! secret is the key id. As the management API requires a signed request if there is
+//! no identity provider, the first key is created with the `create-service-key`
+//! subcommand of the binary, see [`create_service_key`]. Clients sign each request with
+//! the secret of their key and send the following headers instead of a bearer token:
+//!
+//! - `x-iceberg-key-id`: id of the service key
+//! - `x-iceberg-timestamp`: unix timestamp in seconds
+//! - `x-iceberg-nonce`: random value, unique per request
+//! - `x-iceberg-signature`: hex encoded `HMAC-SHA256(secret, string_to_sign)` with
+//!   `string_to_sign = "{method}\n{path_and_query}\n{timestamp}\n{nonce}\n{hex(sha256(body))}"`
+//!
+//! Requests signed more than [`MAX_CLOCK_SKEW`] ago are rejected. Nonces are remembered
+//! until then, so a request cannot be replayed against the same instance.
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use http::{HeaderMap, StatusCode};
+use iceberg_ext::catalog::rest::{ErrorModel, IcebergErrorResponse};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use veil::Redact;
+
+use super::secrets::{SecretIdent, SecretInStorage, SecretStore};
+use super::sigv4_verification::{hex, hmac_sha256};
+use super::token_verification::{AuthDetails, MAX_SIGNED_BODY_BYTES};
+use crate::request_metadata::RequestMetadata;
+
+pub const KEY_ID_HEADER: &str = "x-iceberg-key-id";
+pub const TIMESTAMP_HEADER: &str = "x-iceberg-timestamp";
+pub const NONCE_HEADER: &str = "x-iceberg-nonce";
+pub const SIGNATURE_HEADER: &str = "x-iceberg-signature";
+/// Maximum difference between the signing time of a request and the server time.
+pub const MAX_CLOCK_SKEW: chrono::Duration = chrono::Duration::minutes(5);
+const MAX_NONCE_LENGTH: usize = 128;
+
+/// Shared secret of a service, stored in the secret store.
+#[derive(Redact, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ServiceKey {
+    #[redact]
+    pub hmac_secret: String,
+    /// Principal the service is authenticated as.
+    pub principal: String,
+}
+
+impl SecretInStorage for ServiceKey {}
+
+impl ServiceKey {
+    /// Create a key with a random secret for the given principal.
+    ///
+    /// # Errors
+    /// Fails if no random bytes can be generated.
+    pub fn generate(principal: String) -> crate::api::Result<Self> {
+        let mut secret = [0u8; 32];
+        openssl::rand::rand_bytes(&mut secret).map_err(|e| {
+            ErrorModel::builder()
+                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message("Failed to generate service key.".to_string())
+                .r#type("ServiceKeyGenerationError".to_string())
+                .stack(Some(vec![e.to_string()]))
+                .build()
+        })?;
+
+        Ok(Self {
+            hmac_secret: hex(&secret),
+            principal,
+        })
+    }
+
+    /// Signature of a request, hex encoded.
+    ///
+    /// # Errors
+    /// Fails if the HMAC cannot be computed.
+    pub fn sign(
+        &self,
+        method: &http::Method,
+        path_and_query: &str,
+        timestamp: i64,
+        nonce: &str,
+        body: &[u8],
+    ) -> Result<String, openssl::error::ErrorStack> {
+        let string_to_sign = format!(
+            "{method}\n{path_and_query}\n{timestamp}\n{nonce}\n{}",
+            hex(&openssl::sha::sha256(body))
+        );
+        hmac_sha256(self.hmac_secret.as_bytes(), string_to_sign.as_bytes()).map(|s| hex(&s))
+    }
+}
+
+/// Generate a service key for `principal` and store it in the secret store.
+/// Returns the id of the key together with the key.
+///
+/// # Errors
+/// Fails if the principal is empty or the key cannot be generated or stored.
+pub async fn create_service_key<S: SecretStore>(
+    principal: String,
+    secrets_state: S::State,
+) -> crate::api::Result<(SecretIdent, ServiceKey)> {
+    if principal.is_empty() {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("Principal must not be empty.".to_string())
+            .r#type("InvalidPrincipal".to_string())
+            .build()
+            .into());
+    }
+
+    let key = ServiceKey::generate(principal)?;
+    let key_id = S::create_secret(key.clone(), secrets_state).await?;
+    Ok((key_id, key))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceKeyClaims {
+    pub key_id: SecretIdent,
+    pub principal: String,
+}
+
+/// Nonces seen within the last [`MAX_CLOCK_SKEW`], with the time they can be forgotten.
+#[derive(Debug, Default)]
+struct NonceCache(HashMap<(SecretIdent, String), chrono::DateTime<chrono::Utc>>);
+
+impl NonceCache {
+    /// Returns false if the nonce was already used.
+    fn insert(
+        &mut self,
+        key_id: &SecretIdent,
+        nonce: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> bool {
+        self.0.retain(|_, forget_at| *forget_at > now);
+        self.0
+            .insert(
+                (key_id.clone(), nonce.to_string()),
+                now + MAX_CLOCK_SKEW * 2,
+            )
+            .is_none()
+    }
+}
+
+pub struct HmacVerifier<S: SecretStore> {
+    secrets_state: S::State,
+    nonces: Arc<Mutex<NonceCache>>,
+    /// Reject requests without service key headers.
+    /// If false, they are passed on to token verification.
+    required: bool,
+}
+
+impl<S: SecretStore> Clone for HmacVerifier<S> {
+    fn clone(&self) -> Self {
+        Self {
+            secrets_state: self.secrets_state.clone(),
+            nonces: self.nonces.clone(),
+            required: self.required,
+        }
+    }
+}
+
+impl<S: SecretStore> Debug for HmacVerifier<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HmacVerifier")
+            .field("required", &self.required)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Headers of a request signed with a service key.
+#[derive(Debug)]
+struct SignatureHeaders<'a> {
+    key_id: SecretIdent,
+    timestamp: i64,
+    nonce: &'a str,
+    signature: &'a str,
+}
+
+impl<'a> SignatureHeaders<'a> {
+    fn parse(headers: &'a HeaderMap) -> Result<Self, ErrorModel> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .ok_or_else(|| unauthorized(&format!("Missing or invalid header '{name}'.")))
+        };
+
+        Ok(Self {
+            key_id: uuid::Uuid::parse_str(header(KEY_ID_HEADER)?)
+                .map_err(|_| unauthorized("Invalid service key id."))?
+                .into(),
+            timestamp: header(TIMESTAMP_HEADER)?
+                .parse()
+                .map_err(|_| unauthorized("Invalid timestamp."))?,
+            nonce: header(NONCE_HEADER)?,
+            signature: header(SIGNATURE_HEADER)?,
+        })
+    }
+}
+
+impl<S: SecretStore> HmacVerifier<S> {
+    #[must_use]
+    pub fn new(secrets_state: S::State, required: bool) -> Self {
+        Self {
+            secrets_state,
+            nonces: Arc::default(),
+            required,
+        }
+    }
+
+    async fn verify(
+        &self,
+        headers: &SignatureHeaders<'_>,
+        method: &http::Method,
+        path_and_query: &str,
+        body: &[u8],
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<ServiceKeyClaims, ErrorModel> {
+        let signed_at = chrono::DateTime::from_timestamp(headers.timestamp, 0)
+            .ok_or_else(|| unauthorized("Invalid timestamp."))?;
+        if (now - signed_at).abs() > MAX_CLOCK_SKEW {
+            return Err(unauthorized("Request signature expired."));
+        }
+        if headers.nonce.is_empty() || headers.nonce.len() > MAX_NONCE_LENGTH {
+            return Err(unauthorized("Invalid nonce."));
+        }
+
+        let key = S::get_secret_by_id::<ServiceKey>(&headers.key_id, self.secrets_state.clone())
+            .await
+            .map_err(|e| {
+                tracing::debug!(
+                    "Failed to load service key {}: {:?}",
+                    headers.key_id,
+                    e.error
+                );
+                unauthorized("Unknown service key.")
+            })?
+            .secret;
+
+        let expected = key
+            .sign(
+                method,
+                path_and_query,
+                headers.timestamp,
+                headers.nonce,
+                body,
+            )
+            .map_err(|e| {
+                ErrorModel::builder()
+                    .message("Failed to compute request signature.")
+                    .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                    .r#type("InternalServerError")
+                    .stack(Some(vec![e.to_string()]))
+                    .build()
+            })?;
+        if expected.len() != headers.signature.len()
+            || !openssl::memcmp::eq(expected.as_bytes(), headers.signature.as_bytes())
+        {
+            return Err(unauthorized("Request signature does not match."));
+        }
+
+        // Only remember nonces of valid signatures, so that they cannot be blocked by others.
+        let fresh = self
+            .nonces
+            .lock()
+            .map_err(|_| unauthorized("Nonce cache unavailable."))?
+            .insert(&headers.key_id, headers.nonce, now);
+        if !fresh {
+            return Err(unauthorized("Nonce has already been used."));
+        }
+
+        Ok(ServiceKeyClaims {
+            key_id: headers.key_id.clone(),
+            principal: key.principal,
+        })
+    }
+}
+
+pub(crate) async fn hmac_auth_middleware_fn<S: SecretStore>(
+    State(verifier): State<HmacVerifier<S>>,
+    Extension(mut metadata): Extension<RequestMetadata>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !request.headers().contains_key(KEY_ID_HEADER) {
+        if verifier.required {
+            return IcebergErrorResponse::from(unauthorized("Missing service key headers."))
+                .into_response();
+        }
+        return next.run(request).await;
+    }
+
+    let (parts, body) = request.into_parts();
+    let headers = match SignatureHeaders::parse(&parts.headers) {
+        Ok(headers) => headers,
+        Err(err) => return IcebergErrorResponse::from(err).into_response(),
+    };
+
+    let body = match axum::body::to_bytes(body, MAX_SIGNED_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            return IcebergErrorResponse::from(
+                ErrorModel::builder()
+                    .message("Failed to read body of signed request.")
+                    .code(StatusCode::PAYLOAD_TOO_LARGE.into())
+                    .r#type("PayloadTooLarge")
+                    .stack(Some(vec![e.to_string()]))
+                    .build(),
+            )
+            .into_response()
+        }
+    };
+    let path_and_query = parts
+        .uri
+        .path_and_query()
+        .map_or(parts.uri.path(), http::uri::PathAndQuery::as_str);
+
+    match verifier
+        .verify(
+            &headers,
+            &parts.method,
+            path_and_query,
+            &body,
+            chrono::Utc::now(),
+        )
+        .await
+    {
+        Ok(claims) => metadata.auth_details = Some(AuthDetails::ServiceKey(claims)),
+        Err(err) => {
+            tracing::debug!("Failed to verify service key signature: {:?}", err);
+            return IcebergErrorResponse::from(err).into_response();
+        }
+    }
+
+    let mut request = Request::from_parts(parts, Body::from(body));
+    request.extensions_mut().insert(metadata);
+    next.run(request).await
+}
+
+fn unauthorized(message: &str) -> ErrorModel {
+    ErrorModel::builder()
+        .message(message)
+        .code(StatusCode::UNAUTHORIZED.into())
+        .r#type("UnauthorizedError")
+        .build()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        let key = ServiceKey {
+            hmac_secret: "secret".to_string(),
+            principal: "ingest".to_string(),
+        };
+        let sign = |method: &http::Method, path: &str, nonce: &str, body: &[u8]| {
+            key.sign(method, path, 1_700_000_000, nonce, body).unwrap()
+        };
+        let signature = sign(&http::Method::POST, "/catalog/v1/config", "n1", b"{}");
+        assert_eq!(signature.len(), 64);
+        assert_eq!(
+            signature,
+            sign(&http::Method::POST, "/catalog/v1/config", "n1", b"{}")
+        );
+        assert_ne!(
+            signature,
+            sign(&http::Method::GET, "/catalog/v1/config", "n1", b"{}")
+        );
+        assert_ne!(
+            signature,
+            sign(&http::Method::POST, "/catalog/v1/config", "n2", b"{}")
+        );
+        assert_ne!(
+            signature,
+            sign(&http::Method::POST, "/catalog/v1/config", "n1", b"")
+        );
+    }
+
+    #[test]
+    fn test_nonce_cache() {
+        let mut cache = NonceCache::default();
+        let key_id = SecretIdent::from(uuid::Uuid::now_v7());
+        let now = chrono::Utc::now();
+        assert!(cache.insert(&key_id, "n1", now));
+        assert!(!cache.insert(&key_id, "n1", now));
+        assert!(cache.insert(&SecretIdent::from(uuid::Uuid::now_v7()), "n1", now));
+        // Forgotten once a replay would be rejected because of its timestamp
+        assert!(cache.insert(&key_id, "n1", now + MAX_CLOCK_SKEW * 3));
+    }
+
+    #[tokio::test]
+    async fn test_bootstrapped_key_authenticates() {
+        use crate::implementations::memory::{MemorySecretStore, MemorySecretStoreState};
+        use tower::ServiceExt;
+
+        let secrets_state = MemorySecretStoreState::default();
+        let router = axum::Router::new()
+            .route(
+                "/management/v1/info",
+                axum::routing::get(
+                    |Extension(metadata): Extension<RequestMetadata>| async move {
+                        metadata.principal().unwrap_or_default().to_string()
+                    },
+                ),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                HmacVerifier::<MemorySecretStore>::new(secrets_state.clone(), true),
+                hmac_auth_middleware_fn::<MemorySecretStore>,
+            ))
+            .layer(Extension(RequestMetadata::new_random()));
+        let request = || http::Request::builder().uri("/management/v1/info");
+
+        let response = router
+            .clone()
+            .oneshot(request().body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        create_service_key::<MemorySecretStore>(String::new(), secrets_state.clone())
+            .await
+            .unwrap_err();
+        let (key_id, key) =
+            create_service_key::<MemorySecretStore>("admin".to_string(), secrets_state)
+                .await
+                .unwrap();
+        let timestamp = chrono::Utc::now().timestamp();
+        let signature = key
+            .sign(
+                &http::Method::GET,
+                "/management/v1/info",
+                timestamp,
+                "n1",
+                b"",
+            )
+            .unwrap();
+        let response = router
+            .oneshot(
+                request()
+                    .header(KEY_ID_HEADER, key_id.to_string())
+                    .header(TIMESTAMP_HEADER, timestamp.to_string())
+                    .header(NONCE_HEADER, "n1")
+                    .header(SIGNATURE_HEADER, signature)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body.as_ref(), b"admin");
+    }
+}
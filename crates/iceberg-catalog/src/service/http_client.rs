@@ -0,0 +1,144 @@
+//! Proxy and CA settings of outbound HTTP requests.
+//!
+//! Requests sent by the catalog itself, such as the `OpenID` discovery, use [`http_client`].
+//! It routes requests through `http_proxy` and `https_proxy`, except for hosts listed in
+//! `no_proxy`, and trusts the certificates of `ca_bundle` in addition to the system roots.
+//!
+//! Clients created by dependencies, such as the S3 client of `FileIO` used for storage
+//! validation and the JWKS client, read the standard `HTTP_PROXY`, `HTTPS_PROXY`, `NO_PROXY`
+//! and `SSL_CERT_FILE` environment variables instead. [`export_proxy_env`] sets them from the
+//! config. `SSL_CERT_FILE` replaces the default CA file of these clients, so the bundle must
+//! contain public CAs as well if public endpoints are called.
+use std::path::Path;
+
+use anyhow::Context;
+
+use crate::config::DynAppConfig;
+use crate::CONFIG;
+
+lazy_static::lazy_static! {
+    static ref HTTP_CLIENT: reqwest::Client = client_builder()
+        .and_then(|builder| Ok(builder.build()?))
+        .expect("Proxy and CA bundle are validated with the config");
+}
+
+/// Client for outbound requests, configured with the proxy and CA settings.
+#[must_use]
+pub fn http_client() -> reqwest::Client {
+    HTTP_CLIENT.clone()
+}
+
+/// Builder of a client with the proxy and CA settings, for callers that need
+/// further options such as timeouts.
+///
+/// # Errors
+/// Fails if a proxy URL or the CA bundle is invalid.
+pub fn client_builder() -> anyhow::Result<reqwest::ClientBuilder> {
+    configure(reqwest::Client::builder(), &CONFIG)
+}
+
+fn configure(
+    mut builder: reqwest::ClientBuilder,
+    config: &DynAppConfig,
+) -> anyhow::Result<reqwest::ClientBuilder> {
+    let no_proxy = || reqwest::NoProxy::from_string(&config.no_proxy.join(","));
+    if let Some(proxy) = &config.http_proxy {
+        builder = builder.proxy(reqwest::Proxy::http(proxy.as_str())?.no_proxy(no_proxy()));
+    }
+    if let Some(proxy) = &config.https_proxy {
+        builder = builder.proxy(reqwest::Proxy::https(proxy.as_str())?.no_proxy(no_proxy()));
+    }
+    if let Some(ca_bundle) = &config.ca_bundle {
+        for certificate in load_ca_bundle(ca_bundle)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    Ok(builder)
+}
+
+/// Certificates of a PEM bundle.
+///
+/// # Errors
+/// Fails if the file can't be read or contains no valid certificates.
+pub(crate) fn load_ca_bundle(path: &Path) -> anyhow::Result<Vec<reqwest::Certificate>> {
+    let pem = std::fs::read(path).with_context(|| format!("Can't read '{}'", path.display()))?;
+    let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+        .with_context(|| format!("'{}' is not a valid PEM bundle", path.display()))?;
+    if certificates.is_empty() {
+        anyhow::bail!("'{}' contains no certificates", path.display());
+    }
+    Ok(certificates)
+}
+
+/// Set the standard proxy and CA environment variables from the config, so that
+/// clients of dependencies use the same settings. Variables are only set for
+/// configured options; values already present in the environment are kept otherwise.
+///
+/// Modifying the environment is not thread-safe, so this must be called at startup,
+/// before a runtime or other threads are started.
+pub fn export_proxy_env() {
+    for (var, value) in proxy_env(&CONFIG) {
+        std::env::set_var(var, value);
+    }
+}
+
+fn proxy_env(config: &DynAppConfig) -> Vec<(&'static str, String)> {
+    [
+        (
+            "HTTP_PROXY",
+            config.http_proxy.as_ref().map(ToString::to_string),
+        ),
+        (
+            "HTTPS_PROXY",
+            config.https_proxy.as_ref().map(ToString::to_string),
+        ),
+        (
+            "NO_PROXY",
+            (!config.no_proxy.is_empty()).then(|| config.no_proxy.join(",")),
+        ),
+        (
+            "SSL_CERT_FILE",
+            config
+                .ca_bundle
+                .as_ref()
+                .map(|path| path.display().to_string()),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(var, value)| value.map(|value| (var, value)))
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_proxy_env() {
+        let config = DynAppConfig {
+            https_proxy: Some("http://proxy.internal:3128".parse().unwrap()),
+            no_proxy: vec!["localhost".to_string(), ".svc.cluster.local".to_string()],
+            ..DynAppConfig::default()
+        };
+        assert_eq!(
+            proxy_env(&config),
+            vec![
+                ("HTTPS_PROXY", "http://proxy.internal:3128/".to_string()),
+                ("NO_PROXY", "localhost,.svc.cluster.local".to_string()),
+            ]
+        );
+        assert!(configure(reqwest::Client::builder(), &config)
+            .unwrap()
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_load_ca_bundle() {
+        let path = std::env::temp_dir().join(format!("ca-bundle-{}.pem", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "not a certificate").unwrap();
+        assert!(load_ca_bundle(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert!(load_ca_bundle(&path).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+//! Lineage of snapshots, taken from the fields engines write into snapshot summaries.
+//!
+//! For every snapshot added by a commit that carries at least one lineage field, a
+//! `lineage` event is published. Downstream metadata platforms can subscribe to these
+//! events instead of parsing metadata files. If `store_lineage_events` is enabled, the
+//! events are additionally stored in the [`LineageStore`]. They are stored after the commit,
+//! a commit succeeds even if storing its lineage fails.
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use iceberg::spec::Snapshot;
+use iceberg_ext::spec::TableMetadata;
+
+use super::commit_diff::CommitDiff;
+use super::TableIdentUuid;
+use crate::api::Result;
+use crate::WarehouseIdent;
+
+/// Id of the Spark application that wrote the snapshot.
+pub const SPARK_APP_ID: &str = "spark.app.id";
+/// Id of the write-audit-publish workflow that staged the snapshot.
+pub const WAP_ID: &str = "wap.id";
+/// Set on the snapshot created when a staged WAP snapshot is published.
+pub const PUBLISHED_WAP_ID: &str = "published-wap-id";
+/// Comma-separated tables the snapshot was derived from, i.e. `db.orders,db.customers`.
+/// Not written by engines themselves, jobs can set it via the snapshot properties of the write.
+pub const SOURCE_TABLES: &str = "lineage.source-tables";
+pub const ENGINE_NAME: &str = "engine-name";
+pub const ENGINE_VERSION: &str = "engine-version";
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LineageEvent {
+    pub snapshot_id: i64,
+    pub parent_snapshot_id: Option<i64>,
+    /// Milliseconds since epoch the snapshot was created at, as set by the engine.
+    pub timestamp_ms: i64,
+    /// `operation` of the snapshot summary, e.g. `append`.
+    pub operation: String,
+    pub spark_app_id: Option<String>,
+    /// Id of the WAP workflow that staged or published the snapshot.
+    pub wap_id: Option<String>,
+    /// Whether the snapshot published a staged WAP snapshot.
+    pub wap_published: bool,
+    pub source_tables: Vec<String>,
+    pub engine_name: Option<String>,
+    pub engine_version: Option<String>,
+}
+
+impl LineageEvent {
+    /// Lineage of `snapshot`. `None` if its summary carries no lineage fields.
+    #[must_use]
+    pub fn from_snapshot(snapshot: &Snapshot) -> Option<Self> {
+        let summary = &snapshot.summary().other;
+        let field = |key: &str| {
+            summary
+                .get(key)
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+
+        let spark_app_id = field(SPARK_APP_ID);
+        let published_wap_id = field(PUBLISHED_WAP_ID);
+        let wap_published = published_wap_id.is_some();
+        let wap_id = published_wap_id.or_else(|| field(WAP_ID));
+        let source_tables = source_tables(summary);
+        if spark_app_id.is_none() && wap_id.is_none() && source_tables.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            snapshot_id: snapshot.snapshot_id(),
+            parent_snapshot_id: snapshot.parent_snapshot_id(),
+            timestamp_ms: snapshot.timestamp_ms(),
+            operation: serde_json::to_value(&snapshot.summary().operation)
+                .ok()
+                .and_then(|operation| operation.as_str().map(str::to_string))
+                .unwrap_or_default(),
+            spark_app_id,
+            wap_id,
+            wap_published,
+            source_tables,
+            engine_name: field(ENGINE_NAME),
+            engine_version: field(ENGINE_VERSION),
+        })
+    }
+
+    /// Lineage of the snapshots a commit added, ordered by snapshot id.
+    #[must_use]
+    pub fn from_commit(previous: &TableMetadata, current: &TableMetadata) -> Vec<Self> {
+        CommitDiff::new(previous, current)
+            .added_snapshot_ids
+            .iter()
+            .filter_map(|snapshot_id| current.snapshots.get(snapshot_id))
+            .filter_map(|snapshot| Self::from_snapshot(snapshot))
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+pub trait LineageStore: Debug + Send + Sync {
+    /// Store lineage events of snapshots of `table_id` in `warehouse_id`.
+    /// Events of snapshots that are already stored are ignored.
+    async fn record_lineage_events(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        table_id: TableIdentUuid,
+        events: &[LineageEvent],
+    ) -> Result<()>;
+}
+
+fn source_tables(summary: &HashMap<String, String>) -> Vec<String> {
+    let mut tables = summary
+        .get(SOURCE_TABLES)
+        .map(|tables| {
+            tables
+                .split(',')
+                .map(str::trim)
+                .filter(|table| !table.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    tables.sort();
+    tables.dedup();
+    tables
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn snapshot(summary: serde_json::Value) -> Snapshot {
+        serde_json::from_value(serde_json::json!({
+            "snapshot-id": 2,
+            "parent-snapshot-id": 1,
+            "sequence-number": 2,
+            "timestamp-ms": 1_700_000_000_000_i64,
+            "manifest-list": "s3://bucket/table/metadata/snap-2.avro",
+            "summary": summary,
+            "schema-id": 0
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_from_snapshot() {
+        let event = LineageEvent::from_snapshot(&snapshot(serde_json::json!({
+            "operation": "overwrite",
+            "spark.app.id": "local-1700000000000",
+            "lineage.source-tables": "db.orders, db.customers,,db.orders",
+            "engine-name": "spark",
+            "engine-version": "3.5.1",
+            "added-records": "10"
+        })))
+        .unwrap();
+        assert_eq!(
+            event,
+            LineageEvent {
+                snapshot_id: 2,
+                parent_snapshot_id: Some(1),
+                timestamp_ms: 1_700_000_000_000,
+                operation: "overwrite".to_string(),
+                spark_app_id: Some("local-1700000000000".to_string()),
+                wap_id: None,
+                wap_published: false,
+                source_tables: vec!["db.customers".to_string(), "db.orders".to_string()],
+                engine_name: Some("spark".to_string()),
+                engine_version: Some("3.5.1".to_string()),
+            }
+        );
+
+        let event = LineageEvent::from_snapshot(&snapshot(serde_json::json!({
+            "operation": "append",
+            "published-wap-id": "audit-42"
+        })))
+        .unwrap();
+        assert_eq!(event.wap_id.as_deref(), Some("audit-42"));
+        assert!(event.wap_published);
+
+        assert!(LineageEvent::from_snapshot(&snapshot(serde_json::json!({
+            "operation": "append",
+            "engine-name": "spark",
+            "spark.app.id": " "
+        })))
+        .is_none());
+    }
+}
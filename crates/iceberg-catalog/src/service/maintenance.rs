@@ -0,0 +1,145 @@
+//! Server-wide maintenance mode.
+//!
+//! While maintenance mode is enabled, all mutating requests are rejected with
+//! `503 Service Unavailable` and a `Retry-After` header. Reads continue to be served,
+//! so that operators can i.e. back up Postgres without stopping readers.
+//! The flag is local to this instance.
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use http::{header, HeaderValue, Method, StatusCode};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::api::{ErrorModel, IcebergErrorResponse};
+
+/// `POST` endpoints that do not modify the catalog.
+const NON_MUTATING_POST_SUFFIXES: &[&str] = &[
+    "/aws/s3/sign",
+    "/metrics",
+    "/oauth/tokens",
+    "/management/v1/graphql",
+    "/table/bulk-load",
+];
+/// Path of the management endpoint that toggles maintenance mode.
+/// It must remain reachable while maintenance mode is enabled.
+pub(crate) const MAINTENANCE_PATH: &str = "/management/v1/maintenance";
+/// Endpoints that configure this instance instead of the catalog.
+/// They remain reachable while maintenance mode is enabled.
+const SERVER_PATHS: &[&str] = &[
+    MAINTENANCE_PATH,
+    "/management/v1/config/reload",
+    "/management/v1/server/log-level",
+];
+
+#[derive(Debug, Clone)]
+pub struct MaintenanceMode {
+    enabled: Arc<AtomicBool>,
+    retry_after_seconds: Arc<AtomicU64>,
+}
+
+impl MaintenanceMode {
+    #[must_use]
+    pub fn new(enabled: bool, retry_after_seconds: u64) -> Self {
+        Self {
+            enabled: Arc::new(AtomicBool::new(enabled)),
+            retry_after_seconds: Arc::new(AtomicU64::new(retry_after_seconds)),
+        }
+    }
+
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    #[must_use]
+    pub fn retry_after_seconds(&self) -> u64 {
+        self.retry_after_seconds.load(Ordering::Relaxed)
+    }
+
+    /// Enable or disable maintenance mode.
+    /// If `retry_after_seconds` is `None`, the current value is kept.
+    pub fn set(&self, enabled: bool, retry_after_seconds: Option<u64>) {
+        if let Some(retry_after_seconds) = retry_after_seconds {
+            self.retry_after_seconds
+                .store(retry_after_seconds, Ordering::Relaxed);
+        }
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+}
+
+fn is_mutating_request(method: &Method, path: &str) -> bool {
+    if SERVER_PATHS.contains(&path) {
+        return false;
+    }
+    match *method {
+        Method::GET | Method::HEAD | Method::OPTIONS => false,
+        Method::POST => !NON_MUTATING_POST_SUFFIXES
+            .iter()
+            .any(|suffix| path.ends_with(suffix)),
+        _ => true,
+    }
+}
+
+pub(crate) async fn maintenance_middleware_fn(
+    State(maintenance): State<MaintenanceMode>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if maintenance.is_enabled() && is_mutating_request(request.method(), request.uri().path()) {
+        let mut response = IcebergErrorResponse::from(
+            ErrorModel::builder()
+                .code(StatusCode::SERVICE_UNAVAILABLE.into())
+                .message("The catalog is in maintenance mode. Only reads are served.")
+                .r#type("MaintenanceMode")
+                .build(),
+        )
+        .into_response();
+        response.headers_mut().insert(
+            header::RETRY_AFTER,
+            HeaderValue::from(maintenance.retry_after_seconds()),
+        );
+        return response;
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_mutating_request() {
+        let prefix = "/catalog/v1/my-warehouse";
+        assert!(!is_mutating_request(
+            &Method::GET,
+            &format!("{prefix}/namespaces/ns/tables/t")
+        ));
+        assert!(is_mutating_request(
+            &Method::POST,
+            &format!("{prefix}/namespaces/ns/tables/t")
+        ));
+        assert!(is_mutating_request(
+            &Method::DELETE,
+            &format!("{prefix}/namespaces/ns/tables/t")
+        ));
+        assert!(!is_mutating_request(
+            &Method::POST,
+            &format!("{prefix}/v1/aws/s3/sign")
+        ));
+        assert!(!is_mutating_request(&Method::POST, MAINTENANCE_PATH));
+        assert!(!is_mutating_request(
+            &Method::PUT,
+            "/management/v1/server/log-level"
+        ));
+        assert!(!is_mutating_request(
+            &Method::POST,
+            "/management/v1/graphql"
+        ));
+        assert!(is_mutating_request(
+            &Method::POST,
+            "/management/v1/warehouse"
+        ));
+    }
+}
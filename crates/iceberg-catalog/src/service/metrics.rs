@@ -0,0 +1,259 @@
+//! Metrics of the catalog.
+//!
+//! Metrics are recorded through the [`metrics`] facade. They are only exported if the
+//! binary installs a recorder, such as the Prometheus exporter of the default binary.
+use serde::Deserialize;
+
+use super::resolution_cache::ResolutionCache;
+use super::{Catalog, TableIdent};
+use crate::WarehouseIdent;
+
+/// Counter of operations per warehouse, labeled with `warehouse_id`, `warehouse_name`
+/// and `operation`.
+pub const WAREHOUSE_OPERATIONS_TOTAL: &str = "iceberg_catalog_warehouse_operations_total";
+
+/// Data files matched by scans reported by engines, labeled with `warehouse_id`,
+/// `warehouse_name` and `table`. The same holds for all report metrics below.
+pub const SCAN_RESULT_DATA_FILES_TOTAL: &str = "iceberg_catalog_scan_result_data_files_total";
+/// Delete files matched by reported scans.
+pub const SCAN_RESULT_DELETE_FILES_TOTAL: &str = "iceberg_catalog_scan_result_delete_files_total";
+/// Histogram of the planning duration of reported scans.
+pub const SCAN_PLANNING_DURATION_SECONDS: &str = "iceberg_catalog_scan_planning_duration_seconds";
+/// Records added by reported commits.
+pub const COMMIT_ADDED_RECORDS_TOTAL: &str = "iceberg_catalog_commit_added_records_total";
+/// Data files added by reported commits.
+pub const COMMIT_ADDED_DATA_FILES_TOTAL: &str = "iceberg_catalog_commit_added_data_files_total";
+/// Histogram of the duration of reported commits, including retries.
+pub const COMMIT_DURATION_SECONDS: &str = "iceberg_catalog_commit_duration_seconds";
+/// Histogram of the duration of calls to the catalog backend, labeled with `operation`
+/// and `status`. Recorded by [`super::catalog_layer::MetricsLayer`].
+pub const CATALOG_OPERATION_DURATION_SECONDS: &str =
+    "iceberg_catalog_catalog_operation_duration_seconds";
+
+/// Operation counted in [`WAREHOUSE_OPERATIONS_TOTAL`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum WarehouseOperation {
+    LoadTable,
+    /// Counted once per table, also for transactions spanning multiple tables.
+    CommitTable,
+    DropTable,
+    /// Request signed by the S3 signer, or a pre-signed URL.
+    Sign,
+    /// Storage credentials handed out via `loadTable` or the credentials endpoint.
+    VendCredentials,
+}
+
+/// Count `count` successful operations in a warehouse.
+/// The name of the warehouse is taken from the [`ResolutionCache`]
+/// and left empty if it can't be resolved.
+pub async fn count_warehouse_operation<C: Catalog>(
+    warehouse_id: &WarehouseIdent,
+    operation: WarehouseOperation,
+    count: u64,
+    resolution_cache: &ResolutionCache,
+    catalog_state: C::State,
+) {
+    let warehouse_name = warehouse_name::<C>(warehouse_id, resolution_cache, catalog_state).await;
+    metrics::counter!(
+        WAREHOUSE_OPERATIONS_TOTAL,
+        "warehouse_id" => warehouse_id.to_string(),
+        "warehouse_name" => warehouse_name,
+        "operation" => operation.to_string(),
+    )
+    .increment(count);
+}
+
+async fn warehouse_name<C: Catalog>(
+    warehouse_id: &WarehouseIdent,
+    resolution_cache: &ResolutionCache,
+    catalog_state: C::State,
+) -> String {
+    resolution_cache
+        .warehouse::<C>(warehouse_id, catalog_state)
+        .await
+        .ok()
+        .flatten()
+        .map(|warehouse| warehouse.name.clone())
+        .unwrap_or_default()
+}
+
+/// Report sent by engines to the `metrics` endpoint of a table.
+/// Only the figures exported as metrics are parsed, everything else is ignored.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "report-type", rename_all = "kebab-case")]
+pub enum MetricsReport {
+    ScanReport { metrics: ScanMetrics },
+    CommitReport { metrics: CommitMetrics },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ScanMetrics {
+    pub result_data_files: Option<CounterResult>,
+    pub result_delete_files: Option<CounterResult>,
+    pub total_planning_duration: Option<TimerResult>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CommitMetrics {
+    pub added_records: Option<CounterResult>,
+    pub added_data_files: Option<CounterResult>,
+    pub total_duration: Option<TimerResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CounterResult {
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TimerResult {
+    /// Name of a Java `TimeUnit`, i.e. `nanoseconds`.
+    pub time_unit: String,
+    pub total_duration: u64,
+}
+
+impl TimerResult {
+    /// Total duration in seconds. `None` if the time unit is unknown.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn seconds(&self) -> Option<f64> {
+        let seconds_per_unit = match self.time_unit.to_lowercase().as_str() {
+            "nanoseconds" => 1e-9,
+            "microseconds" => 1e-6,
+            "milliseconds" => 1e-3,
+            "seconds" => 1.0,
+            "minutes" => 60.0,
+            "hours" => 3600.0,
+            "days" => 86400.0,
+            _ => return None,
+        };
+        Some(self.total_duration as f64 * seconds_per_unit)
+    }
+}
+
+/// Export the key figures of a report of `table` as metrics.
+pub async fn record_metrics_report<C: Catalog>(
+    warehouse_id: &WarehouseIdent,
+    table: &TableIdent,
+    report: &MetricsReport,
+    resolution_cache: &ResolutionCache,
+    catalog_state: C::State,
+) {
+    let labels = vec![
+        ("warehouse_id", warehouse_id.to_string()),
+        (
+            "warehouse_name",
+            warehouse_name::<C>(warehouse_id, resolution_cache, catalog_state).await,
+        ),
+        (
+            "table",
+            format!(
+                "{}.{}",
+                table.namespace.clone().inner().join("."),
+                table.name
+            ),
+        ),
+    ];
+
+    match report {
+        MetricsReport::ScanReport { metrics: scan } => {
+            if let Some(files) = &scan.result_data_files {
+                metrics::counter!(SCAN_RESULT_DATA_FILES_TOTAL, labels.clone())
+                    .increment(files.value);
+            }
+            if let Some(files) = &scan.result_delete_files {
+                metrics::counter!(SCAN_RESULT_DELETE_FILES_TOTAL, labels.clone())
+                    .increment(files.value);
+            }
+            if let Some(seconds) = scan
+                .total_planning_duration
+                .as_ref()
+                .and_then(TimerResult::seconds)
+            {
+                metrics::histogram!(SCAN_PLANNING_DURATION_SECONDS, labels.clone()).record(seconds);
+            }
+        }
+        MetricsReport::CommitReport { metrics: commit } => {
+            if let Some(records) = &commit.added_records {
+                metrics::counter!(COMMIT_ADDED_RECORDS_TOTAL, labels.clone())
+                    .increment(records.value);
+            }
+            if let Some(files) = &commit.added_data_files {
+                metrics::counter!(COMMIT_ADDED_DATA_FILES_TOTAL, labels.clone())
+                    .increment(files.value);
+            }
+            if let Some(seconds) = commit
+                .total_duration
+                .as_ref()
+                .and_then(TimerResult::seconds)
+            {
+                metrics::histogram!(COMMIT_DURATION_SECONDS, labels.clone()).record(seconds);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_reports() {
+        let scan = serde_json::json!({
+            "report-type": "scan-report",
+            "table-name": "my_catalog.ns.tbl",
+            "snapshot-id": 3_497_810_964_824_022_504_i64,
+            "filter": true,
+            "schema-id": 0,
+            "projected-field-ids": [1],
+            "projected-field-names": ["id"],
+            "metrics": {
+                "result-data-files": {"unit": "count", "value": 12},
+                "result-delete-files": {"unit": "count", "value": 0},
+                "total-planning-duration": {
+                    "count": 1,
+                    "time-unit": "nanoseconds",
+                    "total-duration": 2_644_235_116_u64
+                },
+                "skipped-data-manifests": {"unit": "count", "value": 3}
+            }
+        });
+        let MetricsReport::ScanReport { metrics } =
+            serde_json::from_value::<MetricsReport>(scan).unwrap()
+        else {
+            panic!("expected a scan report");
+        };
+        assert_eq!(metrics.result_data_files, Some(CounterResult { value: 12 }));
+        let seconds = metrics.total_planning_duration.unwrap().seconds().unwrap();
+        assert!((seconds - 2.644_235_116).abs() < 1e-9);
+
+        let commit = serde_json::json!({
+            "report-type": "commit-report",
+            "table-name": "my_catalog.ns.tbl",
+            "snapshot-id": 1,
+            "sequence-number": 2,
+            "operation": "append",
+            "metrics": {
+                "added-records": {"unit": "count", "value": 1000},
+                "total-duration": {"count": 1, "time-unit": "milliseconds", "total-duration": 250}
+            }
+        });
+        let MetricsReport::CommitReport { metrics } =
+            serde_json::from_value::<MetricsReport>(commit).unwrap()
+        else {
+            panic!("expected a commit report");
+        };
+        assert_eq!(metrics.added_records, Some(CounterResult { value: 1000 }));
+        assert_eq!(metrics.added_data_files, None);
+        assert_eq!(metrics.total_duration.unwrap().seconds(), Some(0.25));
+
+        assert!(serde_json::from_value::<MetricsReport>(
+            serde_json::json!({"report-type": "unknown", "metrics": {}})
+        )
+        .is_err());
+    }
+}
@@ -1,28 +1,76 @@
+pub mod api_usage;
 pub mod auth;
 mod catalog;
+pub mod catalog_layer;
+pub mod catalog_token;
+pub mod client_usage;
+pub mod commit_diff;
+pub mod commit_hook;
+pub mod commit_queue;
 pub mod config;
 pub mod contract_verification;
+pub mod credential_rotation;
 pub mod event_publisher;
+pub mod hmac_auth;
+pub mod http_client;
+pub mod lineage;
+pub mod maintenance;
+pub mod metrics;
+pub mod page_token;
+pub mod recent_writes;
+pub mod resolution_cache;
+pub mod roles;
+pub mod runtime_config;
 pub mod secrets;
+pub mod security;
+pub mod signer_token;
+pub mod sigv4_verification;
+pub mod snapshot_expiration;
+pub mod snapshot_summary;
 pub mod storage;
+pub mod storage_usage;
+pub mod storage_validation;
+pub mod stores;
+pub mod table_cache;
+pub mod table_gc;
+pub mod table_limits;
+pub mod token_revocation;
 pub mod token_verification;
+pub mod view_validation;
+#[cfg(feature = "wasm-policies")]
+pub mod wasm_policy;
+pub mod webhook;
 
 pub use catalog::{
     Catalog, CommitTableResponse, CommitTableResponseExt, CommitTransactionRequest,
     CreateNamespaceRequest, CreateNamespaceResponse, CreateTableRequest, CreateTableResponse,
-    GetNamespaceResponse, GetStorageConfigResponse, GetTableMetadataResponse, GetWarehouseResponse,
-    ListNamespacesQuery, ListNamespacesResponse, LoadTableResponse, NamespaceIdent, Result,
-    TableIdent, Transaction, UpdateNamespacePropertiesRequest, UpdateNamespacePropertiesResponse,
+    DroppedTable, EngineTableConfig, EventFilter, GetNamespaceResponse, GetStorageConfigResponse,
+    GetTableMetadataResponse, GetWarehouseResponse, ListNamespacesQuery, ListNamespacesResponse,
+    LoadTableResponse, LoadedTable, MetadataLogEntry, NamespaceIdent, ObjectOwnership, OwnedObject,
+    Project, PyicebergCompat, ResolvedShare, Result, RetentionCandidate, Role, RoleAssignment,
+    RoleObject, RoleTarget, Share, SharedObject, SnapshotRetentionPolicy, StagedTable, TableCommit,
+    TableIdent, TablePolicy, Transaction, UpdateNamespacePropertiesRequest,
+    UpdateNamespacePropertiesResponse, WarehouseAllowlist,
 };
 
 use crate::api::iceberg::v1::Prefix;
 use crate::api::ThreadSafe as ServiceState;
-pub use crate::api::{ErrorModel, IcebergErrorResponse};
+pub use crate::api::{ErrorModel, ErrorType, IcebergErrorResponse};
 use http::StatusCode;
 use std::str::FromStr;
 
+use crate::service::catalog_layer::{CatalogLayer, Layered, LayeredState};
+use crate::service::commit_hook::CommitHooks;
+use crate::service::commit_queue::CommitQueue;
 use crate::service::contract_verification::ContractVerifiers;
 use crate::service::event_publisher::CloudEventsPublisher;
+use crate::service::maintenance::MaintenanceMode;
+use crate::service::recent_writes::RecentWrites;
+use crate::service::resolution_cache::ResolutionCache;
+use crate::service::runtime_config::RuntimeConfigHandle;
+use crate::service::stores::Stores;
+use crate::service::table_cache::TableCache;
+use crate::service::token_revocation::RevocationList;
 pub use secrets::{SecretIdent, SecretStore};
 
 use self::auth::AuthZHandler;
@@ -62,12 +110,65 @@ pub struct State<A: AuthZHandler, C: Catalog, S: SecretStore> {
     pub auth: A::State,
     pub catalog: C::State,
     pub secrets: S::State,
+    pub stores: Stores,
     pub publisher: CloudEventsPublisher,
     pub contract_verifiers: ContractVerifiers,
+    pub commit_hooks: CommitHooks,
+    pub revocations: RevocationList,
+    /// Verifier of bearer tokens, `None` if authentication by token is disabled.
+    /// Used to validate the subject token of token exchange requests.
+    pub token_verifier: Option<token_verification::Verifier>,
+    pub maintenance: MaintenanceMode,
+    pub runtime_config: RuntimeConfigHandle,
+    pub resolution_cache: ResolutionCache,
+    pub table_cache: TableCache,
+    pub commit_queue: CommitQueue,
+    pub recent_writes: RecentWrites,
 }
 
 impl<A: AuthZHandler, C: Catalog, S: SecretStore> ServiceState for State<A, C, S> {}
 
+impl<A: AuthZHandler, C: Catalog, S: SecretStore> State<A, C, S> {
+    /// Wrap the catalog with `layer`, outside of the layers added before.
+    #[must_use]
+    pub fn layer<L: CatalogLayer>(self, layer: L) -> State<A, Layered<C, L>, S> {
+        let State {
+            auth,
+            catalog,
+            secrets,
+            stores,
+            publisher,
+            contract_verifiers,
+            commit_hooks,
+            revocations,
+            token_verifier,
+            maintenance,
+            runtime_config,
+            resolution_cache,
+            table_cache,
+            commit_queue,
+            recent_writes,
+        } = self;
+        State {
+            auth,
+            catalog: LayeredState::new(catalog, layer),
+            secrets,
+            stores,
+            publisher,
+            contract_verifiers,
+            commit_hooks,
+            revocations,
+            token_verifier,
+            maintenance,
+            runtime_config,
+            resolution_cache,
+            table_cache,
+            commit_queue,
+            recent_writes,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct NamespaceIdentUuid(uuid::Uuid);
 
@@ -190,6 +291,7 @@ pub enum WarehouseStatus {
     Inactive,
 }
 
+#[cfg(feature = "sqlx")]
 impl sqlx::postgres::PgHasArrayType for WarehouseStatus {
     fn array_type_info() -> sqlx::postgres::PgTypeInfo {
         sqlx::postgres::PgTypeInfo::with_name("_warehouse_status")
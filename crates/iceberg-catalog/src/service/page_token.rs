@@ -0,0 +1,284 @@
+//! Page tokens for paginated list endpoints.
+//!
+//! A page token holds the keyset position of the last returned item and a hash of the
+//! query it was issued for, signed with `CONFIG.page_token_secret`. Tokens that were
+//! modified, or that are sent with a different query than the one they were issued
+//! for, are rejected, so clients can't craft tokens that skip parts of a listing.
+//!
+//! Tokens are signed JWTs, not encrypted: they are tamper-proof, but the position, i.e.
+//! the key of the last returned item, can be read by anyone holding the token.
+//! Clients must still treat them as opaque, as the encoding may change between versions.
+//!
+//! If no `page_token_secret` is configured, the signing key is derived from the
+//! encryption key of the configured catalog backend. All instances sharing a database
+//! use the same encryption key, so tokens stay valid across instances and restarts.
+//! Rotating the encryption key invalidates all outstanding page tokens.
+//!
+//! The position is applied by the catalog backend (`WHERE name > $position ORDER BY name
+//! LIMIT $page_size + 1`), so listing a page doesn't load the full listing.
+use http::StatusCode;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+use super::sigv4_verification::hex;
+use crate::api::iceberg::types::PageToken;
+use crate::api::{ErrorModel, Result};
+use crate::config::{CatalogBackend, DynAppConfig};
+use crate::CONFIG;
+
+/// Key id in the header of page tokens.
+const PAGE_TOKEN_KEY_ID: &str = "iceberg-catalog-page";
+
+lazy_static::lazy_static! {
+    static ref PAGE_TOKEN_SECRET: Vec<u8> = page_token_secret(&CONFIG);
+}
+
+/// The configured `page_token_secret`, or a key derived from the encryption key of
+/// the catalog backend.
+fn page_token_secret(config: &DynAppConfig) -> Vec<u8> {
+    if let Some(secret) = &config.page_token_secret {
+        return secret.as_bytes().to_vec();
+    }
+    let encryption_key = match config.catalog_backend {
+        CatalogBackend::Postgres => &config.pg_encryption_key,
+        CatalogBackend::Mysql => &config.mysql_encryption_key,
+    };
+    // Domain separated, so page tokens don't reveal anything about the encryption key.
+    openssl::sha::sha256(format!("{PAGE_TOKEN_KEY_ID}\u{1f}{encryption_key}").as_bytes()).to_vec()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct PageTokenClaims {
+    /// Key of the last item of the previous page.
+    position: String,
+    /// Hex encoded `sha256` of the query the token was issued for.
+    query: String,
+}
+
+/// A page of a listing and the token of the next page, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_page_token: Option<String>,
+}
+
+/// The page requested by a client, to be applied by the catalog backend.
+///
+/// Backends return the items ordered by their key, starting after [`PageQuery::after`]
+/// and limited to [`PageQuery::fetch_limit`] items. [`PageQuery::into_page`] then builds
+/// the response from these items.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageQuery {
+    /// Key of the last item of the previous page. Only items with a greater key are listed.
+    pub after: Option<String>,
+    /// Maximum number of items of the page. `None` lists all remaining items.
+    pub page_size: Option<usize>,
+    query: String,
+}
+
+impl PageQuery {
+    /// Decode the page requested by a client. `query` identifies the listing, such as
+    /// the endpoint, warehouse and namespace; a token is only accepted for the same `query`.
+    ///
+    /// # Errors
+    /// Fails with a `400` if the page token is invalid or was issued for another query.
+    pub fn new(page_token: &PageToken, page_size: Option<i32>, query: &[&str]) -> Result<Self> {
+        let after = match page_token {
+            PageToken::Present(token) => Some(decode(token, &PAGE_TOKEN_SECRET, query)?),
+            PageToken::NotSpecified | PageToken::Empty => None,
+        };
+        Ok(Self {
+            after,
+            page_size: page_size.and_then(|size| usize::try_from(size.max(1)).ok()),
+            query: query_hash(query),
+        })
+    }
+
+    /// All items, for listings that are not requested by a client.
+    #[must_use]
+    pub fn all() -> Self {
+        Self {
+            after: None,
+            page_size: None,
+            query: String::new(),
+        }
+    }
+
+    /// Number of items a backend should fetch: one more than the page size, which
+    /// tells whether a next page exists.
+    #[must_use]
+    pub fn fetch_limit(&self) -> Option<usize> {
+        self.page_size.map(|size| size.saturating_add(1))
+    }
+
+    /// Apply the page to `items` in memory, for backends that can't apply it in a query.
+    #[must_use]
+    pub fn apply<T>(&self, mut items: Vec<T>, key: impl Fn(&T) -> String) -> Vec<T> {
+        items.sort_by_key(&key);
+        if let Some(after) = &self.after {
+            let start = items.partition_point(|item| key(item) <= *after);
+            items.drain(..start);
+        }
+        if let Some(limit) = self.fetch_limit() {
+            items.truncate(limit);
+        }
+        items
+    }
+
+    /// Build the page from `items` fetched by a backend for this query, i.e. ordered by
+    /// their `key`, after [`PageQuery::after`] and limited to [`PageQuery::fetch_limit`].
+    ///
+    /// # Errors
+    /// Fails if the next page token can't be signed.
+    pub fn into_page<T>(self, mut items: Vec<T>, key: impl Fn(&T) -> String) -> Result<Page<T>> {
+        let next_page_token = match self.page_size {
+            Some(size) if items.len() > size => {
+                items.truncate(size);
+                items
+                    .last()
+                    .map(|last| encode(&key(last), &PAGE_TOKEN_SECRET, &self.query))
+                    .transpose()?
+            }
+            _ => None,
+        };
+
+        Ok(Page {
+            items,
+            next_page_token,
+        })
+    }
+}
+
+fn query_hash(query: &[&str]) -> String {
+    // Separate the parts with a character that can't be part of identifiers in urls.
+    hex(&openssl::sha::sha256(query.join("\u{1f}").as_bytes()))
+}
+
+fn encode(position: &str, secret: &[u8], query_hash: &str) -> Result<String> {
+    let header = Header {
+        kid: Some(PAGE_TOKEN_KEY_ID.to_string()),
+        ..Header::new(Algorithm::HS256)
+    };
+    let claims = PageTokenClaims {
+        position: position.to_string(),
+        query: query_hash.to_string(),
+    };
+
+    jsonwebtoken::encode(&header, &claims, &EncodingKey::from_secret(secret)).map_err(|e| {
+        ErrorModel::builder()
+            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+            .message("Failed to create page token.".to_string())
+            .r#type("PageTokenCreationFailed".to_string())
+            .stack(Some(vec![e.to_string()]))
+            .build()
+            .into()
+    })
+}
+
+fn decode(token: &str, secret: &[u8], query: &[&str]) -> Result<String> {
+    let invalid = |stack: String| {
+        ErrorModel::builder()
+            .code(StatusCode::BAD_REQUEST.into())
+            .message("Invalid page token.".to_string())
+            .r#type("InvalidPageToken".to_string())
+            .stack(Some(vec![stack]))
+            .build()
+    };
+
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.validate_aud = false;
+    validation.validate_exp = false;
+    validation.required_spec_claims.clear();
+
+    let claims = jsonwebtoken::decode::<PageTokenClaims>(
+        token,
+        &DecodingKey::from_secret(secret),
+        &validation,
+    )
+    .map_err(|e| invalid(e.to_string()))?
+    .claims;
+
+    if claims.query != query_hash(query) {
+        return Err(invalid("Page token was issued for a different query.".to_string()).into());
+    }
+    Ok(claims.position)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_token_roundtrip() {
+        let query = ["list_tables", "warehouse", "ns"];
+        let token = encode("b", b"secret", &query_hash(&query)).unwrap();
+        assert_eq!(decode(&token, b"secret", &query).unwrap(), "b");
+
+        let err = decode(&token, b"other-secret", &query).unwrap_err();
+        assert_eq!(err.error.code, StatusCode::BAD_REQUEST);
+        let err = decode(&token, b"secret", &["list_tables", "warehouse", "other"]).unwrap_err();
+        assert_eq!(err.error.code, StatusCode::BAD_REQUEST);
+
+        // Tokens with a modified payload are rejected.
+        let mut parts = token.split('.').map(str::to_string).collect::<Vec<_>>();
+        let forged = encode("a", b"forged", &query_hash(&query)).unwrap();
+        parts[1] = forged.split('.').nth(1).unwrap().to_string();
+        assert!(decode(&parts.join("."), b"secret", &query).is_err());
+    }
+
+    #[test]
+    fn test_page_token_secret() {
+        let config = DynAppConfig::default();
+        // Derived from the encryption key, so it's the same on all instances.
+        assert_eq!(page_token_secret(&config), page_token_secret(&config));
+        assert_ne!(
+            page_token_secret(&config),
+            config.pg_encryption_key.as_bytes()
+        );
+
+        let rotated = DynAppConfig {
+            pg_encryption_key: "another key".to_string(),
+            ..DynAppConfig::default()
+        };
+        assert_ne!(page_token_secret(&config), page_token_secret(&rotated));
+
+        let configured = DynAppConfig {
+            page_token_secret: Some("secret".to_string()),
+            ..DynAppConfig::default()
+        };
+        assert_eq!(page_token_secret(&configured), b"secret");
+    }
+
+    #[test]
+    fn test_paginate() {
+        let query = ["list_tables", "warehouse", "ns"];
+        let items = vec!["c", "a", "d", "b"];
+        let key = |item: &&str| (*item).to_string();
+
+        let page = PageQuery::new(&PageToken::NotSpecified, None, &query).unwrap();
+        assert_eq!(page.fetch_limit(), None);
+        let fetched = page.apply(items.clone(), key);
+        let all = page.into_page(fetched, key).unwrap();
+        assert_eq!(all.items, vec!["a", "b", "c", "d"]);
+        assert!(all.next_page_token.is_none());
+
+        let mut listed = vec![];
+        let mut page_token = PageToken::Empty;
+        loop {
+            let page = PageQuery::new(&page_token, Some(3), &query).unwrap();
+            assert_eq!(page.fetch_limit(), Some(4));
+            let fetched = page.apply(items.clone(), key);
+            let page = page.into_page(fetched, key).unwrap();
+            listed.extend(page.items);
+            match page.next_page_token {
+                Some(token) => page_token = PageToken::Present(token),
+                None => break,
+            }
+        }
+        assert_eq!(listed, vec!["a", "b", "c", "d"]);
+
+        let err = PageQuery::new(&PageToken::Present("garbage".to_string()), Some(3), &query)
+            .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::BAD_REQUEST);
+    }
+}
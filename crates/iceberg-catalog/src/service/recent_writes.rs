@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::{Catalog, TableIdent};
+use crate::WarehouseIdent;
+
+/// Tables created, committed to, renamed or dropped through this instance within `window`.
+///
+/// Reads are served by the read pool, which may point to replicas lagging behind
+/// the primary. A client loading a table right after committing to it would then
+/// get the previous metadata, or a `404` right after creating it. Reads of tables
+/// written recently through this instance are routed to the primary instead, see
+/// [`Catalog::primary_state`]. Writes through other instances are not tracked.
+/// A window of zero disables the routing.
+#[derive(Debug, Clone)]
+pub struct RecentWrites {
+    tables: Arc<Mutex<HashMap<(WarehouseIdent, TableIdent), Instant>>>,
+    window: Duration,
+}
+
+impl RecentWrites {
+    #[must_use]
+    pub fn new(window: Duration) -> Self {
+        Self {
+            tables: Arc::new(Mutex::new(HashMap::new())),
+            window,
+        }
+    }
+
+    /// Never routes reads to the primary.
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self::new(Duration::ZERO)
+    }
+
+    /// Remember that `table` was just written. Must be called after the write was committed.
+    pub fn record(&self, warehouse_id: &WarehouseIdent, table: &TableIdent) {
+        if self.window.is_zero() {
+            return;
+        }
+        let mut tables = self.tables.lock().expect("recent writes lock poisoned");
+        tables.retain(|_, written_at| written_at.elapsed() < self.window);
+        tables.insert((warehouse_id.clone(), table.clone()), Instant::now());
+    }
+
+    /// Whether `table` was written within the window.
+    #[must_use]
+    pub fn contains(&self, warehouse_id: &WarehouseIdent, table: &TableIdent) -> bool {
+        if self.window.is_zero() {
+            return false;
+        }
+        self.tables
+            .lock()
+            .expect("recent writes lock poisoned")
+            .get(&(warehouse_id.clone(), table.clone()))
+            .is_some_and(|written_at| written_at.elapsed() < self.window)
+    }
+
+    /// State to read `table` with: the primary if it was written within the window.
+    #[must_use]
+    pub fn catalog_state<C: Catalog>(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        catalog_state: C::State,
+    ) -> C::State {
+        if self.contains(warehouse_id, table) {
+            C::primary_state(catalog_state)
+        } else {
+            catalog_state
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_window() {
+        let recent_writes = RecentWrites::new(Duration::from_millis(50));
+        let warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        let table = TableIdent::from_strs(["ns", "tbl"]).unwrap();
+        let other = TableIdent::from_strs(["ns", "other"]).unwrap();
+
+        recent_writes.record(&warehouse_id, &table);
+        assert!(recent_writes.contains(&warehouse_id, &table));
+        assert!(!recent_writes.contains(&warehouse_id, &other));
+        assert!(!recent_writes.contains(&uuid::Uuid::now_v7().into(), &table));
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(!recent_writes.contains(&warehouse_id, &table));
+    }
+
+    #[test]
+    fn test_disabled() {
+        let recent_writes = RecentWrites::disabled();
+        let warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        let table = TableIdent::from_strs(["ns", "tbl"]).unwrap();
+        recent_writes.record(&warehouse_id, &table);
+        assert!(!recent_writes.contains(&warehouse_id, &table));
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use super::{Catalog, GetWarehouseResponse, NamespaceIdent, NamespaceIdentUuid, Transaction};
+use crate::api::Result;
+use crate::WarehouseIdent;
+
+/// Entries beyond this number trigger a cleanup of expired entries.
+const MAX_ENTRIES: usize = 10_000;
+
+/// Cache for the warehouse and namespace lookups that precede almost every
+/// catalog request, e.g. allowlist, read-only and compatibility checks.
+///
+/// Only existing objects are cached, so that objects created by other
+/// instances are visible immediately. Changes made through this instance
+/// invalidate the affected entries, changes made by other instances become
+/// visible after at most `ttl`. A `ttl` of zero disables the cache.
+#[derive(Debug, Clone)]
+pub struct ResolutionCache {
+    warehouses: Arc<RwLock<HashMap<WarehouseIdent, CacheEntry<Arc<GetWarehouseResponse>>>>>,
+    namespaces:
+        Arc<RwLock<HashMap<(WarehouseIdent, NamespaceIdent), CacheEntry<NamespaceIdentUuid>>>>,
+    ttl: Duration,
+}
+
+#[derive(Debug)]
+struct CacheEntry<T> {
+    value: T,
+    inserted_at: Instant,
+}
+
+impl<T: Clone> CacheEntry<T> {
+    fn new(value: T) -> Self {
+        Self {
+            value,
+            inserted_at: Instant::now(),
+        }
+    }
+
+    fn fresh(&self, ttl: Duration) -> Option<T> {
+        (self.inserted_at.elapsed() < ttl).then(|| self.value.clone())
+    }
+}
+
+impl ResolutionCache {
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            warehouses: Arc::new(RwLock::new(HashMap::new())),
+            namespaces: Arc::new(RwLock::new(HashMap::new())),
+            ttl,
+        }
+    }
+
+    /// Get a warehouse including inactive ones.
+    /// Returns `None` if the warehouse does not exist.
+    ///
+    /// # Errors
+    /// Fails if the warehouse can not be loaded from the catalog.
+    pub async fn warehouse<C: Catalog>(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        catalog_state: C::State,
+    ) -> Result<Option<Arc<GetWarehouseResponse>>> {
+        if let Some(warehouse) = self
+            .warehouses
+            .read()
+            .await
+            .get(warehouse_id)
+            .and_then(|e| e.fresh(self.ttl))
+        {
+            return Ok(Some(warehouse));
+        }
+
+        let mut transaction = C::Transaction::begin_read(catalog_state).await?;
+        let warehouse = C::get_warehouse(warehouse_id, transaction.transaction()).await;
+        transaction.commit().await?;
+        let warehouse = match warehouse {
+            Ok(warehouse) => Arc::new(warehouse),
+            Err(e) if e.error.code == http::StatusCode::NOT_FOUND => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        if !self.ttl.is_zero() {
+            let mut warehouses = self.warehouses.write().await;
+            if warehouses.len() >= MAX_ENTRIES {
+                warehouses.retain(|_, e| e.fresh(self.ttl).is_some());
+            }
+            warehouses.insert(warehouse_id.clone(), CacheEntry::new(warehouse.clone()));
+        }
+
+        Ok(Some(warehouse))
+    }
+
+    /// Same as [`Catalog::namespace_ident_to_id`], but cached.
+    ///
+    /// # Errors
+    /// Fails if the namespace can not be resolved by the catalog.
+    pub async fn namespace_id<C: Catalog>(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+        catalog_state: C::State,
+    ) -> Result<Option<NamespaceIdentUuid>> {
+        let key = (warehouse_id.clone(), namespace.clone());
+        if let Some(namespace_id) = self
+            .namespaces
+            .read()
+            .await
+            .get(&key)
+            .and_then(|e| e.fresh(self.ttl))
+        {
+            return Ok(Some(namespace_id));
+        }
+
+        let namespace_id = C::namespace_ident_to_id(warehouse_id, namespace, catalog_state).await?;
+
+        if let Some(namespace_id) = &namespace_id {
+            if !self.ttl.is_zero() {
+                let mut namespaces = self.namespaces.write().await;
+                if namespaces.len() >= MAX_ENTRIES {
+                    namespaces.retain(|_, e| e.fresh(self.ttl).is_some());
+                }
+                namespaces.insert(key, CacheEntry::new(namespace_id.clone()));
+            }
+        }
+
+        Ok(namespace_id)
+    }
+
+    /// Forget a warehouse and all of its namespaces.
+    /// Must be called after the warehouse was changed or deleted.
+    pub async fn invalidate_warehouse(&self, warehouse_id: &WarehouseIdent) {
+        self.warehouses.write().await.remove(warehouse_id);
+        self.namespaces
+            .write()
+            .await
+            .retain(|(w, _), _| w != warehouse_id);
+    }
+
+    /// Forget a namespace. Must be called after the namespace was dropped.
+    pub async fn invalidate_namespace(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        namespace: &NamespaceIdent,
+    ) {
+        self.namespaces
+            .write()
+            .await
+            .remove(&(warehouse_id.clone(), namespace.clone()));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cache_entry_ttl() {
+        let entry = CacheEntry::new(1);
+        assert_eq!(entry.fresh(Duration::from_secs(60)), Some(1));
+        assert_eq!(entry.fresh(Duration::ZERO), None);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_warehouse_drops_namespaces() {
+        let cache = ResolutionCache::new(Duration::from_secs(60));
+        let warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        let other_warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        let namespace = NamespaceIdent::new("sales".to_string());
+        {
+            let mut namespaces = cache.namespaces.write().await;
+            for w in [&warehouse_id, &other_warehouse_id] {
+                namespaces.insert(
+                    (w.clone(), namespace.clone()),
+                    CacheEntry::new(NamespaceIdentUuid::from(uuid::Uuid::now_v7())),
+                );
+            }
+        }
+
+        cache.invalidate_warehouse(&warehouse_id).await;
+        let namespaces = cache.namespaces.read().await;
+        assert_eq!(namespaces.len(), 1);
+        assert!(namespaces.contains_key(&(other_warehouse_id, namespace)));
+    }
+}
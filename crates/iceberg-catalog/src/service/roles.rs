@@ -0,0 +1,37 @@
+//! Storage of the roles granted via the management API.
+//!
+//! Roles are enforced by the [`RbacAuthZHandler`](crate::implementations::RbacAuthZHandler),
+//! which looks them up on every access check.
+use async_trait::async_trait;
+use std::fmt::Debug;
+
+use super::{Role, RoleAssignment, RoleObject, RoleTarget};
+use crate::api::Result;
+use crate::ProjectIdent;
+
+#[async_trait]
+pub trait RoleStore: Debug + Send + Sync {
+    /// Grant `role` on `object` to `principal`, replacing the role the principal
+    /// had on the object before. Returns the id of the assignment.
+    async fn grant_role(
+        &self,
+        object: &RoleObject,
+        principal: &str,
+        role: Role,
+        created_by: Option<&str>,
+    ) -> Result<uuid::Uuid>;
+
+    /// Revoke the role of `principal` on `object`.
+    async fn revoke_role(&self, object: &RoleObject, principal: &str) -> Result<()>;
+
+    /// List role assignments on objects of `project_id`, or of all projects if `None`,
+    /// optionally only those of `principal`.
+    async fn list_role_assignments(
+        &self,
+        project_id: Option<&ProjectIdent>,
+        principal: Option<&str>,
+    ) -> Result<Vec<RoleAssignment>>;
+
+    /// Roles of `principal` on `target` and all objects containing it.
+    async fn get_roles(&self, principal: &str, target: &RoleTarget) -> Result<Vec<Role>>;
+}
@@ -0,0 +1,282 @@
+//! Options that can be changed without restarting the server.
+//!
+//! Restarts drop in-flight commits and signer requests, so a subset of the
+//! configuration is applied on the fly: the log level, maintenance mode and
+//! allowed CORS origins. On `SIGHUP` or `POST /management/v1/config/reload`,
+//! the configuration is loaded again from the config file and environment,
+//! and options that changed since the last load are applied. Options that were
+//! not changed are kept, so that i.e. maintenance mode enabled or a log level
+//! set via the management API is not reset by an unrelated reload. All other
+//! options require a restart.
+use std::sync::{Arc, RwLock};
+
+use http::HeaderValue;
+use utoipa::ToSchema;
+
+use crate::config::{ConfigError, DynAppConfig};
+use crate::service::maintenance::MaintenanceMode;
+use crate::CONFIG;
+
+/// Replaces the log filter of the running subscriber. Fails if the filter is invalid.
+pub type LogLevelSetter = Arc<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// Options applied on reload.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct RuntimeConfig {
+    /// Log filter in `RUST_LOG` syntax. If not set, `RUST_LOG` applies.
+    pub log_level: Option<String>,
+    /// Whether mutating requests are rejected.
+    pub maintenance_mode: bool,
+    /// Value of the `Retry-After` header during maintenance mode.
+    pub maintenance_retry_after_seconds: u64,
+    /// Origins allowed to send cross-origin requests.
+    pub cors_allowed_origins: Vec<String>,
+}
+
+impl From<&DynAppConfig> for RuntimeConfig {
+    fn from(value: &DynAppConfig) -> Self {
+        Self {
+            log_level: value.log_level.clone(),
+            maintenance_mode: value.maintenance_mode,
+            maintenance_retry_after_seconds: value.maintenance_retry_after_seconds,
+            cors_allowed_origins: value.cors_allowed_origins.clone(),
+        }
+    }
+}
+
+impl axum::response::IntoResponse for RuntimeConfig {
+    fn into_response(self) -> axum::http::Response<axum::body::Body> {
+        axum::Json(self).into_response()
+    }
+}
+
+/// Origins allowed to send cross-origin requests, shared with the CORS layer.
+#[derive(Debug, Clone, Default)]
+pub struct CorsOrigins(Arc<RwLock<Vec<String>>>);
+
+impl CorsOrigins {
+    #[must_use]
+    pub fn new(origins: Vec<String>) -> Self {
+        Self(Arc::new(RwLock::new(origins)))
+    }
+
+    #[must_use]
+    pub fn allows(&self, origin: &HeaderValue) -> bool {
+        self.0
+            .read()
+            .expect("cors origins lock poisoned")
+            .iter()
+            .any(|allowed| allowed == "*" || allowed.as_bytes() == origin.as_bytes())
+    }
+
+    fn set(&self, origins: Vec<String>) {
+        *self.0.write().expect("cors origins lock poisoned") = origins;
+    }
+}
+
+#[derive(Clone)]
+pub struct RuntimeConfigHandle {
+    /// Options of the last load.
+    loaded: Arc<RwLock<RuntimeConfig>>,
+    maintenance: MaintenanceMode,
+    cors_origins: CorsOrigins,
+    log_level: Option<LogLevelSetter>,
+    /// Filter applied last, `None` if `RUST_LOG` still applies.
+    current_log_level: Arc<RwLock<Option<String>>>,
+}
+
+impl std::fmt::Debug for RuntimeConfigHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RuntimeConfigHandle")
+            .field("loaded", &self.loaded)
+            .field("maintenance", &self.maintenance)
+            .field("cors_origins", &self.cors_origins)
+            .field("log_level", &self.log_level.is_some())
+            .field("current_log_level", &self.current_log_level)
+            .finish()
+    }
+}
+
+impl RuntimeConfigHandle {
+    /// Handle for the options in [`CONFIG`]. If `log_level` is `None`,
+    /// changes of the log level are ignored.
+    #[must_use]
+    pub fn new(maintenance: MaintenanceMode, log_level: Option<LogLevelSetter>) -> Self {
+        Self {
+            loaded: Arc::new(RwLock::new(RuntimeConfig::from(&*CONFIG))),
+            maintenance,
+            cors_origins: CorsOrigins::new(CONFIG.cors_allowed_origins.clone()),
+            log_level,
+            current_log_level: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    #[must_use]
+    pub fn maintenance(&self) -> &MaintenanceMode {
+        &self.maintenance
+    }
+
+    #[must_use]
+    pub fn cors_origins(&self) -> &CorsOrigins {
+        &self.cors_origins
+    }
+
+    /// Apply the configured log level, which must happen after the subscriber is installed.
+    ///
+    /// # Errors
+    /// Fails if the log level is invalid.
+    pub fn init_log_level(&self) -> Result<(), ConfigError> {
+        let loaded = self.loaded.read().expect("runtime config lock poisoned");
+        match loaded.log_level.as_deref() {
+            Some(log_level) => self.apply_log_level(log_level),
+            None => Ok(()),
+        }
+    }
+
+    /// Log filter applied last, `None` if `RUST_LOG` applies.
+    #[must_use]
+    pub fn log_level(&self) -> Option<String> {
+        self.current_log_level
+            .read()
+            .expect("log level lock poisoned")
+            .clone()
+    }
+
+    /// Replace the log filter until it is changed again or a reload changes `log_level`.
+    ///
+    /// # Errors
+    /// Fails if the filter is invalid or the log level can't be changed in this server.
+    pub fn set_log_level(&self, log_level: &str) -> Result<(), ConfigError> {
+        if self.log_level.is_none() {
+            return Err(ConfigError {
+                errors: vec!["log_level: can't be changed at runtime in this server".to_string()],
+            });
+        }
+        self.apply_log_level(log_level)
+    }
+
+    /// Load the configuration again and apply the options that changed.
+    ///
+    /// # Errors
+    /// Fails if the configuration is invalid, in which case nothing is applied.
+    pub fn reload(&self) -> Result<RuntimeConfig, ConfigError> {
+        self.apply(RuntimeConfig::from(&DynAppConfig::load()?))
+    }
+
+    fn apply(&self, config: RuntimeConfig) -> Result<RuntimeConfig, ConfigError> {
+        let mut loaded = self.loaded.write().expect("runtime config lock poisoned");
+        if config.log_level != loaded.log_level {
+            if let Some(log_level) = &config.log_level {
+                self.apply_log_level(log_level)?;
+            }
+        }
+        if config.maintenance_mode != loaded.maintenance_mode
+            || config.maintenance_retry_after_seconds != loaded.maintenance_retry_after_seconds
+        {
+            self.maintenance.set(
+                config.maintenance_mode,
+                Some(config.maintenance_retry_after_seconds),
+            );
+        }
+        if config.cors_allowed_origins != loaded.cors_allowed_origins {
+            self.cors_origins.set(config.cors_allowed_origins.clone());
+        }
+        *loaded = config.clone();
+        Ok(config)
+    }
+
+    fn apply_log_level(&self, log_level: &str) -> Result<(), ConfigError> {
+        let Some(setter) = &self.log_level else {
+            return Ok(());
+        };
+        setter(log_level).map_err(|e| ConfigError {
+            errors: vec![format!("log_level: {e}")],
+        })?;
+        *self
+            .current_log_level
+            .write()
+            .expect("log level lock poisoned") = Some(log_level.to_string());
+        Ok(())
+    }
+
+    /// Reload the configuration whenever the process receives `SIGHUP`.
+    pub async fn reload_on_sighup(self) {
+        #[cfg(unix)]
+        {
+            let mut hangup =
+                match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+                    Ok(hangup) => hangup,
+                    Err(e) => {
+                        tracing::error!("Failed to install SIGHUP handler: {e}");
+                        return;
+                    }
+                };
+            while hangup.recv().await.is_some() {
+                match self.reload() {
+                    Ok(config) => tracing::info!("Reloaded configuration: {config:?}"),
+                    Err(e) => tracing::error!("Failed to reload configuration: {e}"),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_apply_only_changed_options() {
+        let maintenance = MaintenanceMode::new(false, 60);
+        let levels = Arc::new(std::sync::Mutex::new(vec![]));
+        let handle = RuntimeConfigHandle::new(maintenance.clone(), {
+            let levels = levels.clone();
+            Some(Arc::new(move |level: &str| {
+                levels.lock().unwrap().push(level.to_string());
+                Ok(())
+            }))
+        });
+        let initial = RuntimeConfig::from(&*CONFIG);
+
+        // Enabled via the management API, kept by a reload that only changes origins
+        maintenance.set(true, None);
+        handle
+            .apply(RuntimeConfig {
+                cors_allowed_origins: vec!["https://ui.example.com".to_string()],
+                ..initial.clone()
+            })
+            .unwrap();
+        assert!(maintenance.is_enabled());
+        assert!(handle
+            .cors_origins()
+            .allows(&HeaderValue::from_static("https://ui.example.com")));
+        assert!(!handle
+            .cors_origins()
+            .allows(&HeaderValue::from_static("https://evil.example.com")));
+        assert!(levels.lock().unwrap().is_empty());
+
+        handle
+            .apply(RuntimeConfig {
+                log_level: Some("debug".to_string()),
+                maintenance_mode: false,
+                maintenance_retry_after_seconds: 120,
+                ..initial
+            })
+            .unwrap();
+        assert!(!maintenance.is_enabled());
+        assert_eq!(maintenance.retry_after_seconds(), 120);
+        assert_eq!(*levels.lock().unwrap(), vec!["debug".to_string()]);
+
+        handle
+            .set_log_level("info,iceberg_catalog::implementations::postgres=debug")
+            .unwrap();
+        assert_eq!(
+            handle.log_level().as_deref(),
+            Some("info,iceberg_catalog::implementations::postgres=debug")
+        );
+        assert!(RuntimeConfigHandle::new(maintenance, None)
+            .set_log_level("debug")
+            .is_err());
+    }
+}
@@ -0,0 +1,333 @@
+//! Hardening of HTTP requests and responses.
+//!
+//! Standard security headers are added to every response. Requests with
+//! oversized headers are rejected before they reach authentication, and
+//! hop-by-hop headers, which only concern the connection to the previous hop,
+//! are removed so that handlers never act on them. Optionally, plain HTTP
+//! requests are redirected to HTTPS or rejected. TLS is usually terminated by
+//! a reverse proxy, so the scheme is taken from the `X-Forwarded-Proto` header.
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode};
+
+use crate::api::{ErrorModel, IcebergErrorResponse};
+use crate::CONFIG;
+
+const X_FORWARDED_PROTO: &str = "x-forwarded-proto";
+const HSTS_MAX_AGE_SECONDS: u64 = 365 * 24 * 3600;
+/// Liveness probes usually connect via plain HTTP.
+const HTTPS_EXEMPT_PATHS: &[&str] = &["/health"];
+
+/// Headers that only apply to a single connection, see RFC 9110, section 7.6.1.
+const HOP_BY_HOP_HEADERS: &[HeaderName] = &[
+    header::CONNECTION,
+    HeaderName::from_static("keep-alive"),
+    HeaderName::from_static("proxy-connection"),
+    header::PROXY_AUTHENTICATE,
+    header::PROXY_AUTHORIZATION,
+    header::TE,
+    header::TRAILER,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+];
+
+/// Handling of requests received via plain HTTP.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HttpsOnly {
+    /// Serve requests regardless of the scheme.
+    #[default]
+    Disabled,
+    /// Redirect to the same URL with `https` scheme.
+    /// Only `GET` and `HEAD` requests are redirected, others are rejected.
+    Redirect,
+    /// Reject the request.
+    Reject,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecurityPolicy {
+    pub headers_enabled: bool,
+    pub max_request_header_bytes: usize,
+    pub https_only: HttpsOnly,
+}
+
+impl SecurityPolicy {
+    #[must_use]
+    pub fn from_config() -> Self {
+        Self {
+            headers_enabled: CONFIG.security_headers_enabled,
+            max_request_header_bytes: CONFIG.max_request_header_bytes,
+            https_only: CONFIG.https_only,
+        }
+    }
+}
+
+pub(crate) async fn security_middleware_fn(
+    State(policy): State<SecurityPolicy>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let header_bytes = header_bytes(request.headers());
+    if header_bytes > policy.max_request_header_bytes {
+        return IcebergErrorResponse::from(
+            ErrorModel::builder()
+                .code(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE.into())
+                .message(format!(
+                    "Request headers are {header_bytes} bytes, which exceeds the limit of {} bytes.",
+                    policy.max_request_header_bytes
+                ))
+                .r#type("RequestHeadersTooLarge")
+                .build(),
+        )
+        .into_response();
+    }
+
+    if policy.https_only != HttpsOnly::Disabled
+        && !is_https(&request)
+        && !HTTPS_EXEMPT_PATHS.contains(&request.uri().path())
+    {
+        if let Some(location) = (policy.https_only == HttpsOnly::Redirect)
+            .then(|| https_location(&request))
+            .flatten()
+        {
+            return (
+                StatusCode::PERMANENT_REDIRECT,
+                [(header::LOCATION, location)],
+            )
+                .into_response();
+        }
+        return IcebergErrorResponse::from(
+            ErrorModel::builder()
+                .code(StatusCode::FORBIDDEN.into())
+                .message("This catalog is only served via HTTPS.")
+                .r#type("HttpsRequired")
+                .build(),
+        )
+        .into_response();
+    }
+
+    strip_hop_by_hop_headers(request.headers_mut());
+    let mut response = next.run(request).await;
+    if policy.headers_enabled {
+        insert_security_headers(response.headers_mut(), policy.https_only);
+    }
+    response
+}
+
+fn header_bytes(headers: &HeaderMap) -> usize {
+    headers
+        .iter()
+        .map(|(name, value)| name.as_str().len() + value.len())
+        .sum()
+}
+
+/// Remove hop-by-hop headers, including those listed in the `Connection` header.
+fn strip_hop_by_hop_headers(headers: &mut HeaderMap) {
+    let listed = headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .filter_map(|name| HeaderName::try_from(name.trim()).ok())
+        .collect::<Vec<_>>();
+    for name in HOP_BY_HOP_HEADERS.iter().chain(&listed) {
+        headers.remove(name);
+    }
+}
+
+fn is_https(request: &Request) -> bool {
+    request
+        .headers()
+        .get(X_FORWARDED_PROTO)
+        .and_then(|v| v.to_str().ok())
+        // Proxies append to the header, the first value is the one of the client.
+        .and_then(|v| v.split(',').next())
+        .map_or_else(
+            || request.uri().scheme_str() == Some("https"),
+            |proto| proto.trim().eq_ignore_ascii_case("https"),
+        )
+}
+
+/// Location of the request with `https` scheme. `None` for methods that can't
+/// be redirected without losing the body or if the host is unknown.
+fn https_location(request: &Request) -> Option<HeaderValue> {
+    if !matches!(*request.method(), http::Method::GET | http::Method::HEAD) {
+        return None;
+    }
+    let host = request
+        .headers()
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .or_else(|| request.uri().authority().map(http::uri::Authority::as_str))?;
+    let path_and_query = request
+        .uri()
+        .path_and_query()
+        .map_or("/", http::uri::PathAndQuery::as_str);
+    HeaderValue::try_from(format!("https://{host}{path_and_query}")).ok()
+}
+
+fn insert_security_headers(headers: &mut HeaderMap, https_only: HttpsOnly) {
+    headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+    headers.insert(header::X_FRAME_OPTIONS, HeaderValue::from_static("DENY"));
+    headers.insert(
+        header::REFERRER_POLICY,
+        HeaderValue::from_static("no-referrer"),
+    );
+    // Only sent if plain HTTP is not served, as browsers would otherwise refuse it.
+    if https_only != HttpsOnly::Disabled {
+        headers.insert(
+            header::STRICT_TRANSPORT_SECURITY,
+            HeaderValue::from_str(&format!("max-age={HSTS_MAX_AGE_SECONDS}"))
+                .expect("Valid header value"),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use axum::routing::get;
+    use itertools::Itertools;
+    use tower::ServiceExt;
+
+    fn router(policy: SecurityPolicy) -> axum::Router {
+        axum::Router::new()
+            .route(
+                "/echo",
+                get(|headers: HeaderMap| async move {
+                    headers.keys().map(HeaderName::as_str).sorted().join(",")
+                })
+                .post(|| async { "OK" }),
+            )
+            .route("/health", get(|| async { "OK" }))
+            .layer(axum::middleware::from_fn_with_state(
+                policy,
+                security_middleware_fn,
+            ))
+    }
+
+    fn request(method: &str, uri: &str) -> http::request::Builder {
+        http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::HOST, "catalog.example.com")
+    }
+
+    async fn body(response: Response) -> String {
+        let bytes = http_body_util::BodyExt::collect(response.into_body())
+            .await
+            .unwrap()
+            .to_bytes();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_security_headers_and_hop_by_hop_headers() {
+        let policy = SecurityPolicy {
+            headers_enabled: true,
+            max_request_header_bytes: 1024,
+            https_only: HttpsOnly::Disabled,
+        };
+        let response = router(policy)
+            .oneshot(
+                request("GET", "/echo")
+                    .header(header::CONNECTION, "keep-alive, x-internal")
+                    .header("x-internal", "1")
+                    .header(header::TE, "trailers")
+                    .header("x-kept", "1")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::X_CONTENT_TYPE_OPTIONS],
+            "nosniff"
+        );
+        assert_eq!(response.headers()[header::X_FRAME_OPTIONS], "DENY");
+        assert!(!response
+            .headers()
+            .contains_key(header::STRICT_TRANSPORT_SECURITY));
+        assert_eq!(body(response).await, "host,x-kept");
+
+        let response = router(policy)
+            .oneshot(
+                request("GET", "/echo")
+                    .header("x-large", "a".repeat(1024))
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            response.status(),
+            StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_https_only() {
+        let policy = SecurityPolicy {
+            headers_enabled: true,
+            max_request_header_bytes: 1024,
+            https_only: HttpsOnly::Redirect,
+        };
+        let response = router(policy)
+            .oneshot(
+                request("GET", "/echo?a=b")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            response.headers()[header::LOCATION],
+            "https://catalog.example.com/echo?a=b"
+        );
+
+        // Requests with a body are not redirected
+        let response = router(policy)
+            .oneshot(
+                request("POST", "/echo")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = router(policy)
+            .oneshot(
+                request("POST", "/echo")
+                    .header(X_FORWARDED_PROTO, "https")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response
+            .headers()
+            .contains_key(header::STRICT_TRANSPORT_SECURITY));
+
+        let response = router(SecurityPolicy {
+            https_only: HttpsOnly::Reject,
+            ..policy
+        })
+        .oneshot(
+            request("GET", "/health")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,254 @@
+//! Short-lived tokens that authorize S3 remote signing for a single table.
+//!
+//! Signer tokens are minted by `loadTable` and `createTable` and placed in the table
+//! config as `s3.signer.token`. They are HMAC-signed with `CONFIG.signer_token_secret`,
+//! so the signer can validate them without consulting the identity provider or the database.
+//!
+//! Signer tokens carry the subject and token id of the request they were minted for and are
+//! checked against the token revocations like any other bearer token. Revoking the subject
+//! or the token used to load the table therefore revokes its signer tokens as well.
+use http::StatusCode;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+use super::token_revocation::RevocableClaims;
+use super::token_verification::AuthDetails;
+use super::{TableIdentUuid, WarehouseIdent};
+use crate::api::{ErrorModel, Result};
+use crate::request_metadata::RequestMetadata;
+use crate::CONFIG;
+
+/// Table config key under which the signer token is returned.
+///
+/// This key is not part of the Iceberg spec, stock clients ignore it. It must not be
+/// `token`, which clients use as credential for all requests concerning the table.
+pub const SIGNER_TOKEN_CONFIG_KEY: &str = "s3.signer.token";
+/// Key id in the header of signer tokens. Used to tell them apart from
+/// tokens of the identity provider.
+pub const SIGNER_TOKEN_KEY_ID: &str = "iceberg-catalog-signer";
+const SIGNER_TOKEN_ISSUER: &str = "iceberg-catalog";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignerAccess {
+    /// Only `GET` and `HEAD` requests may be signed.
+    Read,
+    /// All requests may be signed.
+    ReadWrite,
+}
+
+impl SignerAccess {
+    #[must_use]
+    pub fn allows_write(self) -> bool {
+        matches!(self, SignerAccess::ReadWrite)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignerTokenClaims {
+    pub iss: String,
+    /// Principal the token was minted for.
+    pub sub: String,
+    pub jti: String,
+    /// Id of the token used to mint this token, if it had one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_jti: Option<String>,
+    pub warehouse_id: uuid::Uuid,
+    pub table_id: uuid::Uuid,
+    pub access: SignerAccess,
+    pub iat: usize,
+    pub exp: usize,
+}
+
+impl SignerTokenClaims {
+    /// Check that the token was issued for the given table.
+    ///
+    /// # Errors
+    /// Fails with 403 if warehouse or table do not match.
+    pub fn require_table(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        table_id: &TableIdentUuid,
+    ) -> Result<()> {
+        if self.warehouse_id == warehouse_id.into_uuid() && self.table_id == table_id.into_uuid() {
+            Ok(())
+        } else {
+            Err(ErrorModel::builder()
+                .code(StatusCode::FORBIDDEN.into())
+                .message("Signer token was issued for a different table.".to_string())
+                .r#type("SignerTokenTableMismatch".to_string())
+                .build()
+                .into())
+        }
+    }
+}
+
+impl RevocableClaims for SignerTokenClaims {
+    fn subject(&self) -> &str {
+        &self.sub
+    }
+
+    fn token_ids(&self) -> Vec<&str> {
+        std::iter::once(self.jti.as_str())
+            .chain(self.parent_jti.as_deref())
+            .collect()
+    }
+
+    fn issued_at(&self) -> usize {
+        self.iat
+    }
+}
+
+/// Returns true if a `signer_token_secret` is configured.
+#[must_use]
+pub fn signer_tokens_enabled() -> bool {
+    CONFIG.signer_token_secret.is_some()
+}
+
+/// Returns true if the token header identifies the token as signer token.
+/// The token is not validated.
+#[must_use]
+pub fn is_signer_token(token: &str) -> bool {
+    jsonwebtoken::decode_header(token)
+        .is_ok_and(|header| header.kid.as_deref() == Some(SIGNER_TOKEN_KEY_ID))
+}
+
+/// Mint a signer token for the given table on behalf of the caller of `request_metadata`.
+/// Returns `Ok(None)` if no `signer_token_secret` is configured.
+///
+/// # Errors
+/// Fails if the token cannot be encoded.
+pub fn mint_signer_token(
+    warehouse_id: &WarehouseIdent,
+    table_id: &TableIdentUuid,
+    access: SignerAccess,
+    request_metadata: &RequestMetadata,
+) -> Result<Option<String>> {
+    let Some(secret) = CONFIG.signer_token_secret.as_deref() else {
+        return Ok(None);
+    };
+
+    let iat = usize::try_from(chrono::Utc::now().timestamp()).unwrap_or_default();
+    let ttl = usize::try_from(CONFIG.signer_token_ttl_seconds).unwrap_or(usize::MAX);
+    let claims = SignerTokenClaims {
+        iss: SIGNER_TOKEN_ISSUER.to_string(),
+        sub: request_metadata.principal().unwrap_or_default().to_string(),
+        jti: uuid::Uuid::now_v7().to_string(),
+        parent_jti: match &request_metadata.auth_details {
+            Some(AuthDetails::JWT(claims)) => claims.jti.clone(),
+            _ => None,
+        },
+        warehouse_id: warehouse_id.into_uuid(),
+        table_id: table_id.into_uuid(),
+        access,
+        iat,
+        exp: iat.saturating_add(ttl),
+    };
+
+    encode(&claims, secret).map(Some)
+}
+
+/// Validate a signer token and return its claims.
+///
+/// # Errors
+/// Fails with 401 if signer tokens are disabled or the token is invalid or expired.
+pub fn verify_signer_token(token: &str) -> Result<SignerTokenClaims> {
+    let Some(secret) = CONFIG.signer_token_secret.as_deref() else {
+        return Err(ErrorModel::builder()
+            .code(StatusCode::UNAUTHORIZED.into())
+            .message("Signer tokens are not enabled.".to_string())
+            .r#type("UnauthorizedError".to_string())
+            .build()
+            .into());
+    };
+
+    decode(token, secret)
+}
+
+fn encode(claims: &SignerTokenClaims, secret: &str) -> Result<String> {
+    let header = Header {
+        kid: Some(SIGNER_TOKEN_KEY_ID.to_string()),
+        ..Header::new(Algorithm::HS256)
+    };
+
+    jsonwebtoken::encode(
+        &header,
+        claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .map_err(|e| {
+        ErrorModel::builder()
+            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+            .message("Failed to create signer token.".to_string())
+            .r#type("SignerTokenCreationFailed".to_string())
+            .stack(Some(vec![e.to_string()]))
+            .build()
+            .into()
+    })
+}
+
+fn decode(token: &str, secret: &str) -> Result<SignerTokenClaims> {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.validate_aud = false;
+    validation.set_issuer(&[SIGNER_TOKEN_ISSUER]);
+
+    jsonwebtoken::decode::<SignerTokenClaims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &validation,
+    )
+    .map(|data| data.claims)
+    .map_err(|e| {
+        ErrorModel::builder()
+            .code(StatusCode::UNAUTHORIZED.into())
+            .message("Invalid signer token.".to_string())
+            .r#type("UnauthorizedError".to_string())
+            .stack(Some(vec![e.to_string()]))
+            .build()
+            .into()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn claims(exp_offset: i64) -> SignerTokenClaims {
+        let now = chrono::Utc::now().timestamp();
+        SignerTokenClaims {
+            iss: SIGNER_TOKEN_ISSUER.to_string(),
+            sub: "alice".to_string(),
+            jti: uuid::Uuid::now_v7().to_string(),
+            parent_jti: Some("parent".to_string()),
+            warehouse_id: uuid::Uuid::now_v7(),
+            table_id: uuid::Uuid::now_v7(),
+            access: SignerAccess::Read,
+            iat: usize::try_from(now).unwrap(),
+            exp: usize::try_from(now + exp_offset).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_signer_token_roundtrip() {
+        let claims = claims(600);
+        let token = encode(&claims, "secret").unwrap();
+        assert!(is_signer_token(&token));
+        assert_eq!(decode(&token, "secret").unwrap(), claims);
+        assert_eq!(claims.token_ids(), vec![claims.jti.as_str(), "parent"]);
+        assert!(decode(&token, "other-secret").is_err());
+
+        let warehouse_id = WarehouseIdent::from(claims.warehouse_id);
+        assert!(claims
+            .require_table(&warehouse_id, &TableIdentUuid::from(claims.table_id))
+            .is_ok());
+        assert!(claims
+            .require_table(&warehouse_id, &TableIdentUuid::from(uuid::Uuid::now_v7()))
+            .is_err());
+    }
+
+    #[test]
+    fn test_expired_signer_token() {
+        let token = encode(&claims(-600), "secret").unwrap();
+        assert!(decode(&token, "secret").is_err());
+    }
+}
@@ -0,0 +1,690 @@
+//! Authentication of requests signed with AWS Signature Version 4.
+//!
+//! Some Iceberg REST clients, such as the Java client with `rest.sigv4-enabled`, sign
+//! catalog requests with `SigV4` instead of sending a bearer token. The signature is
+//! recomputed with the secret of the access key from `CONFIG.sigv4_access_keys` and
+//! the request is authenticated as the principal configured for that key.
+//!
+//! Requests signed with temporary credentials issued by STS carry the session token in
+//! the `x-amz-security-token` header. STS does not reveal the secret of temporary
+//! credentials to third parties, so they are verified with a [`SessionCredentialsResolver`]
+//! that has access to it, such as a credential broker or the STS of an S3-compatible store.
+//! The token must be signed and is rejected if no resolver is configured.
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use http::{HeaderMap, Method, StatusCode, Uri};
+use iceberg_ext::catalog::rest::ErrorModel;
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+use url::Url;
+
+use crate::config::{SigV4AccessKey, SigV4AccessKeys};
+
+/// Scheme of the `Authorization` header of `SigV4` signed requests.
+pub const SIGV4_ALGORITHM: &str = "AWS4-HMAC-SHA256";
+const AMZ_DATE_HEADER: &str = "x-amz-date";
+const AMZ_CONTENT_SHA256_HEADER: &str = "x-amz-content-sha256";
+const AMZ_SECURITY_TOKEN_HEADER: &str = "x-amz-security-token";
+const AMZ_DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+/// Maximum difference between the signing time of a request and the server time.
+const MAX_CLOCK_SKEW: chrono::Duration = chrono::Duration::minutes(15);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigV4Claims {
+    pub access_key_id: String,
+    /// Principal configured for the access key.
+    pub principal: String,
+}
+
+/// Resolves temporary credentials to the secret they are signed with.
+#[async_trait]
+pub trait SessionCredentialsResolver: Debug + Send + Sync {
+    /// Secret and principal of the temporary credentials identified by access key id and
+    /// session token. Returns `None` if the credentials are unknown or expired.
+    async fn resolve(
+        &self,
+        access_key_id: &str,
+        session_token: &str,
+    ) -> Result<Option<SigV4AccessKey>, ErrorModel>;
+}
+
+/// [`SessionCredentialsResolver`] querying the `sigv4_session_resolver_url`.
+///
+/// The access key id and session token are posted as JSON object with the keys
+/// `access-key-id` and `session-token`. The endpoint answers with `secret-access-key`
+/// and `principal`, or with `404 Not Found` if the credentials are unknown or expired.
+#[derive(Debug, Clone)]
+pub struct HttpSessionCredentialsResolver {
+    url: Url,
+}
+
+impl HttpSessionCredentialsResolver {
+    #[must_use]
+    pub fn new(url: Url) -> Self {
+        Self { url }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct ResolveSessionCredentialsRequest<'a> {
+    access_key_id: &'a str,
+    session_token: &'a str,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ResolveSessionCredentialsResponse {
+    secret_access_key: String,
+    principal: String,
+}
+
+#[async_trait]
+impl SessionCredentialsResolver for HttpSessionCredentialsResolver {
+    async fn resolve(
+        &self,
+        access_key_id: &str,
+        session_token: &str,
+    ) -> Result<Option<SigV4AccessKey>, ErrorModel> {
+        let response = super::http_client::http_client()
+            .post(self.url.clone())
+            .json(&ResolveSessionCredentialsRequest {
+                access_key_id,
+                session_token,
+            })
+            .send()
+            .await
+            .map_err(|e| resolver_error(&e))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let credentials = response
+            .error_for_status()
+            .map_err(|e| resolver_error(&e))?
+            .json::<ResolveSessionCredentialsResponse>()
+            .await
+            .map_err(|e| resolver_error(&e))?;
+
+        Ok(Some(SigV4AccessKey {
+            secret_access_key: credentials.secret_access_key,
+            principal: credentials.principal,
+        }))
+    }
+}
+
+fn resolver_error(e: &reqwest::Error) -> ErrorModel {
+    ErrorModel::builder()
+        .message("Failed to resolve temporary credentials.")
+        .code(StatusCode::SERVICE_UNAVAILABLE.into())
+        .r#type("SessionCredentialsResolverError")
+        .stack(Some(vec![e.to_string()]))
+        .build()
+}
+
+#[derive(Clone)]
+pub struct SigV4Verifier {
+    access_keys: SigV4AccessKeys,
+    session_resolver: Option<Arc<dyn SessionCredentialsResolver>>,
+}
+
+impl Debug for SigV4Verifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SigV4Verifier").finish_non_exhaustive()
+    }
+}
+
+/// Components of the `Authorization` header of a `SigV4` signed request.
+#[derive(Debug, PartialEq, Eq)]
+struct SigV4Authorization<'a> {
+    access_key_id: &'a str,
+    date: &'a str,
+    region: &'a str,
+    service: &'a str,
+    signed_headers: Vec<&'a str>,
+    signature: &'a str,
+}
+
+impl<'a> SigV4Authorization<'a> {
+    fn parse(authorization: &'a str) -> Result<Self, ErrorModel> {
+        let invalid = || unauthorized("Malformed SigV4 authorization header.");
+        let params = authorization
+            .strip_prefix(SIGV4_ALGORITHM)
+            .ok_or_else(invalid)?
+            .split(',')
+            .filter_map(|param| param.trim().split_once('='))
+            .collect::<HashMap<_, _>>();
+
+        let credential = params.get("Credential").ok_or_else(invalid)?;
+        let [access_key_id, date, region, service, "aws4_request"] =
+            credential.split('/').collect::<Vec<_>>()[..]
+        else {
+            return Err(invalid());
+        };
+        let signed_headers = params
+            .get("SignedHeaders")
+            .ok_or_else(invalid)?
+            .split(';')
+            .collect();
+        let signature = params.get("Signature").ok_or_else(invalid)?;
+
+        Ok(Self {
+            access_key_id,
+            date,
+            region,
+            service,
+            signed_headers,
+            signature,
+        })
+    }
+
+    fn scope(&self) -> String {
+        format!(
+            "{}/{}/{}/aws4_request",
+            self.date, self.region, self.service
+        )
+    }
+}
+
+impl SigV4Verifier {
+    #[must_use]
+    pub fn new(access_keys: SigV4AccessKeys) -> Self {
+        Self {
+            access_keys,
+            session_resolver: None,
+        }
+    }
+
+    /// Additionally accept temporary credentials known to `resolver`.
+    #[must_use]
+    pub fn with_session_resolver(mut self, resolver: Arc<dyn SessionCredentialsResolver>) -> Self {
+        self.session_resolver = Some(resolver);
+        self
+    }
+
+    /// Returns true if the `Authorization` header uses `SigV4`.
+    #[must_use]
+    pub fn is_sigv4(authorization: &str) -> bool {
+        authorization.starts_with(SIGV4_ALGORITHM)
+    }
+
+    /// Verify the signature of a request.
+    ///
+    /// # Errors
+    /// Fails with 401 if the request is not signed by a configured access key or
+    /// resolvable temporary credentials, the signature does not match or the request
+    /// was signed too long ago.
+    pub async fn verify(
+        &self,
+        method: &Method,
+        uri: &Uri,
+        headers: &HeaderMap,
+        body: &[u8],
+        now: DateTime<Utc>,
+    ) -> Result<SigV4Claims, ErrorModel> {
+        let authorization = headers
+            .get(http::header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| unauthorized("Missing authorization header"))?;
+        let authorization = SigV4Authorization::parse(authorization)?;
+
+        let access_key = self.access_key(&authorization, headers).await?;
+
+        // The signing time must be signed itself to prevent replays.
+        if !authorization.signed_headers.contains(&"host")
+            || !authorization.signed_headers.contains(&AMZ_DATE_HEADER)
+        {
+            return Err(unauthorized(
+                "SigV4 signed headers must include 'host' and 'x-amz-date'.",
+            ));
+        }
+        let amz_date = header_str(headers, AMZ_DATE_HEADER)
+            .ok_or_else(|| unauthorized("Missing x-amz-date header."))?;
+        let signed_at = NaiveDateTime::parse_from_str(amz_date, AMZ_DATE_FORMAT)
+            .map_err(|_| unauthorized("Malformed x-amz-date header."))?
+            .and_utc();
+        if !amz_date.starts_with(authorization.date) {
+            return Err(unauthorized(
+                "Date of the credential scope does not match x-amz-date.",
+            ));
+        }
+        if (now - signed_at).abs() > MAX_CLOCK_SKEW {
+            return Err(unauthorized("SigV4 signature expired."));
+        }
+
+        let payload_hash = hex(&openssl::sha::sha256(body));
+        if let Some(content_sha256) = header_str(headers, AMZ_CONTENT_SHA256_HEADER) {
+            if content_sha256 != payload_hash {
+                return Err(unauthorized(
+                    "x-amz-content-sha256 does not match the body.",
+                ));
+            }
+        }
+
+        let canonical_request = canonical_request(
+            method,
+            uri,
+            headers,
+            &authorization.signed_headers,
+            &payload_hash,
+        );
+        let string_to_sign = format!(
+            "{SIGV4_ALGORITHM}\n{amz_date}\n{}\n{}",
+            authorization.scope(),
+            hex(&openssl::sha::sha256(canonical_request.as_bytes()))
+        );
+        let signature = signing_key(
+            &access_key.secret_access_key,
+            authorization.date,
+            authorization.region,
+            authorization.service,
+        )
+        .and_then(|key| hmac_sha256(&key, string_to_sign.as_bytes()))
+        .map_err(|e| {
+            ErrorModel::builder()
+                .message("Failed to compute SigV4 signature.")
+                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                .r#type("InternalServerError")
+                .stack(Some(vec![e.to_string()]))
+                .build()
+        })?;
+
+        let expected = hex(&signature);
+        if expected.len() != authorization.signature.len()
+            || !openssl::memcmp::eq(expected.as_bytes(), authorization.signature.as_bytes())
+        {
+            return Err(unauthorized("SigV4 signature does not match."));
+        }
+
+        Ok(SigV4Claims {
+            access_key_id: authorization.access_key_id.to_string(),
+            principal: access_key.principal,
+        })
+    }
+
+    /// Secret and principal of the access key the request claims to be signed with.
+    async fn access_key(
+        &self,
+        authorization: &SigV4Authorization<'_>,
+        headers: &HeaderMap,
+    ) -> Result<SigV4AccessKey, ErrorModel> {
+        let Some(session_token) = header_str(headers, AMZ_SECURITY_TOKEN_HEADER) else {
+            return self
+                .access_keys
+                .get(authorization.access_key_id)
+                .cloned()
+                .ok_or_else(|| unauthorized("Unknown access key id."));
+        };
+
+        let resolver = self
+            .session_resolver
+            .as_ref()
+            .ok_or_else(|| unauthorized("Temporary credentials are not accepted."))?;
+        if !authorization
+            .signed_headers
+            .contains(&AMZ_SECURITY_TOKEN_HEADER)
+        {
+            return Err(unauthorized(
+                "SigV4 signed headers must include 'x-amz-security-token'.",
+            ));
+        }
+        resolver
+            .resolve(authorization.access_key_id, session_token)
+            .await?
+            .ok_or_else(|| unauthorized("Unknown or expired temporary credentials."))
+    }
+}
+
+fn canonical_request(
+    method: &Method,
+    uri: &Uri,
+    headers: &HeaderMap,
+    signed_headers: &[&str],
+    payload_hash: &str,
+) -> String {
+    // Clients encode the already encoded path once more, as
+    // AWS does for all services except S3.
+    let canonical_uri = uri_encode(uri.path(), false);
+
+    let mut query = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (
+                uri_encode(&percent_decode(key), true),
+                uri_encode(&percent_decode(value), true),
+            )
+        })
+        .collect::<Vec<_>>();
+    query.sort();
+    let canonical_query = query
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    let canonical_headers = signed_headers
+        .iter()
+        .map(|name| {
+            let value = if *name == "host" && !headers.contains_key(http::header::HOST) {
+                uri.authority().map(ToString::to_string).unwrap_or_default()
+            } else {
+                headers
+                    .get_all(*name)
+                    .iter()
+                    .filter_map(|v| v.to_str().ok())
+                    .map(|v| v.split_whitespace().collect::<Vec<_>>().join(" "))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            };
+            format!("{name}:{value}\n")
+        })
+        .collect::<String>();
+
+    format!(
+        "{method}\n{canonical_uri}\n{canonical_query}\n{canonical_headers}\n{}\n{payload_hash}",
+        signed_headers.join(";")
+    )
+}
+
+fn signing_key(
+    secret_access_key: &str,
+    date: &str,
+    region: &str,
+    service: &str,
+) -> Result<Vec<u8>, openssl::error::ErrorStack> {
+    let key = hmac_sha256(
+        format!("AWS4{secret_access_key}").as_bytes(),
+        date.as_bytes(),
+    )?;
+    let key = hmac_sha256(&key, region.as_bytes())?;
+    let key = hmac_sha256(&key, service.as_bytes())?;
+    hmac_sha256(&key, b"aws4_request")
+}
+
+pub(crate) fn hmac_sha256(key: &[u8], data: &[u8]) -> Result<Vec<u8>, openssl::error::ErrorStack> {
+    let key = PKey::hmac(key)?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+    signer.update(data)?;
+    signer.sign_to_vec()
+}
+
+/// Percent-encode everything except unreserved characters, as required by `SigV4`.
+fn uri_encode(s: &str, encode_slash: bool) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            b'/' if !encode_slash => "/".to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    urlencoding::decode(s).map_or_else(|_| s.to_string(), std::borrow::Cow::into_owned)
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+fn unauthorized(message: &str) -> ErrorModel {
+    ErrorModel::builder()
+        .message(message)
+        .code(StatusCode::UNAUTHORIZED.into())
+        .r#type("UnauthorizedError")
+        .build()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    // Example of the AWS SigV4 test suite (`get-vanilla-query-order-key-case`).
+    const AUTHORIZATION: &str = "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date, Signature=b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500";
+
+    fn verifier() -> SigV4Verifier {
+        SigV4Verifier::new(
+            SigV4AccessKeys::from_str("AKIDEXAMPLE:wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY:spark")
+                .unwrap(),
+        )
+    }
+
+    fn headers(authorization: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("host", "example.amazonaws.com".parse().unwrap());
+        headers.insert(AMZ_DATE_HEADER, "20150830T123600Z".parse().unwrap());
+        headers.insert(http::header::AUTHORIZATION, authorization.parse().unwrap());
+        headers
+    }
+
+    fn signed_at() -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str("20150830T123600Z", AMZ_DATE_FORMAT)
+            .unwrap()
+            .and_utc()
+    }
+
+    #[tokio::test]
+    async fn test_verify() {
+        let uri = Uri::from_static("/?Param2=value2&Param1=value1");
+        let claims = verifier()
+            .verify(
+                &Method::GET,
+                &uri,
+                &headers(AUTHORIZATION),
+                b"",
+                signed_at() + chrono::Duration::minutes(1),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            claims,
+            SigV4Claims {
+                access_key_id: "AKIDEXAMPLE".to_string(),
+                principal: "spark".to_string(),
+            }
+        );
+    }
+
+    async fn verify(
+        method: &Method,
+        uri: &Uri,
+        headers: &HeaderMap,
+        body: &[u8],
+        now: DateTime<Utc>,
+    ) -> Result<SigV4Claims, ErrorModel> {
+        verifier().verify(method, uri, headers, body, now).await
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_invalid_requests() {
+        let uri = Uri::from_static("/?Param2=value2&Param1=value1");
+
+        // Modified request
+        assert!(verify(
+            &Method::GET,
+            &Uri::from_static("/?Param2=value2&Param1=other"),
+            &headers(AUTHORIZATION),
+            b"",
+            signed_at()
+        )
+        .await
+        .is_err());
+        assert!(verify(
+            &Method::POST,
+            &uri,
+            &headers(AUTHORIZATION),
+            b"",
+            signed_at()
+        )
+        .await
+        .is_err());
+        assert!(verify(
+            &Method::GET,
+            &uri,
+            &headers(AUTHORIZATION),
+            b"{}",
+            signed_at()
+        )
+        .await
+        .is_err());
+        // Expired
+        assert!(verify(
+            &Method::GET,
+            &uri,
+            &headers(AUTHORIZATION),
+            b"",
+            signed_at() + chrono::Duration::hours(1)
+        )
+        .await
+        .is_err());
+        // Unknown key
+        assert!(verify(
+            &Method::GET,
+            &uri,
+            &headers(&AUTHORIZATION.replace("AKIDEXAMPLE", "AKIDOTHER")),
+            b"",
+            signed_at()
+        )
+        .await
+        .is_err());
+    }
+
+    #[derive(Debug)]
+    struct StaticResolver;
+
+    #[async_trait]
+    impl SessionCredentialsResolver for StaticResolver {
+        async fn resolve(
+            &self,
+            access_key_id: &str,
+            session_token: &str,
+        ) -> Result<Option<SigV4AccessKey>, ErrorModel> {
+            Ok(
+                (access_key_id == "ASIAEXAMPLE" && session_token == "session-token").then(|| {
+                    SigV4AccessKey {
+                        secret_access_key: "temporary-secret".to_string(),
+                        principal: "spark-session".to_string(),
+                    }
+                }),
+            )
+        }
+    }
+
+    /// Headers of a request signed with the temporary credentials `ASIAEXAMPLE`.
+    fn session_headers(uri: &Uri, session_token: &str, signed_headers: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("host", "example.amazonaws.com".parse().unwrap());
+        headers.insert(AMZ_DATE_HEADER, "20150830T123600Z".parse().unwrap());
+        headers.insert(AMZ_SECURITY_TOKEN_HEADER, session_token.parse().unwrap());
+
+        let scope = "20150830/us-east-1/service/aws4_request";
+        let canonical_request = canonical_request(
+            &Method::GET,
+            uri,
+            &headers,
+            signed_headers,
+            &hex(&openssl::sha::sha256(b"")),
+        );
+        let string_to_sign = format!(
+            "{SIGV4_ALGORITHM}\n20150830T123600Z\n{scope}\n{}",
+            hex(&openssl::sha::sha256(canonical_request.as_bytes()))
+        );
+        let key = signing_key("temporary-secret", "20150830", "us-east-1", "service").unwrap();
+        let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()).unwrap());
+        let authorization = format!(
+            "{SIGV4_ALGORITHM} Credential=ASIAEXAMPLE/{scope}, SignedHeaders={}, Signature={signature}",
+            signed_headers.join(";")
+        );
+        headers.insert(http::header::AUTHORIZATION, authorization.parse().unwrap());
+        headers
+    }
+
+    #[tokio::test]
+    async fn test_verify_session_credentials() {
+        let uri = Uri::from_static("/v1/config");
+        let signed_headers = ["host", "x-amz-date", "x-amz-security-token"];
+        let with_resolver = verifier().with_session_resolver(Arc::new(StaticResolver));
+
+        let claims = with_resolver
+            .verify(
+                &Method::GET,
+                &uri,
+                &session_headers(&uri, "session-token", &signed_headers),
+                b"",
+                signed_at(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(claims.access_key_id, "ASIAEXAMPLE");
+        assert_eq!(claims.principal, "spark-session");
+
+        // Not accepted without a resolver
+        assert!(verifier()
+            .verify(
+                &Method::GET,
+                &uri,
+                &session_headers(&uri, "session-token", &signed_headers),
+                b"",
+                signed_at(),
+            )
+            .await
+            .is_err());
+        // Unknown session token
+        assert!(with_resolver
+            .verify(
+                &Method::GET,
+                &uri,
+                &session_headers(&uri, "other-token", &signed_headers),
+                b"",
+                signed_at(),
+            )
+            .await
+            .is_err());
+        // Session token not signed
+        assert!(with_resolver
+            .verify(
+                &Method::GET,
+                &uri,
+                &session_headers(&uri, "session-token", &["host", "x-amz-date"]),
+                b"",
+                signed_at(),
+            )
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_authorization() {
+        let authorization = SigV4Authorization::parse(AUTHORIZATION).unwrap();
+        assert_eq!(authorization.access_key_id, "AKIDEXAMPLE");
+        assert_eq!(
+            authorization.scope(),
+            "20150830/us-east-1/service/aws4_request"
+        );
+        assert_eq!(authorization.signed_headers, vec!["host", "x-amz-date"]);
+
+        assert!(SigV4Authorization::parse("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE").is_err());
+        assert!(SigV4Authorization::parse("Bearer foo").is_err());
+    }
+
+    #[test]
+    fn test_uri_encode() {
+        assert_eq!(uri_encode("/v1/a%1Fb/tables", false), "/v1/a%251Fb/tables");
+        assert_eq!(uri_encode("a b/c", true), "a%20b%2Fc");
+    }
+}
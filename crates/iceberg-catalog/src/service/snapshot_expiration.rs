@@ -0,0 +1,222 @@
+//! Expiration of table snapshots according to retention policies.
+//!
+//! The [`SnapshotExpirationJob`] periodically removes snapshots from the metadata
+//! of all tables that are subject to a [`SnapshotRetentionPolicy`](super::SnapshotRetentionPolicy),
+//! either set on their warehouse, one of their namespaces or in their table properties.
+//! Retention is also applied on every commit; the job covers tables that are not
+//! written to. Removing a snapshot is a regular commit that writes a new metadata
+//! file. Data and manifest files of expired snapshots are not deleted.
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::time::Duration;
+
+use iceberg::spec::MAIN_BRANCH;
+use iceberg::{TableRequirement, TableUpdate};
+use uuid::Uuid;
+
+use super::event_publisher::{CloudEventsPublisher, EventMetadata, EventSubject, EventType};
+use super::storage::StorageCredential;
+use super::{
+    Catalog, CommitTransactionRequest, RetentionCandidate, SecretStore, TableIdentUuid, Transaction,
+};
+use crate::api::{CommitTableRequest, ErrorModel, Result};
+use crate::catalog::io::write_metadata_file;
+
+/// Maximum number of tables checked per batch.
+const BATCH_SIZE: i64 = 100;
+
+pub struct SnapshotExpirationJob<C: Catalog, S: SecretStore> {
+    catalog_state: C::State,
+    secrets_state: S::State,
+    publisher: CloudEventsPublisher,
+}
+
+impl<C: Catalog, S: SecretStore> Debug for SnapshotExpirationJob<C, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapshotExpirationJob")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: Catalog, S: SecretStore> SnapshotExpirationJob<C, S> {
+    #[must_use]
+    pub fn new(
+        catalog_state: C::State,
+        secrets_state: S::State,
+        publisher: CloudEventsPublisher,
+    ) -> Self {
+        Self {
+            catalog_state,
+            secrets_state,
+            publisher,
+        }
+    }
+
+    /// Expire snapshots every `interval` until the task is aborted.
+    pub async fn run(self, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match self.expire().await {
+                Ok(0) => {}
+                Ok(tables) => tracing::info!("Expired snapshots of {tables} tables."),
+                Err(e) => tracing::warn!("Snapshot expiration failed: {:?}", e.error),
+            }
+        }
+    }
+
+    /// Expire the snapshots of all tables subject to a retention policy.
+    /// Returns the number of tables from which snapshots were removed.
+    /// Tables that cannot be updated are logged and retried on the next run.
+    ///
+    /// # Errors
+    /// Fails if the tables cannot be listed.
+    pub async fn expire(&self) -> Result<usize> {
+        let mut updated = 0;
+        let mut after: Option<TableIdentUuid> = None;
+        loop {
+            let candidates =
+                C::list_retention_candidates(after, BATCH_SIZE, self.catalog_state.clone()).await?;
+            let batch_size = candidates.len();
+            after = candidates.last().map(|c| c.table_id);
+
+            for candidate in candidates {
+                let table_id = candidate.table_id;
+                match self.expire_table(candidate).await {
+                    Ok(true) => updated += 1,
+                    Ok(false) => {}
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to expire snapshots of table {table_id}: {:?}",
+                            e.error
+                        );
+                    }
+                }
+            }
+
+            if batch_size < usize::try_from(BATCH_SIZE).unwrap_or(usize::MAX) {
+                return Ok(updated);
+            }
+        }
+    }
+
+    async fn expire_table(&self, candidate: RetentionCandidate) -> Result<bool> {
+        let RetentionCandidate {
+            warehouse_id,
+            table_id,
+            table,
+            policy,
+        } = candidate;
+
+        let metadata = C::load_table(&warehouse_id, &table, self.catalog_state.clone())
+            .await?
+            .table_metadata;
+        let policy = policy
+            .unwrap_or_default()
+            .with_table_properties(metadata.properties());
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let expired_refs = policy.expired_refs(&metadata, now_ms);
+        let expired = policy.expired_snapshots(&metadata, now_ms);
+        if expired_refs.is_empty() && expired.is_empty() {
+            return Ok(false);
+        }
+        let mut updates = expired_refs
+            .iter()
+            .map(|ref_name| TableUpdate::RemoveSnapshotRef {
+                ref_name: ref_name.clone(),
+            })
+            .collect::<Vec<_>>();
+        if !expired.is_empty() {
+            updates.push(TableUpdate::RemoveSnapshots {
+                snapshot_ids: expired.clone(),
+            });
+        }
+
+        let request = CommitTransactionRequest {
+            table_changes: vec![CommitTableRequest {
+                identifier: Some(table.clone()),
+                requirements: vec![TableRequirement::RefSnapshotIdMatch {
+                    r#ref: MAIN_BRANCH.to_string(),
+                    snapshot_id: metadata.current_snapshot().map(|s| s.snapshot_id()),
+                }],
+                updates,
+            }],
+        };
+        let table_ids = HashMap::from([(table.clone(), table_id)]);
+
+        let mut transaction = C::Transaction::begin_write(self.catalog_state.clone()).await?;
+        let result = C::commit_table_transaction(
+            &warehouse_id,
+            request,
+            &table_ids,
+            transaction.transaction(),
+        )
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            ErrorModel::builder()
+                .code(http::StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message("No result from commit_table_transaction".to_string())
+                .r#type("NoResultFromCommitTableTransaction".to_string())
+                .build()
+        })?;
+
+        let storage_secret = if let Some(secret_id) = &result.storage_config.storage_secret_ident {
+            Some(
+                S::get_secret_by_id::<StorageCredential>(secret_id, self.secrets_state.clone())
+                    .await?
+                    .secret,
+            )
+        } else {
+            None
+        };
+        let file_io = result
+            .storage_config
+            .storage_profile
+            .file_io(storage_secret.as_ref())
+            .await?;
+        write_metadata_file(
+            &result.commit_response.metadata_location,
+            &result.commit_response.metadata,
+            &file_io,
+        )
+        .await?;
+        transaction.commit().await?;
+
+        tracing::debug!(
+            "Expired {} snapshots and {} references of table {table:?} ({table_id}) in warehouse {warehouse_id}.",
+            expired.len(),
+            expired_refs.len()
+        );
+
+        let _ = self
+            .publisher
+            .publish(
+                Uuid::now_v7(),
+                EventType::TableSnapshotsExpired,
+                serde_json::json!({
+                    "snapshot-ids": expired,
+                    "refs": expired_refs,
+                    "metadata-location": result.commit_response.metadata_location,
+                }),
+                EventMetadata {
+                    warehouse_id: *warehouse_id.as_uuid(),
+                    subject: EventSubject::Table {
+                        table_id: *table_id.as_uuid(),
+                        namespace: table.namespace.encode_in_url(),
+                        name: table.name,
+                    },
+                    prefix: warehouse_id.to_string(),
+                    num_events: 1,
+                    sequence_number: 0,
+                    trace_id: Uuid::now_v7(),
+                    tenant_id: None,
+                },
+            )
+            .await;
+
+        Ok(true)
+    }
+}
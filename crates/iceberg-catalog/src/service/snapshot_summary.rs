@@ -0,0 +1,203 @@
+//! Validation and enrichment of the summaries of snapshots added by commits.
+//!
+//! Summaries are validated on every commit. If `enrich_snapshot_summaries` is enabled,
+//! the principal, time and request id of the commit are added to the summary, so that
+//! they are part of the table metadata and survive migrations to other catalogs.
+//! Values set by clients for these keys are overwritten.
+use http::StatusCode;
+use iceberg::spec::{Snapshot, Summary};
+use iceberg::TableUpdate;
+
+use crate::api::{ErrorModel, Result};
+
+/// Principal that committed the snapshot. Removed if the request was not authenticated.
+pub const COMMITTED_BY: &str = "iceberg-catalog.committed-by";
+/// Milliseconds since epoch the catalog received the commit.
+pub const COMMIT_TIMESTAMP_MS: &str = "iceberg-catalog.commit-timestamp-ms";
+/// Id of the request that committed the snapshot, also the `trace-id` of its events.
+pub const REQUEST_ID: &str = "iceberg-catalog.request-id";
+
+/// Summary fields the spec defines as integers.
+const NUMERIC_FIELDS: &[&str] = &[
+    "added-data-files",
+    "deleted-data-files",
+    "total-data-files",
+    "added-delete-files",
+    "added-equality-delete-files",
+    "removed-equality-delete-files",
+    "added-position-delete-files",
+    "removed-position-delete-files",
+    "removed-delete-files",
+    "total-delete-files",
+    "added-records",
+    "deleted-records",
+    "total-records",
+    "added-files-size",
+    "removed-files-size",
+    "total-files-size",
+    "added-position-deletes",
+    "removed-position-deletes",
+    "total-position-deletes",
+    "added-equality-deletes",
+    "removed-equality-deletes",
+    "total-equality-deletes",
+    "changed-partition-count",
+];
+
+/// Validate a summary. The `operation` is required when the request is parsed,
+/// so only the numeric fields are checked here.
+///
+/// # Errors
+/// Fails if a numeric field is not a non-negative integer.
+pub fn validate(summary: &Summary) -> Result<()> {
+    let invalid = NUMERIC_FIELDS
+        .iter()
+        .filter(|key| {
+            summary
+                .other
+                .get(**key)
+                .is_some_and(|value| value.parse::<u64>().is_err())
+        })
+        .copied()
+        .collect::<Vec<_>>();
+    if invalid.is_empty() {
+        return Ok(());
+    }
+    Err(ErrorModel::builder()
+        .code(StatusCode::BAD_REQUEST.into())
+        .message(format!(
+            "Snapshot summary fields must be non-negative integers: {}",
+            invalid.join(", ")
+        ))
+        .r#type("InvalidSnapshotSummary".to_string())
+        .build()
+        .into())
+}
+
+/// Add the principal, time and request id of the commit to the summaries
+/// of all snapshots added by `updates`.
+///
+/// # Errors
+/// Fails if a snapshot can't be rebuilt with the new summary.
+pub fn enrich(
+    updates: &mut [TableUpdate],
+    committed_by: Option<&str>,
+    request_id: uuid::Uuid,
+) -> Result<()> {
+    let commit_timestamp_ms = chrono::Utc::now().timestamp_millis();
+    for update in updates {
+        if let TableUpdate::AddSnapshot { snapshot } = update {
+            *snapshot = with_summary_fields(
+                snapshot,
+                committed_by,
+                &[
+                    (COMMIT_TIMESTAMP_MS, commit_timestamp_ms.to_string()),
+                    (REQUEST_ID, request_id.to_string()),
+                ],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Snapshots have no setter for their summary, so they are rebuilt from their json.
+fn with_summary_fields(
+    snapshot: &Snapshot,
+    committed_by: Option<&str>,
+    fields: &[(&str, String)],
+) -> Result<Snapshot> {
+    let internal = |e: serde_json::Error| {
+        ErrorModel::builder()
+            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+            .message("Error adding fields to snapshot summary".to_string())
+            .r#type("SnapshotSummaryEnrichmentError".to_string())
+            .stack(Some(vec![e.to_string()]))
+            .build()
+    };
+
+    let mut value = serde_json::to_value(snapshot).map_err(internal)?;
+    if let Some(summary) = value
+        .get_mut("summary")
+        .and_then(serde_json::Value::as_object_mut)
+    {
+        match committed_by {
+            Some(principal) => {
+                summary.insert(COMMITTED_BY.to_string(), principal.into());
+            }
+            None => {
+                summary.remove(COMMITTED_BY);
+            }
+        }
+        for (key, field) in fields {
+            summary.insert((*key).to_string(), field.clone().into());
+        }
+    }
+    Ok(serde_json::from_value(value).map_err(internal)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn snapshot(summary: serde_json::Value) -> Snapshot {
+        serde_json::from_value(serde_json::json!({
+            "snapshot-id": 1,
+            "sequence-number": 1,
+            "timestamp-ms": 1_700_000_000_000_i64,
+            "manifest-list": "s3://bucket/table/metadata/snap-1.avro",
+            "summary": summary,
+            "schema-id": 0
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate() {
+        let valid = snapshot(serde_json::json!({
+            "operation": "append",
+            "added-records": "10",
+            "spark.app.id": "local-1"
+        }));
+        assert!(validate(valid.summary()).is_ok());
+
+        let invalid = snapshot(serde_json::json!({
+            "operation": "append",
+            "added-records": "ten",
+            "total-records": "-1"
+        }));
+        let err = validate(invalid.summary()).unwrap_err();
+        assert_eq!(err.error.code, StatusCode::BAD_REQUEST);
+        assert!(err.error.message.contains("added-records, total-records"));
+    }
+
+    #[test]
+    fn test_enrich() {
+        let request_id = uuid::Uuid::now_v7();
+        let mut updates = vec![
+            TableUpdate::AddSnapshot {
+                snapshot: snapshot(serde_json::json!({
+                    "operation": "append",
+                    "added-records": "10",
+                    "iceberg-catalog.committed-by": "spoofed"
+                })),
+            },
+            TableUpdate::RemoveProperties { removals: vec![] },
+        ];
+        enrich(&mut updates, Some("peter"), request_id).unwrap();
+        let TableUpdate::AddSnapshot { snapshot } = &updates[0] else {
+            panic!("expected a snapshot");
+        };
+        let summary = &snapshot.summary().other;
+        assert_eq!(summary.get(COMMITTED_BY).map(String::as_str), Some("peter"));
+        assert_eq!(summary.get(REQUEST_ID), Some(&request_id.to_string()));
+        assert!(summary.contains_key(COMMIT_TIMESTAMP_MS));
+        assert_eq!(summary.get("added-records").map(String::as_str), Some("10"));
+        assert_eq!(snapshot.snapshot_id(), 1);
+
+        enrich(&mut updates, None, request_id).unwrap();
+        let TableUpdate::AddSnapshot { snapshot } = &updates[0] else {
+            panic!("expected a snapshot");
+        };
+        assert!(!snapshot.summary().other.contains_key(COMMITTED_BY));
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{
+    service::{NamespaceIdentUuid, TableIdentUuid},
+    WarehouseIdent, CONFIG,
+};
+use http::StatusCode;
+
+use crate::api::{CatalogConfig, ErrorModel, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Storage in a directory of the local filesystem, for development and tests.
+///
+/// Files are written by the catalog and by clients directly, so the directory must be
+/// shared with the clients, e.g. when both run on the same machine. There are no
+/// credentials: clients neither receive credentials nor can use remote signing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::module_name_repetitions)]
+#[schema(rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub struct FileProfile {
+    /// Absolute path of the directory holding the tables of the warehouse.
+    pub path: String,
+}
+
+impl FileProfile {
+    /// Validate the file profile. The directory is created if it does not exist.
+    ///
+    /// # Errors
+    /// - Fails if file storage profiles are not enabled.
+    /// - Fails if the path is not absolute or not writable.
+    pub async fn validate(&mut self) -> Result<()> {
+        if !CONFIG.file_storage_enabled {
+            return Err(invalid(
+                "FileStorageDisabled",
+                "File storage profiles are disabled. Set `file_storage_enabled` to use them.",
+            ));
+        }
+
+        // Remove trailing slashes, but keep the root
+        let trimmed = self.path.trim_end_matches('/');
+        self.path = if trimmed.is_empty() {
+            "/".to_string()
+        } else {
+            trimmed.to_string()
+        };
+        if !Path::new(&self.path).is_absolute() {
+            return Err(invalid(
+                "InvalidPath",
+                "Storage Profile `path` must be an absolute path.",
+            ));
+        }
+
+        tokio::fs::create_dir_all(&self.path).await.map_err(|e| {
+            ErrorModel::builder()
+                .code(StatusCode::BAD_REQUEST.into())
+                .message("Storage Profile `path` can't be created.".to_string())
+                .stack(Some(vec![e.to_string()]))
+                .r#type("InvalidPath".to_string())
+                .build()
+        })?;
+
+        let file_io = self.file_io()?;
+        let test_location =
+            self.table_location(&uuid::Uuid::now_v7().into(), &uuid::Uuid::now_v7().into());
+        super::validate_file_io(&file_io, &test_location, "File")
+            .await
+            .map_err(|mut e| {
+                e.error.push_to_stack(format!("Profile: {self:?}"));
+                e
+            })
+    }
+
+    /// Check if the profile can be updated with the other profile.
+    ///
+    /// # Errors
+    /// Fails if the `path` is different.
+    pub fn can_be_updated_with(&self, other: &Self) -> Result<()> {
+        if self.path != other.path {
+            return Err(invalid(
+                "InvalidPath",
+                "Storage Profile `path` cannot be updated to prevent data loss.",
+            ));
+        }
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn generate_catalog_config(&self, _: &WarehouseIdent) -> CatalogConfig {
+        CatalogConfig {
+            defaults: HashMap::default(),
+            overrides: HashMap::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn table_location(
+        &self,
+        namespace_id: &NamespaceIdentUuid,
+        table_id: &TableIdentUuid,
+    ) -> String {
+        // file:///<path>/<namespace-uuid>/<table-uuid>
+        format!(
+            "file://{}/{namespace_id}/{table_id}",
+            self.path.trim_end_matches('/')
+        )
+    }
+
+    /// Create a new `FileIO` instance for the local filesystem.
+    ///
+    /// # Errors
+    /// Fails if the `FileIO` instance cannot be created.
+    pub fn file_io(&self) -> Result<iceberg::io::FileIO> {
+        iceberg::io::FileIOBuilder::new("file")
+            .build()
+            .map_err(|e| {
+                ErrorModel::builder()
+                    .code(StatusCode::PRECONDITION_FAILED.into())
+                    .message("Error creating local filesystem.".to_string())
+                    .stack(Some(vec![e.to_string()]))
+                    .r#type("FileFileIOError".to_string())
+                    .build()
+                    .into()
+            })
+    }
+}
+
+fn invalid(r#type: &str, message: &str) -> crate::api::IcebergErrorResponse {
+    ErrorModel::builder()
+        .code(StatusCode::BAD_REQUEST.into())
+        .message(message.to_string())
+        .r#type(r#type.to_string())
+        .build()
+        .into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_file_location() {
+        let profile = FileProfile {
+            path: "/tmp/warehouse/".to_string(),
+        };
+        let namespace_id = NamespaceIdentUuid::from(uuid::Uuid::now_v7());
+        let table_id = TableIdentUuid::from(uuid::Uuid::now_v7());
+
+        assert_eq!(
+            profile.table_location(&namespace_id, &table_id),
+            format!("file:///tmp/warehouse/{namespace_id}/{table_id}")
+        );
+        assert!(profile
+            .can_be_updated_with(&FileProfile {
+                path: "/tmp/other".to_string()
+            })
+            .is_err());
+    }
+}
@@ -1,14 +1,170 @@
+mod file;
+mod gcs;
 mod s3;
+#[cfg(feature = "s3-signer")]
+mod sts;
 
 use std::collections::HashMap;
+use std::str::FromStr;
 
 use crate::api::{iceberg::v1::DataAccess, CatalogConfig, ErrorModel, Result};
-pub use s3::{S3Credential, S3Profile};
+pub use file::FileProfile;
+pub use gcs::{GcsCredential, GcsProfile, GcsServiceKey};
+pub use s3::{S3Credential, S3Flavor, S3Profile, S3SseAlgorithm};
 use serde::{Deserialize, Serialize};
 
 use crate::WarehouseIdent;
 
-use super::{secrets::SecretInStorage, NamespaceIdentUuid, TableIdentUuid};
+use super::{
+    secrets::SecretInStorage, sigv4_verification::hex, NamespaceIdentUuid, TableIdentUuid,
+};
+
+/// Table or namespace property that overrides how data access is delegated to clients.
+pub const ACCESS_DELEGATION_PROPERTY: &str = "catalog.access-delegation";
+
+/// Table or namespace property that enables the [`TableLayout::ObjectStorage`] layout.
+/// Iceberg clients use the same property to spread data files below hash prefixes.
+pub const OBJECT_STORAGE_PROPERTY: &str = "write.object-storage.enabled";
+
+/// Access delegation mode forced by the [`ACCESS_DELEGATION_PROPERTY`],
+/// or used by a warehouse if the client does not request a mode.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum_macros::Display,
+    strum_macros::EnumString,
+    Serialize,
+    Deserialize,
+    utoipa::ToSchema,
+)]
+#[strum(serialize_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(
+    feature = "sqlx",
+    sqlx(type_name = "access_delegation", rename_all = "kebab-case")
+)]
+pub enum AccessDelegation {
+    /// Only remote signing is offered, regardless of the `X-Iceberg-Access-Delegation` header.
+    RemoteSigning,
+    /// Only vended credentials are offered, regardless of the `X-Iceberg-Access-Delegation` header.
+    /// The signer rejects requests for the table.
+    VendedCredentials,
+    /// Neither credentials nor signing are offered. The signer rejects requests for the table.
+    Disabled,
+}
+
+impl AccessDelegation {
+    /// Resolve the effective mode of a table.
+    /// The table property takes precedence over the namespace property.
+    /// Unknown values disable delegation, so that a typo never exposes a table.
+    #[must_use]
+    pub fn resolve(
+        table_properties: Option<&HashMap<String, String>>,
+        namespace_properties: Option<&HashMap<String, String>>,
+    ) -> Option<Self> {
+        let value = table_properties
+            .and_then(|p| p.get(ACCESS_DELEGATION_PROPERTY))
+            .or_else(|| namespace_properties.and_then(|p| p.get(ACCESS_DELEGATION_PROPERTY)))?;
+
+        Some(Self::from_str(value).unwrap_or_else(|_| {
+            tracing::warn!(
+                "Invalid value '{value}' for property '{ACCESS_DELEGATION_PROPERTY}', disabling access delegation."
+            );
+            Self::Disabled
+        }))
+    }
+
+    /// Validate the value of the [`ACCESS_DELEGATION_PROPERTY`], if present.
+    ///
+    /// # Errors
+    /// Fails if the value is not a valid access delegation mode.
+    pub fn validate_properties(properties: &HashMap<String, String>) -> Result<()> {
+        if let Some(value) = properties.get(ACCESS_DELEGATION_PROPERTY) {
+            Self::from_str(value).map_err(|e| {
+                ErrorModel::builder()
+                    .code(http::StatusCode::BAD_REQUEST.into())
+                    .message(format!(
+                        "Invalid value '{value}' for property '{ACCESS_DELEGATION_PROPERTY}'. Expected one of 'remote-signing', 'vended-credentials', 'disabled'."
+                    ))
+                    .r#type("InvalidAccessDelegation".to_string())
+                    .stack(Some(vec![e.to_string()]))
+                    .build()
+            })?;
+        }
+        Ok(())
+    }
+
+    /// The forced `mode` of a table, or the `default` of its warehouse if the
+    /// client did not request a mode via the `X-Iceberg-Access-Delegation` header.
+    #[must_use]
+    pub fn or_default(
+        mode: Option<Self>,
+        default: Option<Self>,
+        requested: &DataAccess,
+    ) -> Option<Self> {
+        mode.or_else(|| {
+            default.filter(|_| !requested.vended_credentials && !requested.remote_signing)
+        })
+    }
+
+    /// Apply the mode to the access requested by the client.
+    /// Returns `None` if access delegation is disabled.
+    #[must_use]
+    pub fn apply(mode: Option<Self>, requested: &DataAccess) -> Option<DataAccess> {
+        match mode {
+            None => Some(requested.clone()),
+            Some(Self::RemoteSigning) => Some(DataAccess {
+                vended_credentials: false,
+                remote_signing: true,
+            }),
+            Some(Self::VendedCredentials) => Some(DataAccess {
+                vended_credentials: true,
+                remote_signing: false,
+            }),
+            Some(Self::Disabled) => None,
+        }
+    }
+}
+
+/// Layout of the locations of new tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TableLayout {
+    /// `<base-location>/<namespace-uuid>/<table-uuid>`
+    #[default]
+    Flat,
+    /// `<base-location>/<hash>/<namespace-uuid>/<table-uuid>`. The hash spreads tables
+    /// across the key space of the bucket, so that busy tables don't share a partition
+    /// of the object store.
+    ObjectStorage,
+}
+
+impl TableLayout {
+    /// Resolve the layout of a new table. The [`OBJECT_STORAGE_PROPERTY`] of the table
+    /// takes precedence over the one of the namespace, which takes precedence over
+    /// the setting of the warehouse.
+    #[must_use]
+    pub fn resolve(
+        table_properties: Option<&HashMap<String, String>>,
+        namespace_properties: Option<&HashMap<String, String>>,
+        warehouse_object_storage: bool,
+    ) -> Self {
+        let object_storage = table_properties
+            .and_then(|p| p.get(OBJECT_STORAGE_PROPERTY))
+            .or_else(|| namespace_properties.and_then(|p| p.get(OBJECT_STORAGE_PROPERTY)))
+            .map_or(warehouse_object_storage, |value| {
+                value.eq_ignore_ascii_case("true")
+            });
+        if object_storage {
+            Self::ObjectStorage
+        } else {
+            Self::Flat
+        }
+    }
+}
 
 /// Storage profile for a warehouse.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, derive_more::From, utoipa::ToSchema)]
@@ -19,6 +175,12 @@ pub enum StorageProfile {
     /// S3 storage profile
     #[serde(rename = "s3")]
     S3(S3Profile),
+    /// Google Cloud Storage profile
+    #[serde(rename = "gcs")]
+    Gcs(GcsProfile),
+    /// Local filesystem storage profile, for development and tests
+    #[serde(rename = "file")]
+    File(FileProfile),
 }
 
 #[derive(Debug, Clone, strum_macros::Display)]
@@ -26,6 +188,10 @@ pub enum StorageProfile {
 pub enum StorageType {
     #[strum(serialize = "s3")]
     S3,
+    #[strum(serialize = "gcs")]
+    Gcs,
+    #[strum(serialize = "file")]
+    File,
 }
 
 #[allow(clippy::module_name_repetitions)]
@@ -34,6 +200,8 @@ impl StorageProfile {
     pub fn generate_catalog_config(&self, warehouse_id: &WarehouseIdent) -> CatalogConfig {
         match self {
             StorageProfile::S3(profile) => profile.generate_catalog_config(warehouse_id),
+            StorageProfile::Gcs(profile) => profile.generate_catalog_config(warehouse_id),
+            StorageProfile::File(profile) => profile.generate_catalog_config(warehouse_id),
         }
     }
 
@@ -48,6 +216,23 @@ impl StorageProfile {
             (StorageProfile::S3(this_profile), StorageProfile::S3(other_profile)) => {
                 this_profile.can_be_updated_with(other_profile)
             }
+            (StorageProfile::Gcs(this_profile), StorageProfile::Gcs(other_profile)) => {
+                this_profile.can_be_updated_with(other_profile)
+            }
+            (StorageProfile::File(this_profile), StorageProfile::File(other_profile)) => {
+                this_profile.can_be_updated_with(other_profile)
+            }
+            _ => Err(ErrorModel::builder()
+                .code(http::StatusCode::BAD_REQUEST.into())
+                .message("Storage Profile type cannot be updated to prevent data loss.".to_string())
+                .r#type("InvalidStorageType".to_string())
+                .stack(Some(vec![format!(
+                    "Current: {}, New: {}",
+                    self.storage_type(),
+                    other.storage_type()
+                )]))
+                .build()
+                .into()),
         }
     }
 
@@ -55,25 +240,98 @@ impl StorageProfile {
     ///
     /// # Errors
     /// Fails if the underlying storage profile's file IO creation fails.
-    pub fn file_io(&self, secret: Option<&StorageCredential>) -> Result<iceberg::io::FileIO> {
+    pub async fn file_io(&self, secret: Option<&StorageCredential>) -> Result<iceberg::io::FileIO> {
         match self {
-            StorageProfile::S3(profile) => profile.file_io(secret.map(|s| match s {
-                StorageCredential::S3(s) => s,
-            })),
+            StorageProfile::S3(profile) => {
+                profile
+                    .file_io(secret.map(StorageCredential::as_s3).transpose()?)
+                    .await
+            }
+            StorageProfile::Gcs(profile) => {
+                profile.file_io(secret.map(StorageCredential::as_gcs).transpose()?)
+            }
+            StorageProfile::File(profile) => profile.file_io(),
         }
     }
 
+    /// Location of a new table in the given `layout`. The hash of the
+    /// [`TableLayout::ObjectStorage`] layout is derived from the `table_id`.
     #[must_use]
     pub fn table_location(
         &self,
         namespace_id: &NamespaceIdentUuid,
         table_id: &TableIdentUuid,
+        layout: TableLayout,
     ) -> String {
-        match self {
-            StorageProfile::S3(profile) => profile.table_location(namespace_id, table_id),
+        match layout {
+            TableLayout::Flat => match self {
+                StorageProfile::S3(profile) => profile.table_location(namespace_id, table_id),
+                StorageProfile::Gcs(profile) => profile.table_location(namespace_id, table_id),
+                StorageProfile::File(profile) => profile.table_location(namespace_id, table_id),
+            },
+            TableLayout::ObjectStorage => {
+                // UUIDv7s start with a timestamp, so the id is hashed to spread the prefixes.
+                let hash = hex(&openssl::sha::sha256(table_id.to_string().as_bytes()));
+                format!(
+                    "{}/{}/{namespace_id}/{table_id}",
+                    self.base_location(),
+                    &hash[..8]
+                )
+            }
+        }
+    }
+
+    /// Location below which all tables of the warehouse are stored, without a trailing slash.
+    #[must_use]
+    pub fn base_location(&self) -> String {
+        let (scheme, bucket, key_prefix) = match self {
+            StorageProfile::S3(profile) => ("s3", &profile.bucket, &profile.key_prefix),
+            StorageProfile::Gcs(profile) => ("gs", &profile.bucket, &profile.key_prefix),
+            StorageProfile::File(profile) => {
+                return format!("file://{}", profile.path.trim_end_matches('/'))
+            }
+        };
+        match key_prefix {
+            Some(key_prefix) => format!("{scheme}://{bucket}/{key_prefix}"),
+            None => format!("{scheme}://{bucket}"),
+        }
+    }
+
+    /// Check that `location` is below [`Self::base_location`], so that credentials
+    /// and signatures issued for it stay within the storage of the warehouse.
+    /// For S3, the `s3a` and `s3n` schemes of Hadoop are accepted as well.
+    ///
+    /// # Errors
+    /// Fails if `location` is outside of the storage of the warehouse.
+    pub fn require_location_within(&self, location: &str) -> Result<()> {
+        let normalized = match self {
+            StorageProfile::S3(_) => location
+                .strip_prefix("s3a://")
+                .or_else(|| location.strip_prefix("s3n://"))
+                .map_or_else(|| location.to_string(), |path| format!("s3://{path}")),
+            StorageProfile::Gcs(_) | StorageProfile::File(_) => location.to_string(),
+        };
+        let base_location = self.base_location();
+        let within = normalized.starts_with(&format!("{base_location}/"))
+            && !normalized
+                .split('/')
+                .any(|segment| segment == ".." || segment == ".");
+        if within {
+            Ok(())
+        } else {
+            Err(ErrorModel::builder()
+                .code(http::StatusCode::FORBIDDEN.into())
+                .message(format!(
+                    "Location '{location}' is outside of the storage of the warehouse '{base_location}'"
+                ))
+                .r#type("LocationOutsideWarehouse".to_string())
+                .build()
+                .into())
         }
     }
 
+    /// Location of a metadata file below `table_location`, so that it
+    /// shares the hash prefix of tables in the [`TableLayout::ObjectStorage`] layout.
     #[must_use]
     pub fn metadata_location(&self, table_location: &str, metadata_id: &uuid::Uuid) -> String {
         format!(
@@ -86,35 +344,97 @@ impl StorageProfile {
     pub fn storage_type(&self) -> StorageType {
         match self {
             StorageProfile::S3(_) => StorageType::S3,
+            StorageProfile::Gcs(_) => StorageType::Gcs,
+            StorageProfile::File(_) => StorageType::File,
         }
     }
 
     /// Generate the table config for the storage profile.
+    /// `access_delegation` overrides the `data_access` requested by the client.
+    /// Vended credentials are restricted to `table_location`.
     ///
     /// # Errors
     /// Fails if the underlying storage profile's generation fails.
     pub async fn generate_table_config(
         &self,
         warehouse_id: &WarehouseIdent,
-        namespace_id: &NamespaceIdentUuid,
-        table_id: &TableIdentUuid,
+        table_location: &str,
         data_access: &DataAccess,
+        access_delegation: Option<AccessDelegation>,
         secret: Option<&StorageCredential>,
     ) -> Result<HashMap<String, String>> {
+        let data_access = AccessDelegation::apply(access_delegation, data_access);
         match self {
             StorageProfile::S3(profile) => {
                 profile
                     .generate_table_config(
                         warehouse_id,
-                        table_id,
-                        namespace_id,
-                        data_access,
-                        secret.map(|s| match s {
-                            StorageCredential::S3(s) => s,
-                        }),
+                        table_location,
+                        data_access.as_ref(),
+                        secret.map(StorageCredential::as_s3).transpose()?,
+                    )
+                    .await
+            }
+            StorageProfile::Gcs(profile) => {
+                profile
+                    .generate_table_config(
+                        warehouse_id,
+                        table_location,
+                        data_access.as_ref(),
+                        secret.map(StorageCredential::as_gcs).transpose()?,
                     )
                     .await
             }
+            // Clients access the files directly, there is nothing to delegate.
+            StorageProfile::File(_) => Ok(HashMap::new()),
+        }
+    }
+
+    /// Pre-signed URLs to read the files at `locations`, valid for `expires_in`.
+    /// Only S3 profiles support pre-signing.
+    ///
+    /// # Errors
+    /// Fails if the profile does not support pre-signing or a URL can't be signed.
+    #[cfg_attr(not(feature = "s3-signer"), allow(unused_variables))]
+    pub async fn presign_get(
+        &self,
+        secret: Option<&StorageCredential>,
+        locations: &[String],
+        expires_in: std::time::Duration,
+    ) -> Result<Vec<String>> {
+        match self {
+            #[cfg(feature = "s3-signer")]
+            StorageProfile::S3(profile) => {
+                let credential = secret.map(StorageCredential::as_s3).transpose()?;
+                let mut urls = Vec::with_capacity(locations.len());
+                for location in locations {
+                    urls.push(
+                        profile
+                            .presign_get(credential, location, expires_in)
+                            .await?,
+                    );
+                }
+                Ok(urls)
+            }
+            #[cfg(not(feature = "s3-signer"))]
+            StorageProfile::S3(_) => Err(ErrorModel::builder()
+                .code(http::StatusCode::NOT_IMPLEMENTED.into())
+                .message(
+                    "Pre-signed URLs require the catalog to be built with the `s3-signer` feature."
+                        .to_string(),
+                )
+                .r#type("PresignNotSupported".to_string())
+                .build()
+                .into()),
+            StorageProfile::Gcs(_) | StorageProfile::File(_) => Err(ErrorModel::builder()
+                .code(http::StatusCode::BAD_REQUEST.into())
+                .message(format!(
+                    "Pre-signed URLs are not available for storage type {}",
+                    self.storage_type()
+                ))
+                .r#type("PresignNotSupported".to_string())
+                .build()
+                .into()),
         }
     }
 
@@ -126,12 +446,81 @@ impl StorageProfile {
         match self {
             StorageProfile::S3(profile) => {
                 profile
-                    .validate(secret.map(|s| match s {
-                        StorageCredential::S3(s) => s,
-                    }))
+                    .validate(secret.map(StorageCredential::as_s3).transpose()?)
+                    .await
+            }
+            StorageProfile::Gcs(profile) => {
+                profile
+                    .validate(secret.map(StorageCredential::as_gcs).transpose()?)
                     .await
             }
+            StorageProfile::File(profile) => {
+                if let Some(secret) = secret {
+                    return Err(secret.mismatch(&StorageType::File));
+                }
+                profile.validate().await
+            }
+        }
+    }
+
+    /// Write a canary object to the storage, read it back and delete it.
+    /// Unlike [`Self::validate`], the profile itself is not checked.
+    ///
+    /// # Errors
+    /// Fails if any of the operations fails or the object read differs from the one written.
+    pub async fn probe(&self, secret: Option<&StorageCredential>) -> Result<()> {
+        let file_io = self.file_io(secret).await?;
+        let location = self.table_location(
+            &uuid::Uuid::now_v7().into(),
+            &uuid::Uuid::now_v7().into(),
+            TableLayout::Flat,
+        );
+        let location = format!("{location}/.health-check");
+        let canary = format!("canary {}", uuid::Uuid::now_v7());
+        let error = |r#type: &str, e: &dyn std::fmt::Display| {
+            ErrorModel::builder()
+                .code(http::StatusCode::FAILED_DEPENDENCY.into())
+                .message(format!("Storage probe failed: {e}"))
+                .r#type(r#type.to_string())
+                .stack(Some(vec![format!("Location: {location}")]))
+                .build()
+        };
+
+        let mut writer = file_io
+            .new_output(&location)
+            .map_err(|e| error("StorageProbeWriteError", &e))?
+            .writer()
+            .await
+            .map_err(|e| error("StorageProbeWriteError", &e))?;
+        writer
+            .write(canary.clone().into_bytes().into())
+            .await
+            .map_err(|e| error("StorageProbeWriteError", &e))?;
+        writer
+            .close()
+            .await
+            .map_err(|e| error("StorageProbeWriteError", &e))?;
+
+        let read = file_io
+            .new_input(&location)
+            .map_err(|e| error("StorageProbeReadError", &e))?
+            .read()
+            .await
+            .map_err(|e| error("StorageProbeReadError", &e))?;
+        if read.as_ref() != canary.as_bytes() {
+            return Err(error(
+                "StorageProbeReadError",
+                &"object read differs from the object written",
+            )
+            .into());
         }
+
+        file_io
+            .delete(&location)
+            .await
+            .map_err(|e| error("StorageProbeDeleteError", &e))?;
+
+        Ok(())
     }
 
     /// Try to convert the storage profile into an S3 profile.
@@ -141,8 +530,7 @@ impl StorageProfile {
     pub fn try_into_s3(self, code: u16) -> Result<S3Profile> {
         match self {
             Self::S3(profile) => Ok(profile),
-            #[allow(unreachable_patterns)] // More profiles will be added in the future
-            _ => Err(ErrorModel::builder()
+            Self::Gcs(_) | Self::File(_) => Err(ErrorModel::builder()
                 .code(code)
                 .message("Storage profile is not S3".to_string())
                 .r#type("StorageProfileNotS3".to_string())
@@ -162,6 +550,9 @@ pub enum StorageCredential {
     /// Credentials for S3 storage
     #[serde(rename = "s3")]
     S3(S3Credential),
+    /// Credentials for Google Cloud Storage
+    #[serde(rename = "gcs")]
+    Gcs(GcsCredential),
 }
 
 impl SecretInStorage for StorageCredential {}
@@ -171,9 +562,36 @@ impl StorageCredential {
     pub fn storage_type(&self) -> StorageType {
         match self {
             StorageCredential::S3(_) => StorageType::S3,
+            StorageCredential::Gcs(_) => StorageType::Gcs,
         }
     }
 
+    fn as_s3(&self) -> Result<&S3Credential> {
+        match self {
+            Self::S3(credential) => Ok(credential),
+            Self::Gcs(_) => Err(self.mismatch(&StorageType::S3)),
+        }
+    }
+
+    fn as_gcs(&self) -> Result<&GcsCredential> {
+        match self {
+            Self::Gcs(credential) => Ok(credential),
+            Self::S3(_) => Err(self.mismatch(&StorageType::Gcs)),
+        }
+    }
+
+    fn mismatch(&self, expected: &StorageType) -> crate::api::IcebergErrorResponse {
+        ErrorModel::builder()
+            .code(http::StatusCode::BAD_REQUEST.into())
+            .message(format!(
+                "Storage credential of type {} can't be used for a {expected} storage profile",
+                self.storage_type()
+            ))
+            .r#type("StorageCredentialTypeMismatch".to_string())
+            .build()
+            .into()
+    }
+
     /// Try to convert the credential into an S3 credential.
     ///
     /// # Errors
@@ -181,8 +599,7 @@ impl StorageCredential {
     pub fn try_into_s3(self, code: u16) -> Result<S3Credential> {
         match self {
             Self::S3(profile) => Ok(profile),
-            #[allow(unreachable_patterns)] // More profiles will be added in the future
-            _ => Err(ErrorModel::builder()
+            Self::Gcs(_) => Err(ErrorModel::builder()
                 .code(code)
                 .message("Storage profile is not S3".to_string())
                 .r#type("StorageProfileNotS3".to_string())
@@ -193,10 +610,206 @@ impl StorageCredential {
     }
 }
 
+/// Validate a `FileIO` instance by writing and deleting a test file at `test_location`.
+async fn validate_file_io(
+    file_io: &iceberg::io::FileIO,
+    test_location: &str,
+    storage: &str,
+) -> Result<()> {
+    let error = |kind: &str, e: &dyn std::fmt::Display| {
+        ErrorModel::builder()
+            .code(http::StatusCode::BAD_REQUEST.into())
+            .message(format!("Error validating {storage} Storage Profile: {e}"))
+            .stack(Some(vec![e.to_string()]))
+            .r#type(format!("{storage}{kind}"))
+            .build()
+    };
+
+    let test_file = file_io
+        .new_output(test_location)
+        .map_err(|e| error("TestFileCreationError", &e))?;
+    let mut writer = test_file
+        .writer()
+        .await
+        .map_err(|e| error("TestFileWriterError", &e))?;
+
+    let buf: &[u8; 4] = b"test";
+    writer
+        .write(buf.to_vec().into())
+        .await
+        .map_err(|e| error("TestFileWriteError", &e))?;
+    writer
+        .close()
+        .await
+        .map_err(|e| error("TestFileCloseError", &e))?;
+    file_io
+        .delete(test_location)
+        .await
+        .map_err(|e| error("TestFileDeleteError", &e))?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_require_location_within() {
+        let profile = StorageProfile::S3(S3Profile {
+            bucket: "test-bucket".to_string(),
+            key_prefix: Some("warehouse".to_string()),
+            assume_role_arn: None,
+            external_id: None,
+            endpoint: None,
+            region: "us-east-1".to_string(),
+            path_style_access: None,
+            sts_enabled: false,
+            flavor: S3Flavor::Aws,
+            sse_algorithm: None,
+            kms_key_arn: None,
+        });
+        assert_eq!(profile.base_location(), "s3://test-bucket/warehouse");
+
+        for location in [
+            "s3://test-bucket/warehouse/ns/table",
+            "s3a://test-bucket/warehouse/ns/table/data",
+        ] {
+            assert!(
+                profile.require_location_within(location).is_ok(),
+                "{location}"
+            );
+        }
+        for location in [
+            "s3://test-bucket/warehouse",
+            "s3://test-bucket/warehouse-other/table",
+            "s3://other-bucket/warehouse/table",
+            "s3://test-bucket/warehouse/../other/table",
+            "gs://test-bucket/warehouse/table",
+        ] {
+            let err = profile.require_location_within(location).unwrap_err();
+            assert_eq!(err.error.r#type, "LocationOutsideWarehouse", "{location}");
+        }
+
+        let profile = StorageProfile::File(FileProfile {
+            path: "/tmp/warehouse".to_string(),
+        });
+        assert!(profile
+            .require_location_within("file:///tmp/warehouse/ns/table")
+            .is_ok());
+        assert!(profile
+            .require_location_within("file:///tmp/other/table")
+            .is_err());
+    }
+
+    #[test]
+    fn test_object_storage_table_location() {
+        let profile = StorageProfile::File(FileProfile {
+            path: "/tmp/warehouse".to_string(),
+        });
+        let namespace_id = NamespaceIdentUuid::from(uuid::Uuid::now_v7());
+        let table_id = TableIdentUuid::from(uuid::Uuid::now_v7());
+
+        assert_eq!(
+            profile.table_location(&namespace_id, &table_id, TableLayout::Flat),
+            format!("file:///tmp/warehouse/{namespace_id}/{table_id}")
+        );
+        let location = profile.table_location(&namespace_id, &table_id, TableLayout::ObjectStorage);
+        let hash = location
+            .strip_prefix("file:///tmp/warehouse/")
+            .unwrap()
+            .strip_suffix(&format!("/{namespace_id}/{table_id}"))
+            .unwrap();
+        assert_eq!(hash.len(), 8);
+        assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+        // The hash is stable for a table
+        assert_eq!(
+            profile.table_location(&namespace_id, &table_id, TableLayout::ObjectStorage),
+            location
+        );
+        assert!(profile.require_location_within(&location).is_ok());
+        assert!(profile
+            .metadata_location(&location, &uuid::Uuid::now_v7())
+            .starts_with(&format!("{location}/metadata/")));
+    }
+
+    #[test]
+    fn test_resolve_table_layout() {
+        let enabled = HashMap::from([(OBJECT_STORAGE_PROPERTY.to_string(), "true".to_string())]);
+        let disabled = HashMap::from([(OBJECT_STORAGE_PROPERTY.to_string(), "false".to_string())]);
+
+        assert_eq!(TableLayout::resolve(None, None, false), TableLayout::Flat);
+        assert_eq!(
+            TableLayout::resolve(None, None, true),
+            TableLayout::ObjectStorage
+        );
+        assert_eq!(
+            TableLayout::resolve(Some(&HashMap::new()), Some(&enabled), false),
+            TableLayout::ObjectStorage
+        );
+        assert_eq!(
+            TableLayout::resolve(Some(&disabled), Some(&enabled), true),
+            TableLayout::Flat
+        );
+    }
+
+    #[test]
+    fn test_resolve_access_delegation() {
+        let table = HashMap::from([(
+            ACCESS_DELEGATION_PROPERTY.to_string(),
+            "remote-signing".to_string(),
+        )]);
+        let namespace = HashMap::from([(
+            ACCESS_DELEGATION_PROPERTY.to_string(),
+            "disabled".to_string(),
+        )]);
+        let invalid = HashMap::from([(
+            ACCESS_DELEGATION_PROPERTY.to_string(),
+            "remote-singing".to_string(),
+        )]);
+
+        assert_eq!(AccessDelegation::resolve(None, None), None);
+        assert_eq!(
+            AccessDelegation::resolve(Some(&table), Some(&namespace)),
+            Some(AccessDelegation::RemoteSigning)
+        );
+        assert_eq!(
+            AccessDelegation::resolve(Some(&HashMap::new()), Some(&namespace)),
+            Some(AccessDelegation::Disabled)
+        );
+        assert_eq!(
+            AccessDelegation::resolve(Some(&invalid), None),
+            Some(AccessDelegation::Disabled)
+        );
+        assert!(AccessDelegation::validate_properties(&table).is_ok());
+        assert!(AccessDelegation::validate_properties(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_access_delegation_default() {
+        let none = DataAccess {
+            vended_credentials: false,
+            remote_signing: false,
+        };
+        let vended = DataAccess {
+            vended_credentials: true,
+            remote_signing: false,
+        };
+        let default = Some(AccessDelegation::VendedCredentials);
+
+        assert_eq!(
+            AccessDelegation::or_default(None, default, &none),
+            Some(AccessDelegation::VendedCredentials)
+        );
+        // A mode requested by the client takes precedence over the default
+        assert_eq!(AccessDelegation::or_default(None, default, &vended), None);
+        // A mode forced by a property takes precedence over both
+        assert_eq!(
+            AccessDelegation::or_default(Some(AccessDelegation::Disabled), default, &none),
+            Some(AccessDelegation::Disabled)
+        );
+    }
+
     #[test]
     fn test_redact() {
         let secrets: StorageCredential = S3Credential::AccessKey {
@@ -213,6 +826,45 @@ mod tests {
 
         let debug_print = format!("{secrets:?}");
         assert!(!debug_print.contains("tnFEMI"));
+
+        let request = crate::api::management::v1::warehouse::CreateWarehouseRequest {
+            warehouse_name: "warehouse".to_string(),
+            project_id: uuid::Uuid::nil(),
+            storage_profile: StorageProfile::S3(S3Profile {
+                bucket: "my-bucket".to_string(),
+                key_prefix: None,
+                assume_role_arn: None,
+                external_id: None,
+                endpoint: None,
+                region: "us-east-1".to_string(),
+                path_style_access: None,
+                sts_enabled: false,
+                flavor: S3Flavor::Aws,
+                sse_algorithm: None,
+                kms_key_arn: None,
+            }),
+            storage_credential: Some(secrets),
+        };
+        let table_credential = iceberg_ext::catalog::rest::TableCredential {
+            prefix: "s3://my-bucket".to_string(),
+            config: HashMap::from([(
+                "s3.secret-access-key".to_string(),
+                "wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY".to_string(),
+            )]),
+        };
+        // Error stacks may contain serialized credentials, e.g. from a storage SDK.
+        let error_stack = format!(
+            "Failed to validate credential: {}",
+            serde_json::to_string(&request.storage_credential).unwrap()
+        );
+
+        for output in [
+            format!("{request:?}"),
+            format!("{table_credential:?}"),
+            iceberg_ext::redaction::redact_text(&error_stack),
+        ] {
+            assert!(!output.contains("tnFEMI"), "{output}");
+        }
     }
 
     #[test]
@@ -232,7 +884,12 @@ mod tests {
                 endpoint: Some("http://localhost:9000".to_string()),
                 region: "us-east-1".to_string(),
                 assume_role_arn: None,
+                external_id: None,
                 path_style_access: None,
+                sts_enabled: false,
+                flavor: S3Flavor::Aws,
+                sse_algorithm: None,
+                kms_key_arn: None,
                 key_prefix: None,
             })
         );
@@ -59,6 +59,52 @@ fn is_valid_bucket_name(bucket: &str) -> Result<()> {
     Ok(())
 }
 
+/// Kind of store behind an S3 profile.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::module_name_repetitions)]
+#[serde(rename_all = "kebab-case")]
+pub enum S3Flavor {
+    /// AWS S3 or a store with full parity.
+    #[default]
+    Aws,
+    /// S3-compatible store such as Cloudflare R2 or Ceph RGW, which lack parity with
+    /// AWS and may use custom regions such as `auto`. Requires an `endpoint`.
+    /// STS is not available and path-style access is always used. Requests are signed
+    /// for the region of the profile, regardless of the region sent by the client.
+    S3Compat,
+}
+
+/// Server-side encryption of the objects of a warehouse.
+/// Names follow the `x-amz-server-side-encryption` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[allow(clippy::module_name_repetitions)]
+pub enum S3SseAlgorithm {
+    /// SSE-S3 with keys managed by S3.
+    #[serde(rename = "AES256")]
+    Aes256,
+    /// SSE-KMS with `kms-key-arn`, or the AWS managed key if no key is set.
+    #[serde(rename = "aws:kms")]
+    AwsKms,
+    /// Dual-layer SSE-KMS with `kms-key-arn`, or the AWS managed key if no key is set.
+    #[serde(rename = "aws:kms:dsse")]
+    AwsKmsDsse,
+}
+
+impl S3SseAlgorithm {
+    /// Value of the `s3.sse.type` property understood by Iceberg's `FileIO` implementations.
+    fn sse_type(self) -> &'static str {
+        match self {
+            Self::Aes256 => "s3",
+            Self::AwsKms => "kms",
+            Self::AwsKmsDsse => "dsse-kms",
+        }
+    }
+
+    fn uses_kms(self) -> bool {
+        matches!(self, Self::AwsKms | Self::AwsKmsDsse)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(clippy::module_name_repetitions)]
 #[schema(rename_all = "kebab-case")]
@@ -70,8 +116,13 @@ pub struct S3Profile {
     /// The same prefix can be used for multiple warehouses.
     pub key_prefix: Option<String>,
     #[serde(default)]
-    /// Optional ARN to assume when accessing the bucket
+    /// Optional ARN to assume when accessing the bucket.
+    /// The role is assumed with the storage credential, or with the credentials
+    /// of the server if the warehouse has no credential.
     pub assume_role_arn: Option<String>,
+    /// External id required by the trust policy of `assume_role_arn`.
+    #[serde(default)]
+    pub external_id: Option<String>,
     /// Optional endpoint to use for S3 requests, if not provided
     /// the region will be used to determine the endpoint.
     /// If both region and endpoint are provided, the endpoint will be used.
@@ -82,6 +133,22 @@ pub struct S3Profile {
     /// Path style access for S3 requests.
     #[serde(default)]
     pub path_style_access: Option<bool>,
+    /// Vend temporary credentials from STS that are restricted to the location of a table
+    /// if clients request `vended-credentials`. Requires `assume-role-arn`, unless the
+    /// `endpoint` issues STS credentials without a role, as `MinIO` does.
+    #[serde(default)]
+    pub sts_enabled: bool,
+    /// Kind of store. Defaults to `aws`.
+    #[serde(default)]
+    pub flavor: S3Flavor,
+    /// Server-side encryption of objects written by the catalog and by clients.
+    /// If not set, the default encryption of the bucket applies.
+    #[serde(default)]
+    pub sse_algorithm: Option<S3SseAlgorithm>,
+    /// ARN of the KMS key used by `aws:kms` and `aws:kms:dsse` encryption.
+    /// Must be in the region of the bucket.
+    #[serde(default)]
+    pub kms_key_arn: Option<String>,
 }
 
 impl S3Profile {
@@ -93,6 +160,7 @@ impl S3Profile {
     /// - Fails if the key prefix is too long.
     /// - Fails if the region or endpoint is missing.
     /// - Fails if the endpoint is not a valid URL.
+    /// - Fails if STS is enabled but no credentials can be vended.
     pub async fn validate(&mut self, credential: Option<&S3Credential>) -> Result<()> {
         // If key_prefix is provided, remove any trailing and leading slashes.
         if let Some(key_prefix) = self.key_prefix.as_mut() {
@@ -102,14 +170,77 @@ impl S3Profile {
         let S3Profile {
             bucket,
             key_prefix,
-            // Validated via file_io
-            assume_role_arn: _,
+            assume_role_arn,
+            external_id: _,
             endpoint,
             region,
             // Validated via file_io
             path_style_access: _,
+            sts_enabled,
+            flavor,
+            sse_algorithm,
+            kms_key_arn,
         } = self;
 
+        if let Some(kms_key_arn) = kms_key_arn {
+            if !sse_algorithm.is_some_and(S3SseAlgorithm::uses_kms) {
+                return Err(ErrorModel::builder()
+                    .code(StatusCode::BAD_REQUEST.into())
+                    .message(
+                        "Storage Profile `kms-key-arn` requires `sse-algorithm` `aws:kms` or `aws:kms:dsse`."
+                            .to_string(),
+                    )
+                    .r#type("InvalidSseConfiguration".to_string())
+                    .build()
+                    .into());
+            }
+            validate_kms_key_arn(kms_key_arn, region, *flavor)?;
+        }
+
+        if *flavor == S3Flavor::S3Compat {
+            if endpoint.is_none() {
+                return Err(ErrorModel::builder()
+                    .code(StatusCode::BAD_REQUEST.into())
+                    .message(
+                        "Storage Profile `endpoint` is required for S3-compatible stores."
+                            .to_string(),
+                    )
+                    .r#type("InvalidS3Endpoint".to_string())
+                    .build()
+                    .into());
+            }
+            if *sts_enabled {
+                return Err(ErrorModel::builder()
+                    .code(StatusCode::BAD_REQUEST.into())
+                    .message("STS is not available for S3-compatible stores.".to_string())
+                    .r#type("InvalidStsConfiguration".to_string())
+                    .build()
+                    .into());
+            }
+        }
+
+        if *sts_enabled {
+            if !cfg!(feature = "s3-signer") {
+                return Err(ErrorModel::builder()
+                    .code(StatusCode::NOT_IMPLEMENTED.into())
+                    .message("STS requires the `s3-signer` feature.".to_string())
+                    .r#type("StsNotSupported".to_string())
+                    .build()
+                    .into());
+            }
+            if assume_role_arn.is_none() && endpoint.is_none() {
+                return Err(ErrorModel::builder()
+                    .code(StatusCode::BAD_REQUEST.into())
+                    .message(
+                        "Storage Profile `assume-role-arn` is required to use STS on AWS."
+                            .to_string(),
+                    )
+                    .r#type("InvalidStsConfiguration".to_string())
+                    .build()
+                    .into());
+            }
+        }
+
         is_valid_bucket_name(bucket)?;
 
         if region.len() > 128 {
@@ -159,17 +290,27 @@ impl S3Profile {
             }
         }
 
-        let file_io = self.file_io(credential)?;
+        let file_io = self.file_io(credential).await?;
         let test_location =
             self.table_location(&uuid::Uuid::now_v7().into(), &uuid::Uuid::now_v7().into());
 
-        validate_file_io(&file_io, &test_location)
+        super::validate_file_io(&file_io, &test_location, "S3")
             .await
             .map_err(|mut e| {
                 e.error.push_to_stack(format!("Profile: {self:?}"));
                 e
             })?;
 
+        #[cfg(feature = "s3-signer")]
+        if self.sts_enabled {
+            self.vended_credentials(credential, &test_location)
+                .await
+                .map_err(|mut e| {
+                    e.error.push_to_stack(format!("Profile: {self:?}"));
+                    e
+                })?;
+        }
+
         Ok(())
     }
 
@@ -221,58 +362,176 @@ impl S3Profile {
 
     #[cfg(feature = "s3-signer")]
     /// Get the AWS SDK credentials for the S3 profile.
+    /// If `assume_role_arn` is set, these are temporary credentials of the role.
     ///
     /// # Errors
-    /// Fails if the assume role ARN is provided.
-    /// Fails if the credential is missing.
-    pub fn get_aws_sdk_credentials(
+    /// Fails if the credential is missing or the role can't be assumed.
+    pub async fn get_aws_sdk_credentials(
         &self,
         credential: Option<&S3Credential>,
     ) -> Result<aws_credential_types::Credentials> {
-        let Self {
-            assume_role_arn,
-            endpoint: _,
-            region: _,
-            path_style_access: _,
-            bucket: _,
-            key_prefix: _,
-        } = self;
+        if let Some(assume_role_arn) = &self.assume_role_arn {
+            return self
+                .assume_role(Some(assume_role_arn), credential, None)
+                .await
+                .map(Into::into);
+        }
 
-        // assume_role_arn is not supported currently
-        if let Some(_assume_role_arn) = assume_role_arn {
-            return Err(ErrorModel::builder()
-                .code(StatusCode::NOT_IMPLEMENTED.into())
-                .message("S3 Assume role ARN not supported.".to_string())
-                .r#type("S3AssumeRoleNotSupported".to_string())
+        // Currently there is no supported configuration without Credential
+        credential
+            .map(S3Credential::to_aws_sdk_credentials)
+            .ok_or_else(missing_credential)
+    }
+
+    /// Temporary credentials of `role_arn`, restricted by `policy` if given.
+    #[cfg(feature = "s3-signer")]
+    async fn assume_role(
+        &self,
+        role_arn: Option<&str>,
+        credential: Option<&S3Credential>,
+        policy: Option<&str>,
+    ) -> Result<super::sts::SessionCredentials> {
+        let source = credential
+            .map(S3Credential::to_aws_sdk_credentials)
+            .or_else(super::sts::environment_credentials)
+            .ok_or_else(missing_credential)?;
+        super::sts::assume_role(
+            &source,
+            &self.region,
+            self.endpoint.as_deref(),
+            &super::sts::AssumeRole {
+                role_arn,
+                external_id: self.external_id.as_deref(),
+                policy,
+                duration_seconds: 3600,
+            },
+        )
+        .await
+    }
+
+    /// Whether requests address the bucket in the path instead of the host name.
+    /// Always the case for S3-compatible stores.
+    #[must_use]
+    pub fn path_style(&self) -> bool {
+        self.flavor == S3Flavor::S3Compat || self.path_style_access.unwrap_or(false)
+    }
+
+    /// Pre-signed URL to read the object at `location`, valid for `expires_in`.
+    ///
+    /// # Errors
+    /// Fails if `location` is not in the bucket of the profile or the request can't be signed.
+    #[cfg(feature = "s3-signer")]
+    pub async fn presign_get(
+        &self,
+        credential: Option<&S3Credential>,
+        location: &str,
+        expires_in: std::time::Duration,
+    ) -> Result<String> {
+        use aws_sigv4::http_request::{
+            sign, PercentEncodingMode, SignableBody, SignableRequest, SignatureLocation,
+            SigningSettings, UriPathNormalizationMode,
+        };
+
+        let presign_error = |message: &str, e: &dyn std::fmt::Display| {
+            ErrorModel::builder()
+                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message(message.to_string())
+                .r#type("PresignError".to_string())
+                .stack(Some(vec![e.to_string()]))
                 .build()
-                .into());
+        };
+        let key = location
+            .split_once("://")
+            .and_then(|(_, path)| path.strip_prefix(&format!("{}/", self.bucket)))
+            .ok_or_else(|| {
+                ErrorModel::builder()
+                    .code(StatusCode::BAD_REQUEST.into())
+                    .message(format!(
+                        "Location '{location}' is not in the bucket of the warehouse"
+                    ))
+                    .r#type("InvalidLocation".to_string())
+                    .build()
+            })?;
+        let mut url = self.object_url(key)?;
+        let credentials = self.get_aws_sdk_credentials(credential).await?;
+
+        // S3 expects the path to be encoded once and not normalized.
+        let mut settings = SigningSettings::default();
+        settings.signature_location = SignatureLocation::QueryParams;
+        settings.expires_in = Some(expires_in);
+        settings.percent_encoding_mode = PercentEncodingMode::Single;
+        settings.uri_path_normalization_mode = UriPathNormalizationMode::Disabled;
+        let identity = credentials.into();
+        let signing_params = aws_sigv4::sign::v4::SigningParams::builder()
+            .identity(&identity)
+            .region(&self.region)
+            .name("s3")
+            .time(std::time::SystemTime::now())
+            .settings(settings)
+            .build()
+            .map_err(|e| presign_error("Failed to create signing params", &e))?
+            .into();
+        let signable_request = SignableRequest::new(
+            "GET",
+            url.as_str(),
+            std::iter::empty(),
+            SignableBody::UnsignedPayload,
+        )
+        .map_err(|e| presign_error("Request is not signable", &e))?;
+        let (instructions, _signature) = sign(signable_request, &signing_params)
+            .map_err(|e| presign_error("Failed to sign request", &e))?
+            .into_parts();
+
+        for (name, value) in instructions.params() {
+            url.query_pairs_mut().append_pair(name, value);
         }
+        Ok(url.to_string())
+    }
 
-        // Currently there is no supported configuration without Credential
-        if let Some(credential) = credential {
-            match credential {
-                S3Credential::AccessKey {
-                    aws_access_key_id,
-                    aws_secret_access_key,
-                } => {
-                    let credentials = aws_credential_types::Credentials::new(
-                        aws_access_key_id.clone(),
-                        aws_secret_access_key.clone(),
-                        None,
-                        None,
-                        "iceberg-rest-secret-storage",
-                    );
-                    Ok(credentials)
-                }
-            }
-        } else {
-            Err(ErrorModel::builder()
-                .code(StatusCode::BAD_REQUEST.into())
-                .message("Storage Credentials missing.".to_string())
-                .r#type("MissingStorageCredential".to_string())
+    /// URL of the object `key` in the bucket, honoring `endpoint` and `path_style_access`.
+    #[cfg(feature = "s3-signer")]
+    fn object_url(&self, key: &str) -> Result<url::Url> {
+        let endpoint = self
+            .endpoint
+            .clone()
+            .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", self.region));
+        let mut url = url::Url::parse(&endpoint).map_err(|e| {
+            ErrorModel::builder()
+                .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                .message("Failed to parse storage profile endpoint".to_string())
+                .r#type("FailedToParseStorageProfileEndpoint".to_string())
+                .stack(Some(vec![e.to_string()]))
                 .build()
-                .into())
+        })?;
+        let base_path = url.path().trim_end_matches('/').to_string();
+        if self.path_style() {
+            url.set_path(&format!("{base_path}/{}/{key}", self.bucket));
+        } else {
+            let host = format!("{}.{}", self.bucket, url.host_str().unwrap_or_default());
+            url.set_host(Some(&host)).map_err(|e| {
+                ErrorModel::builder()
+                    .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+                    .message("Bucket is not a valid host name".to_string())
+                    .r#type("InvalidBucketHost".to_string())
+                    .stack(Some(vec![e.to_string()]))
+                    .build()
+            })?;
+            url.set_path(&format!("{base_path}/{key}"));
         }
+        Ok(url)
+    }
+
+    /// Temporary credentials that only permit access to objects below `table_location`,
+    /// and to the KMS key of the profile if one is set.
+    #[cfg(feature = "s3-signer")]
+    async fn vended_credentials(
+        &self,
+        credential: Option<&S3Credential>,
+        table_location: &str,
+    ) -> Result<super::sts::SessionCredentials> {
+        let policy = table_policy(&self.bucket, table_location, self.kms_key_arn.as_deref());
+        self.assume_role(self.assume_role_arn.as_deref(), credential, Some(&policy))
+            .await
     }
 
     #[must_use]
@@ -304,38 +563,25 @@ impl S3Profile {
         }
     }
 
-    // Vended credentials will be async.
-    #[allow(clippy::unused_async)]
     /// Generate the table configuration for S3.
+    /// If `data_access` is `None`, neither credentials nor signing are configured.
+    /// If vended credentials are requested and `sts_enabled` is set, clients receive
+    /// STS session credentials restricted to the location of the table.
     ///
     /// # Errors
-    /// Fails if vended credentials are used - currently not supported.
+    /// Fails if STS does not issue credentials.
+    #[cfg_attr(not(feature = "s3-signer"), allow(clippy::unused_async))]
     pub async fn generate_table_config(
         &self,
         _: &WarehouseIdent,
-        _: &TableIdentUuid,
-        _: &NamespaceIdentUuid,
-        data_access: &DataAccess,
-        _: Option<&S3Credential>,
+        table_location: &str,
+        data_access: Option<&DataAccess>,
+        credential: Option<&S3Credential>,
     ) -> Result<HashMap<String, String>> {
-        let DataAccess {
-            vended_credentials,
-            remote_signing,
-        } = data_access;
-        // If vended_credentials is False and remote_signing is False,
-        // use remote_signing.
-        let mut remote_signing = if !vended_credentials && !remote_signing {
-            true
-        } else {
-            *remote_signing
-        };
-
         let mut config = HashMap::new();
 
-        if let Some(path_style_access) = self.path_style_access {
-            if path_style_access {
-                config.insert("s3.path-style-access".to_string(), "true".to_string());
-            }
+        if self.path_style() {
+            config.insert("s3.path-style-access".to_string(), "true".to_string());
         }
 
         config.insert("s3.region".to_string(), self.region.to_string());
@@ -346,6 +592,41 @@ impl S3Profile {
             config.insert("s3.endpoint".to_string(), endpoint.to_string());
         }
 
+        config.extend(self.sse_properties());
+
+        let Some(DataAccess {
+            vended_credentials,
+            remote_signing,
+        }) = data_access
+        else {
+            return Ok(config);
+        };
+        // If vended_credentials is False and remote_signing is False,
+        // use remote_signing.
+        let mut remote_signing = if !vended_credentials && !remote_signing {
+            true
+        } else {
+            *remote_signing
+        };
+
+        #[cfg(feature = "s3-signer")]
+        if *vended_credentials && self.sts_enabled {
+            let session = self.vended_credentials(credential, table_location).await?;
+            config.insert("s3.access-key-id".to_string(), session.access_key_id);
+            config.insert(
+                "s3.secret-access-key".to_string(),
+                session.secret_access_key,
+            );
+            config.insert("s3.session-token".to_string(), session.session_token);
+            config.insert(
+                "s3.session-token-expires-at-ms".to_string(),
+                session.expiration.timestamp_millis().to_string(),
+            );
+            return Ok(config);
+        }
+        #[cfg(not(feature = "s3-signer"))]
+        let _ = (table_location, credential);
+
         if *vended_credentials {
             // ToDo: Find a better way.
             // Vended-Credentials are requested by pyiceberg. However, we can trick pyiceberg in using
@@ -376,11 +657,27 @@ impl S3Profile {
         Ok(config)
     }
 
+    /// `s3.sse.*` properties making writers encrypt objects as configured.
+    fn sse_properties(&self) -> Vec<(String, String)> {
+        let Some(sse_algorithm) = self.sse_algorithm else {
+            return vec![];
+        };
+        let mut properties = vec![(
+            "s3.sse.type".to_string(),
+            sse_algorithm.sse_type().to_string(),
+        )];
+        if let Some(kms_key_arn) = &self.kms_key_arn {
+            properties.push(("s3.sse.key".to_string(), kms_key_arn.clone()));
+        }
+        properties
+    }
+
     /// Create a new `FileIO` instance for S3.
+    /// If `assume_role_arn` is set, the instance uses temporary credentials of the role.
     ///
     /// # Errors
-    /// Fails if the `FileIO` instance cannot be created.
-    pub fn file_io(&self, credential: Option<&S3Credential>) -> Result<iceberg::io::FileIO> {
+    /// Fails if the `FileIO` instance cannot be created or the role can't be assumed.
+    pub async fn file_io(&self, credential: Option<&S3Credential>) -> Result<iceberg::io::FileIO> {
         let mut builder = iceberg::io::FileIOBuilder::new("s3");
 
         builder = builder.with_prop(iceberg::io::S3_REGION, self.region.clone());
@@ -388,15 +685,32 @@ impl S3Profile {
         if let Some(endpoint) = &self.endpoint {
             builder = builder.with_prop(iceberg::io::S3_ENDPOINT, endpoint);
         }
-        if let Some(_assume_role_arn) = &self.assume_role_arn {
-            return Err(ErrorModel::builder()
-                .code(StatusCode::NOT_IMPLEMENTED.into())
-                .message("Assume role ARN not supported.".to_string())
-                .r#type("AssumeRoleNotSupported".to_string())
-                .build()
-                .into());
+        // Validation writes a test file, which also checks that the KMS key can be used.
+        for (key, value) in self.sse_properties() {
+            builder = builder.with_prop(key, value);
         }
-        if let Some(credential) = credential {
+        if let Some(assume_role_arn) = &self.assume_role_arn {
+            #[cfg(feature = "s3-signer")]
+            {
+                let session = self
+                    .assume_role(Some(assume_role_arn), credential, None)
+                    .await?;
+                builder = builder
+                    .with_prop(iceberg::io::S3_ACCESS_KEY_ID, session.access_key_id)
+                    .with_prop(iceberg::io::S3_SECRET_ACCESS_KEY, session.secret_access_key)
+                    .with_prop(iceberg::io::S3_SESSION_TOKEN, session.session_token);
+            }
+            #[cfg(not(feature = "s3-signer"))]
+            {
+                let _ = assume_role_arn;
+                return Err(ErrorModel::builder()
+                    .code(StatusCode::NOT_IMPLEMENTED.into())
+                    .message("Assume role requires the `s3-signer` feature.".to_string())
+                    .r#type("AssumeRoleNotSupported".to_string())
+                    .build()
+                    .into());
+            }
+        } else if let Some(credential) = credential {
             match credential {
                 S3Credential::AccessKey {
                     aws_access_key_id,
@@ -429,61 +743,103 @@ pub enum S3Credential {
     #[serde(rename_all = "kebab-case")]
     AccessKey {
         aws_access_key_id: String,
-        #[redact(partial)]
+        #[redact]
         aws_secret_access_key: String,
     },
 }
 
-// ToDo: Move somewhere so that other profiles can use it as well?
-async fn validate_file_io(file_io: &iceberg::io::FileIO, test_location: &str) -> Result<()> {
-    // Validate the file_io instance by creating a test file.
-
-    let test_file = file_io.new_output(test_location).map_err(|e| {
-        ErrorModel::builder()
-            .code(StatusCode::BAD_REQUEST.into())
-            .message(format!("Error validating S3 Storage Profile: {e}").to_string())
-            .stack(Some(vec![e.to_string()]))
-            .r#type("S3TestFileCreationError".to_string())
-            .build()
-    })?;
-    let mut writer = test_file.writer().await.map_err(|e| {
-        ErrorModel::builder()
-            .code(StatusCode::BAD_REQUEST.into())
-            .message(format!("Error validating S3 Storage Profile: {e}").to_string())
-            .stack(Some(vec![e.to_string()]))
-            .r#type("S3TestFileWriterError".to_string())
-            .build()
-    })?;
+impl S3Credential {
+    #[cfg(feature = "s3-signer")]
+    fn to_aws_sdk_credentials(&self) -> aws_credential_types::Credentials {
+        match self {
+            S3Credential::AccessKey {
+                aws_access_key_id,
+                aws_secret_access_key,
+            } => aws_credential_types::Credentials::new(
+                aws_access_key_id.clone(),
+                aws_secret_access_key.clone(),
+                None,
+                None,
+                "iceberg-rest-secret-storage",
+            ),
+        }
+    }
+}
 
-    let buf: &[u8; 4] = b"test";
-    writer.write(buf.to_vec().into()).await.map_err(|e| {
+/// Check that `kms_key_arn` is the ARN of a KMS key, e.g.
+/// `arn:aws:kms:us-east-1:111122223333:key/1234abcd-12ab-34cd-56ef-1234567890ab`.
+/// Keys of AWS must be in the region of the bucket.
+fn validate_kms_key_arn(kms_key_arn: &str, region: &str, flavor: S3Flavor) -> Result<()> {
+    let invalid = |message: &str| {
         ErrorModel::builder()
             .code(StatusCode::BAD_REQUEST.into())
-            .message(format!("Error validating S3 Storage Profile: {e}").to_string())
-            .stack(Some(vec![e.to_string()]))
-            .r#type("S3TestFileWriteError".to_string())
+            .message(message.to_string())
+            .r#type("InvalidKmsKeyArn".to_string())
+            .stack(Some(vec![format!("KMS key ARN: {kms_key_arn}")]))
             .build()
-    })?;
+    };
 
-    writer.close().await.map_err(|e| {
-        ErrorModel::builder()
-            .code(StatusCode::BAD_REQUEST.into())
-            .message(format!("Error validating S3 Storage Profile: {e}").to_string())
-            .stack(Some(vec![e.to_string()]))
-            .r#type("S3TestFileCloseError".to_string())
-            .build()
-    })?;
+    let parts = kms_key_arn.split(':').collect::<Vec<_>>();
+    let is_key_arn = parts.len() == 6
+        && parts[0] == "arn"
+        && parts[2] == "kms"
+        && parts[5]
+            .strip_prefix("key/")
+            .is_some_and(|id| !id.is_empty());
+    if !is_key_arn {
+        return Err(invalid("Storage Profile `kms-key-arn` must be the ARN of a KMS key.").into());
+    }
+    if flavor == S3Flavor::Aws && parts[3] != region {
+        return Err(
+            invalid("Storage Profile `kms-key-arn` must be in the region of the bucket.").into(),
+        );
+    }
+    Ok(())
+}
 
-    file_io.delete(test_location).await.map_err(|e| {
-        ErrorModel::builder()
-            .code(StatusCode::BAD_REQUEST.into())
-            .message(format!("Error validating S3 Storage Profile: {e}").to_string())
-            .stack(Some(vec![e.to_string()]))
-            .r#type("S3TestFileDeleteError".to_string())
-            .build()
-    })?;
+/// Session policy that permits reading and writing objects below `table_location`
+/// and listing them. Objects encrypted with `kms_key_arn` also require the key.
+#[cfg(feature = "s3-signer")]
+fn table_policy(bucket: &str, table_location: &str, kms_key_arn: Option<&str>) -> String {
+    let prefix = table_location
+        .strip_prefix(&format!("s3://{bucket}/"))
+        .unwrap_or_default()
+        .trim_end_matches('/');
+    let mut statements = vec![
+        serde_json::json!({
+            "Effect": "Allow",
+            "Action": ["s3:GetObject", "s3:PutObject", "s3:DeleteObject"],
+            "Resource": [format!("arn:aws:s3:::{bucket}/{prefix}/*")]
+        }),
+        serde_json::json!({
+            "Effect": "Allow",
+            "Action": ["s3:ListBucket", "s3:GetBucketLocation"],
+            "Resource": [format!("arn:aws:s3:::{bucket}")],
+            "Condition": {"StringLike": {"s3:prefix": [prefix, format!("{prefix}/*")]}}
+        }),
+    ];
+    if let Some(kms_key_arn) = kms_key_arn {
+        statements.push(serde_json::json!({
+            "Effect": "Allow",
+            "Action": ["kms:Decrypt", "kms:GenerateDataKey"],
+            "Resource": [kms_key_arn]
+        }));
+    }
+    serde_json::json!({
+        "Version": "2012-10-17",
+        "Statement": statements
+    })
+    .to_string()
+}
 
-    Ok(())
+#[cfg(feature = "s3-signer")]
+fn missing_credential() -> crate::api::IcebergErrorResponse {
+    ErrorModel::builder()
+        .code(StatusCode::BAD_REQUEST.into())
+        .message("Storage Credentials missing.".to_string())
+        .r#type("MissingStorageCredential".to_string())
+        .build()
+        .into()
 }
 
 #[cfg(test)]
@@ -528,9 +884,14 @@ mod test {
             bucket: "test_bucket".to_string(),
             key_prefix: Some("test_prefix".to_string()),
             assume_role_arn: None,
+            external_id: None,
             endpoint: None,
             region: "dummy".to_string(),
             path_style_access: Some(true),
+            sts_enabled: false,
+            flavor: S3Flavor::Aws,
+            sse_algorithm: None,
+            kms_key_arn: None,
         };
 
         let namespace_id = NamespaceIdentUuid::from(uuid::Uuid::now_v7());
@@ -551,4 +912,176 @@ mod test {
             format!("s3://test_bucket/{namespace_id}/{table_id}")
         );
     }
+
+    #[cfg(feature = "s3-signer")]
+    #[tokio::test]
+    async fn test_presign_get() {
+        let profile = S3Profile {
+            bucket: "test-bucket".to_string(),
+            key_prefix: None,
+            assume_role_arn: None,
+            external_id: None,
+            endpoint: Some("http://localhost:9000".to_string()),
+            region: "local".to_string(),
+            path_style_access: Some(true),
+            sts_enabled: false,
+            flavor: S3Flavor::Aws,
+            sse_algorithm: None,
+            kms_key_arn: None,
+        };
+        let credential = S3Credential::AccessKey {
+            aws_access_key_id: "access-key".to_string(),
+            aws_secret_access_key: "secret-key".to_string(),
+        };
+
+        let url = profile
+            .presign_get(
+                Some(&credential),
+                "s3://test-bucket/ns/table/metadata/v1.metadata.json",
+                std::time::Duration::from_secs(900),
+            )
+            .await
+            .unwrap();
+        let url = url::Url::parse(&url).unwrap();
+        assert_eq!(url.host_str(), Some("localhost"));
+        assert_eq!(
+            url.path(),
+            "/test-bucket/ns/table/metadata/v1.metadata.json"
+        );
+        let params: HashMap<_, _> = url.query_pairs().collect();
+        assert_eq!(params["X-Amz-Expires"], "900");
+        assert!(params["X-Amz-Credential"].starts_with("access-key/"));
+        assert!(params.contains_key("X-Amz-Signature"));
+
+        let mut profile = profile;
+        profile.path_style_access = None;
+        profile.endpoint = None;
+        let url = profile
+            .presign_get(
+                Some(&credential),
+                "s3://test-bucket/ns/table/metadata/v1.metadata.json",
+                std::time::Duration::from_secs(900),
+            )
+            .await
+            .unwrap();
+        assert!(url.starts_with(
+            "https://test-bucket.s3.local.amazonaws.com/ns/table/metadata/v1.metadata.json?"
+        ));
+
+        assert!(profile
+            .presign_get(
+                Some(&credential),
+                "s3://other-bucket/ns/table/metadata/v1.metadata.json",
+                std::time::Duration::from_secs(900),
+            )
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_s3_compat_profile() {
+        let mut profile = S3Profile {
+            bucket: "test-bucket".to_string(),
+            key_prefix: None,
+            assume_role_arn: None,
+            external_id: None,
+            endpoint: None,
+            region: "auto".to_string(),
+            path_style_access: None,
+            sts_enabled: false,
+            flavor: S3Flavor::S3Compat,
+            sse_algorithm: None,
+            kms_key_arn: None,
+        };
+        assert!(profile.path_style());
+        // S3-compatible stores require an endpoint
+        let err = profile.validate(None).await.unwrap_err();
+        assert_eq!(err.error.r#type, "InvalidS3Endpoint");
+
+        profile.endpoint = Some("https://account.r2.cloudflarestorage.com".to_string());
+        profile.sts_enabled = true;
+        let err = profile.validate(None).await.unwrap_err();
+        assert_eq!(err.error.r#type, "InvalidStsConfiguration");
+
+        let profile: S3Profile = serde_json::from_value(serde_json::json!({
+            "bucket": "test-bucket",
+            "region": "auto",
+            "flavor": "s3-compat",
+        }))
+        .unwrap();
+        assert_eq!(profile.flavor, S3Flavor::S3Compat);
+    }
+
+    #[cfg(feature = "s3-signer")]
+    #[test]
+    fn test_table_policy() {
+        let policy: serde_json::Value = serde_json::from_str(&table_policy(
+            "test-bucket",
+            "s3://test-bucket/prefix/namespace/table",
+            None,
+        ))
+        .unwrap();
+
+        assert_eq!(
+            policy["Statement"][0]["Resource"][0],
+            "arn:aws:s3:::test-bucket/prefix/namespace/table/*"
+        );
+        assert_eq!(
+            policy["Statement"][1]["Resource"][0],
+            "arn:aws:s3:::test-bucket"
+        );
+        assert_eq!(
+            policy["Statement"][1]["Condition"]["StringLike"]["s3:prefix"],
+            serde_json::json!(["prefix/namespace/table", "prefix/namespace/table/*"])
+        );
+        assert_eq!(policy["Statement"].as_array().unwrap().len(), 2);
+
+        let kms_key_arn = "arn:aws:kms:us-east-1:111122223333:key/1234abcd";
+        let policy: serde_json::Value = serde_json::from_str(&table_policy(
+            "test-bucket",
+            "s3://test-bucket/prefix/namespace/table",
+            Some(kms_key_arn),
+        ))
+        .unwrap();
+        assert_eq!(policy["Statement"][2]["Resource"][0], kms_key_arn);
+    }
+
+    #[test]
+    fn test_validate_kms_key_arn() {
+        let key = "arn:aws:kms:us-east-1:111122223333:key/1234abcd-12ab-34cd-56ef-1234567890ab";
+        assert!(validate_kms_key_arn(key, "us-east-1", S3Flavor::Aws).is_ok());
+        assert!(validate_kms_key_arn(key, "eu-central-1", S3Flavor::Aws).is_err());
+        assert!(validate_kms_key_arn(key, "auto", S3Flavor::S3Compat).is_ok());
+        assert!(validate_kms_key_arn(
+            "arn:aws:kms:us-east-1:111122223333:alias/my-key",
+            "us-east-1",
+            S3Flavor::Aws
+        )
+        .is_err());
+        assert!(validate_kms_key_arn("1234abcd", "us-east-1", S3Flavor::Aws).is_err());
+    }
+
+    #[test]
+    fn test_sse_table_config() {
+        let mut profile: S3Profile = serde_json::from_value(serde_json::json!({
+            "bucket": "test-bucket",
+            "region": "us-east-1",
+            "sse-algorithm": "aws:kms",
+            "kms-key-arn": "arn:aws:kms:us-east-1:111122223333:key/1234abcd",
+        }))
+        .unwrap();
+        assert_eq!(
+            profile.sse_properties(),
+            vec![
+                ("s3.sse.type".to_string(), "kms".to_string()),
+                (
+                    "s3.sse.key".to_string(),
+                    "arn:aws:kms:us-east-1:111122223333:key/1234abcd".to_string()
+                ),
+            ]
+        );
+
+        profile.sse_algorithm = None;
+        assert!(profile.sse_properties().is_empty());
+    }
 }
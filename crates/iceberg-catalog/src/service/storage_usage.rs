@@ -0,0 +1,75 @@
+//! Estimated storage usage per namespace and warehouse.
+//!
+//! The [`StorageUsageJob`] periodically sums the `total-files-size` summary of
+//! the current snapshot of each table. Files that are not referenced by the
+//! current snapshot, such as those of expired snapshots or orphaned files, are
+//! not included. Tables whose writers do not report `total-files-size` are
+//! counted separately.
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::NamespaceIdent;
+use crate::api::Result;
+use crate::WarehouseIdent;
+
+/// Storage usage of one namespace, excluding its child namespaces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamespaceStorageUsage {
+    pub warehouse_id: WarehouseIdent,
+    pub namespace: NamespaceIdent,
+    pub table_count: i64,
+    pub total_files_size_bytes: i64,
+    /// Tables whose current snapshot does not report its size.
+    pub tables_without_size: i64,
+    pub computed_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[async_trait::async_trait]
+pub trait StorageUsageStore: Debug + Send + Sync {
+    /// Recompute the storage usage of all namespaces of all warehouses.
+    /// Returns the number of namespaces updated.
+    async fn refresh_storage_usage(&self) -> Result<u64>;
+
+    /// Last computed storage usage of all namespaces of a warehouse.
+    async fn list_storage_usage(
+        &self,
+        warehouse_id: &WarehouseIdent,
+    ) -> Result<Vec<NamespaceStorageUsage>>;
+}
+
+#[derive(Debug)]
+pub struct StorageUsageJob {
+    store: Arc<dyn StorageUsageStore>,
+}
+
+impl StorageUsageJob {
+    #[must_use]
+    pub fn new(store: Arc<dyn StorageUsageStore>) -> Self {
+        Self { store }
+    }
+
+    /// Refresh the storage usage every `interval` until the task is aborted.
+    pub async fn run(self, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match self.refresh().await {
+                Ok(namespaces) => {
+                    tracing::debug!("Refreshed storage usage of {namespaces} namespaces.");
+                }
+                Err(e) => tracing::warn!("Failed to refresh storage usage: {:?}", e.error),
+            }
+        }
+    }
+
+    /// Refresh the storage usage of all namespaces once.
+    /// Returns the number of refreshed namespaces.
+    ///
+    /// # Errors
+    /// Fails if the usage cannot be computed or stored.
+    pub async fn refresh(&self) -> Result<u64> {
+        self.store.refresh_storage_usage().await
+    }
+}
@@ -0,0 +1,136 @@
+//! Periodic check of the storage of all warehouses.
+//!
+//! The [`StorageValidationJob`] validates the storage profile and credential of
+//! every active warehouse, the same way they are validated when a warehouse is
+//! created. Results are stored with the warehouse and returned by the management
+//! API, so that expired or revoked credentials are noticed before jobs of users
+//! start failing.
+use std::fmt::Debug;
+use std::time::Duration;
+
+use iceberg_ext::redaction::redact_text;
+use utoipa::ToSchema;
+
+use super::storage::StorageCredential;
+use super::{Catalog, GetWarehouseResponse, SecretStore};
+use crate::api::Result;
+
+/// Result of the last check of the storage profile and credential of a warehouse.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct StorageValidation {
+    /// Time of the check.
+    pub validated_at: chrono::DateTime<chrono::Utc>,
+    /// Why the storage can't be used. Not set if the check succeeded.
+    pub error: Option<String>,
+}
+
+pub struct StorageValidationJob<C: Catalog, S: SecretStore> {
+    catalog_state: C::State,
+    secrets_state: S::State,
+}
+
+impl<C: Catalog, S: SecretStore> Debug for StorageValidationJob<C, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StorageValidationJob")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: Catalog, S: SecretStore> StorageValidationJob<C, S> {
+    #[must_use]
+    pub fn new(catalog_state: C::State, secrets_state: S::State) -> Self {
+        Self {
+            catalog_state,
+            secrets_state,
+        }
+    }
+
+    /// Validate all warehouses immediately and then every `interval` until the task is aborted.
+    pub async fn run(self, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match self.validate_all().await {
+                Ok(0) => tracing::debug!("Storage of all warehouses is valid."),
+                Ok(broken) => tracing::warn!("Storage of {broken} warehouses is invalid."),
+                Err(e) => tracing::warn!("Storage validation failed: {:?}", e.error),
+            }
+        }
+    }
+
+    /// Validate the storage of all active warehouses once and store the results.
+    /// Returns the number of warehouses whose storage is invalid.
+    ///
+    /// # Errors
+    /// Fails if the warehouses cannot be listed.
+    pub async fn validate_all(&self) -> Result<usize> {
+        let mut broken = 0;
+        for project_id in C::list_projects(self.catalog_state.clone()).await? {
+            let warehouses =
+                C::list_warehouses(&project_id, None, None, self.catalog_state.clone()).await?;
+            for warehouse in warehouses {
+                let validation = self.validate(&warehouse).await;
+                if let Some(error) = &validation.error {
+                    broken += 1;
+                    tracing::warn!(
+                        "Storage of warehouse {} ({}) is invalid: {error}",
+                        warehouse.name,
+                        warehouse.id
+                    );
+                }
+                if let Err(e) = C::set_warehouse_storage_validation(
+                    &warehouse.id,
+                    &validation,
+                    self.catalog_state.clone(),
+                )
+                .await
+                {
+                    tracing::warn!(
+                        "Failed to store storage validation of warehouse {}: {:?}",
+                        warehouse.id,
+                        e.error
+                    );
+                }
+            }
+        }
+        Ok(broken)
+    }
+
+    async fn validate(&self, warehouse: &GetWarehouseResponse) -> StorageValidation {
+        validate_storage::<S>(warehouse, self.secrets_state.clone()).await
+    }
+}
+
+/// Validate the storage profile and current credential of a warehouse.
+pub(crate) async fn validate_storage<S: SecretStore>(
+    warehouse: &GetWarehouseResponse,
+    secrets_state: S::State,
+) -> StorageValidation {
+    let result = async {
+        let credential = if let Some(secret_id) = &warehouse.storage_secret_id {
+            Some(
+                S::get_secret_by_id::<StorageCredential>(secret_id, secrets_state)
+                    .await?
+                    .secret,
+            )
+        } else {
+            None
+        };
+        warehouse
+            .storage_profile
+            .clone()
+            .validate(credential.as_ref())
+            .await
+    }
+    .await;
+
+    StorageValidation {
+        validated_at: chrono::Utc::now(),
+        error: result.err().map(|e| {
+            // Errors of storage SDKs may echo parts of the request
+            redact_text(&format!("{}: {}", e.error.r#type, e.error.message))
+        }),
+    }
+}
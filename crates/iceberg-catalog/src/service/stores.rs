@@ -0,0 +1,52 @@
+//! Storage of data that is not part of the catalog itself, such as roles and usage statistics.
+//!
+//! Each store is a separate trait, so that embedding services can keep single stores
+//! elsewhere, e.g. roles in an existing permission system. All backends implement every
+//! store on their catalog state, see [`Stores::new`].
+use std::sync::Arc;
+
+use super::api_usage::ApiUsageStore;
+use super::client_usage::ClientUsageStore;
+use super::lineage::LineageStore;
+use super::roles::RoleStore;
+use super::storage_usage::StorageUsageStore;
+use super::token_revocation::RevocationStore;
+use super::webhook::WebhookDeadLetterStore;
+
+#[derive(Debug, Clone)]
+pub struct Stores {
+    pub roles: Arc<dyn RoleStore>,
+    pub revocations: Arc<dyn RevocationStore>,
+    pub client_usage: Arc<dyn ClientUsageStore>,
+    pub api_usage: Arc<dyn ApiUsageStore>,
+    pub storage_usage: Arc<dyn StorageUsageStore>,
+    pub lineage: Arc<dyn LineageStore>,
+    pub webhook_dead_letters: Arc<dyn WebhookDeadLetterStore>,
+}
+
+impl Stores {
+    /// All stores backed by `state`, typically the state of the catalog backend.
+    #[must_use]
+    pub fn new<T>(state: T) -> Self
+    where
+        T: RoleStore
+            + RevocationStore
+            + ClientUsageStore
+            + ApiUsageStore
+            + StorageUsageStore
+            + LineageStore
+            + WebhookDeadLetterStore
+            + 'static,
+    {
+        let state = Arc::new(state);
+        Self {
+            roles: state.clone(),
+            revocations: state.clone(),
+            client_usage: state.clone(),
+            api_usage: state.clone(),
+            storage_usage: state.clone(),
+            lineage: state.clone(),
+            webhook_dead_letters: state,
+        }
+    }
+}
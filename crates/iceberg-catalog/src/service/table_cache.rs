@@ -0,0 +1,283 @@
+use async_trait::async_trait;
+use cloudevents::{AttributesReader, Event};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use super::event_publisher::{CloudEventBackend, EventType};
+use super::{Catalog, TableIdent, TableIdentUuid};
+use crate::api::Result;
+use crate::WarehouseIdent;
+
+/// Event types after which cached entries of the affected table are dropped.
+const INVALIDATING_EVENTS: [EventType; 5] = [
+    EventType::TableCommitted,
+    EventType::TableRenamed,
+    EventType::TableDropped,
+    EventType::TableReclaimed,
+    EventType::TableSnapshotsExpired,
+];
+
+/// Bounded LRU cache mapping table identifiers to their id and current metadata location.
+///
+/// Engines tend to call `loadTable` and `tableExists` in tight loops, each of
+/// which resolves the table identifier in the catalog. Entries are dropped when
+/// a commit, rename or drop of the table is published on the event bus, see
+/// [`TableCache::invalidator`]. Changes made by other instances are only picked
+/// up after `ttl`. A capacity of zero disables the cache.
+#[derive(Debug, Clone)]
+pub struct TableCache {
+    entries: Arc<RwLock<HashMap<(WarehouseIdent, TableIdent), CacheEntry>>>,
+    capacity: usize,
+    ttl: Duration,
+}
+
+/// A cached table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedTable {
+    pub table_id: TableIdentUuid,
+    /// `None` until the table was loaded through this instance.
+    pub metadata_location: Option<String>,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    table: CachedTable,
+    inserted_at: Instant,
+    last_access: Instant,
+}
+
+impl TableCache {
+    #[must_use]
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            entries: Arc::new(RwLock::new(HashMap::new())),
+            capacity,
+            ttl,
+        }
+    }
+
+    /// A cache that never stores anything.
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self::new(0, Duration::ZERO)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.capacity > 0 && !self.ttl.is_zero()
+    }
+
+    /// Get a cached table if it is present and not expired.
+    pub async fn get(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+    ) -> Option<CachedTable> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let mut entries = self.entries.write().await;
+        let key = (warehouse_id.clone(), table.clone());
+        let entry = entries.get_mut(&key)?;
+        if entry.inserted_at.elapsed() >= self.ttl {
+            entries.remove(&key);
+            return None;
+        }
+        entry.last_access = Instant::now();
+        Some(entry.table.clone())
+    }
+
+    /// Same as [`Catalog::table_ident_to_id`] excluding staged tables, but cached.
+    ///
+    /// # Errors
+    /// Fails if the table can not be resolved by the catalog.
+    pub async fn table_id<C: Catalog>(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        catalog_state: C::State,
+    ) -> Result<Option<TableIdentUuid>> {
+        if let Some(cached) = self.get(warehouse_id, table).await {
+            return Ok(Some(cached.table_id));
+        }
+
+        let include_staged = false;
+        let table_id =
+            C::table_ident_to_id(warehouse_id, table, include_staged, catalog_state).await?;
+        if let Some(table_id) = &table_id {
+            self.insert(
+                warehouse_id,
+                table,
+                CachedTable {
+                    table_id: table_id.clone(),
+                    metadata_location: None,
+                },
+            )
+            .await;
+        }
+        Ok(table_id)
+    }
+
+    /// Insert or replace a table. Evicts the least recently used entry if the cache is full.
+    pub async fn insert(
+        &self,
+        warehouse_id: &WarehouseIdent,
+        table: &TableIdent,
+        cached: CachedTable,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let mut entries = self.entries.write().await;
+        let key = (warehouse_id.clone(), table.clone());
+        if !entries.contains_key(&key) && entries.len() >= self.capacity {
+            let lru = entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_access)
+                .map(|(k, _)| k.clone());
+            if let Some(lru) = lru {
+                entries.remove(&lru);
+            }
+        }
+        let now = Instant::now();
+        entries.insert(
+            key,
+            CacheEntry {
+                table: cached,
+                inserted_at: now,
+                last_access: now,
+            },
+        );
+    }
+
+    /// Drop all entries pointing to `table_id`.
+    pub async fn invalidate_table(&self, table_id: &TableIdentUuid) {
+        if !self.is_enabled() {
+            return;
+        }
+        self.entries
+            .write()
+            .await
+            .retain(|_, e| &e.table.table_id != table_id);
+    }
+
+    /// Event backend that invalidates this cache.
+    /// Must be added to the sinks of the `CloudEventsPublisherBackgroundTask`
+    /// that receives the events of the catalog.
+    #[must_use]
+    pub fn invalidator(&self) -> TableCacheInvalidator {
+        TableCacheInvalidator {
+            cache: self.clone(),
+        }
+    }
+}
+
+/// [`CloudEventBackend`] that drops entries of a [`TableCache`]
+/// once a table was committed to, renamed or dropped.
+#[derive(Debug, Clone)]
+pub struct TableCacheInvalidator {
+    cache: TableCache,
+}
+
+#[async_trait]
+impl CloudEventBackend for TableCacheInvalidator {
+    async fn publish(&self, event: Event) -> anyhow::Result<()> {
+        if !INVALIDATING_EVENTS
+            .iter()
+            .any(|typ| typ.as_str() == event.ty())
+        {
+            return Ok(());
+        }
+        let Some(table_id) = event.extension("table-id") else {
+            return Ok(());
+        };
+        let table_id = uuid::Uuid::parse_str(&table_id.to_string())?;
+        self.cache.invalidate_table(&table_id.into()).await;
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "table-cache-invalidator"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cloudevents::{EventBuilder, EventBuilderV10};
+
+    fn cached(metadata_location: &str) -> CachedTable {
+        CachedTable {
+            table_id: uuid::Uuid::now_v7().into(),
+            metadata_location: Some(metadata_location.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evicts_least_recently_used() {
+        let cache = TableCache::new(2, Duration::from_secs(60));
+        let warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        let tables = ["a", "b", "c"].map(|name| TableIdent::from_strs(["ns", name]).unwrap());
+
+        cache
+            .insert(&warehouse_id, &tables[0], cached("s3://a"))
+            .await;
+        cache
+            .insert(&warehouse_id, &tables[1], cached("s3://b"))
+            .await;
+        // Touch "a", so that "b" is the least recently used entry.
+        assert!(cache.get(&warehouse_id, &tables[0]).await.is_some());
+        cache
+            .insert(&warehouse_id, &tables[2], cached("s3://c"))
+            .await;
+
+        assert!(cache.get(&warehouse_id, &tables[0]).await.is_some());
+        assert!(cache.get(&warehouse_id, &tables[1]).await.is_none());
+        assert!(cache.get(&warehouse_id, &tables[2]).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_invalidator_drops_committed_table() {
+        let cache = TableCache::new(10, Duration::from_secs(60));
+        let warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        let table = TableIdent::from_strs(["ns", "tbl"]).unwrap();
+        let entry = cached("s3://tbl");
+        cache.insert(&warehouse_id, &table, entry.clone()).await;
+
+        let event = |ty: EventType| {
+            EventBuilderV10::new()
+                .id("1")
+                .source("test")
+                .ty(ty.as_str())
+                .extension("table-id", entry.table_id.to_string())
+                .build()
+                .unwrap()
+        };
+        let invalidator = cache.invalidator();
+        invalidator
+            .publish(event(EventType::TableCreated))
+            .await
+            .unwrap();
+        assert_eq!(cache.get(&warehouse_id, &table).await, Some(entry.clone()));
+
+        invalidator
+            .publish(event(EventType::TableCommitted))
+            .await
+            .unwrap();
+        assert_eq!(cache.get(&warehouse_id, &table).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_disabled() {
+        let cache = TableCache::disabled();
+        let warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        let table = TableIdent::from_strs(["ns", "tbl"]).unwrap();
+        cache
+            .insert(&warehouse_id, &table, cached("s3://tbl"))
+            .await;
+        assert!(cache.get(&warehouse_id, &table).await.is_none());
+    }
+}
@@ -0,0 +1,189 @@
+//! Garbage collection of dropped tables.
+//!
+//! Dropped tables are kept by the catalog for a retention period. Afterwards the
+//! [`TableGarbageCollector`] reclaims them: their remaining catalog data is removed
+//! and, if enabled, their metadata files are deleted from storage.
+//! Data files are not tracked by the catalog and are left untouched.
+use http::StatusCode;
+use std::fmt::Debug;
+use std::time::Duration;
+use uuid::Uuid;
+
+use super::event_publisher::{CloudEventsPublisher, EventMetadata, EventSubject, EventType};
+use super::storage::StorageCredential;
+use super::{Catalog, DroppedTable, SecretStore, Transaction};
+use crate::api::{ErrorModel, Result};
+
+/// Maximum number of tables reclaimed per batch.
+const BATCH_SIZE: i64 = 100;
+
+pub struct TableGarbageCollector<C: Catalog, S: SecretStore> {
+    catalog_state: C::State,
+    secrets_state: S::State,
+    publisher: CloudEventsPublisher,
+    retention: Duration,
+    purge_storage: bool,
+}
+
+impl<C: Catalog, S: SecretStore> Debug for TableGarbageCollector<C, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TableGarbageCollector")
+            .field("retention", &self.retention)
+            .field("purge_storage", &self.purge_storage)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: Catalog, S: SecretStore> TableGarbageCollector<C, S> {
+    #[must_use]
+    pub fn new(
+        catalog_state: C::State,
+        secrets_state: S::State,
+        publisher: CloudEventsPublisher,
+        retention: Duration,
+        purge_storage: bool,
+    ) -> Self {
+        Self {
+            catalog_state,
+            secrets_state,
+            publisher,
+            retention,
+            purge_storage,
+        }
+    }
+
+    /// Reclaim expired tables every `interval` until the task is aborted.
+    pub async fn run(self, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match self.collect().await {
+                Ok(0) => {}
+                Ok(reclaimed) => tracing::info!("Reclaimed {reclaimed} dropped tables."),
+                Err(e) => {
+                    tracing::warn!("Garbage collection of dropped tables failed: {:?}", e.error);
+                }
+            }
+        }
+    }
+
+    /// Reclaim all tables whose retention period has expired.
+    /// Returns the number of reclaimed tables.
+    /// Tables that cannot be reclaimed are logged and retried on the next run.
+    ///
+    /// # Errors
+    /// Fails if the expired tables cannot be listed.
+    pub async fn collect(&self) -> Result<usize> {
+        let Some(dropped_before) = chrono::Duration::from_std(self.retention)
+            .ok()
+            .and_then(|retention| chrono::Utc::now().checked_sub_signed(retention))
+        else {
+            return Ok(0);
+        };
+
+        let mut reclaimed = 0;
+        loop {
+            let tables = C::list_expired_dropped_tables(
+                dropped_before,
+                BATCH_SIZE,
+                self.catalog_state.clone(),
+            )
+            .await?;
+            let batch_size = tables.len();
+            let mut reclaimed_in_batch = 0;
+
+            for table in tables {
+                let table_id = table.table_id;
+                match self.reclaim(table).await {
+                    Ok(()) => reclaimed_in_batch += 1,
+                    Err(e) => {
+                        tracing::warn!("Failed to reclaim dropped table {table_id}: {:?}", e.error);
+                    }
+                }
+            }
+            reclaimed += reclaimed_in_batch;
+
+            // Stop if there are no more tables or none of them could be reclaimed,
+            // as the next batch would contain the same tables.
+            if batch_size < usize::try_from(BATCH_SIZE).unwrap_or(usize::MAX)
+                || reclaimed_in_batch == 0
+            {
+                return Ok(reclaimed);
+            }
+        }
+    }
+
+    async fn reclaim(&self, table: DroppedTable) -> Result<()> {
+        if self.purge_storage {
+            self.purge_metadata_files(&table).await?;
+        }
+
+        let mut transaction = C::Transaction::begin_write(self.catalog_state.clone()).await?;
+        C::reclaim_dropped_table(
+            &table.warehouse_id,
+            &table.table_id,
+            self.purge_storage,
+            transaction.transaction(),
+        )
+        .await?;
+        transaction.commit().await?;
+
+        let _ = self
+            .publisher
+            .publish(
+                Uuid::now_v7(),
+                EventType::TableReclaimed,
+                serde_json::json!({
+                    "dropped-at": table.dropped_at,
+                    "storage-purged": self.purge_storage,
+                    "metadata-locations": table.metadata_locations,
+                }),
+                EventMetadata {
+                    warehouse_id: *table.warehouse_id.as_uuid(),
+                    subject: EventSubject::Table {
+                        table_id: *table.table_id.as_uuid(),
+                        namespace: table.table.namespace.encode_in_url(),
+                        name: table.table.name,
+                    },
+                    prefix: table.warehouse_id.to_string(),
+                    num_events: 1,
+                    sequence_number: 0,
+                    trace_id: Uuid::now_v7(),
+                    tenant_id: None,
+                },
+            )
+            .await;
+
+        Ok(())
+    }
+
+    async fn purge_metadata_files(&self, table: &DroppedTable) -> Result<()> {
+        let storage_secret = if let Some(secret_id) = &table.storage_secret_ident {
+            Some(
+                S::get_secret_by_id::<StorageCredential>(secret_id, self.secrets_state.clone())
+                    .await?
+                    .secret,
+            )
+        } else {
+            None
+        };
+        let file_io = table
+            .storage_profile
+            .file_io(storage_secret.as_ref())
+            .await?;
+
+        for location in &table.metadata_locations {
+            file_io.delete(location).await.map_err(|e| {
+                ErrorModel::builder()
+                    .code(StatusCode::FAILED_DEPENDENCY.into())
+                    .message(format!("Failed to delete metadata file '{location}'."))
+                    .r#type("MetadataFileDeletionFailed".to_string())
+                    .stack(Some(vec![e.to_string()]))
+                    .build()
+            })?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+//! Hard limits on the metadata of a table.
+//!
+//! Tables with thousands of snapshots or schemas produce metadata of many
+//! megabytes, which is stored in a single row and returned by every `loadTable`.
+//! Commits that grow a table beyond a limit are rejected. Commits that do not
+//! grow the table, e.g. those expiring snapshots, are always accepted, so that
+//! tables exceeding a limit can be repaired.
+use http::StatusCode;
+use iceberg_ext::spec::TableMetadata;
+
+use crate::api::{ErrorModel, IcebergErrorResponse, Result};
+use crate::CONFIG;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TableLimits {
+    pub max_metadata_bytes: Option<usize>,
+    pub max_snapshots: Option<usize>,
+    pub max_schemas: Option<usize>,
+}
+
+impl TableLimits {
+    #[must_use]
+    pub fn from_config() -> Self {
+        Self {
+            max_metadata_bytes: CONFIG.max_table_metadata_bytes,
+            max_snapshots: CONFIG.max_table_snapshots,
+            max_schemas: CONFIG.max_table_schemas,
+        }
+    }
+
+    /// Check that a commit from `previous` to `current` does not grow the table
+    /// beyond a limit.
+    ///
+    /// # Errors
+    /// Fails with a `400` describing how to reduce the size of the table.
+    pub fn check(&self, previous: &TableMetadata, current: &TableMetadata) -> Result<()> {
+        if let Some(max_snapshots) = self.max_snapshots {
+            let snapshots = current.snapshots.len();
+            if snapshots > max_snapshots && snapshots > previous.snapshots.len() {
+                return Err(limit_exceeded(
+                    "TooManySnapshots",
+                    format!(
+                        "Table would have {snapshots} snapshots, which exceeds the limit of {max_snapshots}. Expire snapshots, e.g. with a retention policy or the `expire_snapshots` procedure, before committing again."
+                    ),
+                ));
+            }
+        }
+
+        if let Some(max_schemas) = self.max_schemas {
+            let schemas = current.schemas.len();
+            if schemas > max_schemas && schemas > previous.schemas.len() {
+                return Err(limit_exceeded(
+                    "TooManySchemas",
+                    format!(
+                        "Table would have {schemas} schemas, which exceeds the limit of {max_schemas}. Recreate the table or remove unused schemas before changing the schema again."
+                    ),
+                ));
+            }
+        }
+
+        if let Some(max_bytes) = self.max_metadata_bytes {
+            let bytes = serialized_size(current)?;
+            if bytes > max_bytes && bytes > serialized_size(previous)? {
+                return Err(limit_exceeded(
+                    "TableMetadataTooLarge",
+                    format!(
+                        "Table metadata would be {bytes} bytes, which exceeds the limit of {max_bytes} bytes. Expire snapshots and remove unused properties before committing again."
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn serialized_size(metadata: &TableMetadata) -> Result<usize> {
+    serde_json::to_vec(metadata).map(|v| v.len()).map_err(|e| {
+        ErrorModel::builder()
+            .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+            .message("Error serializing table metadata".to_string())
+            .r#type("TableMetadataSerializationError".to_string())
+            .stack(Some(vec![e.to_string()]))
+            .build()
+            .into()
+    })
+}
+
+fn limit_exceeded(r#type: &str, message: String) -> IcebergErrorResponse {
+    ErrorModel::builder()
+        .code(StatusCode::BAD_REQUEST.into())
+        .message(message)
+        .r#type(r#type.to_string())
+        .build()
+        .into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use iceberg::spec::{NestedField, PrimitiveType, Schema, Type};
+    use iceberg_ext::spec::TableMetadataAggregate;
+
+    fn schema(schema_id: i32, fields: &[&str]) -> Schema {
+        Schema::builder()
+            .with_schema_id(schema_id)
+            .with_fields(
+                fields
+                    .iter()
+                    .zip(1..)
+                    .map(|(name, id)| {
+                        NestedField::required(id, *name, Type::Primitive(PrimitiveType::Int)).into()
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_table_limits() {
+        let previous =
+            TableMetadataAggregate::new("s3://bucket/table".to_string(), schema(0, &["id"]))
+                .build()
+                .unwrap();
+        let mut builder = TableMetadataAggregate::new_from_metadata(previous.clone());
+        builder
+            .add_schema(schema(1, &["id", "name"]), None)
+            .unwrap();
+        let current = builder.build().unwrap();
+
+        let limits = TableLimits {
+            max_schemas: Some(1),
+            ..Default::default()
+        };
+        let err = limits.check(&previous, &current).unwrap_err();
+        assert_eq!(err.error.r#type, "TooManySchemas");
+        // Commits not adding schemas are accepted
+        limits.check(&current, &current).unwrap();
+
+        let limits = TableLimits {
+            max_metadata_bytes: Some(10),
+            ..Default::default()
+        };
+        let err = limits.check(&previous, &current).unwrap_err();
+        assert_eq!(err.error.r#type, "TableMetadataTooLarge");
+        limits.check(&current, &previous).unwrap();
+
+        TableLimits::default().check(&previous, &current).unwrap();
+    }
+}
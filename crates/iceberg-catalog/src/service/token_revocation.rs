@@ -0,0 +1,311 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use super::token_verification::Claims;
+use crate::api::Result;
+
+/// Tokens affected by a revocation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevocationTarget {
+    /// A single token, identified by its `jti` claim.
+    TokenId(String),
+    /// All tokens of a subject (`sub` claim) issued at or before the revocation.
+    Subject(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenRevocation {
+    pub target: RevocationTarget,
+    pub revoked_at: DateTime<Utc>,
+    /// After this point in time the revocation no longer needs to be checked,
+    /// typically the expiry of the revoked token.
+    pub expires_at: Option<DateTime<Utc>>,
+    pub reason: Option<String>,
+}
+
+/// Claims of a token that revocations apply to.
+pub trait RevocableClaims {
+    /// Subject the token was issued to.
+    fn subject(&self) -> &str;
+    /// Ids of the token and of the token it was derived from, if any.
+    fn token_ids(&self) -> Vec<&str>;
+    /// Issue time as unix timestamp.
+    fn issued_at(&self) -> usize;
+}
+
+impl RevocableClaims for Claims {
+    fn subject(&self) -> &str {
+        &self.sub
+    }
+
+    fn token_ids(&self) -> Vec<&str> {
+        self.jti.as_deref().into_iter().collect()
+    }
+
+    fn issued_at(&self) -> usize {
+        self.iat
+    }
+}
+
+/// Persisted revocations, consulted by the [`RevocationList`].
+#[async_trait]
+pub trait RevocationStore: Debug + Send + Sync {
+    /// Persist a token revocation.
+    async fn revoke_token(&self, revocation: &TokenRevocation) -> Result<()>;
+
+    /// Return all revocations that have not expired yet.
+    async fn list_active_revocations(&self) -> Result<Vec<TokenRevocation>>;
+}
+
+/// Cached view on a [`RevocationStore`].
+///
+/// The cache is reloaded from the store once it is older than the configured ttl.
+/// Revocations issued by this instance are added to the cache immediately,
+/// revocations issued by other instances become visible after the next reload.
+#[derive(Debug, Clone)]
+pub struct RevocationList {
+    store: Arc<dyn RevocationStore>,
+    cache: Arc<RwLock<RevocationCache>>,
+    ttl: Duration,
+}
+
+impl RevocationList {
+    #[must_use]
+    pub fn new(store: Arc<dyn RevocationStore>, ttl: Duration) -> Self {
+        Self {
+            store,
+            cache: Arc::new(RwLock::new(RevocationCache::default())),
+            ttl,
+        }
+    }
+
+    /// Check if the token with the given claims has been revoked.
+    ///
+    /// # Errors
+    /// Fails if the revocations have never been loaded successfully.
+    /// If a reload fails later on, the stale cache is used until the next attempt.
+    pub async fn is_revoked(&self, claims: &(impl RevocableClaims + Sync)) -> Result<bool> {
+        {
+            let cache = self.cache.read().await;
+            if cache.is_fresh(self.ttl) {
+                return Ok(cache.is_revoked(claims));
+            }
+        }
+
+        let mut cache = self.cache.write().await;
+        if !cache.is_fresh(self.ttl) {
+            match self.store.list_active_revocations().await {
+                Ok(revocations) => {
+                    *cache = RevocationCache::from_revocations(&revocations);
+                }
+                Err(e) if cache.refreshed_at.is_some() => {
+                    tracing::warn!("Failed to reload token revocations: {:?}", e.error);
+                    cache.refreshed_at = Some(Instant::now());
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(cache.is_revoked(claims))
+    }
+
+    /// Persist `revocation` in the store and add it to the local cache.
+    ///
+    /// # Errors
+    /// Fails if the revocation cannot be persisted.
+    pub async fn revoke(&self, revocation: &TokenRevocation) -> Result<()> {
+        self.store.revoke_token(revocation).await?;
+        self.insert(revocation).await;
+        Ok(())
+    }
+
+    /// Add a revocation to the local cache.
+    /// The revocation must be persisted in the store separately.
+    pub async fn insert(&self, revocation: &TokenRevocation) {
+        self.cache.write().await.insert(revocation);
+    }
+}
+
+#[derive(Debug, Default)]
+struct RevocationCache {
+    token_ids: HashSet<String>,
+    subjects: HashMap<String, DateTime<Utc>>,
+    refreshed_at: Option<Instant>,
+}
+
+impl RevocationCache {
+    fn from_revocations(revocations: &[TokenRevocation]) -> Self {
+        let mut cache = Self {
+            refreshed_at: Some(Instant::now()),
+            ..Self::default()
+        };
+        for revocation in revocations {
+            cache.insert(revocation);
+        }
+        cache
+    }
+
+    fn is_fresh(&self, ttl: Duration) -> bool {
+        self.refreshed_at.is_some_and(|t| t.elapsed() < ttl)
+    }
+
+    fn insert(&mut self, revocation: &TokenRevocation) {
+        match &revocation.target {
+            RevocationTarget::TokenId(token_id) => {
+                self.token_ids.insert(token_id.clone());
+            }
+            RevocationTarget::Subject(subject) => {
+                let revoked_at = self
+                    .subjects
+                    .entry(subject.clone())
+                    .or_insert(revocation.revoked_at);
+                *revoked_at = (*revoked_at).max(revocation.revoked_at);
+            }
+        }
+    }
+
+    fn is_revoked(&self, claims: &impl RevocableClaims) -> bool {
+        if claims
+            .token_ids()
+            .into_iter()
+            .any(|jti| self.token_ids.contains(jti))
+        {
+            return true;
+        }
+
+        self.subjects
+            .get(claims.subject())
+            .is_some_and(|revoked_at| {
+                i64::try_from(claims.issued_at()).is_ok_and(|iat| iat <= revoked_at.timestamp())
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::service::signer_token::{SignerAccess, SignerTokenClaims};
+    use crate::service::token_verification::Aud;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Default)]
+    struct InMemoryStore(Mutex<Vec<TokenRevocation>>);
+
+    #[async_trait]
+    impl RevocationStore for InMemoryStore {
+        async fn revoke_token(&self, revocation: &TokenRevocation) -> Result<()> {
+            self.0.lock().unwrap().push(revocation.clone());
+            Ok(())
+        }
+
+        async fn list_active_revocations(&self) -> Result<Vec<TokenRevocation>> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+    }
+
+    fn claims(sub: &str, jti: Option<&str>, iat: DateTime<Utc>) -> Claims {
+        Claims {
+            sub: sub.to_string(),
+            iss: "https://example.com".to_string(),
+            aud: Aud::String("aud".to_string()),
+            exp: usize::try_from(iat.timestamp()).unwrap() + 3600,
+            iat: usize::try_from(iat.timestamp()).unwrap(),
+            jti: jti.map(str::to_string),
+            other: serde_json::json!({}),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_revocation_list() {
+        let now = Utc::now();
+        let store = Arc::new(InMemoryStore::default());
+        store.0.lock().unwrap().push(TokenRevocation {
+            target: RevocationTarget::TokenId("token-1".to_string()),
+            revoked_at: now,
+            expires_at: None,
+            reason: None,
+        });
+        let list = RevocationList::new(store.clone(), Duration::from_secs(3600));
+
+        assert!(list
+            .is_revoked(&claims("alice", Some("token-1"), now))
+            .await
+            .unwrap());
+        assert!(!list
+            .is_revoked(&claims("alice", Some("token-2"), now))
+            .await
+            .unwrap());
+
+        // Not reloaded before the ttl expires
+        store.0.lock().unwrap().push(TokenRevocation {
+            target: RevocationTarget::Subject("bob".to_string()),
+            revoked_at: now,
+            expires_at: None,
+            reason: None,
+        });
+        let old_token = claims("bob", None, now - chrono::Duration::minutes(5));
+        let new_token = claims("bob", None, now + chrono::Duration::minutes(5));
+        assert!(!list.is_revoked(&old_token).await.unwrap());
+
+        // Local revocations are visible immediately
+        let revocation = store.0.lock().unwrap()[1].clone();
+        list.insert(&revocation).await;
+        assert!(list.is_revoked(&old_token).await.unwrap());
+        assert!(!list.is_revoked(&new_token).await.unwrap());
+
+        // Revocations are persisted in the store
+        let revocation = TokenRevocation {
+            target: RevocationTarget::TokenId("token-2".to_string()),
+            revoked_at: now,
+            expires_at: None,
+            reason: None,
+        };
+        list.revoke(&revocation).await.unwrap();
+        assert_eq!(store.0.lock().unwrap().last(), Some(&revocation));
+        assert!(list
+            .is_revoked(&claims("alice", Some("token-2"), now))
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_signer_token_revoked_with_parent() {
+        let now = Utc::now();
+        let store = Arc::new(InMemoryStore::default());
+        let list = RevocationList::new(store.clone(), Duration::from_secs(3600));
+        let signer_token = |sub: &str| SignerTokenClaims {
+            iss: "iceberg-catalog".to_string(),
+            sub: sub.to_string(),
+            jti: "signer-token".to_string(),
+            parent_jti: Some("token-1".to_string()),
+            warehouse_id: uuid::Uuid::now_v7(),
+            table_id: uuid::Uuid::now_v7(),
+            access: SignerAccess::Read,
+            iat: usize::try_from(now.timestamp()).unwrap(),
+            exp: usize::try_from(now.timestamp()).unwrap() + 3600,
+        };
+        assert!(!list.is_revoked(&signer_token("alice")).await.unwrap());
+
+        list.insert(&TokenRevocation {
+            target: RevocationTarget::TokenId("token-1".to_string()),
+            revoked_at: now,
+            expires_at: None,
+            reason: None,
+        })
+        .await;
+        list.insert(&TokenRevocation {
+            target: RevocationTarget::Subject("bob".to_string()),
+            revoked_at: now,
+            expires_at: None,
+            reason: None,
+        })
+        .await;
+        assert!(list.is_revoked(&signer_token("alice")).await.unwrap());
+        assert!(list.is_revoked(&signer_token("bob")).await.unwrap());
+    }
+}
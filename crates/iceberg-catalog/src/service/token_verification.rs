@@ -1,4 +1,5 @@
 use anyhow::Context;
+use axum::body::Body;
 use axum::extract::{Request, State};
 use axum::middleware::Next;
 use axum::response::{IntoResponse, Response};
@@ -13,6 +14,11 @@ use jwks_client_rs::source::WebSource;
 use jwks_client_rs::{JsonWebKey, JwksClient};
 
 use crate::request_metadata::RequestMetadata;
+use crate::service::catalog_token::{is_catalog_token, verify_catalog_token, TokenScope};
+use crate::service::hmac_auth::ServiceKeyClaims;
+use crate::service::signer_token::{self, is_signer_token, SignerTokenClaims};
+use crate::service::sigv4_verification::{SigV4Claims, SigV4Verifier};
+use crate::service::token_revocation::{RevocableClaims, RevocationList};
 use axum::Extension;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
@@ -23,8 +29,17 @@ use url::Url;
 #[derive(Debug, Clone)]
 pub enum AuthDetails {
     JWT(Claims),
+    /// Short-lived token scoped to remote signing for a single table.
+    SignerToken(SignerTokenClaims),
+    /// Request signed with a configured `SigV4` access key.
+    SigV4(SigV4Claims),
+    /// Request signed with a service key from the secret store.
+    ServiceKey(ServiceKeyClaims),
 }
 
+/// Maximum size of the body of `SigV4` signed requests, which is buffered to verify its hash.
+pub(crate) const MAX_SIGNED_BODY_BYTES: usize = 16 * 1024 * 1024;
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Claims {
     pub sub: String,
@@ -32,10 +47,78 @@ pub struct Claims {
     pub aud: Aud,
     pub exp: usize,
     pub iat: usize,
+    #[serde(default)]
+    pub jti: Option<String>,
     #[serde(flatten)]
     pub other: serde_json::Value,
 }
 
+impl Claims {
+    /// Value of the claim at `path`. Nested claims are separated by dots,
+    /// e.g. `realm_access.roles`.
+    #[must_use]
+    pub fn get(&self, path: &str) -> Option<&serde_json::Value> {
+        path.split('.')
+            .try_fold(&self.other, |value, key| value.get(key))
+    }
+
+    /// Strings of the claim at `path`, which may be an array or a single string.
+    #[must_use]
+    pub fn strings(&self, path: &str) -> Vec<&str> {
+        match self.get(path) {
+            Some(serde_json::Value::Array(values)) => values
+                .iter()
+                .filter_map(serde_json::Value::as_str)
+                .collect(),
+            Some(serde_json::Value::String(value)) => vec![value.as_str()],
+            _ => vec![],
+        }
+    }
+
+    /// Scopes granted to the token, taken from the space separated `scope`
+    /// claim or the `scp` claim used by some providers.
+    #[must_use]
+    pub fn scopes(&self) -> Vec<&str> {
+        match self.other.get("scope").and_then(serde_json::Value::as_str) {
+            Some(scope) => scope.split_whitespace().collect(),
+            None => self
+                .strings("scp")
+                .into_iter()
+                .flat_map(str::split_whitespace)
+                .collect(),
+        }
+    }
+}
+
+/// Catalog permission granted to a token via its scopes, see `openid_scope_permissions`.
+///
+/// Permissions restrict what a token may be used for, on top of the access the
+/// principal has. Each permission implies the lower ones.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Permission {
+    /// List and load namespaces, tables and warehouses.
+    Read,
+    /// Additionally create, update and drop namespaces and tables.
+    Write,
+    /// Additionally change settings and grant access.
+    Manage,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(untagged)]
 pub enum Aud {
@@ -47,19 +130,77 @@ pub(crate) async fn auth_middleware_fn(
     State(verifier): State<Verifier>,
     authorization: Option<TypedHeader<Authorization<Bearer>>>,
     Extension(mut metadata): Extension<RequestMetadata>,
-    request: Request,
+    mut request: Request,
     next: Next,
 ) -> Response {
-    if let Some(authorization) = authorization {
-        match verifier.decode::<Claims>(authorization.token()).await {
-            Ok(val) => {
-                metadata.auth_details = Some(AuthDetails::JWT(val));
+    // Already authenticated with a service key
+    if metadata.auth_details.is_some() {
+        return next.run(request).await;
+    }
+
+    if let Some(sigv4) = verifier.sigv4_for(&request) {
+        match authenticate_sigv4(sigv4, request).await {
+            Ok((mut request, claims)) => {
+                metadata.auth_details = Some(AuthDetails::SigV4(claims));
+                request.extensions_mut().insert(metadata);
+                return next.run(request).await;
             }
             Err(err) => {
-                tracing::debug!("Failed to verify token: {:?}", err);
+                tracing::debug!("Failed to verify SigV4 signature: {:?}", err);
                 return IcebergErrorResponse::from(err).into_response();
             }
         }
+    }
+
+    if let Some(authorization) = authorization {
+        if is_signer_token(authorization.token()) {
+            // Signer tokens are only valid for the S3 signer endpoints
+            if !request.uri().path().ends_with("/aws/s3/sign") {
+                return IcebergErrorResponse::from(
+                    ErrorModel::builder()
+                        .message("Signer tokens can only be used for remote signing")
+                        .code(StatusCode::UNAUTHORIZED.into())
+                        .r#type("UnauthorizedError")
+                        .build(),
+                )
+                .into_response();
+            }
+            match verifier.verify_signer_token(authorization.token()).await {
+                Ok(claims) => {
+                    metadata.auth_details = Some(AuthDetails::SignerToken(claims));
+                }
+                Err(err) => {
+                    tracing::debug!("Failed to verify signer token: {:?}", err.error);
+                    return err.into_response();
+                }
+            }
+        } else {
+            match verifier.verify(authorization.token()).await {
+                Ok(val) => {
+                    // Downscoped tokens are checked against their scope by the catalog API
+                    if !TokenScope::from_claims(&val).is_unrestricted()
+                        && !request.uri().path().starts_with("/catalog/")
+                    {
+                        return IcebergErrorResponse::from(
+                            ErrorModel::builder()
+                                .message("Downscoped tokens can only be used for the catalog API")
+                                .code(StatusCode::UNAUTHORIZED.into())
+                                .r#type("UnauthorizedError")
+                                .build(),
+                        )
+                        .into_response();
+                    }
+                    metadata.auth_details = Some(AuthDetails::JWT(val));
+                    if let Err(err) = metadata.validate_project() {
+                        return err.into_response();
+                    }
+                }
+                Err(err) => {
+                    tracing::debug!("Failed to verify token: {:?}", err.error);
+                    return err.into_response();
+                }
+            }
+        }
     } else {
         return IcebergErrorResponse::from(
             ErrorModel::builder()
@@ -71,11 +212,51 @@ pub(crate) async fn auth_middleware_fn(
         .into_response();
     }
 
+    request.extensions_mut().insert(metadata);
     next.run(request).await
 }
 
+/// Verify the signature of a `SigV4` signed request.
+/// Returns the request with its buffered body.
+async fn authenticate_sigv4(
+    verifier: &SigV4Verifier,
+    request: Request,
+) -> Result<(Request, SigV4Claims), ErrorModel> {
+    let (parts, body) = request.into_parts();
+    let body = axum::body::to_bytes(body, MAX_SIGNED_BODY_BYTES)
+        .await
+        .map_err(|e| {
+            ErrorModel::builder()
+                .message("Failed to read body of signed request.")
+                .code(StatusCode::PAYLOAD_TOO_LARGE.into())
+                .r#type("PayloadTooLarge")
+                .stack(Some(vec![e.to_string()]))
+                .build()
+        })?;
+    let claims = verifier
+        .verify(
+            &parts.method,
+            &parts.uri,
+            &parts.headers,
+            &body,
+            chrono::Utc::now(),
+        )
+        .await?;
+
+    Ok((Request::from_parts(parts, Body::from(body)), claims))
+}
+
 #[derive(Clone)]
 pub struct Verifier {
+    provider: Option<OidcProvider>,
+    audience: Option<String>,
+    revocations: Option<RevocationList>,
+    sigv4: Option<SigV4Verifier>,
+}
+
+/// Keys and issuer of the identity provider.
+#[derive(Clone)]
+struct OidcProvider {
     client: JwksClient<WebSource>,
     issuer: String,
 }
@@ -94,7 +275,9 @@ impl Verifier {
         if !url.path().ends_with('/') {
             url.set_path(&format!("{}/", url.path()));
         }
-        let config = reqwest::get(url.join(Self::WELL_KNOWN_CONFIG)?)
+        let config = super::http_client::http_client()
+            .get(url.join(Self::WELL_KNOWN_CONFIG)?)
+            .send()
             .await
             .context("Failed to fetch openid configuration")?
             .json::<WellKnownConfig>()
@@ -103,11 +286,109 @@ impl Verifier {
         let source = WebSource::builder().build(config.jwks_uri)?;
         let client = JwksClient::builder().build(source);
         Ok(Self {
-            client,
-            issuer: config.issuer,
+            provider: Some(OidcProvider {
+                client,
+                issuer: config.issuer,
+            }),
+            audience: None,
+            revocations: None,
+            sigv4: None,
+        })
+    }
+
+    /// Create a verifier without an identity provider, which only accepts
+    /// catalog tokens issued by `/catalog/v1/oauth/tokens`.
+    #[must_use]
+    pub fn catalog_tokens_only() -> Self {
+        Self {
+            provider: None,
+            audience: None,
+            revocations: None,
+            sigv4: None,
+        }
+    }
+
+    /// Only accept tokens issued for `audience`.
+    #[must_use]
+    pub fn with_audience(mut self, audience: String) -> Self {
+        self.audience = Some(audience);
+        self
+    }
+
+    /// Reject tokens contained in the given revocation list.
+    #[must_use]
+    pub fn with_revocations(mut self, revocations: RevocationList) -> Self {
+        self.revocations = Some(revocations);
+        self
+    }
+
+    /// Additionally accept requests signed with `SigV4`.
+    #[must_use]
+    pub fn with_sigv4(mut self, sigv4: SigV4Verifier) -> Self {
+        self.sigv4 = Some(sigv4);
+        self
+    }
+
+    /// The `SigV4` verifier, if it is configured and the request is signed with `SigV4`.
+    fn sigv4_for(&self, request: &Request) -> Option<&SigV4Verifier> {
+        self.sigv4.as_ref().filter(|_| {
+            request
+                .headers()
+                .get(http::header::AUTHORIZATION)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(SigV4Verifier::is_sigv4)
         })
     }
 
+    /// Validate a bearer token, which is either a catalog token or a token of the
+    /// identity provider, and check that it is not revoked.
+    ///
+    /// # Errors
+    /// Fails with 401 if the token is invalid, expired or revoked.
+    pub async fn verify(&self, token: &str) -> Result<Claims, IcebergErrorResponse> {
+        let claims = if is_catalog_token(token) {
+            verify_catalog_token(token)?
+        } else {
+            self.decode::<Claims>(token).await?
+        };
+        self.check_not_revoked(&claims).await?;
+        Ok(claims)
+    }
+
+    /// Verify a signer token minted by this catalog and check that it has not been revoked.
+    ///
+    /// # Errors
+    /// Fails if the token is invalid, expired or revoked.
+    pub async fn verify_signer_token(
+        &self,
+        token: &str,
+    ) -> Result<SignerTokenClaims, IcebergErrorResponse> {
+        let claims = signer_token::verify_signer_token(token)?;
+        self.check_not_revoked(&claims).await?;
+        Ok(claims)
+    }
+
+    async fn check_not_revoked(
+        &self,
+        claims: &(impl RevocableClaims + Sync),
+    ) -> Result<(), IcebergErrorResponse> {
+        let Some(revocations) = &self.revocations else {
+            return Ok(());
+        };
+
+        if revocations.is_revoked(claims).await? {
+            tracing::debug!("Rejecting revoked token of subject {}", claims.subject());
+            return Err(ErrorModel::builder()
+                .message("Token has been revoked.")
+                .code(StatusCode::UNAUTHORIZED.into())
+                .r#type("UnauthorizedError")
+                .build()
+                .into());
+        }
+
+        Ok(())
+    }
+
     // this function is mostly lifted out of jwks_client_rs which is incompatible with azure jwks.
     async fn decode<O: DeserializeOwned>(&self, token: &str) -> Result<O, ErrorModel> {
         let header: Header = jsonwebtoken::decode_header(token).map_err(|e| {
@@ -119,8 +400,16 @@ impl Verifier {
                 .build()
         })?;
 
+        let Some(provider) = &self.provider else {
+            return Err(ErrorModel::builder()
+                .message("No identity provider is configured, only catalog tokens are accepted.")
+                .code(StatusCode::UNAUTHORIZED.into())
+                .r#type("UnauthorizedError")
+                .build());
+        };
+
         if let Some(kid) = header.kid.as_ref() {
-            let key: JsonWebKey = self
+            let key: JsonWebKey = provider
                 .client
                 .get_opt(kid)
                 .await
@@ -133,7 +422,7 @@ impl Verifier {
                         .build()
                 })?;
 
-            let validation = self.setup_validation(&header, &key)?;
+            let validation = self.setup_validation(&provider.issuer, &header, &key)?;
             let decoding_key = Self::setup_decoding_key(key)?;
 
             return Ok(jsonwebtoken::decode(token, &decoding_key, &validation)
@@ -176,6 +465,7 @@ impl Verifier {
 
     fn setup_validation(
         &self,
+        issuer: &str,
         header: &Header,
         key: &JsonWebKey,
     ) -> Result<Validation, ErrorModel> {
@@ -192,10 +482,13 @@ impl Verifier {
             Validation::new(header.alg)
         };
 
-        // TODO: aud validation in multi-tenant setups
-        validation.validate_aud = false;
+        if let Some(audience) = &self.audience {
+            validation.set_audience(&[audience]);
+        } else {
+            validation.validate_aud = false;
+        }
 
-        validation.set_issuer(&[&self.issuer]);
+        validation.set_issuer(&[issuer]);
 
         Ok(validation)
     }
@@ -226,7 +519,14 @@ pub struct WellKnownConfig {
 
 #[cfg(test)]
 mod test {
-    use crate::service::token_verification::Claims;
+    use crate::request_metadata::RequestMetadata;
+    use crate::service::token_verification::{auth_middleware_fn, AuthDetails, Claims, Verifier};
+    use axum::routing::get;
+    use axum::{Extension, Json};
+    use http_body_util::BodyExt;
+    use openssl::pkey::Private;
+    use openssl::rsa::Rsa;
+    use tower::ServiceExt;
 
     #[test]
     fn test_aud_with_array() {
@@ -240,6 +540,26 @@ mod test {
         .unwrap();
     }
 
+    #[test]
+    fn test_nested_claims_and_scopes() {
+        let claims: Claims = serde_json::from_value(serde_json::json!({
+            "sub": "1234567890",
+            "iat": 22,
+            "aud": "aud1",
+            "iss": "https://example.com",
+            "exp": 9022,
+            "realm_access": {"roles": ["analysts", "admins"]},
+            "scope": "openid catalog:read"
+        }))
+        .unwrap();
+        assert_eq!(
+            claims.strings("realm_access.roles"),
+            vec!["analysts", "admins"]
+        );
+        assert!(claims.strings("groups").is_empty());
+        assert_eq!(claims.scopes(), vec!["openid", "catalog:read"]);
+    }
+
     #[test]
     fn test_aud_with_string() {
         let _: Claims = serde_json::from_value(serde_json::json!({
@@ -251,4 +571,95 @@ mod test {
         }))
         .unwrap();
     }
+
+    fn base64_url(bytes: &[u8]) -> String {
+        openssl::base64::encode_block(bytes)
+            .trim_end_matches('=')
+            .replace('+', "-")
+            .replace('/', "_")
+    }
+
+    /// Serve the openid configuration and jwks of `key` on a random local port.
+    async fn serve_provider(key: &Rsa<Private>) -> url::Url {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = url::Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let config = serde_json::json!({
+            "issuer": url.to_string(),
+            "jwks_uri": url.join("jwks").unwrap().to_string(),
+        });
+        let jwks = serde_json::json!({
+            "keys": [{
+                "kty": "RSA",
+                "kid": "test-key",
+                "alg": "RS256",
+                "use": "sig",
+                "n": base64_url(&key.n().to_vec()),
+                "e": base64_url(&key.e().to_vec()),
+            }]
+        });
+        let app = axum::Router::new()
+            .route(
+                "/.well-known/openid-configuration",
+                get(move || async move { Json(config) }),
+            )
+            .route("/jwks", get(move || async move { Json(jwks) }));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_auth_details_are_passed_to_handlers() {
+        let key = Rsa::generate(2048).unwrap();
+        let url = serve_provider(&key).await;
+        let verifier = Verifier::new(url.clone()).await.unwrap();
+
+        let now = chrono::Utc::now().timestamp();
+        let mut header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256);
+        header.kid = Some("test-key".to_string());
+        let token = jsonwebtoken::encode(
+            &header,
+            &serde_json::json!({
+                "sub": "test-user",
+                "iss": url.to_string(),
+                "aud": "test",
+                "iat": now,
+                "exp": now + 300,
+            }),
+            &jsonwebtoken::EncodingKey::from_rsa_pem(&key.private_key_to_pem().unwrap()).unwrap(),
+        )
+        .unwrap();
+
+        let app = axum::Router::new()
+            .route(
+                "/",
+                get(
+                    |Extension(metadata): Extension<RequestMetadata>| async move {
+                        if let Some(AuthDetails::JWT(claims)) = metadata.auth_details {
+                            claims.sub
+                        } else {
+                            String::new()
+                        }
+                    },
+                ),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                verifier,
+                auth_middleware_fn,
+            ))
+            .layer(Extension(RequestMetadata::new_random()));
+
+        let response = app
+            .oneshot(
+                http::Request::builder()
+                    .uri("/")
+                    .header("authorization", format!("Bearer {token}"))
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(&body[..], b"test-user");
+    }
 }
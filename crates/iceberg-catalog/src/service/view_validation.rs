@@ -0,0 +1,176 @@
+//! Validation of the representations of view versions.
+//!
+//! Every view version must have at least one representation, and each representation
+//! must use one of the dialects in `view_dialects`, at most once per version. If
+//! `validate_view_sql` is enabled, the SQL of each representation must also be
+//! parseable in its dialect, so that definitions broken by a client are rejected
+//! before engines fail to read the view.
+use std::collections::HashSet;
+
+use http::StatusCode;
+use iceberg::spec::{ViewRepresentation, ViewVersion};
+
+use crate::api::{ErrorModel, Result};
+use crate::CONFIG;
+
+/// Validate the representations of a view version against the configured dialects.
+///
+/// # Errors
+/// Fails with a `400` if the version has no representations, a representation uses an
+/// unknown or duplicate dialect, or its SQL is blank or can't be parsed.
+pub fn validate_view_version(view_version: &ViewVersion) -> Result<()> {
+    validate_representations(
+        view_version,
+        &CONFIG.view_dialects,
+        CONFIG.validate_view_sql,
+    )
+}
+
+fn validate_representations(
+    view_version: &ViewVersion,
+    dialects: &[String],
+    validate_sql: bool,
+) -> Result<()> {
+    let representations = view_version.representations();
+    if representations.is_empty() {
+        return Err(invalid(
+            "A view version requires at least one representation".to_string(),
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    for representation in representations.iter() {
+        let ViewRepresentation::Sql(representation) = representation;
+        let dialect = representation.dialect.to_lowercase();
+        if !dialects
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(&dialect))
+        {
+            return Err(invalid(format!(
+                "Unknown SQL dialect '{}'. Supported dialects: {}",
+                representation.dialect,
+                dialects.join(", ")
+            )));
+        }
+        if !seen.insert(dialect.clone()) {
+            return Err(invalid(format!(
+                "Dialect '{}' is used by more than one representation",
+                representation.dialect
+            )));
+        }
+        if representation.sql.trim().is_empty() {
+            return Err(invalid(format!(
+                "SQL of the '{}' representation is empty",
+                representation.dialect
+            )));
+        }
+        if validate_sql {
+            check_sql(&dialect, &representation.sql).map_err(|e| {
+                invalid(format!(
+                    "SQL of the '{}' representation can't be parsed: {e}",
+                    representation.dialect
+                ))
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Parse `sql` with the closest dialect of `sqlparser`. Dialects without a
+/// counterpart are parsed with the generic dialect.
+#[cfg(feature = "view-sql-validation")]
+fn check_sql(dialect: &str, sql: &str) -> std::result::Result<(), String> {
+    use sqlparser::dialect::{dialect_from_str, GenericDialect};
+    use sqlparser::parser::Parser;
+
+    let parser_dialect = match dialect {
+        "spark" => "databricks",
+        "trino" | "presto" | "flink" | "dremio" => "generic",
+        "postgres" => "postgresql",
+        other => other,
+    };
+    let parser_dialect = dialect_from_str(parser_dialect).unwrap_or(Box::new(GenericDialect {}));
+    Parser::parse_sql(parser_dialect.as_ref(), sql)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Without the `view-sql-validation` feature the config rejects `validate_view_sql`,
+/// so this is never reached.
+#[cfg(not(feature = "view-sql-validation"))]
+fn check_sql(_dialect: &str, _sql: &str) -> std::result::Result<(), String> {
+    Ok(())
+}
+
+fn invalid(message: String) -> crate::api::IcebergErrorResponse {
+    ErrorModel::builder()
+        .code(StatusCode::BAD_REQUEST.into())
+        .message(message)
+        .r#type("InvalidViewRepresentation".to_string())
+        .build()
+        .into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn view_version(representations: serde_json::Value) -> ViewVersion {
+        serde_json::from_value(serde_json::json!({
+            "version-id": 1,
+            "timestamp-ms": 1_700_000_000_000_i64,
+            "schema-id": 0,
+            "summary": {"engine-name": "spark"},
+            "representations": representations,
+            "default-namespace": []
+        }))
+        .unwrap()
+    }
+
+    fn sql(dialect: &str, sql: &str) -> serde_json::Value {
+        serde_json::json!({"type": "sql", "sql": sql, "dialect": dialect})
+    }
+
+    #[test]
+    fn test_validate_representations() {
+        let dialects = vec!["spark".to_string(), "trino".to_string()];
+        let valid = view_version(serde_json::json!([
+            sql("spark", "SELECT 1"),
+            sql("Trino", "SELECT 1")
+        ]));
+        assert!(validate_representations(&valid, &dialects, false).is_ok());
+
+        for (representations, message) in [
+            (serde_json::json!([]), "at least one"),
+            (serde_json::json!([sql("oracle", "SELECT 1")]), "Unknown"),
+            (
+                serde_json::json!([sql("spark", "SELECT 1"), sql("SPARK", "SELECT 2")]),
+                "more than one",
+            ),
+            (serde_json::json!([sql("spark", " ")]), "empty"),
+        ] {
+            let err = validate_representations(&view_version(representations), &dialects, false)
+                .unwrap_err();
+            assert_eq!(err.error.code, StatusCode::BAD_REQUEST);
+            assert!(err.error.message.contains(message), "{}", err.error.message);
+        }
+    }
+
+    #[cfg(feature = "view-sql-validation")]
+    #[test]
+    fn test_validate_sql() {
+        let dialects = vec!["spark".to_string(), "trino".to_string()];
+        let valid = view_version(serde_json::json!([
+            sql("spark", "SELECT id, count(*) FROM db.orders GROUP BY id"),
+            sql("trino", "SELECT id, count(*) FROM db.orders GROUP BY id")
+        ]));
+        assert!(validate_representations(&valid, &dialects, true).is_ok());
+
+        let broken = view_version(serde_json::json!([sql(
+            "spark",
+            "SELECT * FROM db.orders WHERE"
+        )]));
+        let err = validate_representations(&broken, &dialects, true).unwrap_err();
+        assert!(err.error.message.contains("can't be parsed"));
+    }
+}
@@ -0,0 +1,435 @@
+//! Commit and authorization policies implemented as WASM modules, for deployments that
+//! can't rebuild the server.
+//!
+//! A commit policy is run as [`CommitHook`] for the warehouses it is configured for, an
+//! authorization policy as decision point of the `OpaAuthZHandler`, see [`WasmAuthorizer`].
+//! Modules get no imports, so they can't access the network, files or clocks. Every call
+//! runs in a new instance limited to `wasm_policy_max_memory_bytes` of memory and
+//! `wasm_policy_timeout_ms` of execution. Commit policies that fail, trap or time out
+//! reject the commit, authorization policies deny the request.
+//!
+//! Modules must export:
+//! * `memory`: the linear memory.
+//! * `alloc(len: i32) -> i32`: a buffer of `len` bytes for the input.
+//! * `before_commit(ptr: i32, len: i32) -> i64` for commit policies, `authorize(ptr: i32,
+//!   len: i32) -> i64` for authorization policies: evaluates the JSON input in the buffer
+//!   and returns the location of its JSON output as `ptr << 32 | len`.
+//!
+//! The input of `before_commit` has the fields `warehouse-id`, `table-id`, `namespace`,
+//! `name`, `previous-metadata`, `requirements` and `updates`. The output is one of
+//! `{"decision": "accept"}`, `{"decision": "annotate", "properties": {..}}` or
+//! `{"decision": "reject", "message": ".."}`.
+//!
+//! The input of `authorize` is the `input` an Open Policy Agent receives, e.g.
+//! `{"action": "create-table", "principal": "...", "warehouse-id": "...", ...}`, the output
+//! what its policy returns: `true`, `false` or an object with an `allow` field.
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use http::StatusCode;
+use iceberg_ext::catalog::rest::ErrorModel;
+use wasmtime::{Config, Engine, Instance, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use super::commit_hook::{CommitContext, CommitHook, CommitHookOutcome};
+use crate::CONFIG;
+
+/// Interval in which the epoch of the engine advances. Timeouts are multiples of it.
+const EPOCH_TICK: Duration = Duration::from_millis(1);
+/// Key of the module applied to warehouses without a module of their own.
+const ALL_WAREHOUSES: &str = "*";
+
+/// Engine shared by all modules of the process, so a single thread advances its epoch.
+static ENGINE: OnceLock<Engine> = OnceLock::new();
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct PolicyInput<'a> {
+    warehouse_id: String,
+    table_id: String,
+    namespace: Vec<String>,
+    name: &'a str,
+    previous_metadata: Option<&'a iceberg::spec::TableMetadata>,
+    requirements: &'a [iceberg::TableRequirement],
+    updates: &'a [iceberg::TableUpdate],
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "decision", rename_all = "kebab-case")]
+enum PolicyOutput {
+    Accept,
+    Annotate { properties: HashMap<String, String> },
+    Reject { message: String },
+}
+
+struct StoreState {
+    limits: StoreLimits,
+}
+
+/// Limits modules are run with.
+#[derive(Clone, Copy, Debug)]
+struct Sandbox {
+    timeout_ticks: u64,
+    max_memory_bytes: usize,
+}
+
+impl Sandbox {
+    fn new(timeout: Duration, max_memory_bytes: usize) -> Self {
+        Self {
+            timeout_ticks: u64::try_from(timeout.as_millis() / EPOCH_TICK.as_millis())
+                .unwrap_or(u64::MAX)
+                .max(1),
+            max_memory_bytes,
+        }
+    }
+
+    fn from_config() -> Self {
+        Self::new(
+            Duration::from_millis(CONFIG.wasm_policy_timeout_ms),
+            CONFIG.wasm_policy_max_memory_bytes,
+        )
+    }
+
+    /// Call `export` of a new instance of `module` with `input`, returning its output.
+    fn call(&self, module: &Module, export: &str, input: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let mut store = Store::new(
+            module.engine(),
+            StoreState {
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(self.max_memory_bytes)
+                    .instances(1)
+                    .build(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        store.set_epoch_deadline(self.timeout_ticks);
+
+        let instance = Instance::new(&mut store, module, &[])?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| anyhow::anyhow!("Module does not export `memory`"))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+        let evaluate = instance.get_typed_func::<(i32, i32), i64>(&mut store, export)?;
+
+        let len = i32::try_from(input.len())?;
+        let ptr = alloc.call(&mut store, len)?;
+        memory.write(&mut store, usize::try_from(ptr)?, input)?;
+        let result = evaluate.call(&mut store, (ptr, len))?;
+
+        let output_ptr = usize::try_from(result >> 32)?;
+        let output_len = usize::try_from(result & 0xFFFF_FFFF)?;
+        let output = memory
+            .data(&store)
+            .get(output_ptr..output_ptr + output_len)
+            .ok_or_else(|| anyhow::anyhow!("Output of module is out of bounds"))?;
+        Ok(output.to_vec())
+    }
+}
+
+/// Commit hook running the configured WASM module of the warehouse, if any.
+#[derive(Clone)]
+pub struct WasmPolicyHook {
+    /// Modules by warehouse id, or [`ALL_WAREHOUSES`].
+    modules: Arc<HashMap<String, Module>>,
+    sandbox: Sandbox,
+}
+
+impl std::fmt::Debug for WasmPolicyHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmPolicyHook")
+            .field("warehouses", &self.modules.keys().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+impl WasmPolicyHook {
+    /// Compile the modules in `wasm_policy_modules`. `None` if no modules are configured.
+    ///
+    /// # Errors
+    /// Fails if a module can't be read or compiled.
+    pub fn from_config() -> anyhow::Result<Option<Self>> {
+        if CONFIG.wasm_policy_modules.is_empty() {
+            return Ok(None);
+        }
+        let engine = engine()?;
+        let modules = CONFIG
+            .wasm_policy_modules
+            .iter()
+            .map(|(warehouse, path)| Ok((warehouse.clone(), compile(&engine, path)?)))
+            .collect::<anyhow::Result<HashMap<_, _>>>()?;
+        Ok(Some(Self {
+            modules: Arc::new(modules),
+            sandbox: Sandbox::from_config(),
+        }))
+    }
+
+    fn evaluate(&self, module: &Module, input: &[u8]) -> anyhow::Result<PolicyOutput> {
+        let output = self.sandbox.call(module, "before_commit", input)?;
+        Ok(serde_json::from_slice(&output)?)
+    }
+}
+
+/// Authorization policy deciding access checks in place of an Open Policy Agent.
+#[derive(Clone)]
+pub struct WasmAuthorizer {
+    module: Module,
+    sandbox: Sandbox,
+}
+
+impl std::fmt::Debug for WasmAuthorizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmAuthorizer").finish_non_exhaustive()
+    }
+}
+
+impl WasmAuthorizer {
+    /// Compile the module in `wasm_authz_module`. `None` if no module is configured.
+    ///
+    /// # Errors
+    /// Fails if the module can't be read or compiled.
+    pub fn from_config() -> anyhow::Result<Option<Self>> {
+        let Some(path) = &CONFIG.wasm_authz_module else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            module: compile(&engine()?, path)?,
+            sandbox: Sandbox::from_config(),
+        }))
+    }
+
+    /// Compile a module from its binary or text format.
+    ///
+    /// # Errors
+    /// Fails if the module can't be compiled.
+    pub fn new(wasm: impl AsRef<[u8]>) -> anyhow::Result<Self> {
+        Ok(Self {
+            module: Module::new(&engine()?, wasm)?,
+            sandbox: Sandbox::from_config(),
+        })
+    }
+
+    /// Evaluate `authorize` for the JSON `input`, returning the JSON output of the module.
+    ///
+    /// # Errors
+    /// Fails if the module fails, traps or times out.
+    pub async fn authorize(&self, input: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+        let authorizer = self.clone();
+        tokio::task::spawn_blocking(move || {
+            authorizer
+                .sandbox
+                .call(&authorizer.module, "authorize", &input)
+        })
+        .await?
+    }
+}
+
+#[async_trait]
+impl CommitHook for WasmPolicyHook {
+    fn name(&self) -> &'static str {
+        "WasmPolicyHook"
+    }
+
+    async fn before_commit(
+        &self,
+        context: &CommitContext<'_>,
+    ) -> Result<CommitHookOutcome, ErrorModel> {
+        let warehouse_id = context.warehouse_id.to_string();
+        let Some(module) = self
+            .modules
+            .get(&warehouse_id)
+            .or_else(|| self.modules.get(ALL_WAREHOUSES))
+            .cloned()
+        else {
+            return Ok(CommitHookOutcome::Accept {});
+        };
+
+        let input = serde_json::to_vec(&PolicyInput {
+            warehouse_id,
+            table_id: context.table_id.to_string(),
+            namespace: context.table.namespace.clone().inner(),
+            name: &context.table.name,
+            previous_metadata: context.previous_metadata,
+            requirements: context.requirements,
+            updates: context.updates,
+        })
+        .map_err(|e| policy_failed(&e))?;
+
+        let hook = self.clone();
+        let output = tokio::task::spawn_blocking(move || hook.evaluate(&module, &input))
+            .await
+            .map_err(|e| policy_failed(&e))?
+            .map_err(|e| policy_failed(&e))?;
+
+        Ok(match output {
+            PolicyOutput::Accept => CommitHookOutcome::Accept {},
+            PolicyOutput::Annotate { properties } => CommitHookOutcome::Annotate { properties },
+            PolicyOutput::Reject { message } => CommitHookOutcome::Reject {
+                error_model: ErrorModel::builder()
+                    .code(StatusCode::CONFLICT.into())
+                    .message(message)
+                    .r#type("CommitRejectedByPolicy".to_string())
+                    .build(),
+            },
+        })
+    }
+}
+
+/// The engine of the process. Its epoch is advanced by a single thread started
+/// with the engine.
+fn engine() -> anyhow::Result<Engine> {
+    if let Some(engine) = ENGINE.get() {
+        return Ok(engine.clone());
+    }
+    let mut config = Config::new();
+    config.epoch_interruption(true);
+    let engine = Engine::new(&config)?;
+    // Only the engine that is stored gets a ticker, even if threads race to create one.
+    Ok(ENGINE
+        .get_or_init(|| {
+            spawn_epoch_ticker(engine.clone());
+            engine
+        })
+        .clone())
+}
+
+fn compile(engine: &Engine, path: &Path) -> anyhow::Result<Module> {
+    Module::from_file(engine, path).map_err(|e| {
+        e.context(format!(
+            "Failed to compile WASM policy '{}'",
+            path.display()
+        ))
+    })
+}
+
+/// Advance the epoch of `engine` every [`EPOCH_TICK`], which interrupts modules
+/// running past their deadline.
+fn spawn_epoch_ticker(engine: Engine) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(EPOCH_TICK);
+        engine.increment_epoch();
+    });
+}
+
+fn policy_failed(e: &dyn std::fmt::Display) -> ErrorModel {
+    tracing::warn!("WASM policy failed: {e}");
+    ErrorModel::builder()
+        .code(StatusCode::INTERNAL_SERVER_ERROR.into())
+        .message("Commit policy could not be evaluated".to_string())
+        .r#type("PolicyEvaluationFailed".to_string())
+        .stack(Some(vec![e.to_string()]))
+        .build()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::WarehouseIdent;
+    use iceberg::TableIdent;
+
+    fn sandbox() -> Sandbox {
+        Sandbox::new(Duration::from_millis(50), 1024 * 1024)
+    }
+
+    fn hook(wat: &str, warehouse: &str) -> WasmPolicyHook {
+        let module = Module::new(&engine().unwrap(), wat).unwrap();
+        WasmPolicyHook {
+            modules: Arc::new(HashMap::from_iter([(warehouse.to_string(), module)])),
+            sandbox: sandbox(),
+        }
+    }
+
+    /// Returns the 36 bytes at offset 0 as output.
+    const REJECT: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (data (i32.const 0) "{\"decision\":\"reject\",\"message\":\"no\"}")
+          (func (export "alloc") (param i32) (result i32) i32.const 1024)
+          (func (export "before_commit") (param i32 i32) (result i64) i64.const 36))
+    "#;
+
+    const LOOP: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (func (export "alloc") (param i32) (result i32) i32.const 1024)
+          (func (export "before_commit") (param i32 i32) (result i64)
+            (loop (br 0))
+            i64.const 0))
+    "#;
+
+    /// Returns the 15 bytes at offset 0 as output.
+    const DENY: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (data (i32.const 0) "{\"allow\":false}")
+          (func (export "alloc") (param i32) (result i32) i32.const 1024)
+          (func (export "authorize") (param i32 i32) (result i64) i64.const 15))
+    "#;
+
+    async fn run(
+        hook: &WasmPolicyHook,
+        warehouse_id: &WarehouseIdent,
+    ) -> Result<CommitHookOutcome, ErrorModel> {
+        let table = TableIdent::from_strs(["ns", "tbl"]).unwrap();
+        hook.before_commit(&CommitContext {
+            warehouse_id,
+            table_id: uuid::Uuid::now_v7().into(),
+            table: &table,
+            previous_metadata: None,
+            requirements: &[],
+            updates: &[],
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_reject() {
+        let warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        let hook = hook(REJECT, &warehouse_id.to_string());
+
+        let CommitHookOutcome::Reject { error_model } = run(&hook, &warehouse_id).await.unwrap()
+        else {
+            panic!("expected the commit to be rejected");
+        };
+        assert_eq!(error_model.message, "no");
+
+        // Other warehouses are not affected
+        let other = WarehouseIdent::from(uuid::Uuid::now_v7());
+        assert!(matches!(
+            run(&hook, &other).await.unwrap(),
+            CommitHookOutcome::Accept {}
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let warehouse_id = WarehouseIdent::from(uuid::Uuid::now_v7());
+        let hook = hook(LOOP, ALL_WAREHOUSES);
+
+        let err = run(&hook, &warehouse_id).await.unwrap_err();
+        assert_eq!(err.r#type, "PolicyEvaluationFailed");
+    }
+
+    #[tokio::test]
+    async fn test_authorize() {
+        let output = WasmAuthorizer::new(DENY)
+            .unwrap()
+            .authorize(br#"{"action":"create-table"}"#.to_vec())
+            .await
+            .unwrap();
+        assert_eq!(output, br#"{"allow":false}"#);
+
+        // Modules without `authorize` can't be used for authorization
+        assert!(WasmAuthorizer::new(REJECT)
+            .unwrap()
+            .authorize(b"{}".to_vec())
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_engine_is_shared() {
+        assert!(Engine::same(&engine().unwrap(), &engine().unwrap()));
+    }
+}
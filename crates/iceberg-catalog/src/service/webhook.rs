@@ -0,0 +1,276 @@
+//! Delivery of catalog events to HTTP webhooks.
+//!
+//! Each event is posted to every configured URL in the structured `CloudEvents` JSON format
+//! with content type `application/cloudevents+json`. If `webhook_secret` is set, the body is
+//! signed and the signature sent in the [`SIGNATURE_HEADER`] as
+//! `sha256={hex(HMAC-SHA256(secret, body))}`, so receivers can verify the sender.
+//!
+//! Deliveries failing with a network error, a timeout, `408`, `429` or a `5xx` status are
+//! retried with exponential backoff. Deliveries that fail with any other status or still fail
+//! after `webhook_max_attempts` are stored as [`WebhookDeadLetter`] in the
+//! [`WebhookDeadLetterStore`].
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use cloudevents::{AttributesReader, Event};
+use http::StatusCode;
+use url::Url;
+
+use super::event_publisher::CloudEventBackend;
+use super::sigv4_verification::{hex, hmac_sha256};
+use crate::CONFIG;
+
+pub const SIGNATURE_HEADER: &str = "x-iceberg-webhook-signature";
+const CONTENT_TYPE: &str = "application/cloudevents+json";
+
+/// Delivery of an event to a webhook that failed permanently.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookDeadLetter {
+    pub event_id: String,
+    pub url: Url,
+    /// The event in the structured `CloudEvents` JSON format, as it was posted.
+    pub event: serde_json::Value,
+    pub attempts: u32,
+    /// Error of the last attempt.
+    pub error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait WebhookDeadLetterStore: Debug + Send + Sync {
+    /// Store a webhook delivery that failed permanently.
+    async fn record_webhook_dead_letter(
+        &self,
+        dead_letter: &WebhookDeadLetter,
+    ) -> crate::api::Result<()>;
+}
+
+/// [`CloudEventBackend`] posting events to webhooks.
+///
+/// `publish` returns immediately, deliveries and their retries run in the background
+/// so that slow receivers don't hold up other sinks.
+pub struct WebhookBackend {
+    urls: Vec<Url>,
+    delivery: Arc<Delivery>,
+}
+
+struct Delivery {
+    client: reqwest::Client,
+    secret: Option<String>,
+    max_attempts: u32,
+    initial_backoff: Duration,
+    dead_letters: Arc<dyn WebhookDeadLetterStore>,
+}
+
+impl WebhookBackend {
+    /// Backend for the `webhook_urls` of the config, `None` if no URLs are configured.
+    ///
+    /// # Errors
+    /// Fails if a URL is invalid or the HTTP client can't be built.
+    pub fn from_config(
+        dead_letters: Arc<dyn WebhookDeadLetterStore>,
+    ) -> anyhow::Result<Option<Self>> {
+        if CONFIG.webhook_urls.is_empty() {
+            return Ok(None);
+        }
+        let urls = CONFIG
+            .webhook_urls
+            .iter()
+            .map(|url| Url::parse(url))
+            .collect::<Result<Vec<_>, _>>()?;
+        let client = super::http_client::client_builder()?
+            .timeout(Duration::from_millis(CONFIG.webhook_timeout_ms))
+            .build()?;
+        Ok(Some(Self::new(
+            client,
+            urls,
+            CONFIG.webhook_secret.clone(),
+            CONFIG.webhook_max_attempts,
+            Duration::from_millis(CONFIG.webhook_initial_backoff_ms),
+            dead_letters,
+        )))
+    }
+
+    #[must_use]
+    pub fn new(
+        client: reqwest::Client,
+        urls: Vec<Url>,
+        secret: Option<String>,
+        max_attempts: u32,
+        initial_backoff: Duration,
+        dead_letters: Arc<dyn WebhookDeadLetterStore>,
+    ) -> Self {
+        Self {
+            urls,
+            delivery: Arc::new(Delivery {
+                client,
+                secret,
+                max_attempts: max_attempts.max(1),
+                initial_backoff,
+                dead_letters,
+            }),
+        }
+    }
+}
+
+impl Debug for WebhookBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookBackend")
+            .field("urls", &self.urls)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl CloudEventBackend for WebhookBackend {
+    async fn publish(&self, event: Event) -> anyhow::Result<()> {
+        let event_id = event.id().to_string();
+        let body = serde_json::to_vec(&event)?;
+        let signature = self
+            .delivery
+            .secret
+            .as_ref()
+            .map(|secret| sign(secret, &body))
+            .transpose()?;
+
+        for url in &self.urls {
+            let delivery = self.delivery.clone();
+            let url = url.clone();
+            let event_id = event_id.clone();
+            let body = body.clone();
+            let signature = signature.clone();
+            tokio::spawn(async move {
+                delivery.deliver(event_id, url, body, signature).await;
+            });
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "webhook-publisher"
+    }
+}
+
+impl Delivery {
+    async fn deliver(&self, event_id: String, url: Url, body: Vec<u8>, signature: Option<String>) {
+        let mut backoff = self.initial_backoff;
+        let mut attempts = 0;
+        let error = loop {
+            attempts += 1;
+            let error = match self.post(&url, &body, signature.as_deref()).await {
+                Ok(()) => return,
+                Err(error) => error,
+            };
+            if !error.retryable || attempts >= self.max_attempts {
+                break error.message;
+            }
+            tracing::debug!(
+                "Delivery of event '{event_id}' to webhook '{url}' failed, retrying in {backoff:?}: {}",
+                error.message
+            );
+            tokio::time::sleep(backoff).await;
+            backoff = backoff.saturating_mul(2);
+        };
+
+        tracing::warn!(
+            "Delivery of event '{event_id}' to webhook '{url}' failed after {attempts} attempt(s): {error}"
+        );
+        let dead_letter = WebhookDeadLetter {
+            event_id,
+            url,
+            event: serde_json::from_slice(&body).unwrap_or_default(),
+            attempts,
+            error,
+            failed_at: Utc::now(),
+        };
+        if let Err(e) = self
+            .dead_letters
+            .record_webhook_dead_letter(&dead_letter)
+            .await
+        {
+            tracing::error!(
+                "Failed to store dead letter of event '{}' for webhook '{}': {:?}",
+                dead_letter.event_id,
+                dead_letter.url,
+                e.error
+            );
+        }
+    }
+
+    async fn post(
+        &self,
+        url: &Url,
+        body: &[u8],
+        signature: Option<&str>,
+    ) -> Result<(), DeliveryError> {
+        let mut request = self
+            .client
+            .post(url.clone())
+            .header(http::header::CONTENT_TYPE, CONTENT_TYPE)
+            .body(body.to_vec());
+        if let Some(signature) = signature {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+
+        let response = request.send().await.map_err(|e| DeliveryError {
+            message: e.to_string(),
+            retryable: true,
+        })?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        Err(DeliveryError {
+            message: format!("Webhook responded with status {status}"),
+            retryable: is_retryable(status),
+        })
+    }
+}
+
+struct DeliveryError {
+    message: String,
+    retryable: bool,
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Value of the [`SIGNATURE_HEADER`] for `body`.
+///
+/// # Errors
+/// Fails if the HMAC cannot be computed.
+pub fn sign(secret: &str, body: &[u8]) -> anyhow::Result<String> {
+    Ok(format!(
+        "sha256={}",
+        hex(&hmac_sha256(secret.as_bytes(), body)?)
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?").unwrap(),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(StatusCode::BAD_GATEWAY));
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable(StatusCode::REQUEST_TIMEOUT));
+        assert!(!is_retryable(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable(StatusCode::NOT_FOUND));
+    }
+}
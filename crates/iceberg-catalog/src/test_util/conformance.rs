@@ -0,0 +1,699 @@
+//! Scenarios derived from the Iceberg REST specification that every
+//! [`Catalog`] implementation must pass when served by this crate.
+//!
+//! The suite starts the full router on a random local port with
+//! [`AllowAllAuthZHandler`], creates a fresh warehouse via the management API
+//! and runs all scenarios against it over HTTP. Creating non-staged tables
+//! writes metadata files, so the storage profile must point to a reachable
+//! bucket, e.g. the MinIO of `tests/docker-compose.yaml`.
+//!
+//! ```ignore
+//! let report = run_conformance_suite::<Catalog, Catalog, SecretsStore>(
+//!     catalog_state.clone(),
+//!     secrets_state,
+//!     Stores::new(catalog_state),
+//!     storage_profile,
+//!     Some(storage_credential),
+//! )
+//! .await?;
+//! report.assert_success();
+//! ```
+
+use std::fmt::Write as _;
+
+use http::{Method, StatusCode};
+use reqwest::Url;
+use serde_json::{json, Value};
+
+use super::{AllowAllAuthState, AllowAllAuthZHandler};
+use crate::api::management::v1::warehouse::{CreateWarehouseRequest, CreateWarehouseResponse};
+use crate::api::router::{new_full_router, serve};
+use crate::api::CatalogConfig;
+use crate::implementations::DEFAULT_PROJECT_ID;
+use crate::service::api_usage::ApiUsageRecorder;
+use crate::service::commit_hook::CommitHooks;
+use crate::service::config::ConfigProvider;
+use crate::service::contract_verification::ContractVerifiers;
+use crate::service::event_publisher::{CloudEventsPublisher, CloudEventsPublisherBackgroundTask};
+use crate::service::maintenance::MaintenanceMode;
+use crate::service::runtime_config::RuntimeConfigHandle;
+use crate::service::storage::{StorageCredential, StorageProfile};
+use crate::service::stores::Stores;
+use crate::service::table_cache::TableCache;
+use crate::service::{Catalog, SecretStore};
+
+/// Name of the scenario and the reason it failed.
+#[derive(Debug, Clone)]
+pub struct ScenarioFailure {
+    pub scenario: &'static str,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConformanceReport {
+    pub passed: Vec<&'static str>,
+    pub failed: Vec<ScenarioFailure>,
+}
+
+impl ConformanceReport {
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// # Panics
+    /// If any scenario failed. The message lists all failures.
+    pub fn assert_success(&self) {
+        if self.is_success() {
+            return;
+        }
+
+        let mut message = format!(
+            "{} of {} conformance scenarios failed:",
+            self.failed.len(),
+            self.failed.len() + self.passed.len()
+        );
+        for failure in &self.failed {
+            let _ = write!(message, "\n- {}: {}", failure.scenario, failure.message);
+        }
+        panic!("{message}");
+    }
+}
+
+type ScenarioResult = std::result::Result<(), String>;
+
+/// Serve a router backed by `C` and `S` and run all scenarios against it.
+///
+/// Every run creates a new warehouse with a random name in the default project.
+/// Scenarios are independent of each other and use their own namespaces.
+///
+/// # Errors
+/// Fails if the server can not be started or the warehouse can not be created.
+/// Failing scenarios are reported in the returned [`ConformanceReport`].
+pub async fn run_conformance_suite<CP: ConfigProvider<C>, C: Catalog, S: SecretStore>(
+    catalog_state: C::State,
+    secrets_state: S::State,
+    stores: Stores,
+    storage_profile: StorageProfile,
+    storage_credential: Option<StorageCredential>,
+) -> anyhow::Result<ConformanceReport> {
+    let (tx, rx) = tokio::sync::mpsc::channel(1000);
+    let publisher_handle = tokio::task::spawn(
+        CloudEventsPublisherBackgroundTask {
+            source: rx,
+            sinks: vec![],
+        }
+        .publish(),
+    );
+    let router = new_full_router::<CP, C, AllowAllAuthZHandler, AllowAllAuthZHandler, S>(
+        AllowAllAuthState,
+        catalog_state,
+        secrets_state,
+        stores,
+        CloudEventsPublisher::new(tx),
+        ContractVerifiers::new(vec![]),
+        CommitHooks::default(),
+        TableCache::disabled(),
+        ApiUsageRecorder::new(),
+        None,
+        RuntimeConfigHandle::new(MaintenanceMode::new(false, 0), None),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let base_url: Url = format!("http://{}/", listener.local_addr()?).parse()?;
+    let server_handle = tokio::task::spawn(serve(listener, router));
+
+    let result = async {
+        let context = TestContext::new(base_url, storage_profile, storage_credential).await?;
+        Ok(context.run().await)
+    }
+    .await;
+
+    server_handle.abort();
+    publisher_handle.abort();
+    result
+}
+
+#[derive(Debug)]
+struct TestContext {
+    http: reqwest::Client,
+    catalog_url: Url,
+}
+
+impl TestContext {
+    async fn new(
+        base_url: Url,
+        storage_profile: StorageProfile,
+        storage_credential: Option<StorageCredential>,
+    ) -> anyhow::Result<Self> {
+        let http = reqwest::Client::new();
+        let warehouse_name = format!("conformance-{}", uuid::Uuid::now_v7());
+
+        let response = http
+            .post(base_url.join("management/v1/warehouse")?)
+            .json(&CreateWarehouseRequest {
+                warehouse_name: warehouse_name.clone(),
+                project_id: DEFAULT_PROJECT_ID,
+                storage_profile,
+                storage_credential,
+            })
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Failed to create warehouse: {} {}",
+                response.status(),
+                response.text().await?
+            );
+        }
+        response.json::<CreateWarehouseResponse>().await?;
+
+        let config = http
+            .get(base_url.join("catalog/v1/config")?)
+            .query(&[("warehouse", &warehouse_name)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<CatalogConfig>()
+            .await?;
+        let prefix = config
+            .overrides
+            .get("prefix")
+            .ok_or_else(|| anyhow::anyhow!("Config does not contain a prefix"))?;
+
+        let mut catalog_url = base_url.join("catalog/v1/")?;
+        catalog_url
+            .path_segments_mut()
+            .map_err(|()| anyhow::anyhow!("Invalid base URL"))?
+            .pop_if_empty()
+            .push(prefix)
+            .push("");
+
+        Ok(Self { http, catalog_url })
+    }
+
+    async fn run(&self) -> ConformanceReport {
+        let mut report = ConformanceReport::default();
+        let scenarios: [(&'static str, ScenarioResult); 8] = [
+            ("namespace-lifecycle", self.namespace_lifecycle().await),
+            ("create-table", self.create_table().await),
+            ("stage-create-table", self.stage_create_table().await),
+            ("commit-table", self.commit_table().await),
+            ("commit-conflict", self.commit_conflict().await),
+            ("rename-table", self.rename_table().await),
+            ("drop-table", self.drop_table().await),
+            ("pagination", self.pagination().await),
+        ];
+
+        for (scenario, result) in scenarios {
+            match result {
+                Ok(()) => report.passed.push(scenario),
+                Err(message) => report.failed.push(ScenarioFailure { scenario, message }),
+            }
+        }
+        report
+    }
+
+    // ------------------- Scenarios -------------------
+
+    async fn namespace_lifecycle(&self) -> ScenarioResult {
+        let namespace = unique_name("ns");
+        let ns = namespace.as_str();
+
+        self.create_namespace(ns).await?;
+        self.expect(
+            Method::POST,
+            &["namespaces"],
+            Some(json!({"namespace": [ns]})),
+            StatusCode::CONFLICT,
+        )
+        .await?;
+        let loaded = self
+            .expect(Method::GET, &["namespaces", ns], None, StatusCode::OK)
+            .await?;
+        if loaded["namespace"] != json!([ns]) {
+            return Err(format!("Loaded unexpected namespace: {loaded}"));
+        }
+        self.expect(
+            Method::HEAD,
+            &["namespaces", ns],
+            None,
+            StatusCode::NO_CONTENT,
+        )
+        .await?;
+
+        // Non-empty namespaces must not be dropped
+        self.create_table_request(ns, "t", false, StatusCode::OK)
+            .await?;
+        self.expect(
+            Method::DELETE,
+            &["namespaces", ns],
+            None,
+            StatusCode::CONFLICT,
+        )
+        .await?;
+        self.expect(
+            Method::DELETE,
+            &["namespaces", ns, "tables", "t"],
+            None,
+            StatusCode::NO_CONTENT,
+        )
+        .await?;
+
+        self.expect(
+            Method::DELETE,
+            &["namespaces", ns],
+            None,
+            StatusCode::NO_CONTENT,
+        )
+        .await?;
+        self.expect(
+            Method::GET,
+            &["namespaces", ns],
+            None,
+            StatusCode::NOT_FOUND,
+        )
+        .await?;
+        self.expect(
+            Method::HEAD,
+            &["namespaces", ns],
+            None,
+            StatusCode::NOT_FOUND,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn create_table(&self) -> ScenarioResult {
+        let namespace = unique_name("ns");
+        let ns = namespace.as_str();
+        self.create_namespace(ns).await?;
+
+        let created = self
+            .create_table_request(ns, "t", false, StatusCode::OK)
+            .await?;
+        if created["metadata-location"].as_str().is_none() {
+            return Err(format!("Created table has no metadata location: {created}"));
+        }
+        self.create_table_request(ns, "t", false, StatusCode::CONFLICT)
+            .await?;
+
+        self.expect(
+            Method::GET,
+            &["namespaces", ns, "tables", "t"],
+            None,
+            StatusCode::OK,
+        )
+        .await?;
+        self.expect(
+            Method::HEAD,
+            &["namespaces", ns, "tables", "t"],
+            None,
+            StatusCode::NO_CONTENT,
+        )
+        .await?;
+        self.expect(
+            Method::GET,
+            &["namespaces", ns, "tables", "missing"],
+            None,
+            StatusCode::NOT_FOUND,
+        )
+        .await?;
+        self.create_table_request("missing-namespace", "t", false, StatusCode::NOT_FOUND)
+            .await?;
+        Ok(())
+    }
+
+    async fn stage_create_table(&self) -> ScenarioResult {
+        let namespace = unique_name("ns");
+        let ns = namespace.as_str();
+        self.create_namespace(ns).await?;
+
+        let staged = self
+            .create_table_request(ns, "t", true, StatusCode::OK)
+            .await?;
+        if !staged["metadata-location"].is_null() {
+            return Err(format!("Staged table has a metadata location: {staged}"));
+        }
+        // Staged tables are not visible until they are committed
+        self.expect(
+            Method::GET,
+            &["namespaces", ns, "tables", "t"],
+            None,
+            StatusCode::NOT_FOUND,
+        )
+        .await?;
+        let tables = self
+            .expect(
+                Method::GET,
+                &["namespaces", ns, "tables"],
+                None,
+                StatusCode::OK,
+            )
+            .await?;
+        if !tables["identifiers"].as_array().is_some_and(Vec::is_empty) {
+            return Err(format!("Staged table is listed: {tables}"));
+        }
+
+        // Staging again and creating regularly replace the staged table
+        self.create_table_request(ns, "t", true, StatusCode::OK)
+            .await?;
+        self.create_table_request(ns, "t", false, StatusCode::OK)
+            .await?;
+        self.expect(
+            Method::GET,
+            &["namespaces", ns, "tables", "t"],
+            None,
+            StatusCode::OK,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn commit_table(&self) -> ScenarioResult {
+        let namespace = unique_name("ns");
+        let ns = namespace.as_str();
+        self.create_namespace(ns).await?;
+        let created = self
+            .create_table_request(ns, "t", false, StatusCode::OK)
+            .await?;
+        let table_uuid = created["metadata"]["table-uuid"].clone();
+
+        let committed = self
+            .expect(
+                Method::POST,
+                &["namespaces", ns, "tables", "t"],
+                Some(json!({
+                    "requirements": [{"type": "assert-table-uuid", "uuid": table_uuid}],
+                    "updates": [{"action": "set-properties", "updates": {"owner": "conformance"}}]
+                })),
+                StatusCode::OK,
+            )
+            .await?;
+        if committed["metadata-location"] == created["metadata-location"] {
+            return Err("Commit did not create a new metadata file".to_string());
+        }
+
+        let loaded = self
+            .expect(
+                Method::GET,
+                &["namespaces", ns, "tables", "t"],
+                None,
+                StatusCode::OK,
+            )
+            .await?;
+        if loaded["metadata"]["properties"]["owner"] != json!("conformance") {
+            return Err(format!("Committed property is missing: {loaded}"));
+        }
+
+        self.expect(
+            Method::POST,
+            &["namespaces", ns, "tables", "missing"],
+            Some(json!({
+                "requirements": [],
+                "updates": [{"action": "set-properties", "updates": {"owner": "conformance"}}]
+            })),
+            StatusCode::NOT_FOUND,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn commit_conflict(&self) -> ScenarioResult {
+        let namespace = unique_name("ns");
+        let ns = namespace.as_str();
+        self.create_namespace(ns).await?;
+        self.create_table_request(ns, "t", false, StatusCode::OK)
+            .await?;
+
+        self.expect(
+            Method::POST,
+            &["namespaces", ns, "tables", "t"],
+            Some(json!({
+                "requirements": [{"type": "assert-table-uuid", "uuid": uuid::Uuid::now_v7()}],
+                "updates": [{"action": "set-properties", "updates": {"owner": "conformance"}}]
+            })),
+            StatusCode::CONFLICT,
+        )
+        .await?;
+
+        let loaded = self
+            .expect(
+                Method::GET,
+                &["namespaces", ns, "tables", "t"],
+                None,
+                StatusCode::OK,
+            )
+            .await?;
+        if !loaded["metadata"]["properties"]["owner"].is_null() {
+            return Err(format!("Conflicting commit was applied: {loaded}"));
+        }
+        Ok(())
+    }
+
+    async fn rename_table(&self) -> ScenarioResult {
+        let namespace = unique_name("ns");
+        let ns = namespace.as_str();
+        self.create_namespace(ns).await?;
+        self.create_table_request(ns, "source", false, StatusCode::OK)
+            .await?;
+        self.create_table_request(ns, "existing", false, StatusCode::OK)
+            .await?;
+
+        let rename = |source: &str, destination: &str| {
+            json!({
+                "source": {"namespace": [ns], "name": source},
+                "destination": {"namespace": [ns], "name": destination}
+            })
+        };
+
+        self.expect(
+            Method::POST,
+            &["tables", "rename"],
+            Some(rename("source", "destination")),
+            StatusCode::NO_CONTENT,
+        )
+        .await?;
+        self.expect(
+            Method::GET,
+            &["namespaces", ns, "tables", "source"],
+            None,
+            StatusCode::NOT_FOUND,
+        )
+        .await?;
+        self.expect(
+            Method::GET,
+            &["namespaces", ns, "tables", "destination"],
+            None,
+            StatusCode::OK,
+        )
+        .await?;
+
+        self.expect(
+            Method::POST,
+            &["tables", "rename"],
+            Some(rename("destination", "existing")),
+            StatusCode::CONFLICT,
+        )
+        .await?;
+        self.expect(
+            Method::POST,
+            &["tables", "rename"],
+            Some(rename("missing", "other")),
+            StatusCode::NOT_FOUND,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn drop_table(&self) -> ScenarioResult {
+        let namespace = unique_name("ns");
+        let ns = namespace.as_str();
+        self.create_namespace(ns).await?;
+        self.create_table_request(ns, "t", false, StatusCode::OK)
+            .await?;
+
+        self.expect(
+            Method::DELETE,
+            &["namespaces", ns, "tables", "t"],
+            None,
+            StatusCode::NO_CONTENT,
+        )
+        .await?;
+        self.expect(
+            Method::GET,
+            &["namespaces", ns, "tables", "t"],
+            None,
+            StatusCode::NOT_FOUND,
+        )
+        .await?;
+        self.expect(
+            Method::DELETE,
+            &["namespaces", ns, "tables", "t"],
+            None,
+            StatusCode::NOT_FOUND,
+        )
+        .await?;
+
+        // The name can be re-used after a drop
+        self.create_table_request(ns, "t", false, StatusCode::OK)
+            .await?;
+        Ok(())
+    }
+
+    async fn pagination(&self) -> ScenarioResult {
+        let namespace = unique_name("ns");
+        let ns = namespace.as_str();
+        self.create_namespace(ns).await?;
+        let tables = ["a", "b", "c"];
+        for table in tables {
+            self.create_table_request(ns, table, false, StatusCode::OK)
+                .await?;
+        }
+
+        // Servers either paginate or return all results without a next-page-token.
+        let mut listed = vec![];
+        let mut page_token = String::new();
+        for _ in 0..=tables.len() {
+            let url = self
+                .url(&["namespaces", ns, "tables"])
+                .query_pairs_mut()
+                .append_pair("pageToken", &page_token)
+                .append_pair("pageSize", "1")
+                .finish()
+                .clone();
+            let page = self.send(Method::GET, url, None, StatusCode::OK).await?;
+            listed.extend(
+                page["identifiers"]
+                    .as_array()
+                    .ok_or_else(|| format!("Response contains no identifiers: {page}"))?
+                    .iter()
+                    .filter_map(|i| i["name"].as_str().map(ToString::to_string)),
+            );
+            match page["next-page-token"].as_str() {
+                Some(token) => page_token = token.to_string(),
+                None => break,
+            }
+        }
+        listed.sort();
+        if listed != tables {
+            return Err(format!("Listed tables {listed:?}, expected {tables:?}"));
+        }
+
+        let url = self
+            .url(&["namespaces", ns, "tables"])
+            .query_pairs_mut()
+            .append_pair("pageSize", "not-a-number")
+            .finish()
+            .clone();
+        self.send(Method::GET, url, None, StatusCode::BAD_REQUEST)
+            .await?;
+
+        for listing in ["tables", "views"] {
+            let url = self
+                .url(&["namespaces", ns, listing])
+                .query_pairs_mut()
+                .append_pair("pageToken", "not-a-page-token")
+                .finish()
+                .clone();
+            self.send(Method::GET, url, None, StatusCode::BAD_REQUEST)
+                .await?;
+        }
+        Ok(())
+    }
+
+    // ------------------- Helpers -------------------
+
+    async fn create_namespace(&self, namespace: &str) -> ScenarioResult {
+        self.expect(
+            Method::POST,
+            &["namespaces"],
+            Some(json!({"namespace": [namespace]})),
+            StatusCode::OK,
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn create_table_request(
+        &self,
+        namespace: &str,
+        name: &str,
+        stage_create: bool,
+        expected: StatusCode,
+    ) -> std::result::Result<Value, String> {
+        self.expect(
+            Method::POST,
+            &["namespaces", namespace, "tables"],
+            Some(json!({
+                "name": name,
+                "schema": {
+                    "type": "struct",
+                    "schema-id": 0,
+                    "fields": [{"id": 1, "name": "id", "required": true, "type": "long"}]
+                },
+                "stage-create": stage_create
+            })),
+            expected,
+        )
+        .await
+    }
+
+    fn url(&self, segments: &[&str]) -> Url {
+        let mut url = self.catalog_url.clone();
+        url.path_segments_mut()
+            .expect("Catalog URL can be a base")
+            .pop_if_empty()
+            .extend(segments);
+        url
+    }
+
+    async fn expect(
+        &self,
+        method: Method,
+        segments: &[&str],
+        body: Option<Value>,
+        expected: StatusCode,
+    ) -> std::result::Result<Value, String> {
+        self.send(method, self.url(segments), body, expected).await
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<Value>,
+        expected: StatusCode,
+    ) -> std::result::Result<Value, String> {
+        let description = format!("{method} {}", url.path());
+        let mut request = self.http.request(method, url);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("{description} failed: {e}"))?;
+
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| format!("{description}: Failed to read response: {e}"))?;
+        if status != expected {
+            return Err(format!(
+                "{description} returned {status}, expected {expected}: {text}"
+            ));
+        }
+
+        if text.is_empty() {
+            Ok(Value::Null)
+        } else {
+            serde_json::from_str(&text)
+                .map_err(|e| format!("{description} returned invalid JSON: {e}"))
+        }
+    }
+}
+
+fn unique_name(prefix: &str) -> String {
+    format!("{prefix}_{}", uuid::Uuid::now_v7().simple())
+}
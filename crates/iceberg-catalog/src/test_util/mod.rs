@@ -0,0 +1,895 @@
+//! Ready-made implementations of the service traits for handler tests
+//! that should not require a database or message broker.
+//!
+//! ```ignore
+//! let (backend, publisher) = RecordingEventBackend::publisher();
+//! let context = api_context::<AllowAllAuthZHandler, NoopCatalog, MemorySecretStore>(
+//!     AllowAllAuthState,
+//!     NoopCatalogState,
+//!     MemorySecretStoreState::default(),
+//!     Stores::new(NoopCatalogState),
+//!     publisher,
+//! );
+//! ```
+
+pub mod conformance;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use cloudevents::Event;
+use http::StatusCode;
+
+use crate::api::{ApiContext, ErrorModel, ErrorType, Result};
+pub use crate::implementations::memory::{MemorySecretStore, MemorySecretStoreState};
+pub use crate::implementations::{AllowAllAuthState, AllowAllAuthZHandler};
+pub use crate::request_metadata::RequestMetadata;
+use crate::service::api_usage::{ApiUsage, ApiUsageStore, UsageWindow};
+use crate::service::auth::{AuthZHandler, UnauthorizedResponse};
+use crate::service::client_usage::{ClientInfo, ClientUsage, ClientUsageStore};
+use crate::service::commit_hook::CommitHooks;
+use crate::service::commit_queue::CommitQueue;
+use crate::service::contract_verification::ContractVerifiers;
+use crate::service::event_publisher::{
+    CloudEventBackend, CloudEventsPublisher, CloudEventsPublisherBackgroundTask,
+};
+use crate::service::lineage::{LineageEvent, LineageStore};
+use crate::service::maintenance::MaintenanceMode;
+use crate::service::page_token::PageQuery;
+use crate::service::recent_writes::RecentWrites;
+use crate::service::resolution_cache::ResolutionCache;
+use crate::service::roles::RoleStore;
+use crate::service::runtime_config::RuntimeConfigHandle;
+use crate::service::storage::{AccessDelegation, StorageProfile};
+use crate::service::storage_usage::{NamespaceStorageUsage, StorageUsageStore};
+use crate::service::storage_validation::StorageValidation;
+use crate::service::stores::Stores;
+use crate::service::table_cache::TableCache;
+use crate::service::token_revocation::{RevocationList, RevocationStore, TokenRevocation};
+use crate::service::webhook::{WebhookDeadLetter, WebhookDeadLetterStore};
+use crate::service::{
+    Catalog, CommitTableResponseExt, CommitTransactionRequest, CreateNamespaceRequest,
+    CreateNamespaceResponse, CreateTableRequest, CreateTableResponse, DroppedTable,
+    EngineTableConfig, EventFilter, GetNamespaceResponse, GetTableMetadataResponse,
+    GetWarehouseResponse, ListNamespacesQuery, ListNamespacesResponse, LoadTableResponse,
+    LoadedTable, MetadataLogEntry, NamespaceIdent, NamespaceIdentUuid, ObjectOwnership,
+    OwnedObject, Project, ProjectIdent, PyicebergCompat, ResolvedShare, RetentionCandidate, Role,
+    RoleAssignment, RoleObject, RoleTarget, SecretIdent, SecretStore, Share, SharedObject,
+    SnapshotRetentionPolicy, StagedTable, State, TableCommit, TableIdent, TableIdentUuid,
+    TablePolicy, Transaction, UpdateNamespacePropertiesRequest, UpdateNamespacePropertiesResponse,
+    WarehouseAllowlist, WarehouseIdent, WarehouseStatus,
+};
+
+/// Build an [`ApiContext`] from the given states, e.g. to call
+/// management or catalog handlers directly.
+///
+/// Revocations are loaded from `stores`, maintenance mode and all caches
+/// are disabled and no contract verifiers or commit hooks are registered.
+#[must_use]
+pub fn api_context<A: AuthZHandler, C: Catalog, S: SecretStore>(
+    auth: A::State,
+    catalog: C::State,
+    secrets: S::State,
+    stores: Stores,
+    publisher: CloudEventsPublisher,
+) -> ApiContext<State<A, C, S>> {
+    let maintenance = MaintenanceMode::new(false, 0);
+    ApiContext {
+        v1_state: State {
+            auth,
+            revocations: RevocationList::new(
+                stores.revocations.clone(),
+                std::time::Duration::from_secs(0),
+            ),
+            token_verifier: None,
+            catalog,
+            secrets,
+            stores,
+            publisher,
+            contract_verifiers: ContractVerifiers::new(vec![]),
+            commit_hooks: CommitHooks::default(),
+            runtime_config: RuntimeConfigHandle::new(maintenance.clone(), None),
+            maintenance,
+            resolution_cache: ResolutionCache::new(std::time::Duration::ZERO),
+            table_cache: TableCache::disabled(),
+            commit_queue: CommitQueue::disabled(),
+            recent_writes: RecentWrites::disabled(),
+        },
+    }
+}
+
+/// [`ApiContext`] backed by [`AllowAllAuthZHandler`], [`NoopCatalog`] and [`MemorySecretStore`].
+#[must_use]
+pub fn noop_api_context(
+    publisher: CloudEventsPublisher,
+) -> ApiContext<State<AllowAllAuthZHandler, NoopCatalog, MemorySecretStore>> {
+    api_context::<AllowAllAuthZHandler, NoopCatalog, MemorySecretStore>(
+        AllowAllAuthState,
+        NoopCatalogState,
+        MemorySecretStoreState::default(),
+        Stores::new(NoopCatalogState),
+        publisher,
+    )
+}
+
+// ------------------- Events -------------------
+
+/// Event backend that keeps all published events in memory.
+#[derive(Debug, Clone, Default)]
+pub struct RecordingEventBackend {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+impl RecordingEventBackend {
+    /// Create a backend together with a publisher that forwards to it.
+    ///
+    /// Must be called from within a tokio runtime, as the
+    /// publisher's background task is spawned on it.
+    #[must_use]
+    pub fn publisher() -> (Self, CloudEventsPublisher) {
+        let backend = Self::default();
+        let (tx, rx) = tokio::sync::mpsc::channel(1000);
+        let task = CloudEventsPublisherBackgroundTask {
+            source: rx,
+            sinks: vec![Arc::new(backend.clone())],
+        };
+        tokio::task::spawn(task.publish());
+        (backend, CloudEventsPublisher::new(tx))
+    }
+
+    /// All events received so far, oldest first.
+    ///
+    /// # Panics
+    /// If the lock is poisoned.
+    #[must_use]
+    pub fn events(&self) -> Vec<Event> {
+        self.events.lock().expect("lock poisoned").clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl CloudEventBackend for RecordingEventBackend {
+    async fn publish(&self, event: Event) -> anyhow::Result<()> {
+        self.events.lock().expect("lock poisoned").push(event);
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "recording-publisher"
+    }
+}
+
+// ------------------- Catalog -------------------
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopCatalogState;
+
+#[derive(Debug)]
+pub struct NoopTransaction;
+
+#[async_trait::async_trait]
+impl Transaction<NoopCatalogState> for NoopTransaction {
+    type Transaction<'a> = ();
+
+    async fn begin_write(_: NoopCatalogState) -> Result<Self> {
+        Ok(Self)
+    }
+
+    async fn begin_read(_: NoopCatalogState) -> Result<Self> {
+        Ok(Self)
+    }
+
+    async fn commit(self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn rollback(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn transaction(&mut self) {}
+}
+
+/// Catalog that stores nothing.
+///
+/// Writes succeed without effect, lists are empty and lookups of
+/// single objects fail with 404. Operations that would have to return
+/// table metadata fail with 501.
+#[derive(Debug, Clone)]
+pub struct NoopCatalog;
+
+fn not_found(r#type: &str, message: &str) -> ErrorModel {
+    ErrorModel::builder()
+        .code(StatusCode::NOT_FOUND.into())
+        .message(message)
+        .r#type(r#type)
+        .build()
+}
+
+fn namespace_not_found() -> ErrorModel {
+    ErrorModel {
+        error_type: Some(ErrorType::NoSuchNamespace),
+        ..not_found("NamespaceNotFound", "Namespace not found")
+    }
+}
+
+fn table_not_found() -> ErrorModel {
+    ErrorModel {
+        error_type: Some(ErrorType::NoSuchTable),
+        ..not_found("NoSuchTableError", "Table not found")
+    }
+}
+
+fn not_implemented(operation: &str) -> ErrorModel {
+    ErrorModel::builder()
+        .code(StatusCode::NOT_IMPLEMENTED.into())
+        .message(format!("{operation} is not supported by the NoopCatalog"))
+        .r#type("NotImplemented")
+        .build()
+}
+
+#[async_trait::async_trait]
+impl Catalog for NoopCatalog {
+    type Transaction = NoopTransaction;
+    type State = NoopCatalogState;
+
+    fn primary_state(catalog_state: NoopCatalogState) -> NoopCatalogState {
+        catalog_state
+    }
+
+    async fn list_namespaces(
+        _: &WarehouseIdent,
+        _: &ListNamespacesQuery,
+        _: NoopCatalogState,
+    ) -> Result<ListNamespacesResponse> {
+        Ok(ListNamespacesResponse {
+            next_page_token: None,
+            namespaces: vec![],
+        })
+    }
+
+    async fn create_namespace<'a>(
+        _: &WarehouseIdent,
+        request: CreateNamespaceRequest,
+        _: Option<&str>,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<CreateNamespaceResponse> {
+        Ok(CreateNamespaceResponse {
+            namespace: request.namespace,
+            properties: request.properties,
+        })
+    }
+
+    async fn get_namespace<'a>(
+        _: &WarehouseIdent,
+        _: &NamespaceIdent,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<GetNamespaceResponse> {
+        Err(namespace_not_found().into())
+    }
+
+    async fn namespace_ident_to_id(
+        _: &WarehouseIdent,
+        _: &NamespaceIdent,
+        _: NoopCatalogState,
+    ) -> Result<Option<NamespaceIdentUuid>> {
+        Ok(None)
+    }
+
+    async fn drop_namespace<'a>(
+        _: &WarehouseIdent,
+        _: &NamespaceIdent,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn update_namespace_properties<'a>(
+        _: &WarehouseIdent,
+        _: &NamespaceIdent,
+        request: UpdateNamespacePropertiesRequest,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<UpdateNamespacePropertiesResponse> {
+        Ok(UpdateNamespacePropertiesResponse {
+            updated: request.updates.unwrap_or_default().into_keys().collect(),
+            removed: request.removals.unwrap_or_default(),
+            missing: None,
+        })
+    }
+
+    async fn create_table<'a>(
+        _: &NamespaceIdentUuid,
+        _: &TableIdent,
+        _: &TableIdentUuid,
+        _: CreateTableRequest,
+        _: Option<&String>,
+        _: Option<&str>,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<CreateTableResponse> {
+        Err(not_implemented("create_table").into())
+    }
+
+    async fn list_tables(
+        _: &WarehouseIdent,
+        _: &NamespaceIdent,
+        _: bool,
+        _: &PageQuery,
+        _: NoopCatalogState,
+    ) -> Result<Vec<(TableIdentUuid, TableIdent)>> {
+        Ok(vec![])
+    }
+
+    async fn table_ident_to_id(
+        _: &WarehouseIdent,
+        _: &TableIdent,
+        _: bool,
+        _: NoopCatalogState,
+    ) -> Result<Option<TableIdentUuid>> {
+        Ok(None)
+    }
+
+    async fn table_exists(
+        _: &WarehouseIdent,
+        _: &TableIdent,
+        _: bool,
+        _: NoopCatalogState,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn table_idents_to_ids(
+        _: &WarehouseIdent,
+        tables: HashSet<&TableIdent>,
+        _: bool,
+        _: NoopCatalogState,
+    ) -> Result<HashMap<TableIdent, Option<TableIdentUuid>>> {
+        Ok(tables.into_iter().map(|t| (t.clone(), None)).collect())
+    }
+
+    async fn load_table(
+        _: &WarehouseIdent,
+        _: &TableIdent,
+        _: NoopCatalogState,
+    ) -> Result<LoadTableResponse> {
+        Err(table_not_found().into())
+    }
+
+    async fn get_table_metadata_by_id(
+        _: &WarehouseIdent,
+        _: &TableIdentUuid,
+        _: bool,
+        _: NoopCatalogState,
+    ) -> Result<GetTableMetadataResponse> {
+        Err(table_not_found().into())
+    }
+
+    async fn get_table_metadata_by_s3_location(
+        _: &WarehouseIdent,
+        _: &str,
+        _: bool,
+        _: NoopCatalogState,
+    ) -> Result<GetTableMetadataResponse> {
+        Err(table_not_found().into())
+    }
+
+    async fn get_table_metadata_log(
+        _: &WarehouseIdent,
+        _: &TableIdentUuid,
+        _: NoopCatalogState,
+    ) -> Result<Vec<MetadataLogEntry>> {
+        Err(table_not_found().into())
+    }
+
+    async fn list_table_commits(
+        _: &WarehouseIdent,
+        _: &TableIdentUuid,
+        _: Option<chrono::DateTime<chrono::Utc>>,
+        _: i64,
+        _: NoopCatalogState,
+    ) -> Result<Vec<TableCommit>> {
+        Err(table_not_found().into())
+    }
+
+    async fn load_tables(
+        _: &WarehouseIdent,
+        _: HashSet<&TableIdent>,
+        _: NoopCatalogState,
+    ) -> Result<Vec<LoadedTable>> {
+        Ok(vec![])
+    }
+
+    async fn list_staged_tables(
+        _: &WarehouseIdent,
+        _: NoopCatalogState,
+    ) -> Result<Vec<StagedTable>> {
+        Ok(vec![])
+    }
+
+    async fn discard_staged_table<'a>(
+        _: &WarehouseIdent,
+        _: &TableIdentUuid,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Err(table_not_found().into())
+    }
+
+    async fn rename_table<'a>(
+        _: &WarehouseIdent,
+        _: &TableIdentUuid,
+        _: &TableIdent,
+        _: &TableIdent,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn drop_table<'a>(
+        _: &WarehouseIdent,
+        _: &TableIdentUuid,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn list_expired_dropped_tables(
+        _: chrono::DateTime<chrono::Utc>,
+        _: i64,
+        _: NoopCatalogState,
+    ) -> Result<Vec<DroppedTable>> {
+        Ok(vec![])
+    }
+
+    async fn reclaim_dropped_table<'a>(
+        _: &WarehouseIdent,
+        _: &TableIdentUuid,
+        _: bool,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn commit_table_transaction<'a>(
+        _: &WarehouseIdent,
+        _: CommitTransactionRequest,
+        _: &HashMap<TableIdent, TableIdentUuid>,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<Vec<CommitTableResponseExt>> {
+        Err(not_implemented("commit_table_transaction").into())
+    }
+
+    async fn record_table_commits<'a>(
+        _: &[TableCommit],
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn create_warehouse<'a>(
+        _: String,
+        _: ProjectIdent,
+        _: StorageProfile,
+        _: Option<SecretIdent>,
+        _: Option<&str>,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<WarehouseIdent> {
+        Ok(uuid::Uuid::now_v7().into())
+    }
+
+    async fn list_projects(_: NoopCatalogState) -> Result<HashSet<ProjectIdent>> {
+        Ok(HashSet::new())
+    }
+
+    async fn get_projects(
+        _: Option<&HashSet<ProjectIdent>>,
+        _: NoopCatalogState,
+    ) -> Result<Vec<Project>> {
+        Ok(vec![])
+    }
+
+    async fn get_project_id_by_name(_: &str, _: NoopCatalogState) -> Result<Option<ProjectIdent>> {
+        Ok(None)
+    }
+
+    async fn rename_project<'a>(
+        _: &ProjectIdent,
+        _: &str,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn set_project_metadata<'a>(
+        _: &ProjectIdent,
+        _: &HashMap<String, String>,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn delete_project<'a>(
+        _: &ProjectIdent,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn list_warehouses(
+        _: &ProjectIdent,
+        _: Option<Vec<WarehouseStatus>>,
+        _: Option<&HashSet<WarehouseIdent>>,
+        _: NoopCatalogState,
+    ) -> Result<Vec<GetWarehouseResponse>> {
+        Ok(vec![])
+    }
+
+    async fn get_warehouse_id_by_name(
+        _: &ProjectIdent,
+        _: &str,
+        _: NoopCatalogState,
+    ) -> Result<Option<WarehouseIdent>> {
+        Ok(None)
+    }
+
+    async fn get_warehouse<'a>(
+        _: &WarehouseIdent,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<GetWarehouseResponse> {
+        Err(not_found("WarehouseNotFound", "Warehouse not found").into())
+    }
+
+    async fn delete_warehouse<'a>(
+        _: &WarehouseIdent,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn rename_warehouse<'a>(
+        _: &WarehouseIdent,
+        _: &str,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn set_warehouse_status<'a>(
+        _: &WarehouseIdent,
+        _: WarehouseStatus,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn update_storage_profile<'a>(
+        _: &WarehouseIdent,
+        _: StorageProfile,
+        _: Option<SecretIdent>,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_warehouse_allowlist(
+        _: &WarehouseIdent,
+        _: NoopCatalogState,
+    ) -> Result<Option<WarehouseAllowlist>> {
+        Ok(None)
+    }
+
+    async fn set_warehouse_allowlist<'a>(
+        _: &WarehouseIdent,
+        _: Option<WarehouseAllowlist>,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_warehouse_signer_uri(
+        _: &WarehouseIdent,
+        _: NoopCatalogState,
+    ) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    async fn set_warehouse_signer_uri<'a>(
+        _: &WarehouseIdent,
+        _: Option<&url::Url>,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn set_warehouse_unauthorized_response<'a>(
+        _: &WarehouseIdent,
+        _: Option<UnauthorizedResponse>,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn rotate_warehouse_storage_secret<'a>(
+        _: &WarehouseIdent,
+        _: Option<&SecretIdent>,
+        _: &SecretIdent,
+        _: chrono::DateTime<chrono::Utc>,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn clear_warehouse_previous_storage_secret(
+        _: &WarehouseIdent,
+        _: &SecretIdent,
+        _: NoopCatalogState,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn set_warehouse_storage_validation(
+        _: &WarehouseIdent,
+        _: &StorageValidation,
+        _: NoopCatalogState,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn set_warehouse_access_delegation<'a>(
+        _: &WarehouseIdent,
+        _: Option<AccessDelegation>,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn set_warehouse_allow_external_locations<'a>(
+        _: &WarehouseIdent,
+        _: bool,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn set_warehouse_object_storage_layout<'a>(
+        _: &WarehouseIdent,
+        _: bool,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn set_warehouse_retention_policy<'a>(
+        _: &WarehouseIdent,
+        _: Option<SnapshotRetentionPolicy>,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_engine_table_config(
+        _: &WarehouseIdent,
+        _: NoopCatalogState,
+    ) -> Result<EngineTableConfig> {
+        Ok(EngineTableConfig::default())
+    }
+
+    async fn set_engine_table_config<'a>(
+        _: &WarehouseIdent,
+        _: EngineTableConfig,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn set_event_filter<'a>(
+        _: &WarehouseIdent,
+        _: EventFilter,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_ownership<'a>(
+        _: &WarehouseIdent,
+        _: &OwnedObject,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<ObjectOwnership> {
+        Ok(ObjectOwnership {
+            owner: None,
+            created_by: None,
+        })
+    }
+
+    async fn set_owner<'a>(
+        _: &WarehouseIdent,
+        _: &OwnedObject,
+        _: &str,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn is_warehouse_read_only(_: &WarehouseIdent, _: NoopCatalogState) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn set_warehouse_read_only<'a>(
+        _: &WarehouseIdent,
+        _: bool,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_pyiceberg_compat(
+        _: &WarehouseIdent,
+        _: NoopCatalogState,
+    ) -> Result<Option<PyicebergCompat>> {
+        Ok(None)
+    }
+
+    async fn set_warehouse_pyiceberg_compat<'a>(
+        _: &WarehouseIdent,
+        _: bool,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn set_table_policy<'a>(
+        _: &WarehouseIdent,
+        _: &TableIdent,
+        _: Option<TablePolicy>,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn set_namespace_retention_policy<'a>(
+        _: &WarehouseIdent,
+        _: &NamespaceIdent,
+        _: Option<SnapshotRetentionPolicy>,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn list_retention_candidates(
+        _: Option<TableIdentUuid>,
+        _: i64,
+        _: NoopCatalogState,
+    ) -> Result<Vec<RetentionCandidate>> {
+        Ok(vec![])
+    }
+
+    async fn create_share<'a>(
+        _: &WarehouseIdent,
+        _: &SharedObject,
+        _: &ProjectIdent,
+        _: &str,
+        _: Option<&str>,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<uuid::Uuid> {
+        Ok(uuid::Uuid::now_v7())
+    }
+
+    async fn list_shares(_: &WarehouseIdent, _: NoopCatalogState) -> Result<Vec<Share>> {
+        Ok(vec![])
+    }
+
+    async fn delete_share<'a>(
+        _: &WarehouseIdent,
+        _: &uuid::Uuid,
+        _: <Self::Transaction as Transaction<NoopCatalogState>>::Transaction<'a>,
+    ) -> Result<()> {
+        Err(not_found("ShareNotFound", "Share not found").into())
+    }
+
+    async fn resolve_share(
+        _: &WarehouseIdent,
+        _: &str,
+        _: NoopCatalogState,
+    ) -> Result<Option<ResolvedShare>> {
+        Ok(None)
+    }
+}
+
+// ------------------- Stores -------------------
+
+#[async_trait::async_trait]
+impl RoleStore for NoopCatalogState {
+    async fn grant_role(
+        &self,
+        _: &RoleObject,
+        _: &str,
+        _: Role,
+        _: Option<&str>,
+    ) -> Result<uuid::Uuid> {
+        Ok(uuid::Uuid::now_v7())
+    }
+
+    async fn revoke_role(&self, _: &RoleObject, _: &str) -> Result<()> {
+        Err(not_found("RoleAssignmentNotFound", "Role assignment not found").into())
+    }
+
+    async fn list_role_assignments(
+        &self,
+        _: Option<&ProjectIdent>,
+        _: Option<&str>,
+    ) -> Result<Vec<RoleAssignment>> {
+        Ok(vec![])
+    }
+
+    async fn get_roles(&self, _: &str, _: &RoleTarget) -> Result<Vec<Role>> {
+        Ok(vec![])
+    }
+}
+
+#[async_trait::async_trait]
+impl RevocationStore for NoopCatalogState {
+    async fn revoke_token(&self, _: &TokenRevocation) -> Result<()> {
+        Ok(())
+    }
+
+    async fn list_active_revocations(&self) -> Result<Vec<TokenRevocation>> {
+        Ok(vec![])
+    }
+}
+
+#[async_trait::async_trait]
+impl ClientUsageStore for NoopCatalogState {
+    async fn record_client_usage(&self, _: &WarehouseIdent, _: &ClientInfo) -> Result<()> {
+        Ok(())
+    }
+
+    async fn list_client_usage(&self, _: &WarehouseIdent) -> Result<Vec<ClientUsage>> {
+        Ok(vec![])
+    }
+}
+
+#[async_trait::async_trait]
+impl ApiUsageStore for NoopCatalogState {
+    async fn record_api_usage(&self, _: Vec<ApiUsage>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn list_api_usage(
+        &self,
+        _: &ProjectIdent,
+        _: Option<&HashSet<WarehouseIdent>>,
+        _: UsageWindow,
+        _: chrono::DateTime<chrono::Utc>,
+        _: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<ApiUsage>> {
+        Ok(vec![])
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageUsageStore for NoopCatalogState {
+    async fn refresh_storage_usage(&self) -> Result<u64> {
+        Ok(0)
+    }
+
+    async fn list_storage_usage(&self, _: &WarehouseIdent) -> Result<Vec<NamespaceStorageUsage>> {
+        Ok(vec![])
+    }
+}
+
+#[async_trait::async_trait]
+impl LineageStore for NoopCatalogState {
+    async fn record_lineage_events(
+        &self,
+        _: &WarehouseIdent,
+        _: TableIdentUuid,
+        _: &[LineageEvent],
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookDeadLetterStore for NoopCatalogState {
+    async fn record_webhook_dead_letter(&self, _: &WebhookDeadLetter) -> Result<()> {
+        Ok(())
+    }
+}
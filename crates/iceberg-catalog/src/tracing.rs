@@ -1,4 +1,5 @@
 use http::Request;
+use iceberg_ext::redaction::redact_text;
 use tower_http::request_id::{MakeRequestId, RequestId};
 use tower_http::trace::MakeSpan;
 use tracing::{Level, Span};
@@ -32,7 +33,7 @@ impl<B> MakeSpan<B> for RestMakeSpan {
                         $level,
                         "request",
                         method = %request.method(),
-                        uri = %request.uri(),
+                        uri = %redact_text(&request.uri().to_string()),
                         version = ?request.version(),
                         request_id = %request
                                     .headers()
@@ -25,17 +25,18 @@ pub mod rest {
 
     mod error;
     pub(crate) use error::impl_into_response;
-    pub use error::{Error, ErrorModel, IcebergErrorResponse};
+    pub use error::{Error, ErrorModel, ErrorType, IcebergErrorResponse};
 
     mod table;
     pub use table::{
         CommitTableRequest, CommitTableResponse, CommitTransactionRequest, CreateTableRequest,
-        ListTablesResponse, LoadTableResult, RegisterTableRequest, RenameTableRequest,
+        ListTablesResponse, LoadCredentialsResponse, LoadTableResult, PresignRequest,
+        PresignResponse, PresignedUrl, RegisterTableRequest, RenameTableRequest, TableCredential,
         TableRequirementExt, TableUpdateExt,
     };
 
     mod view;
-    pub use view::{CommitViewRequest, CreateViewRequest, LoadViewResult};
+    pub use view::{CommitViewRequest, CreateViewRequest, ListViewsResponse, LoadViewResult};
 
     mod namespace;
     pub use namespace::{
@@ -1,4 +1,5 @@
 use super::impl_into_response;
+use veil::Redact;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "grant_type")]
@@ -9,20 +10,23 @@ pub enum OAuthTokenRequest {
     OAuthClientCredentialsRequest(OAuthClientCredentialsRequest),
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Redact, PartialEq, Serialize, Deserialize)]
 pub struct OAuthTokenExchangeRequest {
     pub scope: Option<String>,
     pub requested_token_type: Option<OAuthTokenType>,
+    #[redact]
     pub subject_token: String,
     pub subject_token_type: OAuthTokenType,
+    #[redact]
     pub actor_token: Option<String>,
     pub actor_token_type: Option<OAuthTokenType>,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Redact, PartialEq, Serialize, Deserialize)]
 pub struct OAuthClientCredentialsRequest {
     pub scope: Option<String>,
     pub client_id: String,
+    #[redact]
     pub client_secret: String,
 }
 
@@ -93,9 +97,10 @@ pub enum OAuthErrorType {
     InvalidScope,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Redact, PartialEq, Serialize, Deserialize)]
 pub struct OAuthTokenResponse {
     /// The access token, for client credentials or token exchange
+    #[redact]
     pub access_token: String,
     /// Access token type for client credentials or token exchange.
     /// See [rfc6749](https://datatracker.ietf.org/doc/html/rfc6749#section-7.1)
@@ -105,6 +110,7 @@ pub struct OAuthTokenResponse {
     pub issued_token_type: Option<OAuthTokenType>,
     /// Refresh token for client credentials or token exchange
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[redact]
     pub refresh_token: Option<String>,
     /// Authorization scope for client credentials or token exchange
     #[serde(skip_serializing_if = "Option::is_none")]
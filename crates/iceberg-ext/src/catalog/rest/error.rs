@@ -65,6 +65,11 @@ pub struct ErrorModel {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(default)]
     pub stack: Option<Vec<String>>,
+    /// Type of the Iceberg REST specification returned to clients instead of `type`.
+    /// If not set, it is derived from the `code`, see [`ErrorType::of`].
+    #[serde(skip)]
+    #[builder(default, setter(strip_option))]
+    pub error_type: Option<ErrorType>,
 }
 
 impl ErrorModel {
@@ -79,28 +84,117 @@ impl ErrorModel {
     }
 }
 
+/// Exception types defined by the Iceberg REST specification.
+///
+/// Errors are created with a detailed, internal `type` such as `WarehouseNotFound`.
+/// Responses carry the spec type set with [`ErrorModel::error_type`] instead, so that
+/// clients can rely on it. The internal type is logged and returned in the `stack`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorType {
+    BadRequest,
+    NotAuthorized,
+    Forbidden,
+    NotFound,
+    NoSuchNamespace,
+    NoSuchTable,
+    NoSuchView,
+    AlreadyExists,
+    NamespaceNotEmpty,
+    CommitFailed,
+    /// A conflict that is not a [`ErrorType::CommitFailed`]. Retrying the request
+    /// with refreshed metadata doesn't resolve it, so clients must not retry.
+    Conflict,
+    UnsupportedOperation,
+    ServiceUnavailable,
+    ServerError,
+}
+
+impl ErrorType {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::BadRequest => "BadRequestException",
+            Self::NotAuthorized => "NotAuthorizedException",
+            Self::Forbidden => "ForbiddenException",
+            Self::NotFound => "NotFoundException",
+            Self::NoSuchNamespace => "NoSuchNamespaceException",
+            Self::NoSuchTable => "NoSuchTableException",
+            Self::NoSuchView => "NoSuchViewException",
+            Self::AlreadyExists => "AlreadyExistsException",
+            Self::NamespaceNotEmpty => "NamespaceNotEmptyException",
+            Self::CommitFailed => "CommitFailedException",
+            Self::Conflict => "ConflictException",
+            Self::UnsupportedOperation => "UnsupportedOperationException",
+            Self::ServiceUnavailable => "ServiceUnavailableException",
+            Self::ServerError => "ServerErrorException",
+        }
+    }
+
+    /// The spec type of an error: its [`ErrorModel::error_type`] if set, otherwise
+    /// a generic type for its status code.
+    #[must_use]
+    pub fn of(error: &ErrorModel) -> Self {
+        error
+            .error_type
+            .unwrap_or_else(|| Self::from_code(error.code))
+    }
+
+    fn from_code(code: u16) -> Self {
+        match code {
+            400 | 413 | 422 => Self::BadRequest,
+            401 => Self::NotAuthorized,
+            403 => Self::Forbidden,
+            404 => Self::NotFound,
+            405 | 406 | 501 => Self::UnsupportedOperation,
+            // Only errors that are known to be resolved by a retry are `CommitFailed`.
+            409 => Self::Conflict,
+            503 => Self::ServiceUnavailable,
+            _ => Self::ServerError,
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[cfg(feature = "axum")]
 impl axum::response::IntoResponse for IcebergErrorResponse {
     fn into_response(mut self) -> axum::http::Response<axum::body::Body> {
         // ToDo: Better Log handling. kv-log-macro?
         let error_id = uuid::Uuid::now_v7();
 
+        // Stacks contain debug output of arbitrary errors, which might include credentials.
         let console_log = serde_json::json!(
             {
                 "error_id": error_id.to_string(),
-                "message": self.error.message,
+                "message": crate::redaction::redact_text(&self.error.message),
                 "type": self.error.r#type,
                 "code": self.error.code,
-                "stack": self.error.stack
+                "stack": self.error.stack.as_ref().map(|stack| {
+                    stack
+                        .iter()
+                        .map(|s| crate::redaction::redact_text(s))
+                        .collect::<Vec<_>>()
+                })
             }
         );
         let code = self.error.code;
 
         // Exchange stack for error_id. We don't want the stack
         // to be exposed to the client
-        self.error.stack = Some(vec![format!("Error ID: {}", error_id)]);
+        let error_type = ErrorType::of(&self.error);
+        self.error.stack = Some(vec![
+            format!("Error ID: {}", error_id),
+            format!("Reason: {}", self.error.r#type),
+        ]);
+        self.error.r#type = error_type.to_string();
 
-        let mut response = axum::Json(self).into_response();
+        let mut response = axum::Json(&self).into_response();
+        // Attached so that middlewares can extend the error, i.e. with the request id.
+        response.extensions_mut().insert(self);
 
         log::info!("{}", console_log.to_string());
 
@@ -126,4 +220,97 @@ mod tests {
         let resp: IcebergErrorResponse = serde_json::from_value(json.clone()).unwrap();
         assert_eq!(serde_json::to_value(resp).unwrap(), json);
     }
+
+    #[test]
+    fn test_error_type() {
+        let error = |r#type: &str, code: u16| {
+            ErrorModel::builder()
+                .message("")
+                .r#type(r#type)
+                .code(code)
+                .build()
+        };
+        let typed = |r#type: &str, code: u16, error_type: ErrorType| ErrorModel {
+            error_type: Some(error_type),
+            ..error(r#type, code)
+        };
+        assert_eq!(
+            ErrorType::of(&typed("NoSuchTableError", 404, ErrorType::NoSuchTable)),
+            ErrorType::NoSuchTable
+        );
+        assert_eq!(
+            ErrorType::of(&error("WarehouseNotFound", 404)),
+            ErrorType::NotFound
+        );
+        assert_eq!(
+            ErrorType::of(&error("InvalidNamespace", 400)),
+            ErrorType::BadRequest
+        );
+        assert_eq!(
+            ErrorType::of(&error("NamespaceParseError", 500)),
+            ErrorType::ServerError
+        );
+        // The internal type is not interpreted.
+        assert_eq!(
+            ErrorType::of(&error("NoSuchTableError", 404)),
+            ErrorType::NotFound
+        );
+        assert_eq!(ErrorType::NoSuchTable.to_string(), "NoSuchTableException");
+    }
+
+    #[test]
+    fn test_conflict_error_type() {
+        let error = |r#type: &str| {
+            ErrorModel::builder()
+                .message("")
+                .r#type(r#type)
+                .code(409)
+                .build()
+        };
+        let typed = |r#type: &str, error_type: ErrorType| ErrorModel {
+            error_type: Some(error_type),
+            ..error(r#type)
+        };
+        assert_eq!(
+            ErrorType::of(&typed("TableAlreadyExists", ErrorType::AlreadyExists)),
+            ErrorType::AlreadyExists
+        );
+        assert_eq!(
+            ErrorType::of(&typed("CommitFailedException", ErrorType::CommitFailed)),
+            ErrorType::CommitFailed
+        );
+        assert_eq!(
+            ErrorType::of(&typed("NamespaceNotEmpty", ErrorType::NamespaceNotEmpty)),
+            ErrorType::NamespaceNotEmpty
+        );
+        // Conflicts that a retry doesn't resolve must not be reported as `CommitFailed`.
+        for r#type in [
+            "WarehouseNotEmpty",
+            "CredentialRotationConflict",
+            "ContractViolation",
+            "CommitRejectedByPolicy",
+            "SomethingAlreadyExists",
+        ] {
+            assert_eq!(ErrorType::of(&error(r#type)), ErrorType::Conflict);
+        }
+        assert_eq!(ErrorType::Conflict.to_string(), "ConflictException");
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn test_conflict_response_type() {
+        use axum::response::IntoResponse;
+
+        let response = IcebergErrorResponse::from(
+            ErrorModel::builder()
+                .message("Warehouse is not empty")
+                .r#type("WarehouseNotEmpty")
+                .code(409)
+                .build(),
+        )
+        .into_response();
+        assert_eq!(response.status(), 409);
+        let body = response.extensions().get::<IcebergErrorResponse>().unwrap();
+        assert_eq!(body.error.r#type, "ConflictException");
+    }
 }
@@ -1,18 +1,21 @@
 use http::StatusCode;
+use veil::Redact;
 
 use crate::catalog::{TableIdent, TableRequirement, TableUpdate};
 use crate::spec::{Schema, SortOrder, TableMetadata, TableMetadataAggregate, UnboundPartitionSpec};
 
-use super::{impl_into_response, ErrorModel, IcebergErrorResponse};
+use super::{impl_into_response, ErrorModel, ErrorType, IcebergErrorResponse};
 
 /// Result used when a table is successfully loaded.
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Redact, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct LoadTableResult {
     /// May be null if the table is staged as part of a transaction
     pub metadata_location: Option<String>,
     #[serde(rename = "metadata")]
     pub metadata: TableMetadata,
+    /// May contain vended credentials.
+    #[redact]
     pub config: Option<std::collections::HashMap<String, String>>,
 }
 
@@ -61,6 +64,55 @@ pub struct CommitTableRequest {
     pub updates: Vec<TableUpdate>,
 }
 
+// `TableRequirement` does not implement `Clone`, so requirements are copied by hand.
+impl Clone for CommitTableRequest {
+    fn clone(&self) -> Self {
+        Self {
+            identifier: self.identifier.clone(),
+            requirements: self.requirements.iter().map(clone_requirement).collect(),
+            updates: self.updates.clone(),
+        }
+    }
+}
+
+fn clone_requirement(requirement: &TableRequirement) -> TableRequirement {
+    match requirement {
+        TableRequirement::NotExist => TableRequirement::NotExist,
+        TableRequirement::UuidMatch { uuid } => TableRequirement::UuidMatch { uuid: *uuid },
+        TableRequirement::CurrentSchemaIdMatch { current_schema_id } => {
+            TableRequirement::CurrentSchemaIdMatch {
+                current_schema_id: *current_schema_id,
+            }
+        }
+        TableRequirement::DefaultSortOrderIdMatch {
+            default_sort_order_id,
+        } => TableRequirement::DefaultSortOrderIdMatch {
+            default_sort_order_id: *default_sort_order_id,
+        },
+        TableRequirement::RefSnapshotIdMatch { r#ref, snapshot_id } => {
+            TableRequirement::RefSnapshotIdMatch {
+                r#ref: r#ref.clone(),
+                snapshot_id: *snapshot_id,
+            }
+        }
+        TableRequirement::DefaultSpecIdMatch { default_spec_id } => {
+            TableRequirement::DefaultSpecIdMatch {
+                default_spec_id: *default_spec_id,
+            }
+        }
+        TableRequirement::LastAssignedPartitionIdMatch {
+            last_assigned_partition_id,
+        } => TableRequirement::LastAssignedPartitionIdMatch {
+            last_assigned_partition_id: *last_assigned_partition_id,
+        },
+        TableRequirement::LastAssignedFieldIdMatch {
+            last_assigned_field_id,
+        } => TableRequirement::LastAssignedFieldIdMatch {
+            last_assigned_field_id: *last_assigned_field_id,
+        },
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct CommitTableResponse {
@@ -69,15 +121,61 @@ pub struct CommitTableResponse {
     pub config: Option<std::collections::HashMap<String, String>>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct CommitTransactionRequest {
     pub table_changes: Vec<CommitTableRequest>,
 }
 
+/// Storage config to use for all files below `prefix`.
+#[derive(Clone, Redact, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TableCredential {
+    pub prefix: String,
+    #[redact]
+    pub config: std::collections::HashMap<String, String>,
+}
+
+/// Result used when the credentials of a table are refreshed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LoadCredentialsResponse {
+    pub storage_credentials: Vec<TableCredential>,
+}
+
+/// Request for pre-signed URLs to read files of a table.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PresignRequest {
+    /// Locations of the files to read, i.e. of manifest lists and manifests.
+    /// If empty, the URL of the current metadata file is returned.
+    #[serde(default)]
+    pub locations: Vec<String>,
+}
+
+/// Pre-signed URL to read the file at `location`.
+#[derive(Clone, Redact, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PresignedUrl {
+    pub location: String,
+    #[redact]
+    pub url: String,
+}
+
+/// Result used when pre-signed URLs of files of a table are issued.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PresignResponse {
+    pub urls: Vec<PresignedUrl>,
+    /// Milliseconds since the epoch after which the URLs are no longer valid.
+    pub expires_at_ms: i64,
+}
+
 impl_into_response!(LoadTableResult);
 impl_into_response!(ListTablesResponse);
 impl_into_response!(CommitTableResponse);
+impl_into_response!(LoadCredentialsResponse);
+impl_into_response!(PresignResponse);
 
 #[allow(clippy::module_name_repetitions)]
 pub trait TableRequirementExt {
@@ -90,135 +188,104 @@ pub trait TableRequirementExt {
 }
 
 impl TableRequirementExt for TableRequirement {
-    #[allow(clippy::too_many_lines)]
     fn assert(&self, metadata: &TableMetadata, exists: bool) -> Result<(), IcebergErrorResponse> {
         match self {
             TableRequirement::NotExist => {
                 if exists {
-                    return Err(ErrorModel::builder()
-                        .code(StatusCode::CONFLICT.as_u16())
-                        .message("assert-create Table Requirement violated".to_string())
-                        .r#type("TableRequirementNotExist".to_string())
-                        .build()
-                        .into());
+                    return Err(requirement_failed(
+                        "assert-create",
+                        "TableRequirementNotExist",
+                        "no table",
+                        "an existing table",
+                        false,
+                    ));
                 }
             }
             TableRequirement::UuidMatch { uuid } => {
                 if &metadata.uuid() != uuid {
-                    return Err(ErrorModel::builder()
-                        .code(StatusCode::CONFLICT.as_u16())
-                        .message("assert-table-uuid Table Requirement violated".to_string())
-                        .r#type("TableRequirementUuidMatch".to_string())
-                        .stack(Some(vec![format!(
-                            "Expected: {uuid}, Found: {}",
-                            metadata.uuid()
-                        )]))
-                        .build()
-                        .into());
+                    return Err(requirement_failed(
+                        "assert-table-uuid",
+                        "TableRequirementUuidMatch",
+                        format!("table uuid {uuid}"),
+                        format!("table uuid {}", metadata.uuid()),
+                        false,
+                    ));
                 }
             }
             TableRequirement::CurrentSchemaIdMatch { current_schema_id } => {
                 // ToDo: Harmonize the types of current_schema_id
                 if i64::from(metadata.current_schema_id) != *current_schema_id {
-                    return Err(ErrorModel::builder()
-                        .code(StatusCode::CONFLICT.as_u16())
-                        .message("assert-current-schema-id Table Requirement violated".to_string())
-                        .r#type("TableRequirementCurrentSchemaIdMatch".to_string())
-                        .stack(Some(vec![format!(
-                            "Expected: {current_schema_id}, Found: {}",
-                            metadata.current_schema_id
-                        )]))
-                        .build()
-                        .into());
+                    return Err(requirement_failed(
+                        "assert-current-schema-id",
+                        "TableRequirementCurrentSchemaIdMatch",
+                        format!("current schema id {current_schema_id}"),
+                        format!("current schema id {}", metadata.current_schema_id),
+                        true,
+                    ));
                 }
             }
             TableRequirement::DefaultSortOrderIdMatch {
                 default_sort_order_id,
             } => {
                 if metadata.default_sort_order_id != *default_sort_order_id {
-                    return Err(ErrorModel::builder()
-                        .code(StatusCode::CONFLICT.as_u16())
-                        .message(
-                            "assert-default-sort-order-id Table Requirement violated".to_string(),
-                        )
-                        .r#type("TableRequirementDefaultSortOrderIdMatch".to_string())
-                        .stack(Some(vec![format!(
-                            "Expected: {default_sort_order_id}, Found: {}",
-                            metadata.default_sort_order_id
-                        )]))
-                        .build()
-                        .into());
+                    return Err(requirement_failed(
+                        "assert-default-sort-order-id",
+                        "TableRequirementDefaultSortOrderIdMatch",
+                        format!("default sort order id {default_sort_order_id}"),
+                        format!("default sort order id {}", metadata.default_sort_order_id),
+                        true,
+                    ));
                 }
             }
             TableRequirement::RefSnapshotIdMatch { r#ref, snapshot_id } => {
-                if let Some(snapshot_id) = snapshot_id {
-                    let snapshot_ref = metadata.refs.get(r#ref).ok_or(
-                        ErrorModel::builder()
-                            .code(StatusCode::NOT_FOUND.as_u16())
-                            .message("Snapshot reference not found".to_string())
-                            .r#type("TableRequirementRefSnapshotIdMatch".to_string())
-                            .build(),
-                    )?;
-                    if snapshot_ref.snapshot_id != *snapshot_id {
-                        return Err(ErrorModel::builder()
-                            .code(StatusCode::CONFLICT.as_u16())
-                            .message(
-                                "assert-ref-snapshot-id Branch points to different snapshot."
-                                    .to_string(),
-                            )
-                            .r#type("TableRequirementRefSnapshotIdMatch".to_string())
-                            .stack(Some(vec![format!(
-                                "Expected: {snapshot_id}, Found: {}",
-                                snapshot_ref.snapshot_id
-                            )]))
-                            .build()
-                            .into());
-                    }
-                } else if metadata.refs.contains_key(r#ref) {
-                    return Err(ErrorModel::builder()
-                        .code(StatusCode::CONFLICT.as_u16())
-                        .message("assert-ref-snapshot-id Branch points a snapshot.".to_string())
-                        .r#type("TableRequirementRefSnapshotExists".to_string())
-                        .stack(Some(vec![format!(
-                            "Expected: None, Found: {}",
-                            metadata.refs.get(r#ref).unwrap().snapshot_id
-                        )]))
-                        .build()
-                        .into());
+                let found = metadata.refs.get(r#ref).map(|r| r.snapshot_id);
+                if found != *snapshot_id {
+                    let (r#type, expected) = match snapshot_id {
+                        Some(id) => (
+                            "TableRequirementRefSnapshotIdMatch",
+                            format!("ref `{}` at snapshot id {id}", r#ref),
+                        ),
+                        None => (
+                            "TableRequirementRefSnapshotExists",
+                            format!("no ref `{}`", r#ref),
+                        ),
+                    };
+                    let found = match found {
+                        Some(id) => format!("ref `{}` at snapshot id {id}", r#ref),
+                        None => format!("no ref `{}`", r#ref),
+                    };
+                    return Err(requirement_failed(
+                        "assert-ref-snapshot-id",
+                        r#type,
+                        expected,
+                        found,
+                        true,
+                    ));
                 }
             }
             TableRequirement::DefaultSpecIdMatch { default_spec_id } => {
                 // ToDo: Harmonize the types of default_spec_id
                 if i64::from(metadata.default_spec_id) != *default_spec_id {
-                    return Err(ErrorModel::builder()
-                        .code(StatusCode::CONFLICT.as_u16())
-                        .message("assert-default-spec-id Table Requirement violated".to_string())
-                        .r#type("TableRequirementDefaultSpecIdMatch".to_string())
-                        .stack(Some(vec![format!(
-                            "Expected: {default_spec_id}, Found: {}",
-                            metadata.default_spec_id
-                        )]))
-                        .build()
-                        .into());
+                    return Err(requirement_failed(
+                        "assert-default-spec-id",
+                        "TableRequirementDefaultSpecIdMatch",
+                        format!("default spec id {default_spec_id}"),
+                        format!("default spec id {}", metadata.default_spec_id),
+                        true,
+                    ));
                 }
             }
             TableRequirement::LastAssignedPartitionIdMatch {
                 last_assigned_partition_id,
             } => {
                 if i64::from(metadata.last_partition_id) != *last_assigned_partition_id {
-                    return Err(ErrorModel::builder()
-                        .code(StatusCode::CONFLICT.as_u16())
-                        .message(
-                            "assert-last-assigned-partition-id Table Requirement violated"
-                                .to_string(),
-                        )
-                        .r#type("TableRequirementLastAssignedPartitionIdMatch".to_string())
-                        .stack(Some(vec![format!(
-                            "Expected: {last_assigned_partition_id}, Found: {}",
-                            metadata.last_partition_id
-                        )]))
-                        .build()
-                        .into());
+                    return Err(requirement_failed(
+                        "assert-last-assigned-partition-id",
+                        "TableRequirementLastAssignedPartitionIdMatch",
+                        format!("last assigned partition id {last_assigned_partition_id}"),
+                        format!("last assigned partition id {}", metadata.last_partition_id),
+                        true,
+                    ));
                 }
             }
             TableRequirement::LastAssignedFieldIdMatch {
@@ -228,18 +295,13 @@ impl TableRequirementExt for TableRequirement {
                 // and metadata i32
                 let last_column_id: i64 = metadata.last_column_id.into();
                 if &last_column_id != last_assigned_field_id {
-                    return Err(ErrorModel::builder()
-                        .code(StatusCode::CONFLICT.as_u16())
-                        .message(
-                            "assert-last-assigned-field-id Table Requirement violated".to_string(),
-                        )
-                        .r#type("TableRequirementLastAssignedFieldIdMatch".to_string())
-                        .stack(Some(vec![format!(
-                            "Expected: {last_assigned_field_id}, Found: {}",
-                            metadata.last_column_id
-                        )]))
-                        .build()
-                        .into());
+                    return Err(requirement_failed(
+                        "assert-last-assigned-field-id",
+                        "TableRequirementLastAssignedFieldIdMatch",
+                        format!("last assigned field id {last_assigned_field_id}"),
+                        format!("last assigned field id {}", metadata.last_column_id),
+                        true,
+                    ));
                 }
             }
         };
@@ -247,6 +309,42 @@ impl TableRequirementExt for TableRequirement {
     }
 }
 
+/// Error for a violated table requirement.
+///
+/// Requirements that are `retryable` fail because the table was changed by a
+/// concurrent commit; the commit can succeed once the client refreshes the
+/// table and re-applies its changes. Other failures persist across retries.
+fn requirement_failed(
+    requirement: &str,
+    r#type: &str,
+    expected: impl std::fmt::Display,
+    found: impl std::fmt::Display,
+    retryable: bool,
+) -> IcebergErrorResponse {
+    let hint = if retryable {
+        "The table was modified by a concurrent commit. Refresh the table and retry the commit."
+    } else {
+        "Retrying the commit will not succeed."
+    };
+    ErrorModel::builder()
+        .code(StatusCode::CONFLICT.as_u16())
+        .message(format!(
+            "Requirement {requirement} failed: expected {expected}, found {found}. {hint}"
+        ))
+        .r#type(r#type.to_string())
+        .error_type(if retryable {
+            ErrorType::CommitFailed
+        } else {
+            ErrorType::Conflict
+        })
+        .stack(Some(vec![
+            format!("Expected: {expected}, Found: {found}"),
+            format!("Retryable: {retryable}"),
+        ]))
+        .build()
+        .into()
+}
+
 #[allow(clippy::module_name_repetitions)]
 pub trait TableUpdateExt {
     /// Apply the update to the given metadata builder.
@@ -356,4 +454,70 @@ mod tests {
         assert_eq!(r.location, None);
         assert_eq!(r.schema.schema_id(), 1);
     }
+
+    #[test]
+    fn test_requirement_failure_details() {
+        let metadata = TableMetadataAggregate::new(
+            "s3://bucket/table".to_string(),
+            Schema::builder().build().unwrap(),
+        )
+        .build()
+        .unwrap();
+
+        let err = TableRequirement::RefSnapshotIdMatch {
+            r#ref: "main".to_string(),
+            snapshot_id: Some(1),
+        }
+        .assert(&metadata, true)
+        .unwrap_err();
+        assert_eq!(err.error.code, StatusCode::CONFLICT.as_u16());
+        assert_eq!(ErrorType::of(&err.error), ErrorType::CommitFailed);
+        assert_eq!(
+            err.error.message,
+            "Requirement assert-ref-snapshot-id failed: expected ref `main` at snapshot id 1, found no ref `main`. The table was modified by a concurrent commit. Refresh the table and retry the commit."
+        );
+
+        let err = TableRequirement::NotExist
+            .assert(&metadata, true)
+            .unwrap_err();
+        assert_eq!(err.error.r#type, "TableRequirementNotExist");
+        assert_eq!(ErrorType::of(&err.error), ErrorType::Conflict);
+        assert_eq!(
+            err.error.stack,
+            Some(vec![
+                "Expected: no table, Found: an existing table".to_string(),
+                "Retryable: false".to_string()
+            ])
+        );
+
+        TableRequirement::RefSnapshotIdMatch {
+            r#ref: "main".to_string(),
+            snapshot_id: None,
+        }
+        .assert(&metadata, true)
+        .unwrap();
+    }
+
+    #[test]
+    fn test_clone_commit_transaction_request() {
+        let request: CommitTransactionRequest = serde_json::from_value(serde_json::json!({
+            "table-changes": [{
+                "identifier": {"namespace": ["ns"], "name": "tbl"},
+                "requirements": [
+                    {"type": "assert-create"},
+                    {"type": "assert-table-uuid", "uuid": "2cc52516-5e73-41f2-b139-545d41a4e151"},
+                    {"type": "assert-ref-snapshot-id", "ref": "main", "snapshot-id": 1},
+                    {"type": "assert-last-assigned-field-id", "last-assigned-field-id": 3},
+                    {"type": "assert-current-schema-id", "current-schema-id": 0},
+                    {"type": "assert-last-assigned-partition-id", "last-assigned-partition-id": 1000},
+                    {"type": "assert-default-spec-id", "default-spec-id": 0},
+                    {"type": "assert-default-sort-order-id", "default-sort-order-id": 0}
+                ],
+                "updates": [{"action": "set-properties", "updates": {"owner": "alice"}}]
+            }]
+        }))
+        .unwrap();
+
+        assert_eq!(request.clone(), request);
+    }
 }
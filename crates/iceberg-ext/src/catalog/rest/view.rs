@@ -31,6 +31,16 @@ pub struct LoadViewResult {
     pub config: Option<std::collections::HashMap<String, String>>,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ListViewsResponse {
+    /// An opaque token that allows clients to make use of pagination for list
+    /// APIs (e.g. `ListViews`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
+    pub identifiers: Vec<TableIdent>,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct CommitViewRequest {
@@ -42,3 +52,4 @@ pub struct CommitViewRequest {
 }
 
 impl_into_response!(LoadViewResult);
+impl_into_response!(ListViewsResponse);
@@ -7,6 +7,7 @@
 #![forbid(unsafe_code)]
 
 pub mod catalog;
+pub mod redaction;
 pub mod spec;
 pub mod validation;
 
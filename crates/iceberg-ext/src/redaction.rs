@@ -0,0 +1,168 @@
+//! Masking of secrets in values that are logged or published.
+//!
+//! Types holding credentials implement `Debug` via `veil::Redact`. The
+//! functions in this module cover the remaining paths: free text such as
+//! error stacks and URIs, and JSON bodies such as those of published events,
+//! which may contain credentials as table properties or storage config.
+
+/// Replacement of redacted values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Parts of keys whose values are secret.
+const SECRET_KEY_MARKERS: &[&str] = &[
+    "secret",
+    "token",
+    "password",
+    "signature",
+    "authorization",
+    "private_key",
+    "private-key",
+];
+
+/// Suffixes of keys that contain a marker but whose values are not secret,
+/// e.g. `subject_token_type`, `s3.signer.token-expires-at-ms` or `private_key_id`.
+const NON_SECRET_KEY_SUFFIXES: &[&str] = &[
+    "type",
+    "expires-at",
+    "expires-at-ms",
+    "endpoint",
+    "uri",
+    "url",
+    "key_id",
+];
+
+/// Authorization schemes that precede the secret in `Authorization` values.
+const AUTHORIZATION_SCHEMES: &[&str] = &["bearer ", "basic "];
+
+/// Whether the value of a property, header or field named `key` is secret.
+#[must_use]
+pub fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_KEY_MARKERS.iter().any(|m| key.contains(m))
+        && !NON_SECRET_KEY_SUFFIXES.iter().any(|s| key.ends_with(s))
+}
+
+/// Replace all string values of secret keys in `value`, at any depth.
+pub fn redact_json(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if value.is_string() && is_secret_key(key) {
+                    *value = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// Replace values of secret keys in free text, such as `secret_access_key: "..."`
+/// in debug output or `X-Amz-Signature=...` in URIs.
+#[must_use]
+pub fn redact_text(text: &str) -> String {
+    // ASCII lowercasing keeps byte offsets intact.
+    let lower = text.to_ascii_lowercase();
+    let bytes = text.as_bytes();
+    let mut redacted = String::with_capacity(text.len());
+    let mut pos = 0;
+
+    while let Some((start, len)) = SECRET_KEY_MARKERS
+        .iter()
+        .filter_map(|m| lower[pos..].find(m).map(|i| (pos + i, m.len())))
+        .min()
+    {
+        // Rest of the key, i.e. `_access_key` of `secret_access_key`.
+        let key_end = skip(bytes, start + len, |b| {
+            b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b'.')
+        });
+        let separator = skip(bytes, key_end, |b| matches!(b, b'"' | b'\'' | b' '));
+        if !matches!(bytes.get(separator), Some(b'=' | b':')) {
+            redacted.push_str(&text[pos..key_end]);
+            pos = key_end;
+            continue;
+        }
+
+        let mut value_start = skip(bytes, separator + 1, |b| matches!(b, b'"' | b'\'' | b' '));
+        if let Some(scheme) = AUTHORIZATION_SCHEMES
+            .iter()
+            .find(|s| lower[value_start..].starts_with(*s))
+        {
+            value_start += scheme.len();
+        }
+        let value_end = skip(bytes, value_start, |b| {
+            !b.is_ascii_whitespace()
+                && !matches!(b, b'&' | b'"' | b'\'' | b',' | b';' | b'}' | b')')
+        });
+
+        redacted.push_str(&text[pos..value_start]);
+        if value_end > value_start {
+            redacted.push_str(REDACTED);
+        }
+        pos = value_end;
+    }
+
+    redacted.push_str(&text[pos..]);
+    redacted
+}
+
+/// Index of the first byte at or after `from` that does not satisfy `f`.
+fn skip(bytes: &[u8], from: usize, f: impl Fn(u8) -> bool) -> usize {
+    bytes[from..]
+        .iter()
+        .position(|b| !f(*b))
+        .map_or(bytes.len(), |i| from + i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARKER: &str = "wJalrXUtnFEMIK7MDENGbPxRfiCYEXAMPLEKEY";
+
+    #[test]
+    fn test_is_secret_key() {
+        assert!(is_secret_key("s3.secret-access-key"));
+        assert!(is_secret_key("s3.session-token"));
+        assert!(is_secret_key("Authorization"));
+        assert!(!is_secret_key("s3.access-key-id"));
+        assert!(!is_secret_key("subject_token_type"));
+        assert!(!is_secret_key("s3.signer.token-expires-at-ms"));
+        assert!(is_secret_key("gcs.oauth2.token"));
+        assert!(!is_secret_key("gcs.oauth2.token-expires-at"));
+        assert!(is_secret_key("private_key"));
+        assert!(!is_secret_key("private_key_id"));
+    }
+
+    #[test]
+    fn test_redact_json() {
+        let mut value = serde_json::json!({
+            "name": "table",
+            "properties": {"s3.secret-access-key": MARKER, "owner": "me"},
+            "updates": [{"action": "set-properties", "updates": {"s3.session-token": MARKER}}]
+        });
+        redact_json(&mut value);
+        assert!(!value.to_string().contains(MARKER));
+        assert_eq!(value["properties"]["owner"], "me");
+        assert_eq!(value["properties"]["s3.secret-access-key"], REDACTED);
+    }
+
+    #[test]
+    fn test_redact_text() {
+        for text in [
+            format!("S3(AccessKey {{ aws_access_key_id: \"AKIA\", aws_secret_access_key: \"{MARKER}\" }})"),
+            format!("https://bucket.s3.amazonaws.com/key?X-Amz-Security-Token={MARKER}&X-Amz-Signature={MARKER}"),
+            format!("authorization: Bearer {MARKER}"),
+            format!("{{\"s3.secret-access-key\":\"{MARKER}\"}}"),
+        ] {
+            let redacted = redact_text(&text);
+            assert!(!redacted.contains(MARKER), "{redacted}");
+            assert!(redacted.contains(REDACTED), "{redacted}");
+        }
+
+        let text = "Invalid token provided for table ns.tbl";
+        assert_eq!(redact_text(text), text);
+    }
+}
@@ -126,8 +126,9 @@ impl PartitionSpecBinder {
         ErrorModel {
             message: message.into(),
             r#type: "FailedToBuildPartitionSpec".to_owned(),
-            code: StatusCode::CONFLICT.into(),
+            code: StatusCode::BAD_REQUEST.into(),
             stack: None,
+            error_type: None,
         }
     }
 
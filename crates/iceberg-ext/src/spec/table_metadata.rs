@@ -11,7 +11,7 @@ use iceberg::spec::{
 use iceberg::TableUpdate;
 use uuid::Uuid;
 
-use crate::catalog::rest::ErrorModel;
+use crate::catalog::rest::{ErrorModel, ErrorType};
 use crate::spec::partition_binder::PartitionSpecBinder;
 
 // ToDo: Migrate to schema impl
@@ -171,7 +171,7 @@ impl TableMetadataAggregate {
                 FormatVersion::V1 => FormatVersion::V1,
                 FormatVersion::V2 => {
                     return Err(ErrorModel::builder()
-                        .code(StatusCode::CONFLICT.into())
+                        .code(StatusCode::BAD_REQUEST.into())
                         .message("Cannot downgrade FormatVersion from V2 to V1")
                         .r#type("FormatVersionNoDowngrade")
                         .build())
@@ -218,7 +218,7 @@ impl TableMetadataAggregate {
             .any(|(prop_name, _)| reserved_props.contains(prop_name))
         {
             return Err(ErrorModel::builder()
-                .code(StatusCode::CONFLICT.into())
+                .code(StatusCode::BAD_REQUEST.into())
                 .message("Table properties should not contain reserved properties")
                 .r#type("FailedToSetProperties")
                 .build());
@@ -267,7 +267,7 @@ impl TableMetadataAggregate {
                     new_last_column_id, self.metadata.last_column_id
                 ))
                 .r#type("LastColumnIdTooLow")
-                .code(StatusCode::CONFLICT.into())
+                .code(StatusCode::BAD_REQUEST.into())
                 .build());
         }
 
@@ -343,7 +343,7 @@ impl TableMetadataAggregate {
                 self.set_current_schema(id)
             } else {
                 Err(ErrorModel::builder()
-                    .code(StatusCode::CONFLICT.into())
+                    .code(StatusCode::BAD_REQUEST.into())
                     .message("Cannot set last added schema: no schema has been added.")
                     .r#type("CurrentSchemaNotAdded")
                     .build())
@@ -356,7 +356,7 @@ impl TableMetadataAggregate {
 
         let Some(schema) = self.metadata.schemas.get(&schema_id) else {
             return Err(ErrorModel::builder()
-                .code(StatusCode::CONFLICT.into())
+                .code(StatusCode::BAD_REQUEST.into())
                 .message(format!(
                     "Cannot set current schema to schema with unknown Id: '{schema_id}'"
                 ))
@@ -396,7 +396,7 @@ impl TableMetadataAggregate {
                     .map_err(|e| {
                         ErrorModel::builder()
                             .message(e.message())
-                            .code(StatusCode::CONFLICT.into())
+                            .code(StatusCode::BAD_REQUEST.into())
                             .r#type(e.kind().into_static())
                             .stack(Some(vec![e.to_string()]))
                             .build()
@@ -428,7 +428,7 @@ impl TableMetadataAggregate {
     pub fn add_partition_spec(&mut self, unbound_spec: UnboundPartitionSpec) -> Result<&mut Self> {
         if self.metadata.current_schema_id == Self::LAST_ADDED_I32 {
             return Err(ErrorModel::builder()
-                .code(StatusCode::CONFLICT.into())
+                .code(StatusCode::BAD_REQUEST.into())
                 .message("Cannot add partition spec before current schema has been set.")
                 .r#type("AddPartitionSpecBeforeSchema")
                 .build());
@@ -453,7 +453,7 @@ impl TableMetadataAggregate {
             && !Self::has_sequential_ids(&spec.fields)
         {
             return Err(ErrorModel::builder()
-                .code(StatusCode::CONFLICT.into())
+                .code(StatusCode::BAD_REQUEST.into())
                 .message("Spec does not use sequential IDs that are required in v1.")
                 .r#type("FailedToBuildPartitionSpec")
                 .build());
@@ -507,7 +507,7 @@ impl TableMetadataAggregate {
                 self.set_default_partition_spec(id)
             } else {
                 Err(ErrorModel::builder()
-                    .code(StatusCode::CONFLICT.into())
+                    .code(StatusCode::BAD_REQUEST.into())
                     .message("Cannot set last added spec: no spec has been added.")
                     .r#type("FailedToSetDefaultPartitionSpec")
                     .build())
@@ -545,7 +545,7 @@ impl TableMetadataAggregate {
 
         if self.metadata.current_schema_id == Self::LAST_ADDED_I32 {
             return Err(ErrorModel::builder()
-                .code(StatusCode::CONFLICT.into())
+                .code(StatusCode::BAD_REQUEST.into())
                 .message("Cannot add sort order before current schema has been set.")
                 .r#type("AddSortOrderBeforeSchema")
                 .build());
@@ -559,7 +559,7 @@ impl TableMetadataAggregate {
             .map_err(|e| {
                 ErrorModel::builder()
                     .message("Failed to bind 'SortOrder'")
-                    .code(StatusCode::CONFLICT.into())
+                    .code(StatusCode::BAD_REQUEST.into())
                     .r#type("FailedToBindSortOrder")
                     .stack(Some(vec![e.to_string()]))
                     .build()
@@ -609,7 +609,7 @@ impl TableMetadataAggregate {
                 self.set_default_sort_order(id)
             } else {
                 Err(ErrorModel::builder()
-                    .code(StatusCode::CONFLICT.into())
+                    .code(StatusCode::BAD_REQUEST.into())
                     .message("Cannot set last added sort order: no sort order has been added.")
                     .r#type("FailedToSetDefaultSortOrderSpec")
                     .build())
@@ -643,7 +643,7 @@ impl TableMetadataAggregate {
         if self.metadata.schemas.is_empty() {
             return Err(ErrorModel::builder()
                 .message("Attempting to add a snapshot before a schema is added")
-                .code(StatusCode::CONFLICT.into())
+                .code(StatusCode::BAD_REQUEST.into())
                 .r#type("AddSnapshotBeforeSchema")
                 .build());
         }
@@ -651,7 +651,7 @@ impl TableMetadataAggregate {
         if self.metadata.partition_specs.is_empty() {
             return Err(ErrorModel::builder()
                 .message("Attempting to add a snapshot before a partition spec is added")
-                .code(StatusCode::CONFLICT.into())
+                .code(StatusCode::BAD_REQUEST.into())
                 .r#type("AddSnapshotBeforePartitionSpec")
                 .build());
         }
@@ -659,7 +659,7 @@ impl TableMetadataAggregate {
         if self.metadata.sort_orders.is_empty() {
             return Err(ErrorModel::builder()
                 .message("Attempting to add a snapshot before a sort order is added")
-                .code(StatusCode::CONFLICT.into())
+                .code(StatusCode::BAD_REQUEST.into())
                 .r#type("AddSnapshotBeforeSortOrder")
                 .build());
         }
@@ -676,6 +676,7 @@ impl TableMetadataAggregate {
                 ))
                 .code(StatusCode::CONFLICT.into())
                 .r#type("SnapshotAlreadyExists")
+                .error_type(ErrorType::AlreadyExists)
                 .build());
         }
 
@@ -691,6 +692,7 @@ impl TableMetadataAggregate {
                 ))
                 .code(StatusCode::CONFLICT.into())
                 .r#type("AddSnapshotOlderThanLast")
+                .error_type(ErrorType::CommitFailed)
                 .build()
             );
         }
@@ -722,7 +724,7 @@ impl TableMetadataAggregate {
         });
 
         for (snapshot_name, snapshot_ref) in self.metadata.refs.clone() {
-            if self
+            if !self
                 .metadata
                 .snapshots
                 .contains_key(&snapshot_ref.snapshot_id)
@@ -731,6 +733,11 @@ impl TableMetadataAggregate {
             }
         }
 
+        let snapshots = &self.metadata.snapshots;
+        self.metadata
+            .snapshot_log
+            .retain(|entry| snapshots.contains_key(&entry.snapshot_id));
+
         Ok(self)
     }
 
@@ -758,7 +765,7 @@ impl TableMetadataAggregate {
                     "Cannot set '{ref_name}' to unknown snapshot: '{}'",
                     reference.snapshot_id
                 ))
-                .code(StatusCode::CONFLICT.into())
+                .code(StatusCode::BAD_REQUEST.into())
                 .r#type("SetReferenceToUnknownSnapshot")
                 .build());
         };
@@ -824,7 +831,7 @@ impl TableMetadataAggregate {
         if self.metadata.current_schema_id < 0 {
             return Err(ErrorModel::builder()
                 .message("Cannot create a table without current_schema_id")
-                .code(StatusCode::CONFLICT.into())
+                .code(StatusCode::BAD_REQUEST.into())
                 .r#type("CurrentSchemaIdMissing")
                 .build());
         }
@@ -832,7 +839,7 @@ impl TableMetadataAggregate {
         if self.metadata.last_column_id < 0 {
             return Err(ErrorModel::builder()
                 .message("Cannot create a table without last_column_id")
-                .code(StatusCode::CONFLICT.into())
+                .code(StatusCode::BAD_REQUEST.into())
                 .r#type("LastColumnIdMissing")
                 .build());
         }
@@ -897,7 +904,7 @@ impl TableMetadataAggregate {
     ) -> impl FnOnce() -> ErrorModel {
         || -> ErrorModel {
             ErrorModel::builder()
-                .code(StatusCode::CONFLICT.into())
+                .code(StatusCode::BAD_REQUEST.into())
                 .message(msg)
                 .r#type(r#type)
                 .build()